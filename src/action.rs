@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use crate::config::KeyId;
 use crate::input::focus::FocusArea;
-use crate::llm::{LLMError, StreamChunk, PendingToolUse, ToolResult, ToolUse};
+use crate::llm::{AnswerValidation, LLMError, StreamChunk, ToolResult, ToolUse};
 
 /// A question for the ask_user tool
 #[derive(Debug, Clone)]
@@ -15,6 +15,12 @@ pub struct AskUserQuestion {
     pub options: Vec<AskUserOption>,
     /// Allow multiple selections
     pub multi_select: bool,
+    /// Whether an answer is needed before submitting
+    pub required: bool,
+    /// Constraints on a custom answer
+    pub validation: Option<AnswerValidation>,
+    /// Answer used if the dialog times out
+    pub default: Vec<String>,
 }
 
 /// An option for an ask_user question
@@ -33,6 +39,8 @@ pub struct AskUserRequest {
     pub tool_use_id: String,
     /// Questions to ask (1-4)
     pub questions: Vec<AskUserQuestion>,
+    /// Seconds before defaults are used (None = the configured default)
+    pub timeout_secs: Option<u64>,
 }
 
 /// Response from ask_user dialog
@@ -40,8 +48,21 @@ pub struct AskUserRequest {
 pub struct AskUserResponse {
     /// Tool use ID this responds to
     pub tool_use_id: String,
-    /// Map of question index to selected option(s) or custom text
-    pub answers: Vec<String>,
+    /// One answer per question, in question order
+    pub answers: Vec<AskUserAnswer>,
+    /// Answered by the timeout with the questions' defaults
+    pub timed_out: bool,
+}
+
+/// The user's answer to one ask_user question
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AskUserAnswer {
+    pub header: String,
+    /// Labels of the chosen options (empty for a skipped optional question)
+    pub selected: Vec<String>,
+    /// Custom "Other" text, if given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -83,11 +104,15 @@ pub enum Action {
     PtyInput(Vec<u8>),
     PtyOutput(Vec<u8>),
     PtyResize { cols: u16, rows: u16 },
+    /// Reset the active terminal (RIS, clean grid, re-sent size) without killing the shell
+    TerminalReset,
 
     ScrollUp(u16),
     ScrollDown(u16),
     ScrollPageUp,
     ScrollPageDown,
+    ScrollHalfPageUp,
+    ScrollHalfPageDown,
     ScrollToTop,
     ScrollToBottom,
 
@@ -121,6 +146,13 @@ pub enum Action {
     StreamViewerScrollDown(u16),
     StreamViewerScrollToTop,
     StreamViewerScrollToBottom,
+    /// Toggle line wrapping in the stream viewer
+    StreamViewerToggleWrap,
+    /// Toggle following the newest stream output
+    StreamViewerToggleAutoScroll,
+    /// Scroll unwrapped stream viewer lines left/right by columns
+    StreamViewerScrollLeft(u16),
+    StreamViewerScrollRight(u16),
 
     // Activity Stream actions (SIRK/Forge)
     /// Show activity stream panel
@@ -168,19 +200,48 @@ pub enum Action {
 
     // LLM actions
     LlmSendMessage(String),
+    /// Send even though the context window will overflow (confirmed)
+    LlmSendMessageConfirmed(String),
+    /// Start a fresh thread and send the message there instead
+    LlmSendInNewThread(String),
     LlmStreamChunk(StreamChunk),
     LlmStreamComplete,
     LlmStreamError(LLMError),
     LlmCancel,
+    /// Hold the agent loop after the current tool results instead of continuing
+    LlmPause,
+    /// Continue a paused agent loop
+    LlmResume,
+    /// Re-send the request of a turn that ended in an error
+    AgentRetryLastTurn,
     LlmSelectModel(String),
     LlmSelectProvider(String),
+    /// Open the quick model switcher
+    ModelPickerShow,
+    ModelPickerHide,
+    /// Switch provider and model together (from the model switcher)
+    ModelPickerSelect { provider: String, model: String },
     LlmClearConversation,
+    /// Show the last captured LLM request/response (llm.toml `debug_capture`)
+    ShowLastLlmExchange,
+    /// Write a crash report bundle (logs, redacted config, last exchange)
+    GenerateCrashReport,
+    /// List the files the last agent turn created, modified or deleted
+    ShowTurnChanges,
+    /// Ask before restoring the files of the last agent turn that changed any
+    UndoLastEdit,
+    /// Restore them (confirmed)
+    UndoLastEditConfirm,
 
     // Chat input actions
     /// Clear the chat input buffer
     ChatInputClear,
     /// Paste text into chat input
     ChatInputPaste(String),
+    /// Attach the clipboard image to the next message (text paste if there is none)
+    ChatInputPasteImage,
+    /// Attach an image file to the next message (`/image <path>` in the chat input)
+    ChatInputAttachImageFile(std::path::PathBuf),
     /// Copy selected text from chat input to clipboard
     ChatInputCopy,
     /// Scroll chat input up by n lines
@@ -195,18 +256,28 @@ pub enum Action {
     SubagentSelectProvider { agent_type: String, provider: String },
 
     // Tool execution actions
-    /// LLM requested a tool use, needs confirmation
-    ToolUseReceived(PendingToolUse),
     /// User confirmed tool execution
     ToolConfirm,
     /// User rejected tool execution  
     ToolReject,
+    /// Approve every tool awaiting confirmation in the current turn
+    ToolConfirmAll,
+    /// Reject every tool awaiting confirmation in the current turn
+    ToolRejectAll,
     /// Tool execution completed
     ToolResult(ToolResult),
     /// Toggle dangerous mode for tool execution
     ToolToggleDangerousMode,
     /// Set dangerous mode explicitly (used by CLI flag --dangerously-allow-all)
     ToolSetDangerousMode(bool),
+    /// Toggle step-through mode (confirm every tool, pause after each result)
+    AgentToggleStepMode,
+    /// Toggle continuing automatically after tool results (off = wait for Enter)
+    AgentToggleAutoContinue,
+    /// Toggle dry-run planning (tools are described to the model, not executed)
+    AgentToggleDryRun,
+    /// Select a persona from prompts.toml by name (None = default prompt)
+    AgentSelectPersona(Option<String>),
 
     // Thread management actions (Phase 2)
     /// Create a new conversation thread
@@ -219,6 +290,12 @@ pub enum Action {
     ThreadSave,
     /// Clear current thread (start fresh without deleting)
     ThreadClear,
+    /// Delete a saved thread by ID
+    ThreadDelete(String),
+    /// Ask before deleting saved threads older than `conversation.thread_retention_days`
+    ThreadPrune,
+    /// Delete them (confirmed)
+    ThreadPruneConfirm,
 
     // Thread picker actions (P2-003)
     /// Show thread picker dialog for selecting a thread to resume
@@ -237,6 +314,16 @@ pub enum Action {
     ThreadRenameBackspace,
     /// Confirm thread rename with new name
     ThreadRename(String),
+    /// Start editing the current thread's tags
+    ThreadStartTagEdit,
+    /// Replace the current thread's tags (space-separated)
+    ThreadSetTags(String),
+    /// Show the current thread's token usage and cost report
+    ThreadUsageReport,
+    /// Write the current thread's usage report to CSV and JSON
+    ThreadUsageExport,
+    /// Show status and recent output of this turn's sub-agents
+    SubagentPeek,
 
     // Config actions
     /// Configuration file changed (hot-reload trigger)
@@ -245,6 +332,10 @@ pub enum Action {
     ConfigReload,
     /// Apply theme changes
     ConfigApplyTheme,
+    /// Open a new tab with a shell in the config directory
+    ConfigOpenDir,
+    /// Open config.toml in $EDITOR in a new tab
+    ConfigEditFile,
 
     // Key storage actions
     /// Store an API key securely
@@ -263,6 +354,8 @@ pub enum Action {
     // Conversation viewer actions
     /// Toggle conversation viewer visibility
     ConversationToggle,
+    /// Show/hide the plan sidebar next to the conversation
+    PlanPanelToggle,
     /// Scroll conversation viewer
     ConversationScrollUp(u16),
     ConversationScrollDown(u16),
@@ -326,6 +419,14 @@ pub enum Action {
     LogViewerScrollPageDown,
     /// Toggle auto-scroll for log viewer
     LogViewerToggleAutoScroll,
+    /// Toggle line wrapping in the log viewer
+    LogViewerToggleWrap,
+    /// Show or hide the timestamp column in the log viewer
+    LogViewerToggleTimestamps,
+    /// Scroll unwrapped log viewer lines left by columns
+    LogViewerScrollLeft(u16),
+    /// Scroll unwrapped log viewer lines right by columns
+    LogViewerScrollRight(u16),
     /// Clear all log entries
     LogViewerClear,
     /// Add log entry (target, message)
@@ -517,12 +618,18 @@ pub enum Action {
     PaneResizeLeftShrink,
     /// Reset all panes to default sizes
     PaneResetLayout,
+    /// Hide the process monitor and menu panes, showing them as overlays when focused
+    ToggleCompactMode,
     /// Start mouse drag on a border
     PaneStartDrag(PaneBorder),
     /// Continue mouse drag
     PaneDrag { x: u16, y: u16 },
     /// End mouse drag
     PaneEndDrag,
+    /// Start dragging an overlay's resize border (continues with PaneDrag/PaneEndDrag)
+    OverlayStartDrag(OverlayKind),
+    /// Move the topmost open overlay to its next position (center/right/left/full)
+    OverlayCyclePosition,
 
     // Settings Editor actions (TS-003+)
     /// Show settings editor
@@ -549,6 +656,8 @@ pub enum Action {
     SettingsCancelEdit,
     /// API key entered for a provider
     SettingsKeyEntered { provider: String, key: String },
+    /// Base URL override entered for a provider (empty clears it)
+    SettingsBaseUrlEntered { provider: String, url: String },
     /// Provider selection changed
     SettingsProviderChanged(String),
     /// Model selection changed
@@ -561,6 +670,10 @@ pub enum Action {
     SettingsTemperatureChanged(f32),
     /// Max tokens changed (TS-010)
     SettingsMaxTokensChanged(u32),
+    /// Extended thinking toggled
+    SettingsThinkingToggled(bool),
+    /// Extended thinking budget changed
+    SettingsThinkingBudgetChanged(u32),
     /// Save settings
     SettingsSave,
 
@@ -595,6 +708,22 @@ pub enum Action {
     AskUserCancel,
     /// Response from ask_user dialog (internal - sends result back to tool)
     AskUserRespond(AskUserResponse),
+    /// ask_user timed out without defaults for every required question (tool_use_id)
+    AskUserTimedOut(String),
+
+    // Pager actions
+    /// Open the pager overlay with arbitrary text content
+    PagerShow { title: String, content: String },
+    /// Close the pager overlay
+    PagerClose,
+    /// Copy the pager's full content to the clipboard
+    PagerCopy,
+    /// Open the fully resolved system prompt in the pager
+    ShowSystemPrompt,
+    /// Open the selected (or most recent) tool call's full output in the pager
+    ToolCallViewOutput,
+    /// Open the filtered log entries in the pager
+    LogViewerOpenPager,
 
     None,
 }
@@ -610,6 +739,41 @@ pub enum PaneBorder {
     LeftHorizontal,
 }
 
+/// Resizable overlay panels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayKind {
+    StreamViewer,
+    LogViewer,
+    ConfigPanel,
+}
+
+impl OverlayKind {
+    pub fn focus_area(self) -> FocusArea {
+        match self {
+            OverlayKind::StreamViewer => FocusArea::StreamViewer,
+            OverlayKind::LogViewer => FocusArea::LogViewer,
+            OverlayKind::ConfigPanel => FocusArea::ConfigPanel,
+        }
+    }
+
+    pub fn from_focus_area(area: FocusArea) -> Option<Self> {
+        match area {
+            FocusArea::StreamViewer => Some(OverlayKind::StreamViewer),
+            FocusArea::LogViewer => Some(OverlayKind::LogViewer),
+            FocusArea::ConfigPanel => Some(OverlayKind::ConfigPanel),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OverlayKind::StreamViewer => "Stream viewer",
+            OverlayKind::LogViewer => "Log viewer",
+            OverlayKind::ConfigPanel => "Settings panel",
+        }
+    }
+}
+
 /// Target type for context menus (TRC-020)
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
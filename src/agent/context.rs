@@ -8,6 +8,55 @@ use crate::llm::types::{LLMRequest, Message, ToolDefinition};
 
 use super::models::ModelCatalog;
 use super::tokens::TokenCounter;
+use super::usage::TurnUsage;
+
+/// Tools that are never dropped from a request, however tight the context
+pub const CORE_TOOLS: &[&str] = &[
+    "file_read", "file_write", "edit", "apply_edit", "search_code",
+    "grep", "glob", "list_directory", "bash_execute",
+];
+
+/// Shrink tool definitions to their first description sentence, without
+/// per-property descriptions. Names, types and required fields are kept.
+pub fn compact_tool_definitions(tools: &[ToolDefinition]) -> Vec<ToolDefinition> {
+    tools
+        .iter()
+        .map(|tool| {
+            let mut schema = tool.input_schema.clone();
+            strip_property_descriptions(&mut schema);
+            ToolDefinition {
+                name: tool.name.clone(),
+                description: first_sentence(&tool.description),
+                input_schema: schema,
+            }
+        })
+        .collect()
+}
+
+fn first_sentence(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.find(". ") {
+        Some(end) => text[..=end].to_string(),
+        None => text,
+    }
+}
+
+fn strip_property_descriptions(schema: &mut serde_json::Value) {
+    if let Some(props) = schema.get_mut("properties").and_then(|p| p.as_object_mut()) {
+        for prop in props.values_mut() {
+            if let Some(obj) = prop.as_object_mut() {
+                obj.remove("description");
+            }
+            strip_property_descriptions(prop);
+        }
+    }
+    if let Some(items) = schema.get_mut("items") {
+        if let Some(obj) = items.as_object_mut() {
+            obj.remove("description");
+        }
+        strip_property_descriptions(items);
+    }
+}
 
 /// Kind of context segment for priority-based retention
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,6 +84,9 @@ pub struct ContextSegment {
     pub token_count: Option<u32>,
     /// Timestamp for ordering (newer = higher)
     pub sequence: u64,
+    /// Provider-reported usage for the assistant response in this segment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TurnUsage>,
 }
 
 impl ContextSegment {
@@ -44,6 +96,7 @@ impl ContextSegment {
             messages,
             token_count: None,
             sequence,
+            usage: None,
         }
     }
 
@@ -82,6 +135,10 @@ pub struct BuildContextParams {
     pub segments: Vec<ContextSegment>,
     /// Maximum output tokens to reserve
     pub max_output_tokens: Option<u32>,
+    /// Always send compacted tool descriptions
+    pub compact_tools: bool,
+    /// Mark the system prompt and tool definitions as cacheable
+    pub prompt_cache: bool,
 }
 
 /// Result of building a context-aware request
@@ -100,6 +157,14 @@ pub struct BuiltContext {
     pub segments_included: usize,
     /// Number of segments dropped
     pub segments_dropped: usize,
+    /// Tokens spent on the system prompt actually sent (full or short)
+    pub system_prompt_tokens: u32,
+    /// Tokens spent on tool definitions (estimated)
+    pub tool_definition_tokens: u32,
+    /// Tokens spent on conversation messages that made the cut
+    pub conversation_tokens: u32,
+    /// Number of non-core tools left out to fit the budget
+    pub tools_omitted: usize,
 }
 
 /// Lightweight stats for UI display (Phase 3: Context indicator)
@@ -114,6 +179,12 @@ pub struct ContextStats {
     pub truncated: bool,
     /// Number of messages in conversation
     pub message_count: usize,
+    /// Portion of tokens_used spent on the system prompt
+    pub system_prompt_tokens: u32,
+    /// Portion of tokens_used spent on tool definitions
+    pub tool_definition_tokens: u32,
+    /// Portion of tokens_used spent on conversation messages
+    pub conversation_tokens: u32,
 }
 
 impl ContextStats {
@@ -123,9 +194,19 @@ impl ContextStats {
             tokens_budget,
             truncated,
             message_count,
+            system_prompt_tokens: 0,
+            tool_definition_tokens: 0,
+            conversation_tokens: 0,
         }
     }
 
+    pub fn with_breakdown(mut self, system_prompt_tokens: u32, tool_definition_tokens: u32, conversation_tokens: u32) -> Self {
+        self.system_prompt_tokens = system_prompt_tokens;
+        self.tool_definition_tokens = tool_definition_tokens;
+        self.conversation_tokens = conversation_tokens;
+        self
+    }
+
     /// Calculate usage percentage (0-100)
     pub fn usage_percent(&self) -> u8 {
         if self.tokens_budget == 0 {
@@ -152,12 +233,27 @@ impl ContextStats {
         let budget = Self::format_tokens(self.tokens_budget);
         let percent = self.usage_percent();
         
-        if self.truncated {
+        let gauge = if self.truncated {
             format!("{}↓/{}({}%)", used, budget, percent)
         } else {
             format!("{}/{}({}%)", used, budget, percent)
+        };
+        if self.tool_definition_tokens > 0 {
+            format!("{} tools:{}", gauge, Self::format_tokens(self.tool_definition_tokens))
+        } else {
+            gauge
         }
     }
+
+    /// Breakdown of where the context budget goes, e.g. "system 1.2k · tools 3.4k · conversation 5.6k"
+    pub fn format_breakdown(&self) -> String {
+        format!(
+            "system {} · tools {} · conversation {}",
+            Self::format_tokens(self.system_prompt_tokens),
+            Self::format_tokens(self.tool_definition_tokens),
+            Self::format_tokens(self.conversation_tokens),
+        )
+    }
 }
 
 /// Manages context window budget and builds optimized requests
@@ -186,18 +282,10 @@ impl ContextManager {
 
     /// Build an LLMRequest with intelligent context truncation
     pub fn build_request(&self, params: BuildContextParams) -> BuiltContext {
-        let model_info = self.catalog.info_for(&params.model);
-
-        // Calculate budget
         let max_output = params
             .max_output_tokens
-            .unwrap_or(model_info.default_max_output_tokens);
-        let safety_buffer =
-            (model_info.max_context_tokens * self.safety_margin_percent) / 100;
-        let budget = model_info
-            .max_context_tokens
-            .saturating_sub(max_output)
-            .saturating_sub(safety_buffer);
+            .unwrap_or(self.catalog.info_for(&params.model).default_max_output_tokens);
+        let budget = self.budget_for(&params.model, Some(max_output));
 
         // Count always-preserved content
         let system_tokens = params
@@ -206,7 +294,12 @@ impl ContextManager {
             .map(|s| self.counter.count_text(&params.model, s))
             .unwrap_or(0);
 
-        let tools_tokens = self.count_tools(&params.model, &params.tools);
+        let mut tools = if params.compact_tools {
+            compact_tool_definitions(&params.tools)
+        } else {
+            params.tools.clone()
+        };
+        let mut tools_tokens = self.count_tools(&params.model, &tools);
 
         // Debug: log input segments
         tracing::info!("🏗️ BUILD_REQUEST: {} input segments", params.segments.len());
@@ -241,6 +334,23 @@ impl ContextManager {
             .map(|s| self.count_segment(&params.model, s))
             .sum();
 
+        // When tight, shrink tool definitions before touching history:
+        // compact descriptions first, then drop everything but the core tools
+        let mut tools_omitted = 0;
+        if system_tokens + tools_tokens + last_turn_tokens > budget && !params.compact_tools {
+            tools = compact_tool_definitions(&tools);
+            tools_tokens = self.count_tools(&params.model, &tools);
+        }
+        if system_tokens + tools_tokens + last_turn_tokens > budget {
+            let before = tools.len();
+            tools.retain(|t| CORE_TOOLS.contains(&t.name.as_str()));
+            tools_omitted = before - tools.len();
+            if tools_omitted > 0 {
+                tracing::info!("Context tight: omitted {} non-core tool definitions", tools_omitted);
+                tools_tokens = self.count_tools(&params.model, &tools);
+            }
+        }
+
         let preserved_tokens = system_tokens + tools_tokens + last_turn_tokens;
 
         // Check if we need to use short system prompt
@@ -260,17 +370,19 @@ impl ContextManager {
         let mut remaining_budget = budget.saturating_sub(preserved_tokens);
 
         // Fill remaining budget with older segments (newest first)
+        let mut conversation_tokens = last_turn_tokens;
         let mut included_segments: Vec<&ContextSegment> = Vec::new();
         let mut segments_dropped = 0;
 
         // Sort older segments by sequence (newest first)
         let mut older_sorted: Vec<&ContextSegment> = older_segments.clone();
-        older_sorted.sort_by(|a, b| b.sequence.cmp(&a.sequence));
+        older_sorted.sort_by_key(|s| std::cmp::Reverse(s.sequence));
 
         for segment in &older_sorted {
             let seg_tokens = self.count_segment(&params.model, segment);
             if seg_tokens <= remaining_budget {
                 included_segments.push(segment);
+                conversation_tokens += seg_tokens;
                 remaining_budget = remaining_budget.saturating_sub(seg_tokens);
             } else {
                 segments_dropped += 1;
@@ -344,9 +456,10 @@ impl ContextManager {
             model: params.model.clone(),
             system: final_system,
             messages,
-            tools: params.tools,
+            tools,
             max_tokens: Some(max_output),
             stream: true,
+            prompt_cache: params.prompt_cache,
             ..Default::default()
         };
 
@@ -357,10 +470,27 @@ impl ContextManager {
             truncated: segments_dropped > 0,
             segments_included: included_segments.len() + last_turn_segments.len(),
             segments_dropped,
+            system_prompt_tokens: system_used_tokens,
+            tool_definition_tokens: tools_tokens,
+            conversation_tokens,
+            tools_omitted,
         }
     }
 
-    fn count_tools(&self, model: &str, tools: &[ToolDefinition]) -> u32 {
+    /// Input tokens a request for `model` may use: the context window minus the
+    /// output reservation and the safety margin
+    pub fn budget_for(&self, model: &str, max_output_tokens: Option<u32>) -> u32 {
+        let model_info = self.catalog.info_for(model);
+        let max_output = max_output_tokens.unwrap_or(model_info.default_max_output_tokens);
+        let safety_buffer = (model_info.max_context_tokens * self.safety_margin_percent) / 100;
+        model_info
+            .max_context_tokens
+            .saturating_sub(max_output)
+            .saturating_sub(safety_buffer)
+    }
+
+    /// Estimate the tokens a set of tool definitions costs in a request
+    pub fn count_tools(&self, model: &str, tools: &[ToolDefinition]) -> u32 {
         let mut total = 0u32;
         for tool in tools {
             total += self.counter.count_text(model, &tool.name);
@@ -371,6 +501,16 @@ impl ContextManager {
         total
     }
 
+    /// Estimate the tokens a piece of text costs for `model`
+    pub fn count_text(&self, model: &str, text: &str) -> u32 {
+        self.counter.count_text(model, text)
+    }
+
+    /// Estimate the tokens a list of messages costs for `model`
+    pub fn count_messages(&self, model: &str, messages: &[Message]) -> u32 {
+        self.counter.count_messages(model, messages)
+    }
+
     fn count_segment(&self, model: &str, segment: &ContextSegment) -> u32 {
         segment.token_count.unwrap_or_else(|| {
             self.counter.count_messages(model, &segment.messages)
@@ -437,6 +577,8 @@ mod tests {
                 1,
             )],
             max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
         };
 
         let built = manager.build_request(params);
@@ -444,6 +586,92 @@ mod tests {
         assert!(built.total_tokens < built.budget);
     }
 
+    #[test]
+    fn test_built_context_breakdown_sums_to_total() {
+        let catalog = Arc::new(ModelCatalog::new());
+        let counter = Arc::new(DefaultTokenCounter::new(catalog.clone()));
+        let manager = ContextManager::new(catalog, counter);
+        let tools = crate::llm::ToolExecutor::new(std::env::temp_dir()).tool_definitions_for_llm();
+
+        let params = BuildContextParams {
+            model: "gpt-4o".to_string(),
+            system_prompt: Some("You are a helpful assistant.".to_string()),
+            short_system_prompt: Some("Be helpful.".to_string()),
+            tools,
+            segments: (0..5)
+                .map(|i| ContextSegment::chat(vec![Message::user("Hello"), Message::assistant("Hi there!")], i))
+                .collect(),
+            max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
+        };
+
+        let built = manager.build_request(params);
+        assert!(built.system_prompt_tokens > 0);
+        assert!(built.tool_definition_tokens > 0);
+        assert!(built.conversation_tokens > 0);
+        assert_eq!(
+            built.system_prompt_tokens + built.tool_definition_tokens + built.conversation_tokens,
+            built.total_tokens
+        );
+    }
+
+    #[test]
+    fn test_compact_tool_definitions_round_trip() {
+        let tools = crate::llm::ToolExecutor::new(std::env::temp_dir()).tool_definitions_for_llm();
+        let compact = compact_tool_definitions(&tools);
+        assert_eq!(compact.len(), tools.len());
+
+        for (full, small) in tools.iter().zip(&compact) {
+            assert_eq!(full.name, small.name);
+            assert!(small.description.len() <= full.description.len());
+
+            // Still a valid object schema that survives serialization
+            let json = serde_json::to_string(&small.input_schema).unwrap();
+            let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed["type"], "object");
+            assert_eq!(parsed.get("required"), full.input_schema.get("required"));
+            if let Some(props) = parsed["properties"].as_object() {
+                for (name, prop) in props {
+                    assert!(prop.get("description").is_none(), "{}.{} kept a description", small.name, name);
+                    assert_eq!(prop.get("type"), full.input_schema["properties"][name].get("type"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tight_context_keeps_core_tools() {
+        let catalog = Arc::new(ModelCatalog::new());
+        let counter = Arc::new(DefaultTokenCounter::new(catalog.clone()));
+        let manager = ContextManager::new(catalog, counter);
+        let tools = crate::llm::ToolExecutor::new(std::env::temp_dir()).tool_definitions_for_llm();
+
+        let full_tokens = manager.count_tools("gpt-4o", &tools);
+        let compact_tokens = manager.count_tools("gpt-4o", &compact_tool_definitions(&tools));
+        assert!(compact_tokens < full_tokens);
+
+        // A huge last turn forces trimming down to the core set
+        let params = BuildContextParams {
+            model: "gpt-4o".to_string(),
+            system_prompt: None,
+            short_system_prompt: None,
+            tools: tools.clone(),
+            segments: vec![ContextSegment::chat(vec![Message::user("word ".repeat(200_000))], 1)],
+            max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
+        };
+        let built = manager.build_request(params);
+        assert!(built.tools_omitted > 0);
+        for core in CORE_TOOLS {
+            if tools.iter().any(|t| t.name == *core) {
+                assert!(built.request.tools.iter().any(|t| t.name == *core), "{} was trimmed", core);
+            }
+        }
+        assert!(built.request.tools.iter().all(|t| CORE_TOOLS.contains(&t.name.as_str())));
+    }
+
     #[test]
     fn test_context_stats_format_tokens() {
         assert_eq!(ContextStats::format_tokens(500), "500");
@@ -476,5 +704,11 @@ mod tests {
 
         let stats = ContextStats::new(5000, 100000, true, 10);
         assert_eq!(stats.format_compact(), "5.0k↓/100.0k(5%)");
+
+        let stats = ContextStats::new(5000, 100000, false, 10).with_breakdown(0, 1200, 3800);
+        assert_eq!(stats.format_compact(), "5.0k/100.0k(5%) tools:1.2k");
+
+        let stats = ContextStats::new(5000, 100000, false, 10).with_breakdown(800, 1200, 3000);
+        assert_eq!(stats.format_breakdown(), "system 800 · tools 1.2k · conversation 3.0k");
     }
 }
@@ -1,6 +1,13 @@
 //! Disk-based thread storage using JSON files
 //!
-//! Stores threads as JSON files in ~/.config/ridge-control/threads/{thread_id}.json
+//! Stores threads as JSON files in <data dir>/threads/{thread_id}.json, or
+//! `agent.threads_dir` when set (see `ConfigManager::threads_dir`). An existing
+//! threads dir under the config dir, the old default, is still used if the
+//! data dir one does not exist yet.
+//! With encryption on (`agent.encrypt_threads`), files are sealed with
+//! ChaCha20-Poly1305; plaintext files are still read and re-encrypted on save.
+//! Until the key is available (e.g. the keystore is locked) saves are refused
+//! rather than written in plaintext.
 
 use std::collections::HashMap;
 use std::fs;
@@ -9,6 +16,12 @@ use std::path::PathBuf;
 use std::sync::RwLock;
 
 use super::thread::{AgentThread, ThreadStore, ThreadSummary};
+use crate::config::{is_sealed, seal, unseal};
+use crate::util::write_atomic_with;
+
+/// Current thread file format, written as a top-level `version` key.
+/// Files written before versioning have no `version` key but the same fields.
+const THREAD_FILE_VERSION: u64 = 1;
 
 /// File-based thread persistence using JSON
 pub struct DiskThreadStore {
@@ -16,12 +29,16 @@ pub struct DiskThreadStore {
     base_path: PathBuf,
     /// Cache of loaded threads for performance
     cache: RwLock<HashMap<String, AgentThread>>,
+    /// Key for sealing files; set late when the keystore is unlocked
+    key: RwLock<Option<[u8; 32]>>,
+    /// Refuse plaintext writes while `key` is missing
+    encrypted: bool,
 }
 
 impl DiskThreadStore {
     /// Create a new DiskThreadStore with the default path
     ///
-    /// Default: ~/.config/ridge-control/threads/
+    /// Default: <data dir>/threads/ (~/.local/share/ridge-control/threads/ on Linux)
     pub fn new() -> Result<Self, String> {
         let base_path = Self::default_path()?;
         Self::with_path(base_path)
@@ -38,14 +55,45 @@ impl DiskThreadStore {
         Ok(Self {
             base_path,
             cache: RwLock::new(HashMap::new()),
+            key: RwLock::new(None),
+            encrypted: false,
         })
     }
 
-    /// Get the default storage path (~/.config/ridge-control/threads/)
+    /// Encrypt thread files with `key` from now on
+    #[cfg(test)]
+    pub fn with_encryption(self, key: [u8; 32]) -> Self {
+        self.set_encryption_key(key);
+        self.requiring_encryption()
+    }
+
+    /// Encrypt thread files, refusing to save until a key is set
+    pub fn requiring_encryption(mut self) -> Self {
+        self.encrypted = true;
+        self
+    }
+
+    /// Supply the encryption key, e.g. once the keystore is unlocked
+    pub fn set_encryption_key(&self, key: [u8; 32]) {
+        if let Ok(mut slot) = self.key.write() {
+            *slot = Some(key);
+        }
+    }
+
+    /// Encryption is on but no key has been supplied yet
+    pub fn awaiting_key(&self) -> bool {
+        self.encrypted && self.key().is_none()
+    }
+
+    fn key(&self) -> Option<[u8; 32]> {
+        self.key.read().ok().and_then(|key| *key)
+    }
+
+    /// Get the default storage path (<data dir>/threads/)
     pub fn default_path() -> Result<PathBuf, String> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| "Could not determine config directory".to_string())?;
-        Ok(config_dir.join("ridge-control").join("threads"))
+        crate::config::resolve_data_dir(None, None)
+            .map(|dir| dir.join("threads"))
+            .map_err(|e| e.to_string())
     }
 
     /// Get the file path for a thread ID
@@ -66,37 +114,50 @@ impl DiskThreadStore {
     /// Load a thread from disk into cache
     fn load_from_disk(&self, id: &str) -> Result<AgentThread, String> {
         let path = self.thread_path(id);
-        let content = fs::read_to_string(&path)
+        let mut content = fs::read(&path)
             .map_err(|e| format!("Failed to read thread file: {}", e))?;
-        let thread: AgentThread = serde_json::from_str(&content)
+        if is_sealed(&content) {
+            let key = self.key().ok_or_else(|| {
+                if self.encrypted {
+                    "Thread file is encrypted; unlock the keystore to read it".to_string()
+                } else {
+                    "Thread file is encrypted; set agent.encrypt_threads = true".to_string()
+                }
+            })?;
+            content = unseal(&key, &content).map_err(|e| e.to_string())?;
+        }
+        let mut value: serde_json::Value = serde_json::from_slice(&content)
             .map_err(|e| format!("Failed to parse thread JSON: {}", e))?;
+        migrate_thread(&mut value);
+        let thread: AgentThread = serde_json::from_value(value)
+            .map_err(|e| format!("Incompatible thread file: {}", e))?;
         Ok(thread)
     }
 
-    /// Atomic write: write to temp file then rename
+    /// Atomic write: serialize into a temp file, fsync, then rename over the target
     fn atomic_write(&self, id: &str, thread: &AgentThread) -> Result<(), String> {
-        let target_path = self.thread_path(id);
-        let temp_path = self.base_path.join(format!(".{}.tmp", id.replace(['/', '\\', '\0'], "_")));
-
-        // Serialize to JSON with pretty formatting
-        let json = serde_json::to_string_pretty(thread)
+        // Stamp the file version alongside the thread fields
+        let mut value = serde_json::to_value(thread)
             .map_err(|e| format!("Failed to serialize thread: {}", e))?;
-
-        // Write to temp file
-        {
-            let mut file = fs::File::create(&temp_path)
-                .map_err(|e| format!("Failed to create temp file: {}", e))?;
-            file.write_all(json.as_bytes())
-                .map_err(|e| format!("Failed to write temp file: {}", e))?;
-            file.sync_all()
-                .map_err(|e| format!("Failed to sync temp file: {}", e))?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("version".to_string(), THREAD_FILE_VERSION.into());
         }
 
-        // Atomic rename
-        fs::rename(&temp_path, &target_path)
-            .map_err(|e| format!("Failed to rename temp file: {}", e))?;
+        if let Some(key) = self.key() {
+            let json = serde_json::to_vec(&value)
+                .map_err(|e| format!("Failed to serialize thread: {}", e))?;
+            let sealed = seal(&key, &json).map_err(|e| e.to_string())?;
+            return write_atomic_with(&self.thread_path(id), |file| file.write_all(&sealed))
+                .map_err(|e| format!("Failed to write thread file: {}", e));
+        }
+        if self.encrypted {
+            return Err("Thread encryption is on but the keystore is locked; unlock it to save threads".to_string());
+        }
 
-        Ok(())
+        write_atomic_with(&self.thread_path(id), |file| {
+            serde_json::to_writer_pretty(&mut *file, &value).map_err(std::io::Error::from)
+        })
+        .map_err(|e| format!("Failed to write thread file: {}", e))
     }
 }
 
@@ -124,7 +185,13 @@ impl ThreadStore for DiskThreadStore {
                 }
                 Some(thread)
             }
-            Err(_) => None,
+            Err(e) => {
+                // A corrupt file is skipped rather than treated as fatal
+                if self.thread_path(id).exists() {
+                    tracing::warn!("Skipping unreadable thread {}: {}", id, e);
+                }
+                None
+            }
         }
     }
 
@@ -192,16 +259,26 @@ impl ThreadStore for DiskThreadStore {
                     model: thread.model.clone(),
                     updated_at: thread.updated_at,
                     segment_count: thread.segments.len(),
+                    tags: thread.tags.clone(),
                 })
             })
             .collect();
 
         // Sort by most recent first
-        summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
         summaries
     }
 }
 
+/// Upgrade a raw thread object in place to `THREAD_FILE_VERSION`.
+/// Unversioned files already match v1. Files from newer versions are parsed
+/// as-is and may still fail.
+fn migrate_thread(value: &mut serde_json::Value) {
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("version");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,11 +298,13 @@ mod tests {
         let (store, _temp_dir) = create_test_store();
         
         let mut thread = AgentThread::new("gpt-4o").with_title("Test Thread");
-        let segment = ContextSegment::new(
+        thread.set_tags("work");
+        let mut segment = ContextSegment::new(
             SegmentKind::ChatHistory,
             vec![Message::user("Hello, world!")],
             0,
         );
+        segment.token_count = Some(7);
         thread.add_segment(segment);
 
         // Save
@@ -241,9 +320,134 @@ mod tests {
         // Load
         let loaded = store.get(&thread.id).expect("Failed to load thread");
         assert_eq!(loaded.id, thread.id);
+        assert_eq!(loaded.tags, vec!["work"]);
         assert_eq!(loaded.title, "Test Thread");
         assert_eq!(loaded.model, "gpt-4o");
         assert_eq!(loaded.segments.len(), 1);
+        assert_eq!(loaded.segments[0].token_count, Some(7));
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = [42u8; 32];
+        let store = DiskThreadStore::with_path(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_encryption(key);
+
+        let mut thread = AgentThread::new("gpt-4o").with_title("Payroll refactor");
+        thread.add_segment(ContextSegment::new(
+            SegmentKind::ChatHistory,
+            vec![Message::user("the db password is hunter2")],
+            0,
+        ));
+        store.save(&thread).unwrap();
+
+        let raw = fs::read(store.thread_path(&thread.id)).unwrap();
+        assert!(is_sealed(&raw));
+        assert!(!String::from_utf8_lossy(&raw).contains("hunter2"));
+
+        // Reopen with the same key
+        let reopened = DiskThreadStore::with_path(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_encryption(key);
+        let loaded = reopened.get(&thread.id).unwrap();
+        assert_eq!(loaded.title, "Payroll refactor");
+        assert_eq!(
+            serde_json::to_value(&loaded.segments).unwrap(),
+            serde_json::to_value(&thread.segments).unwrap()
+        );
+
+        // Without the key the file is unreadable, not misparsed
+        let plain = DiskThreadStore::with_path(temp_dir.path().to_path_buf()).unwrap();
+        assert!(plain.get(&thread.id).is_none());
+    }
+
+    #[test]
+    fn test_encrypted_store_reads_plaintext_files() {
+        let (plain, temp_dir) = create_test_store();
+        let thread = AgentThread::new("gpt-4o").with_title("Old");
+        plain.save(&thread).unwrap();
+
+        let store = DiskThreadStore::with_path(temp_dir.path().to_path_buf())
+            .unwrap()
+            .with_encryption([1u8; 32]);
+        assert_eq!(store.get(&thread.id).unwrap().title, "Old");
+        store.save(&thread).unwrap();
+        assert!(is_sealed(&fs::read(store.thread_path(&thread.id)).unwrap()));
+    }
+
+    #[test]
+    fn test_store_without_key_refuses_to_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = DiskThreadStore::with_path(temp_dir.path().to_path_buf())
+            .unwrap()
+            .requiring_encryption();
+        let thread = AgentThread::new("gpt-4o").with_title("Secret");
+
+        assert!(store.awaiting_key());
+        assert!(store.save(&thread).is_err());
+        assert!(!store.thread_path(&thread.id).exists());
+
+        store.set_encryption_key([7u8; 32]);
+        assert!(!store.awaiting_key());
+        store.save(&thread).unwrap();
+        assert!(is_sealed(&fs::read(store.thread_path(&thread.id)).unwrap()));
+    }
+
+    #[test]
+    fn test_load_unversioned_thread() {
+        let (store, _temp_dir) = create_test_store();
+
+        // Written before thread files carried a version
+        let unversioned = r#"{
+            "id": "T-legacy",
+            "title": "Legacy",
+            "model": "gpt-4o",
+            "segments": [
+                {"kind": "ChatHistory", "messages": [{"role": "user", "content": [{"Text": "hi"}]}], "token_count": null, "sequence": 4}
+            ],
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z",
+            "next_sequence": 5,
+            "metadata": {"source": "import"}
+        }"#;
+        fs::write(store.thread_path("T-legacy"), unversioned).unwrap();
+
+        let mut thread = store.get("T-legacy").expect("unversioned thread should load");
+        assert_eq!(thread.title, "Legacy");
+        assert_eq!(thread.peek_sequence(), 5);
+        assert_eq!(thread.metadata["source"], "import");
+
+        // Re-saving writes the current version
+        thread.add_segment(ContextSegment::new(SegmentKind::ChatHistory, vec![Message::user("again")], 0));
+        store.save(&thread).unwrap();
+        let raw: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(store.thread_path("T-legacy")).unwrap()).unwrap();
+        assert_eq!(raw["version"], THREAD_FILE_VERSION);
+    }
+
+    #[test]
+    fn test_disk_store_persists_parameters() {
+        let (store, _temp_dir) = create_test_store();
+
+        let mut thread = AgentThread::new("claude-sonnet-4-20250514");
+        thread.set_parameters(Some(0.3), Some(16_000));
+        store.save(&thread).unwrap();
+        store.cache.write().unwrap().clear();
+
+        let loaded = store.get(&thread.id).unwrap();
+        assert_eq!(loaded.model, "claude-sonnet-4-20250514");
+        assert_eq!(loaded.temperature, Some(0.3));
+        assert_eq!(loaded.max_tokens, Some(16_000));
+
+        // Threads saved before parameters were recorded still load
+        let mut legacy = serde_json::to_value(thread).unwrap();
+        legacy.as_object_mut().unwrap().remove("temperature");
+        legacy.as_object_mut().unwrap().remove("max_tokens");
+        let legacy: AgentThread = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.temperature, None);
+        assert_eq!(legacy.max_tokens, None);
     }
 
     #[test]
@@ -347,6 +551,20 @@ mod tests {
         assert_eq!(loaded1.id, loaded2.id);
     }
 
+    #[test]
+    fn test_disk_store_skips_corrupt_thread() {
+        let (store, _temp_dir) = create_test_store();
+
+        fs::write(store.thread_path("broken"), "{ not json").unwrap();
+
+        assert!(store.get("broken").is_none());
+        assert!(store.get("missing").is_none());
+        // Corrupt files don't break summaries of the remaining threads
+        let thread = AgentThread::new("gpt-4o");
+        store.save(&thread).unwrap();
+        assert_eq!(store.list_summary().len(), 1);
+    }
+
     #[test]
     fn test_disk_store_path_sanitization() {
         let (store, _temp_dir) = create_test_store();
@@ -1,16 +1,28 @@
 //! Agent engine - main state machine for the agent loop
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use tokio::sync::mpsc;
 
-use crate::llm::types::{ContentBlock, Message, Role, StopReason, StreamChunk, ToolDefinition, ToolResult, ToolUse, Usage};
+use crate::llm::types::{ContentBlock, ImageContent, Message, Role, StopReason, StreamChunk, ThinkingConfig, ToolDefinition, ToolResult, ToolUse, Usage};
 use crate::llm::{LLMEvent, LLMManager};
+use crate::config::PersonaTemplate;
 
-use super::context::{BuildContextParams, ContextManager, ContextSegment, SegmentKind};
-use super::prompt::{SystemPromptBuilder, PlatformInfo};
-use super::thread::{AgentThread, ThreadStore};
+use super::context::{compact_tool_definitions, BuildContextParams, ContextManager, ContextSegment, SegmentKind};
+use super::plan::Plan;
+use super::prompt::{SystemPromptBuilder, PlatformInfo, PromptRedaction};
+use super::thread::{AgentThread, ThreadStore, ThreadSummary};
 use super::tools::AgentToolOrchestrator;
+use super::usage::TurnUsage;
+
+/// A user message with its attached images ahead of the text
+fn user_message(text: impl Into<String>, images: Vec<ImageContent>) -> Message {
+    let mut message = Message::user(text);
+    message.content.splice(0..0, images.into_iter().map(ContentBlock::Image));
+    message
+}
 
 /// Maximum length for auto-generated thread titles
 const MAX_TITLE_LENGTH: usize = 60;
@@ -60,6 +72,8 @@ pub enum AgentState {
     ExecutingTools,
     /// Finalizing the turn (saving to thread)
     FinalizingTurn,
+    /// Tool results collected; holding before the next continuation until resumed
+    Paused,
     /// Error state (recoverable)
     Error,
 }
@@ -92,6 +106,25 @@ pub enum AgentEvent {
         tokens_used: u32,
         budget: u32,
     },
+    /// Current turn switched to a fallback model after the primary failed;
+    /// `after` is the provider whose error caused the switch
+    ModelFallback {
+        provider: String,
+        model: String,
+        after: String,
+    },
+    /// The provider's rate limit is used up; the request waits `delay`
+    Throttled {
+        provider: String,
+        delay: std::time::Duration,
+    },
+    /// A transient error is being retried after `delay`
+    Retrying {
+        retry: u32,
+        max_retries: u32,
+        delay: std::time::Duration,
+        reason: String,
+    },
 }
 
 /// Configuration for the agent engine
@@ -104,6 +137,8 @@ pub struct AgentConfig {
     pub max_turns: usize,
     /// Whether to auto-continue after tool execution
     pub auto_continue: bool,
+    /// Send shortened tool descriptions to save context
+    pub compact_tool_descriptions: bool,
 }
 
 impl Default for AgentConfig {
@@ -112,6 +147,7 @@ impl Default for AgentConfig {
             tools: Vec::new(),
             max_turns: 10,
             auto_continue: true,
+            compact_tool_descriptions: false,
         }
     }
 }
@@ -143,6 +179,31 @@ pub struct AgentEngine<S: ThreadStore> {
     current_response: Vec<ContentBlock>,
     /// Pending tool uses
     pending_tools: Vec<ToolUse>,
+    /// Usage reported by the provider for the current response
+    current_usage: Option<Usage>,
+    /// Model that served the current response, when the LLM fell back to another
+    fallback_model: Option<String>,
+    /// Sampling temperature for requests (None = provider default)
+    temperature: Option<f32>,
+    /// Max output tokens for requests (None = model default)
+    max_tokens: Option<u32>,
+    /// Ask for prompt caching of the system prompt and tools (llm.prompt_cache)
+    prompt_cache: bool,
+    /// Hold at the next tool-result boundary instead of continuing
+    pause_requested: bool,
+    /// Step-through mode: hold after every round of tool results
+    step_mode: bool,
+}
+
+/// Outcome of resuming a saved thread
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadResume {
+    /// Model the thread will continue on
+    pub model: String,
+    /// Saved model that could not be restored, if the thread fell back
+    pub unavailable_model: Option<String>,
+    /// Persona name saved with the thread, for the caller to resolve
+    pub persona: Option<String>,
 }
 
 impl<S: ThreadStore> AgentEngine<S> {
@@ -167,6 +228,13 @@ impl<S: ThreadStore> AgentEngine<S> {
             turn_count: 0,
             current_response: Vec::new(),
             pending_tools: Vec::new(),
+            current_usage: None,
+            fallback_model: None,
+            temperature: None,
+            max_tokens: None,
+            prompt_cache: false,
+            pause_requested: false,
+            step_mode: false,
         }
     }
 
@@ -199,22 +267,60 @@ impl<S: ThreadStore> AgentEngine<S> {
 
     /// Start a new conversation thread
     pub fn new_thread(&mut self, model: impl Into<String>) {
-        let thread = AgentThread::new(model);
+        let mut thread = AgentThread::new(model);
+        thread.set_parameters(self.temperature, self.max_tokens);
+        thread.persona = self.prompt_builder.persona().map(|p| p.name.clone());
         self.current_thread = Some(thread);
         self.turn_count = 0;
+        // Branch and dirty state may have moved since the last conversation
+        self.prompt_builder.refresh_repo();
         self.transition(AgentState::AwaitingUserInput);
     }
 
-    /// Load an existing thread
-    pub fn load_thread(&mut self, id: &str) -> Result<(), String> {
-        let thread = self
+    /// Load an existing thread and restore the model and parameters it ran with.
+    /// A saved model that no registered provider serves leaves the current model
+    /// in place and is reported in `ThreadResume::unavailable_model`.
+    pub fn load_thread(&mut self, id: &str) -> Result<ThreadResume, String> {
+        let mut thread = self
             .thread_store
             .get(id)
             .ok_or_else(|| format!("Thread not found: {}", id))?;
+
+        let saved_model = thread.model.clone();
+        let provider = if saved_model.is_empty() {
+            None
+        } else {
+            self.llm.provider_name_for_model(&saved_model)
+        };
+        let unavailable_model = match provider {
+            Some(provider) => {
+                self.llm.set_provider(&provider);
+                self.llm.set_model(&saved_model);
+                None
+            }
+            None => {
+                tracing::warn!("Thread {} model '{}' is unavailable; keeping '{}'", id, saved_model, self.llm.current_model());
+                Some(saved_model)
+            }
+        };
+
+        if thread.temperature.is_some() {
+            self.set_temperature(thread.temperature);
+        }
+        if thread.max_tokens.is_some() {
+            self.max_tokens = thread.max_tokens;
+        }
+        thread.set_parameters(self.temperature, self.max_tokens);
+
+        let resume = ThreadResume {
+            model: self.llm.current_model().to_string(),
+            unavailable_model,
+            persona: thread.persona.clone(),
+        };
         self.current_thread = Some(thread);
         self.turn_count = 0;
         self.transition(AgentState::AwaitingUserInput);
-        Ok(())
+        Ok(resume)
     }
 
     /// Manually save the current thread to storage
@@ -227,11 +333,72 @@ impl<S: ThreadStore> AgentEngine<S> {
         }
     }
 
+    /// Delete a saved thread. Deleting the current thread cancels any
+    /// in-flight turn and leaves the engine without a thread.
+    /// Returns whether the deleted thread was the current one.
+    pub fn delete_thread(&mut self, id: &str) -> Result<bool, String> {
+        self.thread_store.delete(id)?;
+
+        let was_current = self.current_thread.as_ref().is_some_and(|t| t.id == id);
+        if was_current {
+            self.llm.cancel();
+            self.current_thread = None;
+            self.turn_count = 0;
+            self.transition(AgentState::Idle);
+        }
+        Ok(was_current)
+    }
+
+    /// Saved threads not updated within `max_age`, leaving out the loaded thread
+    pub fn threads_older_than(&self, max_age: chrono::Duration) -> Vec<ThreadSummary> {
+        let cutoff = chrono::Utc::now() - max_age;
+        let current_id = self.current_thread.as_ref().map(|t| t.id.as_str());
+        self.thread_store
+            .list_summary()
+            .into_iter()
+            .filter(|s| s.updated_at < cutoff && current_id != Some(s.id.as_str()))
+            .collect()
+    }
+
+    /// Delete saved threads not updated within `max_age`, keeping the current one.
+    /// Failures don't stop the sweep; they're reported together at the end.
+    pub fn delete_threads_older_than(&mut self, max_age: chrono::Duration) -> Result<usize, String> {
+        let mut deleted = 0;
+        let mut errors = Vec::new();
+        for summary in self.threads_older_than(max_age) {
+            match self.thread_store.delete(&summary.id) {
+                Ok(()) => deleted += 1,
+                Err(e) => errors.push(format!("{}: {}", summary.id, e)),
+            }
+        }
+
+        match errors.first() {
+            None => Ok(deleted),
+            Some(first) => Err(format!(
+                "Deleted {} threads; {} could not be deleted ({})",
+                deleted,
+                errors.len(),
+                first
+            )),
+        }
+    }
+
     /// Get a reference to the thread store for listing threads
     pub fn thread_store(&self) -> &Arc<S> {
         &self.thread_store
     }
 
+    /// Replace the current thread's tags and save it
+    pub fn set_thread_tags(&mut self, input: &str) -> Result<(), String> {
+        match self.current_thread.as_mut() {
+            Some(thread) => {
+                thread.set_tags(input);
+                self.thread_store.save(thread)
+            }
+            None => Err("No active thread to tag".to_string()),
+        }
+    }
+
     /// Rename the current thread
     pub fn rename_thread(&mut self, new_title: &str) -> Result<(), String> {
         if let Some(thread) = self.current_thread.as_mut() {
@@ -254,6 +421,144 @@ impl<S: ThreadStore> AgentEngine<S> {
         self.llm.set_model(model);
     }
 
+    /// Set the sampling temperature for this and later turns of the current thread
+    pub fn set_temperature(&mut self, temperature: Option<f32>) {
+        self.temperature = temperature;
+        self.llm.set_temperature(temperature);
+        if let Some(thread) = self.current_thread.as_mut() {
+            thread.temperature = temperature;
+        }
+    }
+
+    /// Request an extended thinking budget on models that support it
+    pub fn set_thinking(&mut self, enabled: bool, budget_tokens: u32) {
+        self.llm.set_thinking(enabled.then_some(ThinkingConfig {
+            enabled: true,
+            budget_tokens: Some(budget_tokens),
+        }));
+    }
+
+    /// Set the max output tokens for this and later turns of the current thread
+    pub fn set_max_tokens(&mut self, max_tokens: Option<u32>) {
+        self.max_tokens = max_tokens;
+        if let Some(thread) = self.current_thread.as_mut() {
+            thread.max_tokens = max_tokens;
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn temperature(&self) -> Option<f32> {
+        self.temperature
+    }
+
+    #[allow(dead_code)]
+    pub fn max_tokens(&self) -> Option<u32> {
+        self.max_tokens
+    }
+
+    /// Toggle compacted tool descriptions for subsequent requests
+    pub fn set_compact_tool_descriptions(&mut self, compact: bool) {
+        self.config.compact_tool_descriptions = compact;
+    }
+
+    pub fn compact_tool_descriptions(&self) -> bool {
+        self.config.compact_tool_descriptions
+    }
+
+    /// Estimated tokens the tool definitions add to each request for `model`
+    pub fn tool_definition_tokens(&self, model: &str) -> u32 {
+        if self.config.compact_tool_descriptions {
+            self.context_manager.count_tools(model, &compact_tool_definitions(&self.config.tools))
+        } else {
+            self.context_manager.count_tools(model, &self.config.tools)
+        }
+    }
+
+    /// Estimated tokens the full system prompt adds to each request for `model`
+    pub fn system_prompt_tokens(&self, model: &str) -> u32 {
+        self.context_manager.count_text(model, &self.resolved_system_prompt())
+    }
+
+    /// Hash of the system prompt the next request will send; changes whenever
+    /// the persona, redaction, repository or provider changes what gets built
+    pub fn system_prompt_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.resolved_system_prompt().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Estimated request size and budget when sending `message` (with `images`)
+    /// on the current thread would overflow the context window (and drop older messages)
+    pub fn context_overflow(&self, message: &str, images: &[ImageContent]) -> Option<(u32, u32)> {
+        let model = self.current_thread.as_ref().map_or(self.current_model(), |t| t.model.as_str());
+        let history: u32 = self
+            .current_thread
+            .iter()
+            .flat_map(|t| &t.segments)
+            .map(|s| s.token_count.unwrap_or_else(|| self.context_manager.count_messages(model, &s.messages)))
+            .sum();
+        let needed = self.system_prompt_tokens(model)
+            + self.tool_definition_tokens(model)
+            + history
+            + self.context_manager.count_messages(model, &[user_message(message, images.to_vec())]);
+        let budget = self.context_manager.budget_for(model, self.max_tokens);
+        (needed > budget).then_some((needed, budget))
+    }
+
+    /// Re-detect the repository section of the system prompt for a new working directory
+    pub fn set_working_dir(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.prompt_builder.set_repo_dir(dir);
+    }
+
+    /// Set the persona for this and later turns of the current thread
+    pub fn set_persona(&mut self, persona: Option<PersonaTemplate>) {
+        if let Some(thread) = self.current_thread.as_mut() {
+            thread.persona = persona.as_ref().map(|p| p.name.clone());
+        }
+        self.prompt_builder.set_persona(persona);
+    }
+
+    pub fn persona(&self) -> Option<&PersonaTemplate> {
+        self.prompt_builder.persona()
+    }
+
+    /// The system prompt the next request will send, exactly as built
+    pub fn resolved_system_prompt(&self) -> String {
+        self.active_prompt().build()
+    }
+
+    /// Local models (ollama) get a smaller prompt carrying the same redaction and persona
+    fn active_prompt(&self) -> SystemPromptBuilder {
+        if self.llm.current_provider() == "ollama" {
+            SystemPromptBuilder::local_model()
+                .with_platform(PlatformInfo::gather())
+                .with_redaction(self.prompt_builder.redaction().clone())
+                .with_persona(self.prompt_builder.persona().cloned())
+        } else {
+            self.prompt_builder.clone()
+        }
+    }
+
+    /// Update what the system prompt may reveal about this machine
+    pub fn set_prompt_redaction(&mut self, redaction: PromptRedaction) {
+        self.prompt_builder.set_redaction(redaction);
+    }
+
+    /// Set the fallback model chain (llm.fallback_models)
+    pub fn set_fallback_models(&mut self, models: Vec<String>) {
+        self.llm.set_fallback_models(models);
+    }
+
+    /// Set the providers tried after the fallback models (llm.fallback_providers)
+    pub fn set_fallback_providers(&mut self, providers: Vec<(String, Option<String>)>) {
+        self.llm.set_fallback_providers(providers);
+    }
+
+    /// Set how often a transient error is retried on the same model (llm.max_retries)
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.llm.set_max_retries(max_retries);
+    }
+
     /// Get the current LLM provider name
     pub fn current_provider(&self) -> &str {
         self.llm.current_provider()
@@ -269,6 +574,26 @@ impl<S: ThreadStore> AgentEngine<S> {
         self.llm.registered_providers()
     }
 
+    /// Keep the last raw LLM exchange for debugging (llm.toml `debug_capture`)
+    pub fn set_debug_capture(&mut self, enabled: bool) {
+        self.llm.set_debug_capture(enabled);
+    }
+
+    /// Mark the system prompt and tool definitions cacheable (llm.prompt_cache)
+    pub fn set_prompt_cache(&mut self, enabled: bool) {
+        self.prompt_cache = enabled;
+    }
+
+    /// Rate-limit budgets of the current provider, if it reports them
+    pub fn rate_limit(&self) -> Option<crate::llm::rate_limit::RateLimitState> {
+        self.llm.rate_limit()
+    }
+
+    /// Last captured LLM request/response, secrets redacted
+    pub fn last_llm_exchange(&self) -> Option<crate::llm::debug_capture::LlmExchange> {
+        self.llm.last_exchange()
+    }
+
     /// Get mutable access to the internal LLMManager for provider registration
     pub fn llm_manager_mut(&mut self) -> &mut LLMManager {
         &mut self.llm
@@ -280,8 +605,8 @@ impl<S: ThreadStore> AgentEngine<S> {
         self.llm.is_configured()
     }
 
-    /// Send a user message and start the agent loop
-    pub fn send_message(&mut self, message: impl Into<String>) {
+    /// Send a user message, with any images placed before its text, and start the agent loop
+    pub fn send_message(&mut self, message: impl Into<String>, images: Vec<ImageContent>) {
         let message = message.into();
 
         let thread = match self.current_thread.as_mut() {
@@ -299,17 +624,19 @@ impl<S: ThreadStore> AgentEngine<S> {
             tracing::info!("Auto-named thread: {}", auto_title);
         }
 
-        // Add user message as a chat segment
-        let user_msg = Message::user(message);
-        let segment = ContextSegment::new(
+        // Add user message as a chat segment, with its token count for the viewer
+        let user_msg = user_message(message, images);
+        let mut segment = ContextSegment::new(
             SegmentKind::ChatHistory,
             vec![user_msg],
             thread.peek_sequence(),
         );
+        segment.token_count = Some(self.context_manager.count_messages(&thread.model, &segment.messages));
         thread.add_segment(segment);
 
-        // Reset turn counter
+        // Reset turn counter; a pause only applies to the run it was requested in
         self.turn_count = 0;
+        self.pause_requested = false;
         self.current_response.clear();
         self.pending_tools.clear();
 
@@ -354,6 +681,15 @@ impl<S: ThreadStore> AgentEngine<S> {
             }
         };
 
+        // A successful update_plan replaces the thread's plan
+        for result in results.iter().filter(|r| !r.is_error) {
+            let plan_update = self.pending_tools.iter()
+                .find(|t| t.id == result.tool_use_id && t.name == "update_plan");
+            if let Some(Ok(plan)) = plan_update.map(|t| Plan::from_tool_input(&t.input)) {
+                thread.plan = plan;
+            }
+        }
+
         // Add tool results as messages
         let tool_messages: Vec<Message> = results
             .into_iter()
@@ -378,10 +714,77 @@ impl<S: ThreadStore> AgentEngine<S> {
         self.pending_tools.clear();
         self.current_response.clear();
 
+        if self.pause_requested || self.step_mode || !self.config.auto_continue {
+            tracing::info!("⏸ Agent loop paused before continuation");
+            self.pause_requested = false;
+            self.transition(AgentState::Paused);
+            return;
+        }
+
         // Continue the loop
         self.prepare_and_send();
     }
 
+    /// Pause the agent loop at the next safe boundary (after the current tool
+    /// results are collected). Returns false when no run is in progress.
+    pub fn pause(&mut self) -> bool {
+        match self.state {
+            AgentState::PreparingRequest | AgentState::StreamingResponse | AgentState::ExecutingTools => {
+                self.pause_requested = true;
+                true
+            }
+            AgentState::Paused => true,
+            _ => false,
+        }
+    }
+
+    /// Resume a paused loop, or withdraw a pause that has not taken effect yet.
+    /// Returns false when there was nothing to resume.
+    pub fn resume(&mut self) -> bool {
+        if std::mem::take(&mut self.pause_requested) {
+            return true;
+        }
+        if self.state != AgentState::Paused {
+            return false;
+        }
+        self.prepare_and_send();
+        true
+    }
+
+    /// Re-send the request of a turn that ended in an error. The thread already
+    /// holds the user message (or the tool results being continued from), so the
+    /// request is rebuilt from it unchanged and nothing is added. Returns false
+    /// unless the engine is in the Error state.
+    pub fn retry_last_turn(&mut self) -> bool {
+        if self.state != AgentState::Error || self.current_thread.is_none() {
+            return false;
+        }
+        // Partial output of the failed attempt was never saved to the thread
+        self.current_response.clear();
+        self.pending_tools.clear();
+        self.current_usage = None;
+        self.fallback_model = None;
+        self.pause_requested = false;
+        self.prepare_and_send();
+        true
+    }
+
+    /// Enable or disable step-through mode (pause after every tool round)
+    pub fn set_step_mode(&mut self, enabled: bool) {
+        self.step_mode = enabled;
+    }
+
+    /// Enable or disable continuing automatically once tool results are in.
+    /// When off the loop pauses with the results saved until `resume`.
+    pub fn set_auto_continue(&mut self, enabled: bool) {
+        self.config.auto_continue = enabled;
+    }
+
+    /// Whether a pause was requested but the loop has not reached a boundary yet
+    pub fn pause_pending(&self) -> bool {
+        self.pause_requested
+    }
+
     /// Handle an LLM event from the stream
     pub fn handle_llm_event(&mut self, event: LLMEvent) {
         match event {
@@ -395,7 +798,25 @@ impl<S: ThreadStore> AgentEngine<S> {
                 self.emit(AgentEvent::Error(format!("LLM error: {}", e)));
                 self.transition(AgentState::Error);
             }
+            LLMEvent::Fallback { provider, model, after } => {
+                // Record the model actually serving this turn for accurate token counting
+                if let Some(thread) = self.current_thread.as_mut() {
+                    thread.set_model(model.clone());
+                }
+                self.fallback_model = Some(model.clone());
+                self.emit(AgentEvent::ModelFallback { provider, model, after });
+            }
+            LLMEvent::Throttled { provider, delay } => {
+                self.emit(AgentEvent::Throttled { provider, delay });
+            }
+            LLMEvent::Retrying { retry, max_retries, delay, error } => {
+                self.emit(AgentEvent::Retrying { retry, max_retries, delay, reason: error.to_string() });
+            }
             LLMEvent::ToolUseDetected(tool_use) => {
+                if self.pending_tools.iter().any(|t| t.id == tool_use.id) {
+                    tracing::warn!("Ignoring duplicate ToolUseDetected for {}", tool_use.id);
+                    return;
+                }
                 tracing::info!(
                     "🔧 TOOL_DETECTED: id={} name={} (buffering, NOT emitting yet)",
                     tool_use.id, tool_use.name
@@ -417,12 +838,22 @@ impl<S: ThreadStore> AgentEngine<S> {
     /// Cancel current operation
     pub fn cancel(&mut self) {
         self.llm.cancel();
+        self.pause_requested = false;
         self.transition(AgentState::AwaitingUserInput);
     }
 
     fn prepare_and_send(&mut self) {
         self.transition(AgentState::PreparingRequest);
 
+        // Each turn starts on the configured model; a fallback only lasts for its turn
+        self.fallback_model = None;
+        let current_model = self.llm.current_model().to_string();
+        if let Some(thread) = self.current_thread.as_mut() {
+            if !current_model.is_empty() {
+                thread.set_model(current_model);
+            }
+        }
+
         let thread = match self.current_thread.as_ref() {
             Some(t) => t,
             None => return,
@@ -449,19 +880,16 @@ impl<S: ThreadStore> AgentEngine<S> {
         for tool in &tools {
             tracing::debug!("  Tool: {}", tool.name);
         }
-        let active_prompt = if is_local {
-            SystemPromptBuilder::local_model()
-                .with_platform(PlatformInfo::gather())
-        } else {
-            self.prompt_builder.clone()
-        };
+        let active_prompt = self.active_prompt();
         let params = BuildContextParams {
             model: thread.model.clone(),
             system_prompt: Some(active_prompt.build()),
             short_system_prompt: Some(active_prompt.build_short()),
             tools,
             segments: thread.segments.clone(),
-            max_output_tokens: None,
+            max_output_tokens: self.max_tokens,
+            compact_tools: self.config.compact_tool_descriptions,
+            prompt_cache: self.prompt_cache,
         };
 
         let built = self.context_manager.build_request(params);
@@ -490,50 +918,32 @@ impl<S: ThreadStore> AgentEngine<S> {
             tracing::debug!("  [{}] {:?}: {:?}", i, msg.role, content_summary);
         }
         
-        for msg in &built.request.messages {
-            match msg.role {
-                Role::User => {
-                    // Handle different content types
-                    for block in &msg.content {
-                        match block {
-                            ContentBlock::Text(t) => self.llm.add_user_message(t.clone()),
-                            ContentBlock::ToolResult(r) => self.llm.add_tool_result(r.clone()),
-                            _ => {}
-                        }
-                    }
-                }
-                Role::Assistant => {
-                    for block in &msg.content {
-                        match block {
-                            ContentBlock::Text(t) => self.llm.add_assistant_message(t.clone()),
-                            ContentBlock::ToolUse(tu) => self.llm.add_tool_use(tu.clone()),
-                            _ => {}
-                        }
-                    }
-                }
+        // Whole messages, so images and grouped tool results reach the provider.
+        // Thinking is not sent back to providers.
+        for mut msg in built.request.messages {
+            msg.content.retain(|b| !matches!(b, ContentBlock::Thinking(_)));
+            if !msg.content.is_empty() {
+                self.llm.add_message(msg);
             }
         }
 
-        // Use filtered tools for local models (same filter as prepare_and_send)
-        let tools = if self.llm.current_provider() == "ollama" {
-            const LOCAL_MODEL_TOOLS: &[&str] = &[
-                "file_read", "file_write", "list_directory", "grep", "glob",
-                "tree", "bash_execute", "edit", "ask_user",
-            ];
-            self.config.tools.iter()
-                .filter(|t| LOCAL_MODEL_TOOLS.contains(&t.name.as_str()))
-                .cloned()
-                .collect()
-        } else {
-            self.config.tools.clone()
-        };
-        self.llm.continue_after_tool(built.request.system, tools, built.request.max_tokens);
+        // Tools as filtered and trimmed by the context manager
+        self.llm.continue_after_tool(
+            built.request.system,
+            built.request.tools,
+            built.request.max_tokens,
+            built.request.prompt_cache,
+        );
         self.transition(AgentState::StreamingResponse);
     }
 
     fn handle_chunk(&mut self, chunk: StreamChunk) {
         self.emit(AgentEvent::Chunk(chunk.clone()));
 
+        if let StreamChunk::Stop { usage: Some(ref usage), .. } = chunk {
+            self.current_usage = Some(usage.clone());
+        }
+
         // Accumulate response content
         if let StreamChunk::Delta(delta) = chunk {
             match delta {
@@ -568,6 +978,21 @@ impl<S: ThreadStore> AgentEngine<S> {
             self.pending_tools.len()
         );
 
+        let usage = self.current_usage.take();
+        let turn_usage = match (self.current_thread.as_ref(), usage.as_ref()) {
+            (Some(thread), Some(usage)) => Some(TurnUsage {
+                model: self.fallback_model.take().unwrap_or_else(|| thread.model.clone()),
+                input_tokens: usage.input_tokens as u64,
+                output_tokens: usage.output_tokens as u64,
+                thinking_tokens: usage.thinking_tokens.unwrap_or(0) as u64,
+                cache_write_tokens: usage.cache_creation_tokens as u64,
+                cache_read_tokens: usage.cache_read_tokens as u64,
+                tool_calls: self.pending_tools.len() as u32,
+                at: chrono::Utc::now(),
+            }),
+            _ => None,
+        };
+
         // Save assistant response to thread FIRST
         // This ensures ToolUse blocks are persisted BEFORE we start tool execution
         if let Some(thread) = self.current_thread.as_mut() {
@@ -576,11 +1001,12 @@ impl<S: ThreadStore> AgentEngine<S> {
                     role: Role::Assistant,
                     content: self.current_response.clone(),
                 };
-                let segment = ContextSegment::new(
+                let mut segment = ContextSegment::new(
                     SegmentKind::ChatHistory,
                     vec![assistant_msg],
                     thread.peek_sequence(),
                 );
+                segment.usage = turn_usage.clone();
                 thread.add_segment(segment);
                 tracing::info!(
                     "📦 SAVED assistant segment seq={} with {} content blocks",
@@ -590,6 +1016,10 @@ impl<S: ThreadStore> AgentEngine<S> {
             }
         }
 
+        if let (Some(thread), Some(turn_usage)) = (self.current_thread.as_mut(), turn_usage) {
+            thread.record_usage(turn_usage);
+        }
+
         // Check if we have pending tools
         if !self.pending_tools.is_empty() {
             self.transition(AgentState::ExecutingTools);
@@ -607,7 +1037,7 @@ impl<S: ThreadStore> AgentEngine<S> {
             }
             // UI will handle tool execution and call continue_after_tools
         } else {
-            self.finalize_turn(StopReason::EndTurn, None);
+            self.finalize_turn(StopReason::EndTurn, usage);
         }
     }
 
@@ -628,6 +1058,7 @@ impl<S: ThreadStore> AgentEngine<S> {
 
         self.current_response.clear();
         self.pending_tools.clear();
+        self.pause_requested = false;
         self.transition(AgentState::AwaitingUserInput);
     }
 
@@ -695,6 +1126,351 @@ mod tests {
         assert!(matches!(event, AgentEvent::StateChanged(AgentState::AwaitingUserInput)));
     }
 
+    #[test]
+    fn test_context_overflow_estimate() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        assert!(engine.context_overflow("hello", &[]).is_none());
+
+        let (needed, budget) = engine.context_overflow(&"lorem ".repeat(150_000), &[]).unwrap();
+        assert!(needed > budget);
+        assert!(budget < 128_000);
+
+        // Attached images count even when the text is short
+        let image = ImageContent {
+            source: crate::llm::types::ImageSource::Base64("iVBORw0KGgo=".to_string()),
+            media_type: "image/png".to_string(),
+        };
+        let (needed, _) = engine.context_overflow("what changed?", &vec![image; 200]).unwrap();
+        assert!(needed > 200 * 700);
+    }
+
+    /// Engine with the recording mock provider; returns the provider's request log
+    fn create_mock_engine() -> (
+        AgentEngine<InMemoryThreadStore>,
+        mpsc::UnboundedReceiver<LLMEvent>,
+        Arc<std::sync::Mutex<Vec<crate::llm::LLMRequest>>>,
+    ) {
+        create_engine_with(crate::llm::mock::MockProvider::new(
+            crate::llm::mock::MockScript::new(1).turn(vec![crate::llm::mock::MockStep::text("ok")]),
+        ))
+    }
+
+    fn create_engine_with(
+        provider: crate::llm::mock::MockProvider,
+    ) -> (
+        AgentEngine<InMemoryThreadStore>,
+        mpsc::UnboundedReceiver<LLMEvent>,
+        Arc<std::sync::Mutex<Vec<crate::llm::LLMRequest>>>,
+    ) {
+        use crate::llm::mock::{MOCK_MODEL, MOCK_PROVIDER};
+
+        let (mut engine, _rx) = create_test_engine();
+        let llm = engine.llm_manager_mut();
+        let requests = llm.register_recording_provider(provider);
+        llm.set_provider(MOCK_PROVIDER);
+        let llm_rx = llm.take_event_rx().unwrap();
+        engine.new_thread(MOCK_MODEL);
+        (engine, llm_rx, requests)
+    }
+
+    #[tokio::test]
+    async fn test_send_message_delivers_images_to_provider() {
+        let (mut engine, mut llm_rx, requests) = create_mock_engine();
+        let image = ImageContent {
+            source: crate::llm::types::ImageSource::Base64("iVBORw0KGgo=".to_string()),
+            media_type: "image/png".to_string(),
+        };
+        engine.send_message("What is in this screenshot?", vec![image]);
+        // The first stream event arrives after the provider took the request
+        llm_rx.recv().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let user = requests[0].messages.last().unwrap();
+        assert!(matches!(user.role, Role::User));
+        assert!(matches!(
+            &user.content[0],
+            ContentBlock::Image(img) if img.media_type == "image/png"
+                && matches!(&img.source, crate::llm::types::ImageSource::Base64(d) if d == "iVBORw0KGgo=")
+        ));
+        assert!(matches!(&user.content[1], ContentBlock::Text(t) if t == "What is in this screenshot?"));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_cache_flag_reaches_provider() {
+        let (mut engine, mut llm_rx, requests) = create_mock_engine();
+        engine.set_prompt_cache(true);
+        engine.send_message("hello", Vec::new());
+        llm_rx.recv().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].prompt_cache);
+        assert!(requests[0].system.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_attached_image_file_reaches_provider() {
+        use crate::components::chat_input::PastedImage;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Named .txt: the type comes from the PNG signature
+        let path = dir.path().join("screenshot.txt");
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\n0000").unwrap();
+        let image = PastedImage::from_file(&path).unwrap().into_content();
+
+        let (mut engine, mut llm_rx, requests) = create_mock_engine();
+        engine.send_message("Explain this", vec![image]);
+        llm_rx.recv().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        let images: Vec<&ImageContent> = requests[0]
+            .messages
+            .iter()
+            .flat_map(|m| m.content.iter())
+            .filter_map(|b| match b {
+                ContentBlock::Image(img) => Some(img),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].media_type, "image/png");
+    }
+
+    #[tokio::test]
+    async fn test_image_to_provider_without_vision_is_rejected() {
+        use crate::llm::mock::{MockProvider, MockScript, MockStep};
+
+        let provider = MockProvider::new(MockScript::new(1).turn(vec![MockStep::text("ok")])).without_vision();
+        let (mut engine, mut llm_rx, requests) = create_engine_with(provider);
+        let image = ImageContent {
+            source: crate::llm::types::ImageSource::Base64("iVBORw0KGgo=".to_string()),
+            media_type: "image/png".to_string(),
+        };
+        engine.send_message("What is this?", vec![image]);
+
+        let event = llm_rx.recv().await.unwrap();
+        assert!(matches!(
+            event,
+            LLMEvent::Error(crate::llm::types::LLMError::InvalidRequest { ref message })
+                if message.contains("doesn't accept images")
+        ));
+        assert!(requests.lock().unwrap().is_empty(), "the image never reaches the provider");
+    }
+
+    #[test]
+    fn test_pause_holds_after_tool_results_until_resumed() {
+        let (mut engine, mut rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        assert!(!engine.pause(), "nothing to pause between turns");
+
+        let tool_use = ToolUse {
+            id: "toolu_1".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({"path": "/etc/hostname"}),
+        };
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(tool_use));
+        engine.handle_llm_event(LLMEvent::Complete);
+        assert_eq!(engine.state(), AgentState::ExecutingTools);
+
+        // Requested mid-run, the pause waits for the tool results
+        assert!(engine.pause());
+        assert!(engine.pause_pending());
+        assert_eq!(engine.state(), AgentState::ExecutingTools);
+
+        engine.continue_after_tools(vec![ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content: crate::llm::ToolResultContent::Text("host".to_string()),
+            is_error: false,
+        }]);
+        assert_eq!(engine.state(), AgentState::Paused);
+        assert!(!engine.pause_pending());
+        let segments = engine.current_thread().unwrap().segments.len();
+        assert_eq!(segments, 2, "assistant tool use and tool results are both saved");
+        while rx.try_recv().is_ok() {}
+
+        assert!(engine.resume());
+        assert_ne!(engine.state(), AgentState::Paused);
+        assert!(matches!(rx.try_recv(), Ok(AgentEvent::StateChanged(AgentState::PreparingRequest))));
+        assert!(!engine.resume());
+    }
+
+    #[test]
+    fn test_retry_after_error_resends_without_duplicating() {
+        let (mut engine, mut rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        assert!(!engine.retry_last_turn(), "nothing failed yet");
+
+        engine.send_message("hello", Vec::new());
+        engine.handle_llm_event(LLMEvent::Chunk(StreamChunk::Delta(
+            crate::llm::types::StreamDelta::Text("partial".to_string()),
+        )));
+        engine.handle_llm_event(LLMEvent::Error(crate::llm::types::LLMError::StreamInterrupted));
+        assert_eq!(engine.state(), AgentState::Error);
+        while rx.try_recv().is_ok() {}
+
+        assert!(engine.retry_last_turn());
+        assert!(matches!(rx.try_recv(), Ok(AgentEvent::StateChanged(AgentState::PreparingRequest))));
+        let thread = engine.current_thread().unwrap();
+        assert_eq!(thread.segments.len(), 1, "the user message is not added twice");
+        assert!(engine.current_response.is_empty(), "partial output is discarded");
+        assert!(!engine.retry_last_turn(), "only an errored turn can be retried");
+    }
+
+    #[test]
+    fn test_step_mode_pauses_after_every_tool_round() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        engine.set_step_mode(true);
+
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(ToolUse {
+            id: "toolu_1".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({"path": "/etc/hostname"}),
+        }));
+        engine.handle_llm_event(LLMEvent::Complete);
+        engine.continue_after_tools(vec![ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content: crate::llm::ToolResultContent::Text("host".to_string()),
+            is_error: false,
+        }]);
+        assert_eq!(engine.state(), AgentState::Paused);
+        assert!(!engine.pause_pending());
+    }
+
+    #[test]
+    fn test_duplicate_tool_use_requests_once() {
+        let (mut engine, mut rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        while rx.try_recv().is_ok() {}
+
+        let tool_use = ToolUse {
+            id: "toolu_1".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({"path": "/etc/hostname"}),
+        };
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(tool_use.clone()));
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(tool_use));
+        engine.handle_llm_event(LLMEvent::Complete);
+
+        let mut requested = 0;
+        while let Ok(event) = rx.try_recv() {
+            if matches!(event, AgentEvent::ToolUseRequested(ref tu) if tu.id == "toolu_1") {
+                requested += 1;
+            }
+        }
+        assert_eq!(requested, 1, "one tool request must yield exactly one execution");
+    }
+
+    #[test]
+    fn test_update_plan_result_sets_thread_plan() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        engine.set_auto_continue(false);
+
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(ToolUse {
+            id: "toolu_1".to_string(),
+            name: "update_plan".to_string(),
+            input: serde_json::json!({"items": [{"content": "Fix the bug", "status": "in_progress"}]}),
+        }));
+        engine.handle_llm_event(LLMEvent::Complete);
+        engine.continue_after_tools(vec![ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content: crate::llm::ToolResultContent::Text("Plan updated".to_string()),
+            is_error: false,
+        }]);
+
+        let plan = &engine.current_thread().unwrap().plan;
+        assert_eq!(plan.items().len(), 1);
+        assert_eq!(plan.items()[0].status, crate::agent::PlanStatus::InProgress);
+    }
+
+    #[test]
+    fn test_manual_continue_holds_tool_results() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        engine.set_auto_continue(false);
+
+        engine.handle_llm_event(LLMEvent::ToolUseDetected(ToolUse {
+            id: "toolu_1".to_string(),
+            name: "read_file".to_string(),
+            input: serde_json::json!({"path": "/etc/hostname"}),
+        }));
+        engine.handle_llm_event(LLMEvent::Complete);
+        engine.continue_after_tools(vec![ToolResult {
+            tool_use_id: "toolu_1".to_string(),
+            content: crate::llm::ToolResultContent::Text("host".to_string()),
+            is_error: false,
+        }]);
+        assert_eq!(engine.state(), AgentState::Paused);
+        assert_eq!(engine.current_thread().unwrap().segments.len(), 2);
+
+        assert!(engine.resume());
+        assert_ne!(engine.state(), AgentState::Paused);
+    }
+
+    #[test]
+    fn test_delete_current_thread_clears_it() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.new_thread("gpt-4o");
+        engine.save_thread().unwrap();
+        let id = engine.current_thread().unwrap().id.clone();
+
+        assert!(engine.delete_thread(&id).unwrap());
+        assert!(engine.current_thread().is_none());
+        assert_eq!(engine.state(), AgentState::Idle);
+        assert!(engine.thread_store().get(&id).is_none());
+
+        // Deleting some other thread leaves the current one alone
+        engine.new_thread("gpt-4o");
+        let other = AgentThread::new("gpt-4o");
+        engine.thread_store().save(&other).unwrap();
+        assert!(!engine.delete_thread(&other.id).unwrap());
+        assert!(engine.current_thread().is_some());
+    }
+
+    #[test]
+    fn test_delete_threads_older_than_keeps_recent_and_current() {
+        let (mut engine, _rx) = create_test_engine();
+        let store = engine.thread_store().clone();
+
+        let mut old = AgentThread::new("gpt-4o");
+        old.updated_at = chrono::Utc::now() - chrono::Duration::days(40);
+        store.save(&old).unwrap();
+        let recent = AgentThread::new("gpt-4o");
+        store.save(&recent).unwrap();
+
+        // An old thread that is currently loaded survives the sweep
+        let mut current = AgentThread::new("gpt-4o");
+        current.updated_at = chrono::Utc::now() - chrono::Duration::days(90);
+        store.save(&current).unwrap();
+        engine.load_thread(&current.id).unwrap();
+
+        let stale = engine.threads_older_than(chrono::Duration::days(30));
+        assert_eq!(stale.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec![old.id.as_str()]);
+        assert_eq!(engine.delete_threads_older_than(chrono::Duration::days(30)), Ok(1));
+        assert!(store.get(&old.id).is_none());
+        assert!(store.get(&recent.id).is_some());
+        assert!(store.get(&current.id).is_some());
+    }
+
+    #[test]
+    fn test_system_prompt_hash_follows_persona() {
+        let (mut engine, _rx) = create_test_engine();
+        let base = engine.system_prompt_hash();
+        assert_eq!(engine.system_prompt_hash(), base);
+
+        engine.set_persona(Some(PersonaTemplate {
+            name: "reviewer".to_string(),
+            description: String::new(),
+            prefix: Some("Review every change carefully.".to_string()),
+            suffix: None,
+        }));
+        assert_ne!(engine.system_prompt_hash(), base);
+
+        engine.set_persona(None);
+        assert_eq!(engine.system_prompt_hash(), base);
+    }
+
     #[test]
     fn test_generate_title_simple() {
         let title = generate_title_from_message("What is the capital of France?");
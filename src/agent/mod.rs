@@ -18,18 +18,21 @@ pub mod thread;
 pub mod disk_store;
 pub mod tools;
 pub mod engine;
+pub mod usage;
 pub mod subagent;
 pub mod mandrel;
+pub mod plan;
 
 pub use models::{ModelInfo, ModelCatalog, TokenizerKind};
 pub use tokens::{TokenCounter, DefaultTokenCounter};
 pub use context::{ContextSegment, SegmentKind, ContextManager, BuildContextParams, BuiltContext, ContextStats};
-pub use prompt::{SystemPromptBuilder, PlatformInfo, RepoContextInfo};
+pub use prompt::{SystemPromptBuilder, PlatformInfo, PromptRedaction, RepoContextInfo};
 pub use thread::{AgentThread, ThreadStore, InMemoryThreadStore};
 pub use disk_store::DiskThreadStore;
 pub use tools::{AgentToolOrchestrator, ConfirmationRequiredExecutor};
-pub use engine::{AgentEngine, AgentState, AgentEvent, AgentConfig};
-pub use subagent::{SubagentManager, SubagentResult, SubagentStatus, SubagentError};
+pub use plan::{Plan, PlanItem, PlanStatus};
+pub use engine::{AgentEngine, AgentState, AgentEvent, AgentConfig, ThreadResume};
+pub use subagent::{SubagentActivity, SubagentEvent, SubagentLaunch, SubagentManager, SubagentMonitor, SubagentResult, SubagentStatus, SubagentError};
 pub use mandrel::{MandrelClient, MandrelConfig, MandrelError, Context as MandrelContext, Task as MandrelTask};
 
 #[cfg(test)]
@@ -85,11 +88,11 @@ mod integration_tests {
         assert!(gemini_tokens > 0);
         assert!(unknown_tokens > 0);
         
-        // GPT and Claude use cl100k, should be identical
-        assert_eq!(gpt4_tokens, claude_tokens);
-        
-        // Gemini also uses cl100k approximation
-        assert_eq!(gpt4_tokens, gemini_tokens);
+        // Claude's tokenizer splits English finer than OpenAI's BPEs
+        assert!(claude_tokens >= gpt4_tokens);
+
+        // Gemini uses the documented ~4 chars/token
+        assert_eq!(gemini_tokens, (text.len() as f64 / 4.0).ceil() as u32);
         
         // Unknown uses heuristic (chars/4), may differ
         // 50 chars / 4 = ~12-13 tokens
@@ -162,6 +165,8 @@ mod integration_tests {
             tools: vec![],
             segments,
             max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
         };
         
         let built = manager.build_request(params);
@@ -207,6 +212,8 @@ mod integration_tests {
             tools: vec![],
             segments,
             max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
         };
         
         let built = manager.build_request(params);
@@ -264,6 +271,8 @@ mod integration_tests {
             tools: vec![],
             segments,
             max_output_tokens: Some(4096),
+            compact_tools: false,
+            prompt_cache: false,
         };
         
         let built = manager.build_request(params);
@@ -323,7 +332,7 @@ mod integration_tests {
         let (mut engine, mut rx) = create_test_engine();
         
         // Try sending without a thread
-        engine.send_message("Hello");
+        engine.send_message("Hello", Vec::new());
         
         // Should emit an error
         let event = rx.try_recv().unwrap();
@@ -351,6 +360,33 @@ mod integration_tests {
         assert!(matches!(event, AgentEvent::Error(_)));
     }
 
+    #[test]
+    fn test_engine_records_fallback_model_on_thread() {
+        let (mut engine, mut rx) = create_test_engine();
+
+        engine.new_thread("claude-sonnet-4-20250514");
+        // Drain state change event
+        let _ = rx.try_recv();
+
+        engine.handle_llm_event(LLMEvent::Fallback {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            after: "anthropic".to_string(),
+        });
+
+        assert_eq!(engine.current_thread().unwrap().model, "gpt-4o");
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(event, AgentEvent::ModelFallback { ref model, .. } if model == "gpt-4o"));
+
+        // Usage is billed to the model that served the response
+        engine.handle_llm_event(LLMEvent::Chunk(StreamChunk::Stop {
+            reason: StopReason::EndTurn,
+            usage: Some(Usage { input_tokens: 10, output_tokens: 5, ..Default::default() }),
+        }));
+        engine.handle_llm_event(LLMEvent::Complete);
+        assert_eq!(engine.current_thread().unwrap().usage.last().unwrap().model, "gpt-4o");
+    }
+
     #[test]
     fn test_engine_handles_stream_chunks() {
         let (mut engine, mut rx) = create_test_engine();
@@ -444,6 +480,7 @@ mod integration_tests {
             tools: vec![tool],
             max_turns: 5,
             auto_continue: false,
+            compact_tool_descriptions: false,
         };
         
         let engine = engine.with_config(config);
@@ -522,6 +559,7 @@ mod integration_tests {
                 input_tokens: 10,
                 output_tokens: 5,
                 thinking_tokens: None,
+                ..Default::default()
             }),
         }));
         
@@ -597,6 +635,7 @@ mod integration_tests {
                 input_tokens: 50,
                 output_tokens: 20,
                 thinking_tokens: None,
+                ..Default::default()
             }),
         }));
         
@@ -636,6 +675,7 @@ mod integration_tests {
                 input_tokens: 80,
                 output_tokens: 15,
                 thinking_tokens: None,
+                ..Default::default()
             }),
         }));
         
@@ -794,6 +834,7 @@ mod integration_tests {
                 input_tokens: 20,
                 output_tokens: 10,
                 thinking_tokens: None,
+                ..Default::default()
             }),
         }));
         engine.handle_llm_event(LLMEvent::Complete);
@@ -839,6 +880,34 @@ mod integration_tests {
         assert!(has_user_msg, "Loaded thread should contain the original message");
     }
 
+    #[test]
+    fn test_load_thread_restores_model_and_parameters() {
+        let (mut engine, _rx) = create_test_engine();
+        engine.llm_manager_mut().register_anthropic("test-key");
+        engine.set_provider("anthropic");
+        engine.set_temperature(Some(0.7));
+
+        let mut saved = AgentThread::new("claude-sonnet-4-20250514");
+        saved.set_parameters(Some(0.2), Some(12_000));
+        engine.thread_store().save(&saved).unwrap();
+
+        let resume = engine.load_thread(&saved.id).unwrap();
+        assert_eq!(resume.model, "claude-sonnet-4-20250514");
+        assert_eq!(resume.unavailable_model, None);
+        assert_eq!(engine.current_model(), "claude-sonnet-4-20250514");
+        assert_eq!(engine.temperature(), Some(0.2));
+        assert_eq!(engine.max_tokens(), Some(12_000));
+
+        // A model nobody serves any more is reported, not fatal
+        let retired = AgentThread::new("retired-model-1");
+        engine.thread_store().save(&retired).unwrap();
+        let resume = engine.load_thread(&retired.id).unwrap();
+        assert_eq!(resume.unavailable_model.as_deref(), Some("retired-model-1"));
+        assert_eq!(resume.model, "claude-sonnet-4-20250514");
+        // Parameters the thread never recorded carry over from the engine
+        assert_eq!(engine.current_thread().unwrap().temperature, Some(0.2));
+    }
+
     /// Test state transition event sequence for complete turn
     #[test]
     fn test_state_transition_event_sequence() {
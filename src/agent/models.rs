@@ -1,20 +1,48 @@
 //! Model metadata - context windows, tokenizers, and capabilities
 
 use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::usage::cost_of;
+use crate::config::ModelPricing;
+use crate::llm::types::Usage;
 
 /// Tokenizer type for a model family
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TokenizerKind {
-    /// Claude models - use claude-tokenizer crate
+    /// Claude models - cl100k scaled to Claude's tokenizer (no public BPE)
     Claude,
-    /// GPT/OpenAI-compatible - use tiktoken-style BPE
+    /// Current OpenAI models (GPT-4o and later, o-series) - o200k_base BPE
     GptLike,
+    /// GPT-4 / GPT-3.5 generation - cl100k_base BPE
+    Cl100k,
     /// Gemini models - use character heuristic (no public tokenizer)
     Gemini,
     /// Fallback heuristic (chars / 4)
     Heuristic,
 }
 
+impl TokenizerKind {
+    /// Best guess from a model id, for models missing from the catalog
+    pub fn infer(model: &str) -> Self {
+        let model = model.to_lowercase();
+        if model.starts_with("claude") {
+            TokenizerKind::Claude
+        } else if model.starts_with("gpt-4-") || model == "gpt-4" || model.starts_with("gpt-3.5") {
+            TokenizerKind::Cl100k
+        } else if model.starts_with("gpt") || model.starts_with("chatgpt") || ["o1", "o3", "o4"].iter().any(|p| model.starts_with(p)) {
+            TokenizerKind::GptLike
+        } else if model.starts_with("gemini") {
+            TokenizerKind::Gemini
+        } else {
+            TokenizerKind::Heuristic
+        }
+    }
+}
+
 /// Metadata about a specific model
 #[derive(Debug, Clone)]
 pub struct ModelInfo {
@@ -30,8 +58,12 @@ pub struct ModelInfo {
     pub supports_tools: bool,
     /// Whether model supports extended thinking
     pub supports_thinking: bool,
+    /// Whether model accepts image input
+    pub supports_vision: bool,
     /// Provider name (e.g., "anthropic", "openai")
     pub provider: String,
+    /// List price in USD per million tokens; None when unknown
+    pub pricing: Option<ModelPricing>,
 }
 
 impl ModelInfo {
@@ -43,6 +75,7 @@ impl ModelInfo {
         tokenizer: TokenizerKind,
         provider: impl Into<String>,
     ) -> Self {
+        let provider = provider.into();
         Self {
             name: name.into(),
             max_context_tokens,
@@ -50,7 +83,11 @@ impl ModelInfo {
             tokenizer,
             supports_tools: true,
             supports_thinking: false,
-            provider: provider.into(),
+            // Every current Anthropic, OpenAI and Gemini chat model reads images
+            supports_vision: matches!(provider.as_str(), "anthropic" | "openai" | "gemini"),
+            // Local models cost nothing to run
+            pricing: (provider == "ollama").then_some(ModelPricing { input_per_mtok: 0.0, output_per_mtok: 0.0, ..Default::default() }),
+            provider,
         }
     }
 
@@ -59,6 +96,21 @@ impl ModelInfo {
         self
     }
 
+    pub fn with_vision(mut self) -> Self {
+        self.supports_vision = true;
+        self
+    }
+
+    pub fn without_vision(mut self) -> Self {
+        self.supports_vision = false;
+        self
+    }
+
+    pub fn with_pricing(mut self, input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        self.pricing = Some(ModelPricing { input_per_mtok, output_per_mtok, ..Default::default() });
+        self
+    }
+
     #[allow(dead_code)]
     pub fn without_tools(mut self) -> Self {
         self.supports_tools = false;
@@ -66,10 +118,63 @@ impl ModelInfo {
     }
 }
 
+/// One `[models."<id>"]` entry of models.toml. Every field is optional
+/// for a built-in model; a new model needs at least `provider`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ModelEntry {
+    provider: Option<String>,
+    context_window: Option<u32>,
+    max_output_tokens: Option<u32>,
+    tokenizer: Option<TokenizerKind>,
+    input_per_mtok: Option<f64>,
+    output_per_mtok: Option<f64>,
+    thinking: Option<bool>,
+    vision: Option<bool>,
+    tools: Option<bool>,
+}
+
+impl ModelEntry {
+    /// Apply the entry over `base`, or build a new model when there is none
+    fn apply(self, name: &str, base: Option<ModelInfo>) -> Result<ModelInfo, String> {
+        let mut info = match base {
+            Some(info) => info,
+            None => {
+                let provider = self.provider.clone().ok_or("new models need a provider")?;
+                ModelInfo::new(name, 128_000, 4_096, TokenizerKind::infer(name), provider)
+            }
+        };
+        if let Some(provider) = self.provider {
+            info.provider = provider;
+        }
+        if let Some(tokens) = self.context_window {
+            info.max_context_tokens = tokens;
+        }
+        if let Some(tokens) = self.max_output_tokens {
+            info.default_max_output_tokens = tokens;
+        }
+        if let Some(tokenizer) = self.tokenizer {
+            info.tokenizer = tokenizer;
+        }
+        match (self.input_per_mtok, self.output_per_mtok) {
+            (Some(input), Some(output)) => info = info.with_pricing(input, output),
+            (None, None) => {}
+            _ => return Err("set both input_per_mtok and output_per_mtok".to_string()),
+        }
+        info.supports_thinking = self.thinking.unwrap_or(info.supports_thinking);
+        info.supports_vision = self.vision.unwrap_or(info.supports_vision);
+        info.supports_tools = self.tools.unwrap_or(info.supports_tools);
+        Ok(info)
+    }
+}
+
 /// Catalog of known models with their metadata
 #[derive(Debug, Clone)]
 pub struct ModelCatalog {
     models: HashMap<String, ModelInfo>,
+    /// Model names in the order they were first registered (built-ins as
+    /// seeded, then models.toml additions); per-provider lists follow it
+    order: Vec<String>,
 }
 
 impl ModelCatalog {
@@ -77,11 +182,59 @@ impl ModelCatalog {
     pub fn new() -> Self {
         let mut catalog = Self {
             models: HashMap::new(),
+            order: Vec::new(),
         };
         catalog.seed_defaults();
         catalog
     }
 
+    /// Built-in models with `models.toml` merged over them. A missing or empty
+    /// file leaves the built-in list; malformed entries are skipped with a warning.
+    ///
+    /// ```toml
+    /// [models."gpt-4.1"]
+    /// provider = "openai"
+    /// context_window = 1047576
+    /// tokenizer = "gpt_like"
+    /// input_per_mtok = 2.0
+    /// output_per_mtok = 8.0
+    /// ```
+    pub fn from_file(path: &Path) -> Self {
+        let mut catalog = Self::new();
+        match std::fs::read_to_string(path) {
+            Ok(content) => catalog.merge_toml(&content, path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Could not read {}: {}", path.display(), e),
+        }
+        catalog
+    }
+
+    fn merge_toml(&mut self, content: &str, path: &Path) {
+        let mut table: toml::Table = match toml::from_str(content) {
+            Ok(table) => table,
+            Err(e) => {
+                tracing::warn!("Ignoring {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let Some(models) = table.remove("models") else {
+            return;
+        };
+        let toml::Value::Table(models) = models else {
+            tracing::warn!("Ignoring {}: `models` must be a table", path.display());
+            return;
+        };
+        for (name, value) in models {
+            let merged = ModelEntry::deserialize(value)
+                .map_err(|e| e.to_string())
+                .and_then(|entry| entry.apply(&name, self.models.get(&name).cloned()));
+            match merged {
+                Ok(info) => self.register(info),
+                Err(e) => tracing::warn!("Skipping model '{}' in {}: {}", name, path.display(), e),
+            }
+        }
+    }
+
     /// Get model info by name, with fuzzy matching for version-less lookups
     pub fn get(&self, model: &str) -> Option<&ModelInfo> {
         // Exact match first
@@ -107,15 +260,36 @@ impl ModelCatalog {
                 model,
                 128_000,  // Conservative default
                 4_096,
-                TokenizerKind::Heuristic,
+                TokenizerKind::infer(model),
                 "unknown",
             )
         })
     }
 
+    /// List price of a model. Exact ids only: a near match could be a
+    /// differently priced release.
+    pub fn pricing(&self, model: &str) -> Option<&ModelPricing> {
+        self.models.get(model)?.pricing.as_ref()
+    }
+
+    /// Estimated USD cost of a response at list price, prompt-cache writes and
+    /// reads included, or None for models without a list price
+    pub fn estimated_cost(&self, model: &str, usage: &Usage) -> Option<f64> {
+        cost_of(
+            self.pricing(model),
+            usage.input_tokens as u64,
+            usage.output_tokens as u64,
+            usage.cache_creation_tokens as u64,
+            usage.cache_read_tokens as u64,
+        )
+    }
+
     /// Register a custom model
     pub fn register(&mut self, info: ModelInfo) {
-        self.models.insert(info.name.clone(), info);
+        let name = info.name.clone();
+        if self.models.insert(name.clone(), info).is_none() {
+            self.order.push(name);
+        }
     }
 
     /// Sync local models with default URL probing
@@ -130,6 +304,7 @@ impl ModelCatalog {
     pub fn sync_ollama_models_with_url(&mut self, base_url: Option<&str>) {
         // Remove existing hardcoded ollama models
         self.models.retain(|_, m| m.provider != "ollama");
+        self.order.retain(|name| self.models.contains_key(name));
 
         let base_url_owned = base_url.map(|s| s.to_string());
 
@@ -272,14 +447,12 @@ impl ModelCatalog {
         providers
     }
 
-    /// List all models for a specific provider
+    /// List all models for a specific provider, in registration order
     pub fn models_for_provider(&self, provider: &str) -> Vec<&str> {
-        let mut models: Vec<&str> = self.models.values()
-            .filter(|m| m.provider == provider)
-            .map(|m| m.name.as_str())
-            .collect();
-        models.sort();
-        models
+        self.order.iter()
+            .filter(|name| self.models.get(name.as_str()).is_some_and(|m| m.provider == provider))
+            .map(String::as_str)
+            .collect()
     }
 
     fn seed_defaults(&mut self) {
@@ -290,37 +463,46 @@ impl ModelCatalog {
         self.register(
             ModelInfo::new("claude-opus-4-5-20251101", 200_000, 16_384, TokenizerKind::Claude, "anthropic")
                 .with_thinking()
+                .with_pricing(5.0, 25.0)
         );
         self.register(
             ModelInfo::new("claude-sonnet-4-5-20250929", 200_000, 16_384, TokenizerKind::Claude, "anthropic")
                 .with_thinking()
+                .with_pricing(3.0, 15.0)
         );
         self.register(
             ModelInfo::new("claude-haiku-4-5-20251001", 200_000, 8_192, TokenizerKind::Claude, "anthropic")
                 .with_thinking()
+                .with_pricing(1.0, 5.0)
         );
         // Claude 4 series
         self.register(
             ModelInfo::new("claude-sonnet-4-20250514", 200_000, 8_192, TokenizerKind::Claude, "anthropic")
                 .with_thinking()
+                .with_pricing(3.0, 15.0)
         );
         self.register(
             ModelInfo::new("claude-opus-4-20250514", 200_000, 8_192, TokenizerKind::Claude, "anthropic")
                 .with_thinking()
+                .with_pricing(15.0, 75.0)
         );
         // Claude 3.5 series
         self.register(
             ModelInfo::new("claude-3-5-sonnet-20241022", 200_000, 8_192, TokenizerKind::Claude, "anthropic")
+                .with_pricing(3.0, 15.0)
         );
         self.register(
             ModelInfo::new("claude-3-5-haiku-20241022", 200_000, 8_192, TokenizerKind::Claude, "anthropic")
+                .with_pricing(0.8, 4.0)
         );
         // Claude 3 series (legacy)
         self.register(
             ModelInfo::new("claude-3-opus-20240229", 200_000, 4_096, TokenizerKind::Claude, "anthropic")
+                .with_pricing(15.0, 75.0)
         );
         self.register(
             ModelInfo::new("claude-3-haiku-20240307", 200_000, 4_096, TokenizerKind::Claude, "anthropic")
+                .with_pricing(0.25, 1.25)
         );
 
         // ─────────────────────────────────────────────────────────────────────
@@ -343,29 +525,38 @@ impl ModelCatalog {
         );
         self.register(
             ModelInfo::new("gpt-5-mini-2025-08-07", 128_000, 16_384, TokenizerKind::GptLike, "openai")
+                .with_pricing(0.25, 2.0)
         );
         // GPT-4 series
         self.register(
             ModelInfo::new("gpt-4o", 128_000, 16_384, TokenizerKind::GptLike, "openai")
+                .with_pricing(2.5, 10.0)
         );
         self.register(
             ModelInfo::new("gpt-4o-mini", 128_000, 16_384, TokenizerKind::GptLike, "openai")
+                .with_pricing(0.15, 0.6)
         );
         self.register(
-            ModelInfo::new("gpt-4-turbo", 128_000, 4_096, TokenizerKind::GptLike, "openai")
+            ModelInfo::new("gpt-4-turbo", 128_000, 4_096, TokenizerKind::Cl100k, "openai")
+                .with_pricing(10.0, 30.0)
         );
         // o-series (reasoning)
         self.register(
             ModelInfo::new("o1", 200_000, 100_000, TokenizerKind::GptLike, "openai")
                 .with_thinking()
+                .with_pricing(15.0, 60.0)
         );
         self.register(
             ModelInfo::new("o1-mini", 128_000, 65_536, TokenizerKind::GptLike, "openai")
                 .with_thinking()
+                .without_vision()
+                .with_pricing(1.1, 4.4)
         );
         self.register(
             ModelInfo::new("o3-mini", 200_000, 100_000, TokenizerKind::GptLike, "openai")
                 .with_thinking()
+                .without_vision()
+                .with_pricing(1.1, 4.4)
         );
 
         // ─────────────────────────────────────────────────────────────────────
@@ -374,14 +565,17 @@ impl ModelCatalog {
         // Gemini 2.5 series (latest)
         self.register(
             ModelInfo::new("gemini-2.5-flash", 1_000_000, 8_192, TokenizerKind::Gemini, "gemini")
+                .with_pricing(0.3, 2.5)
         );
         self.register(
             ModelInfo::new("gemini-2.5-pro", 1_000_000, 8_192, TokenizerKind::Gemini, "gemini")
                 .with_thinking()
+                .with_pricing(1.25, 10.0)
         );
         // Gemini 2.0 series
         self.register(
             ModelInfo::new("gemini-2.0-flash", 1_000_000, 8_192, TokenizerKind::Gemini, "gemini")
+                .with_pricing(0.1, 0.4)
         );
         // Gemini 1.5 series
         self.register(
@@ -398,20 +592,26 @@ impl ModelCatalog {
         self.register(
             ModelInfo::new("grok-4", 256_000, 32_768, TokenizerKind::GptLike, "grok")
                 .with_thinking()
+                .with_vision()
+                .with_pricing(3.0, 15.0)
         );
         self.register(
             ModelInfo::new("grok-4-fast-reasoning", 2_000_000, 32_768, TokenizerKind::GptLike, "grok")
                 .with_thinking()
+                .with_vision()
         );
         self.register(
             ModelInfo::new("grok-4-fast-non-reasoning", 2_000_000, 32_768, TokenizerKind::GptLike, "grok")
+                .with_vision()
         );
         self.register(
             ModelInfo::new("grok-4-1-fast-reasoning", 2_000_000, 32_768, TokenizerKind::GptLike, "grok")
                 .with_thinking()
+                .with_vision()
         );
         self.register(
             ModelInfo::new("grok-4-1-fast-non-reasoning", 2_000_000, 32_768, TokenizerKind::GptLike, "grok")
+                .with_vision()
         );
         self.register(
             ModelInfo::new("grok-code-fast-1", 256_000, 32_768, TokenizerKind::GptLike, "grok")
@@ -420,6 +620,7 @@ impl ModelCatalog {
         // Grok 3 series
         self.register(
             ModelInfo::new("grok-3", 131_072, 16_384, TokenizerKind::GptLike, "grok")
+                .with_pricing(3.0, 15.0)
         );
         self.register(
             ModelInfo::new("grok-3-mini", 131_072, 16_384, TokenizerKind::GptLike, "grok")
@@ -430,6 +631,7 @@ impl ModelCatalog {
         );
         self.register(
             ModelInfo::new("grok-2-vision-1212", 32_768, 8_192, TokenizerKind::GptLike, "grok")
+                .with_vision()
         );
 
         // ─────────────────────────────────────────────────────────────────────
@@ -437,12 +639,14 @@ impl ModelCatalog {
         // ─────────────────────────────────────────────────────────────────────
         self.register(
             ModelInfo::new("llama-3.3-70b-versatile", 128_000, 8_192, TokenizerKind::GptLike, "groq")
+                .with_pricing(0.59, 0.79)
         );
         self.register(
             ModelInfo::new("llama-3.1-70b-versatile", 128_000, 8_192, TokenizerKind::GptLike, "groq")
         );
         self.register(
             ModelInfo::new("llama-3.1-8b-instant", 128_000, 8_192, TokenizerKind::GptLike, "groq")
+                .with_pricing(0.05, 0.08)
         );
         self.register(
             ModelInfo::new("mixtral-8x7b-32768", 32_768, 4_096, TokenizerKind::GptLike, "groq")
@@ -485,12 +689,89 @@ mod tests {
         assert!(info.supports_thinking);
     }
 
+    #[test]
+    fn test_models_toml_merges_over_defaults() {
+        let mut catalog = ModelCatalog::new();
+        catalog.merge_toml(
+            r#"
+[models."gpt-4.1"]
+provider = "openai"
+context_window = 1047576
+tokenizer = "gpt_like"
+input_per_mtok = 2.0
+output_per_mtok = 8.0
+
+[models."claude-sonnet-4-20250514"]
+context_window = 1000000
+
+[models."no-provider"]
+context_window = 1000
+
+[models."bad-tokenizer"]
+provider = "openai"
+tokenizer = "sentencepiece"
+"#,
+            Path::new("models.toml"),
+        );
+
+        let added = catalog.get("gpt-4.1").unwrap();
+        assert_eq!(added.max_context_tokens, 1_047_576);
+        assert_eq!(added.pricing.as_ref().unwrap().output_per_mtok, 8.0);
+        // Additions come after the built-ins; overrides keep their place
+        assert_eq!(catalog.models_for_provider("openai").last(), Some(&"gpt-4.1"));
+        assert_eq!(catalog.models_for_provider("anthropic"), ModelCatalog::new().models_for_provider("anthropic"));
+
+        // Overrides keep the built-in fields they don't mention
+        let sonnet = catalog.get("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.max_context_tokens, 1_000_000);
+        assert!(sonnet.supports_thinking);
+        assert!(sonnet.pricing.is_some());
+
+        // Malformed entries are skipped, the rest of the file still applies
+        assert!(!catalog.list().contains(&"no-provider"));
+        assert!(!catalog.list().contains(&"bad-tokenizer"));
+    }
+
+    #[test]
+    fn test_from_file_falls_back_to_defaults() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("models.toml");
+        let defaults = ModelCatalog::new().list().len();
+        assert_eq!(ModelCatalog::from_file(&path).list().len(), defaults);
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(ModelCatalog::from_file(&path).list().len(), defaults);
+        std::fs::write(&path, "not [valid").unwrap();
+        assert_eq!(ModelCatalog::from_file(&path).list().len(), defaults);
+    }
+
+    #[test]
+    fn test_estimated_cost() {
+        let catalog = ModelCatalog::new();
+        let usage = Usage { input_tokens: 1_000_000, output_tokens: 200_000, thinking_tokens: None, ..Default::default() };
+        assert_eq!(catalog.estimated_cost("claude-opus-4-20250514", &usage), Some(30.0));
+        assert_eq!(catalog.estimated_cost("qwen3:8b", &usage), Some(0.0));
+        // Unknown models and near matches aren't guessed
+        assert_eq!(catalog.estimated_cost("unknown-model-xyz", &usage), None);
+        assert_eq!(catalog.estimated_cost("claude-opus-4", &usage), None);
+
+        // Cache writes at 1.25x and reads at 0.1x the $15 input price
+        let cached = Usage { cache_creation_tokens: 100_000, cache_read_tokens: 1_000_000, ..usage };
+        let cost = catalog.estimated_cost("claude-opus-4-20250514", &cached).unwrap();
+        assert!((cost - 33.375).abs() < 1e-9);
+    }
+
     #[test]
     fn test_catalog_fallback() {
         let catalog = ModelCatalog::new();
         let info = catalog.info_for("unknown-model-xyz");
         assert_eq!(info.max_context_tokens, 128_000);
         assert_eq!(info.tokenizer, TokenizerKind::Heuristic);
+
+        // Unlisted releases still get their family's tokenizer
+        assert_eq!(catalog.info_for("claude-future-5").tokenizer, TokenizerKind::Claude);
+        assert_eq!(catalog.info_for("gpt-4-0613").tokenizer, TokenizerKind::Cl100k);
+        assert_eq!(catalog.info_for("gpt-4.1").tokenizer, TokenizerKind::GptLike);
     }
 
     #[test]
@@ -501,6 +782,17 @@ mod tests {
         assert_eq!(info.tokenizer, TokenizerKind::GptLike);
     }
 
+    #[test]
+    fn test_catalog_vision() {
+        let catalog = ModelCatalog::new();
+        assert!(catalog.info_for("claude-sonnet-4-20250514").supports_vision);
+        assert!(catalog.info_for("gpt-4o").supports_vision);
+        assert!(!catalog.info_for("o3-mini").supports_vision);
+        assert!(catalog.info_for("grok-2-vision-1212").supports_vision);
+        assert!(!catalog.info_for("llama-3.3-70b-versatile").supports_vision);
+        assert!(!catalog.info_for("unknown-model-xyz").supports_vision);
+    }
+
     #[test]
     fn test_catalog_gemini() {
         let catalog = ModelCatalog::new();
@@ -0,0 +1,122 @@
+//! Agent plan - the step list the agent keeps up to date with the update_plan tool
+
+use serde::{Deserialize, Serialize};
+
+/// Progress of one plan step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    Pending,
+    InProgress,
+    Completed,
+}
+
+impl PlanStatus {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(Self::Pending),
+            "in_progress" => Some(Self::InProgress),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// One step of the plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanItem {
+    pub content: String,
+    pub status: PlanStatus,
+}
+
+/// The agent's current plan; each update_plan call replaces it whole
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Plan {
+    items: Vec<PlanItem>,
+}
+
+impl Plan {
+    /// Parse the `items` array of an update_plan tool call
+    pub fn from_tool_input(input: &serde_json::Value) -> Result<Self, String> {
+        let items = input
+            .get("items")
+            .and_then(|i| i.as_array())
+            .ok_or_else(|| "'items' must be an array".to_string())?;
+
+        let items = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let content = item
+                    .get("content")
+                    .and_then(|c| c.as_str())
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .ok_or_else(|| format!("Item {} is missing 'content'", i + 1))?;
+                let status = item.get("status").and_then(|s| s.as_str()).unwrap_or("pending");
+                let status = PlanStatus::parse(status).ok_or_else(|| {
+                    format!("Item {} has unknown status '{}' (pending, in_progress, completed)", i + 1, status)
+                })?;
+                Ok(PlanItem { content: content.to_string(), status })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self { items })
+    }
+
+    pub fn items(&self) -> &[PlanItem] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn completed(&self) -> usize {
+        self.items.iter().filter(|i| i.status == PlanStatus::Completed).count()
+    }
+
+    /// Short confirmation returned to the model, e.g. "Plan updated: 2/5 completed"
+    pub fn summary(&self) -> String {
+        if self.items.is_empty() {
+            return "Plan cleared".to_string();
+        }
+        let mut summary = format!("Plan updated: {}/{} completed", self.completed(), self.items.len());
+        if let Some(current) = self.items.iter().find(|i| i.status == PlanStatus::InProgress) {
+            summary.push_str(&format!(", in progress: {}", current.content));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plan_from_tool_input() {
+        let plan = Plan::from_tool_input(&json!({"items": [
+            {"content": "Read the parser", "status": "completed"},
+            {"content": "Add the flag", "status": "in_progress"},
+            {"content": " Write tests "},
+        ]}))
+        .unwrap();
+        assert_eq!(plan.items().len(), 3);
+        assert_eq!(plan.items()[2], PlanItem { content: "Write tests".to_string(), status: PlanStatus::Pending });
+        assert_eq!(plan.summary(), "Plan updated: 1/3 completed, in progress: Add the flag");
+
+        assert!(Plan::from_tool_input(&json!({"items": [{"content": "x", "status": "done"}]})).is_err());
+        assert!(Plan::from_tool_input(&json!({"items": [{"status": "pending"}]})).is_err());
+        assert!(Plan::from_tool_input(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_plan_serializes_as_list() {
+        let plan = Plan::from_tool_input(&json!({"items": [{"content": "a", "status": "in_progress"}]})).unwrap();
+        let value = serde_json::to_value(&plan).unwrap();
+        assert_eq!(value, json!([{"content": "a", "status": "in_progress"}]));
+        assert_eq!(serde_json::from_value::<Plan>(value).unwrap(), plan);
+    }
+}
@@ -1,6 +1,10 @@
 //! System prompt builder with platform and repository awareness
 
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, OnceLock};
+
+use crate::config::{PersonaTemplate, PromptConfig};
 
 /// Platform information for system prompt
 #[derive(Debug, Clone, Default)]
@@ -33,12 +37,95 @@ impl PlatformInfo {
     }
 }
 
+/// Controls which machine-identifying details reach the system prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptRedaction {
+    /// Replace the home directory prefix with `~`
+    pub redact_paths: bool,
+    /// Replace the username and hostname with placeholders
+    pub redact_user: bool,
+    /// Leave the repository root out of the prompt entirely
+    pub omit_repo_path: bool,
+}
+
+impl PromptRedaction {
+    /// Redact a path or value using the current user's home, username and hostname
+    pub fn apply(&self, value: &str, home: &str) -> String {
+        let user = std::env::var("USER")
+            .ok()
+            .or_else(|| {
+                std::path::Path::new(home)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+        let host = std::env::var("HOSTNAME")
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|h| h.trim().to_string())
+            .unwrap_or_default();
+        self.apply_with(value, home, &user, &host)
+    }
+
+    fn apply_with(&self, value: &str, home: &str, user: &str, host: &str) -> String {
+        let mut out = value.to_string();
+        if self.redact_paths && !home.is_empty() && home != "/" {
+            if out == home {
+                out = "~".to_string();
+            } else if let Some(rest) = out.strip_prefix(&format!("{}/", home.trim_end_matches('/'))) {
+                out = format!("~/{}", rest);
+            }
+        }
+        if self.redact_user {
+            out = replace_whole_word(&out, user, "<user>");
+            out = replace_whole_word(&out, host, "<host>");
+        }
+        out
+    }
+}
+
+/// Replace `word` only where it isn't part of a longer name, so user `al`
+/// redacts `/home/al/x` but leaves `local` alone
+fn replace_whole_word(text: &str, word: &str, replacement: &str) -> String {
+    if word.is_empty() {
+        return text.to_string();
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(idx) = rest.find(word) {
+        let before = if idx > 0 { rest[..idx].chars().last() } else { out.chars().last() };
+        let after = rest[idx + word.len()..].chars().next();
+        out.push_str(&rest[..idx]);
+        if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+            out.push_str(word);
+        } else {
+            out.push_str(replacement);
+        }
+        rest = &rest[idx + word.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+impl From<&PromptConfig> for PromptRedaction {
+    fn from(config: &PromptConfig) -> Self {
+        Self {
+            redact_paths: config.redact_paths,
+            redact_user: config.redact_user,
+            omit_repo_path: config.omit_repo_path,
+        }
+    }
+}
+
 /// Repository context information
 #[derive(Debug, Clone, Default)]
 pub struct RepoContextInfo {
     pub root: Option<String>,
+    pub name: Option<String>,
     pub branch: Option<String>,
-    pub has_uncommitted: bool,
+    /// Whether the working tree has uncommitted changes; None until checked
+    pub has_uncommitted: Option<bool>,
     pub language: Option<String>,
 }
 
@@ -46,52 +133,60 @@ impl RepoContextInfo {
     /// Gather git repository information from current directory
     #[allow(dead_code)]
     pub fn gather() -> Self {
-        let mut info = Self::default();
+        std::env::current_dir()
+            .ok()
+            .and_then(|dir| Self::detect(&dir))
+            .unwrap_or_default()
+    }
 
-        // Get git root
-        if let Ok(output) = Command::new("git")
-            .args(["rev-parse", "--show-toplevel"])
-            .output()
-        {
-            if output.status.success() {
-                info.root = Some(
-                    String::from_utf8_lossy(&output.stdout)
-                        .trim()
-                        .to_string(),
-                );
-            }
-        }
+    /// Detect the git repository containing `dir`, or None if it isn't in one.
+    /// The branch comes straight from `.git/HEAD`; the dirty state is left
+    /// unknown, see `has_uncommitted_changes`.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        let root = dir.ancestors().find(|d| d.join(".git").exists())?;
+        let git_dir = Self::resolve_git_dir(root)?;
+
+        let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+        let head = head.trim();
+        let branch = match head.strip_prefix("ref: ") {
+            Some(reference) => reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string(),
+            // Detached HEAD: show the short commit id
+            None => format!("detached at {}", head.chars().take(7).collect::<String>()),
+        };
+
+        Some(Self {
+            root: Some(root.display().to_string()),
+            name: root.file_name().map(|n| n.to_string_lossy().to_string()),
+            branch: Some(branch),
+            has_uncommitted: None,
+            language: Self::detect_language(root),
+        })
+    }
 
-        // Get current branch
-        if let Ok(output) = Command::new("git")
-            .args(["branch", "--show-current"])
+    /// Run `git status` in `root`; slow on large trees, so keep it off the UI thread.
+    /// None if git could not tell.
+    pub fn has_uncommitted_changes(root: &Path) -> Option<bool> {
+        Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .current_dir(root)
             .output()
-        {
-            if output.status.success() {
-                let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !branch.is_empty() {
-                    info.branch = Some(branch);
-                }
-            }
-        }
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| !o.stdout.is_empty())
+    }
 
-        // Check for uncommitted changes
-        if let Ok(output) = Command::new("git")
-            .args(["status", "--porcelain"])
-            .output()
-        {
-            if output.status.success() {
-                info.has_uncommitted = !output.stdout.is_empty();
-            }
+    /// `.git` is usually a directory, but worktrees and submodules use a `gitdir:` file
+    fn resolve_git_dir(root: &Path) -> Option<PathBuf> {
+        let dot_git = root.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
         }
-
-        // Detect primary language from common files
-        info.language = Self::detect_language();
-
-        info
+        let content = std::fs::read_to_string(&dot_git).ok()?;
+        let target = content.trim().strip_prefix("gitdir:")?.trim();
+        Some(root.join(target))
     }
 
-    fn detect_language() -> Option<String> {
+    fn detect_language(root: &Path) -> Option<String> {
         let indicators = [
             ("Cargo.toml", "Rust"),
             ("package.json", "JavaScript/TypeScript"),
@@ -105,13 +200,31 @@ impl RepoContextInfo {
         ];
 
         for (file, lang) in indicators {
-            if std::path::Path::new(file).exists() {
+            if root.join(file).exists() {
                 return Some(lang.to_string());
             }
         }
 
         None
     }
+
+    /// One-line summary, e.g. "You are on branch feature/x with uncommitted changes."
+    /// The dirty clause is left out while the state is unknown.
+    fn situation(&self) -> Option<String> {
+        let Some(branch) = self.branch.as_ref() else {
+            return (self.has_uncommitted == Some(true)).then(|| "The working tree has uncommitted changes.".to_string());
+        };
+        let state = match self.has_uncommitted {
+            Some(true) => " with uncommitted changes",
+            Some(false) => " with a clean working tree",
+            None => "",
+        };
+        if branch.starts_with("detached at ") {
+            Some(format!("You are {}{}.", branch, state))
+        } else {
+            Some(format!("You are on branch {}{}.", branch, state))
+        }
+    }
 }
 
 /// Builder for constructing system prompts
@@ -124,6 +237,14 @@ pub struct SystemPromptBuilder {
     tool_instructions: Vec<String>,
     user_preferences: Vec<String>,
     coding_guidelines: Vec<String>,
+    redaction: PromptRedaction,
+    /// Directory the repo section was detected from, for refreshes
+    repo_dir: Option<PathBuf>,
+    /// Dirty state from the background `git status` of the last refresh; unset
+    /// until it finishes
+    repo_dirty: Arc<OnceLock<bool>>,
+    /// Active persona template (None = base prompt only)
+    persona: Option<PersonaTemplate>,
 }
 
 impl SystemPromptBuilder {
@@ -135,6 +256,10 @@ impl SystemPromptBuilder {
             tool_instructions: Vec::new(),
             user_preferences: Vec::new(),
             coding_guidelines: Vec::new(),
+            redaction: PromptRedaction::default(),
+            repo_dir: None,
+            repo_dirty: Arc::default(),
+            persona: None,
         }
     }
 
@@ -145,6 +270,7 @@ impl SystemPromptBuilder {
              You help users with software development tasks including writing code, debugging, \
              explaining concepts, and using system tools. You are concise, accurate, and proactive."
         )
+        .with_repo_at(std::env::current_dir().unwrap_or_default())
         // Cross-session memory tools (Mandrel integration)
         .add_tool_instruction(
             "You have access to Mandrel for cross-session memory. These tools are available on-demand:"
@@ -221,6 +347,80 @@ impl SystemPromptBuilder {
         self
     }
 
+    /// Populate the repository section from the git repo containing `dir`
+    pub fn with_repo_at(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.set_repo_dir(dir);
+        self
+    }
+
+    /// Point the repository section at a new working directory
+    pub fn set_repo_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.repo_dir = Some(dir.into());
+        self.refresh_repo();
+    }
+
+    /// Re-read branch and dirty state; the section is omitted outside a repo.
+    /// The branch is read right away; `git status` runs on a blocking thread
+    /// and its answer shows up in the next prompt built after it finishes;
+    /// until then the prompt says nothing about uncommitted changes.
+    pub fn refresh_repo(&mut self) {
+        let Some(ref dir) = self.repo_dir else {
+            return;
+        };
+        self.repo = RepoContextInfo::detect(dir);
+        // A fresh cell, so a check still running for the old state can't land here
+        self.repo_dirty = Arc::default();
+        let Some(root) = self.repo.as_ref().and_then(|r| r.root.clone()).map(PathBuf::from) else {
+            return;
+        };
+        let dirty = self.repo_dirty.clone();
+        let check = move || {
+            if let Some(has_uncommitted) = RepoContextInfo::has_uncommitted_changes(&root) {
+                let _ = dirty.set(has_uncommitted);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => drop(handle.spawn_blocking(check)),
+            Err(_) => check(),
+        }
+    }
+
+    pub fn with_redaction(mut self, redaction: PromptRedaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    pub fn redaction(&self) -> &PromptRedaction {
+        &self.redaction
+    }
+
+    pub fn set_redaction(&mut self, redaction: PromptRedaction) {
+        self.redaction = redaction;
+    }
+
+    /// Compose a persona template with the base prompt
+    pub fn with_persona(mut self, persona: Option<PersonaTemplate>) -> Self {
+        self.persona = persona;
+        self
+    }
+
+    pub fn persona(&self) -> Option<&PersonaTemplate> {
+        self.persona.as_ref()
+    }
+
+    pub fn set_persona(&mut self, persona: Option<PersonaTemplate>) {
+        self.persona = persona;
+    }
+
+    /// Home directory used for redaction (from platform info when available)
+    fn home(&self) -> String {
+        self.platform
+            .as_ref()
+            .map(|p| p.home.clone())
+            .or_else(|| dirs::home_dir().map(|p| p.display().to_string()))
+            .unwrap_or_default()
+    }
+
     pub fn add_tool_instruction(mut self, instruction: impl Into<String>) -> Self {
         self.tool_instructions.push(instruction.into());
         self
@@ -244,20 +444,38 @@ impl SystemPromptBuilder {
 
         // Role
         parts.push(self.role.clone());
+        if let Some(prefix) = self.persona.as_ref().and_then(|p| p.prefix.as_ref()) {
+            parts.push(prefix.clone());
+        }
+
+        let home = self.home();
 
         // Platform context
         if let Some(ref platform) = self.platform {
             parts.push(format!(
                 "\n## Environment\n- OS: {} ({})\n- Shell: {}\n- Working directory: {}\n- Date: {}",
-                platform.os, platform.arch, platform.shell, platform.cwd, platform.date
+                platform.os,
+                platform.arch,
+                self.redaction.apply(&platform.shell, &home),
+                self.redaction.apply(&platform.cwd, &home),
+                platform.date
             ));
         }
 
         // Repository context
-        if let Some(ref repo) = self.repo {
+        let repo = self.repo.clone().map(|mut repo| {
+            repo.has_uncommitted = self.repo_dirty.get().copied().or(repo.has_uncommitted);
+            repo
+        });
+        if let Some(ref repo) = repo {
             let mut repo_lines = Vec::new();
             if let Some(ref root) = repo.root {
-                repo_lines.push(format!("- Repository root: {}", root));
+                if !self.redaction.omit_repo_path {
+                    repo_lines.push(format!("- Repository root: {}", self.redaction.apply(root, &home)));
+                }
+            }
+            if let Some(ref name) = repo.name {
+                repo_lines.push(format!("- Repository: {}", self.redaction.apply(name, &home)));
             }
             if let Some(ref branch) = repo.branch {
                 repo_lines.push(format!("- Branch: {}", branch));
@@ -265,8 +483,8 @@ impl SystemPromptBuilder {
             if let Some(ref lang) = repo.language {
                 repo_lines.push(format!("- Primary language: {}", lang));
             }
-            if repo.has_uncommitted {
-                repo_lines.push("- Has uncommitted changes".to_string());
+            if let Some(situation) = repo.situation() {
+                repo_lines.push(format!("- {}", situation));
             }
             if !repo_lines.is_empty() {
                 parts.push(format!("\n## Repository\n{}", repo_lines.join("\n")));
@@ -309,6 +527,10 @@ impl SystemPromptBuilder {
             ));
         }
 
+        if let Some(suffix) = self.persona.as_ref().and_then(|p| p.suffix.as_ref()) {
+            parts.push(format!("\n{}", suffix));
+        }
+
         parts.join("\n")
     }
 
@@ -316,13 +538,21 @@ impl SystemPromptBuilder {
     pub fn build_short(&self) -> String {
         let mut parts = Vec::new();
         parts.push(self.role.clone());
+        if let Some(prefix) = self.persona.as_ref().and_then(|p| p.prefix.as_ref()) {
+            parts.push(prefix.clone());
+        }
 
         if let Some(ref platform) = self.platform {
             parts.push(format!(
                 "Environment: {} {}, cwd: {}",
-                platform.os, platform.arch, platform.cwd
+                platform.os,
+                platform.arch,
+                self.redaction.apply(&platform.cwd, &self.home())
             ));
         }
+        if let Some(suffix) = self.persona.as_ref().and_then(|p| p.suffix.as_ref()) {
+            parts.push(suffix.clone());
+        }
 
         parts.join(" ")
     }
@@ -366,6 +596,142 @@ mod tests {
         assert!(prompt.contains("Rust conventions"));
     }
 
+    #[test]
+    fn test_persona_wraps_base_prompt() {
+        let persona = PersonaTemplate {
+            name: "reviewer".to_string(),
+            description: String::new(),
+            prefix: Some("Act as a reviewer.".to_string()),
+            suffix: Some("End with a verdict.".to_string()),
+        };
+        let builder = SystemPromptBuilder::new("You are Ridge Control.")
+            .add_tool_instruction("Use grep")
+            .with_persona(Some(persona));
+        let prompt = builder.build();
+
+        assert!(prompt.starts_with("You are Ridge Control.\nAct as a reviewer."));
+        assert!(prompt.ends_with("End with a verdict."));
+        assert!(prompt.find("Use grep") < prompt.find("End with a verdict."));
+        let short = builder.build_short();
+        assert!(short.contains("Act as a reviewer."));
+        assert!(short.ends_with("End with a verdict."));
+    }
+
+    #[test]
+    fn test_redaction_removes_home_and_user() {
+        let platform = PlatformInfo {
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            shell: "/bin/bash".to_string(),
+            cwd: "/home/alice/project".to_string(),
+            home: "/home/alice".to_string(),
+            date: "2025-01-01".to_string(),
+        };
+        let repo = RepoContextInfo {
+            root: Some("/home/alice/project".to_string()),
+            branch: Some("main".to_string()),
+            ..Default::default()
+        };
+        let redaction = PromptRedaction {
+            redact_paths: true,
+            redact_user: true,
+            omit_repo_path: false,
+        };
+
+        let prompt = SystemPromptBuilder::ridge_control()
+            .with_platform(platform.clone())
+            .with_repo(repo.clone())
+            .with_redaction(redaction.clone())
+            .build();
+        assert!(!prompt.contains("/home/alice"));
+        assert!(prompt.contains("Working directory: ~/project"));
+        assert!(prompt.contains("Repository root: ~/project"));
+
+        let omitted = SystemPromptBuilder::ridge_control()
+            .with_platform(platform)
+            .with_repo(repo)
+            .with_redaction(PromptRedaction { omit_repo_path: true, ..redaction })
+            .build();
+        assert!(!omitted.contains("Repository root"));
+        assert!(omitted.contains("Branch: main"));
+    }
+
+    #[test]
+    fn test_redaction_apply_with() {
+        let redaction = PromptRedaction { redact_paths: false, redact_user: true, omit_repo_path: false };
+        assert_eq!(
+            redaction.apply_with("/srv/alice@box/data", "/home/alice", "alice", "box"),
+            "/srv/<user>@<host>/data"
+        );
+        assert_eq!(PromptRedaction::default().apply_with("/home/alice", "/home/alice", "alice", "box"), "/home/alice");
+
+        // Only whole names are redacted, not substrings of other words
+        assert_eq!(
+            redaction.apply_with("/usr/local/al/al-x/al_y al", "/home/al", "al", "box"),
+            "/usr/local/<user>/al-x/al_y <user>"
+        );
+        assert_eq!(redaction.apply_with("boxes in box.lan", "/home/al", "al", "box"), "boxes in <host>.lan");
+    }
+
+    #[test]
+    fn test_repo_detect_reads_head() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/feature/x\n").unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+
+        let info = RepoContextInfo::detect(&dir.path().join("src/nested")).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("feature/x"));
+        assert_eq!(info.root.as_deref(), Some(dir.path().to_str().unwrap()));
+        assert_eq!(info.language.as_deref(), Some("Rust"));
+
+        std::fs::write(dir.path().join(".git/HEAD"), "0123456789abcdef\n").unwrap();
+        let info = RepoContextInfo::detect(dir.path()).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("detached at 0123456"));
+
+        std::fs::write(dir.path().join(".git/HEAD"), "ééééééééé\n").unwrap();
+        let info = RepoContextInfo::detect(dir.path()).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("detached at ééééééé"));
+    }
+
+    #[test]
+    fn test_repo_section_omitted_outside_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(RepoContextInfo::detect(dir.path()).is_none());
+
+        let prompt = SystemPromptBuilder::new("role").with_repo_at(dir.path()).build();
+        assert!(!prompt.contains("## Repository"));
+    }
+
+    #[test]
+    fn test_repo_situation_in_prompt() {
+        let prompt = SystemPromptBuilder::new("role")
+            .with_repo(RepoContextInfo {
+                name: Some("ridge".to_string()),
+                branch: Some("feature/x".to_string()),
+                has_uncommitted: Some(true),
+                ..Default::default()
+            })
+            .build();
+        assert!(prompt.contains("- Repository: ridge"));
+        assert!(prompt.contains("You are on branch feature/x with uncommitted changes."));
+    }
+
+    #[test]
+    fn test_repo_situation_unknown_dirty_state() {
+        let repo = RepoContextInfo {
+            branch: Some("main".to_string()),
+            ..Default::default()
+        };
+        // Before `git status` has answered, claim neither clean nor dirty
+        assert_eq!(repo.situation().as_deref(), Some("You are on branch main."));
+        let clean = RepoContextInfo { has_uncommitted: Some(false), ..repo.clone() };
+        assert_eq!(clean.situation().as_deref(), Some("You are on branch main with a clean working tree."));
+        let no_branch = RepoContextInfo { branch: None, ..repo };
+        assert_eq!(no_branch.situation(), None);
+    }
+
     #[test]
     fn test_short_prompt() {
         let prompt = SystemPromptBuilder::ridge_control()
@@ -3,12 +3,23 @@
 //! SubagentManager handles spawning sub-agents (explore, plan, review) with
 //! different models and tool configurations. Sub-agents run autonomously and
 //! return summarized results.
+//!
+//! At most `max_concurrent` sub-agents run at once; further spawns wait on a
+//! semaphore in a queue. Each turn may spawn at most `max_per_turn`.
+//!
+//! Running sub-agents report progress as `SubagentEvent`s, which the UI folds
+//! into a `SubagentMonitor` for transient status and output peeking.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
-use tokio::task::JoinHandle;
+use futures::StreamExt;
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::{AbortHandle, JoinError, JoinHandle};
 
 use crate::config::{KeyId, KeyStore, SubagentsConfig, SubagentConfig};
 use crate::llm::anthropic::AnthropicProvider;
@@ -16,8 +27,9 @@ use crate::llm::gemini::GeminiProvider;
 use crate::llm::grok::GrokProvider;
 use crate::llm::groq::GroqProvider;
 use crate::llm::openai::OpenAIProvider;
+use crate::llm::manager::ToolAssembler;
 use crate::llm::provider::Provider;
-use crate::llm::types::{ContentBlock, LLMRequest, Message, ToolDefinition};
+use crate::llm::types::{ContentBlock, LLMRequest, Message, StreamChunk, StreamDelta, ToolDefinition};
 
 /// Result of a sub-agent execution
 #[derive(Debug, Clone)]
@@ -73,6 +85,169 @@ pub enum SubagentError {
 
     #[error("Keystore not available")]
     NoKeystore,
+
+    #[error("Sub-agent limit reached: at most {limit} per turn")]
+    TurnLimitReached { limit: usize },
+
+    #[error("Sub-agent cancelled")]
+    Cancelled,
+}
+
+impl From<JoinError> for SubagentError {
+    fn from(e: JoinError) -> Self {
+        if e.is_cancelled() {
+            SubagentError::Cancelled
+        } else {
+            SubagentError::LLMError { message: format!("Task panicked: {}", e) }
+        }
+    }
+}
+
+/// A sub-agent's eventual result
+pub type SubagentFuture = Pin<Box<dyn Future<Output = Result<SubagentResult, SubagentError>> + Send>>;
+
+/// A sub-agent started by `SubagentManager::start`
+pub enum SubagentLaunch {
+    /// Queued as a background task; progress arrives as `SubagentEvent`s
+    Background { task_id: String },
+    /// Already running (or queued behind the concurrency limit); await for the result
+    Inline(SubagentFuture),
+}
+
+/// Progress from a sub-agent, forwarded to the UI while it runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubagentEvent {
+    /// Got a concurrency permit and started working
+    Started { task_id: String, agent_type: String, summary: String },
+    /// Short status update (e.g. "calling grep")
+    Status { task_id: String, status: String },
+    /// Streamed text or thinking
+    Output { task_id: String, text: String, thinking: bool },
+    /// Finished; the result itself is delivered through the task APIs
+    Finished { task_id: String, success: bool },
+}
+
+/// Output kept per sub-agent for peeking
+const MONITOR_OUTPUT_LIMIT: usize = 4000;
+
+/// What the UI knows about one sub-agent
+#[derive(Debug, Clone, Default)]
+struct MonitoredTask {
+    task_id: String,
+    agent_type: String,
+    status: String,
+    output: String,
+    in_thinking: bool,
+    done: bool,
+}
+
+/// Folds `SubagentEvent`s into per-task status and a tail of recent output
+#[derive(Debug, Default)]
+pub struct SubagentMonitor {
+    tasks: Vec<MonitoredTask>,
+}
+
+impl SubagentMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&mut self, event: SubagentEvent) {
+        match event {
+            SubagentEvent::Started { task_id, agent_type, summary } => {
+                self.tasks.push(MonitoredTask {
+                    task_id,
+                    agent_type,
+                    status: summary,
+                    ..Default::default()
+                });
+            }
+            SubagentEvent::Status { task_id, status } => {
+                if let Some(task) = self.task_mut(&task_id) {
+                    task.status = status;
+                }
+            }
+            SubagentEvent::Output { task_id, text, thinking } => {
+                if let Some(task) = self.task_mut(&task_id) {
+                    if thinking != task.in_thinking {
+                        task.output.push_str(if thinking { "\n[thinking]\n" } else { "\n[/thinking]\n" });
+                        task.in_thinking = thinking;
+                    }
+                    task.output.push_str(&text);
+                    if task.output.len() > MONITOR_OUTPUT_LIMIT {
+                        let mut cut = task.output.len() - MONITOR_OUTPUT_LIMIT;
+                        while !task.output.is_char_boundary(cut) {
+                            cut += 1;
+                        }
+                        task.output.drain(..cut);
+                    }
+                }
+            }
+            SubagentEvent::Finished { task_id, success } => {
+                if let Some(task) = self.task_mut(&task_id) {
+                    task.done = true;
+                    task.status = if success { "done".to_string() } else { "failed".to_string() };
+                }
+            }
+        }
+    }
+
+    fn task_mut(&mut self, task_id: &str) -> Option<&mut MonitoredTask> {
+        self.tasks.iter_mut().find(|t| t.task_id == task_id)
+    }
+
+    /// Status of the most recently started sub-agent that is still working,
+    /// e.g. "explore: calling grep"
+    pub fn status_line(&self) -> Option<String> {
+        self.tasks
+            .iter()
+            .rev()
+            .find(|t| !t.done)
+            .map(|t| format!("{}: {}", t.agent_type, t.status))
+    }
+
+    /// Status and recent output of every sub-agent this turn, for the pager
+    pub fn peek_text(&self) -> String {
+        if self.tasks.is_empty() {
+            return "No sub-agents have run this turn.\n".to_string();
+        }
+        let mut out = String::new();
+        for task in &self.tasks {
+            out.push_str(&format!("── {} [{}] {}\n", task.agent_type, task.task_id, task.status));
+            out.push_str(task.output.trim());
+            out.push_str("\n\n");
+        }
+        out
+    }
+
+    /// Forget all sub-agents (new turn or cancellation)
+    pub fn clear(&mut self) {
+        self.tasks.clear();
+    }
+}
+
+/// Queued and running sub-agent counts for the UI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubagentActivity {
+    pub queued: usize,
+    pub running: usize,
+}
+
+/// Holds one count in a shared counter; released on drop so aborted
+/// tasks are accounted for
+struct CountGuard(Arc<AtomicUsize>);
+
+impl CountGuard {
+    fn new(counter: &Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for CountGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// Manager for spawning and tracking sub-agents
@@ -80,23 +255,139 @@ pub enum SubagentError {
 pub struct SubagentManager {
     /// Sub-agent configuration
     config: SubagentsConfig,
-    /// Running background tasks
+    /// Background tasks whose result has not been claimed
     running_tasks: HashMap<String, JoinHandle<Result<SubagentResult, SubagentError>>>,
+    /// Every spawned sub-agent, inline or background, for `cancel_all`
+    abort_handles: HashMap<String, AbortHandle>,
     /// Completed results (cached for retrieval)
     completed_results: HashMap<String, Result<SubagentResult, SubagentError>>,
     /// All available tool definitions (for filtering)
     all_tools: Vec<ToolDefinition>,
+    /// Permits for concurrently running sub-agents
+    semaphore: Arc<Semaphore>,
+    /// Sub-agents waiting for a permit
+    queued: Arc<AtomicUsize>,
+    /// Sub-agents holding a permit
+    running: Arc<AtomicUsize>,
+    /// Sub-agents spawned since the last `begin_turn`
+    spawned_this_turn: usize,
+    /// Progress events from running sub-agents
+    event_tx: mpsc::UnboundedSender<SubagentEvent>,
+    /// Receiver handed to the UI via `take_event_rx`
+    event_rx: Option<mpsc::UnboundedReceiver<SubagentEvent>>,
 }
 
 impl SubagentManager {
     /// Create a new SubagentManager
     pub fn new(config: SubagentsConfig) -> Self {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
         Self {
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
             config,
             running_tasks: HashMap::new(),
+            abort_handles: HashMap::new(),
             completed_results: HashMap::new(),
             all_tools: Vec::new(),
+            queued: Arc::new(AtomicUsize::new(0)),
+            running: Arc::new(AtomicUsize::new(0)),
+            spawned_this_turn: 0,
+            event_tx,
+            event_rx: Some(event_rx),
+        }
+    }
+
+    /// Take the progress event receiver for polling in the UI event loop
+    pub fn take_event_rx(&mut self) -> Option<mpsc::UnboundedReceiver<SubagentEvent>> {
+        self.event_rx.take()
+    }
+
+    /// Reset the per-turn spawn count (call when the user sends a message)
+    pub fn begin_turn(&mut self) {
+        self.spawned_this_turn = 0;
+    }
+
+    /// Current queued and running counts
+    pub fn activity(&self) -> SubagentActivity {
+        SubagentActivity {
+            queued: self.queued.load(Ordering::SeqCst),
+            running: self.running.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Abort every queued and running sub-agent, inline or background.
+    /// Returns how many were cancelled.
+    pub fn cancel_all(&mut self) -> usize {
+        self.running_tasks.clear();
+        let mut count = 0;
+        for (_, handle) in self.abort_handles.drain() {
+            if !handle.is_finished() {
+                handle.abort();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Count a spawn against the per-turn cap
+    fn reserve_turn_slot(&mut self) -> Result<(), SubagentError> {
+        if self.spawned_this_turn >= self.config.max_per_turn {
+            return Err(SubagentError::TurnLimitReached { limit: self.config.max_per_turn });
         }
+        self.spawned_this_turn += 1;
+        Ok(())
+    }
+
+    /// Run `work` once a concurrency permit is free. Dropping the returned
+    /// future (e.g. aborting its task) gives up its queue slot or permit.
+    fn limited<F>(&self, work: F) -> impl Future<Output = F::Output> + Send + 'static
+    where
+        F: Future + Send + 'static,
+    {
+        let semaphore = self.semaphore.clone();
+        let queued = CountGuard::new(&self.queued);
+        let running = self.running.clone();
+        async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("subagent semaphore is never closed");
+            drop(queued);
+            let _running = CountGuard::new(&running);
+            work.await
+        }
+    }
+
+    /// Spawn `work` under the concurrency limit, keeping its abort handle
+    fn spawn_tracked<F>(&mut self, task_id: String, work: F) -> JoinHandle<F::Output>
+    where
+        F: Future<Output = Result<SubagentResult, SubagentError>> + Send + 'static,
+    {
+        self.abort_handles.retain(|_, handle| !handle.is_finished());
+        let handle = tokio::spawn(self.limited(work));
+        self.abort_handles.insert(task_id, handle.abort_handle());
+        handle
+    }
+
+    /// Queue `work` as a background task under the concurrency limit
+    fn spawn_background<F>(&mut self, task_id: String, work: F) -> Result<(), SubagentError>
+    where
+        F: Future<Output = Result<SubagentResult, SubagentError>> + Send + 'static,
+    {
+        self.reserve_turn_slot()?;
+        let handle = self.spawn_tracked(task_id.clone(), work);
+        self.running_tasks.insert(task_id, handle);
+        Ok(())
+    }
+
+    /// Start `work` right away under the concurrency limit; the returned
+    /// future only waits for it, so `cancel_all` can still stop it
+    fn spawn_inline<F>(&mut self, task_id: String, work: F) -> Result<SubagentFuture, SubagentError>
+    where
+        F: Future<Output = Result<SubagentResult, SubagentError>> + Send + 'static,
+    {
+        self.reserve_turn_slot()?;
+        let handle = self.spawn_tracked(task_id, work);
+        Ok(Box::pin(async move { handle.await? }))
     }
 
     /// Set available tools (called when tools are configured)
@@ -105,9 +396,13 @@ impl SubagentManager {
         self.all_tools = tools;
     }
 
-    /// Update configuration
+    /// Update configuration. A new concurrency limit applies to sub-agents
+    /// spawned afterwards.
     #[allow(dead_code)]
     pub fn set_config(&mut self, config: SubagentsConfig) {
+        if config.max_concurrent != self.config.max_concurrent {
+            self.semaphore = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+        }
         self.config = config;
     }
 
@@ -117,21 +412,21 @@ impl SubagentManager {
         &self.config
     }
 
-    /// Spawn a sub-agent with the given prompt
+    /// Start a sub-agent with the given prompt. Both kinds count against the
+    /// per-turn cap right away; the work itself waits for a concurrency permit.
     ///
     /// # Arguments
     /// * `keystore` - KeyStore for getting API keys
     /// * `agent_type` - Type of agent (explore, plan, review)
     /// * `prompt` - Task description for the sub-agent
-    /// * `background` - Run in background (returns immediately with pending status)
-    #[allow(dead_code)]
-    pub async fn spawn(
+    /// * `background` - Queue as a background task instead of returning a future to await
+    pub fn start(
         &mut self,
         keystore: &KeyStore,
         agent_type: &str,
         prompt: &str,
         background: bool,
-    ) -> Result<SubagentResult, SubagentError> {
+    ) -> Result<SubagentLaunch, SubagentError> {
         let task_id = uuid::Uuid::new_v4().to_string();
         let config = self.config.get(agent_type).clone();
 
@@ -148,24 +443,17 @@ impl SubagentManager {
         let task_id_clone = task_id.clone();
 
         if background {
-            // Spawn as background task
-            let handle = tokio::spawn(async move {
-                execute_subagent(task_id_clone, agent_type_owned, provider, request).await
-            });
-
-            self.running_tasks.insert(task_id.clone(), handle);
-
-            Ok(SubagentResult {
-                task_id,
-                agent_type: agent_type.to_string(),
-                result: String::new(),
-                tokens_used: 0,
-                duration_ms: 0,
-                status: SubagentStatus::Pending,
-            })
+            // Spawn as background task, queued behind the concurrency limit
+            self.spawn_background(
+                task_id.clone(),
+                execute_subagent(task_id_clone, agent_type_owned, provider, request, self.event_tx.clone()),
+            )?;
+            Ok(SubagentLaunch::Background { task_id })
         } else {
-            // Execute synchronously
-            execute_subagent(task_id, agent_type_owned, provider, request).await
+            // Execute inline, still waiting for a concurrency permit
+            let events = self.event_tx.clone();
+            let run = execute_subagent(task_id_clone, agent_type_owned, provider, request, events);
+            Ok(SubagentLaunch::Inline(self.spawn_inline(task_id, run)?))
         }
     }
 
@@ -181,36 +469,31 @@ impl SubagentManager {
         false
     }
 
-    /// Get result of a background task (blocks if still running)
-    #[allow(dead_code)]
-    pub async fn get_task_result(&mut self, task_id: &str) -> Result<SubagentResult, SubagentError> {
-        // Check completed cache first
+    /// Claim a background task's result (the `task_output` tool). A finished
+    /// task, or with `block` a running one, yields a future for its result;
+    /// the task stays cancellable by `cancel_all` while it is awaited.
+    pub fn claim_task(&mut self, task_id: &str, block: bool) -> Result<SubagentFuture, SubagentError> {
         if let Some(result) = self.completed_results.remove(task_id) {
-            return result;
+            return Ok(Box::pin(async move { result }));
         }
-
-        // Check running tasks
-        if let Some(handle) = self.running_tasks.remove(task_id) {
-            match handle.await {
-                Ok(result) => {
-                    // Cache and return
-                    let result_clone = result.clone();
-                    self.completed_results.insert(task_id.to_string(), result);
-                    result_clone
-                }
-                Err(e) => {
-                    Err(SubagentError::LLMError {
-                        message: format!("Task panicked: {}", e),
-                    })
-                }
+        match self.running_tasks.get(task_id) {
+            None => Err(SubagentError::TaskNotFound { task_id: task_id.to_string() }),
+            Some(handle) if !block && !handle.is_finished() => {
+                Err(SubagentError::TaskStillRunning { task_id: task_id.to_string() })
+            }
+            Some(_) => {
+                let handle = self.running_tasks.remove(task_id).expect("checked above");
+                Ok(Box::pin(async move { handle.await? }))
             }
-        } else {
-            Err(SubagentError::TaskNotFound {
-                task_id: task_id.to_string(),
-            })
         }
     }
 
+    /// Get result of a background task (waits if still running)
+    #[allow(dead_code)]
+    pub async fn get_task_result(&mut self, task_id: &str) -> Result<SubagentResult, SubagentError> {
+        self.claim_task(task_id, true)?.await
+    }
+
     /// Poll a background task without blocking
     #[allow(dead_code)]
     pub fn poll_task(&mut self, task_id: &str) -> Option<Result<SubagentResult, SubagentError>> {
@@ -254,11 +537,13 @@ impl SubagentManager {
     /// Cancel a running task
     #[allow(dead_code)]
     pub fn cancel_task(&mut self, task_id: &str) -> bool {
-        if let Some(handle) = self.running_tasks.remove(task_id) {
-            handle.abort();
-            true
-        } else {
-            false
+        self.running_tasks.remove(task_id);
+        match self.abort_handles.remove(task_id) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
         }
     }
 
@@ -278,7 +563,6 @@ impl SubagentManager {
 }
 
 /// Create a provider for the given provider name
-#[allow(dead_code)]
 fn create_provider(keystore: &KeyStore, provider_name: &str) -> Result<Arc<dyn Provider>, SubagentError> {
     let key_id = match provider_name {
         "anthropic" => KeyId::Anthropic,
@@ -320,7 +604,6 @@ fn create_provider(keystore: &KeyStore, provider_name: &str) -> Result<Arc<dyn P
 }
 
 /// Build an LLM request for the sub-agent
-#[allow(dead_code)]
 fn build_request(
     config: &SubagentConfig,
     prompt: &str,
@@ -335,46 +618,65 @@ fn build_request(
         system: Some(system_prompt),
         messages: vec![Message::user(prompt.to_string())],
         tools,
-        stream: false,
+        stream: true,
         max_tokens: config.max_context_tokens.map(|t| t.min(4096)), // Cap output
         ..Default::default()
     }
 }
 
 /// Execute a sub-agent (internal async function)
-#[allow(dead_code)]
+///
+/// Streams each turn so progress and output reach `events` as they arrive.
+/// Only the final text is returned; the events are for transient display.
 async fn execute_subagent(
     task_id: String,
     agent_type: String,
     provider: Arc<dyn Provider>,
     mut request: LLMRequest,
+    events: mpsc::UnboundedSender<SubagentEvent>,
 ) -> Result<SubagentResult, SubagentError> {
     let start = Instant::now();
     let mut total_tokens = 0u32;
     let mut accumulated_response = String::new();
-
-    // Simple loop for tool use (max 5 iterations to prevent runaway)
-    for _turn in 0..5 {
-        let response = provider.complete(request.clone()).await.map_err(|e| {
-            SubagentError::LLMError {
-                message: e.to_string(),
+    let emit = |event: SubagentEvent| {
+        let _ = events.send(event);
+    };
+    let status = |text: String| SubagentEvent::Status { task_id: task_id.clone(), status: text };
+
+    let prompt = request
+        .messages
+        .first()
+        .and_then(|m| m.content.iter().find_map(|b| match b {
+            ContentBlock::Text(t) => Some(t.as_str()),
+            _ => None,
+        }))
+        .unwrap_or_default();
+    emit(SubagentEvent::Started {
+        task_id: task_id.clone(),
+        agent_type: agent_type.clone(),
+        summary: summarize_prompt(prompt),
+    });
+
+    let outcome: Result<(), SubagentError> = async {
+        // Simple loop for tool use (max 5 iterations to prevent runaway)
+        for turn in 0..5 {
+            if turn > 0 {
+                emit(status(format!("turn {}", turn + 1)));
             }
-        })?;
-
-        // Accumulate tokens
-        total_tokens += response.usage.input_tokens + response.usage.output_tokens;
+            let mut stream = provider.stream(request.clone()).await.map_err(|e| {
+                SubagentError::LLMError {
+                    message: e.to_string(),
+                }
+            })?;
 
-        // Extract text and tool uses from response
-        let mut has_tool_use = false;
-        let mut tool_results = Vec::new();
+            let mut assembler = ToolAssembler::new();
+            let mut content: Vec<ContentBlock> = Vec::new();
+            let mut tool_results = Vec::new();
 
-        for block in &response.content {
-            match block {
-                ContentBlock::Text(text) => {
-                    accumulated_response.push_str(text);
-                }
-                ContentBlock::ToolUse(tool_use) => {
-                    has_tool_use = true;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|e| SubagentError::LLMError { message: e.to_string() })?;
+                if let Some(tool_use) = assembler.process_chunk(&chunk) {
+                    emit(status(format!("calling {}", tool_use.name)));
                     // For now, we don't execute tools in sub-agents
                     // Just note that a tool was requested
                     tool_results.push(crate::llm::types::ToolResult {
@@ -384,29 +686,55 @@ async fn execute_subagent(
                         ),
                         is_error: true,
                     });
+                    content.push(ContentBlock::ToolUse(tool_use));
+                    continue;
+                }
+                match chunk {
+                    StreamChunk::Delta(StreamDelta::Text(text)) => {
+                        accumulated_response.push_str(&text);
+                        match content.last_mut() {
+                            Some(ContentBlock::Text(t)) => t.push_str(&text),
+                            _ => content.push(ContentBlock::Text(text.clone())),
+                        }
+                        emit(SubagentEvent::Output { task_id: task_id.clone(), text, thinking: false });
+                    }
+                    StreamChunk::Delta(StreamDelta::Thinking(text)) => {
+                        emit(SubagentEvent::Output { task_id: task_id.clone(), text, thinking: true });
+                    }
+                    StreamChunk::Stop { usage: Some(usage), .. } => {
+                        total_tokens += usage.input_tokens + usage.output_tokens;
+                    }
+                    _ => {}
                 }
-                _ => {}
             }
-        }
 
-        if !has_tool_use {
-            // No tool use, we're done
-            break;
-        }
-
-        // Add assistant response and tool results to continue
-        request.messages.push(Message {
-            role: crate::llm::types::Role::Assistant,
-            content: response.content.clone(),
-        });
+            if tool_results.is_empty() {
+                // No tool use, we're done
+                break;
+            }
 
-        for result in tool_results {
+            // Add assistant response and tool results to continue
             request.messages.push(Message {
-                role: crate::llm::types::Role::User,
-                content: vec![ContentBlock::ToolResult(result)],
+                role: crate::llm::types::Role::Assistant,
+                content,
             });
+
+            for result in tool_results {
+                request.messages.push(Message {
+                    role: crate::llm::types::Role::User,
+                    content: vec![ContentBlock::ToolResult(result)],
+                });
+            }
         }
+        Ok(())
     }
+    .await;
+
+    emit(SubagentEvent::Finished {
+        task_id: task_id.clone(),
+        success: outcome.is_ok(),
+    });
+    outcome?;
 
     let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -420,6 +748,17 @@ async fn execute_subagent(
     })
 }
 
+/// First line of a prompt, shortened for a status line
+fn summarize_prompt(prompt: &str) -> String {
+    const MAX: usize = 48;
+    let line = prompt.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > MAX {
+        format!("{}…", line.chars().take(MAX - 1).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -471,6 +810,167 @@ mod tests {
         assert!(manager.running_task_ids().is_empty());
     }
 
+    fn completed(task_id: &str) -> SubagentResult {
+        SubagentResult {
+            task_id: task_id.to_string(),
+            agent_type: "explore".to_string(),
+            result: String::new(),
+            tokens_used: 0,
+            duration_ms: 0,
+            status: SubagentStatus::Completed,
+        }
+    }
+
+    /// Work that reports when it starts and finishes once `gate` gets a permit
+    fn gated(
+        task_id: &str,
+        started: &mpsc::UnboundedSender<String>,
+        gate: &Arc<Semaphore>,
+    ) -> impl Future<Output = Result<SubagentResult, SubagentError>> + Send + 'static {
+        let (id, started, gate) = (task_id.to_string(), started.clone(), gate.clone());
+        async move {
+            let _ = started.send(id.clone());
+            let _permit = gate.acquire().await.expect("gate is never closed");
+            Ok(completed(&id))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_queues_extra_subagents() {
+        let config = SubagentsConfig { max_concurrent: 2, ..Default::default() };
+        let mut manager = SubagentManager::new(config);
+        let (started_tx, mut started_rx) = mpsc::unbounded_channel();
+        let gate = Arc::new(Semaphore::new(0));
+
+        for i in 0..5 {
+            let task_id = format!("t{}", i);
+            let work = gated(&task_id, &started_tx, &gate);
+            manager.spawn_background(task_id, work).unwrap();
+        }
+
+        started_rx.recv().await.unwrap();
+        started_rx.recv().await.unwrap();
+        assert_eq!(manager.activity(), SubagentActivity { queued: 3, running: 2 });
+        assert!(started_rx.try_recv().is_err(), "a third sub-agent started past the limit");
+
+        gate.add_permits(5);
+        for i in 0..5 {
+            manager.get_task_result(&format!("t{}", i)).await.unwrap();
+        }
+        assert_eq!(manager.activity(), SubagentActivity::default());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_clears_queued_and_running() {
+        let config = SubagentsConfig { max_concurrent: 1, ..Default::default() };
+        let mut manager = SubagentManager::new(config);
+        let (started_tx, mut started_rx) = mpsc::unbounded_channel();
+        let gate = Arc::new(Semaphore::new(0));
+        for i in 0..3 {
+            let task_id = format!("t{}", i);
+            let work = gated(&task_id, &started_tx, &gate);
+            manager.spawn_background(task_id, work).unwrap();
+        }
+        started_rx.recv().await.unwrap();
+        assert_eq!(manager.activity(), SubagentActivity { queued: 2, running: 1 });
+
+        // Claimed results are still cancelled
+        let waits: Vec<SubagentFuture> =
+            (0..3).map(|i| manager.claim_task(&format!("t{}", i), true).unwrap()).collect();
+        assert_eq!(manager.cancel_all(), 3);
+        for wait in waits {
+            assert!(matches!(wait.await, Err(SubagentError::Cancelled)));
+        }
+        assert_eq!(manager.activity(), SubagentActivity::default());
+        assert!(manager.running_task_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_stops_inline_subagents() {
+        let mut manager = SubagentManager::new(SubagentsConfig::default());
+        let (started_tx, mut started_rx) = mpsc::unbounded_channel();
+        let gate = Arc::new(Semaphore::new(0));
+        let run = manager.spawn_inline("inline".to_string(), gated("inline", &started_tx, &gate)).unwrap();
+        started_rx.recv().await.unwrap();
+        assert_eq!(manager.activity().running, 1);
+
+        assert_eq!(manager.cancel_all(), 1);
+        assert!(matches!(run.await, Err(SubagentError::Cancelled)));
+        assert_eq!(manager.activity(), SubagentActivity::default());
+    }
+
+    #[tokio::test]
+    async fn test_claim_task_result() {
+        let mut manager = SubagentManager::new(SubagentsConfig::default());
+        let (started_tx, mut started_rx) = mpsc::unbounded_channel();
+        let gate = Arc::new(Semaphore::new(0));
+        manager.spawn_background("bg".to_string(), gated("bg", &started_tx, &gate)).unwrap();
+        started_rx.recv().await.unwrap();
+
+        assert!(matches!(manager.claim_task("bg", false), Err(SubagentError::TaskStillRunning { .. })));
+        assert!(matches!(manager.claim_task("missing", true), Err(SubagentError::TaskNotFound { .. })));
+
+        gate.add_permits(1);
+        let result = manager.claim_task("bg", true).unwrap().await.unwrap();
+        assert_eq!(result.task_id, "bg");
+        assert!(matches!(manager.claim_task("bg", true), Err(SubagentError::TaskNotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_per_turn_cap() {
+        let config = SubagentsConfig { max_per_turn: 1, ..Default::default() };
+        let mut manager = SubagentManager::new(config);
+        manager.spawn_background("a".to_string(), async { Ok(completed("a")) }).unwrap();
+        let err = manager.spawn_background("b".to_string(), async { Ok(completed("b")) }).unwrap_err();
+        assert!(matches!(err, SubagentError::TurnLimitReached { limit: 1 }));
+
+        manager.begin_turn();
+        assert!(manager.spawn_background("c".to_string(), async { Ok(completed("c")) }).is_ok());
+    }
+
+    #[test]
+    fn test_monitor_tracks_status_and_output() {
+        let mut monitor = SubagentMonitor::new();
+        assert_eq!(monitor.status_line(), None);
+
+        monitor.apply(SubagentEvent::Started {
+            task_id: "a".to_string(),
+            agent_type: "explore".to_string(),
+            summary: "analyzing module X".to_string(),
+        });
+        assert_eq!(monitor.status_line().as_deref(), Some("explore: analyzing module X"));
+
+        monitor.apply(SubagentEvent::Output { task_id: "a".to_string(), text: "hmm".to_string(), thinking: true });
+        monitor.apply(SubagentEvent::Output { task_id: "a".to_string(), text: "Found it".to_string(), thinking: false });
+        monitor.apply(SubagentEvent::Status { task_id: "a".to_string(), status: "calling grep".to_string() });
+        assert_eq!(monitor.status_line().as_deref(), Some("explore: calling grep"));
+        let peek = monitor.peek_text();
+        assert!(peek.contains("[thinking]\nhmm\n[/thinking]\nFound it"));
+
+        monitor.apply(SubagentEvent::Finished { task_id: "a".to_string(), success: true });
+        assert_eq!(monitor.status_line(), None);
+        assert!(monitor.peek_text().contains("done"));
+
+        // Output is capped to a tail
+        monitor.apply(SubagentEvent::Started {
+            task_id: "b".to_string(),
+            agent_type: "plan".to_string(),
+            summary: String::new(),
+        });
+        monitor.apply(SubagentEvent::Output {
+            task_id: "b".to_string(),
+            text: "é".repeat(MONITOR_OUTPUT_LIMIT),
+            thinking: false,
+        });
+        assert!(monitor.tasks[1].output.len() <= MONITOR_OUTPUT_LIMIT);
+    }
+
+    #[test]
+    fn test_summarize_prompt() {
+        assert_eq!(summarize_prompt("Find the parser\nmore detail"), "Find the parser");
+        assert_eq!(summarize_prompt(&"x".repeat(100)).chars().count(), 48);
+    }
+
     #[test]
     fn test_filter_tools_empty_allows_all() {
         // When allowed_tools is empty, all tools should be returned
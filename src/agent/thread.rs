@@ -9,6 +9,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::llm::types::{ContentBlock, Role};
 use super::context::ContextSegment;
+use super::plan::Plan;
+use super::models::ModelCatalog;
+use super::usage::{turn_cost, TurnUsage};
+use crate::config::ModelPricing;
 
 /// An agent conversation thread
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +23,15 @@ pub struct AgentThread {
     pub title: String,
     /// Model used for this thread
     pub model: String,
+    /// Sampling temperature used for this thread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Max output tokens used for this thread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Persona template name this thread runs with (None = base prompt)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
     /// Context segments in order
     pub segments: Vec<ContextSegment>,
     /// Creation timestamp
@@ -29,6 +42,15 @@ pub struct AgentThread {
     next_sequence: u64,
     /// Arbitrary metadata
     pub metadata: HashMap<String, String>,
+    /// User-assigned tags for organizing threads (lowercase, unique)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Provider-reported token usage, one entry per LLM response
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usage: Vec<TurnUsage>,
+    /// Latest plan from the update_plan tool
+    #[serde(default, skip_serializing_if = "Plan::is_empty")]
+    pub plan: Plan,
 }
 
 impl AgentThread {
@@ -38,11 +60,17 @@ impl AgentThread {
             id: format!("T-{}", Uuid::new_v4()),
             title: "New conversation".to_string(),
             model: model.into(),
+            temperature: None,
+            max_tokens: None,
+            persona: None,
             segments: Vec::new(),
             created_at: now,
             updated_at: now,
             next_sequence: 0,
             metadata: HashMap::new(),
+            tags: Vec::new(),
+            usage: Vec::new(),
+            plan: Plan::default(),
         }
     }
 
@@ -92,12 +120,35 @@ impl AgentThread {
         self.updated_at = chrono::Utc::now();
     }
 
+    /// Record the generation parameters this thread runs with
+    pub fn set_parameters(&mut self, temperature: Option<f32>, max_tokens: Option<u32>) {
+        self.temperature = temperature;
+        self.max_tokens = max_tokens;
+    }
+
     /// Update the thread title
     pub fn set_title(&mut self, title: impl Into<String>) {
         self.title = title.into();
         self.updated_at = chrono::Utc::now();
     }
 
+    /// Record token usage for a completed LLM response
+    pub fn record_usage(&mut self, usage: TurnUsage) {
+        self.usage.push(usage);
+    }
+
+    /// Running USD spend across the recorded responses; None if any of their
+    /// models has no known price
+    pub fn estimated_cost(&self, pricing: &HashMap<String, ModelPricing>, catalog: &ModelCatalog) -> Option<f64> {
+        self.usage.iter().map(|usage| turn_cost(usage, pricing, catalog)).sum()
+    }
+
+    /// Replace the thread's tags from space- or comma-separated input
+    pub fn set_tags(&mut self, input: &str) {
+        self.tags = parse_tags(input);
+        self.updated_at = chrono::Utc::now();
+    }
+
     #[allow(dead_code)]
     /// Repair corrupted thread data by removing orphaned ToolResult blocks.
     /// Returns the number of orphaned ToolResults that were removed.
@@ -190,6 +241,20 @@ pub struct ThreadSummary {
     pub model: String,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub segment_count: usize,
+    pub tags: Vec<String>,
+}
+
+/// Split tag input on spaces/commas, dropping a leading '#', lowercasing and
+/// removing duplicates while keeping the given order
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split([' ', ',']) {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
 }
 
 /// In-memory thread store (for development/testing)
@@ -255,10 +320,11 @@ impl ThreadStore for InMemoryThreadStore {
                         model: t.model.clone(),
                         updated_at: t.updated_at,
                         segment_count: t.segments.len(),
+                        tags: t.tags.clone(),
                     })
                     .collect();
                 // Sort by most recent first
-                summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+                summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
                 summaries
             })
             .unwrap_or_default()
@@ -271,6 +337,16 @@ mod tests {
     use crate::agent::context::SegmentKind;
     use crate::llm::types::Message;
 
+    #[test]
+    fn test_set_tags_normalizes() {
+        let mut thread = AgentThread::new("gpt-4o");
+        thread.set_tags("Work, #debugging  work scratch");
+        assert_eq!(thread.tags, vec!["work", "debugging", "scratch"]);
+
+        thread.set_tags("");
+        assert!(thread.tags.is_empty());
+    }
+
     #[test]
     fn test_thread_creation() {
         let thread = AgentThread::new("gpt-4o").with_title("Test Thread");
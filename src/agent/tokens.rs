@@ -1,6 +1,10 @@
 //! Token counting - per-model tokenizers with heuristic fallback
+//!
+//! OpenAI models are counted exactly with tiktoken (o200k_base for GPT-4o and
+//! later, cl100k_base for GPT-4/3.5). Claude and Gemini have no public BPE, so
+//! their counts are calibrated estimates; see `count_with_tokenizer`.
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use super::models::{ModelCatalog, ModelInfo, TokenizerKind};
 use crate::llm::types::Message;
@@ -14,32 +18,54 @@ pub trait TokenCounter: Send + Sync {
     fn count_messages(&self, model: &str, messages: &[Message]) -> u32;
 }
 
+/// Claude 3+ tokens per cl100k token. Claude's tokenizer typically yields
+/// 10-15% more tokens than cl100k on English prose and source code.
+const CLAUDE_PER_CL100K: f64 = 1.12;
+
+/// Characters per token Google documents for Gemini models
+const GEMINI_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Tokens one attached image costs for `provider`. Pixel sizes aren't known
+/// when counting, so each figure is a full-size screenshot after the provider's
+/// own downscaling: Anthropic bills ~1.15 MP at 750 px per token, OpenAI high
+/// detail at 1024x1024 is 4 tiles x 170 + 85, and Gemini charges 258 per image.
+fn image_tokens(provider: &str) -> u32 {
+    match provider {
+        "anthropic" => 1_600,
+        "openai" | "grok" => 765,
+        "gemini" => 258,
+        _ => 1_000,
+    }
+}
+
+/// BPE tables are large to build, so every counter shares one copy of each
+static CL100K: OnceLock<tiktoken_rs::CoreBPE> = OnceLock::new();
+static O200K: OnceLock<tiktoken_rs::CoreBPE> = OnceLock::new();
+
 /// Default token counter using per-model tokenizers
 pub struct DefaultTokenCounter {
     catalog: Arc<ModelCatalog>,
-    cl100k: tiktoken_rs::CoreBPE,
+    cl100k: &'static tiktoken_rs::CoreBPE,
+    o200k: &'static tiktoken_rs::CoreBPE,
 }
 
 impl DefaultTokenCounter {
     pub fn new(catalog: Arc<ModelCatalog>) -> Self {
-        let cl100k = tiktoken_rs::cl100k_base().expect("Failed to load cl100k tokenizer");
-        Self { catalog, cl100k }
+        let cl100k = CL100K.get_or_init(|| tiktoken_rs::cl100k_base().expect("Failed to load cl100k tokenizer"));
+        let o200k = O200K.get_or_init(|| tiktoken_rs::o200k_base().expect("Failed to load o200k tokenizer"));
+        Self { catalog, cl100k, o200k }
     }
 
     fn count_with_tokenizer(&self, tokenizer: TokenizerKind, text: &str) -> u32 {
         match tokenizer {
             TokenizerKind::Claude => {
-                // Claude uses a similar tokenizer to GPT-4, cl100k is close enough
-                // For production accuracy, we'd use claude-tokenizer crate
-                self.cl100k.encode_ordinary(text).len() as u32
-            }
-            TokenizerKind::GptLike => {
-                self.cl100k.encode_ordinary(text).len() as u32
+                let cl100k = self.cl100k.encode_ordinary(text).len() as f64;
+                (cl100k * CLAUDE_PER_CL100K).ceil() as u32
             }
+            TokenizerKind::GptLike => self.o200k.encode_ordinary(text).len() as u32,
+            TokenizerKind::Cl100k => self.cl100k.encode_ordinary(text).len() as u32,
             TokenizerKind::Gemini => {
-                // Gemini doesn't have a public tokenizer, use heuristic
-                // Their tokens are roughly similar to GPT-4
-                self.cl100k.encode_ordinary(text).len() as u32
+                (text.chars().count() as f64 / GEMINI_CHARS_PER_TOKEN).ceil() as u32
             }
             TokenizerKind::Heuristic => {
                 // Fallback: ~4 characters per token on average
@@ -97,11 +123,11 @@ impl DefaultTokenCounter {
                     crate::llm::types::ToolResultContent::Json(v) => {
                         self.count_with_tokenizer(info.tokenizer, &v.to_string())
                     }
-                    crate::llm::types::ToolResultContent::Image(_) => 1000, // Rough estimate for images
+                    crate::llm::types::ToolResultContent::Image(_) => image_tokens(&info.provider),
                 };
                 content_tokens + 10 // overhead
             }
-            ContentBlock::Image(_) => 1000, // Images are roughly 1000 tokens
+            ContentBlock::Image(_) => image_tokens(&info.provider),
         }
     }
 }
@@ -142,4 +168,37 @@ mod tests {
         // ~4 chars per token = ~100 tokens
         assert!((99..=101).contains(&tokens));
     }
+
+    #[test]
+    fn test_counts_match_reference_tokenizers() {
+        let counter = DefaultTokenCounter::new(Arc::new(ModelCatalog::new()));
+        let text = "tiktoken is great!";
+
+        // Exact BPE counts (reference values from the tiktoken Python package)
+        assert_eq!(counter.count_with_tokenizer(TokenizerKind::Cl100k, text), 6);
+        assert_eq!(counter.count_text("gpt-4-turbo", text), 6);
+        assert_eq!(counter.count_text("gpt-4o", "Hello, world!"), 4);
+
+        // o200k stays within 15% of cl100k for a longer English sample
+        let sample = "The quick brown fox jumps over the lazy dog. ".repeat(20);
+        let cl100k = counter.count_with_tokenizer(TokenizerKind::Cl100k, &sample) as f64;
+        let o200k = counter.count_text("gpt-4o", &sample) as f64;
+        assert!(((o200k - cl100k) / cl100k).abs() <= 0.15);
+    }
+
+    #[test]
+    fn test_image_tokens_per_provider() {
+        use crate::llm::types::{ContentBlock, ImageContent, ImageSource};
+        let counter = DefaultTokenCounter::new(Arc::new(ModelCatalog::new()));
+        let image = ContentBlock::Image(ImageContent {
+            source: ImageSource::Base64("iVBORw0KGgo=".to_string()),
+            media_type: "image/png".to_string(),
+        });
+        let catalog = ModelCatalog::new();
+        let count = |model: &str| counter.count_content_block(&catalog.info_for(model), &image);
+
+        assert_eq!(count("claude-sonnet-4-5-20250929"), 1_600);
+        assert_eq!(count("gpt-4o"), 765);
+        assert!(count("gemini-2.5-pro") < count("gpt-4o"));
+    }
 }
@@ -0,0 +1,470 @@
+//! Per-thread token usage and cost reporting
+//!
+//! Each completed LLM response records a `TurnUsage` on its thread. A
+//! `UsageReport` totals those records by model, prices them from the
+//! `[pricing]` table in llm.toml or else the model catalog's list prices,
+//! and counts tool calls from the thread's messages. Models without pricing
+//! report "n/a" cost instead of failing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::context::{ContextSegment, ContextStats};
+use super::models::ModelCatalog;
+use super::thread::AgentThread;
+use crate::config::ModelPricing;
+use crate::llm::types::{ContentBlock, Role, Usage};
+
+/// Tokens reported by the provider for one LLM response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnUsage {
+    /// Model that served the response (after any fallback)
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Estimated thinking tokens; already included in `output_tokens` for billing
+    #[serde(default)]
+    pub thinking_tokens: u64,
+    /// Input tokens written to the prompt cache; not part of `input_tokens`
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    /// Input tokens read from the prompt cache; not part of `input_tokens`
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    /// Tool calls requested by the response
+    #[serde(default)]
+    pub tool_calls: u32,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Totals for one model across a thread
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub turns: usize,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub thinking_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub tool_calls: u32,
+    /// USD, or None when the model has no pricing
+    pub cost: Option<f64>,
+}
+
+/// One row of the turn-by-turn breakdown
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TurnRow {
+    pub turn: usize,
+    #[serde(flatten)]
+    pub usage: TurnUsage,
+    pub cost: Option<f64>,
+}
+
+/// Token usage and cost report for a thread
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub thread_id: String,
+    pub title: String,
+    pub models: Vec<ModelUsage>,
+    pub turns: Vec<TurnRow>,
+    /// Tool calls by tool name, from the thread's messages
+    pub tool_counts: BTreeMap<String, u32>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// USD, or None when any model used has no pricing
+    pub total_cost: Option<f64>,
+}
+
+/// Pricing for a model: exact id first, then the longest configured prefix
+/// (so `claude-sonnet-4` covers dated releases)
+pub fn pricing_for<'a>(pricing: &'a HashMap<String, ModelPricing>, model: &str) -> Option<&'a ModelPricing> {
+    pricing.get(model).or_else(|| {
+        pricing
+            .iter()
+            .filter(|(key, _)| model.starts_with(key.as_str()))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, price)| price)
+    })
+}
+
+/// Pricing from llm.toml, else the catalog's list price
+fn price_of<'a>(
+    pricing: &'a HashMap<String, ModelPricing>,
+    catalog: &'a ModelCatalog,
+    model: &str,
+) -> Option<&'a ModelPricing> {
+    pricing_for(pricing, model).or_else(|| catalog.pricing(model))
+}
+
+/// USD for uncached input and output plus prompt-cache writes and reads
+pub(super) fn cost_of(
+    pricing: Option<&ModelPricing>,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_write_tokens: u64,
+    cache_read_tokens: u64,
+) -> Option<f64> {
+    pricing.map(|p| p.cost(input_tokens, output_tokens) + p.cache_cost(cache_write_tokens, cache_read_tokens))
+}
+
+/// USD spent on one recorded response, or None if its model has no price.
+/// llm.toml pricing wins; otherwise the catalog's list price applies.
+pub fn turn_cost(usage: &TurnUsage, pricing: &HashMap<String, ModelPricing>, catalog: &ModelCatalog) -> Option<f64> {
+    match pricing_for(pricing, &usage.model) {
+        Some(price) => cost_of(
+            Some(price),
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cache_write_tokens,
+            usage.cache_read_tokens,
+        ),
+        None => catalog.estimated_cost(
+            &usage.model,
+            &Usage {
+                input_tokens: usage.input_tokens as u32,
+                output_tokens: usage.output_tokens as u32,
+                thinking_tokens: None,
+                cache_creation_tokens: usage.cache_write_tokens as u32,
+                cache_read_tokens: usage.cache_read_tokens as u32,
+            },
+        ),
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map_or_else(|| "n/a".to_string(), |c| format!("${:.4}", c))
+}
+
+/// Footer shown under a segment's message in the conversation viewer:
+/// usage and cost for an assistant response, counted tokens for a user
+/// message. None when nothing was recorded (e.g. threads saved before this).
+pub fn segment_footer(
+    segment: &ContextSegment,
+    pricing: &HashMap<String, ModelPricing>,
+    catalog: &ModelCatalog,
+) -> Option<String> {
+    let is_user_text = segment.messages.iter().any(|m| {
+        m.role == Role::User && m.content.iter().any(|b| matches!(b, ContentBlock::Text(_)))
+    });
+    match (&segment.usage, segment.token_count) {
+        (Some(usage), _) => {
+            let mut footer = format!(
+                "{} in · {} out",
+                ContextStats::format_tokens(usage.input_tokens.min(u32::MAX as u64) as u32),
+                ContextStats::format_tokens(usage.output_tokens.min(u32::MAX as u64) as u32),
+            );
+            if let Some(cost) = turn_cost(usage, pricing, catalog) {
+                footer.push_str(&format!(" · ${:.4}", cost));
+            }
+            Some(footer)
+        }
+        (None, Some(tokens)) if is_user_text => {
+            Some(format!("{} tokens", ContextStats::format_tokens(tokens)))
+        }
+        _ => None,
+    }
+}
+
+impl UsageReport {
+    pub fn from_thread(thread: &AgentThread, pricing: &HashMap<String, ModelPricing>, catalog: &ModelCatalog) -> Self {
+        let mut models: Vec<ModelUsage> = Vec::new();
+        let mut turns = Vec::with_capacity(thread.usage.len());
+
+        for (i, usage) in thread.usage.iter().enumerate() {
+            turns.push(TurnRow {
+                turn: i + 1,
+                usage: usage.clone(),
+                cost: turn_cost(usage, pricing, catalog),
+            });
+
+            let entry = match models.iter_mut().position(|m| m.model == usage.model) {
+                Some(idx) => &mut models[idx],
+                None => {
+                    models.push(ModelUsage {
+                        model: usage.model.clone(),
+                        turns: 0,
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        thinking_tokens: 0,
+                        cache_write_tokens: 0,
+                        cache_read_tokens: 0,
+                        tool_calls: 0,
+                        cost: None,
+                    });
+                    models.last_mut().expect("just pushed")
+                }
+            };
+            entry.turns += 1;
+            entry.input_tokens += usage.input_tokens;
+            entry.output_tokens += usage.output_tokens;
+            entry.thinking_tokens += usage.thinking_tokens;
+            entry.cache_write_tokens += usage.cache_write_tokens;
+            entry.cache_read_tokens += usage.cache_read_tokens;
+            entry.tool_calls += usage.tool_calls;
+        }
+
+        for model in &mut models {
+            model.cost = cost_of(
+                price_of(pricing, catalog, &model.model),
+                model.input_tokens,
+                model.output_tokens,
+                model.cache_write_tokens,
+                model.cache_read_tokens,
+            );
+        }
+
+        let mut tool_counts = BTreeMap::new();
+        for segment in thread.segments() {
+            for message in &segment.messages {
+                for block in &message.content {
+                    if let ContentBlock::ToolUse(tool_use) = block {
+                        *tool_counts.entry(tool_use.name.clone()).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        Self {
+            thread_id: thread.id.clone(),
+            title: thread.title.clone(),
+            input_tokens: models.iter().map(|m| m.input_tokens).sum(),
+            output_tokens: models.iter().map(|m| m.output_tokens).sum(),
+            total_cost: models.iter().map(|m| m.cost).sum(),
+            models,
+            turns,
+            tool_counts,
+        }
+    }
+
+    /// Plain-text rendering for the pager
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Usage report: {} ({})\n\n", self.title, self.thread_id);
+        if self.turns.is_empty() {
+            out.push_str("No token usage recorded for this thread yet.\n");
+            return out;
+        }
+
+        out.push_str(&format!(
+            "Total: {} input, {} output tokens, cost {}\n\n",
+            self.input_tokens,
+            self.output_tokens,
+            format_cost(self.total_cost)
+        ));
+        let (cache_write, cache_read) = self
+            .models
+            .iter()
+            .fold((0, 0), |(w, r), m| (w + m.cache_write_tokens, r + m.cache_read_tokens));
+        if cache_write + cache_read > 0 {
+            out.push_str(&format!("Prompt cache: {} written, {} read\n\n", cache_write, cache_read));
+        }
+
+        out.push_str("By model\n");
+        out.push_str(&format!(
+            "  {:<32} {:>6} {:>10} {:>10} {:>9} {:>6} {:>10}\n",
+            "model", "turns", "input", "output", "thinking", "tools", "cost"
+        ));
+        for m in &self.models {
+            out.push_str(&format!(
+                "  {:<32} {:>6} {:>10} {:>10} {:>9} {:>6} {:>10}\n",
+                m.model, m.turns, m.input_tokens, m.output_tokens, m.thinking_tokens, m.tool_calls, format_cost(m.cost)
+            ));
+        }
+
+        out.push_str("\nTurns\n");
+        for row in &self.turns {
+            out.push_str(&format!(
+                "  {:>3}  {}  {:<32} {:>8} in {:>8} out {:>3} tools  {}\n",
+                row.turn,
+                row.usage.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                row.usage.model,
+                row.usage.input_tokens,
+                row.usage.output_tokens,
+                row.usage.tool_calls,
+                format_cost(row.cost)
+            ));
+        }
+
+        if !self.tool_counts.is_empty() {
+            out.push_str("\nTool calls\n");
+            for (name, count) in &self.tool_counts {
+                out.push_str(&format!("  {:<32} {:>6}\n", name, count));
+            }
+        }
+        out
+    }
+
+    /// Turn-by-turn CSV; unpriced turns leave the cost column empty
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("turn,timestamp,model,input_tokens,output_tokens,thinking_tokens,cache_write_tokens,cache_read_tokens,tool_calls,cost_usd\n");
+        for row in &self.turns {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{}\n",
+                row.turn,
+                row.usage.at.to_rfc3339(),
+                csv_field(&row.usage.model),
+                row.usage.input_tokens,
+                row.usage.output_tokens,
+                row.usage.thinking_tokens,
+                row.usage.cache_write_tokens,
+                row.usage.cache_read_tokens,
+                row.usage.tool_calls,
+                row.cost.map(|c| format!("{:.6}", c)).unwrap_or_default()
+            ));
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize usage report: {}", e))
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::context::{ContextSegment, SegmentKind};
+    use crate::llm::types::{Message, Role, ToolUse};
+
+    fn turn(model: &str, input: u64, output: u64, tool_calls: u32) -> TurnUsage {
+        TurnUsage {
+            model: model.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            thinking_tokens: 0,
+            cache_write_tokens: 0,
+            cache_read_tokens: 0,
+            tool_calls,
+            at: chrono::Utc::now(),
+        }
+    }
+
+    fn pricing() -> HashMap<String, ModelPricing> {
+        HashMap::from([(
+            "claude-sonnet-4".to_string(),
+            ModelPricing { input_per_mtok: 3.0, output_per_mtok: 15.0, ..Default::default() },
+        )])
+    }
+
+    #[test]
+    fn test_report_itemizes_per_model() {
+        let mut thread = AgentThread::new("claude-sonnet-4-20250514");
+        thread.record_usage(turn("claude-sonnet-4-20250514", 1_000_000, 0, 1));
+        thread.record_usage(turn("local-model", 500, 100, 0));
+        thread.record_usage(turn("claude-sonnet-4-20250514", 0, 1_000_000, 0));
+        thread.add_segment(ContextSegment::new(
+            SegmentKind::ChatHistory,
+            vec![Message {
+                role: Role::Assistant,
+                content: vec![ContentBlock::ToolUse(ToolUse {
+                    id: "t1".to_string(),
+                    name: "file_read".to_string(),
+                    input: serde_json::json!({}),
+                })],
+            }],
+            0,
+        ));
+
+        let report = UsageReport::from_thread(&thread, &pricing(), &ModelCatalog::new());
+        assert_eq!(report.models.len(), 2);
+        assert_eq!(report.turns.len(), 3);
+
+        let sonnet = &report.models[0];
+        assert_eq!(sonnet.turns, 2);
+        assert_eq!(sonnet.cost, Some(18.0));
+        assert_eq!(sonnet.tool_calls, 1);
+
+        // Unpriced model: n/a, and the total can't be known
+        assert_eq!(report.models[1].cost, None);
+        assert_eq!(report.total_cost, None);
+        assert!(report.to_text().contains("n/a"));
+        assert_eq!(report.tool_counts.get("file_read"), Some(&1));
+
+        let csv = report.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(2).unwrap().ends_with(",0,"));
+    }
+
+    #[test]
+    fn test_segment_footer() {
+        let mut assistant = ContextSegment::new(SegmentKind::ChatHistory, vec![Message::assistant("hi")], 1);
+        let catalog = ModelCatalog::new();
+        assert_eq!(segment_footer(&assistant, &pricing(), &catalog), None);
+
+        assistant.usage = Some(turn("claude-sonnet-4-20250514", 12_300, 456, 0));
+        assert_eq!(segment_footer(&assistant, &pricing(), &catalog).unwrap(), "12.3k in · 456 out · $0.0437");
+        assistant.usage = Some(turn("unpriced", 10, 20, 0));
+        assert_eq!(segment_footer(&assistant, &pricing(), &catalog).unwrap(), "10 in · 20 out");
+
+        let mut user = ContextSegment::new(SegmentKind::ChatHistory, vec![Message::user("hello")], 0);
+        user.token_count = Some(42);
+        assert_eq!(segment_footer(&user, &pricing(), &catalog).unwrap(), "42 tokens");
+    }
+
+    #[test]
+    fn test_pricing_prefers_exact_then_longest_prefix() {
+        let mut table = pricing();
+        table.insert("claude".to_string(), ModelPricing { input_per_mtok: 1.0, output_per_mtok: 1.0, ..Default::default() });
+        table.insert("claude-sonnet-4-20250514".to_string(), ModelPricing { input_per_mtok: 2.0, output_per_mtok: 2.0, ..Default::default() });
+
+        assert_eq!(pricing_for(&table, "claude-sonnet-4-20250514").unwrap().input_per_mtok, 2.0);
+        assert_eq!(pricing_for(&table, "claude-sonnet-4-5").unwrap().input_per_mtok, 3.0);
+        assert_eq!(pricing_for(&table, "claude-haiku").unwrap().input_per_mtok, 1.0);
+        assert!(pricing_for(&table, "gpt-4o").is_none());
+    }
+
+    #[test]
+    fn test_catalog_prices_models_missing_from_config() {
+        let catalog = ModelCatalog::new();
+        let mut thread = AgentThread::new("gpt-4o");
+        thread.record_usage(turn("gpt-4o", 1_000_000, 100_000, 0));
+        assert_eq!(thread.estimated_cost(&HashMap::new(), &catalog), Some(3.5));
+
+        // llm.toml prices win over the catalog
+        let table = HashMap::from([("gpt-4o".to_string(), ModelPricing { input_per_mtok: 1.0, output_per_mtok: 0.0, ..Default::default() })]);
+        assert_eq!(thread.estimated_cost(&table, &catalog), Some(1.0));
+
+        thread.record_usage(turn("local-model", 10, 10, 0));
+        assert_eq!(thread.estimated_cost(&HashMap::new(), &catalog), None);
+    }
+
+    #[test]
+    fn test_prompt_cache_tokens_priced() {
+        let mut cached = turn("claude-sonnet-4-20250514", 0, 0, 0);
+        cached.cache_write_tokens = 1_000_000;
+        cached.cache_read_tokens = 1_000_000;
+        let catalog = ModelCatalog::new();
+
+        // Default rates: writes at 1.25x and reads at 0.1x the $3 input price
+        let cost = turn_cost(&cached, &pricing(), &catalog).unwrap();
+        assert!((cost - 4.05).abs() < 1e-9);
+
+        let table = HashMap::from([(
+            "claude-sonnet-4".to_string(),
+            ModelPricing { input_per_mtok: 3.0, cache_write_per_mtok: Some(2.0), cache_read_per_mtok: Some(0.5), ..Default::default() },
+        )]);
+        assert_eq!(turn_cost(&cached, &table, &catalog), Some(2.5));
+
+        let mut thread = AgentThread::new("claude-sonnet-4-20250514");
+        thread.record_usage(cached);
+        let report = UsageReport::from_thread(&thread, &table, &catalog);
+        assert_eq!(report.models[0].cache_read_tokens, 1_000_000);
+        assert!(report.to_text().contains("Prompt cache: 1000000 written, 1000000 read"));
+
+        // Threads saved before the cache fields load with zeros
+        let old: TurnUsage = serde_json::from_str(
+            r#"{"model":"m","input_tokens":1,"output_tokens":2,"at":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!((old.cache_write_tokens, old.cache_read_tokens), (0, 0));
+    }
+}
@@ -7,13 +7,16 @@ use std::sync::Arc;
 
 use tokio::sync::mpsc;
 
+use super::tool_batch::ToolBatch;
+use super::turn_changes::{EditHistory, TurnChanges};
 use crate::agent::{
-    AgentEngine, AgentEvent, DiskThreadStore, ModelCatalog, SubagentManager, TokenCounter,
+    AgentEngine, AgentEvent, DiskThreadStore, ModelCatalog, SubagentEvent, SubagentManager, SubagentMonitor, TokenCounter,
 };
 use crate::components::chat_input::ChatInput;
 use crate::components::conversation_viewer::ConversationViewer;
+use crate::components::plan_panel::PlanPanel;
 use crate::components::thread_picker::ThreadPicker;
-use crate::llm::{BlockType, LLMEvent, PendingToolUse, ToolExecutor, ToolResult};
+use crate::llm::{BlockType, DryRun, LLMEvent, PendingToolUse, ToolExecutor, ToolResult};
 
 pub struct AgentRuntimeState {
     // Core agent engine and event channels
@@ -24,7 +27,11 @@ pub struct AgentRuntimeState {
     // Thread management
     pub current_thread_id: Option<String>,
     pub thread_rename_buffer: Option<String>,
+    /// Space-separated tags being edited for the current thread
+    pub thread_tags_buffer: Option<String>,
     pub thread_picker: ThreadPicker,
+    /// Thread whose context-overflow warning the user already dismissed by sending anyway
+    pub overflow_confirmed_thread: Option<String>,
 
     // Streaming state
     pub llm_response_buffer: String,
@@ -37,35 +44,56 @@ pub struct AgentRuntimeState {
     pub model_catalog: Arc<ModelCatalog>,
     pub token_counter: Arc<dyn TokenCounter>,
     pub cached_token_count: Option<(usize, u32)>,
+    /// Tool-definition token cost, keyed by (model, compact descriptions)
+    pub cached_tool_tokens: Option<(String, bool, u32)>,
+    /// System-prompt token cost, keyed by (model, hash of the built prompt)
+    pub cached_system_tokens: Option<(String, u64, u32)>,
 
     // Chat UI components (agent-centric)
     pub conversation_viewer: ConversationViewer,
+    /// Sidebar with the current thread's plan
+    pub plan_panel: PlanPanel,
     pub chat_input: ChatInput,
     pub show_conversation: bool,
+    /// Set when the user explicitly closes the conversation; streaming won't reopen it
+    pub conversation_pinned_closed: bool,
 
     // Sub-agents (T2.2)
     pub subagent_manager: Option<SubagentManager>,
+    pub subagent_event_rx: Option<mpsc::UnboundedReceiver<SubagentEvent>>,
+    /// Transient sub-agent progress for the status bar and peek view
+    pub subagent_monitor: SubagentMonitor,
 
     // Tool execution
     pub tool_executor: ToolExecutor,
 
     // Tool batch tracking (Order 6 optimization)
     pub pending_tools: HashMap<String, PendingToolUse>,
-    pub current_batch_id: u64,
-    pub expected_tool_batch: Option<(u64, usize)>,
-    pub collected_results: HashMap<String, ToolResult>,
-    pub tool_batch_map: HashMap<String, u64>,
+    pub tool_batch: ToolBatch,
+    /// Snapshots of files written this turn
+    pub turn_changes: TurnChanges,
+    /// Changesets of the last turns that wrote files, for undo
+    pub edit_history: EditHistory,
 
     // Tool streaming state
     pub confirming_tool_id: Option<String>,
-    pub current_tool_id: Option<String>,
-    pub current_tool_name: Option<String>,
-    pub current_tool_input: String,
     pub tool_result_rxs:
         HashMap<String, mpsc::UnboundedReceiver<std::result::Result<ToolResult, crate::llm::ToolError>>>,
 
     // Dangerous mode (TRC-018)
     pub dangerous_mode: bool,
+
+    /// Step-through mode: confirm every tool and pause after each result
+    pub step_mode: bool,
+
+    /// Continue the agent loop automatically once tool results are collected
+    pub auto_continue: bool,
+
+    /// Dry-run planning: tools are described to the model instead of executed
+    pub dry_run: bool,
+
+    /// When the current user turn started (first request, through tool rounds)
+    pub turn_started_at: Option<std::time::Instant>,
 }
 
 impl AgentRuntimeState {
@@ -77,15 +105,18 @@ impl AgentRuntimeState {
         model_catalog: Arc<ModelCatalog>,
         token_counter: Arc<dyn TokenCounter>,
         tool_executor: ToolExecutor,
-        subagent_manager: Option<SubagentManager>,
+        mut subagent_manager: Option<SubagentManager>,
     ) -> Self {
+        let subagent_event_rx = subagent_manager.as_mut().and_then(|m| m.take_event_rx());
         Self {
             agent_engine,
             agent_event_rx: Some(agent_event_rx),
             agent_llm_event_rx,
             current_thread_id: None,
             thread_rename_buffer: None,
+            thread_tags_buffer: None,
             thread_picker: ThreadPicker::new(),
+            overflow_confirmed_thread: None,
             llm_response_buffer: String::new(),
             thinking_buffer: String::new(),
             current_block_type: None,
@@ -93,33 +124,55 @@ impl AgentRuntimeState {
             model_catalog,
             token_counter,
             cached_token_count: None,
+            cached_tool_tokens: None,
+            cached_system_tokens: None,
             conversation_viewer: ConversationViewer::new(),
+            plan_panel: PlanPanel::new(),
             chat_input: ChatInput::new(),
             show_conversation: false,
+            conversation_pinned_closed: false,
             subagent_manager,
+            subagent_event_rx,
+            subagent_monitor: SubagentMonitor::new(),
             tool_executor,
             pending_tools: HashMap::new(),
-            current_batch_id: 0,
-            expected_tool_batch: None,
-            collected_results: HashMap::new(),
-            tool_batch_map: HashMap::new(),
+            tool_batch: ToolBatch::new(),
+            turn_changes: TurnChanges::new(),
+            edit_history: EditHistory::new(),
             confirming_tool_id: None,
-            current_tool_id: None,
-            current_tool_name: None,
-            current_tool_input: String::new(),
             tool_result_rxs: HashMap::new(),
             dangerous_mode: false,
+            step_mode: false,
+            auto_continue: true,
+            dry_run: false,
+            turn_started_at: None,
         }
     }
 
-    /// Clear all streaming buffers (response, thinking, tool input)
+    /// True while response or thinking text is streaming in
+    pub fn is_streaming_response(&self) -> bool {
+        !self.llm_response_buffer.is_empty() || !self.thinking_buffer.is_empty()
+    }
+
+    /// Whether the conversation panel is shown. Streaming opens it automatically
+    /// when `auto_open` is on, unless the user pinned it closed.
+    pub fn conversation_visible(&self, auto_open: bool) -> bool {
+        self.show_conversation
+            || (auto_open && !self.conversation_pinned_closed && self.is_streaming_response())
+    }
+
+    /// Open or close the conversation at the user's request
+    pub fn set_conversation_open(&mut self, open: bool) {
+        self.show_conversation = open;
+        self.conversation_pinned_closed = !open;
+    }
+
+    /// Clear all streaming buffers (response, thinking, block type).
+    /// Tool input is assembled by the LLM manager and owned by AgentEngine.
     pub fn clear_streaming_buffers(&mut self) {
         self.llm_response_buffer.clear();
         self.thinking_buffer.clear();
         self.current_block_type = None;
-        self.current_tool_id = None;
-        self.current_tool_name = None;
-        self.current_tool_input.clear();
     }
 
     /// Set dangerous mode on both the state and tool executor
@@ -128,6 +181,24 @@ impl AgentRuntimeState {
         self.tool_executor.set_dangerous_mode(enabled);
     }
 
+    /// Set step-through mode on both the state and agent engine
+    pub fn set_step_mode(&mut self, enabled: bool) {
+        self.step_mode = enabled;
+        self.agent_engine.set_step_mode(enabled);
+    }
+
+    /// Set dry-run mode on both the state and tool executor
+    pub fn set_dry_run(&mut self, enabled: bool, intercept_reads: bool) {
+        self.dry_run = enabled;
+        self.tool_executor.set_dry_run(DryRun { enabled, intercept_reads });
+    }
+
+    /// Set auto-continue on both the state and agent engine
+    pub fn set_auto_continue(&mut self, enabled: bool) {
+        self.auto_continue = enabled;
+        self.agent_engine.set_auto_continue(enabled);
+    }
+
     /// Invalidate token count cache (call when messages change)
     #[inline]
     pub fn invalidate_token_cache(&mut self) {
@@ -0,0 +1,139 @@
+// Desktop notifications and bell - surface important events while ridge-control is in the background
+//
+// Notifications go through the platform's own notifier (notify-send, osascript)
+// rather than the notify-rust crate. That crate pulls in a D-Bus client
+// (zbus or dbus-rs) for a feature that is off by default. Going through the
+// notifier adds no build dependency, works the same on Linux and macOS, and a
+// missing notifier only logs at debug level.
+
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::NotificationsConfig;
+
+/// Events that can raise a desktop notification (`[notifications]` toggles)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesktopEvent {
+    TurnComplete,
+    ToolConfirmation,
+    StreamDisconnect,
+    LlmError,
+}
+
+impl DesktopEvent {
+    fn enabled(self, config: &NotificationsConfig) -> bool {
+        match self {
+            DesktopEvent::TurnComplete => config.turn_complete,
+            DesktopEvent::ToolConfirmation => config.tool_confirmation,
+            DesktopEvent::StreamDisconnect => config.stream_disconnect,
+            DesktopEvent::LlmError => config.llm_error,
+        }
+    }
+}
+
+/// Whether `event` should notify. `terminal_focused` is None until the host
+/// terminal reports focus, in which case notifications always fire.
+pub fn should_notify(config: &NotificationsConfig, event: DesktopEvent, terminal_focused: Option<bool>) -> bool {
+    config.desktop && event.enabled(config) && terminal_focused != Some(true)
+}
+
+/// Show a desktop notification via the platform notifier (notify-send or
+/// osascript). Runs in the background; failures are only logged.
+pub fn send(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            escape_applescript(body),
+            escape_applescript(title)
+        );
+        let mut cmd = Command::new("osascript");
+        cmd.arg("-e").arg(script);
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.arg("--app-name=ridge-control").arg(title).arg(body);
+        cmd
+    };
+    command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    std::thread::spawn(move || {
+        if let Err(e) = command.status() {
+            tracing::debug!("Desktop notification failed: {}", e);
+        }
+    });
+}
+
+/// Whether `event` should ring the bell. Only turn completion and tool
+/// confirmation ring; completions faster than `bell_min_turn_secs` stay quiet.
+pub fn should_ring(config: &NotificationsConfig, event: DesktopEvent, turn_duration: Option<Duration>) -> bool {
+    match event {
+        DesktopEvent::TurnComplete => {
+            config.bell_on_complete
+                && turn_duration.is_some_and(|d| d >= Duration::from_secs(config.bell_min_turn_secs))
+        }
+        DesktopEvent::ToolConfirmation => config.bell_on_confirm,
+        DesktopEvent::StreamDisconnect | DesktopEvent::LlmError => false,
+    }
+}
+
+/// Run the configured sound command in the background
+pub fn play_sound(command: &str) {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    std::thread::spawn(move || {
+        if let Err(e) = cmd.status() {
+            tracing::debug!("Bell command failed: {}", e);
+        }
+    });
+}
+
+fn escape_applescript(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_notify_gating() {
+        let mut config = NotificationsConfig::default();
+        assert!(!should_notify(&config, DesktopEvent::TurnComplete, None));
+
+        config.desktop = true;
+        assert!(should_notify(&config, DesktopEvent::TurnComplete, None));
+        assert!(should_notify(&config, DesktopEvent::LlmError, Some(false)));
+        // Focused terminal: the in-app toast is enough
+        assert!(!should_notify(&config, DesktopEvent::LlmError, Some(true)));
+
+        config.stream_disconnect = false;
+        assert!(!should_notify(&config, DesktopEvent::StreamDisconnect, None));
+        assert!(should_notify(&config, DesktopEvent::ToolConfirmation, None));
+    }
+
+    #[test]
+    fn test_should_ring() {
+        let secs = |s| Some(Duration::from_secs(s));
+        let mut config = NotificationsConfig::default();
+        assert!(!should_ring(&config, DesktopEvent::TurnComplete, secs(60)));
+        assert!(!should_ring(&config, DesktopEvent::ToolConfirmation, None));
+
+        config.bell_on_complete = true;
+        config.bell_on_confirm = true;
+        assert!(should_ring(&config, DesktopEvent::TurnComplete, secs(60)));
+        // Trivially short turns stay quiet
+        assert!(!should_ring(&config, DesktopEvent::TurnComplete, secs(2)));
+        assert!(!should_ring(&config, DesktopEvent::TurnComplete, None));
+        assert!(should_ring(&config, DesktopEvent::ToolConfirmation, None));
+        assert!(!should_ring(&config, DesktopEvent::LlmError, secs(60)));
+
+        config.bell_min_turn_secs = 0;
+        assert!(should_ring(&config, DesktopEvent::TurnComplete, secs(0)));
+    }
+
+    #[test]
+    fn test_escape_applescript() {
+        assert_eq!(escape_applescript(r#"say "hi" \o/"#), r#"say \"hi\" \\o/"#);
+    }
+}
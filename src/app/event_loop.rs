@@ -1,6 +1,8 @@
 // Event loop - main run() method using tokio::select! for event-driven processing
 // Order 9: Converted from polling-based to event-driven architecture
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crossterm::event::{self, Event as CrosstermEvent, MouseEventKind};
@@ -12,10 +14,27 @@ use crate::config::ConfigEvent;
 use crate::error::Result;
 use crate::event::PtyEvent;
 use crate::input::mode::InputMode;
-use crate::llm::{ToolError, ToolResult, ToolResultContent};
+use crate::llm::{ToolError, ToolExecutionCheck, ToolResult, ToolResultContent};
 use crate::sirk::ForgeEvent;
 use crate::tabs::TabId;
 
+/// Input poll interval while the user, a PTY, or an animation is active
+const ACTIVE_POLL_MS: u64 = 16;
+/// Quiet period before the input poll interval starts backing off
+const IDLE_AFTER_MS: u64 = 2000;
+
+/// Input poll timeout after `idle_for` without activity: 16ms while active,
+/// then doubling every second of idleness up to `idle_cap_ms`.
+fn input_poll_timeout(idle_for: Duration, idle_cap_ms: u64) -> Duration {
+    let idle_ms = idle_for.as_millis() as u64;
+    if idle_ms < IDLE_AFTER_MS {
+        return Duration::from_millis(ACTIVE_POLL_MS);
+    }
+    let doublings = ((idle_ms - IDLE_AFTER_MS) / 1000 + 1).min(16) as u32;
+    let backoff = ACTIVE_POLL_MS << doublings;
+    Duration::from_millis(backoff.min(idle_cap_ms.max(ACTIVE_POLL_MS)))
+}
+
 impl App {
     /// Handle a single PTY event
     fn handle_pty_event(&mut self, tab_id: TabId, event: PtyEvent) {
@@ -58,8 +77,8 @@ impl App {
             Ok(tool_result) => {
                 self.dispatch(Action::ToolResult(tool_result))?;
             }
-            Err(ToolError::WaitingForUserInput { tool_use_id, questions }) => {
-                self.ui.ask_user_dialog.show(tool_use_id, questions);
+            Err(ToolError::WaitingForUserInput { tool_use_id, questions, timeout_secs }) => {
+                self.show_ask_user(tool_use_id, questions, timeout_secs);
             }
             Err(e) => {
                 let error_result = ToolResult {
@@ -81,7 +100,9 @@ impl App {
     /// without returning an Action, so they always need a redraw.
     fn handle_input_event(&mut self, event: crossterm::event::Event) -> Result<()> {
         let overlay_active = matches!(self.ui.input_mode, InputMode::CommandPalette | InputMode::Confirm { .. })
-            || self.ui.ask_user_dialog.is_visible();
+            || self.ui.ask_user_dialog.is_visible()
+            || self.ui.pager.is_visible()
+            || self.ui.model_picker.is_visible();
 
         if let Some(action) = self.handle_event(event) {
             // PtyInput just writes bytes to the PTY — no visual change until the
@@ -281,24 +302,26 @@ impl App {
     }
 
     /// Spawn the input reader thread that forwards crossterm events to a tokio channel.
-    /// Uses adaptive poll rate: 16ms when active, 33ms when idle (after 2s).
+    /// Uses adaptive poll rate: 16ms when active, backing off to `ui.idle_poll_ms`
+    /// when idle. The returned flag lets the main loop report PTY output and
+    /// animations as activity so the reader snaps back to the fast path.
     /// Filters out high-frequency mouse motion events that flood TMUX multiplexers.
-    fn spawn_input_reader(&self) -> mpsc::UnboundedReceiver<crossterm::event::Event> {
+    fn spawn_input_reader(&self) -> (mpsc::UnboundedReceiver<crossterm::event::Event>, Arc<AtomicBool>) {
         let (tx, rx) = mpsc::unbounded_channel();
         let in_tmux = std::env::var("TMUX").is_ok();
+        let idle_cap_ms = self.config_manager.app_config().ui.idle_poll_ms;
+        let activity = Arc::new(AtomicBool::new(false));
+        let reader_activity = activity.clone();
 
         std::thread::spawn(move || {
             let mut last_activity = Instant::now();
 
             loop {
-                // Adaptive poll rate: 16ms active, 33ms idle.
-                // Idle threshold at 2s so normal typing pauses stay in active mode.
-                let idle = last_activity.elapsed() > Duration::from_millis(2000);
-                let poll_timeout = if idle {
-                    Duration::from_millis(33)
-                } else {
-                    Duration::from_millis(16)
-                };
+                // Idle threshold at 2s so normal typing pauses stay in active mode
+                if reader_activity.swap(false, Ordering::Relaxed) {
+                    last_activity = Instant::now();
+                }
+                let poll_timeout = input_poll_timeout(last_activity.elapsed(), idle_cap_ms);
 
                 match event::poll(poll_timeout) {
                     Ok(true) => {
@@ -338,7 +361,7 @@ impl App {
             }
         });
 
-        rx
+        (rx, activity)
     }
 
     /// Spawn a thread that forwards config watcher events to a tokio channel.
@@ -372,7 +395,7 @@ impl App {
         let mut stream_rx = self.stream_manager.take_event_rx();
         
         // Spawn adapters for blocking sources
-        let mut input_rx = self.spawn_input_reader();
+        let (mut input_rx, input_activity) = self.spawn_input_reader();
         let mut config_rx = self.spawn_config_watcher_adapter();
 
         // Create a unified PTY event channel and spawn forwarders
@@ -440,11 +463,19 @@ impl App {
                 let id = tool_id.clone();
                 let handle = tokio::spawn(async move {
                     let mut rx = rx;
+                    let mut delivered = false;
                     while let Some(result) = rx.recv().await {
+                        delivered = true;
                         if tx.send((id.clone(), result)).is_err() {
                             break;
                         }
                     }
+                    // A task that dies without a result still counts, so the batch can't stall
+                    if !delivered {
+                        let _ = tx.send((id, Err(ToolError::ExecutionFailed(
+                            "tool task ended without returning a result".to_string(),
+                        ))));
+                    }
                 });
                 tool_forwarder_handles.push(handle);
             }
@@ -470,6 +501,7 @@ impl App {
 
                 // 2. PTY events
                 Some((tab_id, event)) = pty_rx.recv() => {
+                    input_activity.store(true, Ordering::Relaxed);
                     self.handle_pty_event(tab_id, event);
                     // Drain any buffered PTY events for efficiency
                     while let Ok((tid, ev)) = pty_rx.try_recv() {
@@ -521,6 +553,23 @@ impl App {
                     self.mark_dirty();
                 }
 
+                // 4b. Sub-agent progress (transient; results arrive via the task APIs)
+                Some(subagent_event) = async {
+                    if let Some(ref mut rx) = self.agent.subagent_event_rx {
+                        rx.recv().await
+                    } else {
+                        std::future::pending().await
+                    }
+                } => {
+                    self.agent.subagent_monitor.apply(subagent_event);
+                    if let Some(ref mut rx) = self.agent.subagent_event_rx {
+                        while let Ok(ev) = rx.try_recv() {
+                            self.agent.subagent_monitor.apply(ev);
+                        }
+                    }
+                    self.mark_dirty();
+                }
+
                 // 5. Tool results
                 Some((tool_id, result)) = tool_rx.recv() => {
                     self.handle_tool_result(tool_id, result)?;
@@ -529,9 +578,10 @@ impl App {
                         self.handle_tool_result(tid, res)?;
                     }
                     // Clean up pending tools
-                    self.agent.pending_tools.retain(|id, _| {
-                        // Keep if still has active receiver or in collected results
-                        self.agent.collected_results.contains_key(id)
+                    self.agent.pending_tools.retain(|id, pending| {
+                        // Keep if in collected results or still waiting for the user
+                        self.agent.tool_batch.has_result(id)
+                            || (pending.check != ToolExecutionCheck::Allowed && self.agent.tool_batch.contains(id))
                     });
                 }
 
@@ -620,7 +670,19 @@ impl App {
                     self.mark_dirty();
                 }
 
-                // 10. Timer: fires for pending render deadline or tick, whichever is sooner
+                // 10. API key test results from the settings editor
+                Some(action) = async {
+                    if let Some(ref mut rx) = self.key_test_rx {
+                        rx.recv().await
+                    } else {
+                        std::future::pending().await
+                    }
+                } => {
+                    self.dispatch(action)?;
+                    self.mark_dirty();
+                }
+
+                // 11. Timer: fires for pending render deadline or tick, whichever is sooner
                 _ = tokio::time::sleep(timer_duration) => {
                     // Only dispatch tick when actually due
                     if self.last_tick.elapsed() >= Duration::from_millis(TICK_INTERVAL_MS) {
@@ -639,6 +701,11 @@ impl App {
                 break;
             }
 
+            // Spinners and notifications keep input polling on the fast path
+            if self.ui.spinner_manager.active_count() > 0 || self.ui.notification_manager.has_notifications() {
+                input_activity.store(true, Ordering::Relaxed);
+            }
+
             // Draw once if anything changed, throttled to prevent
             // escape sequence floods that cause TMUX lag.
             // Draw once if anything changed, throttled to ~30 FPS to prevent
@@ -656,3 +723,21 @@ impl App {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_poll_timeout_backs_off_to_cap() {
+        let ms = Duration::from_millis;
+        assert_eq!(input_poll_timeout(ms(0), 250), ms(16));
+        assert_eq!(input_poll_timeout(ms(1999), 250), ms(16));
+        assert_eq!(input_poll_timeout(ms(2000), 250), ms(32));
+        assert_eq!(input_poll_timeout(ms(3000), 250), ms(64));
+        assert_eq!(input_poll_timeout(ms(10_000), 250), ms(250));
+        assert_eq!(input_poll_timeout(ms(3_600_000), 250), ms(250));
+        // A cap below the active rate never slows the fast path
+        assert_eq!(input_poll_timeout(ms(10_000), 5), ms(16));
+    }
+}
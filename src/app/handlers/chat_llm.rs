@@ -1,67 +1,233 @@
 // Chat, LLM, threads, tools, and conversation dispatch
 // Domain: LLM messaging, chat input, conversation viewer, tool execution, thread management
 
+use std::path::PathBuf;
+
 use crate::action::Action;
-use crate::agent::ThreadStore;
+use crate::agent::usage::UsageReport;
+use crate::agent::{AgentState, ContextStats, ThreadStore};
+use crate::components::chat_input::PastedImage;
+use crate::components::pager::PagerKind;
 use crate::components::spinner_manager::SpinnerKey;
 use crate::error::Result;
 use crate::input::focus::FocusArea;
 use crate::input::mode::InputMode;
 use crate::llm::{PendingToolUse, ToolExecutionCheck};
+use crate::util::write_atomic;
 
+use super::super::tool_batch::BatchDecision;
+use super::super::turn_changes;
+use super::super::ui_state::ActionPrompt;
 use super::super::App;
 
+/// Thread titles listed in the "Delete Old Threads" prompt before "…and N more"
+const PRUNE_PREVIEW_TITLES: usize = 5;
+
 impl App {
     pub(super) fn dispatch_chat_llm(&mut self, action: Action) -> Result<()> {
         match action {
             // LLM messaging actions
             Action::LlmSendMessage(msg) => {
-                tracing::info!("Sending LLM message: {} chars", msg.len());
-                // Ensure conversation is visible when sending a message
-                if !self.agent.show_conversation {
-                    self.agent.show_conversation = true;
-                }
-
-                // Route through AgentEngine (always available)
-                // Ensure we have an active thread
-                if self.agent.agent_engine.current_thread().is_none() {
-                    let model = self.agent.agent_engine.current_model().to_string();
-                    self.agent.agent_engine.new_thread(model);
-                    // TP2-002-15: Update current_thread_id when auto-creating thread
-                    self.agent.current_thread_id = self.agent.agent_engine.current_thread().map(|t| t.id.clone());
-                    tracing::info!("Created new AgentEngine thread: {:?}", self.agent.current_thread_id);
+                let images: Vec<_> = self.agent.chat_input.images().iter().cloned().map(PastedImage::into_content).collect();
+                let overflow = self.agent.agent_engine.context_overflow(&msg, &images);
+                let confirmed = matches!(
+                    (&self.agent.overflow_confirmed_thread, self.agent.agent_engine.current_thread()),
+                    (Some(id), Some(thread)) if *id == thread.id
+                );
+                match overflow {
+                    Some((needed, budget)) if !confirmed && self.config_manager.app_config().conversation.warn_context_overflow => {
+                        // Hand the message back to the input until the user decides
+                        self.agent.chat_input.set_text(&msg);
+                        self.ui.action_prompt = Some(ActionPrompt {
+                            title: " Context Window ".to_string(),
+                            lines: vec![
+                                "This will exceed the context window and older messages will be dropped — proceed?".to_string(),
+                                format!(
+                                    "Estimated {} tokens, budget {}",
+                                    ContextStats::format_tokens(needed),
+                                    ContextStats::format_tokens(budget),
+                                ),
+                            ],
+                            action: Action::LlmSendMessageConfirmed(msg.clone()),
+                            alternate: Some(('f', "start a fresh thread", Action::LlmSendInNewThread(msg))),
+                        });
+                    }
+                    _ => self.send_llm_message(msg),
                 }
-
-                // Send message through AgentEngine
-                self.agent.agent_engine.send_message(msg);
-                tracing::info!("Message sent through AgentEngine");
+            }
+            Action::LlmSendMessageConfirmed(msg) => {
+                self.agent.chat_input.clear();
+                self.send_llm_message(msg);
+                // Don't ask again on every send for a thread the user chose to keep going in
+                self.agent.overflow_confirmed_thread = self.agent.agent_engine.current_thread().map(|t| t.id.clone());
+            }
+            Action::LlmSendInNewThread(msg) => {
+                self.agent.chat_input.clear();
+                self.dispatch_chat_llm(Action::ThreadNew)?;
+                self.send_llm_message(msg);
             }
             Action::LlmCancel => {
                 // Cancel AgentEngine's internal LLM
                 self.agent.agent_engine.cancel();
+                // Sub-agents spawned for this turn go with it, queued or running
+                if let Some(ref mut subagents) = self.agent.subagent_manager {
+                    let cancelled = subagents.cancel_all();
+                    if cancelled > 0 {
+                        tracing::info!("Cancelled {} sub-agents", cancelled);
+                    }
+                }
+                self.agent.subagent_monitor.clear();
                 // Immediately stop spinner and clear buffers for responsive UI
                 // (don't wait for async AgentEvent::Error to propagate)
                 self.ui.spinner_manager.stop(&SpinnerKey::LlmLoading);
-                self.agent.llm_response_buffer.clear();
-                self.agent.thinking_buffer.clear();
-                self.agent.current_block_type = None;
-                self.agent.current_tool_id = None;
-                self.agent.current_tool_name = None;
-                self.agent.current_tool_input.clear();
+                self.agent.clear_streaming_buffers();
+                // Results from tools still running belong to the cancelled turn
+                self.agent.tool_batch.clear();
+                self.record_turn_changes();
                 self.ui.notification_manager.info_with_message("Request Cancelled", "LLM request interrupted by user");
             }
+            Action::ShowLastLlmExchange => {
+                match self.agent.agent_engine.last_llm_exchange() {
+                    Some(exchange) => {
+                        let title = format!("LLM exchange · {} · {}", exchange.provider, exchange.model);
+                        self.ui.pager.show(title, &exchange.render());
+                    }
+                    None if self.config_manager.llm_config().debug_capture => {
+                        self.ui.notification_manager.info("No LLM call captured yet");
+                    }
+                    None => {
+                        self.ui.notification_manager.info_with_message(
+                            "LLM capture is off",
+                            "Set debug_capture = true in llm.toml",
+                        );
+                    }
+                }
+            }
+            Action::GenerateCrashReport => {
+                let report = crate::crash_report::CrashReport::collect(
+                    self.config_manager.config_dir(),
+                    self.config_manager.data_dir(),
+                    self.config_manager.log_dir(),
+                    self.agent.agent_engine.last_llm_exchange(),
+                    &crate::crash_report::known_secrets(self.keystore.as_ref()),
+                );
+                match report.write_to(&crate::crash_report::crash_dir(self.config_manager.data_dir())) {
+                    Ok(path) => self.ui.notification_manager.success_with_message(
+                        "Crash report written",
+                        format!("{} (review before sharing)", path.display()),
+                    ),
+                    Err(e) => self.ui.notification_manager.error_with_message("Crash report failed", e.to_string()),
+                }
+            }
+            Action::ShowTurnChanges => {
+                match self.agent.edit_history.last() {
+                    Some(changes) => {
+                        let base = std::env::current_dir().unwrap_or_default();
+                        let content = turn_changes::format_stat(changes, &base);
+                        self.ui.pager.show(turn_changes::headline(changes), &content);
+                    }
+                    None => self.ui.notification_manager.info("No agent turn has changed files yet"),
+                }
+            }
+            Action::UndoLastEdit => {
+                if !matches!(self.agent.agent_engine.state(), AgentState::Idle | AgentState::AwaitingUserInput | AgentState::Error) {
+                    self.ui.notification_manager.info("Wait for the agent turn to finish before undoing");
+                    return Ok(());
+                }
+                let Some(changes) = self.agent.edit_history.last() else {
+                    self.ui.notification_manager.info("No agent edits to undo");
+                    return Ok(());
+                };
+                let base = std::env::current_dir().unwrap_or_default();
+                let mut lines = vec![format!("Restore {} to before the agent's edit:", turn_changes::headline(changes))];
+                lines.extend(turn_changes::format_stat(changes, &base).lines().map(|l| format!("  {}", l)));
+                let changed: Vec<_> = changes.iter().filter(|c| c.changed_since()).collect();
+                if !changed.is_empty() {
+                    lines.push(String::new());
+                    lines.push("⚠ Changed since the agent's edit; those changes will be lost:".to_string());
+                    lines.extend(changed.iter().map(|c| {
+                        format!("  {}", c.path.strip_prefix(&base).unwrap_or(&c.path).display())
+                    }));
+                }
+                self.ui.action_prompt = Some(ActionPrompt {
+                    title: " Undo Last Edit ".to_string(),
+                    lines,
+                    action: Action::UndoLastEditConfirm,
+                    alternate: None,
+                });
+            }
+            Action::UndoLastEditConfirm => {
+                let Some(changes) = self.agent.edit_history.pop() else {
+                    return Ok(());
+                };
+                let failed: Vec<String> = changes
+                    .iter()
+                    .filter_map(|c| c.restore().err().map(|e| format!("{}: {}", c.path.display(), e)))
+                    .collect();
+                if failed.is_empty() {
+                    let files = if changes.len() == 1 { "file" } else { "files" };
+                    self.ui.notification_manager.success(format!("Restored {} {}", changes.len(), files));
+                } else {
+                    tracing::warn!("Undo failed for {}", failed.join(", "));
+                    self.ui.notification_manager.error_with_message("Undo incomplete", failed.join("\n"));
+                }
+            }
+            Action::LlmPause => {
+                if !self.agent.agent_engine.pause() {
+                    self.ui.notification_manager.info("Agent is not running");
+                } else if self.agent.agent_engine.pause_pending() {
+                    self.ui.notification_manager.info_with_message("Pausing Agent", "Will hold after the current tool results");
+                }
+            }
+            Action::LlmResume => {
+                if !self.agent.agent_engine.resume() {
+                    self.ui.notification_manager.info("Agent is not paused");
+                }
+            }
+            Action::AgentRetryLastTurn => {
+                if self.agent.agent_engine.retry_last_turn() {
+                    // Drop whatever the failed attempt streamed; new chunks are
+                    // only handled after this action, so nothing is lost
+                    self.agent.clear_streaming_buffers();
+                    self.agent.conversation_viewer.set_auto_scroll(true);
+                    self.ui.notification_manager.info("Retrying last turn");
+                } else {
+                    self.ui.notification_manager.info("Last turn did not fail; nothing to retry");
+                }
+            }
             Action::LlmSelectModel(model) => {
                 // Update AgentEngine's LLMManager
                 self.agent.agent_engine.set_model(&model);
-                // Also persist to config so model is remembered on restart
-                self.config_manager.llm_config_mut().defaults.model = model.clone();
-                if let Err(e) = self.config_manager.save_llm_config() {
-                    tracing::warn!("Failed to save model selection: {}", e);
-                }
+                self.record_model_switch(None, Some(&model));
             }
             Action::LlmSelectProvider(provider) => {
                 // Update AgentEngine's LLMManager
                 self.agent.agent_engine.set_provider(&provider);
+                // set_provider moved to that provider's default model; save it too
+                let model = self.agent.agent_engine.current_model().to_string();
+                self.record_model_switch(Some(&provider), Some(&model));
+            }
+            Action::ModelPickerShow => {
+                let entries = self.model_picker_entries();
+                self.ui.model_picker.show(entries);
+            }
+            Action::ModelPickerHide => {
+                self.ui.model_picker.hide();
+            }
+            Action::ModelPickerSelect { provider, model } => {
+                self.ui.model_picker.hide();
+                let registered = self.agent.agent_engine.registered_providers().contains(&provider);
+                self.agent.agent_engine.set_provider(&provider);
+                self.agent.agent_engine.set_model(&model);
+                self.record_model_switch(Some(&provider), Some(&model));
+                if registered {
+                    self.ui.notification_manager.info(format!("Model: {}/{}", provider, model));
+                } else {
+                    self.ui.notification_manager.warning_with_message(
+                        format!("Model: {}/{}", provider, model),
+                        format!("No API key for {} - add one in Settings before sending", provider),
+                    );
+                }
             }
             Action::LlmClearConversation => {
                 // Start a new thread to clear conversation (AgentEngine tracks via thread)
@@ -72,7 +238,7 @@ impl App {
                 self.agent.conversation_viewer.clear_tool_calls();
             }
             Action::LlmStreamChunk(_) | Action::LlmStreamComplete | Action::LlmStreamError(_) => {
-                // These are handled by handle_llm_event, not dispatched directly
+                // Streaming is handled via AgentEvent, not dispatched directly
             }
 
             // Chat input actions
@@ -82,6 +248,50 @@ impl App {
             Action::ChatInputPaste(text) => {
                 self.agent.chat_input.paste_text(&text);
             }
+            Action::ChatInputPasteImage => {
+                let Some(ref mut clipboard) = self.ui.clipboard else {
+                    self.ui.notification_manager.warning("Clipboard unavailable");
+                    return Ok(());
+                };
+                let model = self.agent.agent_engine.current_model().to_string();
+                let vision = self.agent.model_catalog.info_for(&model).supports_vision;
+                let image = if vision { clipboard.get_image().ok() } else { None };
+                match image {
+                    Some(image) => match PastedImage::from_rgba(image.width, image.height, &image.bytes) {
+                        Ok(pasted) => {
+                            self.ui.notification_manager.info(pasted.label());
+                            self.agent.chat_input.attach_image(pasted);
+                        }
+                        Err(e) => self.ui.notification_manager.error_with_message("Could not read the clipboard image", e),
+                    },
+                    None => {
+                        // Fall back to a text paste
+                        if let Ok(text) = clipboard.get_text() {
+                            self.agent.chat_input.paste_text(&text);
+                        }
+                        let reason = if vision {
+                            "No image on the clipboard".to_string()
+                        } else {
+                            format!("{} doesn't accept images", model)
+                        };
+                        self.ui.notification_manager.info(format!("{}; pasted text instead", reason));
+                    }
+                }
+            }
+            Action::ChatInputAttachImageFile(path) => {
+                let model = self.agent.agent_engine.current_model().to_string();
+                if !self.agent.model_catalog.info_for(&model).supports_vision {
+                    self.ui.notification_manager.warning(format!("{} doesn't accept images", model));
+                    return Ok(());
+                }
+                match PastedImage::from_file(&crate::config::expand_home(&path)) {
+                    Ok(image) => {
+                        self.ui.notification_manager.info(image.label());
+                        self.agent.chat_input.attach_image(image);
+                    }
+                    Err(e) => self.ui.notification_manager.error_with_message("Could not attach the image", e),
+                }
+            }
             Action::ChatInputCopy => {
                 // Copy selected text from chat input to clipboard
                 if let Some(text) = self.agent.chat_input.get_selected_text() {
@@ -119,7 +329,9 @@ impl App {
 
             // Conversation viewer actions
             Action::ConversationToggle => {
-                self.agent.show_conversation = !self.agent.show_conversation;
+                // An explicit toggle overrides auto-open until it is toggled again
+                let open = !self.agent.show_conversation;
+                self.agent.set_conversation_open(open);
                 // When opening conversation, focus the chat input for typing
                 if self.agent.show_conversation {
                     self.ui.focus.focus(FocusArea::ChatInput);
@@ -128,6 +340,13 @@ impl App {
                     self.ui.focus.focus(FocusArea::Terminal);
                 }
             }
+            Action::PlanPanelToggle => {
+                self.agent.plan_panel.toggle();
+                let has_plan = self.agent.agent_engine.current_thread().is_some_and(|t| !t.plan.is_empty());
+                if self.agent.plan_panel.is_visible() && !has_plan {
+                    self.ui.notification_manager.info("No plan yet - the panel opens when the agent makes one");
+                }
+            }
             Action::ConversationScrollUp(n) => {
                 self.agent.conversation_viewer.scroll_up(n);
             }
@@ -162,9 +381,6 @@ impl App {
             }
 
             // Tool execution actions
-            Action::ToolUseReceived(pending) => {
-                self.handle_tool_use_request(pending.tool.clone());
-            }
             Action::ToolConfirm => {
                 // User confirmed tool execution
                 self.ui.confirm_dialog.dismiss();
@@ -181,6 +397,7 @@ impl App {
                         self.execute_tool(confirmed_pending);
                     }
                 }
+                self.show_next_confirmation();
             }
             Action::ToolReject => {
                 // User rejected tool execution
@@ -189,35 +406,36 @@ impl App {
 
                 // Get the tool from pending_tools using confirming_tool_id
                 if let Some(tool_id) = self.agent.confirming_tool_id.take() {
-                    if let Some(pending) = self.agent.pending_tools.remove(&tool_id) {
-                        // Update tool state in conversation viewer (TRC-016)
-                        self.agent.conversation_viewer.reject_tool(&pending.tool.id);
-
-                        // Create rejection result
-                        let error_result = crate::llm::ToolResult {
-                            tool_use_id: pending.tool.id.clone(),
-                            content: crate::llm::ToolResultContent::Text(
-                                "User rejected tool execution".to_string()
-                            ),
-                            is_error: true,
-                        };
+                    if self.agent.pending_tools.contains_key(&tool_id) {
+                        self.reject_tool(&tool_id);
+                    }
+                }
+                self.show_next_confirmation();
+            }
+            Action::ToolConfirmAll => {
+                // Approve the rest of this turn only; the next turn prompts as usual
+                self.ui.confirm_dialog.dismiss();
+                self.ui.input_mode = InputMode::Normal;
+                self.agent.confirming_tool_id = None;
+                self.agent.tool_batch.set_decision(BatchDecision::ApproveAll);
 
-                        // TP2-002-12: Bridge tool rejection to AgentEngine if active
-                        // Collect rejection as a result
-                        self.agent.collected_results.insert(pending.tool.id.clone(), error_result);
-
-                        // Check if we have all results for current batch
-                        if let Some((batch_id, expected)) = self.agent.expected_tool_batch {
-                            if self.agent.collected_results.len() >= expected {
-                                let all_results: Vec<crate::llm::ToolResult> = self.agent.collected_results.drain().map(|(_, r)| r).collect();
-                                self.agent.agent_engine.continue_after_tools(all_results);
-                                self.agent.expected_tool_batch = None;
-                                // Clean up batch mappings for completed batch
-                                self.agent.tool_batch_map.retain(|_, bid| *bid != batch_id);
-                            }
-                        }
+                // Blocked tools can't be approved here; they still get their own dialog
+                for pending in self.awaiting_confirmation() {
+                    if pending.check == ToolExecutionCheck::RequiresConfirmation {
+                        self.execute_tool(PendingToolUse::new(pending.tool, ToolExecutionCheck::Allowed));
                     }
                 }
+                self.show_next_confirmation();
+            }
+            Action::ToolRejectAll => {
+                self.ui.confirm_dialog.dismiss();
+                self.ui.input_mode = InputMode::Normal;
+                self.agent.confirming_tool_id = None;
+                self.agent.tool_batch.set_decision(BatchDecision::RejectAll);
+
+                for pending in self.awaiting_confirmation() {
+                    self.reject_tool(&pending.tool.id);
+                }
             }
             Action::ToolResult(result) => {
                 let tool_use_id = result.tool_use_id.clone();
@@ -239,50 +457,8 @@ impl App {
                 // Remove from pending_tools since we got the result
                 self.agent.pending_tools.remove(&tool_use_id);
 
-                // Get the batch this tool belongs to
-                let tool_batch = self.agent.tool_batch_map.get(&tool_use_id).copied();
-
                 // TP2-002-12: Bridge tool result to AgentEngine
-                // Only collect if this tool belongs to current batch
-                if let Some((current_batch_id, expected)) = self.agent.expected_tool_batch {
-                    if tool_batch == Some(current_batch_id) {
-                        self.agent.collected_results.insert(tool_use_id.clone(), result);
-
-                        tracing::info!(
-                            "📥 TOOL_RESULT collected: id={}, batch={}, collected={}/{} expected",
-                            tool_use_id, current_batch_id, self.agent.collected_results.len(), expected
-                        );
-
-                        // Only continue when we have ALL expected results for this batch
-                        if self.agent.collected_results.len() >= expected {
-                            let all_results: Vec<crate::llm::ToolResult> = self.agent.collected_results.drain().map(|(_, r)| r).collect();
-
-                            tracing::info!(
-                                "✅ ALL_TOOLS_COMPLETE: batch={}, sending {} results to engine",
-                                current_batch_id, all_results.len()
-                            );
-
-                            self.agent.agent_engine.continue_after_tools(all_results);
-
-                            // Reset tracking state for next tool batch
-                            self.agent.expected_tool_batch = None;
-                            // Clean up batch mappings for completed batch
-                            self.agent.tool_batch_map.retain(|_, bid| *bid != current_batch_id);
-                        }
-                    } else {
-                        // Tool from a different/stale batch - log and ignore
-                        tracing::warn!(
-                            "⚠️ TOOL_RESULT from stale batch: id={}, tool_batch={:?}, current_batch={}",
-                            tool_use_id, tool_batch, current_batch_id
-                        );
-                    }
-                } else {
-                    // No active batch - this is a stale result
-                    tracing::warn!(
-                        "⚠️ TOOL_RESULT with no active batch: id={}, tool_batch={:?}",
-                        tool_use_id, tool_batch
-                    );
-                }
+                self.collect_tool_result(result);
             }
             Action::ToolToggleDangerousMode => {
                 let current = self.agent.dangerous_mode;
@@ -291,6 +467,46 @@ impl App {
             Action::ToolSetDangerousMode(enabled) => {
                 self.agent.set_dangerous_mode(enabled);
             }
+            Action::AgentToggleStepMode => {
+                let enabled = !self.agent.step_mode;
+                self.agent.set_step_mode(enabled);
+                if enabled {
+                    self.ui.notification_manager.info_with_message("Step Mode On", "Every tool needs confirmation; the agent pauses after each result");
+                } else {
+                    self.ui.notification_manager.info("Step Mode Off");
+                }
+            }
+            Action::AgentToggleAutoContinue => {
+                let enabled = !self.agent.auto_continue;
+                self.agent.set_auto_continue(enabled);
+                if enabled {
+                    self.ui.notification_manager.info("Auto-Continue On");
+                } else {
+                    self.ui.notification_manager.info_with_message("Auto-Continue Off", "The agent waits for Enter after each round of tool results");
+                }
+            }
+            Action::AgentToggleDryRun => {
+                let enabled = !self.agent.dry_run;
+                let intercept_reads = self.config_manager.app_config().tools.dry_run_intercept_reads;
+                self.agent.set_dry_run(enabled, intercept_reads);
+                if enabled {
+                    self.ui.notification_manager.info_with_message("Dry-Run On", "Tool calls are described to the model instead of executed");
+                } else {
+                    self.ui.notification_manager.info("Dry-Run Off");
+                }
+            }
+            Action::ShowSystemPrompt => {
+                let prompt = self.agent.agent_engine.resolved_system_prompt();
+                self.ui.pager.show_kind(PagerKind::SystemPrompt, "System Prompt", &prompt);
+            }
+            Action::AgentSelectPersona(name) => {
+                let requested = name.is_some();
+                match self.apply_persona(name) {
+                    Some(applied) => self.ui.notification_manager.info(format!("Persona: {}", applied)),
+                    None if !requested => self.ui.notification_manager.info("Persona: default"),
+                    None => {}
+                }
+            }
 
             // Tool Call UI actions (TRC-016)
             Action::ToolCallNextTool => {
@@ -314,6 +530,12 @@ impl App {
             Action::ToolCallRegister(tool_use) => {
                 self.agent.conversation_viewer.register_tool_use(tool_use);
             }
+            Action::ToolCallViewOutput => {
+                match self.agent.conversation_viewer.selected_tool_output() {
+                    Some((title, content)) => self.ui.pager.show(title, &content),
+                    None => self.ui.notification_manager.info("No tool calls to view"),
+                }
+            }
 
             // TRC-017: Thinking block toggle
             Action::ThinkingToggleCollapse => {
@@ -342,8 +564,30 @@ impl App {
             Action::ThreadLoad(id) => {
                 // TP2-002-09: Load existing thread by ID
                 match self.agent.agent_engine.load_thread(&id) {
-                    Ok(()) => {
+                    Ok(resume) => {
                         self.agent.current_thread_id = Some(id.clone());
+                        // A saved model that can no longer be served resumes on the configured default
+                        let model = match resume.unavailable_model {
+                            Some(ref missing) => {
+                                let defaults = self.config_manager.llm_config().defaults.clone();
+                                self.agent.agent_engine.set_provider(&defaults.provider);
+                                self.agent.agent_engine.set_model(&defaults.model);
+                                if let Some(thread) = self.agent.agent_engine.current_thread_mut() {
+                                    thread.set_model(defaults.model.clone());
+                                }
+                                self.ui.notification_manager.warning_with_message(
+                                    format!("Resumed thread on {}", defaults.model),
+                                    format!("Saved model {} is no longer available", missing),
+                                );
+                                defaults.model
+                            }
+                            None => {
+                                self.ui.notification_manager.info(format!("Resumed thread on {}", resume.model));
+                                resume.model
+                            }
+                        };
+                        self.apply_persona(resume.persona.clone());
+
                         // Clear and repopulate conversation viewer
                         self.agent.conversation_viewer.clear();
 
@@ -367,9 +611,7 @@ impl App {
                                 }
                             }
 
-                            let title = thread.title.clone();
-                            self.ui.notification_manager.info(format!("Loaded thread: {}", title));
-                            tracing::info!("Loaded thread: {} ({})", title, id);
+                            tracing::info!("Loaded thread: {} ({}) on {}", thread.title, id, model);
                         }
                     }
                     Err(e) => {
@@ -415,6 +657,63 @@ impl App {
                 }
             }
 
+            Action::ThreadDelete(id) => {
+                match self.agent.agent_engine.delete_thread(&id) {
+                    Ok(was_current) => {
+                        if was_current {
+                            self.agent.current_thread_id = None;
+                            self.agent.conversation_viewer.clear();
+                        }
+                        self.agent.thread_picker.remove_thread(&id);
+                        if self.agent.thread_picker.is_empty() {
+                            self.agent.thread_picker.hide();
+                            self.ui.input_mode = InputMode::Normal;
+                        }
+                        self.ui.notification_manager.info("Thread deleted");
+                        tracing::info!("Deleted thread {}", id);
+                    }
+                    Err(e) => {
+                        self.ui.notification_manager.error_with_message("Failed to delete thread", e.clone());
+                        tracing::error!("Failed to delete thread {}: {}", id, e);
+                    }
+                }
+            }
+            Action::ThreadPrune => {
+                let days = self.config_manager.app_config().conversation.thread_retention_days;
+                let stale = self.agent.agent_engine.threads_older_than(chrono::Duration::days(days as i64));
+                if stale.is_empty() {
+                    self.ui.notification_manager.info(format!("No threads older than {} days", days));
+                    return Ok(());
+                }
+                let mut lines = vec![format!("Delete {} saved threads not updated in {} days?", stale.len(), days)];
+                lines.extend(stale.iter().take(PRUNE_PREVIEW_TITLES).map(|s| format!("  {}", s.title)));
+                if stale.len() > PRUNE_PREVIEW_TITLES {
+                    lines.push(format!("  …and {} more", stale.len() - PRUNE_PREVIEW_TITLES));
+                }
+                self.ui.action_prompt = Some(ActionPrompt {
+                    title: " Delete Old Threads ".to_string(),
+                    lines,
+                    action: Action::ThreadPruneConfirm,
+                    alternate: None,
+                });
+            }
+            Action::ThreadPruneConfirm => {
+                let days = self.config_manager.app_config().conversation.thread_retention_days;
+                match self.agent.agent_engine.delete_threads_older_than(chrono::Duration::days(days as i64)) {
+                    Ok(0) => {
+                        self.ui.notification_manager.info(format!("No threads older than {} days", days));
+                    }
+                    Ok(count) => {
+                        self.ui.notification_manager.success(format!("Deleted {} threads older than {} days", count, days));
+                        tracing::info!("Pruned {} threads older than {} days", count, days);
+                    }
+                    Err(e) => {
+                        self.ui.notification_manager.error_with_message("Failed to delete old threads", e.clone());
+                        tracing::error!("Thread prune failed: {}", e);
+                    }
+                }
+            }
+
             // P2-003: Thread picker actions
             Action::ThreadPickerShow => {
                 // Get thread summaries from DiskThreadStore
@@ -476,8 +775,132 @@ impl App {
                 self.ui.input_mode = InputMode::Normal;
             }
 
+            Action::ThreadStartTagEdit => {
+                if let Some(thread) = self.agent.agent_engine.current_thread() {
+                    self.agent.thread_tags_buffer = Some(thread.tags.join(" "));
+                    self.ui.input_mode = InputMode::Insert { target: crate::input::mode::InsertTarget::ThreadTags };
+                } else {
+                    self.ui.notification_manager.warning("No active thread to tag");
+                }
+            }
+            Action::ThreadSetTags(input) => {
+                match self.agent.agent_engine.set_thread_tags(&input) {
+                    Ok(()) => {
+                        let tags = self.agent.agent_engine.current_thread()
+                            .map(|t| t.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "))
+                            .unwrap_or_default();
+                        if tags.is_empty() {
+                            self.ui.notification_manager.info("Thread tags cleared");
+                        } else {
+                            self.ui.notification_manager.success(format!("Thread tagged {}", tags));
+                        }
+                    }
+                    Err(e) => {
+                        self.ui.notification_manager.error_with_message("Failed to update tags", e.clone());
+                        tracing::error!("ThreadSetTags: failed - {}", e);
+                    }
+                }
+                self.agent.thread_tags_buffer = None;
+                self.ui.input_mode = InputMode::Normal;
+            }
+
+            Action::SubagentPeek => {
+                self.ui.pager.show("Sub-agents", &self.agent.subagent_monitor.peek_text());
+            }
+            Action::ThreadUsageReport => {
+                match self.thread_usage_report() {
+                    Some(report) => self.ui.pager.show("Usage Report", &report.to_text()),
+                    None => self.ui.notification_manager.warning("No active thread"),
+                }
+            }
+            Action::ThreadUsageExport => {
+                let Some(report) = self.thread_usage_report() else {
+                    self.ui.notification_manager.warning("No active thread");
+                    return Ok(());
+                };
+                match Self::export_usage_report(&report, self.config_manager.config_dir()) {
+                    Ok(path) => {
+                        self.ui.notification_manager.success(format!("Usage report written to {}", path.display()));
+                    }
+                    Err(e) => {
+                        self.ui.notification_manager.error_with_message("Failed to export usage report", e.clone());
+                        tracing::error!("Usage export failed: {}", e);
+                    }
+                }
+            }
+
             _ => unreachable!("non-chat/llm action passed to dispatch_chat_llm: {:?}", action),
         }
         Ok(())
     }
+
+    /// Resolve `name` against prompts.toml and make it the active persona.
+    /// Unknown names fall back to the default prompt with a warning.
+    /// Returns the applied persona name, if any.
+    pub(super) fn apply_persona(&mut self, name: Option<String>) -> Option<String> {
+        let template = name.as_deref().and_then(|n| self.config_manager.prompts_config().find(n));
+        if let (Some(missing), None) = (&name, &template) {
+            self.ui.notification_manager.warning_with_message(
+                format!("Unknown persona '{}'", missing),
+                "Using the default prompt",
+            );
+        }
+        let applied = template.as_ref().map(|t| t.name.clone());
+        self.agent.agent_engine.set_persona(template);
+        self.agent.conversation_viewer.set_persona(applied.clone());
+        applied
+    }
+
+    /// Usage report for the current thread, priced from llm.toml
+    fn thread_usage_report(&self) -> Option<UsageReport> {
+        let thread = self.agent.agent_engine.current_thread()?;
+        Some(UsageReport::from_thread(thread, &self.config_manager.llm_config().pricing, &self.agent.model_catalog))
+    }
+
+    /// Write `<thread>-usage-<time>.csv` and `.json` under the config dir's
+    /// `reports/` directory, returning the CSV path
+    fn export_usage_report(report: &UsageReport, config_dir: &std::path::Path) -> std::result::Result<PathBuf, String> {
+        let dir = config_dir.join("reports");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create reports directory: {}", e))?;
+
+        let stem = format!("{}-usage-{}", report.thread_id, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let csv_path = dir.join(format!("{}.csv", stem));
+        write_atomic(&csv_path, report.to_csv().as_bytes()).map_err(|e| format!("Failed to write CSV: {}", e))?;
+        write_atomic(&dir.join(format!("{}.json", stem)), report.to_json()?.as_bytes())
+            .map_err(|e| format!("Failed to write JSON: {}", e))?;
+        Ok(csv_path)
+    }
+
+    /// Send a chat message through the AgentEngine, creating a thread if needed
+    fn send_llm_message(&mut self, msg: String) {
+        tracing::info!("Sending LLM message: {} chars", msg.len());
+
+        // Ensure conversation is visible when sending a message
+        if !self.agent.show_conversation {
+            self.agent.set_conversation_open(true);
+        }
+        // Follow the new response; a manual scroll-up during it sticks until
+        // the user scrolls back to the bottom or sends again
+        self.agent.conversation_viewer.set_auto_scroll(true);
+        // A new user turn gets a fresh sub-agent allowance
+        if let Some(ref mut subagents) = self.agent.subagent_manager {
+            subagents.begin_turn();
+        }
+        self.agent.subagent_monitor.clear();
+
+        // Route through AgentEngine (always available)
+        // Ensure we have an active thread
+        if self.agent.agent_engine.current_thread().is_none() {
+            let model = self.agent.agent_engine.current_model().to_string();
+            self.agent.agent_engine.new_thread(model);
+            // TP2-002-15: Update current_thread_id when auto-creating thread
+            self.agent.current_thread_id = self.agent.agent_engine.current_thread().map(|t| t.id.clone());
+            tracing::info!("Created new AgentEngine thread: {:?}", self.agent.current_thread_id);
+        }
+
+        // Send message (and any pasted images) through AgentEngine
+        let images = self.agent.chat_input.take_images().into_iter().map(PastedImage::into_content).collect();
+        self.agent.agent_engine.send_message(msg, images);
+        tracing::info!("Message sent through AgentEngine");
+    }
 }
@@ -1,14 +1,18 @@
 // Configuration, settings editor, key storage, and config panel dispatch
 // Domain: Config hot-reload, config panel, settings editor, API key management
 
-use crate::action::Action;
+use crate::action::{Action, OverlayKind};
+use crate::agent::PromptRedaction;
 use crate::components::Component;
-use crate::config::SecretString;
+use crate::config::{KeyStoreBackend, SecretString};
 use crate::error::Result;
-use crate::input::focus::FocusArea;
+use crate::llm::shell_session::shell_escape;
 
 use super::super::App;
 
+/// Used by ConfigEditFile when neither $VISUAL nor $EDITOR is set
+const FALLBACK_EDITOR: &str = "vi";
+
 impl App {
     pub(super) fn dispatch_config_settings(&mut self, action: Action) -> Result<()> {
         match action {
@@ -22,11 +26,40 @@ impl App {
                     self.reload_streams_from_config();
                 }
 
+                if path.file_name().and_then(|n| n.to_str()) == Some("config.toml") {
+                    let app_config = self.config_manager.app_config();
+                    let redaction = PromptRedaction::from(&app_config.prompt);
+                    let compact_tools = app_config.tools.compact_descriptions;
+                    self.agent.agent_engine.set_prompt_redaction(redaction);
+                    self.agent.agent_engine.set_compact_tool_descriptions(compact_tools);
+                    self.agent.chat_input.set_send_key(app_config.ui.chat_send_key, self.pty.keyboard_enhanced);
+                    self.agent.chat_input.set_emacs_keys(app_config.ui.chat_emacs_keys);
+                    self.pty.tab_manager.set_pty_read_buf(app_config.terminal.pty_read_buf);
+                    self.apply_logs_config();
+                    self.apply_spinner_config();
+                    self.apply_scroll_config();
+                    self.apply_overlay_config();
+                }
+
+                // Re-resolve the active persona so edits to its template take effect
+                if path.file_name().and_then(|n| n.to_str()) == Some("prompts.toml") {
+                    let current = self.agent.agent_engine.persona().map(|p| p.name.clone());
+                    self.apply_persona(current);
+                }
+
                 // Re-apply LLM settings when llm.toml changes (fixes model not updating after hot-reload)
                 if path.file_name().and_then(|n| n.to_str()) == Some("llm.toml") {
                     let llm_config = self.config_manager.llm_config();
                     self.agent.agent_engine.set_provider(&llm_config.defaults.provider);
                     self.agent.agent_engine.set_model(&llm_config.defaults.model);
+                    self.agent.agent_engine.set_fallback_models(llm_config.fallback_models.clone());
+                    self.agent.agent_engine.set_fallback_providers(llm_config.fallback_provider_models());
+                    self.agent.agent_engine.set_max_retries(llm_config.max_retries);
+                    self.agent.agent_engine.set_debug_capture(llm_config.debug_capture);
+                    self.agent.agent_engine.set_temperature(Some(llm_config.parameters.temperature));
+                    self.agent.agent_engine.set_max_tokens(Some(llm_config.parameters.max_tokens));
+                    self.agent.agent_engine.set_thinking(llm_config.thinking.enabled, llm_config.thinking.budget_tokens);
+                    self.agent.agent_engine.set_prompt_cache(llm_config.prompt_cache);
                     tracing::info!(
                         "Re-applied LLM settings after hot-reload: provider={}, model={}",
                         llm_config.defaults.provider,
@@ -41,6 +74,27 @@ impl App {
             Action::ConfigApplyTheme => {
                 tracing::debug!("Theme changes applied");
             }
+            Action::ConfigOpenDir => {
+                let dir = self.config_manager.config_dir().to_string_lossy().into_owned();
+                self.run_in_new_tab("Config", &format!("cd {}", shell_escape(&dir)));
+            }
+            Action::ConfigEditFile => {
+                // Saving triggers the config watcher, which hot-reloads as usual
+                let path = self.config_manager.main_config_path().to_string_lossy().into_owned();
+                let editor = std::env::var("VISUAL")
+                    .or_else(|_| std::env::var("EDITOR"))
+                    .ok()
+                    .filter(|e| !e.trim().is_empty());
+                if editor.is_none() {
+                    self.ui.notification_manager.info_with_message(
+                        "No $EDITOR Set",
+                        format!("Opening config.toml with {}; set $EDITOR to choose another editor", FALLBACK_EDITOR),
+                    );
+                }
+                // $EDITOR may carry flags (e.g. "code -w"), so it is passed through unquoted
+                let editor = editor.as_deref().unwrap_or(FALLBACK_EDITOR);
+                self.run_in_new_tab("config.toml", &format!("{} {}", editor, shell_escape(&path)));
+            }
 
             // Config panel actions (TRC-014)
             Action::ConfigPanelShow => {
@@ -52,17 +106,14 @@ impl App {
                     self.config_manager.theme(),
                     &providers,
                 );
-                self.show_config_panel = true;
-                self.ui.focus.focus(FocusArea::ConfigPanel);
+                self.open_overlay(OverlayKind::ConfigPanel);
             }
             Action::ConfigPanelHide => {
-                self.show_config_panel = false;
-                self.ui.focus.focus(FocusArea::Menu);
+                self.close_overlay(OverlayKind::ConfigPanel);
             }
             Action::ConfigPanelToggle => {
                 if self.show_config_panel {
-                    self.show_config_panel = false;
-                    self.ui.focus.focus(FocusArea::Menu);
+                    self.close_overlay(OverlayKind::ConfigPanel);
                 } else {
                     let providers = self.agent.agent_engine.registered_providers();
                     self.config_panel.refresh(
@@ -71,8 +122,7 @@ impl App {
                         self.config_manager.theme(),
                         &providers,
                     );
-                    self.show_config_panel = true;
-                    self.ui.focus.focus(FocusArea::ConfigPanel);
+                    self.open_overlay(OverlayKind::ConfigPanel);
                 }
             }
             Action::ConfigPanelScrollUp(n) => {
@@ -145,6 +195,9 @@ impl App {
                 // Store the key in keystore and update SettingsEditor
                 self.handle_settings_key_entered(provider.clone(), key.clone());
             }
+            Action::SettingsBaseUrlEntered { ref provider, ref url } => {
+                self.handle_settings_base_url_entered(provider, url);
+            }
             Action::SettingsProviderChanged(ref provider) => {
                 // Update AgentEngine with new provider
                 self.agent.agent_engine.set_provider(provider);
@@ -165,15 +218,37 @@ impl App {
             }
             Action::SettingsTestKeyResult { ref provider, success, ref error } => {
                 self.settings_editor.set_key_test_result(provider, success, error.clone());
+                if success {
+                    self.ui.notification_manager.success(format!("{} key verified", provider));
+                } else {
+                    self.ui.notification_manager.error_with_message(
+                        format!("{} key test failed", provider),
+                        error.clone().unwrap_or_default(),
+                    );
+                }
             }
             Action::SettingsTemperatureChanged(temp) => {
                 // Update config with new temperature
+                self.agent.agent_engine.set_temperature(Some(temp));
                 self.config_manager.llm_config_mut().parameters.temperature = temp;
             }
             Action::SettingsMaxTokensChanged(tokens) => {
                 // Update config with new max tokens
+                self.agent.agent_engine.set_max_tokens(Some(tokens));
                 self.config_manager.llm_config_mut().parameters.max_tokens = tokens;
             }
+            Action::SettingsThinkingToggled(enabled) => {
+                let thinking = &mut self.config_manager.llm_config_mut().thinking;
+                thinking.enabled = enabled;
+                let budget = thinking.budget_tokens;
+                self.agent.agent_engine.set_thinking(enabled, budget);
+            }
+            Action::SettingsThinkingBudgetChanged(budget) => {
+                let thinking = &mut self.config_manager.llm_config_mut().thinking;
+                thinking.budget_tokens = budget;
+                let enabled = thinking.enabled;
+                self.agent.agent_engine.set_thinking(enabled, budget);
+            }
             Action::SettingsSave => {
                 self.handle_settings_save();
             }
@@ -205,7 +280,7 @@ impl App {
                 }
             }
             Action::KeyGet(_key_id) => {
-                // Key retrieval is handled internally by register_from_keystore
+                // Key retrieval is handled internally by register_from_keystore_with_config
                 // This action exists for programmatic access if needed
             }
             Action::KeyDelete(key_id) => {
@@ -232,11 +307,20 @@ impl App {
                     match ks.unlock(&password) {
                         Ok(()) => {
                             tracing::info!("Keystore unlocked");
-                            // Re-register providers after unlock
-                            let registered = self.agent.agent_engine.llm_manager_mut().register_from_keystore(ks);
+                            // Register providers whose keys were locked away; keys from the
+                            // command line or environment stay in place
+                            let registered = self.agent.agent_engine.llm_manager_mut().register_unlocked_keys(ks);
                             if !registered.is_empty() {
                                 tracing::info!("Loaded API keys for providers: {:?}", registered);
                             }
+                            // Thread saves were refused while the key was unavailable
+                            let store = self.agent.agent_engine.thread_store();
+                            if store.awaiting_key() && ks.backend() != KeyStoreBackend::Plaintext {
+                                match ks.threads_key() {
+                                    Ok(key) => store.set_encryption_key(key),
+                                    Err(e) => tracing::error!("Failed to load thread encryption key: {}", e),
+                                }
+                            }
                         }
                         Err(e) => tracing::error!("Failed to unlock keystore: {}", e),
                     }
@@ -30,6 +30,25 @@ impl App {
                 self.agent.conversation_viewer.tick_spinner();
                 // Tick notifications to expire old ones (TRC-023)
                 self.ui.notification_manager.tick();
+                // Advance relative timestamps in the log and activity viewers
+                self.log_viewer.tick();
+                if let Some(ref mut stream) = self.activity_stream {
+                    stream.tick();
+                }
+                // Unattended tool confirmations resolve to their configured default
+                if self.ui.confirm_dialog.countdown_active() {
+                    self.mark_dirty();
+                }
+                if let Some(answer) = self.ui.confirm_dialog.tick() {
+                    self.dispatch(answer)?;
+                }
+                // Unanswered ask_user questions fall back to their defaults
+                if self.ui.ask_user_dialog.countdown_active() {
+                    self.mark_dirty();
+                }
+                if let Some(answer) = self.ui.ask_user_dialog.tick() {
+                    self.dispatch(answer)?;
+                }
             }
             Action::EnterPtyMode => {
                 self.ui.input_mode = InputMode::PtyRaw;
@@ -57,6 +76,10 @@ impl App {
                 let current_model = self.agent.agent_engine.current_model();
                 self.ui.command_palette.set_models(&models, current_model);
 
+                let personas = self.config_manager.prompts_config().all();
+                let current_persona = self.agent.agent_engine.persona().map(|p| p.name.clone());
+                self.ui.command_palette.set_personas(&personas, current_persona.as_deref());
+
                 // Populate subagent model commands (T2.1b)
                 self.refresh_subagent_commands();
 
@@ -67,6 +90,8 @@ impl App {
                 self.ui.command_palette.hide();
                 self.ui.input_mode = InputMode::Normal;
             }
+            // With several overlays open, Tab cycles among them
+            Action::FocusNext | Action::FocusPrev if self.cycle_overlay_focus(matches!(action, Action::FocusNext)) => {}
             Action::FocusNext => {
                 let skip_chat = !self.agent.show_conversation;
                 self.ui.focus.next_skip_chat(skip_chat);
@@ -6,7 +6,10 @@ use ratatui::layout::Rect;
 
 use crate::action::{Action, ContextMenuTarget, PaneBorder};
 use crate::components::pane_layout::ResizableBorder;
+use crate::components::scroll::{half_page_step, page_step, Following};
+use crate::components::search::HORIZONTAL_SCROLL_STEP;
 use crate::components::Component;
+use crate::config::SequenceStep;
 use crate::input::focus::FocusArea;
 use crate::input::mode::InputMode;
 use crate::tabs::TabBar;
@@ -19,6 +22,14 @@ impl App {
             CrosstermEvent::Key(key) => self.handle_key(key),
             CrosstermEvent::Mouse(mouse) => self.handle_mouse(mouse),
             CrosstermEvent::Paste(text) => self.handle_paste(text),
+            CrosstermEvent::FocusGained => {
+                self.ui.terminal_focused = Some(true);
+                None
+            }
+            CrosstermEvent::FocusLost => {
+                self.ui.terminal_focused = Some(false);
+                None
+            }
             CrosstermEvent::Resize(cols, rows) => {
                 let (term_cols, term_rows) = crate::app::pty_state::PtyState::calculate_terminal_size(Rect::new(0, 0, cols, rows));
                 Some(Action::PtyResize {
@@ -26,7 +37,6 @@ impl App {
                     rows: term_rows,
                 })
             }
-            _ => None,
         }
     }
 
@@ -64,6 +74,28 @@ impl App {
             return self.ui.ask_user_dialog.handle_event(&CrosstermEvent::Key(key));
         }
 
+        if let Some(prompt) = self.ui.action_prompt.take() {
+            match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => return Some(prompt.action),
+                KeyCode::Char('n') | KeyCode::Esc => {}
+                KeyCode::Char(c) if prompt.alternate.as_ref().is_some_and(|(key, _, _)| *key == c) => {
+                    return prompt.alternate.map(|(_, _, action)| action);
+                }
+                _ => self.ui.action_prompt = Some(prompt),
+            }
+            return None;
+        }
+
+        // Pager overlay captures all keys while open
+        if self.ui.pager.is_visible() {
+            return self.ui.pager.handle_event(&CrosstermEvent::Key(key));
+        }
+
+        // Model switcher captures all keys while open
+        if self.ui.model_picker.is_visible() {
+            return self.ui.model_picker.handle_event(&CrosstermEvent::Key(key));
+        }
+
         // Command palette and confirm dialog take priority over overlay panels
         match &self.ui.input_mode {
             InputMode::Confirm { .. } => {
@@ -100,7 +132,27 @@ impl App {
 
         match &self.ui.input_mode {
             InputMode::PtyRaw => {
-                // First check configurable keybindings
+                // The escape hatch works whatever the inner program does with keys
+                let app_cursor = self.pty.tab_manager
+                    .active_pty_session()
+                    .map(|s| s.terminal().application_cursor_keys())
+                    .unwrap_or(false);
+                let sequence = self.config_manager.keybindings().pty_escape_sequence();
+                match self.ui.pty_escape.feed(&sequence, &key) {
+                    SequenceStep::Complete => return Some(Action::EnterNormalMode),
+                    SequenceStep::Pending => return None,
+                    // A broken-off sequence sends the held keys on, followed by this one
+                    SequenceStep::Unmatched(held) if !held.is_empty() => {
+                        let bytes: Vec<u8> = held.into_iter()
+                            .chain(std::iter::once(key))
+                            .flat_map(|k| key_to_bytes(k, app_cursor))
+                            .collect();
+                        return Some(Action::PtyInput(bytes));
+                    }
+                    SequenceStep::Unmatched(_) => {}
+                }
+
+                // Then check configurable keybindings
                 if let Some(action) = self.config_manager.keybindings().get_action(&self.ui.input_mode, &key) {
                     return Some(action);
                 }
@@ -137,7 +189,7 @@ impl App {
                 }
 
                 // Pass through to PTY
-                let bytes = key_to_bytes(key);
+                let bytes = key_to_bytes(key, app_cursor);
                 if !bytes.is_empty() {
                     return Some(Action::PtyInput(bytes));
                 }
@@ -163,11 +215,19 @@ impl App {
                     FocusArea::StreamViewer => {
                         // Handle StreamViewer key events (also used for conversation history)
                         // 'i' focuses chat input when conversation is visible
-                        if self.agent.show_conversation && (key.code == KeyCode::Char('i') || key.code == KeyCode::Tab) {
+                        // (Tab falls through to overlay cycling when several overlays are tiled)
+                        let tab_cycles_overlays = key.code == KeyCode::Tab && self.ui.overlay_layout.is_tiled();
+                        if self.agent.show_conversation && !tab_cycles_overlays
+                            && (key.code == KeyCode::Char('i') || key.code == KeyCode::Tab)
+                        {
                             self.ui.focus.focus(FocusArea::ChatInput);
                             return None;
                         }
 
+                        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                        let conv_height = self.agent.conversation_viewer.visible_height();
+                        let stream_height = self.stream_viewer.visible_height();
+
                         // When conversation is visible, route scroll keys to conversation viewer
                         if self.agent.show_conversation {
                             match key.code {
@@ -175,12 +235,15 @@ impl App {
                                 KeyCode::Char('k') | KeyCode::Up => Some(Action::ConversationScrollUp(1)),
                                 KeyCode::Char('g') => Some(Action::ConversationScrollToTop),
                                 KeyCode::Char('G') => Some(Action::ConversationScrollToBottom),
-                                KeyCode::PageUp => Some(Action::ConversationScrollUp(10)),
-                                KeyCode::PageDown => Some(Action::ConversationScrollDown(10)),
-                                KeyCode::Char('a') => {
+                                KeyCode::PageUp => Some(Action::ConversationScrollUp(page_step(conv_height))),
+                                KeyCode::PageDown => Some(Action::ConversationScrollDown(page_step(conv_height))),
+                                KeyCode::Char('u') if ctrl => Some(Action::ConversationScrollUp(half_page_step(conv_height))),
+                                KeyCode::Char('d') if ctrl => Some(Action::ConversationScrollDown(half_page_step(conv_height))),
+                                KeyCode::Char('f') | KeyCode::Char('a') => {
                                     self.agent.conversation_viewer.toggle_auto_scroll();
                                     None
                                 }
+                                KeyCode::Char('o') => Some(Action::ToolCallViewOutput),
                                 KeyCode::Esc | KeyCode::Char('q') => Some(Action::StreamViewerHide),
                                 _ => None,
                             }
@@ -190,8 +253,14 @@ impl App {
                                 KeyCode::Char('k') | KeyCode::Up => Some(Action::StreamViewerScrollUp(1)),
                                 KeyCode::Char('g') => Some(Action::StreamViewerScrollToTop),
                                 KeyCode::Char('G') => Some(Action::StreamViewerScrollToBottom),
-                                KeyCode::PageUp => Some(Action::StreamViewerScrollUp(10)),
-                                KeyCode::PageDown => Some(Action::StreamViewerScrollDown(10)),
+                                KeyCode::PageUp => Some(Action::StreamViewerScrollUp(page_step(stream_height))),
+                                KeyCode::PageDown => Some(Action::StreamViewerScrollDown(page_step(stream_height))),
+                                KeyCode::Char('u') if ctrl => Some(Action::StreamViewerScrollUp(half_page_step(stream_height))),
+                                KeyCode::Char('d') if ctrl => Some(Action::StreamViewerScrollDown(half_page_step(stream_height))),
+                                KeyCode::Char('f') => Some(Action::StreamViewerToggleAutoScroll),
+                                KeyCode::Char('w') => Some(Action::StreamViewerToggleWrap),
+                                KeyCode::Char('h') | KeyCode::Left => Some(Action::StreamViewerScrollLeft(HORIZONTAL_SCROLL_STEP)),
+                                KeyCode::Char('l') | KeyCode::Right => Some(Action::StreamViewerScrollRight(HORIZONTAL_SCROLL_STEP)),
                                 KeyCode::Esc | KeyCode::Char('q') => Some(Action::StreamViewerHide),
                                 _ => None,
                             }
@@ -210,19 +279,38 @@ impl App {
                             KeyCode::Char('G') => Some(Action::LogViewerScrollToBottom),
                             KeyCode::PageUp => Some(Action::LogViewerScrollPageUp),
                             KeyCode::PageDown => Some(Action::LogViewerScrollPageDown),
-                            KeyCode::Char('a') => Some(Action::LogViewerToggleAutoScroll),
+                            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                Some(Action::LogViewerScrollUp(half_page_step(self.log_viewer.visible_height())))
+                            }
+                            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                Some(Action::LogViewerScrollDown(half_page_step(self.log_viewer.visible_height())))
+                            }
+                            KeyCode::Char('f') | KeyCode::Char('a') => Some(Action::LogViewerToggleAutoScroll),
+                            KeyCode::Char('w') => Some(Action::LogViewerToggleWrap),
+                            KeyCode::Char('t') => Some(Action::LogViewerToggleTimestamps),
+                            KeyCode::Char('h') | KeyCode::Left => Some(Action::LogViewerScrollLeft(HORIZONTAL_SCROLL_STEP)),
+                            KeyCode::Char('l') | KeyCode::Right => Some(Action::LogViewerScrollRight(HORIZONTAL_SCROLL_STEP)),
                             KeyCode::Char('c') => Some(Action::LogViewerClear),
+                            KeyCode::Char('o') => Some(Action::LogViewerOpenPager),
                             KeyCode::Esc | KeyCode::Char('q') => Some(Action::LogViewerHide),
                             _ => None,
                         }
                     }
                     FocusArea::ChatInput => {
                         // Handle ChatInput key events - delegate to component
-                        // Escape returns focus to conversation viewer
-                        if key.code == KeyCode::Esc {
+                        // Escape returns focus to conversation viewer (or cancels a history search)
+                        if key.code == KeyCode::Esc && !self.agent.chat_input.is_searching() {
                             self.ui.focus.focus(FocusArea::StreamViewer);
                             return None;
                         }
+                        // Enter on an empty input resumes a loop waiting after tool results
+                        if key.code == KeyCode::Enter
+                            && key.modifiers.is_empty()
+                            && self.agent.chat_input.is_empty()
+                            && self.agent.agent_engine.state() == crate::agent::AgentState::Paused
+                        {
+                            return Some(Action::LlmResume);
+                        }
                         self.agent.chat_input.handle_event(&CrosstermEvent::Key(key))
                     }
                     FocusArea::SettingsEditor => {
@@ -296,6 +384,37 @@ impl App {
                     }
                 }
 
+                // Inline thread tag editing
+                if matches!(target, crate::input::mode::InsertTarget::ThreadTags) {
+                    match key.code {
+                        KeyCode::Esc => {
+                            self.agent.thread_tags_buffer = None;
+                            self.ui.input_mode = InputMode::Normal;
+                            return None;
+                        }
+                        KeyCode::Enter => {
+                            if let Some(tags) = self.agent.thread_tags_buffer.take() {
+                                return Some(Action::ThreadSetTags(tags));
+                            }
+                            self.ui.input_mode = InputMode::Normal;
+                            return None;
+                        }
+                        KeyCode::Backspace => {
+                            if let Some(ref mut buffer) = self.agent.thread_tags_buffer {
+                                buffer.pop();
+                            }
+                            return None;
+                        }
+                        KeyCode::Char(c) => {
+                            if let Some(ref mut buffer) = self.agent.thread_tags_buffer {
+                                buffer.push(c);
+                            }
+                            return None;
+                        }
+                        _ => {}
+                    }
+                }
+
                 // Fall back to configurable keybindings for other insert targets
                 if let Some(action) = self.config_manager.keybindings().get_action(&self.ui.input_mode, &key) {
                     return Some(action);
@@ -320,6 +439,11 @@ impl App {
         }
     }
 
+    /// Lines per mouse-wheel notch (`ui.scroll_lines`)
+    fn wheel_lines(&self) -> u16 {
+        self.config_manager.app_config().ui.scroll_lines.max(1)
+    }
+
     pub(super) fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
         // DEBUG: Log scroll events to trace the issue
         if matches!(mouse.kind, MouseEventKind::ScrollUp | MouseEventKind::ScrollDown) {
@@ -329,6 +453,11 @@ impl App {
             );
         }
 
+        // Pager overlay consumes mouse events (scrolling) while open
+        if self.ui.pager.is_visible() {
+            return self.ui.pager.handle_event(&CrosstermEvent::Mouse(mouse));
+        }
+
         // TRC-020: If context menu is visible, route all mouse events to it first
         if self.ui.context_menu.is_visible() {
             if let Some(action) = self.ui.context_menu.handle_event(&CrosstermEvent::Mouse(mouse)) {
@@ -344,6 +473,23 @@ impl App {
             return self.handle_right_click(mouse.column, mouse.row);
         }
 
+        // In compact mode the focused process monitor or menu floats over the other
+        // panes, so it gets every event inside it (unless a drag started elsewhere)
+        let focus = self.ui.focus.current();
+        if self.ui.pane_layout.compact
+            && matches!(focus, FocusArea::ProcessMonitor | FocusArea::Menu)
+            && !self.ui.drag_state.is_dragging()
+            && !self.agent.conversation_viewer.is_selecting()
+            && !self.agent.chat_input.is_selecting()
+            && self.ui.pane_layout.compact_overlay_area(self.ui.content_area).contains((mouse.column, mouse.row).into())
+        {
+            let event = CrosstermEvent::Mouse(mouse);
+            return match focus {
+                FocusArea::ProcessMonitor => self.process_monitor.handle_event(&event),
+                _ => self.ui.menu.handle_event(&event),
+            };
+        }
+
         // TRC-010: Check for clicks on the tab bar first (before focus-based routing)
         if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
             // Check if click is within tab bar area
@@ -366,8 +512,15 @@ impl App {
                 return None;
             }
 
+            // Overlay borders sit above the panes (tiled overlays have fixed columns)
+            if let Some(kind) = self.topmost_overlay().filter(|_| !self.ui.overlay_layout.is_tiled()) {
+                if self.ui.overlay_layout.get(kind).hit_test_border(mouse.column, mouse.row, self.ui.content_area) {
+                    return Some(Action::OverlayStartDrag(kind));
+                }
+            }
+
             // TRC-024: Check for clicks on pane borders for resize
-            let show_conv = self.agent.show_conversation || !self.agent.llm_response_buffer.is_empty() || !self.agent.thinking_buffer.is_empty();
+            let show_conv = self.agent.conversation_visible(self.config_manager.app_config().conversation.auto_open);
             if let Some(border) = self.ui.pane_layout.hit_test_border(mouse.column, mouse.row, self.ui.content_area, show_conv) {
                 let pan_border = match border {
                     ResizableBorder::MainVertical => PaneBorder::MainVertical,
@@ -484,57 +637,57 @@ impl App {
 
         // In PtyRaw mode, scroll always goes to PTY regardless of focus
         // (user is interacting with nested TUI, scroll should go there)
-        if self.ui.input_mode == InputMode::PtyRaw {
-            if matches!(mouse.kind, MouseEventKind::ScrollUp | MouseEventKind::ScrollDown) {
-                let mouse_mode = self.pty.tab_manager.active_mouse_mode();
-                
-                // Calculate coordinates relative to terminal pane (1-based for SGR)
-                let term_area = self.ui.terminal_area;
-                let rel_x = mouse.column.saturating_sub(term_area.x).saturating_add(1);
-                let rel_y = mouse.row.saturating_sub(term_area.y).saturating_add(1);
+        if self.ui.input_mode == InputMode::PtyRaw
+            && matches!(mouse.kind, MouseEventKind::ScrollUp | MouseEventKind::ScrollDown)
+        {
+            let mouse_mode = self.pty.tab_manager.active_mouse_mode();
+            
+            // Calculate coordinates relative to terminal pane (1-based for SGR)
+            let term_area = self.ui.terminal_area;
+            let rel_x = mouse.column.saturating_sub(term_area.x).saturating_add(1);
+            let rel_y = mouse.row.saturating_sub(term_area.y).saturating_add(1);
+            
+            tracing::debug!(
+                "PTYRAW SCROLL: mouse_mode={:?}, sgr={}, any_enabled={}",
+                mouse_mode, mouse_mode.sgr_ext, mouse_mode.any_enabled()
+            );
+            
+            if mouse_mode.any_enabled() {
+                // Nested app has mouse tracking enabled - send SGR mouse wheel sequences
+                // SGR format: CSI < button ; x ; y M  (button: 64=wheel up, 65=wheel down)
+                let button = match mouse.kind {
+                    MouseEventKind::ScrollUp => 64,
+                    MouseEventKind::ScrollDown => 65,
+                    _ => return None,
+                };
                 
-                tracing::debug!(
-                    "PTYRAW SCROLL: mouse_mode={:?}, sgr={}, any_enabled={}",
-                    mouse_mode, mouse_mode.sgr_ext, mouse_mode.any_enabled()
-                );
+                // SGR encoding: \x1b[<{button};{x};{y}M
+                let seq = format!("\x1b[<{};{};{}M", button, rel_x, rel_y);
+                tracing::debug!("PTYRAW SGR: sending {:?}", seq);
+                return Some(Action::PtyInput(seq.into_bytes()));
+            } else {
+                // Nested app doesn't have mouse tracking - check if in alternate screen
+                let in_alt_screen = self.pty.tab_manager.is_active_alternate_screen();
                 
-                if mouse_mode.any_enabled() {
-                    // Nested app has mouse tracking enabled - send SGR mouse wheel sequences
-                    // SGR format: CSI < button ; x ; y M  (button: 64=wheel up, 65=wheel down)
-                    let button = match mouse.kind {
-                        MouseEventKind::ScrollUp => 64,
-                        MouseEventKind::ScrollDown => 65,
+                if in_alt_screen {
+                    // In alternate screen (TUI app like CC, vim, less) - send application cursor keys
+                    // This is what Windows Terminal does with alternate scroll mode (DECSET 1007)
+                    // Application cursor keys: ESC O A (up), ESC O B (down)
+                    let arrow_seq = match mouse.kind {
+                        MouseEventKind::ScrollUp => b"\x1bOA",   // Application mode Up
+                        MouseEventKind::ScrollDown => b"\x1bOB", // Application mode Down
                         _ => return None,
                     };
-                    
-                    // SGR encoding: \x1b[<{button};{x};{y}M
-                    let seq = format!("\x1b[<{};{};{}M", button, rel_x, rel_y);
-                    tracing::debug!("PTYRAW SGR: sending {:?}", seq);
-                    return Some(Action::PtyInput(seq.into_bytes()));
+                    tracing::debug!("PTYRAW ALT-SCREEN: sending application cursor key {:?}", arrow_seq);
+                    return Some(Action::PtyInput(arrow_seq.to_vec()));
                 } else {
-                    // Nested app doesn't have mouse tracking - check if in alternate screen
-                    let in_alt_screen = self.pty.tab_manager.is_active_alternate_screen();
-                    
-                    if in_alt_screen {
-                        // In alternate screen (TUI app like CC, vim, less) - send application cursor keys
-                        // This is what Windows Terminal does with alternate scroll mode (DECSET 1007)
-                        // Application cursor keys: ESC O A (up), ESC O B (down)
-                        let arrow_seq = match mouse.kind {
-                            MouseEventKind::ScrollUp => b"\x1bOA",   // Application mode Up
-                            MouseEventKind::ScrollDown => b"\x1bOB", // Application mode Down
-                            _ => return None,
-                        };
-                        tracing::debug!("PTYRAW ALT-SCREEN: sending application cursor key {:?}", arrow_seq);
-                        return Some(Action::PtyInput(arrow_seq.to_vec()));
-                    } else {
-                        // Not in alternate screen - scroll our terminal buffer
-                        tracing::debug!("PTYRAW NORMAL: scrolling terminal buffer");
-                        return match mouse.kind {
-                            MouseEventKind::ScrollUp => Some(Action::ScrollUp(3)),
-                            MouseEventKind::ScrollDown => Some(Action::ScrollDown(3)),
-                            _ => None,
-                        };
-                    }
+                    // Not in alternate screen - scroll our terminal buffer
+                    tracing::debug!("PTYRAW NORMAL: scrolling terminal buffer");
+                    return match mouse.kind {
+                        MouseEventKind::ScrollUp => Some(Action::ScrollUp(self.wheel_lines())),
+                        MouseEventKind::ScrollDown => Some(Action::ScrollDown(self.wheel_lines())),
+                        _ => None,
+                    };
                 }
             }
         }
@@ -564,8 +717,8 @@ impl App {
                 }
                 // Scroll in Normal mode scrolls the terminal scrollback
                 // (PtyRaw scroll is handled earlier, before focus-based routing)
-                MouseEventKind::ScrollUp => Some(Action::ScrollUp(3)),
-                MouseEventKind::ScrollDown => Some(Action::ScrollDown(3)),
+                MouseEventKind::ScrollUp => Some(Action::ScrollUp(self.wheel_lines())),
+                MouseEventKind::ScrollDown => Some(Action::ScrollDown(self.wheel_lines())),
                 _ => None,
             },
             FocusArea::ProcessMonitor => {
@@ -578,16 +731,23 @@ impl App {
             // Overlay areas
             FocusArea::StreamViewer => {
                 // Handle mouse scroll for conversation/stream viewer
+                // A small wheel nudge while following is absorbed (`ui.scroll_lock_threshold`)
+                let lines = self.wheel_lines();
                 if self.agent.show_conversation {
                     match mouse.kind {
-                        MouseEventKind::ScrollUp => Some(Action::ConversationScrollUp(3)),
-                        MouseEventKind::ScrollDown => Some(Action::ConversationScrollDown(3)),
+                        MouseEventKind::ScrollUp => self.agent.conversation_viewer.follow_mut().wheel_up(lines)
+                            .then_some(Action::ConversationScrollUp(lines)),
+                        MouseEventKind::ScrollDown => Some(Action::ConversationScrollDown(lines)),
                         _ => None,
                     }
                 } else {
                     match mouse.kind {
-                        MouseEventKind::ScrollUp => Some(Action::StreamViewerScrollUp(3)),
-                        MouseEventKind::ScrollDown => Some(Action::StreamViewerScrollDown(3)),
+                        MouseEventKind::ScrollUp => self.stream_viewer.follow_mut().wheel_up(lines)
+                            .then_some(Action::StreamViewerScrollUp(lines)),
+                        MouseEventKind::ScrollDown => Some(Action::StreamViewerScrollDown(lines)),
+                        MouseEventKind::Down(MouseButton::Left) if self.stream_viewer.follow().hint_hit(mouse.column, mouse.row) => {
+                            Some(Action::StreamViewerScrollToBottom)
+                        }
                         _ => None,
                     }
                 }
@@ -598,9 +758,14 @@ impl App {
             }
             FocusArea::LogViewer => {
                 // Handle LogViewer mouse events (TRC-013)
+                let lines = self.wheel_lines();
                 match mouse.kind {
-                    MouseEventKind::ScrollUp => Some(Action::LogViewerScrollUp(3)),
-                    MouseEventKind::ScrollDown => Some(Action::LogViewerScrollDown(3)),
+                    MouseEventKind::ScrollUp => self.log_viewer.follow_mut().wheel_up(lines)
+                        .then_some(Action::LogViewerScrollUp(lines)),
+                    MouseEventKind::ScrollDown => Some(Action::LogViewerScrollDown(lines)),
+                    MouseEventKind::Down(MouseButton::Left) if self.log_viewer.follow().hint_hit(mouse.column, mouse.row) => {
+                        Some(Action::LogViewerScrollToBottom)
+                    }
                     MouseEventKind::Down(MouseButton::Left) => {
                         // Click on title bar area toggles auto-scroll
                         Some(Action::LogViewerToggleAutoScroll)
@@ -655,9 +820,22 @@ impl App {
         let right_top_height = content_height / 2;
         let right_bottom_y = content_y + right_top_height;
 
+        // Right pane under the click; in compact mode only the focused one, as an overlay
+        let right_pane = if self.ui.pane_layout.compact {
+            let focused = self.ui.focus.current();
+            let overlay = self.ui.pane_layout.compact_overlay_area(self.ui.content_area);
+            Some(focused).filter(|f| {
+                matches!(f, FocusArea::ProcessMonitor | FocusArea::Menu) && overlay.contains((x, y).into())
+            })
+        } else if x >= left_width {
+            Some(if y < right_bottom_y { FocusArea::ProcessMonitor } else { FocusArea::Menu })
+        } else {
+            None
+        };
+
         // Determine which area was clicked
          // Check chat input area first (it's on the left side, need to check before Terminal)
-         if self.ui.chat_input_area.height > 0 && self.ui.chat_input_area.contains((x, y).into()) {
+         if right_pane.is_none() && self.ui.chat_input_area.height > 0 && self.ui.chat_input_area.contains((x, y).into()) {
             // Focus ChatInput when right-clicking on it
             self.ui.focus.focus(FocusArea::ChatInput);
             Some(Action::ContextMenuShow {
@@ -665,7 +843,7 @@ impl App {
                 y,
                 target: ContextMenuTarget::ChatInput,
             })
-        } else if self.ui.conversation_area.height > 0 && self.ui.conversation_area.contains((x, y).into()) {
+        } else if right_pane.is_none() && self.ui.conversation_area.height > 0 && self.ui.conversation_area.contains((x, y).into()) {
             // Conversation viewer area
             self.ui.focus.focus(FocusArea::StreamViewer);
             Some(Action::ContextMenuShow {
@@ -673,14 +851,14 @@ impl App {
                 y,
                 target: ContextMenuTarget::Conversation,
             })
-        } else if x < left_width {
+        } else if right_pane.is_none() {
             // Terminal area
             Some(Action::ContextMenuShow {
                 x,
                 y,
                 target: ContextMenuTarget::Terminal,
             })
-        } else if y < right_bottom_y {
+        } else if right_pane == Some(FocusArea::ProcessMonitor) {
             // Process monitor area
             // Try to find which process was clicked - pass raw screen Y
             let selected_pid = self.process_monitor.get_pid_at_screen_y(y);
@@ -710,9 +888,11 @@ impl App {
     }
 }
 
-/// Convert a key event to bytes for PTY input
-pub(super) fn key_to_bytes(key: KeyEvent) -> Vec<u8> {
+/// Convert a key event to bytes for PTY input.
+/// `app_cursor` selects DECCKM application mode (`ESC O A`) for arrows, Home and End.
+pub(super) fn key_to_bytes(key: KeyEvent, app_cursor: bool) -> Vec<u8> {
     let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let cursor_key = |c: u8| vec![0x1b, if app_cursor { b'O' } else { b'[' }, c];
 
     match key.code {
         KeyCode::Char(c) => {
@@ -726,12 +906,12 @@ pub(super) fn key_to_bytes(key: KeyEvent) -> Vec<u8> {
         KeyCode::Backspace => vec![0x7f],
         KeyCode::Tab => vec![b'\t'],
         KeyCode::Esc => vec![0x1b],
-        KeyCode::Up => vec![0x1b, b'[', b'A'],
-        KeyCode::Down => vec![0x1b, b'[', b'B'],
-        KeyCode::Right => vec![0x1b, b'[', b'C'],
-        KeyCode::Left => vec![0x1b, b'[', b'D'],
-        KeyCode::Home => vec![0x1b, b'[', b'H'],
-        KeyCode::End => vec![0x1b, b'[', b'F'],
+        KeyCode::Up => cursor_key(b'A'),
+        KeyCode::Down => cursor_key(b'B'),
+        KeyCode::Right => cursor_key(b'C'),
+        KeyCode::Left => cursor_key(b'D'),
+        KeyCode::Home => cursor_key(b'H'),
+        KeyCode::End => cursor_key(b'F'),
         KeyCode::PageUp => vec![0x1b, b'[', b'5', b'~'],
         KeyCode::PageDown => vec![0x1b, b'[', b'6', b'~'],
         KeyCode::Delete => vec![0x1b, b'[', b'3', b'~'],
@@ -29,9 +29,10 @@ impl App {
 
             // 2. Terminal, PTY, tabs, and pane layout
             PtyInput(_) | PtyOutput(_)
-            | PtyResize { .. }
+            | PtyResize { .. } | TerminalReset
             | ScrollUp(_) | ScrollDown(_)
             | ScrollPageUp | ScrollPageDown
+            | ScrollHalfPageUp | ScrollHalfPageDown
             | ScrollToTop | ScrollToBottom
             | Copy | Paste
             | TabCreate | TabClose | TabCloseIndex(_)
@@ -43,7 +44,8 @@ impl App {
             | PaneResizeMainGrow | PaneResizeMainShrink
             | PaneResizeRightGrow | PaneResizeRightShrink
             | PaneResizeLeftGrow | PaneResizeLeftShrink
-            | PaneResetLayout | PaneStartDrag(_) | PaneDrag { .. } | PaneEndDrag
+            | PaneResetLayout | ToggleCompactMode | PaneStartDrag(_) | PaneDrag { .. } | PaneEndDrag
+            | OverlayStartDrag(_) | OverlayCyclePosition
                 => self.dispatch_terminal_tabs(action),
 
             // 3. Streams, process monitor, menu, log viewer
@@ -54,6 +56,7 @@ impl App {
             | StreamViewerShow(_) | StreamViewerHide | StreamViewerToggle
             | StreamViewerScrollUp(_) | StreamViewerScrollDown(_)
             | StreamViewerScrollToTop | StreamViewerScrollToBottom
+            | StreamViewerToggleWrap | StreamViewerToggleAutoScroll | StreamViewerScrollLeft(_) | StreamViewerScrollRight(_)
             | StreamViewerSearchStart | StreamViewerSearchClose
             | StreamViewerSearchNext | StreamViewerSearchPrev
             | StreamViewerSearchQuery(_) | StreamViewerSearchToggleCase
@@ -70,6 +73,7 @@ impl App {
             | LogViewerScrollToTop | LogViewerScrollToBottom
             | LogViewerScrollPageUp | LogViewerScrollPageDown
             | LogViewerToggleAutoScroll | LogViewerClear | LogViewerPush(_, _)
+            | LogViewerToggleWrap | LogViewerToggleTimestamps | LogViewerScrollLeft(_) | LogViewerScrollRight(_)
             | LogViewerSearchStart | LogViewerSearchClose
             | LogViewerSearchNext | LogViewerSearchPrev
             | LogViewerSearchQuery(_) | LogViewerSearchToggleCase
@@ -77,6 +81,7 @@ impl App {
             | LogViewerFilterApply | LogViewerFilterPattern(_)
             | LogViewerFilterToggleCase | LogViewerFilterToggleRegex
             | LogViewerFilterToggleInvert | LogViewerFilterClear
+            | LogViewerOpenPager
             | ActivityStreamShow | ActivityStreamHide | ActivityStreamToggle
             | ActivityStreamClear | ActivityStreamToggleAutoScroll
             | SirkPanelShow | SirkPanelHide | SirkPanelToggle
@@ -85,39 +90,44 @@ impl App {
                 => self.dispatch_streams_process(action),
 
             // 4. Chat, LLM, threads, tools, conversation
-            LlmSendMessage(_) | LlmStreamChunk(_)
+            LlmSendMessage(_) | LlmSendMessageConfirmed(_) | LlmSendInNewThread(_) | LlmStreamChunk(_)
             | LlmStreamComplete | LlmStreamError(_)
-            | LlmCancel
+            | LlmCancel | LlmPause | LlmResume | AgentRetryLastTurn
             | LlmSelectModel(_) | LlmSelectProvider(_)
-            | LlmClearConversation
+            | ModelPickerShow | ModelPickerHide | ModelPickerSelect { .. }
+            | LlmClearConversation | ShowLastLlmExchange | GenerateCrashReport | ShowTurnChanges
+            | UndoLastEdit | UndoLastEditConfirm
             | SubagentSelectModel { .. } | SubagentSelectProvider { .. }
-            | ChatInputClear | ChatInputPaste(_) | ChatInputCopy
+            | ChatInputClear | ChatInputPaste(_) | ChatInputPasteImage | ChatInputAttachImageFile(_) | ChatInputCopy
             | ChatInputScrollUp(_) | ChatInputScrollDown(_)
-            | ConversationToggle
+            | ConversationToggle | PlanPanelToggle
             | ConversationScrollUp(_) | ConversationScrollDown(_)
             | ConversationScrollToTop | ConversationScrollToBottom
             | ConversationCopy
             | ConversationSearchStart | ConversationSearchClose
             | ConversationSearchNext | ConversationSearchPrev
             | ConversationSearchQuery(_) | ConversationSearchToggleCase
-            | ToolUseReceived(_)
-            | ToolConfirm | ToolReject
+            | ToolConfirm | ToolReject | ToolConfirmAll | ToolRejectAll
             | ToolResult(_)
-            | ToolToggleDangerousMode | ToolSetDangerousMode(_)
+            | ToolToggleDangerousMode | ToolSetDangerousMode(_) | AgentToggleStepMode | AgentToggleAutoContinue | AgentToggleDryRun
+            | AgentSelectPersona(_) | ShowSystemPrompt
             | ToolCallNextTool | ToolCallPrevTool
             | ToolCallToggleExpand | ToolCallExpandAll | ToolCallCollapseAll
             | ToolCallStartExecution(_) | ToolCallRegister(_)
+            | ToolCallViewOutput
             | ThinkingToggleCollapse
             | ToolResultToggleCollapse | ToolVerbosityCycle
             | ThreadNew | ThreadLoad(_) | ThreadList
-            | ThreadSave | ThreadClear
+            | ThreadSave | ThreadClear | ThreadDelete(_) | ThreadPrune | ThreadPruneConfirm
             | ThreadPickerShow | ThreadPickerHide
             | ThreadStartRename | ThreadCancelRename
             | ThreadRenameInput(_) | ThreadRenameBackspace | ThreadRename(_)
+            | ThreadStartTagEdit | ThreadSetTags(_)
+            | ThreadUsageReport | ThreadUsageExport | SubagentPeek
                 => self.dispatch_chat_llm(action),
 
             // 5. Configuration, settings editor, key storage, config panel
-            ConfigChanged(_) | ConfigReload | ConfigApplyTheme
+            ConfigChanged(_) | ConfigReload | ConfigApplyTheme | ConfigOpenDir | ConfigEditFile
             | ConfigPanelShow | ConfigPanelHide | ConfigPanelToggle
             | ConfigPanelScrollUp(_) | ConfigPanelScrollDown(_)
             | ConfigPanelScrollToTop | ConfigPanelScrollToBottom
@@ -128,16 +138,17 @@ impl App {
             | SettingsNextItem | SettingsPrevItem
             | SettingsScrollUp(_) | SettingsScrollDown(_)
             | SettingsStartEdit | SettingsCancelEdit
-            | SettingsKeyEntered { .. }
+            | SettingsKeyEntered { .. } | SettingsBaseUrlEntered { .. }
             | SettingsProviderChanged(_) | SettingsModelChanged(_)
             | SettingsTestKey | SettingsTestKeyResult { .. }
             | SettingsTemperatureChanged(_) | SettingsMaxTokensChanged(_)
+            | SettingsThinkingToggled(_) | SettingsThinkingBudgetChanged(_)
             | SettingsSave
             | KeyStore(_, _) | KeyGet(_) | KeyDelete(_) | KeyList
             | KeyUnlock(_) | KeyInit(_)
                 => self.dispatch_config_settings(action),
 
-            // 6. UI chrome: notifications, context menu, spinners, ask_user dialog
+            // 6. UI chrome: notifications, context menu, spinners, ask_user dialog, pager
             NotifyInfo(_) | NotifyInfoMessage(_, _)
             | NotifySuccess(_) | NotifySuccessMessage(_, _)
             | NotifyWarning(_) | NotifyWarningMessage(_, _)
@@ -153,7 +164,8 @@ impl App {
             | AskUserStartCustom | AskUserCancelCustom
             | AskUserCustomInput(_) | AskUserCustomBackspace
             | AskUserSubmitCustom | AskUserSubmit
-            | AskUserCancel | AskUserRespond(_)
+            | AskUserCancel | AskUserRespond(_) | AskUserTimedOut(_)
+            | PagerShow { .. } | PagerClose | PagerCopy
                 => self.dispatch_ui_chrome(action),
 
             // Catch-all for Action::None
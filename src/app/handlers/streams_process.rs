@@ -1,10 +1,9 @@
 // Streams, process monitor, menu, and log viewer dispatch
 // Domain: Stream connections, menu navigation, process monitor, log viewer with search/filter
 
-use crate::action::Action;
+use crate::action::{Action, OverlayKind};
 use crate::components::Component;
 use crate::error::Result;
-use crate::input::focus::FocusArea;
 use crate::streams::ConnectionState;
 
 use super::super::App;
@@ -49,7 +48,7 @@ impl App {
                 });
                 if let Some((id, state)) = id_and_state {
                     match state {
-                        ConnectionState::Connected => self.stream_manager.disconnect(&id),
+                        ConnectionState::Connected | ConnectionState::Queued => self.stream_manager.disconnect(&id),
                         _ => self.stream_manager.connect(&id),
                     }
                 }
@@ -81,20 +80,16 @@ impl App {
             // Stream viewer actions
             Action::StreamViewerShow(idx) => {
                 self.selected_stream_index = Some(idx);
-                self.show_stream_viewer = true;
-                self.ui.focus.focus(FocusArea::StreamViewer);
+                self.open_overlay(OverlayKind::StreamViewer);
             }
             Action::StreamViewerHide => {
-                self.show_stream_viewer = false;
-                self.ui.focus.focus(FocusArea::Menu);
+                self.close_overlay(OverlayKind::StreamViewer);
             }
             Action::StreamViewerToggle => {
                 if self.show_stream_viewer {
-                    self.show_stream_viewer = false;
-                    self.ui.focus.focus(FocusArea::Menu);
+                    self.close_overlay(OverlayKind::StreamViewer);
                 } else if self.selected_stream_index.is_some() {
-                    self.show_stream_viewer = true;
-                    self.ui.focus.focus(FocusArea::StreamViewer);
+                    self.open_overlay(OverlayKind::StreamViewer);
                 }
             }
             Action::StreamViewerScrollUp(n) => {
@@ -109,6 +104,18 @@ impl App {
             Action::StreamViewerScrollToBottom => {
                 self.stream_viewer.scroll_to_bottom();
             }
+            Action::StreamViewerToggleWrap => {
+                self.stream_viewer.toggle_wrap();
+            }
+            Action::StreamViewerToggleAutoScroll => {
+                self.stream_viewer.toggle_auto_scroll();
+            }
+            Action::StreamViewerScrollLeft(n) => {
+                self.stream_viewer.scroll_left(n);
+            }
+            Action::StreamViewerScrollRight(n) => {
+                self.stream_viewer.scroll_right(n);
+            }
 
             // Stream viewer search actions (TRC-021) - placeholder, methods not yet implemented
             Action::StreamViewerSearchStart
@@ -148,20 +155,16 @@ impl App {
 
             // Log viewer actions (TRC-013)
             Action::LogViewerShow => {
-                self.show_log_viewer = true;
-                self.ui.focus.focus(FocusArea::LogViewer);
+                self.open_overlay(OverlayKind::LogViewer);
             }
             Action::LogViewerHide => {
-                self.show_log_viewer = false;
-                self.ui.focus.focus(FocusArea::Menu);
+                self.close_overlay(OverlayKind::LogViewer);
             }
             Action::LogViewerToggle => {
                 if self.show_log_viewer {
-                    self.show_log_viewer = false;
-                    self.ui.focus.focus(FocusArea::Menu);
+                    self.close_overlay(OverlayKind::LogViewer);
                 } else {
-                    self.show_log_viewer = true;
-                    self.ui.focus.focus(FocusArea::LogViewer);
+                    self.open_overlay(OverlayKind::LogViewer);
                 }
             }
             Action::LogViewerScrollUp(n) => {
@@ -185,6 +188,18 @@ impl App {
             Action::LogViewerToggleAutoScroll => {
                 self.log_viewer.toggle_auto_scroll();
             }
+            Action::LogViewerToggleWrap => {
+                self.log_viewer.toggle_wrap();
+            }
+            Action::LogViewerToggleTimestamps => {
+                self.log_viewer.toggle_timestamps();
+            }
+            Action::LogViewerScrollLeft(n) => {
+                self.log_viewer.scroll_left(n);
+            }
+            Action::LogViewerScrollRight(n) => {
+                self.log_viewer.scroll_right(n);
+            }
             Action::LogViewerClear => {
                 self.log_viewer.clear();
             }
@@ -213,6 +228,10 @@ impl App {
             | Action::LogViewerFilterClear => {
                 // TODO: Implement filter in LogViewer
             }
+            Action::LogViewerOpenPager => {
+                let content = self.log_viewer.to_plain_text();
+                self.ui.pager.show("Logs", &content);
+            }
 
             // Activity Stream actions (SIRK/Forge)
             Action::ActivityStreamShow => {
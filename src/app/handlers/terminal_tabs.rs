@@ -25,6 +25,12 @@ impl App {
                 // Resize all PTY sessions (TRC-005)
                 self.pty.tab_manager.set_terminal_size(cols, rows);
             }
+            Action::TerminalReset => {
+                if let Some(session) = self.pty.tab_manager.active_pty_session_mut() {
+                    session.reset();
+                    self.ui.notification_manager.info("Terminal reset");
+                }
+            }
 
             // Scroll actions
             Action::ScrollUp(n) => {
@@ -48,6 +54,11 @@ impl App {
                     session.terminal_mut().update(&Action::ScrollPageDown);
                 }
             }
+            Action::ScrollHalfPageUp | Action::ScrollHalfPageDown => {
+                if let Some(session) = self.pty.tab_manager.active_pty_session_mut() {
+                    session.terminal_mut().update(&action);
+                }
+            }
             Action::ScrollToTop => {
                 if let Some(session) = self.pty.tab_manager.active_pty_session_mut() {
                     session.terminal_mut().update(&Action::ScrollToTop);
@@ -207,6 +218,17 @@ impl App {
             Action::PaneResetLayout => {
                 self.ui.pane_layout.reset_to_defaults();
             }
+            Action::ToggleCompactMode => {
+                self.ui.pane_layout.toggle_compact();
+                if self.ui.pane_layout.compact {
+                    self.ui.notification_manager.info_with_message(
+                        "Compact mode on",
+                        "Focus the process monitor or menu to show it",
+                    );
+                } else {
+                    self.ui.notification_manager.info("Compact mode off");
+                }
+            }
             Action::PaneStartDrag(border) => {
                 let rb = match border {
                     PaneBorder::MainVertical => ResizableBorder::MainVertical,
@@ -218,11 +240,21 @@ impl App {
             Action::PaneDrag { x, y } => {
                 if let Some(border) = self.ui.drag_state.border() {
                     self.ui.pane_layout.handle_mouse_drag(x, y, self.ui.content_area, border, self.agent.show_conversation);
+                } else if let Some(kind) = self.ui.drag_state.overlay() {
+                    self.ui.overlay_layout.get_mut(kind).drag_to(x, self.ui.content_area);
                 }
             }
             Action::PaneEndDrag => {
                 self.ui.drag_state.stop();
             }
+            Action::OverlayStartDrag(kind) => {
+                self.ui.drag_state.start_overlay(kind);
+            }
+            Action::OverlayCyclePosition => {
+                if let Some(kind) = self.topmost_overlay() {
+                    self.ui.overlay_layout.get_mut(kind).cycle_position();
+                }
+            }
 
             _ => unreachable!("non-terminal/tabs action passed to dispatch_terminal_tabs: {:?}", action),
         }
@@ -234,7 +266,7 @@ impl App {
 /// Avoids adding a crate dependency for a single use.
 fn simple_base64_encode(data: &[u8]) -> String {
     const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as u32;
         let b1 = if chunk.len() > 1 { chunk[1] as u32 } else { 0 };
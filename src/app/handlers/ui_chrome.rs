@@ -1,5 +1,5 @@
-// UI chrome dispatch: notifications, context menu, spinners, ask_user dialog
-// Domain: Notification toasts, context menus, spinner animations, ask_user dialogs
+// UI chrome dispatch: notifications, context menu, spinners, ask_user dialog, pager
+// Domain: Notification toasts, context menus, spinner animations, ask_user dialogs, pager overlay
 
 use crate::action::Action;
 use crate::components::spinner_manager::SpinnerKey;
@@ -86,9 +86,12 @@ impl App {
                             description: o.description.clone(),
                         }).collect(),
                         multi_select: q.multi_select,
+                        required: q.required,
+                        validation: q.validation.clone(),
+                        default: q.default.clone(),
                     }
                 }).collect();
-                self.ui.ask_user_dialog.show(request.tool_use_id.clone(), questions);
+                self.show_ask_user(request.tool_use_id.clone(), questions, request.timeout_secs);
             }
             Action::AskUserCancel => {
                 // User cancelled - create error result
@@ -100,9 +103,13 @@ impl App {
             Action::AskUserRespond(ref response) => {
                 // User responded - create tool result with answers
                 self.ui.ask_user_dialog.hide();
-                let answers_json = serde_json::json!({
+                let mut answers_json = serde_json::json!({
                     "answers": response.answers
                 });
+                if response.timed_out {
+                    answers_json["timed_out"] = serde_json::Value::Bool(true);
+                    self.ui.notification_manager.info("No response - answered ask_user with defaults");
+                }
                 let tool_result = crate::llm::ToolResult {
                     tool_use_id: response.tool_use_id.clone(),
                     content: crate::llm::ToolResultContent::Text(answers_json.to_string()),
@@ -110,17 +117,20 @@ impl App {
                 };
                 // Remove from pending and add to collected
                 self.agent.pending_tools.remove(&response.tool_use_id);
-                self.agent.collected_results.insert(response.tool_use_id.clone(), tool_result);
-
-                // Check if we have all results for current batch
-                if let Some((batch_id, expected)) = self.agent.expected_tool_batch {
-                    if self.agent.collected_results.len() >= expected {
-                        let results: Vec<crate::llm::ToolResult> = self.agent.collected_results.drain().map(|(_, r)| r).collect();
-                        self.agent.agent_engine.continue_after_tools(results);
-                        self.agent.expected_tool_batch = None;
-                        self.agent.tool_batch_map.retain(|_, bid| *bid != batch_id);
-                    }
-                }
+                self.collect_tool_result(tool_result);
+            }
+            Action::AskUserTimedOut(ref tool_use_id) => {
+                self.ui.ask_user_dialog.hide();
+                self.ui.notification_manager.warning("No response to ask_user before the timeout");
+                let tool_result = crate::llm::ToolResult {
+                    tool_use_id: tool_use_id.clone(),
+                    content: crate::llm::ToolResultContent::Text(
+                        "The user did not respond before the timeout. Proceed without their input or ask again later.".to_string(),
+                    ),
+                    is_error: true,
+                };
+                self.agent.pending_tools.remove(tool_use_id);
+                self.collect_tool_result(tool_result);
             }
 
             // These ask_user actions are handled by the dialog's handle_event
@@ -139,6 +149,20 @@ impl App {
                 // These are handled by the dialog's handle_event
             }
 
+            // Pager overlay
+            Action::PagerShow { title, content } => {
+                self.ui.pager.show(title, &content);
+            }
+            Action::PagerClose => {
+                self.ui.pager.hide();
+            }
+            Action::PagerCopy => {
+                if let Some(ref mut clipboard) = self.ui.clipboard {
+                    let _ = clipboard.set_text(self.ui.pager.content());
+                    self.ui.notification_manager.info("Copied to clipboard");
+                }
+            }
+
             _ => unreachable!("non-ui-chrome action passed to dispatch_ui_chrome: {:?}", action),
         }
         Ok(())
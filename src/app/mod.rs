@@ -7,13 +7,18 @@
 #![allow(dead_code)]
 
 mod agent_state;
+mod desktop_notify;
 mod event_loop;
 mod handlers;
 pub(crate) mod pty_state;
 mod rendering;
+mod tool_batch;
+mod turn_changes;
 mod ui_state;
 
 use self::agent_state::AgentRuntimeState;
+use self::desktop_notify::DesktopEvent;
+use self::tool_batch::BatchDecision;
 use self::pty_state::PtyState;
 use self::ui_state::UiState;
 
@@ -23,7 +28,8 @@ use std::time::Instant;
 
 use arboard::Clipboard;
 use crossterm::{
-    event::{DisableBracketedPaste, DisableMouseCapture},
+    cursor::SetCursorStyle,
+    event::{DisableBracketedPaste, DisableFocusChange, DisableMouseCapture},
     execute,
     terminal::{disable_raw_mode, LeaveAlternateScreen},
 };
@@ -31,9 +37,11 @@ use ratatui::layout::Rect;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 
-use crate::action::{Action, ContextMenuTarget};
+use crate::action::{Action, ContextMenuTarget, OverlayKind};
 use crate::cli::Cli;
 use crate::components::activity_stream::ActivityStream;
+use crate::components::inline_image;
+use crate::components::input_history::InputHistory;
 use crate::spindles::{new_shared_store, SharedActivityStore, SpindlesStream};
 use crate::sirk::{ForgeController, ForgeEvent};
 use crate::components::config_panel::ConfigPanel;
@@ -41,30 +49,41 @@ use crate::components::settings_editor::SettingsEditor;
 use crate::components::context_menu::ContextMenuItem;
 use crate::components::log_viewer::LogViewer;
 use crate::components::menu::Menu;
+use crate::components::model_picker::ModelEntry;
+use crate::components::overlay_layout::OverlayLayout;
 use crate::components::process_monitor::ProcessMonitor;
+use crate::components::scroll::Following;
 use crate::components::spinner_manager::SpinnerKey;
 use crate::components::stream_viewer::StreamViewer;
 
-use crate::config::{ConfigManager, ConfigWatcherMode, KeyStore, SecretString, SessionData, SessionManager};
+use crate::config::{ConfigManager, ConfigWatcherMode, KeyId, KeyStore, KeyStoreBackend, SecretString, SessionData, SessionManager, ViewerPrefs};
 use crate::error::{Result, RidgeError};
 use crate::input::focus::FocusArea;
 use crate::input::mode::InputMode;
 use crate::llm::{
-    BlockType, LLMManager, LLMEvent, StreamChunk, StreamDelta, StopReason,
-    ToolExecutor, ToolExecutionCheck, PendingToolUse, ToolUse,
+    LLMManager, StreamChunk, StreamDelta, StopReason,
+    ToolExecutor, ToolExecutionCheck, ParsedQuestion, PendingToolUse, ToolUse,
 };
 use crate::streams::{StreamEvent, StreamManager, StreamsConfig, ConnectionState};
 use crate::tabs::TabId;
 use crate::agent::{
     AgentEngine, AgentEvent, ConfirmationRequiredExecutor, ContextManager, DiskThreadStore,
-    ModelCatalog, DefaultTokenCounter, TokenCounter, SystemPromptBuilder,
-    SubagentManager, AgentToolOrchestrator,
-    MandrelClient,
+    ModelCatalog, DefaultTokenCounter, TokenCounter, SystemPromptBuilder, PromptRedaction,
+    SubagentLaunch, SubagentManager, AgentToolOrchestrator,
+    MandrelClient, ThreadStore,
 };
 use crate::lsp::LspManager;
 
 pub(super) const TICK_INTERVAL_MS: u64 = 500;
 
+/// Conversation to open at launch in place of the session's last thread
+enum StartupThread {
+    /// `--thread <id>`
+    Resume(String),
+    /// `--new-thread`
+    New,
+}
+
 pub struct App {
     should_quit: bool,
     // UI state extracted to UiState (Order 8.2)
@@ -89,6 +108,8 @@ pub struct App {
     keystore: Option<KeyStore>,
     // Session persistence (TRC-012)
     session_manager: Option<SessionManager>,
+    /// Thread requested by `--thread` / `--new-thread`, opened once at launch
+    startup_thread: Option<StartupThread>,
     // Log viewer with auto-scroll (TRC-013)
     log_viewer: LogViewer,
     show_log_viewer: bool,
@@ -126,10 +147,20 @@ pub struct App {
     forge_resume_pending: Option<crate::sirk::ResumePromptEvent>,
     // SIRK/Forge: Pending resume response (true=resume, false=abort)
     forge_resume_response_pending: Option<bool>,
+    // Settings: results of API key tests running on spawned tasks
+    key_test_tx: mpsc::UnboundedSender<Action>,
+    key_test_rx: Option<mpsc::UnboundedReceiver<Action>>,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
+    /// `config_dir` replaces the XDG config dir (`--config-dir`), `data_dir` the
+    /// XDG data dir (`--data-dir`); `threads_dir` overrides where threads are
+    /// stored (`--threads-dir`)
+    pub fn new(
+        config_dir: Option<&std::path::Path>,
+        data_dir: Option<&std::path::Path>,
+        threads_dir: Option<&std::path::Path>,
+    ) -> Result<Self> {
         // Get terminal size for PTY initialization
         let (term_width, term_height) = crossterm::terminal::size()
             .map_err(|e| RidgeError::Terminal(e.to_string()))?;
@@ -139,6 +170,15 @@ impl App {
         // Initialize PtyState (handles raw mode, alternate screen, terminal, tab_manager)
         let pty = PtyState::new(term_cols, term_rows)?;
 
+        // Initialize configuration system
+        let mut config_manager = match config_dir {
+            Some(dir) => ConfigManager::new_in(dir.to_path_buf()),
+            None => ConfigManager::new()?,
+        };
+        if let Some(dir) = data_dir {
+            config_manager = config_manager.with_data_dir(dir.to_path_buf());
+        }
+
         let clipboard = match Clipboard::new() {
             Ok(cb) => {
                 tracing::info!("Clipboard: available (arboard)");
@@ -150,9 +190,21 @@ impl App {
             }
         };
 
-        let streams_config = StreamsConfig::load();
+        match inline_image::resolve_protocol(config_manager.app_config().ui.image_protocol) {
+            Some(protocol) => tracing::info!(
+                "Images: {:?} graphics available; image results use the saved-file placeholder",
+                protocol
+            ),
+            None => tracing::info!("Images: no graphics protocol; image results use the saved-file placeholder"),
+        }
+
+        let streams_config = StreamsConfig::load(config_manager.config_dir());
         let mut stream_manager = StreamManager::new();
         stream_manager.load_streams(&streams_config);
+        // A bad bundle is reported once, with the LLM HTTP settings below
+        if let Err(e) = stream_manager.set_ca_bundle(config_manager.llm_config().ca_bundle.as_deref()) {
+            tracing::warn!("SSE streams ignore ca_bundle: {}", e);
+        }
 
         let mut menu = Menu::new();
         let stream_count = stream_manager.clients().len();
@@ -161,23 +213,21 @@ impl App {
         // Initialize selected_stream_index to 0 if streams exist
         let initial_stream_index = if stream_count > 0 { Some(0) } else { None };
 
-        // Initialize secure key storage (TRC-011)
-        let keystore = match KeyStore::new() {
-            Ok(ks) => Some(ks),
-            Err(e) => {
-                tracing::warn!("Failed to initialize keystore: {}", e);
-                None
-            }
-        };
-        
         // Get working directory for tool executor
         let working_dir = std::env::current_dir().unwrap_or_else(|_| {
             dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
         });
         let mut tool_executor = ToolExecutor::new(working_dir);
+        tool_executor.set_config_dir(config_manager.config_dir().to_path_buf());
 
-        // Initialize configuration system
-        let config_manager = ConfigManager::new()?;
+        // Initialize secure key storage (TRC-011) with the configured backend
+        let mut keystore = match KeyStore::open_in(config_manager.app_config().keystore.backend, config_manager.config_dir()) {
+            Ok(ks) => Some(ks),
+            Err(e) => {
+                tracing::warn!("Failed to initialize keystore: {}", e);
+                None
+            }
+        };
 
         // T2.3: Initialize MandrelClient for cross-session memory
         let mandrel_config = config_manager.mandrel_config().clone();
@@ -231,7 +281,7 @@ impl App {
         };
         
         // Phase 3: Initialize token counting infrastructure
-        let mut model_catalog = ModelCatalog::new();
+        let mut model_catalog = ModelCatalog::from_file(&config_manager.models_path());
         let ollama_base_url = config_manager.llm_config()
             .providers.get("ollama")
             .and_then(|p| p.base_url.as_deref())
@@ -242,28 +292,65 @@ impl App {
 
         // Phase 2: Initialize AgentEngine (TP2-002-04)
         let (agent_event_tx, agent_event_rx) = mpsc::unbounded_channel::<AgentEvent>();
+        let (key_test_tx, key_test_rx) = mpsc::unbounded_channel::<Action>();
         let context_manager = std::sync::Arc::new(ContextManager::new(model_catalog.clone(), token_counter.clone()));
-        let prompt_builder = SystemPromptBuilder::ridge_control();
+        let prompt_builder = SystemPromptBuilder::ridge_control()
+            .with_redaction(PromptRedaction::from(&config_manager.app_config().prompt));
         let agent_tool_executor: std::sync::Arc<dyn AgentToolOrchestrator> = std::sync::Arc::new(ConfirmationRequiredExecutor);
-        let thread_store = match DiskThreadStore::new() {
-            Ok(store) => std::sync::Arc::new(store),
-            Err(e) => {
-                tracing::warn!("Failed to create DiskThreadStore: {}, using default path", e);
-                // Fallback: try with temp directory
-                std::sync::Arc::new(DiskThreadStore::with_path(std::env::temp_dir().join("ridge-control-threads"))
-                    .expect("Failed to create fallback thread store"))
+        let threads_dir = config_manager.threads_dir(threads_dir);
+        let session_dir = config_manager.session_dir();
+        tracing::info!(
+            "Paths: config {}, data {}, logs {}, threads {}, session {}",
+            config_manager.config_dir().display(),
+            config_manager.data_dir().display(),
+            config_manager.log_dir().display(),
+            threads_dir.path.display(),
+            session_dir.path.display(),
+        );
+        let mut storage_warnings: Vec<(&str, String)> = [&threads_dir, &session_dir]
+            .iter()
+            .filter_map(|dir| dir.warning.clone())
+            .map(|warning| ("Storage directory unavailable", warning))
+            .collect();
+        let mut thread_store = DiskThreadStore::with_path(threads_dir.path).map_err(RidgeError::Config)?;
+        if config_manager.app_config().agent.encrypt_threads {
+            // Never fall back to plaintext: without a key, threads are not saved
+            thread_store = thread_store.requiring_encryption();
+            match keystore.as_mut() {
+                Some(ks) if ks.backend() == KeyStoreBackend::Plaintext => storage_warnings.push((
+                    "Threads are not saved",
+                    "agent.encrypt_threads needs the system keyring or an encrypted keystore".to_string(),
+                )),
+                Some(ks) => match ks.threads_key() {
+                    Ok(key) => thread_store.set_encryption_key(key),
+                    Err(e) => storage_warnings.push((
+                        "Threads are not saved until the keystore is unlocked",
+                        e.to_string(),
+                    )),
+                },
+                None => storage_warnings.push((
+                    "Threads are not saved",
+                    "Thread encryption is on but the keystore is unavailable".to_string(),
+                )),
             }
-        };
+        }
+        let thread_store = std::sync::Arc::new(thread_store);
         
         // Create separate LLMManager for AgentEngine (with same provider registrations)
         let llm_config = config_manager.llm_config();
         let mut agent_llm_manager = LLMManager::new();
+        agent_llm_manager.set_base_urls(llm_config);
+        let http_settings_error = agent_llm_manager.set_http_settings(llm_config).err();
         if let Some(ref ks) = keystore {
-            agent_llm_manager.register_from_keystore_with_config(ks, Some(&llm_config));
+            agent_llm_manager.register_from_keystore_with_config(ks, Some(llm_config));
         }
         // Apply same provider/model settings
         agent_llm_manager.set_provider(&llm_config.defaults.provider);
         agent_llm_manager.set_model(&llm_config.defaults.model);
+        agent_llm_manager.set_fallback_models(llm_config.fallback_models.clone());
+        agent_llm_manager.set_fallback_providers(llm_config.fallback_provider_models());
+        agent_llm_manager.set_max_retries(llm_config.max_retries);
+        agent_llm_manager.set_debug_capture(llm_config.debug_capture);
         tracing::info!(
             "Loaded LLM settings: provider={}, model={}",
             llm_config.defaults.provider,
@@ -278,6 +365,7 @@ impl App {
         }
         let agent_config = crate::agent::AgentConfig {
             tools: tool_defs,
+            compact_tool_descriptions: config_manager.app_config().tools.compact_descriptions,
             ..Default::default()
         };
         
@@ -289,18 +377,16 @@ impl App {
             thread_store,
             agent_event_tx,
         ).with_config(agent_config);
+        agent_engine.set_temperature(Some(llm_config.parameters.temperature));
+        agent_engine.set_max_tokens(Some(llm_config.parameters.max_tokens));
+        agent_engine.set_thinking(llm_config.thinking.enabled, llm_config.thinking.budget_tokens);
+        agent_engine.set_prompt_cache(llm_config.prompt_cache);
         
         // TP2-002-FIX-01: Take the internal LLM event receiver for polling in run()
         let agent_llm_event_rx = agent_engine.take_llm_event_rx();
 
         // Initialize session manager (TRC-012)
-        let session_manager = match SessionManager::new() {
-            Ok(sm) => Some(sm),
-            Err(e) => {
-                tracing::warn!("Failed to initialize session manager: {}", e);
-                None
-            }
-        };
+        let session_manager = Some(SessionManager::in_dir(&session_dir.path));
 
         // T2.2: Initialize SubagentManager
         let subagent_manager = {
@@ -312,10 +398,18 @@ impl App {
         };
 
         // Create UiState with extracted UI fields (Order 8.2)
-        let ui = UiState::new(menu, clipboard);
+        let mut ui = UiState::new(menu, clipboard);
+        if let Some(e) = http_settings_error {
+            ui.notification_manager.warning_with_message("LLM HTTP settings ignored", e);
+        }
+        for (title, warning) in storage_warnings {
+            ui.notification_manager.warning_with_message(title, warning);
+        }
+
+        let settings_editor = SettingsEditor::new().with_model_catalog(model_catalog.clone());
 
         // Create AgentRuntimeState with extracted agent/LLM/tool fields (Order 8.4)
-        let agent = AgentRuntimeState::new(
+        let mut agent = AgentRuntimeState::new(
             agent_engine,
             agent_event_rx,
             agent_llm_event_rx,
@@ -324,6 +418,9 @@ impl App {
             tool_executor,
             subagent_manager,
         );
+        agent.chat_input.set_history(InputHistory::load(config_manager.chat_history_path()));
+        agent.chat_input.set_send_key(config_manager.app_config().ui.chat_send_key, pty.keyboard_enhanced);
+        agent.chat_input.set_emacs_keys(config_manager.app_config().ui.chat_emacs_keys);
 
         Ok(Self {
             should_quit: false,
@@ -340,11 +437,12 @@ impl App {
             config_watcher,
             keystore,
             session_manager,
+            startup_thread: None,
             log_viewer: LogViewer::new(),
             show_log_viewer: false,
             config_panel: ConfigPanel::new(),
             show_config_panel: false,
-            settings_editor: SettingsEditor::new(),
+            settings_editor,
             show_settings_editor: false,
             mandrel_client,
             lsp_manager,
@@ -360,6 +458,8 @@ impl App {
             forge_reset_pending: false,
             forge_resume_pending: None,
             forge_resume_response_pending: None,
+            key_test_tx,
+            key_test_rx: Some(key_test_rx),
         })
     }
 
@@ -371,7 +471,7 @@ impl App {
 
     /// Create App with CLI arguments (TRC-018)
     pub fn with_cli(cli: &Cli) -> Result<Self> {
-        let mut app = Self::new()?;
+        let mut app = Self::new(cli.config_dir.as_deref(), cli.data_dir.as_deref(), cli.threads_dir.as_deref())?;
         
         // TRC-018: Set dangerous mode from CLI flag
         if cli.dangerously_allow_all {
@@ -382,6 +482,8 @@ impl App {
         // Set working directory if provided
         if let Some(ref working_dir) = cli.working_dir {
             app.agent.tool_executor = ToolExecutor::new(working_dir.clone());
+            app.agent.tool_executor.set_config_dir(app.config_manager.config_dir().to_path_buf());
+            app.agent.agent_engine.set_working_dir(working_dir.clone());
             if app.agent.dangerous_mode {
                 app.agent.tool_executor.set_dangerous_mode(true);
             }
@@ -395,26 +497,165 @@ impl App {
             }
         }
         
-        // Register API keys from CLI (override keystore/config)
-        if let Some(ref key) = cli.anthropic_api_key {
-            app.agent.agent_engine.llm_manager_mut().register_anthropic(key.clone());
-        }
-        if let Some(ref key) = cli.openai_api_key {
-            app.agent.agent_engine.llm_manager_mut().register_openai(key.clone());
+        if let Some(reason) = app.keystore.as_ref().and_then(|ks| ks.fallback_reason()) {
+            let reason = reason.to_string();
+            app.ui.notification_manager.warning_with_message("Keyring unavailable", reason);
         }
-        if let Some(ref key) = cli.gemini_api_key {
-            app.agent.agent_engine.llm_manager_mut().register_gemini(key.clone());
-        }
-        if let Some(ref key) = cli.grok_api_key {
-            app.agent.agent_engine.llm_manager_mut().register_grok(key.clone());
+
+        // Register API keys from CLI, then environment (both override keystore)
+        let import_env = app.config_manager.app_config().keystore.import_env;
+        let cli_keys = [
+            (KeyId::Anthropic, &cli.anthropic_api_key),
+            (KeyId::OpenAI, &cli.openai_api_key),
+            (KeyId::Gemini, &cli.gemini_api_key),
+            (KeyId::Grok, &cli.grok_api_key),
+            (KeyId::Groq, &cli.groq_api_key),
+        ];
+        for (key_id, cli_key) in cli_keys {
+            let llm_manager = app.agent.agent_engine.llm_manager_mut();
+            if let Some(key) = cli_key {
+                llm_manager.register_key(&key_id, key);
+                tracing::info!("Registered {} provider from command line", key_id);
+            } else if let Some((var, key)) = import_env.then(|| key_id.env_value()).flatten() {
+                llm_manager.register_key(&key_id, &key);
+                tracing::info!("Registered {} provider from ${}", key_id, var);
+            }
         }
-        if let Some(ref key) = cli.groq_api_key {
-            app.agent.agent_engine.llm_manager_mut().register_groq(key.clone());
+
+        if let Some(ref path) = cli.mock_script {
+            match crate::llm::mock::MockScript::load(path) {
+                Ok(script) => {
+                    let turns = script.turns.len();
+                    app.agent.agent_engine.llm_manager_mut().register_mock(script);
+                    app.agent.agent_engine.set_provider(crate::llm::mock::MOCK_PROVIDER);
+                    app.agent.agent_engine.set_model(crate::llm::mock::MOCK_MODEL);
+                    app.ui.notification_manager.info(format!("Mock provider replaying {} scripted turn(s)", turns));
+                }
+                Err(e) => app.ui.notification_manager.error_with_message("Mock script not loaded", e),
+            }
         }
+
+        app.startup_thread = match (&cli.thread, cli.new_thread) {
+            (Some(id), _) => Some(StartupThread::Resume(id.clone())),
+            (None, true) => Some(StartupThread::New),
+            (None, false) => None,
+        };
+
+        app.pty.tab_manager.set_pty_read_buf(app.config_manager.app_config().terminal.pty_read_buf);
+        app.apply_logs_config();
+        app.apply_spinner_config();
+        app.apply_scroll_config();
+        app.ui.overlay_layout = OverlayLayout::from_config(&app.config_manager.app_config().ui);
         
         Ok(app)
     }
 
+    /// Apply `[logs]` timestamp settings to the log viewer and activity stream
+    fn apply_logs_config(&mut self) {
+        let logs = self.config_manager.app_config().logs.clone();
+        self.log_viewer.set_show_timestamps(logs.show_timestamps);
+        self.log_viewer.set_timestamp_format(logs.timestamp_format);
+        if let Some(ref mut stream) = self.activity_stream {
+            stream.set_show_timestamps(logs.show_timestamps);
+            stream.set_timestamp_format(logs.timestamp_format);
+        }
+    }
+
+    /// `ui.scroll_lock_threshold` for the viewers that follow new output, and
+    /// `ui.scroll_lines` for components that handle the wheel themselves
+    fn apply_scroll_config(&mut self) {
+        let ui = &self.config_manager.app_config().ui;
+        let (threshold, lines) = (ui.scroll_lock_threshold, ui.scroll_lines);
+        self.agent.conversation_viewer.follow_mut().set_lock_threshold(threshold);
+        self.log_viewer.follow_mut().set_lock_threshold(threshold);
+        self.stream_viewer.follow_mut().set_lock_threshold(threshold);
+        self.log_viewer.set_scroll_lines(lines);
+        self.ui.pager.set_scroll_lines(lines);
+        self.pty.tab_manager.set_scroll_lines(lines);
+    }
+
+    /// Share `ui.spinner_style` / `ui.spinner_interval_ms` across the LLM and stream spinners
+    fn apply_spinner_config(&mut self) {
+        let ui = self.config_manager.app_config().ui.clone();
+        self.ui.spinner_manager.configure(&ui.spinner_style, ui.spinner_interval_ms);
+        let spinner = self.ui.spinner_manager.spinner();
+        self.ui.menu.set_connecting_spinner(spinner.clone());
+        self.agent.conversation_viewer.set_streaming_spinner(spinner);
+    }
+
+    /// Most recently opened stream/log/config overlay, if any
+    pub(crate) fn topmost_overlay(&self) -> Option<OverlayKind> {
+        self.ui.overlay_layout.topmost()
+    }
+
+    /// Show an overlay and focus it. If that exceeds `ui.max_overlays`, the
+    /// oldest overlay is closed and a notification says so.
+    pub(crate) fn open_overlay(&mut self, kind: OverlayKind) {
+        self.set_overlay_visible(kind, true);
+        if let Some(closed) = self.ui.overlay_layout.open(kind) {
+            self.set_overlay_visible(closed, false);
+            self.notify_overlay_closed(closed);
+        }
+        self.ui.focus.focus(kind.focus_area());
+    }
+
+    /// Hide an overlay; focus moves to the next open overlay, or the menu
+    pub(crate) fn close_overlay(&mut self, kind: OverlayKind) {
+        self.set_overlay_visible(kind, false);
+        self.ui.overlay_layout.close(kind);
+        let next = self.topmost_overlay().map(OverlayKind::focus_area).unwrap_or(FocusArea::Menu);
+        self.ui.focus.focus(next);
+    }
+
+    /// Move focus to the next/previous open overlay when several are tiled
+    /// and one of them has focus. Returns false if focus was not on one.
+    pub(crate) fn cycle_overlay_focus(&mut self, forward: bool) -> bool {
+        if !self.ui.overlay_layout.is_tiled() {
+            return false;
+        }
+        let Some(current) = OverlayKind::from_focus_area(self.ui.focus.current()) else {
+            return false;
+        };
+        match self.ui.overlay_layout.cycle(current, forward) {
+            Some(next) => {
+                self.ui.focus.focus(next.focus_area());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply overlay defaults after config.toml changes
+    pub(crate) fn apply_overlay_config(&mut self) {
+        let closed = self.ui.overlay_layout.reconfigure(&self.config_manager.app_config().ui);
+        for kind in closed {
+            self.set_overlay_visible(kind, false);
+            self.notify_overlay_closed(kind);
+        }
+        if let Some(kind) = OverlayKind::from_focus_area(self.ui.focus.current()) {
+            if !self.ui.overlay_layout.open_overlays().contains(&kind) {
+                let next = self.topmost_overlay().map(OverlayKind::focus_area).unwrap_or(FocusArea::Menu);
+                self.ui.focus.focus(next);
+            }
+        }
+    }
+
+    fn set_overlay_visible(&mut self, kind: OverlayKind, visible: bool) {
+        match kind {
+            OverlayKind::StreamViewer => self.show_stream_viewer = visible,
+            OverlayKind::LogViewer => self.show_log_viewer = visible,
+            OverlayKind::ConfigPanel => self.show_config_panel = visible,
+        }
+    }
+
+    fn notify_overlay_closed(&mut self, kind: OverlayKind) {
+        let max = self.config_manager.app_config().ui.max_overlays.max(1);
+        self.ui.notification_manager.info_with_message(
+            format!("{} closed", kind.label()),
+            format!("At most {} overlay(s) open (ui.max_overlays)", max),
+        );
+    }
+
     /// Spawn PTY for the main tab (TRC-005)
     /// This is called once at startup for backward compatibility
     pub fn spawn_pty(&mut self) -> Result<()> {
@@ -428,8 +669,27 @@ impl App {
             return Ok(());
         };
 
-        let session = session_manager.load();
-        
+        let session = match session_manager.load_checked() {
+            Ok(session) => session,
+            Err(e) => {
+                self.ui
+                    .notification_manager
+                    .warning_with_message("Session not restored", e);
+                SessionData::default()
+            }
+        };
+        self.log_viewer.set_wrap(session.viewers.log_wrap);
+        self.stream_viewer.set_wrap(session.viewers.stream_wrap);
+        self.ui.pane_layout.compact = session.compact_mode;
+
+        // Reopen the last conversation before tabs so it survives tab restore failures.
+        // A thread requested on the command line takes its place.
+        if self.startup_thread.is_none() && self.config_manager.app_config().general.restore_last_thread {
+            if let Some(ref thread_id) = session.active_thread_id {
+                self.open_thread_or_new(thread_id, "Could not restore last thread")?;
+            }
+        }
+
         // Skip if only main tab (default session)
         if session.tabs.len() <= 1 {
             tracing::debug!("No additional tabs to restore");
@@ -456,16 +716,78 @@ impl App {
         Ok(())
     }
 
+    /// Open the thread given by `--thread` or `--new-thread`, if any.
+    /// Called once after session restore, which skips its own thread when one was given.
+    pub fn open_startup_thread(&mut self) -> Result<()> {
+        match self.startup_thread.take() {
+            Some(StartupThread::Resume(thread_id)) => {
+                self.open_thread_or_new(&thread_id, &format!("Could not open thread {}", thread_id))
+            }
+            Some(StartupThread::New) => {
+                self.dispatch(Action::ThreadNew)?;
+                self.agent.set_conversation_open(true);
+                self.ui.focus.focus(FocusArea::ChatInput);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Reopen a saved thread and show it in the chat panel.
+    /// A missing or unreadable thread starts a fresh one instead.
+    fn open_thread_or_new(&mut self, thread_id: &str, failure_title: &str) -> Result<()> {
+        let store = self.agent.agent_engine.thread_store();
+        if store.get(thread_id).is_none() {
+            let reason = if store.thread_path(thread_id).exists() {
+                "could not be read"
+            } else {
+                "no longer exists"
+            };
+            tracing::warn!("Thread {} {}, starting a new one", thread_id, reason);
+
+            let model = self.agent.agent_engine.current_model().to_string();
+            self.agent.agent_engine.new_thread(model);
+            self.agent.current_thread_id = self.agent.agent_engine.current_thread().map(|t| t.id.clone());
+            self.ui.notification_manager.warning_with_message(
+                failure_title.to_string(),
+                format!("Thread {} {}; started a new one", thread_id, reason),
+            );
+            return Ok(());
+        }
+
+        self.dispatch(Action::ThreadLoad(thread_id.to_string()))?;
+        if self.agent.current_thread_id.as_deref() == Some(thread_id) {
+            // Open the chat panel so the restored conversation is visible
+            self.agent.set_conversation_open(true);
+            self.ui.focus.focus(FocusArea::ChatInput);
+        }
+        Ok(())
+    }
+
     /// Save current session to disk (TRC-012)
     fn save_session(&self) {
         let Some(ref session_manager) = self.session_manager else {
             return;
         };
 
+        // Only threads with messages have been persisted by the thread store
+        let active_thread_id = self
+            .agent
+            .agent_engine
+            .current_thread()
+            .filter(|t| !t.segments.is_empty())
+            .map(|t| t.id.clone());
+
         let session = SessionData::from_tabs(
             self.pty.tab_manager.tabs_for_session(),
             self.pty.tab_manager.active_index(),
-        );
+        )
+        .with_active_thread(active_thread_id)
+        .with_viewers(ViewerPrefs {
+            log_wrap: self.log_viewer.is_wrap(),
+            stream_wrap: self.stream_viewer.is_wrap(),
+        })
+        .with_compact_mode(self.ui.pane_layout.compact);
 
         if let Err(e) = session_manager.save(&session) {
             tracing::error!("Failed to save session: {}", e);
@@ -477,93 +799,71 @@ impl App {
         self.pty.spawn_pty_for_tab(tab_id)
     }
 
+    /// Open a new tab and type `command` into its shell. The shell stays
+    /// open afterwards, so the tab survives the command exiting.
+    fn run_in_new_tab(&mut self, name: &str, command: &str) {
+        let tab_id = self.pty.tab_manager.create_tab(name);
+        if let Err(e) = self.spawn_pty_for_tab(tab_id) {
+            tracing::error!("Failed to spawn PTY for tab {}: {}", tab_id, e);
+            self.ui.notification_manager.error_with_message("Tab Error", format!("Failed to spawn shell: {}", e));
+            return;
+        }
+        if let Some(session) = self.pty.tab_manager.get_pty_session(tab_id) {
+            session.write(format!("{}\n", command).into_bytes());
+        }
+        self.ui.focus.focus(FocusArea::Terminal);
+        self.ui.input_mode = InputMode::PtyRaw;
+    }
+
     // NOTE: poll_pty_events() and run() moved to event_loop.rs
 
-    fn handle_llm_event(&mut self, event: LLMEvent) {
-        match event {
-            LLMEvent::Chunk(chunk) => {
-                match chunk {
-                    StreamChunk::BlockStart { block_type, tool_id, tool_name, .. } => {
-                        // TRC-017: Track what type of block we're receiving
-                        self.agent.current_block_type = Some(block_type);
-                        
-                        // If this is a tool use block, capture the tool id and name
-                        if block_type == BlockType::ToolUse {
-                            if let Some(id) = tool_id {
-                                self.agent.current_tool_id = Some(id);
-                            }
-                            self.agent.current_tool_name = tool_name;
-                            self.agent.current_tool_input.clear();
-                        }
-                    }
-                    StreamChunk::Delta(delta) => {
-                        match delta {
-                            StreamDelta::Text(text) => {
-                                self.agent.llm_response_buffer.push_str(&text);
-                            }
-                            StreamDelta::Thinking(text) => {
-                                // TRC-017: Route thinking to separate buffer
-                                self.agent.thinking_buffer.push_str(&text);
-                            }
-                            StreamDelta::ToolInput { input_json, .. } => {
-                                // Accumulate tool input JSON
-                                self.agent.current_tool_input.push_str(&input_json);
-                            }
-                        }
+    /// Update streaming display state from a chunk forwarded by AgentEngine.
+    ///
+    /// AgentEngine is the sole owner of tool orchestration: it consumes raw
+    /// `LLMEvent`s (including `ToolUseDetected`) and the app only executes tools
+    /// in response to `AgentEvent::ToolUseRequested`.
+    fn handle_stream_chunk(&mut self, chunk: StreamChunk) {
+        match chunk {
+            StreamChunk::BlockStart { block_type, .. } => {
+                // TRC-017: Track what type of block we're receiving
+                self.agent.current_block_type = Some(block_type);
+            }
+            StreamChunk::Delta(delta) => {
+                match delta {
+                    StreamDelta::Text(text) => {
+                        self.agent.llm_response_buffer.push_str(&text);
                     }
-                    StreamChunk::BlockStop { .. } => {
-                        // TRC-017: When a thinking block stops, finalize the thinking content
-                        if self.agent.current_block_type == Some(BlockType::Thinking) {
-                            // Thinking block completed - it will be stored with the message
-                            // when the full response completes
-                        }
-                        
-                        // When a tool use block stops, we have the complete tool use
-                        // AgentEngine owns tool orchestration - just clear local state
-                        if let (Some(id), Some(_name)) = (self.agent.current_tool_id.take(), self.agent.current_tool_name.take()) {
-                            self.agent.current_tool_input.clear();
-                            tracing::debug!(
-                                "BlockStop: tool {} handled by AgentEngine, cleared local state",
-                                id
-                            );
-                        }
-                        
-                        // Clear current block type
-                        self.agent.current_block_type = None;
+                    StreamDelta::Thinking(text) => {
+                        // TRC-017: Route thinking to separate buffer
+                        self.agent.thinking_buffer.push_str(&text);
                     }
-                    StreamChunk::Stop { .. } => {
-                        // Clear buffers on stop - AgentEngine tracks conversation via thread
-                        self.agent.llm_response_buffer.clear();
-                        self.agent.thinking_buffer.clear();
-                        self.agent.current_block_type = None;
+                    StreamDelta::ToolInput { .. } => {
+                        // Tool input is assembled by the LLM manager for AgentEngine
                     }
-                    _ => {}
                 }
             }
-            LLMEvent::Complete => {
-                // Clear buffers on complete - AgentEngine tracks conversation via thread
-                self.agent.llm_response_buffer.clear();
-                self.agent.thinking_buffer.clear();
+            StreamChunk::BlockStop { .. } => {
+                // Thinking is stored with the message when the full response completes
                 self.agent.current_block_type = None;
             }
-            LLMEvent::Error(err) => {
-                // TRC-023: Notify on LLM error
-                self.ui.notification_manager.error_with_message("LLM Error", err.to_string());
-                self.agent.llm_response_buffer.clear();
-                self.agent.thinking_buffer.clear();
-                self.agent.current_block_type = None;
-                self.agent.current_tool_id = None;
-                self.agent.current_tool_name = None;
-                self.agent.current_tool_input.clear();
-            }
-            LLMEvent::ToolUseDetected(tool_use) => {
-                // AgentEngine handles tool orchestration via AgentEvent::ToolUseRequested
-                // This event is ignored - just log for debugging
-                tracing::debug!(
-                    "ToolUseDetected: tool {} handled by AgentEngine",
-                    tool_use.id
-                );
+            StreamChunk::Stop { .. } => {
+                // Clear buffers on stop - AgentEngine tracks conversation via thread
+                self.agent.clear_streaming_buffers();
             }
+            _ => {}
+        }
+    }
+
+    /// Summarize the files written during the turn and keep them for undo.
+    /// Called when a turn ends for any reason, so snapshots never leak into the next one.
+    pub(crate) fn record_turn_changes(&mut self) {
+        let changes = self.agent.turn_changes.finish();
+        if !changes.is_empty() {
+            self.ui.notification_manager.info_with_message(
+                turn_changes::headline(&changes),
+                "Show Turn Changes lists them",
+            );
+            self.agent.edit_history.push(changes);
         }
     }
     
@@ -579,6 +879,7 @@ impl App {
                 // Manage spinner based on state transitions
                 match state {
                     AgentState::PreparingRequest | AgentState::StreamingResponse => {
+                        self.agent.turn_started_at.get_or_insert_with(Instant::now);
                         // Start LLM loading spinner
                         self.ui.spinner_manager.start(
                             SpinnerKey::LlmLoading,
@@ -602,8 +903,17 @@ impl App {
                     AgentState::Idle | AgentState::AwaitingUserInput => {
                         // Stop spinner when idle or waiting for input
                         self.ui.spinner_manager.stop(&SpinnerKey::LlmLoading);
-                        // Re-enable auto-scroll for next response
-                        self.agent.conversation_viewer.set_auto_scroll(true);
+                        // Cancelled turns end here without a TurnComplete
+                        self.agent.turn_started_at = None;
+                    }
+                    AgentState::Paused => {
+                        // Nothing is in flight while paused
+                        self.ui.spinner_manager.stop(&SpinnerKey::LlmLoading);
+                        if self.agent.auto_continue {
+                            self.ui.notification_manager.info_with_message("Agent Paused", "Run \"Resume Agent\" to continue");
+                        } else {
+                            self.ui.notification_manager.info("Tools complete — press Enter to continue");
+                        }
                     }
                     AgentState::Error => {
                         // Stop spinner on error
@@ -614,33 +924,17 @@ impl App {
             AgentEvent::Chunk(chunk) => {
                 // Invalidate token cache - content is changing
                 self.agent.cached_token_count = None;
-                // Forward to existing LLM event handler for streaming display
-                self.handle_llm_event(LLMEvent::Chunk(chunk));
+                self.handle_stream_chunk(chunk);
             }
             AgentEvent::ToolUseRequested(tool_use) => {
-                // Track tool in current batch with batch ID
-                let batch_id = match self.agent.expected_tool_batch {
-                    Some((id, count)) => {
-                        // Existing batch - increment count
-                        self.agent.expected_tool_batch = Some((id, count + 1));
-                        id
-                    }
-                    None => {
-                        // New batch - increment batch ID and start fresh
-                        self.agent.current_batch_id += 1;
-                        self.agent.expected_tool_batch = Some((self.agent.current_batch_id, 1));
-                        self.agent.collected_results.clear(); // Clear any stale results
-                        self.agent.current_batch_id
-                    }
-                };
-                
-                // Map this tool to its batch
-                self.agent.tool_batch_map.insert(tool_use.id.clone(), batch_id);
-                
-                let (_, expected) = self.agent.expected_tool_batch.unwrap();
+                // A tool id is executed at most once, even if the request is repeated
+                if !self.agent.tool_batch.request(&tool_use.id) {
+                    tracing::warn!("Ignoring duplicate ToolUseRequested for {}", tool_use.id);
+                    return;
+                }
                 tracing::info!(
                     "⚡ TOOL_REQUESTED: id={} name={}, batch={}, expected_count={}",
-                    tool_use.id, tool_use.name, batch_id, expected
+                    tool_use.id, tool_use.name, self.agent.tool_batch.id(), self.agent.tool_batch.expected()
                 );
                 // Forward to existing tool use handler
                 self.handle_tool_use_request(tool_use);
@@ -668,22 +962,33 @@ impl App {
                 // Stop any running spinners
                 self.ui.spinner_manager.stop(&SpinnerKey::LlmLoading);
                 
-                // Re-enable auto-scroll for next response
-                self.agent.conversation_viewer.set_auto_scroll(true);
-                
                 // Show usage info as notification if available
                 if let Some(ref u) = usage {
+                    // Priced like the thread total: llm.toml [pricing] first, then
+                    // the catalog, for the model that served the response
+                    let cost = self.agent.agent_engine.current_thread()
+                        .and_then(|t| t.usage.last())
+                        .and_then(|turn| crate::agent::usage::turn_cost(
+                            turn,
+                            &self.config_manager.llm_config().pricing,
+                            &self.agent.model_catalog,
+                        ));
                     tracing::info!(
-                        "Turn usage: {} input, {} output tokens",
+                        "Turn usage: {} input, {} output tokens, cost {}",
                         u.input_tokens,
-                        u.output_tokens
+                        u.output_tokens,
+                        cost.map_or_else(|| "unknown".to_string(), |c| format!("${:.4}", c))
                     );
                 }
                 
+                self.record_turn_changes();
+
                 // Handle stop reason
                 match stop_reason {
                     StopReason::EndTurn => {
-                        // Normal completion - no notification needed
+                        let turn_duration = self.agent.turn_started_at.take().map(|t| t.elapsed());
+                        self.ring_bell(DesktopEvent::TurnComplete, turn_duration);
+                        self.notify_desktop(DesktopEvent::TurnComplete, "Agent finished", "The agent turn is complete");
                     }
                     StopReason::ToolUse => {
                         // Tool use requested - spinner already managed by StateChanged
@@ -702,14 +1007,39 @@ impl App {
             AgentEvent::Error(err) => {
                 // Stop spinners on error
                 self.ui.spinner_manager.stop(&SpinnerKey::LlmLoading);
+                self.agent.turn_started_at = None;
+                self.notify_desktop(DesktopEvent::LlmError, "Agent error", &err);
                 self.ui.notification_manager.error_with_message("Agent Error", err);
                 // Clear streaming buffers (mirrors LLMEvent::Error cleanup)
-                self.agent.llm_response_buffer.clear();
-                self.agent.thinking_buffer.clear();
-                self.agent.current_block_type = None;
-                self.agent.current_tool_id = None;
-                self.agent.current_tool_name = None;
-                self.agent.current_tool_input.clear();
+                self.agent.clear_streaming_buffers();
+                // Edits made before the failure stay undoable
+                self.record_turn_changes();
+            }
+            AgentEvent::ModelFallback { provider, model, after } => {
+                tracing::warn!("{} failed, falling back to {} ({})", after, model, provider);
+                // Token counts depend on the model now in use
+                self.agent.cached_token_count = None;
+                if provider == after {
+                    self.ui.notification_manager.warning(format!("Falling back to {}", model));
+                } else {
+                    self.ui.notification_manager.warning_with_message(
+                        format!("Switched to {} after {} error", provider, after),
+                        format!("Continuing this turn on {}", model),
+                    );
+                }
+            }
+            AgentEvent::Throttled { provider, delay } => {
+                self.ui.notification_manager.info(format!(
+                    "{} rate limit reached, waiting {}s",
+                    provider,
+                    delay.as_secs().max(1)
+                ));
+            }
+            AgentEvent::Retrying { retry, max_retries, delay, reason } => {
+                self.ui.notification_manager.info_with_message(
+                    format!("Retrying (attempt {}/{}) in {:.1}s", retry + 1, max_retries + 1, delay.as_secs_f32()),
+                    reason,
+                );
             }
             AgentEvent::ContextTruncated { segments_dropped, tokens_used, budget } => {
                 tracing::info!(
@@ -729,6 +1059,28 @@ impl App {
         }
     }
 
+    /// All catalog models across providers for the quick model switcher
+    fn model_picker_entries(&self) -> Vec<ModelEntry> {
+        let registered = self.agent.agent_engine.registered_providers();
+        let current_provider = self.agent.agent_engine.current_provider();
+        let current_model = self.agent.agent_engine.current_model();
+        let catalog = &self.agent.model_catalog;
+        catalog
+            .providers()
+            .into_iter()
+            .flat_map(|provider| {
+                catalog.models_for_provider(provider).into_iter().map(move |model| (provider, model))
+            })
+            .map(|(provider, model)| ModelEntry {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                context_window: catalog.info_for(model).max_context_tokens,
+                registered: registered.iter().any(|p| p == provider),
+                current: provider == current_provider && model == current_model,
+            })
+            .collect()
+    }
+
     /// Refresh subagent model commands in command palette (T2.1b)
     fn refresh_subagent_commands(&mut self) {
         // Build map of provider -> available models
@@ -752,42 +1104,140 @@ impl App {
         
         let tool_id = tool_use.id.clone();
         
+        // Invalid input can't be confirmed meaningfully; run it straight through so
+        // the executor returns the error result to the model
+        if self.agent.tool_executor.validate_input(&tool_use).is_err() {
+            self.execute_tool(PendingToolUse::new(tool_use, ToolExecutionCheck::Allowed));
+            return;
+        }
+
+        // Dry-run describes the call instead of running it, so there is nothing to confirm
+        if self.agent.tool_executor.intercepts(&tool_use) {
+            self.execute_tool(PendingToolUse::new(tool_use, ToolExecutionCheck::Allowed));
+            return;
+        }
+
         // Check if the tool can be executed
-        let check = self.agent.tool_executor.can_execute(&tool_use, false);
+        let mut check = self.agent.tool_executor.can_execute(&tool_use, false);
+        // Step mode asks about every tool, even ones normally allowed
+        if self.agent.step_mode && check == ToolExecutionCheck::Allowed {
+            check = ToolExecutionCheck::RequiresConfirmation;
+        }
         
-        match check {
-            ToolExecutionCheck::Allowed => {
-                // No confirmation needed, execute directly
-                let pending = PendingToolUse::new(tool_use, check);
-                self.execute_tool(pending);
+        match (&check, self.agent.tool_batch.decision()) {
+            // No confirmation needed (or already approved for this turn), execute directly
+            (ToolExecutionCheck::Allowed, _)
+            | (ToolExecutionCheck::RequiresConfirmation, Some(BatchDecision::ApproveAll)) => {
+                self.execute_tool(PendingToolUse::new(tool_use, ToolExecutionCheck::Allowed));
+            }
+            (_, Some(BatchDecision::RejectAll)) => {
+                self.reject_tool(&tool_id);
             }
-            ToolExecutionCheck::RequiresConfirmation => {
-                // Show confirmation dialog
-                let pending = PendingToolUse::new(tool_use, check);
-                self.agent.pending_tools.insert(tool_id.clone(), pending.clone());
-                self.agent.confirming_tool_id = Some(tool_id);
-                self.ui.confirm_dialog.show(pending);
-                self.ui.input_mode = InputMode::Confirm {
-                    title: "Tool Execution".to_string(),
-                    message: "Confirm tool use?".to_string(),
-                };
+            _ => {
+                // Queue behind any dialog already open; tools are asked about in request order
+                self.agent.pending_tools.insert(tool_id, PendingToolUse::new(tool_use, check));
+                if self.agent.confirming_tool_id.is_none() {
+                    self.show_next_confirmation();
+                }
             }
-            ToolExecutionCheck::RequiresDangerousMode
-            | ToolExecutionCheck::PathNotAllowed
-            | ToolExecutionCheck::UnknownTool => {
-                // Show dialog explaining why it can't run
-                let pending = PendingToolUse::new(tool_use, check);
-                self.agent.pending_tools.insert(tool_id.clone(), pending.clone());
-                self.agent.confirming_tool_id = Some(tool_id);
-                self.ui.confirm_dialog.show(pending);
-                self.ui.input_mode = InputMode::Confirm {
-                    title: "Tool Blocked".to_string(),
-                    message: "Tool cannot execute".to_string(),
-                };
+        }
+    }
+
+    /// Tools of the open batch still waiting for the user, in request order
+    fn awaiting_confirmation(&self) -> Vec<PendingToolUse> {
+        self.agent.tool_batch.requested().iter()
+            .filter_map(|id| self.agent.pending_tools.get(id))
+            .filter(|pending| pending.check != ToolExecutionCheck::Allowed)
+            .cloned()
+            .collect()
+    }
+
+    /// Open the ask_user dialog; `timeout_secs` from the tool input overrides
+    /// `ask_user.default_timeout_secs` (0 = wait indefinitely)
+    fn show_ask_user(&mut self, tool_use_id: String, questions: Vec<ParsedQuestion>, timeout_secs: Option<u64>) {
+        self.ui.ask_user_dialog.show(tool_use_id, questions);
+        let timeout = timeout_secs.unwrap_or(self.config_manager.app_config().ask_user.default_timeout_secs);
+        if timeout > 0 {
+            self.ui.ask_user_dialog.start_countdown(std::time::Duration::from_secs(timeout));
+        }
+    }
+
+    /// Open the confirm dialog for the next tool awaiting an answer, if any
+    fn show_next_confirmation(&mut self) {
+        let Some(pending) = self.awaiting_confirmation().into_iter().next() else {
+            return;
+        };
+        self.agent.confirming_tool_id = Some(pending.tool.id.clone());
+        if pending.check == ToolExecutionCheck::RequiresConfirmation {
+            let body = format!("{} is waiting for confirmation", pending.tool.name);
+            self.ring_bell(DesktopEvent::ToolConfirmation, None);
+            self.notify_desktop(DesktopEvent::ToolConfirmation, "Tool needs confirmation", &body);
+            let tool_name = pending.tool.name.clone();
+            self.ui.confirm_dialog.show(pending, self.agent.tool_executor.working_dir());
+            let tools_config = &self.config_manager.app_config().tools;
+            if tools_config.confirm_countdown_secs > 0 && !self.agent.step_mode {
+                self.ui.confirm_dialog.start_countdown(
+                    tools_config.confirm_default_for(&tool_name),
+                    std::time::Duration::from_secs(tools_config.confirm_countdown_secs),
+                );
             }
+            self.ui.input_mode = InputMode::Confirm {
+                title: "Tool Execution".to_string(),
+                message: "Confirm tool use?".to_string(),
+            };
+        } else {
+            // Show dialog explaining why it can't run
+            self.ui.confirm_dialog.show(pending, self.agent.tool_executor.working_dir());
+            self.ui.input_mode = InputMode::Confirm {
+                title: "Tool Blocked".to_string(),
+                message: "Tool cannot execute".to_string(),
+            };
         }
     }
+
+    /// Answer a tool with a rejection error result
+    fn reject_tool(&mut self, tool_id: &str) {
+        self.agent.pending_tools.remove(tool_id);
+        // Update tool state in conversation viewer (TRC-016)
+        self.agent.conversation_viewer.reject_tool(tool_id);
+
+        // TP2-002-12: Bridge tool rejection to AgentEngine as a result
+        self.collect_tool_result(crate::llm::ToolResult {
+            tool_use_id: tool_id.to_string(),
+            content: crate::llm::ToolResultContent::Text("User rejected tool execution".to_string()),
+            is_error: true,
+        });
+    }
     
+    /// Remember an in-session provider/model switch as the default on restart
+    /// (`llm.persist_model_switch`)
+    fn record_model_switch(&mut self, provider: Option<&str>, model: Option<&str>) {
+        if self.config_manager.llm_config_mut().record_model_switch(provider, model) {
+            if let Err(e) = self.config_manager.save_llm_config() {
+                tracing::warn!("Failed to save model selection: {}", e);
+            }
+        }
+    }
+
+    /// Collect a tool result (executed, rejected or answered) for the open batch,
+    /// continuing the agent once every tool in the batch has a result
+    fn collect_tool_result(&mut self, result: crate::llm::ToolResult) {
+        let tool_use_id = result.tool_use_id.clone();
+        match self.agent.tool_batch.record(result) {
+            Some(results) => {
+                tracing::info!(
+                    "✅ ALL_TOOLS_COMPLETE: batch={}, sending {} results to engine",
+                    self.agent.tool_batch.id(), results.len()
+                );
+                self.agent.agent_engine.continue_after_tools(results);
+            }
+            None => tracing::info!(
+                "📥 TOOL_RESULT collected: id={}, batch={}, collected={}/{} expected",
+                tool_use_id, self.agent.tool_batch.id(), self.agent.tool_batch.collected(), self.agent.tool_batch.expected()
+            ),
+        }
+    }
+
     fn execute_tool(&mut self, pending: PendingToolUse) {
         // Clear any streaming buffer content - AgentEngine tracks conversation via thread
         self.agent.llm_response_buffer.clear();
@@ -802,6 +1252,8 @@ impl App {
         let working_dir = std::env::current_dir().unwrap_or_else(|_| {
             dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
         });
+        // Keep the file's contents from before the turn's first write to it
+        self.agent.turn_changes.snapshot(&tool, &working_dir);
         
         // We need to create a new executor for the async task
         let dangerous_mode = self.agent.tool_executor.registry().is_dangerous_mode();
@@ -809,6 +1261,8 @@ impl App {
         let mandrel_enabled = self.config_manager.mandrel_config().enabled;
         let lsp_manager = self.lsp_manager.clone();
         let lsp_enabled = self.config_manager.lsp_config().enabled;
+        let config_dir = self.config_manager.config_dir().to_path_buf();
+        let dry_run = self.agent.tool_executor.dry_run();
 
         // Spawn the tool execution with its own result channel
         let (result_tx, result_rx) = mpsc::unbounded_channel();
@@ -817,9 +1271,57 @@ impl App {
         tracing::info!("⚡ EXECUTE_TOOL: id={} name={}, active_receivers={}",
             tool_id, pending.tool.name, self.agent.tool_result_rxs.len());
 
+        // Sub-agents go through the manager so its concurrency and per-turn limits apply,
+        // and so cancelling the turn stops them
+        if matches!(tool.name.as_str(), "task" | "task_output")
+            && !self.agent.tool_executor.intercepts(&tool)
+            && self.agent.tool_executor.validate_input(&tool).is_ok()
+        {
+            let launch = match (self.agent.subagent_manager.as_mut(), self.keystore.as_ref()) {
+                (Some(subagents), _) if tool.name == "task_output" => {
+                    let task_id = tool.input["task_id"].as_str().unwrap_or_default();
+                    let block = tool.input["block"].as_bool().unwrap_or(true);
+                    Some(subagents.claim_task(task_id, block).map(SubagentLaunch::Inline))
+                }
+                (Some(subagents), Some(keystore)) if tool.name == "task" => {
+                    let prompt = tool.input["prompt"].as_str().unwrap_or_default();
+                    let agent_type = tool.input["agent_type"].as_str().unwrap_or("explore");
+                    let background = tool.input["run_in_background"].as_bool().unwrap_or(false);
+                    Some(subagents.start(keystore, agent_type, prompt, background))
+                }
+                _ => None,
+            };
+            if let Some(launch) = launch {
+                let tool_use_id = tool.id.clone();
+                tokio::spawn(async move {
+                    let outcome = match launch {
+                        Ok(SubagentLaunch::Background { task_id }) => Ok(format!(
+                            "Started background sub-agent {}; call task_output with this task_id for its result",
+                            task_id
+                        )),
+                        Ok(SubagentLaunch::Inline(run)) => run.await.map(|r| r.result),
+                        Err(e) => Err(e),
+                    };
+                    let (text, is_error) = match outcome {
+                        Ok(text) => (text, false),
+                        Err(e) => (e.to_string(), true),
+                    };
+                    let _ = result_tx.send(Ok(crate::llm::ToolResult {
+                        tool_use_id,
+                        content: crate::llm::ToolResultContent::Text(text),
+                        is_error,
+                    }));
+                });
+                self.agent.pending_tools.insert(tool_id, pending);
+                return;
+            }
+        }
+
         tokio::spawn(async move {
             let mut executor = ToolExecutor::new(working_dir);
             executor.set_dangerous_mode(dangerous_mode);
+            executor.set_config_dir(config_dir);
+            executor.set_dry_run(dry_run);
             // Set Mandrel client for cross-session memory tools
             if mandrel_enabled {
                 executor.set_mandrel_client(mandrel_client);
@@ -869,7 +1371,10 @@ impl App {
                 } else {
                     // TRC-023: Notify on stream disconnect
                     let msg = reason.as_deref().unwrap_or("Disconnected");
-                    self.ui.notification_manager.info_with_message("Stream Disconnected", format!("{}: {}", stream_name, msg));
+                    let body = format!("{}: {}", stream_name, msg);
+                    self.notify_desktop(DesktopEvent::StreamDisconnect, "Stream disconnected", &body);
+                    self.ui.notification_manager.info_with_message("Stream Disconnected", body);
+                    self.stream_manager.connect_queued();
                 }
             }
             StreamEvent::Data(id, data) => {
@@ -897,6 +1402,7 @@ impl App {
                 } else {
                     // TRC-023: Notify on stream error (only if not reconnecting)
                     self.ui.notification_manager.error_with_message("Stream Error", format!("{}: {}", stream_name, msg));
+                    self.stream_manager.connect_queued();
                 }
             }
             StreamEvent::StateChanged(id, state) => {
@@ -923,6 +1429,7 @@ impl App {
                     "Connection Failed",
                     format!("{}: Max retries reached. Use 'r' to retry manually.", stream_name)
                 );
+                self.stream_manager.connect_queued();
             }
         }
     }
@@ -953,7 +1460,7 @@ impl App {
     /// TRC-028: Reload streams from configuration file and update menu
     /// This is called when streams.toml changes (hot-reload) or when StreamRefresh is triggered
     fn reload_streams_from_config(&mut self) {
-        let config = StreamsConfig::load();
+        let config = StreamsConfig::load(self.config_manager.config_dir());
         let old_count = self.stream_manager.clients().len();
         
         self.stream_manager.load_streams(&config);
@@ -1094,6 +1601,7 @@ impl App {
                     ContextMenuItem::new("Paste", Action::Paste).with_shortcut("Ctrl+V"),
                     ContextMenuItem::separator(),
                     ContextMenuItem::new("Clear Scrollback", Action::ScrollToTop),
+                    ContextMenuItem::new("Reset Terminal", Action::TerminalReset),
                     ContextMenuItem::separator(),
                     ContextMenuItem::new("New Tab", Action::TabCreate).with_shortcut("Ctrl+T"),
                 ]
@@ -1106,11 +1614,18 @@ impl App {
                     ContextMenuItem::separator(),
                     ContextMenuItem::new("Toggle Auto-scroll", Action::LogViewerToggleAutoScroll)
                         .with_shortcut("a"),
+                    ContextMenuItem::new("Toggle Wrap", Action::LogViewerToggleWrap)
+                        .with_shortcut("w"),
+                    ContextMenuItem::new("Toggle Timestamps", Action::LogViewerToggleTimestamps)
+                        .with_shortcut("t"),
                     ContextMenuItem::separator(),
                     ContextMenuItem::new("Scroll to Top", Action::LogViewerScrollToTop)
                         .with_shortcut("g"),
                     ContextMenuItem::new("Scroll to Bottom", Action::LogViewerScrollToBottom)
                         .with_shortcut("G"),
+                    ContextMenuItem::separator(),
+                    ContextMenuItem::new("Open in Pager", Action::LogViewerOpenPager)
+                        .with_shortcut("o"),
                 ]
             }
             
@@ -1123,6 +1638,8 @@ impl App {
                     ContextMenuItem::separator(),
                     ContextMenuItem::new("Expand All Tools", Action::ToolCallExpandAll),
                     ContextMenuItem::new("Collapse All Tools", Action::ToolCallCollapseAll),
+                    ContextMenuItem::new("View Full Tool Output", Action::ToolCallViewOutput)
+                        .with_shortcut("o"),
                 ]
             }
 
@@ -1233,32 +1750,71 @@ impl App {
     fn handle_settings_test_key(&mut self) {
         if let Some(provider) = self.settings_editor.selected_provider() {
             let provider = provider.to_string();
+            if !crate::components::settings_editor::needs_api_key(&provider) {
+                self.ui.notification_manager.info(format!("{} runs locally and needs no API key", provider));
+                return;
+            }
             self.settings_editor.start_key_test(&provider);
             
-            // Check if provider has a key configured
-            let has_key = self.keystore.as_ref()
-                .map(|ks| {
-                    let key_id = crate::config::KeyId::from_provider_str(&provider);
-                    ks.exists(&key_id).unwrap_or(false)
-                })
-                .unwrap_or(false);
-            
-            if !has_key {
+            if self.keystore.as_ref().is_some_and(|ks| !ks.is_unlocked()) {
                 self.settings_editor.set_key_test_result(
                     &provider,
                     false,
-                    Some("No API key configured".to_string()),
+                    Some("Keystore is locked; unlock it to test the key".to_string()),
                 );
                 return;
             }
-            
-            // For now, just verify the key exists - actual API test would require async
-            // Future: Spawn async task to actually test the API endpoint
-            self.settings_editor.set_key_test_result(&provider, true, None);
-            self.ui.notification_manager.success(format!("{} key verified", provider));
+            let key_id = crate::config::KeyId::from_provider_str(&provider);
+            let api_key = self.keystore.as_ref()
+                .and_then(|ks| ks.get(&key_id).ok().flatten());
+            let Some(api_key) = api_key else {
+                self.settings_editor.set_key_test_result(
+                    &provider,
+                    false,
+                    Some("No API key configured".to_string()),
+                );
+                return;
+            };
+
+            // Send a minimal request to the provider; the result comes back
+            // through the event loop as SettingsTestKeyResult
+            let api_key = api_key.expose().to_string();
+            let llm_config = self.config_manager.llm_config().clone();
+            let tx = self.key_test_tx.clone();
+            tokio::spawn(async move {
+                let result = crate::llm::manager::test_api_key(&provider, &api_key, &llm_config).await;
+                let _ = tx.send(Action::SettingsTestKeyResult {
+                    provider,
+                    success: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                });
+            });
         }
     }
     
+    /// Validate and apply a provider base URL override from the settings editor
+    fn handle_settings_base_url_entered(&mut self, provider: &str, url: &str) {
+        let base_url = match crate::config::normalize_base_url(url) {
+            Ok(base_url) => base_url,
+            Err(e) => {
+                self.ui.notification_manager.error_with_message("Base URL not changed", e);
+                return;
+            }
+        };
+
+        self.config_manager.llm_config_mut().set_base_url_for_provider(provider, base_url.clone());
+        self.settings_editor.set_provider_base_url(provider, base_url.clone());
+        if provider != "ollama" {
+            self.agent.agent_engine.llm_manager_mut().set_base_url(provider, base_url.clone());
+        }
+
+        let title = match base_url {
+            Some(url) => format!("{} now uses {}", provider, url),
+            None => format!("{} uses its default endpoint", provider),
+        };
+        self.ui.notification_manager.info(title);
+    }
+
     /// Handle settings save request
     fn handle_settings_save(&mut self) {
         let config = self.settings_editor.config().clone();
@@ -1266,6 +1822,14 @@ impl App {
         // Update AgentEngine with new settings
         self.agent.agent_engine.set_provider(&config.defaults.provider);
         self.agent.agent_engine.set_model(&config.defaults.model);
+        self.agent.agent_engine.set_fallback_models(config.fallback_models.clone());
+        self.agent.agent_engine.set_fallback_providers(config.fallback_provider_models());
+        self.agent.agent_engine.set_max_retries(config.max_retries);
+        self.agent.agent_engine.set_debug_capture(config.debug_capture);
+        self.agent.agent_engine.set_temperature(Some(config.parameters.temperature));
+        self.agent.agent_engine.set_max_tokens(Some(config.parameters.max_tokens));
+        self.agent.agent_engine.set_thinking(config.thinking.enabled, config.thinking.budget_tokens);
+        self.agent.agent_engine.set_prompt_cache(config.prompt_cache);
 
         // Update config manager with new settings
         *self.config_manager.llm_config_mut() = config;
@@ -1282,9 +1846,44 @@ impl App {
     }
 }
 
+impl App {
+    /// Raise a desktop notification if `[notifications]` enables `event` and
+    /// the terminal isn't known to be focused
+    fn notify_desktop(&self, event: DesktopEvent, title: &str, body: &str) {
+        let config = &self.config_manager.app_config().notifications;
+        if desktop_notify::should_notify(config, event, self.ui.terminal_focused) {
+            desktop_notify::send(title, body);
+        }
+    }
+
+    /// Ring the bell (terminal BEL or `notifications.bell_command`) if enabled for `event`
+    fn ring_bell(&mut self, event: DesktopEvent, turn_duration: Option<std::time::Duration>) {
+        let config = &self.config_manager.app_config().notifications;
+        if !desktop_notify::should_ring(config, event, turn_duration) {
+            return;
+        }
+        match config.bell_command.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(command) => desktop_notify::play_sound(command),
+            None => {
+                use std::io::Write;
+                let backend = self.pty.terminal.backend_mut();
+                let _ = backend.write_all(b"\x07").and_then(|_| backend.flush());
+            }
+        }
+    }
+}
+
 impl Drop for App {
     fn drop(&mut self) {
+        pty_state::pop_keyboard_enhancement();
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableBracketedPaste);
+        let _ = execute!(
+            io::stdout(),
+            SetCursorStyle::DefaultUserShape,
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableBracketedPaste,
+            DisableFocusChange
+        );
     }
 }
@@ -2,31 +2,61 @@
 // Contains terminal, tab management, and PTY event receivers
 
 use std::io::{self, Stdout};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crossterm::{
-    event::{EnableBracketedPaste, EnableMouseCapture},
+    event::{
+        EnableBracketedPaste, EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{enable_raw_mode, EnterAlternateScreen},
+    terminal::{enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 use tokio::sync::mpsc;
 
 use crate::error::{Result, RidgeError};
 use crate::event::PtyEvent;
+use crate::pty::CursorStyle;
 use crate::tabs::{TabId, TabManager};
 
+/// Set while keyboard enhancement flags are pushed, so teardown (including
+/// the panic hook) knows to pop them
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Ask the terminal to report modified keys such as Ctrl+Enter unambiguously.
+/// Returns false when the terminal doesn't support it.
+fn push_keyboard_enhancement(stdout: &mut Stdout) -> bool {
+    let pushed = supports_keyboard_enhancement().unwrap_or(false)
+        && execute!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)).is_ok();
+    KEYBOARD_ENHANCED.store(pushed, Ordering::SeqCst);
+    pushed
+}
+
+/// Undo `push_keyboard_enhancement`, if it took effect
+pub fn pop_keyboard_enhancement() {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+}
+
 pub struct PtyState {
     pub terminal: Terminal<CrosstermBackend<Stdout>>,
     pub tab_manager: TabManager,
     pub pty_receivers: Vec<mpsc::UnboundedReceiver<(TabId, PtyEvent)>>,
+    /// Host cursor shape last sent to the real terminal (None = user default)
+    pub host_cursor_style: Option<CursorStyle>,
+    /// The terminal reports Ctrl+Enter as such (keyboard enhancement pushed)
+    pub keyboard_enhanced: bool,
 }
 
 impl PtyState {
     pub fn new(term_cols: u16, term_rows: u16) -> Result<Self> {
         enable_raw_mode().map_err(|e| RidgeError::Terminal(e.to_string()))?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste)
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableBracketedPaste, EnableFocusChange)
             .map_err(|e| RidgeError::Terminal(e.to_string()))?;
+        let keyboard_enhanced = push_keyboard_enhancement(&mut stdout);
 
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend).map_err(|e| RidgeError::Terminal(e.to_string()))?;
@@ -38,13 +68,16 @@ impl PtyState {
             terminal,
             tab_manager,
             pty_receivers: Vec::new(),
+            host_cursor_style: None,
+            keyboard_enhanced,
         })
     }
 
     /// Calculate terminal size from layout area
     pub fn calculate_terminal_size(area: Rect) -> (u16, u16) {
-        let terminal_width = (area.width * 2 / 3).saturating_sub(2);
-        let terminal_height = area.height.saturating_sub(2);
+        // Never resize the PTY to zero; tiny windows still get a 1x1 grid
+        let terminal_width = (area.width * 2 / 3).saturating_sub(2).max(1);
+        let terminal_height = area.height.saturating_sub(2).max(1);
         (terminal_width, terminal_height)
     }
 
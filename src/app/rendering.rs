@@ -1,20 +1,40 @@
 // Rendering - draw() method and UI layout helpers
 // Extracted as part of REFACTOR-P5.3
 
+use crossterm::{cursor::SetCursorStyle, execute};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
 use super::App;
+use crate::action::OverlayKind;
+use crate::agent::usage::segment_footer;
 use crate::agent::ContextStats;
+use crate::components::pager::PagerKind;
+use crate::components::plan_panel::PLAN_PANEL_WIDTH;
 use crate::components::Component;
 use crate::config::Theme;
 use crate::error::{Result, RidgeError};
 use crate::input::focus::FocusArea;
 use crate::llm::Message;
+use crate::pty::{CursorShape, CursorStyle};
 use crate::tabs::TabBar;
 
+/// Smallest terminal the full layout is drawn at
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+
 impl App {
     /// Main drawing method - renders entire UI
     pub(super) fn draw(&mut self) -> Result<()> {
+        // Below the minimum size the pane math degenerates; show a notice until resized
+        let size = self.pty.terminal.size().unwrap_or_default();
+        if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+            let theme = self.config_manager.theme().clone();
+            self.pty.terminal
+                .draw(|frame| render_too_small(frame, &theme))
+                .map_err(|e| RidgeError::Terminal(e.to_string()))?;
+            return Ok(());
+        }
+
         // Pre-compute focus booleans to avoid cloning FocusManager
         let focus_terminal = self.ui.focus.is_focused(FocusArea::Terminal);
         let focus_stream_viewer = self.ui.focus.is_focused(FocusArea::StreamViewer);
@@ -28,14 +48,23 @@ impl App {
         let streams: Vec<_> = self.stream_manager.clients().to_vec();
         let show_confirm = self.ui.confirm_dialog.is_visible();
         let show_palette = self.ui.command_palette.is_visible();
+        let show_model_picker = self.ui.model_picker.is_visible();
         let show_thread_picker = self.agent.thread_picker.is_visible();
         let show_thread_rename = self.agent.thread_rename_buffer.is_some();
         let thread_rename_text = self.agent.thread_rename_buffer.clone().unwrap_or_default();
+        let thread_tags_text = self.agent.thread_tags_buffer.clone();
         let show_ask_user = self.ui.ask_user_dialog.is_visible();
+        let action_prompt = self.ui.action_prompt.as_ref().map(|p| (p.title.clone(), p.lines.clone(), p.help()));
+        let show_pager = self.ui.pager.is_visible();
+        // The system prompt view follows persona, redaction and working-dir changes
+        if show_pager && self.ui.pager.kind() == PagerKind::SystemPrompt {
+            let prompt = self.agent.agent_engine.resolved_system_prompt();
+            self.ui.pager.refresh(&prompt);
+        }
         let show_context_menu = self.ui.context_menu.is_visible();
         let has_notifications = self.ui.notification_manager.has_notifications();
         let _show_tabs = self.pty.tab_manager.count() > 1; // Kept for potential future use
-        let show_conversation = self.agent.show_conversation || !self.agent.llm_response_buffer.is_empty() || !self.agent.thinking_buffer.is_empty();
+        let show_conversation = self.agent.conversation_visible(self.config_manager.app_config().conversation.auto_open);
         let show_stream_viewer = self.show_stream_viewer;
         let show_log_viewer = self.show_log_viewer;
         let show_config_panel = self.show_config_panel;
@@ -54,6 +83,29 @@ impl App {
         } else {
             Vec::new()
         };
+        // Token/cost footers, attached to the last message of each segment
+        let pricing = &self.config_manager.llm_config().pricing;
+        let message_footers: Vec<Option<String>> = match self.agent.agent_engine.current_thread() {
+            Some(thread) => {
+                thread.segments().iter()
+                    .flat_map(|segment| {
+                        let footer = segment_footer(segment, pricing, &self.agent.model_catalog);
+                        let mut footers = vec![None; segment.messages.len()];
+                        if let Some(last) = footers.last_mut() {
+                            *last = footer;
+                        }
+                        footers
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.agent.conversation_viewer.set_message_footers(message_footers);
+        let thread_cost = self.agent.agent_engine.current_thread()
+            .filter(|thread| !thread.usage.is_empty())
+            .and_then(|thread| thread.estimated_cost(pricing, &self.agent.model_catalog));
+        self.agent.conversation_viewer.set_thread_cost(thread_cost);
+        let plan = self.agent.agent_engine.current_thread().map(|t| t.plan.clone()).unwrap_or_default();
         let streaming_buffer = self.agent.llm_response_buffer.clone();
         // TRC-017: Clone thinking buffer for rendering
         let thinking_buffer = self.agent.thinking_buffer.clone();
@@ -78,6 +130,11 @@ impl App {
         self.ui.tab_bar_area = computed_tab_bar_area;
         // TRC-024: Store content area for pane resize mouse hit-testing
         self.ui.content_area = computed_content_area;
+        // Overlay areas for this frame (tiled when several are open)
+        let overlay_areas = self.ui.overlay_layout.tiled_areas(computed_content_area);
+        let overlay_area = |kind: OverlayKind, content: Rect| {
+            overlay_areas.iter().find(|(k, _)| *k == kind).map(|(_, area)| *area).unwrap_or(content)
+        };
 
         self.pty.terminal
             .draw(|frame| {
@@ -103,6 +160,18 @@ impl App {
                 if show_status_bar {
                     let tab_bar = TabBar::from_manager_themed(&self.pty.tab_manager, &theme)
                         .dangerous_mode(self.agent.dangerous_mode)
+                        .step_mode(self.agent.step_mode)
+                        .dry_run(self.agent.dry_run)
+                        .compact_mode(self.ui.pane_layout.compact)
+                        .auto_continue(self.agent.auto_continue)
+                        .agent_paused(
+                            self.agent.agent_engine.state() == crate::agent::AgentState::Paused,
+                            self.agent.agent_engine.pause_pending(),
+                        )
+                        .background_response(!show_conversation && self.agent.is_streaming_response())
+                        .subagents(self.agent.subagent_manager.as_ref().map(|m| m.activity()).unwrap_or_default())
+                        .subagent_status(self.agent.subagent_monitor.status_line())
+                        .rate_limit(self.agent.agent_engine.rate_limit().and_then(|s| s.summary(std::time::Instant::now())))
                         .input_mode(self.ui.input_mode.clone());
                     frame.render_widget(tab_bar, tab_bar_area);
                 }
@@ -142,9 +211,26 @@ impl App {
                     // Split conversation area: messages on top, chat input at bottom
                     let conv_chunks = Layout::default()
                         .direction(Direction::Vertical)
-                        .constraints([Constraint::Min(5), Constraint::Length(6)])
+                        .constraints([
+                            Constraint::Min(5),
+                            Constraint::Length(self.agent.chat_input.height_for(left_chunks[1].width)),
+                        ])
                         .split(left_chunks[1]);
 
+                    // Plan sidebar to the right of the messages
+                    let (messages_area, plan_area) = if self.agent.plan_panel.shown_for(&plan, conv_chunks[0].width) {
+                        let chunks = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Min(0), Constraint::Length(PLAN_PANEL_WIDTH)])
+                            .split(conv_chunks[0]);
+                        (chunks[0], Some(chunks[1]))
+                    } else {
+                        (conv_chunks[0], None)
+                    };
+                    if let Some(plan_area) = plan_area {
+                        self.agent.plan_panel.render(frame, plan_area, &plan, &theme);
+                    }
+
                     // TRC-017: Pass thinking_buffer for extended thinking display
                     // Pass model info for header display
                     let model_info = {
@@ -173,18 +259,37 @@ impl App {
                                     tokens
                                 }
                             };
+                            let compact = self.agent.agent_engine.compact_tool_descriptions();
+                            let tools_tokens = match self.agent.cached_tool_tokens {
+                                Some((ref m, c, tokens)) if m == model && c == compact => tokens,
+                                _ => {
+                                    let tokens = self.agent.agent_engine.tool_definition_tokens(model);
+                                    self.agent.cached_tool_tokens = Some((model.to_string(), compact, tokens));
+                                    tokens
+                                }
+                            };
+                            let prompt_hash = self.agent.agent_engine.system_prompt_hash();
+                            let system_tokens = match self.agent.cached_system_tokens {
+                                Some((ref m, hash, tokens)) if m == model && hash == prompt_hash => tokens,
+                                _ => {
+                                    let tokens = self.agent.agent_engine.system_prompt_tokens(model);
+                                    self.agent.cached_system_tokens = Some((model.to_string(), prompt_hash, tokens));
+                                    tokens
+                                }
+                            };
                             // Budget = context window - default output tokens - 2% safety
                             let safety = model_info.max_context_tokens / 50; // 2%
                             let budget = model_info.max_context_tokens
                                 .saturating_sub(model_info.default_max_output_tokens)
                                 .saturating_sub(safety);
-                            Some(ContextStats::new(tokens_used, budget, false, messages.len()))
+                            Some(ContextStats::new(system_tokens + tools_tokens + tokens_used, budget, false, messages.len())
+                                .with_breakdown(system_tokens, tools_tokens, tokens_used))
                         }
                     };
 
                     self.agent.conversation_viewer.render_conversation(
                         frame,
-                        conv_chunks[0],
+                        messages_area,
                         focus_stream_viewer, // Conversation history focus
                         &messages,
                         &streaming_buffer,
@@ -214,7 +319,7 @@ impl App {
                     let conv_inner = {
                         let block = ratatui::widgets::Block::default()
                             .borders(ratatui::widgets::Borders::ALL);
-                        block.inner(conv_chunks[0])
+                        block.inner(messages_area)
                     };
                     self.agent.conversation_viewer.set_inner_area(conv_inner);
 
@@ -227,7 +332,7 @@ impl App {
                     self.agent.chat_input.set_inner_area(chat_input_inner);
 
                     // Save conversation area for mouse hit-testing
-                    self.ui.conversation_area = conv_chunks[0];
+                    self.ui.conversation_area = messages_area;
                     // Save chat input area for mouse hit-testing (paste routing and selection)
                     self.ui.chat_input_area = conv_chunks[1];
                 } else {
@@ -256,44 +361,58 @@ impl App {
                     }
                 }
 
-                self.process_monitor.render(
-                    frame,
-                    right_chunks[0],
-                    focus_process_monitor,
-                    &theme,
-                );
-                self.ui.menu.render_with_streams(
-                    frame,
-                    right_chunks[1],
-                    focus_menu,
-                    &streams,
-                    &theme,
-                );
+                // Compact mode: the right panes only show, as an overlay on the
+                // right side, while focused
+                let (proc_area, menu_area) = if self.ui.pane_layout.compact {
+                    let panel = self.ui.pane_layout.compact_overlay_area(content_area);
+                    (
+                        if focus_process_monitor { panel } else { Rect::default() },
+                        if focus_menu { panel } else { Rect::default() },
+                    )
+                } else {
+                    (right_chunks[0], right_chunks[1])
+                };
+
+                if !proc_area.is_empty() {
+                    frame.render_widget(ratatui::widgets::Clear, proc_area);
+                    self.process_monitor.render(
+                        frame,
+                        proc_area,
+                        focus_process_monitor,
+                        &theme,
+                    );
+                }
+                if !menu_area.is_empty() {
+                    frame.render_widget(ratatui::widgets::Clear, menu_area);
+                    self.ui.menu.render_with_streams(
+                        frame,
+                        menu_area,
+                        focus_menu,
+                        &streams,
+                        self.stream_manager.pool_status(),
+                        &theme,
+                    );
+                }
 
                 let proc_inner = {
                     let block = ratatui::widgets::Block::default()
                         .borders(ratatui::widgets::Borders::ALL);
-                    block.inner(right_chunks[0])
+                    block.inner(proc_area)
                 };
                 self.process_monitor.set_inner_area(proc_inner);
 
                 let menu_inner = {
                     let block = ratatui::widgets::Block::default()
                         .borders(ratatui::widgets::Borders::ALL);
-                    block.inner(right_chunks[1])
+                    block.inner(menu_area)
                 };
                 self.ui.menu.set_inner_area(menu_inner);
                 
                 // Render overlays (in order of z-index)
                 
-                // Stream viewer overlay - centered modal dialog
+                // Stream viewer overlay - position/size from overlay layout
                 if show_stream_viewer {
-                    // Calculate centered dialog size (70% width, 70% height for stream content)
-                    let dialog_width = (size.width * 70 / 100).clamp(60, 120);
-                    let dialog_height = (size.height * 70 / 100).clamp(20, 40);
-                    let dialog_x = (size.width.saturating_sub(dialog_width)) / 2;
-                    let dialog_y = (size.height.saturating_sub(dialog_height)) / 2;
-                    let stream_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+                    let stream_area = overlay_area(OverlayKind::StreamViewer, content_area);
 
                     // Clear the area behind for readability
                     frame.render_widget(ratatui::widgets::Clear, stream_area);
@@ -310,14 +429,9 @@ impl App {
                     );
                 }
                 
-                // Log viewer overlay (TRC-013) - centered modal dialog
+                // Log viewer overlay (TRC-013) - position/size from overlay layout
                 if show_log_viewer {
-                    // Calculate centered dialog size (70% width, 70% height for log content)
-                    let dialog_width = (size.width * 70 / 100).clamp(60, 120);
-                    let dialog_height = (size.height * 70 / 100).clamp(20, 40);
-                    let dialog_x = (size.width.saturating_sub(dialog_width)) / 2;
-                    let dialog_y = (size.height.saturating_sub(dialog_height)) / 2;
-                    let log_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+                    let log_area = overlay_area(OverlayKind::LogViewer, content_area);
 
                     // Clear the area behind for readability
                     frame.render_widget(ratatui::widgets::Clear, log_area);
@@ -337,14 +451,9 @@ impl App {
                     self.log_viewer.set_inner_area(log_inner);
                 }
                 
-                // Config panel overlay (TRC-014) - centered modal dialog
+                // Config panel overlay (TRC-014) - position/size from overlay layout
                 if show_config_panel {
-                    // Calculate centered dialog size (60% width, 60% height, clamped)
-                    let dialog_width = (size.width * 60 / 100).clamp(50, 100);
-                    let dialog_height = (size.height * 60 / 100).clamp(15, 35);
-                    let dialog_x = (size.width.saturating_sub(dialog_width)) / 2;
-                    let dialog_y = (size.height.saturating_sub(dialog_height)) / 2;
-                    let config_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+                    let config_area = overlay_area(OverlayKind::ConfigPanel, content_area);
 
                     // Clear the area behind for readability
                     frame.render_widget(ratatui::widgets::Clear, config_area);
@@ -430,6 +539,26 @@ impl App {
                     }
                 }
 
+                // Pager overlay (full tool/log output) - centered modal dialog
+                if show_pager {
+                    let dialog_width = (size.width * 80 / 100).clamp(60, 160);
+                    let dialog_height = (size.height * 80 / 100).clamp(20, 60);
+                    let dialog_x = (size.width.saturating_sub(dialog_width)) / 2;
+                    let dialog_y = (size.height.saturating_sub(dialog_height)) / 2;
+                    let pager_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+
+                    // Size the pager before rendering so wrap and scroll match this frame
+                    let pager_inner = {
+                        let block = ratatui::widgets::Block::default()
+                            .borders(ratatui::widgets::Borders::ALL);
+                        block.inner(pager_area)
+                    };
+                    self.ui.pager.set_inner_area(pager_inner);
+
+                    frame.render_widget(ratatui::widgets::Clear, pager_area);
+                    self.ui.pager.render(frame, pager_area, true, &theme);
+                }
+
                 if show_confirm {
                     self.ui.confirm_dialog.render(frame, size, &theme);
                 }
@@ -438,6 +567,10 @@ impl App {
                     self.ui.command_palette.render(frame, size, &theme);
                 }
 
+                if show_model_picker {
+                    self.ui.model_picker.render(frame, size, &theme);
+                }
+
                 // P2-003: Thread picker overlay
                 if show_thread_picker {
                     self.agent.thread_picker.render(frame, size, &theme);
@@ -445,7 +578,28 @@ impl App {
 
                 // P2-003: Thread rename dialog overlay
                 if show_thread_rename {
-                    Self::render_thread_rename_dialog(frame, size, &theme, &thread_rename_text);
+                    Self::render_thread_text_dialog(
+                        frame,
+                        size,
+                        &theme,
+                        " Rename Thread ",
+                        &thread_rename_text,
+                        "Enter to confirm, Esc to cancel",
+                    );
+                }
+                if let Some(ref tags) = thread_tags_text {
+                    Self::render_thread_text_dialog(
+                        frame,
+                        size,
+                        &theme,
+                        " Thread Tags ",
+                        tags,
+                        "Space-separated · Enter to save, Esc to cancel",
+                    );
+                }
+
+                if let Some((ref title, ref lines, ref help)) = action_prompt {
+                    Self::render_action_prompt(frame, size, &theme, title, lines, help);
                 }
 
                 // T2.4: Ask user dialog overlay
@@ -465,15 +619,39 @@ impl App {
             })
             .map_err(|e| RidgeError::Terminal(e.to_string()))?;
 
+        // Pass the nested app's underline/bar cursor through to the host terminal
+        let host_cursor_style = if focus_terminal {
+            self.pty.tab_manager.get_pty_session(active_tab_id)
+                .map(|session| session.terminal().cursor_style())
+                .filter(|style| style.shape != CursorShape::Block)
+                .map(|style| CursorStyle { blink: style.blink && theme.terminal.cursor_blink, ..style })
+        } else {
+            None
+        };
+        if host_cursor_style != self.pty.host_cursor_style {
+            let command = match host_cursor_style {
+                Some(CursorStyle { shape: CursorShape::Underline, blink: true }) => SetCursorStyle::BlinkingUnderScore,
+                Some(CursorStyle { shape: CursorShape::Underline, blink: false }) => SetCursorStyle::SteadyUnderScore,
+                Some(CursorStyle { shape: CursorShape::Bar, blink: true }) => SetCursorStyle::BlinkingBar,
+                Some(CursorStyle { shape: CursorShape::Bar, blink: false }) => SetCursorStyle::SteadyBar,
+                _ => SetCursorStyle::DefaultUserShape,
+            };
+            execute!(self.pty.terminal.backend_mut(), command)
+                .map_err(|e| RidgeError::Terminal(e.to_string()))?;
+            self.pty.host_cursor_style = host_cursor_style;
+        }
+
         Ok(())
     }
 
-    /// Render the thread rename dialog overlay
-    fn render_thread_rename_dialog(
+    /// Render a single-line thread edit dialog overlay (rename, tags)
+    fn render_thread_text_dialog(
         frame: &mut ratatui::Frame,
         size: Rect,
         theme: &Theme,
-        thread_rename_text: &str,
+        title: &str,
+        text: &str,
+        help: &str,
     ) {
         use ratatui::widgets::{Block, Borders, Clear, Paragraph};
         use ratatui::text::{Line, Span};
@@ -492,7 +670,7 @@ impl App {
 
         // Dialog block
         let block = Block::default()
-            .title(" Rename Thread ")
+            .title(title)
             .title_style(Style::default().fg(theme.command_palette.border.to_color()).add_modifier(Modifier::BOLD))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.command_palette.border.to_color()));
@@ -502,16 +680,98 @@ impl App {
 
         // Input line with cursor
         let input_line = Line::from(vec![
-            Span::styled(thread_rename_text, Style::default().fg(theme.command_palette.input_fg.to_color())),
+            Span::styled(text, Style::default().fg(theme.command_palette.input_fg.to_color())),
             Span::styled("▎", Style::default().fg(theme.colors.primary.to_color())),
         ]);
         frame.render_widget(Paragraph::new(input_line), inner);
 
         // Help text below
         let help_area = Rect::new(inner.x, inner.y + 1, inner.width, 1);
-        let help_text = Paragraph::new("Enter to confirm, Esc to cancel")
+        let help_text = Paragraph::new(help)
+            .style(Style::default().fg(theme.command_palette.description_fg.to_color()))
+            .alignment(Alignment::Center);
+        frame.render_widget(help_text, help_area);
+    }
+
+    /// Render a yes/no prompt listing what the action will do
+    fn render_action_prompt(frame: &mut ratatui::Frame, size: Rect, theme: &Theme, title: &str, lines: &[String], help: &str) {
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+        use ratatui::style::{Modifier, Style};
+        use ratatui::layout::Alignment;
+
+        let widest = lines.iter().map(|l| l.chars().count()).chain([help.chars().count()]).max().unwrap_or(0) as u16;
+        let dialog_width = (widest + 4).clamp(40, 80).min(size.width.saturating_sub(4));
+        let dialog_height = (lines.len() as u16 + 4).min(size.height.saturating_sub(2));
+        let dialog_x = (size.width.saturating_sub(dialog_width)) / 2;
+        let dialog_y = (size.height.saturating_sub(dialog_height)) / 2;
+        let dialog_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+
+        frame.render_widget(Clear, dialog_area);
+
+        let block = Block::default()
+            .title(title)
+            .title_style(Style::default().fg(theme.colors.warning.to_color()).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.colors.warning.to_color()));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let body_area = Rect::new(inner.x, inner.y, inner.width, inner.height.saturating_sub(1));
+        let body = lines.join("\n");
+        frame.render_widget(
+            Paragraph::new(body).style(Style::default().fg(theme.colors.foreground.to_color())),
+            body_area,
+        );
+
+        let help_area = Rect::new(inner.x, inner.y + inner.height.saturating_sub(1), inner.width, 1);
+        let help_text = Paragraph::new(help)
             .style(Style::default().fg(theme.command_palette.description_fg.to_color()))
             .alignment(Alignment::Center);
         frame.render_widget(help_text, help_area);
     }
 }
+
+/// "Terminal too small (need ≥ 40x10)", centered and wrapped to whatever fits
+fn render_too_small(frame: &mut ratatui::Frame, theme: &Theme) {
+    use ratatui::layout::Alignment;
+    use ratatui::style::Style;
+    use ratatui::widgets::{Paragraph, Wrap};
+
+    let area = frame.area();
+    let message = format!("Terminal too small (need ≥ {}x{})", MIN_WIDTH, MIN_HEIGHT);
+    let top = area.height.saturating_sub(1) / 2;
+    let text_area = Rect::new(area.x, area.y + top, area.width, area.height - top);
+    frame.render_widget(
+        Paragraph::new(message)
+            .style(Style::default().fg(theme.colors.warning.to_color()))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        text_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_too_small_notice_renders_at_any_size() {
+        let theme = Theme::default();
+        for (width, height) in [(1, 1), (20, 5), (MIN_WIDTH - 1, MIN_HEIGHT)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|frame| render_too_small(frame, &theme)).unwrap();
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(39, 5)).unwrap();
+        terminal.draw(|frame| render_too_small(frame, &theme)).unwrap();
+        let text: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Terminal too small"));
+    }
+
+    #[test]
+    fn test_pty_size_never_zero() {
+        assert_eq!(crate::app::pty_state::PtyState::calculate_terminal_size(Rect::new(0, 0, 1, 1)), (1, 1));
+    }
+}
@@ -0,0 +1,193 @@
+// ToolBatch - Collects tool results for one round of tool calls
+//
+// AgentEngine requests every tool of a round together, but results come back
+// from independent tasks in any order (or as rejections / ask_user answers).
+// The batch matches each result to its tool_use_id and hands back the full set,
+// in request order, exactly once when the last expected result arrives.
+
+use std::collections::HashMap;
+
+use crate::llm::ToolResult;
+
+/// An answer the user gave for every remaining confirmation of one batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchDecision {
+    ApproveAll,
+    RejectAll,
+}
+
+#[derive(Debug, Default)]
+pub struct ToolBatch {
+    /// Incremented each time a new batch opens
+    id: u64,
+    /// Tool ids of the open batch, in request order (empty = no open batch)
+    requested: Vec<String>,
+    results: HashMap<String, ToolResult>,
+    /// Approve/reject-all answer; never outlives the batch it was given for
+    decision: Option<BatchDecision>,
+}
+
+impl ToolBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a tool to the open batch, opening a new one if needed.
+    /// Returns false if the tool is already part of the batch.
+    pub fn request(&mut self, tool_id: &str) -> bool {
+        if self.contains(tool_id) {
+            return false;
+        }
+        if self.requested.is_empty() {
+            self.id += 1;
+            self.results.clear();
+            self.decision = None;
+        }
+        self.requested.push(tool_id.to_string());
+        true
+    }
+
+    pub fn contains(&self, tool_id: &str) -> bool {
+        self.requested.iter().any(|id| id == tool_id)
+    }
+
+    /// Tool ids of the open batch, in request order
+    pub fn requested(&self) -> &[String] {
+        &self.requested
+    }
+
+    pub fn decision(&self) -> Option<BatchDecision> {
+        self.decision
+    }
+
+    /// Apply `decision` to the rest of the open batch (ignored when none is open)
+    pub fn set_decision(&mut self, decision: BatchDecision) {
+        if !self.requested.is_empty() {
+            self.decision = Some(decision);
+        }
+    }
+
+    pub fn has_result(&self, tool_id: &str) -> bool {
+        self.results.contains_key(tool_id)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn expected(&self) -> usize {
+        self.requested.len()
+    }
+
+    pub fn collected(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Record a result. Once every requested tool has one, returns them all in
+    /// request order and closes the batch. Results for tools outside the open
+    /// batch, and repeats for a tool already answered, are ignored.
+    pub fn record(&mut self, result: ToolResult) -> Option<Vec<ToolResult>> {
+        if !self.contains(&result.tool_use_id) {
+            tracing::warn!(
+                "⚠️ TOOL_RESULT outside the open batch: id={}, batch={}",
+                result.tool_use_id, self.id
+            );
+            return None;
+        }
+        if self.has_result(&result.tool_use_id) {
+            tracing::warn!("⚠️ Duplicate TOOL_RESULT ignored: id={}", result.tool_use_id);
+            return None;
+        }
+        self.results.insert(result.tool_use_id.clone(), result);
+        if self.results.len() < self.requested.len() {
+            return None;
+        }
+        self.decision = None;
+        let mut results = std::mem::take(&mut self.results);
+        let ordered = std::mem::take(&mut self.requested)
+            .iter()
+            .filter_map(|id| results.remove(id))
+            .collect();
+        Some(ordered)
+    }
+
+    /// Drop the open batch (e.g. when the turn is cancelled)
+    pub fn clear(&mut self) {
+        self.requested.clear();
+        self.results.clear();
+        self.decision = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ToolResultContent;
+
+    fn result(id: &str, is_error: bool) -> ToolResult {
+        ToolResult {
+            tool_use_id: id.to_string(),
+            content: ToolResultContent::Text(format!("{} done", id)),
+            is_error,
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_results_complete_once() {
+        let mut batch = ToolBatch::new();
+        for id in ["a", "b", "c"] {
+            assert!(batch.request(id));
+        }
+        assert!(!batch.request("b"), "a tool is only requested once");
+
+        let mut completions = Vec::new();
+        for r in [result("c", false), result("b", true), result("a", false), result("c", false)] {
+            if let Some(all) = batch.record(r) {
+                completions.push(all);
+            }
+        }
+
+        assert_eq!(completions.len(), 1, "continuation fires exactly once");
+        let ids: Vec<&str> = completions[0].iter().map(|r| r.tool_use_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert!(completions[0][1].is_error);
+        assert_eq!(batch.expected(), 0);
+    }
+
+    #[test]
+    fn test_stale_results_are_ignored() {
+        let mut batch = ToolBatch::new();
+        batch.request("a");
+        assert!(batch.record(result("old", false)).is_none());
+        assert_eq!(batch.collected(), 0);
+
+        batch.clear();
+        assert!(batch.record(result("a", false)).is_none());
+
+        batch.request("b");
+        assert_eq!(batch.id(), 2);
+        assert_eq!(batch.record(result("b", false)).map(|r| r.len()), Some(1));
+    }
+
+    #[test]
+    fn test_decision_is_scoped_to_one_batch() {
+        let mut batch = ToolBatch::new();
+        batch.set_decision(BatchDecision::ApproveAll);
+        assert_eq!(batch.decision(), None, "no batch open yet");
+
+        batch.request("a");
+        batch.request("b");
+        batch.set_decision(BatchDecision::ApproveAll);
+        batch.record(result("a", false));
+        assert_eq!(batch.decision(), Some(BatchDecision::ApproveAll));
+
+        batch.record(result("b", false));
+        assert_eq!(batch.decision(), None, "completing the batch ends the decision");
+
+        batch.request("c");
+        assert_eq!(batch.decision(), None, "the next turn prompts again");
+        batch.set_decision(BatchDecision::RejectAll);
+        batch.clear();
+        assert_eq!(batch.decision(), None);
+    }
+}
@@ -0,0 +1,389 @@
+// TurnChanges - "what changed" summary of the files the agent wrote in one turn
+//
+// Before a file tool runs, the target file's contents are snapshotted (once per
+// turn). When the turn ends, each snapshot is compared with the file on disk,
+// giving a `git diff --stat`-style changeset that doesn't depend on git. The
+// last few changesets are kept so their edits can be undone. Files over
+// `MAX_SNAPSHOT_BYTES` are tracked by size and mtime only, so they show up in
+// the summary but cannot be undone.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::llm::tools::resolve_tool_path;
+use crate::llm::ToolUse;
+#[cfg(unix)]
+use crate::util::write_atomic_mode;
+#[cfg(not(unix))]
+use crate::util::write_atomic;
+
+/// Tools that write files, with the input field naming the file
+const FILE_TOOLS: &[(&str, &str)] = &[
+    ("file_write", "path"),
+    ("edit", "file_path"),
+    ("apply_edit", "path"),
+    ("file_delete", "path"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// Largest file whose contents are kept for diffing and undo
+const MAX_SNAPSHOT_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineStat {
+    Lines { added: usize, removed: usize },
+    Binary,
+    /// Over `MAX_SNAPSHOT_BYTES`; not diffed
+    TooLarge,
+}
+
+/// A file's state at one point in the turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Snapshot {
+    Missing,
+    Content { bytes: Vec<u8>, permissions: std::fs::Permissions },
+    /// Too large to keep; identified by size and mtime
+    Large { len: u64, modified: Option<SystemTime> },
+}
+
+impl Snapshot {
+    fn take(path: &Path) -> Self {
+        let Ok(meta) = std::fs::metadata(path) else {
+            return Snapshot::Missing;
+        };
+        if meta.len() > MAX_SNAPSHOT_BYTES {
+            return Snapshot::Large { len: meta.len(), modified: meta.modified().ok() };
+        }
+        match std::fs::read(path) {
+            Ok(bytes) => Snapshot::Content { bytes, permissions: meta.permissions() },
+            Err(_) => Snapshot::Missing,
+        }
+    }
+
+    fn exists(&self) -> bool {
+        !matches!(self, Snapshot::Missing)
+    }
+}
+
+/// One file touched during the turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+    pub stat: LineStat,
+    /// State before the turn
+    before: Snapshot,
+    /// State the turn left behind
+    after: Snapshot,
+}
+
+impl FileChange {
+    /// Whether the file no longer holds what the agent left there
+    pub fn changed_since(&self) -> bool {
+        Snapshot::take(&self.path) != self.after
+    }
+
+    /// Put the file back as it was before the turn, including its mode.
+    /// Written atomically, so a failed undo leaves the agent's version intact.
+    pub fn restore(&self) -> std::io::Result<()> {
+        match &self.before {
+            Snapshot::Content { bytes, permissions } => {
+                if let Some(parent) = self.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    write_atomic_mode(&self.path, bytes, permissions.mode() & 0o7777)
+                }
+                #[cfg(not(unix))]
+                {
+                    write_atomic(&self.path, bytes)?;
+                    std::fs::set_permissions(&self.path, permissions.clone())
+                }
+            }
+            Snapshot::Large { .. } => Err(std::io::Error::other("too large to undo")),
+            Snapshot::Missing if self.path.exists() => std::fs::remove_file(&self.path),
+            Snapshot::Missing => Ok(()),
+        }
+    }
+}
+
+/// Turns kept for undo
+const UNDO_DEPTH: usize = 10;
+
+/// Changesets of the most recent turns that wrote files, newest last
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    turns: VecDeque<Vec<FileChange>>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, changes: Vec<FileChange>) {
+        if self.turns.len() == UNDO_DEPTH {
+            self.turns.pop_front();
+        }
+        self.turns.push_back(changes);
+    }
+
+    pub fn last(&self) -> Option<&[FileChange]> {
+        self.turns.back().map(Vec::as_slice)
+    }
+
+    pub fn pop(&mut self) -> Option<Vec<FileChange>> {
+        self.turns.pop_back()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TurnChanges {
+    /// State before the first write of the turn
+    before: BTreeMap<PathBuf, Snapshot>,
+}
+
+impl TurnChanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot the file `tool` is about to write, unless it already was this turn
+    pub fn snapshot(&mut self, tool: &ToolUse, working_dir: &Path) {
+        let Some(path) = target_path(tool, working_dir) else {
+            return;
+        };
+        self.before.entry(path).or_insert_with_key(|path| Snapshot::take(path));
+    }
+
+    /// Compare every snapshot with the file on disk and start a new turn.
+    /// Files that ended up unchanged are left out.
+    pub fn finish(&mut self) -> Vec<FileChange> {
+        std::mem::take(&mut self.before)
+            .into_iter()
+            .filter_map(|(path, before)| {
+                let after = Snapshot::take(&path);
+                let kind = match (before.exists(), after.exists()) {
+                    (false, true) => ChangeKind::Created,
+                    (true, false) => ChangeKind::Deleted,
+                    (true, true) if before != after => ChangeKind::Modified,
+                    _ => return None,
+                };
+                let stat = match (&before, &after) {
+                    (Snapshot::Large { .. }, _) | (_, Snapshot::Large { .. }) => LineStat::TooLarge,
+                    _ => line_stat(content(&before), content(&after)),
+                };
+                Some(FileChange { path, kind, stat, before, after })
+            })
+            .collect()
+    }
+}
+
+fn content(snapshot: &Snapshot) -> &[u8] {
+    match snapshot {
+        Snapshot::Content { bytes, .. } => bytes,
+        Snapshot::Missing | Snapshot::Large { .. } => &[],
+    }
+}
+
+fn target_path(tool: &ToolUse, working_dir: &Path) -> Option<PathBuf> {
+    let (_, field) = FILE_TOOLS.iter().find(|(name, _)| *name == tool.name)?;
+    let path = tool.input.get(*field)?.as_str()?;
+    Some(resolve_tool_path(working_dir, path))
+}
+
+fn line_stat(old: &[u8], new: &[u8]) -> LineStat {
+    let as_text = |bytes: &[u8]| std::str::from_utf8(bytes).ok().filter(|s| !s.contains('\0')).map(str::to_owned);
+    let (Some(old), Some(new)) = (as_text(old), as_text(new)) else {
+        return LineStat::Binary;
+    };
+    let (mut added, mut removed) = (0, 0);
+    for change in TextDiff::from_lines(&old, &new).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    LineStat::Lines { added, removed }
+}
+
+/// One line per file, e.g. "M src/main.rs  +3 -1", paths relative to `base`
+pub fn format_stat(changes: &[FileChange], base: &Path) -> String {
+    changes
+        .iter()
+        .map(|change| {
+            let marker = match change.kind {
+                ChangeKind::Created => "A",
+                ChangeKind::Modified => "M",
+                ChangeKind::Deleted => "D",
+            };
+            let stat = match change.stat {
+                LineStat::Lines { added, removed } => format!("+{} -{}", added, removed),
+                LineStat::Binary => "(binary changed)".to_string(),
+                LineStat::TooLarge => "(too large to diff)".to_string(),
+            };
+            let path = change.path.strip_prefix(base).unwrap_or(&change.path);
+            format!("{} {}  {}", marker, path.display(), stat)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Notification headline, e.g. "3 files changed (+12 -4)"
+pub fn headline(changes: &[FileChange]) -> String {
+    let (added, removed) = changes.iter().fold((0, 0), |(a, r), change| match change.stat {
+        LineStat::Lines { added, removed } => (a + added, r + removed),
+        LineStat::Binary | LineStat::TooLarge => (a, r),
+    });
+    let files = if changes.len() == 1 { "file" } else { "files" };
+    format!("{} {} changed (+{} -{})", changes.len(), files, added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, input: serde_json::Value) -> ToolUse {
+        ToolUse { id: "t".to_string(), name: name.to_string(), input }
+    }
+
+    #[test]
+    fn test_turn_changes_distinguish_created_modified_deleted() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("keep.rs"), "a\nb\nc\n").unwrap();
+        std::fs::write(dir.join("gone.rs"), "x\ny\n").unwrap();
+        std::fs::write(dir.join("same.rs"), "same\n").unwrap();
+        std::fs::write(dir.join("logo.png"), [0u8, 1, 2]).unwrap();
+
+        let mut changes = TurnChanges::new();
+        changes.snapshot(&tool("edit", serde_json::json!({"file_path": "keep.rs"})), dir);
+        changes.snapshot(&tool("file_delete", serde_json::json!({"path": "gone.rs"})), dir);
+        changes.snapshot(&tool("file_write", serde_json::json!({"path": "new.rs"})), dir);
+        changes.snapshot(&tool("file_write", serde_json::json!({"path": "same.rs"})), dir);
+        changes.snapshot(&tool("file_write", serde_json::json!({"path": "logo.png"})), dir);
+        changes.snapshot(&tool("file_read", serde_json::json!({"path": "other.rs"})), dir);
+
+        std::fs::write(dir.join("keep.rs"), "a\nB\nc\nd\n").unwrap();
+        // A second write in the same turn keeps the original snapshot
+        changes.snapshot(&tool("edit", serde_json::json!({"file_path": "keep.rs"})), dir);
+        std::fs::remove_file(dir.join("gone.rs")).unwrap();
+        std::fs::write(dir.join("new.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.join("logo.png"), [0u8, 9]).unwrap();
+
+        let result = changes.finish();
+        assert_eq!(
+            format_stat(&result, dir),
+            "D gone.rs  +0 -2\nM keep.rs  +2 -1\nM logo.png  (binary changed)\nA new.rs  +1 -0"
+        );
+        assert_eq!(headline(&result), "4 files changed (+3 -3)");
+        assert!(changes.finish().is_empty(), "finish starts a new turn");
+    }
+
+    #[test]
+    fn test_escaped_file_names_resolve_like_the_executor() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("a\\b.txt"), "old\n").unwrap();
+
+        let mut changes = TurnChanges::new();
+        // Tools display a backslash in a file name as `\\`
+        changes.snapshot(&tool("file_write", serde_json::json!({"path": "a\\\\b.txt"})), dir);
+        std::fs::write(dir.join("a\\b.txt"), "new\n").unwrap();
+
+        let result = changes.finish();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].path, dir.join("a\\b.txt"));
+    }
+
+    #[test]
+    fn test_restore_undoes_the_turn() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("keep.rs"), "old\n").unwrap();
+        std::fs::write(dir.join("gone.rs"), "x\n").unwrap();
+
+        let mut changes = TurnChanges::new();
+        for path in ["keep.rs", "gone.rs", "new.rs"] {
+            changes.snapshot(&tool("file_write", serde_json::json!({"path": path})), dir);
+        }
+        std::fs::write(dir.join("keep.rs"), "new\n").unwrap();
+        std::fs::remove_file(dir.join("gone.rs")).unwrap();
+        std::fs::write(dir.join("new.rs"), "fresh\n").unwrap();
+
+        let mut history = EditHistory::new();
+        history.push(changes.finish());
+        let turn = history.pop().unwrap();
+        assert!(turn.iter().all(|c| !c.changed_since()));
+
+        // An edit made after the turn is detected before it gets clobbered
+        std::fs::write(dir.join("keep.rs"), "mine\n").unwrap();
+        assert!(turn.iter().find(|c| c.path.ends_with("keep.rs")).unwrap().changed_since());
+
+        for change in &turn {
+            change.restore().unwrap();
+        }
+        assert_eq!(std::fs::read_to_string(dir.join("keep.rs")).unwrap(), "old\n");
+        assert_eq!(std::fs::read_to_string(dir.join("gone.rs")).unwrap(), "x\n");
+        assert!(!dir.join("new.rs").exists());
+        assert!(history.last().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restore_keeps_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("run.sh"), "#!/bin/sh\n").unwrap();
+        std::fs::set_permissions(dir.join("run.sh"), std::fs::Permissions::from_mode(0o750)).unwrap();
+
+        let mut changes = TurnChanges::new();
+        changes.snapshot(&tool("file_delete", serde_json::json!({"path": "run.sh"})), dir);
+        std::fs::remove_file(dir.join("run.sh")).unwrap();
+
+        changes.finish()[0].restore().unwrap();
+        let mode = std::fs::metadata(dir.join("run.sh")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+        assert_eq!(std::fs::read_to_string(dir.join("run.sh")).unwrap(), "#!/bin/sh\n");
+    }
+
+    #[test]
+    fn test_large_files_are_tracked_without_contents() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let big = vec![b'a'; MAX_SNAPSHOT_BYTES as usize + 1];
+        std::fs::write(dir.join("big.log"), &big).unwrap();
+
+        let mut changes = TurnChanges::new();
+        changes.snapshot(&tool("file_write", serde_json::json!({"path": "big.log"})), dir);
+        std::fs::write(dir.join("big.log"), [&big[..], b"more"].concat()).unwrap();
+
+        let result = changes.finish();
+        assert_eq!(format_stat(&result, dir), "M big.log  (too large to diff)");
+        assert!(matches!(result[0].before, Snapshot::Large { .. }));
+        assert!(result[0].restore().is_err());
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = EditHistory::new();
+        for _ in 0..UNDO_DEPTH + 3 {
+            history.push(Vec::new());
+        }
+        assert_eq!(history.turns.len(), UNDO_DEPTH);
+    }
+}
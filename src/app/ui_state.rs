@@ -6,16 +6,39 @@ use std::time::Instant;
 use arboard::Clipboard;
 use ratatui::layout::Rect;
 
+use crate::action::Action;
 use crate::components::ask_user_dialog::AskUserDialog;
 use crate::components::command_palette::CommandPalette;
 use crate::components::confirm_dialog::ConfirmDialog;
 use crate::components::context_menu::ContextMenu;
 use crate::components::menu::Menu;
 use crate::components::notification::NotificationManager;
+use crate::components::pager::Pager;
 use crate::components::pane_layout::{DragState, PaneLayout};
+use crate::components::model_picker::ModelPicker;
+use crate::components::overlay_layout::OverlayLayout;
 use crate::components::spinner_manager::SpinnerManager;
 use crate::input::focus::FocusManager;
 use crate::input::mode::InputMode;
+use crate::config::KeySequence;
+
+/// Yes/no prompt guarding one action (y/Enter runs it, n/Esc cancels)
+pub struct ActionPrompt {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub action: Action,
+    /// Another key to offer, with its help label and action
+    pub alternate: Option<(char, &'static str, Action)>,
+}
+
+impl ActionPrompt {
+    pub fn help(&self) -> String {
+        match self.alternate {
+            Some((key, label, _)) => format!("y/Enter to confirm, {} to {}, n/Esc to cancel", key, label),
+            None => "y/Enter to confirm, n/Esc to cancel".to_string(),
+        }
+    }
+}
 
 /// Minimum interval between renders (33ms = ~30 FPS).
 pub const MIN_RENDER_INTERVAL_MS: u64 = 33;
@@ -28,6 +51,10 @@ pub struct UiState {
     pub last_render: Instant,
     pub last_activity: Instant,
     pub last_esc_press: Option<Instant>,
+    /// Progress through the PTY escape key sequence
+    pub pty_escape: KeySequence,
+    /// Host terminal focus from focus-change events; None until the first report
+    pub terminal_focused: Option<bool>,
 
     // UI chrome/components
     pub menu: Menu,
@@ -37,6 +64,9 @@ pub struct UiState {
     pub notification_manager: NotificationManager,
     pub spinner_manager: SpinnerManager,
     pub ask_user_dialog: AskUserDialog,
+    pub pager: Pager,
+    pub model_picker: ModelPicker,
+    pub action_prompt: Option<ActionPrompt>,
     pub clipboard: Option<Clipboard>,
 
     // Layout / hit testing areas
@@ -47,6 +77,7 @@ pub struct UiState {
     pub content_area: Rect,
     pub pane_layout: PaneLayout,
     pub drag_state: DragState,
+    pub overlay_layout: OverlayLayout,
 
     // Activity Stream visibility (SIRK/Forge)
     pub activity_stream_visible: bool,
@@ -64,6 +95,8 @@ impl UiState {
             last_render: now - std::time::Duration::from_millis(MIN_RENDER_INTERVAL_MS + 1),
             last_activity: now,
             last_esc_press: None,
+            pty_escape: KeySequence::default(),
+            terminal_focused: None,
             menu,
             command_palette: CommandPalette::new(),
             confirm_dialog: ConfirmDialog::new(),
@@ -71,6 +104,9 @@ impl UiState {
             notification_manager: NotificationManager::new(),
             spinner_manager: SpinnerManager::new(),
             ask_user_dialog: AskUserDialog::new(),
+            pager: Pager::new(),
+            model_picker: ModelPicker::new(),
+            action_prompt: None,
             clipboard,
             tab_bar_area: Rect::default(),
             terminal_area: Rect::default(),
@@ -79,6 +115,7 @@ impl UiState {
             content_area: Rect::default(),
             pane_layout: PaneLayout::new(),
             drag_state: DragState::default(),
+            overlay_layout: OverlayLayout::new(),
             activity_stream_visible: false,
             sirk_panel_visible: false,
         }
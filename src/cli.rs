@@ -23,30 +23,58 @@ pub struct Cli {
     pub working_dir: Option<std::path::PathBuf>,
 
     /// API key for Anthropic (Claude). Overrides keystore/config.
-    #[arg(long, env = "ANTHROPIC_API_KEY", hide_env_values = true)]
+    #[arg(long)]
     pub anthropic_api_key: Option<String>,
 
     /// API key for OpenAI. Overrides keystore/config.
-    #[arg(long, env = "OPENAI_API_KEY", hide_env_values = true)]
+    #[arg(long)]
     pub openai_api_key: Option<String>,
 
     /// API key for Google Gemini. Overrides keystore/config.
-    #[arg(long, env = "GOOGLE_API_KEY", hide_env_values = true)]
+    #[arg(long)]
     pub gemini_api_key: Option<String>,
 
     /// API key for xAI (Grok). Overrides keystore/config.
-    #[arg(long, env = "XAI_API_KEY", hide_env_values = true)]
+    #[arg(long)]
     pub grok_api_key: Option<String>,
 
     /// API key for Groq. Overrides keystore/config.
-    #[arg(long, env = "GROQ_API_KEY", hide_env_values = true)]
+    #[arg(long)]
     pub groq_api_key: Option<String>,
 
+    /// Load config, keybindings, theme, LLM and stream settings from DIR instead
+    /// of the XDG config directory. Keys are stored there too, and so are threads
+    /// and session state unless --data-dir is given. Logs stay in the data dir.
+    #[arg(long, value_name = "DIR")]
+    pub config_dir: Option<std::path::PathBuf>,
+
+    /// Store logs, threads and session state in DIR instead of the XDG data
+    /// directory (~/.local/share/ridge-control)
+    #[arg(long, value_name = "DIR")]
+    pub data_dir: Option<std::path::PathBuf>,
+
+    /// Store conversation threads in DIR instead of the configured location
+    #[arg(long, value_name = "DIR")]
+    pub threads_dir: Option<std::path::PathBuf>,
+
+    /// Resume thread ID on launch instead of the session's last thread
+    #[arg(long, value_name = "ID", conflicts_with = "new_thread")]
+    pub thread: Option<String>,
+
+    /// Start a fresh thread on launch instead of restoring the last one
+    #[arg(long, default_value_t = false)]
+    pub new_thread: bool,
+
+    /// Replay a scripted conversation (.toml or .json) through the "mock"
+    /// provider instead of calling a real LLM; for demos and testing
+    #[arg(long, value_name = "PATH")]
+    pub mock_script: Option<std::path::PathBuf>,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info")]
     pub log_level: String,
 
-    /// Restore previous session on startup
+    /// Restore previous session (tabs and last active thread) on startup
     #[arg(long, default_value_t = true)]
     pub restore_session: bool,
 }
@@ -59,6 +87,13 @@ pub enum Command {
         #[command(subcommand)]
         action: KeysAction,
     },
+    /// Bundle the log tail, redacted config, terminal details and the last
+    /// panic into a .tar.gz for a bug report. Nothing is sent anywhere.
+    CrashReport {
+        /// Directory to write the bundle to
+        #[arg(short, long, value_name = "DIR", default_value = ".")]
+        output: std::path::PathBuf,
+    },
 }
 
 /// Key management actions
@@ -103,6 +138,11 @@ mod tests {
         let cli = Cli::parse_from(["ridge-control"]);
         assert!(!cli.dangerously_allow_all);
         assert!(cli.working_dir.is_none());
+        assert!(cli.threads_dir.is_none());
+        assert!(cli.config_dir.is_none());
+        assert!(cli.data_dir.is_none());
+        assert!(cli.thread.is_none());
+        assert!(!cli.new_thread);
         assert!(cli.anthropic_api_key.is_none());
         assert_eq!(cli.log_level, "info");
         assert!(cli.restore_session);
@@ -120,6 +160,24 @@ mod tests {
         assert_eq!(cli.working_dir, Some(std::path::PathBuf::from("/tmp/test")));
     }
 
+    #[test]
+    fn test_config_dir_flag() {
+        let cli = Cli::parse_from(["ridge-control", "--config-dir", "/tmp/rc", "keys", "list"]);
+        assert_eq!(cli.config_dir, Some(std::path::PathBuf::from("/tmp/rc")));
+        assert!(matches!(cli.command, Some(Command::Keys { action: KeysAction::List })));
+    }
+
+    #[test]
+    fn test_thread_flags() {
+        let cli = Cli::parse_from(["ridge-control", "--thread", "abc123"]);
+        assert_eq!(cli.thread.as_deref(), Some("abc123"));
+
+        let cli = Cli::parse_from(["ridge-control", "--new-thread"]);
+        assert!(cli.new_thread);
+
+        assert!(Cli::try_parse_from(["ridge-control", "--thread", "abc123", "--new-thread"]).is_err());
+    }
+
     #[test]
     fn test_api_key_flags() {
         let cli = Cli::parse_from([
@@ -11,9 +11,12 @@ use ratatui::{
 
 use crate::action::Action;
 use crate::components::Component;
-use crate::config::Theme;
+use crate::config::{Theme, TimestampFormat};
 use crate::spindles::{ActivityMessage, SharedActivityStore, ToolCallInfo};
 
+/// Snapshot of store contents taken under lock for rendering
+type RenderSnapshot = (Option<(u32, u32)>, Vec<(ActivityMessage, Option<ToolCallInfo>)>);
+
 pub struct ActivityStream {
     store: SharedActivityStore,
     scroll_offset: usize,
@@ -21,6 +24,10 @@ pub struct ActivityStream {
     auto_scroll: bool,
     header_run_name: Option<String>,
     header_instance: Option<(u32, u32)>,
+    show_timestamps: bool,
+    timestamp_format: TimestampFormat,
+    /// Reference time for relative timestamps, refreshed on tick
+    now: chrono::DateTime<chrono::Local>,
 }
 
 impl ActivityStream {
@@ -32,6 +39,9 @@ impl ActivityStream {
             auto_scroll: true,
             header_run_name: None,
             header_instance: None,
+            show_timestamps: true,
+            timestamp_format: TimestampFormat::default(),
+            now: chrono::Local::now(),
         }
     }
 
@@ -82,9 +92,32 @@ impl ActivityStream {
         self.scroll_offset = 0;
     }
 
-    /// Push a text message to the activity stream (used for stderr output)
+    pub fn set_show_timestamps(&mut self, show: bool) {
+        self.show_timestamps = show;
+    }
+
+    pub fn toggle_timestamps(&mut self) {
+        self.show_timestamps = !self.show_timestamps;
+    }
+
+    pub fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp_format = format;
+    }
+
+    /// Advance the reference time used by relative timestamps
+    pub fn tick(&mut self) {
+        self.now = chrono::Local::now();
+    }
+
+    /// Push a text message to the activity stream (used for stderr output).
+    /// An empty timestamp is stamped with the push time.
     pub fn push_text(&mut self, content: String, timestamp: String) {
         use crate::spindles::TextActivity;
+        let timestamp = if timestamp.is_empty() {
+            chrono::Local::now().to_rfc3339()
+        } else {
+            timestamp
+        };
         let activity = ActivityMessage::Text(TextActivity {
             content,
             timestamp,
@@ -177,7 +210,7 @@ impl ActivityStream {
                 None
             }
             // Grep - show pattern and optional path
-            "Grep" | "grep" => {
+            "Grep" | "grep" | "search_code" => {
                 let mut parts = Vec::new();
                 if let Some(pattern) = input.get("pattern") {
                     if let Some(s) = pattern.as_str() {
@@ -274,20 +307,41 @@ impl ActivityStream {
         }
     }
 
+    /// Timestamp column text; falls back to the raw time-of-day if the
+    /// activity's timestamp isn't RFC 3339
+    fn format_timestamp(&self, timestamp: &str) -> String {
+        match chrono::DateTime::parse_from_rfc3339(timestamp) {
+            Ok(at) => {
+                let at = at.with_timezone(&chrono::Local);
+                self.timestamp_format.format(at, self.now.max(at))
+            }
+            Err(_) => timestamp.get(11..19).unwrap_or(timestamp).to_string(),
+        }
+    }
+
     fn render_activity<'a>(
+        &self,
         activity: &'a ActivityMessage,
         theme: &'a Theme,
         tool_info_lookup: Option<&ToolCallInfo>,
     ) -> Vec<Line<'a>> {
-        let icon = activity.icon();
-        let timestamp = activity.timestamp();
-        let time_short = if timestamp.len() > 10 {
-            &timestamp[11..19]
-        } else {
-            timestamp
-        };
+        let mut lines = Self::render_activity_body(activity, theme, tool_info_lookup);
+        if self.show_timestamps {
+            if let Some(first) = lines.first_mut() {
+                let time_style = Style::default().fg(theme.colors.muted.to_color());
+                let time = format!("[{}] ", self.format_timestamp(activity.timestamp()));
+                first.spans.insert(0, Span::styled(time, time_style));
+            }
+        }
+        lines
+    }
 
-        let time_style = Style::default().fg(theme.colors.muted.to_color());
+    fn render_activity_body<'a>(
+        activity: &'a ActivityMessage,
+        theme: &'a Theme,
+        tool_info_lookup: Option<&ToolCallInfo>,
+    ) -> Vec<Line<'a>> {
+        let icon = activity.icon();
 
         match activity {
             ActivityMessage::Thinking(a) => {
@@ -298,7 +352,6 @@ impl ActivityStream {
                 let first_line = a.content.lines().next().unwrap_or(&a.content);
                 let truncated = Self::truncate_str(first_line.trim(), 80);
                 vec![Line::from(vec![
-                    Span::styled(format!("[{}] ", time_short), time_style),
                     Span::raw(format!("{} ", icon)),
                     Span::styled(truncated, content_style),
                 ])]
@@ -311,7 +364,6 @@ impl ActivityStream {
                 let detail = Self::extract_tool_detail(&tc.tool_name, &tc.input);
 
                 let mut spans = vec![
-                    Span::styled(format!("[{}] ", time_short), time_style),
                     Span::raw(format!("{} ", icon)),
                     Span::styled(tc.tool_name.clone(), tool_style),
                 ];
@@ -351,7 +403,6 @@ impl ActivityStream {
                     });
 
                 let mut spans = vec![
-                    Span::styled(format!("[{}] ", time_short), time_style),
                     Span::raw(format!("{} ", icon)),
                     Span::styled(display_name, tool_style),
                 ];
@@ -368,7 +419,6 @@ impl ActivityStream {
             }
             ActivityMessage::Text(t) => {
                 vec![Line::from(vec![
-                    Span::styled(format!("[{}] ", time_short), time_style),
                     Span::raw(format!("{} ", icon)),
                     Span::raw(t.content.clone()),
                 ])]
@@ -376,7 +426,6 @@ impl ActivityStream {
             ActivityMessage::Error(e) => {
                 let error_style = Style::default().fg(theme.colors.error.to_color()).add_modifier(Modifier::BOLD);
                 vec![Line::from(vec![
-                    Span::styled(format!("[{}] ", time_short), time_style),
                     Span::raw(format!("{} ", icon)),
                     Span::styled(e.message.clone(), error_style),
                 ])]
@@ -418,6 +467,10 @@ impl Component for ActivityStream {
                     self.toggle_auto_scroll();
                     Some(Action::Noop)
                 }
+                KeyCode::Char('t') if modifiers.is_empty() => {
+                    self.toggle_timestamps();
+                    Some(Action::Noop)
+                }
                 _ => None,
             },
             Event::Mouse(MouseEvent { kind, .. }) => match kind {
@@ -439,6 +492,7 @@ impl Component for ActivityStream {
         match action {
             Action::ActivityStreamClear => self.clear(),
             Action::ActivityStreamToggleAutoScroll => self.toggle_auto_scroll(),
+            Action::Tick => self.tick(),
             _ => {}
         }
     }
@@ -451,7 +505,7 @@ impl Component for ActivityStream {
         };
 
         // Collect all data from store under lock, then release
-        let (instance_info, activities_with_info): (Option<(u32, u32)>, Vec<(ActivityMessage, Option<ToolCallInfo>)>) = {
+        let (instance_info, activities_with_info): RenderSnapshot = {
             let store = self.store.lock().unwrap();
 
             // Get current instance info from store (updated from incoming activities)
@@ -479,7 +533,7 @@ impl Component for ActivityStream {
         let lines: Vec<Line> = activities_with_info
             .iter()
             .flat_map(|(activity, tool_info)| {
-                Self::render_activity(activity, theme, tool_info.as_ref())
+                self.render_activity(activity, theme, tool_info.as_ref())
             })
             .collect();
 
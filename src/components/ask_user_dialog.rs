@@ -1,7 +1,11 @@
 //! Ask User dialog component for structured user input
 //!
 //! Displays questions from the ask_user tool and captures user responses.
-//! Supports single-select, multi-select, and custom text input.
+//! Supports single-select, multi-select, and custom text input, with optional
+//! questions and validation of custom answers. An optional timeout answers
+//! with each question's default when the user doesn't respond.
+
+use std::time::{Duration, Instant};
 
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use ratatui::{
@@ -12,7 +16,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::action::{Action, AskUserResponse};
+use crate::action::{Action, AskUserAnswer, AskUserResponse};
 use crate::config::Theme;
 use crate::llm::ParsedQuestion;
 
@@ -25,6 +29,14 @@ enum InputMode {
     CustomText,
 }
 
+/// Timer that answers with the defaults when it runs out
+struct Countdown {
+    remaining: Duration,
+    last_tick: Instant,
+    /// Set once the user interacts with the dialog
+    paused: bool,
+}
+
 /// Ask User dialog component
 pub struct AskUserDialog {
     /// Whether the dialog is currently visible
@@ -35,8 +47,12 @@ pub struct AskUserDialog {
     questions: Vec<ParsedQuestion>,
     /// Current question index
     current_question: usize,
-    /// Answers for each question (option labels or custom text)
+    /// Selected option labels for each question
     answers: Vec<Vec<String>>,
+    /// Custom "Other" text for each question
+    customs: Vec<Option<String>>,
+    /// Validation or missing-answer message shown inline
+    error: Option<String>,
     /// Current selection index within options (includes "Other" at end)
     selected_option: usize,
     /// List state for option selection
@@ -45,6 +61,8 @@ pub struct AskUserDialog {
     input_mode: InputMode,
     /// Custom text input buffer
     custom_text: String,
+    /// Timeout before the defaults are submitted
+    countdown: Option<Countdown>,
 }
 
 impl AskUserDialog {
@@ -55,10 +73,13 @@ impl AskUserDialog {
             questions: Vec::new(),
             current_question: 0,
             answers: Vec::new(),
+            customs: Vec::new(),
+            error: None,
             selected_option: 0,
             list_state: ListState::default(),
             input_mode: InputMode::Selecting,
             custom_text: String::new(),
+            countdown: None,
         }
     }
 
@@ -72,12 +93,85 @@ impl AskUserDialog {
         self.visible = true;
         self.tool_use_id = tool_use_id;
         self.answers = vec![Vec::new(); questions.len()];
+        self.customs = vec![None; questions.len()];
+        self.error = None;
         self.questions = questions;
         self.current_question = 0;
         self.selected_option = 0;
         self.list_state.select(Some(0));
         self.input_mode = InputMode::Selecting;
         self.custom_text.clear();
+        self.countdown = None;
+    }
+
+    /// Submit the defaults (or time out) unless the user responds within `duration`
+    pub fn start_countdown(&mut self, duration: Duration) {
+        self.countdown = Some(Countdown {
+            remaining: duration,
+            last_tick: Instant::now(),
+            paused: false,
+        });
+    }
+
+    /// Whether a countdown is running (needs periodic redraws)
+    pub fn countdown_active(&self) -> bool {
+        self.visible && self.countdown.as_ref().is_some_and(|c| !c.paused)
+    }
+
+    /// Advance the countdown; returns the response once it expires
+    pub fn tick(&mut self) -> Option<Action> {
+        let elapsed = self.countdown.as_ref()?.last_tick.elapsed();
+        self.advance(elapsed)
+    }
+
+    fn advance(&mut self, elapsed: Duration) -> Option<Action> {
+        if !self.visible {
+            return None;
+        }
+        let countdown = self.countdown.as_mut()?;
+        countdown.last_tick = Instant::now();
+        if countdown.paused {
+            return None;
+        }
+        countdown.remaining = countdown.remaining.saturating_sub(elapsed);
+        if !countdown.remaining.is_zero() {
+            return None;
+        }
+        self.countdown = None;
+        self.apply_defaults();
+        if self.first_missing_required().is_some() {
+            Some(Action::AskUserTimedOut(self.tool_use_id.clone()))
+        } else {
+            Some(self.build_response(true))
+        }
+    }
+
+    /// Fill unanswered questions from their defaults: option labels are
+    /// selected, anything else becomes the "Other" answer
+    fn apply_defaults(&mut self) {
+        for i in 0..self.questions.len() {
+            let q = &self.questions[i];
+            if self.is_answered(i) || q.default.is_empty() {
+                continue;
+            }
+            let (mut labels, custom): (Vec<String>, Vec<String>) = q.default
+                .iter()
+                .cloned()
+                .partition(|d| q.options.iter().any(|o| &o.label == d));
+            if !q.multi_select {
+                if labels.is_empty() {
+                    self.customs[i] = custom.into_iter().next();
+                } else {
+                    labels.truncate(1);
+                    self.answers[i] = labels;
+                }
+                continue;
+            }
+            self.answers[i] = labels;
+            if !custom.is_empty() {
+                self.customs[i] = Some(custom.join(", "));
+            }
+        }
     }
 
     /// Hide the dialog and clear state
@@ -86,11 +180,14 @@ impl AskUserDialog {
         self.tool_use_id.clear();
         self.questions.clear();
         self.answers.clear();
+        self.customs.clear();
+        self.error = None;
         self.current_question = 0;
         self.selected_option = 0;
         self.list_state.select(None);
         self.input_mode = InputMode::Selecting;
         self.custom_text.clear();
+        self.countdown = None;
     }
 
     /// Get current question if any
@@ -119,6 +216,12 @@ impl AskUserDialog {
         }
 
         if let Event::Key(key) = event {
+            // A message stays up until the next key press
+            self.error = None;
+            // Any key means the user is here; stop the countdown
+            if let Some(countdown) = self.countdown.as_mut() {
+                countdown.paused = true;
+            }
             match self.input_mode {
                 InputMode::Selecting => self.handle_selecting_input(key),
                 InputMode::CustomText => self.handle_custom_text_input(key),
@@ -183,9 +286,7 @@ impl AskUserDialog {
                             answers.push(option_label);
                         }
                     } else {
-                        // Single select - replace answer
-                        let option_label = q.options[self.selected_option].label.clone();
-                        self.answers[self.current_question] = vec![option_label];
+                        self.select_single();
                     }
                 }
                 None
@@ -198,35 +299,15 @@ impl AskUserDialog {
                     None
                 } else {
                     // Select option and possibly submit
-                    if let Some(q) = self.current_question_data() {
-                        if !q.multi_select {
-                            // Single select - select this option
-                            let option_label = q.options[self.selected_option].label.clone();
-                            self.answers[self.current_question] = vec![option_label];
-                        }
-                    }
-
-                    // Check if we can submit (all questions have answers)
-                    if self.all_questions_answered() {
-                        Some(self.build_response())
-                    } else if self.current_question < self.questions.len() - 1 {
-                        // Move to next unanswered question
-                        self.current_question += 1;
-                        self.selected_option = 0;
-                        self.list_state.select(Some(0));
-                        None
-                    } else {
-                        None
+                    if self.current_question_data().is_some_and(|q| !q.multi_select) {
+                        self.select_single();
                     }
+                    self.submit_or_advance()
                 }
             }
             KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                // Ctrl+S to submit if all answered
-                if self.all_questions_answered() {
-                    Some(self.build_response())
-                } else {
-                    None
-                }
+                // Ctrl+S submits once every required question is answered
+                self.try_submit()
             }
             _ => None,
         }
@@ -242,26 +323,21 @@ impl AskUserDialog {
             }
             KeyCode::Enter => {
                 // Submit custom text
-                if !self.custom_text.is_empty() {
-                    let text = format!("Other: {}", self.custom_text);
-                    self.answers[self.current_question] = vec![text];
-                    self.input_mode = InputMode::Selecting;
-                    self.custom_text.clear();
-
-                    // Check if we can submit
-                    if self.all_questions_answered() {
-                        Some(self.build_response())
-                    } else if self.current_question < self.questions.len() - 1 {
-                        self.current_question += 1;
-                        self.selected_option = 0;
-                        self.list_state.select(Some(0));
-                        None
-                    } else {
-                        None
-                    }
-                } else {
-                    None
+                if self.custom_text.is_empty() {
+                    return None;
                 }
+                let validation = self.current_question_data().and_then(|q| q.validation.as_ref());
+                if let Some(Err(message)) = validation.map(|v| v.check(&self.custom_text)) {
+                    self.error = Some(message);
+                    return None;
+                }
+                let multi_select = self.current_question_data().is_some_and(|q| q.multi_select);
+                if !multi_select {
+                    self.answers[self.current_question].clear();
+                }
+                self.customs[self.current_question] = Some(std::mem::take(&mut self.custom_text));
+                self.input_mode = InputMode::Selecting;
+                self.submit_or_advance()
             }
             KeyCode::Backspace => {
                 self.custom_text.pop();
@@ -275,20 +351,74 @@ impl AskUserDialog {
         }
     }
 
+    /// Single select: the highlighted option replaces any earlier answer
+    fn select_single(&mut self) {
+        if let Some(option) = self.current_question_data().and_then(|q| q.options.get(self.selected_option)) {
+            let label = option.label.clone();
+            self.answers[self.current_question] = vec![label];
+            self.customs[self.current_question] = None;
+        }
+    }
+
+    fn is_answered(&self, index: usize) -> bool {
+        !self.answers[index].is_empty() || self.customs[index].is_some()
+    }
+
     fn all_questions_answered(&self) -> bool {
-        self.answers.iter().all(|a| !a.is_empty())
+        (0..self.questions.len()).all(|i| self.is_answered(i))
+    }
+
+    /// First required question without an answer
+    fn first_missing_required(&self) -> Option<usize> {
+        (0..self.questions.len()).find(|&i| self.questions[i].required && !self.is_answered(i))
+    }
+
+    fn go_to_question(&mut self, index: usize) {
+        self.current_question = index;
+        self.selected_option = 0;
+        self.list_state.select(Some(0));
+    }
+
+    /// After answering: submit once everything is answered, otherwise move on to
+    /// the next question; on the last one, submit if only optional ones are left
+    fn submit_or_advance(&mut self) -> Option<Action> {
+        if self.all_questions_answered() {
+            Some(self.build_response(false))
+        } else if self.current_question + 1 < self.questions.len() {
+            self.go_to_question(self.current_question + 1);
+            None
+        } else {
+            self.try_submit()
+        }
+    }
+
+    /// Submit, or jump to the first unanswered required question and say so
+    fn try_submit(&mut self) -> Option<Action> {
+        match self.first_missing_required() {
+            Some(index) => {
+                self.error = Some(format!("An answer is required for \"{}\"", self.questions[index].header));
+                self.go_to_question(index);
+                None
+            }
+            None => Some(self.build_response(false)),
+        }
     }
 
-    fn build_response(&self) -> Action {
-        // Join multi-select answers with ", "
-        let answers: Vec<String> = self.answers
+    fn build_response(&self, timed_out: bool) -> Action {
+        let answers: Vec<AskUserAnswer> = self.questions
             .iter()
-            .map(|a| a.join(", "))
+            .enumerate()
+            .map(|(i, q)| AskUserAnswer {
+                header: q.header.clone(),
+                selected: self.answers[i].clone(),
+                other: self.customs[i].clone(),
+            })
             .collect();
 
         let response = AskUserResponse {
             tool_use_id: self.tool_use_id.clone(),
             answers,
+            timed_out,
         };
 
         self.hide_without_clear();
@@ -364,35 +494,69 @@ impl AskUserDialog {
             self.render_options(frame, chunks[2], &q, theme);
         }
 
-        // Custom text input area
+        // Custom text input area; validation errors replace its title
+        let error_style = Style::default().fg(theme.colors.error.to_color());
         if self.input_mode == InputMode::CustomText {
+            let (title, border) = match self.error {
+                Some(ref message) => (format!(" {} ", message), error_style),
+                None => (" Enter custom response ".to_string(), Style::default().fg(theme.colors.primary.to_color())),
+            };
             let input_block = Block::default()
-                .title(" Enter custom response ")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.colors.primary.to_color()));
+                .border_style(border);
             let input_text = Paragraph::new(self.custom_text.as_str())
                 .block(input_block);
             frame.render_widget(input_text, chunks[3]);
+        } else if let Some(ref message) = self.error {
+            let error_text = Paragraph::new(message.as_str())
+                .style(error_style)
+                .alignment(Alignment::Center);
+            frame.render_widget(error_text, chunks[3]);
         }
 
         // Hints
         let hints = if self.input_mode == InputMode::CustomText {
             "Enter: submit | Esc: cancel"
         } else if self.current_question_data().map(|q| q.multi_select).unwrap_or(false) {
-            "Space: toggle | Enter: next/submit | Tab: next Q | Esc: cancel"
+            "Space: toggle | Enter: next/submit | Tab: next Q | C-s: submit | Esc: cancel"
         } else {
-            "Enter: select | Tab: next Q | Esc: cancel"
+            "Enter: select | Tab: next Q | C-s: submit | Esc: cancel"
         };
-        let hints_text = Paragraph::new(hints)
+        let mut hint_lines = vec![Line::from(hints)];
+        if let Some(line) = self.countdown_line(theme) {
+            hint_lines.push(line);
+        }
+        let hints_text = Paragraph::new(hint_lines)
             .style(Style::default().fg(theme.colors.muted.to_color()))
             .alignment(Alignment::Center);
         frame.render_widget(hints_text, chunks[4]);
     }
 
+    /// "Using defaults in 12s", "No response in 12s" when some required
+    /// question has no default, or "Timeout paused" once the user interacted
+    fn countdown_line(&self, theme: &Theme) -> Option<Line<'static>> {
+        let countdown = self.countdown.as_ref()?;
+        if countdown.paused {
+            return Some(Line::from("Timeout paused"));
+        }
+        let has_defaults = (0..self.questions.len())
+            .all(|i| !self.questions[i].required || self.is_answered(i) || !self.questions[i].default.is_empty());
+        let (label, color) = if has_defaults {
+            ("Using defaults", theme.colors.warning.to_color())
+        } else {
+            ("No response", theme.colors.error.to_color())
+        };
+        Some(Line::from(Span::styled(
+            format!("{} in {}s", label, countdown.remaining.as_secs_f32().ceil() as u64),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )))
+    }
+
     fn render_question_tabs(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let mut spans = Vec::new();
         for (i, q) in self.questions.iter().enumerate() {
-            let answered = !self.answers[i].is_empty();
+            let answered = self.is_answered(i);
             let is_current = i == self.current_question;
 
             let style = if is_current {
@@ -404,7 +568,8 @@ impl AskUserDialog {
             };
 
             let marker = if answered { "[x]" } else { "[ ]" };
-            spans.push(Span::styled(format!(" {} {} ", marker, q.header), style));
+            let optional = if q.required { "" } else { " (optional)" };
+            spans.push(Span::styled(format!(" {} {}{} ", marker, q.header, optional), style));
 
             if i < self.questions.len() - 1 {
                 spans.push(Span::raw(" | "));
@@ -451,7 +616,8 @@ impl AskUserDialog {
             .chain(std::iter::once({
                 // "Other" option
                 let is_other_selected = self.selected_option >= question.options.len();
-                let has_custom = current_answers.iter().any(|a| a.starts_with("Other:"));
+                let custom = self.customs[self.current_question].as_deref();
+                let has_custom = custom.is_some();
                 let marker = if question.multi_select {
                     if has_custom { "[x]" } else { "[ ]" }
                 } else if has_custom {
@@ -471,7 +637,7 @@ impl AskUserDialog {
                 ListItem::new(vec![
                     Line::from(Span::styled(format!("{} Other...", marker), style)),
                     Line::from(Span::styled(
-                        "    Enter a custom response",
+                        format!("    {}", custom.unwrap_or("Enter a custom response")),
                         Style::default().fg(theme.colors.muted.to_color()),
                     )),
                 ])
@@ -490,3 +656,144 @@ impl Default for AskUserDialog {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{AnswerValidation, ParsedOption};
+    use crossterm::event::KeyEvent;
+
+    fn question(header: &str, required: bool, multi_select: bool, validation: Option<AnswerValidation>) -> ParsedQuestion {
+        ParsedQuestion {
+            header: header.to_string(),
+            question: format!("{}?", header),
+            options: ["a", "b"].iter().map(|l| ParsedOption { label: l.to_string(), description: String::new() }).collect(),
+            multi_select,
+            required,
+            validation,
+            default: Vec::new(),
+        }
+    }
+
+    fn press(dialog: &mut AskUserDialog, code: KeyCode) -> Option<Action> {
+        dialog.handle_event(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn type_text(dialog: &mut AskUserDialog, text: &str) {
+        for c in text.chars() {
+            press(dialog, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_required_questions_block_submit() {
+        let mut dialog = AskUserDialog::new();
+        dialog.show("t1".to_string(), vec![question("Lang", true, false, None), question("Notes", false, false, None)]);
+
+        // Ctrl+S before answering points at the missing required question
+        let submit = Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(dialog.handle_event(&submit).is_none());
+        assert!(dialog.error.as_deref().is_some_and(|e| e.contains("Lang")));
+
+        // Answering the required one moves on; the optional one can be skipped
+        assert!(press(&mut dialog, KeyCode::Enter).is_none());
+        assert_eq!(dialog.current_question, 1);
+        match dialog.handle_event(&submit) {
+            Some(Action::AskUserRespond(response)) => {
+                let json = serde_json::to_value(&response.answers).unwrap();
+                assert_eq!(json, serde_json::json!([
+                    {"header": "Lang", "selected": ["a"]},
+                    {"header": "Notes", "selected": []},
+                ]));
+            }
+            other => panic!("expected a response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_custom_answer_validation() {
+        let range = AnswerValidation { min: Some(1.0), max: Some(10.0), ..Default::default() };
+        let mut dialog = AskUserDialog::new();
+        dialog.show("t1".to_string(), vec![question("Count", true, true, Some(range))]);
+
+        // Select "a", then open "Other..." and enter an out-of-range number
+        press(&mut dialog, KeyCode::Char(' '));
+        press(&mut dialog, KeyCode::Down);
+        press(&mut dialog, KeyCode::Down);
+        press(&mut dialog, KeyCode::Enter);
+        type_text(&mut dialog, "42");
+        assert!(press(&mut dialog, KeyCode::Enter).is_none());
+        assert_eq!(dialog.error.as_deref(), Some("Must be between 1 and 10"));
+        assert_eq!(dialog.input_mode, InputMode::CustomText, "stays open to fix the answer");
+
+        press(&mut dialog, KeyCode::Backspace);
+        press(&mut dialog, KeyCode::Backspace);
+        type_text(&mut dialog, "7");
+        match press(&mut dialog, KeyCode::Enter) {
+            Some(Action::AskUserRespond(response)) => {
+                assert_eq!(response.answers[0].selected, ["a"], "multi-select keeps chosen options");
+                assert_eq!(response.answers[0].other.as_deref(), Some("7"));
+            }
+            other => panic!("expected a response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_timeout_submits_defaults() {
+        let mut lang = question("Lang", true, false, None);
+        lang.default = vec!["b".to_string()];
+        let mut tags = question("Tags", true, true, None);
+        tags.default = vec!["a".to_string(), "custom".to_string()];
+        let mut dialog = AskUserDialog::new();
+        dialog.show("t1".to_string(), vec![lang, tags, question("Notes", false, false, None)]);
+        dialog.start_countdown(Duration::from_secs(10));
+        assert!(dialog.countdown_active());
+
+        assert!(dialog.advance(Duration::from_secs(4)).is_none());
+        match dialog.advance(Duration::from_secs(6)) {
+            Some(Action::AskUserRespond(response)) => {
+                assert!(response.timed_out);
+                let json = serde_json::to_value(&response.answers).unwrap();
+                assert_eq!(json, serde_json::json!([
+                    {"header": "Lang", "selected": ["b"]},
+                    {"header": "Tags", "selected": ["a"], "other": "custom"},
+                    {"header": "Notes", "selected": []},
+                ]));
+            }
+            other => panic!("expected a response, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_timeout_without_default_times_out() {
+        let mut dialog = AskUserDialog::new();
+        dialog.show("t1".to_string(), vec![question("Lang", true, false, None)]);
+        dialog.start_countdown(Duration::from_secs(5));
+        assert!(matches!(
+            dialog.advance(Duration::from_secs(5)),
+            Some(Action::AskUserTimedOut(id)) if id == "t1"
+        ));
+    }
+
+    #[test]
+    fn test_key_press_pauses_timeout() {
+        let mut dialog = AskUserDialog::new();
+        dialog.show("t1".to_string(), vec![question("Lang", true, false, None)]);
+        dialog.start_countdown(Duration::from_secs(5));
+        press(&mut dialog, KeyCode::Down);
+        assert!(!dialog.countdown_active());
+        assert!(dialog.advance(Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_validation_rules() {
+        let pattern = AnswerValidation { pattern: Some("[a-z]+".to_string()), ..Default::default() };
+        assert!(pattern.check("abc").is_ok());
+        assert!(pattern.check("abc1").is_err(), "the whole answer must match");
+
+        let min = AnswerValidation { min: Some(0.5), ..Default::default() };
+        assert!(min.check("0.5").is_ok());
+        assert_eq!(min.check("x"), Err("Must be a number".to_string()));
+        assert_eq!(min.check("0"), Err("Must be at least 0.5".to_string()));
+    }
+}
@@ -8,9 +8,17 @@ use ratatui::{
     Frame,
 };
 
+use base64::Engine;
+
 use crate::action::Action;
+use crate::components::input_history::InputHistory;
 use crate::components::Component;
-use crate::config::Theme;
+use crate::config::{ChatSendKey, Theme};
+use crate::llm::{ImageContent, ImageSource};
+
+/// Input box height bounds (including borders); it grows with the text in between
+const MIN_HEIGHT: u16 = 3;
+const MAX_HEIGHT: u16 = 12;
 
 /// Selection position in logical text coordinates (line_index, char_column)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,6 +33,92 @@ impl SelectionPos {
     }
 }
 
+/// Images larger than this are refused (Anthropic's per-image limit)
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// An image pasted from the clipboard or attached with `/image <path>`,
+/// sent with the next message
+#[derive(Debug, Clone)]
+pub struct PastedImage {
+    /// Shown in the input title
+    label: String,
+    media_type: String,
+    /// Base64-encoded image bytes
+    data: String,
+}
+
+impl PastedImage {
+    /// Encode clipboard pixels (8-bit RGBA, as arboard returns them) as PNG
+    pub fn from_rgba(width: usize, height: usize, rgba: &[u8]) -> Result<Self, String> {
+        let mut png_data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png_data, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(rgba).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())?;
+        Ok(Self {
+            label: format!("📎 image pasted ({}x{})", width, height),
+            media_type: "image/png".to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(png_data),
+        })
+    }
+
+    /// Read a PNG, JPEG, GIF or WebP file; the type comes from the file's
+    /// contents, not its extension
+    pub fn from_file(path: &std::path::Path) -> Result<Self, String> {
+        let size = std::fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?.len();
+        if size > MAX_IMAGE_BYTES {
+            return Err(format!("{} is larger than {} MB", path.display(), MAX_IMAGE_BYTES / (1024 * 1024)));
+        }
+        let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let media_type = sniff_image_type(&bytes)
+            .ok_or_else(|| format!("{} is not a PNG, JPEG, GIF or WebP image", path.display()))?;
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        Ok(Self {
+            label: format!("📎 {}", name),
+            media_type: media_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        })
+    }
+
+    pub fn into_content(self) -> ImageContent {
+        ImageContent {
+            source: ImageSource::Base64(self.data),
+            media_type: self.media_type,
+        }
+    }
+
+    /// "📎 image pasted (WxH)" or "📎 <file name>"
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+}
+
+/// Media type from an image's magic bytes
+fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Ctrl+R reverse search through the input history
+struct HistorySearch {
+    query: String,
+    /// History index of the entry shown (None = no match)
+    found: Option<usize>,
+    /// Input text before the search, restored on cancel
+    original: String,
+}
+
 /// Multi-line text input component for composing LLM messages
 pub struct ChatInput {
     /// Text buffer containing all lines
@@ -41,6 +135,17 @@ pub struct ChatInput {
     selecting: bool,
     /// Inner area (without borders) for coordinate conversion
     inner_area: Rect,
+    /// Sent messages, recalled with Up/Down and Ctrl+R
+    history: InputHistory,
+    search: Option<HistorySearch>,
+    /// Key that sends (resolved); with a modified send key Enter inserts a newline
+    send_key: ChatSendKey,
+    /// The terminal reports Ctrl+Enter; without it Ctrl+Enter arrives as Ctrl+J
+    keyboard_enhanced: bool,
+    /// Ctrl+A/Ctrl+E line start/end and Alt+B/Alt+F word movement (off: Ctrl+A selects all)
+    emacs_keys: bool,
+    /// Images attached to the next message; kept when the text is cleared
+    images: Vec<PastedImage>,
 }
 
 impl ChatInput {
@@ -53,9 +158,163 @@ impl ChatInput {
             selection: None,
             selecting: false,
             inner_area: Rect::default(),
+            history: InputHistory::new(),
+            search: None,
+            send_key: ChatSendKey::default().resolve(false),
+            keyboard_enhanced: false,
+            emacs_keys: true,
+            images: Vec::new(),
+        }
+    }
+
+    /// Attach an image to the next message
+    pub fn attach_image(&mut self, image: PastedImage) {
+        self.images.push(image);
+    }
+
+    /// Images attached to the next message
+    pub fn images(&self) -> &[PastedImage] {
+        &self.images
+    }
+
+    /// Take the attached images for sending
+    pub fn take_images(&mut self) -> Vec<PastedImage> {
+        std::mem::take(&mut self.images)
+    }
+
+    /// Apply `ui.chat_emacs_keys`
+    pub fn set_emacs_keys(&mut self, enabled: bool) {
+        self.emacs_keys = enabled;
+    }
+
+    /// Apply `ui.chat_send_key`, given whether the terminal accepted keyboard enhancement
+    pub fn set_send_key(&mut self, send_key: ChatSendKey, keyboard_enhanced: bool) {
+        self.send_key = send_key.resolve(keyboard_enhanced);
+        self.keyboard_enhanced = keyboard_enhanced;
+    }
+
+    /// Send key as the user has to press it on this terminal
+    fn send_key_label(&self) -> &'static str {
+        match self.send_key {
+            ChatSendKey::CtrlEnter if !self.keyboard_enhanced => "Ctrl+J",
+            key => key.label(),
+        }
+    }
+
+    /// Box height for `width` columns: one row per wrapped line plus borders,
+    /// between MIN_HEIGHT and MAX_HEIGHT (the text scrolls beyond that)
+    pub fn height_for(&self, width: u16) -> u16 {
+        let wrap_width = width.saturating_sub(2).max(1) as usize;
+        let rows: usize = self.lines.iter().map(|line| line.chars().count().div_ceil(wrap_width).max(1)).sum();
+        (rows as u16).saturating_add(2).clamp(MIN_HEIGHT, MAX_HEIGHT)
+    }
+
+    fn is_send_key(&self, key: &KeyEvent) -> bool {
+        let mods = key.modifiers;
+        match self.send_key {
+            ChatSendKey::CtrlEnter => {
+                mods.contains(KeyModifiers::CONTROL) && matches!(key.code, KeyCode::Enter | KeyCode::Char('j'))
+            }
+            ChatSendKey::AltEnter => mods.contains(KeyModifiers::ALT) && key.code == KeyCode::Enter,
+            ChatSendKey::Enter => {
+                key.code == KeyCode::Enter
+                    && !mods.intersects(KeyModifiers::ALT | KeyModifiers::SHIFT | KeyModifiers::CONTROL)
+            }
+            // Resolved in set_send_key
+            ChatSendKey::Auto => false,
+        }
+    }
+
+    /// Send the message, unless there is nothing to send
+    fn send(&mut self) -> Option<Action> {
+        if self.is_empty() {
+            return Some(Action::None);
+        }
+        let message = self.text();
+        self.clear();
+        self.history.push(&message);
+        // `/image <path>` attaches a file instead of sending
+        if let Some(path) = message.trim().strip_prefix("/image ").map(str::trim).filter(|p| !p.is_empty()) {
+            return Some(Action::ChatInputAttachImageFile(path.into()));
+        }
+        Some(Action::LlmSendMessage(message))
+    }
+
+    /// Use `history` for recall (e.g. one loaded from disk)
+    pub fn set_history(&mut self, history: InputHistory) {
+        self.history = history;
+    }
+
+    /// Whether a Ctrl+R search is in progress (it owns Esc)
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Replace the buffer with `text`, cursor at the end
+    pub fn set_text(&mut self, text: &str) {
+        self.clear();
+        self.paste_text(text);
+    }
+
+    /// Up on the first line: show the previous message
+    fn history_older(&mut self) {
+        if let Some(text) = self.history.older(&self.text()) {
+            self.set_text(&text);
+        }
+    }
+
+    /// Down on the last line while browsing: show the next message or the draft
+    fn history_newer(&mut self) {
+        if let Some(text) = self.history.newer(&self.text()) {
+            self.set_text(&text);
+        }
+    }
+
+    /// Show the newest match of the search query older than `before`
+    fn search_from(&mut self, before: usize) {
+        let Some(ref mut search) = self.search else {
+            return;
+        };
+        let found = self.history.search(&search.query, before).map(|(i, entry)| (i, entry.to_string()));
+        search.found = found.as_ref().map(|(i, _)| *i);
+        if let Some((_, entry)) = found {
+            self.set_text(&entry);
         }
     }
 
+    /// Keys while Ctrl+R search is active
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let search = self.search.as_mut()?;
+        match (key.modifiers, key.code) {
+            // Again: next older match
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                let before = search.found.unwrap_or(usize::MAX);
+                self.search_from(before);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('g')) | (_, KeyCode::Esc) => {
+                let original = std::mem::take(&mut search.original);
+                self.search = None;
+                self.set_text(&original);
+            }
+            (_, KeyCode::Backspace) => {
+                search.query.pop();
+                self.search_from(usize::MAX);
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                search.query.push(c);
+                self.search_from(usize::MAX);
+            }
+            // Enter keeps the match in the input for editing
+            (_, KeyCode::Enter) => self.search = None,
+            // Anything else keeps the match and acts as usual
+            _ => {
+                self.search = None;
+                return self.handle_key(key);
+            }
+        }
+        Some(Action::None)
+    }
+
     /// Get the current text content as a single string
     pub fn text(&self) -> String {
         self.lines.join("\n")
@@ -241,6 +500,107 @@ impl ChatInput {
         self.ensure_cursor_visible();
     }
 
+    /// Move cursor to the start of the current or previous word
+    fn move_word_left(&mut self) {
+        self.clear_selection();
+        let (line, col) = self.cursor;
+        if col == 0 {
+            if line > 0 {
+                self.cursor = (line - 1, self.lines[line - 1].chars().count());
+            }
+        } else {
+            let chars: Vec<char> = self.lines[line].chars().collect();
+            let mut i = col;
+            while i > 0 && !is_word_char(chars[i - 1]) {
+                i -= 1;
+            }
+            while i > 0 && is_word_char(chars[i - 1]) {
+                i -= 1;
+            }
+            self.cursor.1 = i;
+        }
+        self.ensure_cursor_visible();
+    }
+
+    /// Move cursor to the end of the current or next word
+    fn move_word_right(&mut self) {
+        self.clear_selection();
+        let (line, col) = self.cursor;
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        if col >= chars.len() {
+            if line + 1 < self.lines.len() {
+                self.cursor = (line + 1, 0);
+            }
+        } else {
+            let mut i = col;
+            while i < chars.len() && !is_word_char(chars[i]) {
+                i += 1;
+            }
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            self.cursor.1 = i;
+        }
+        self.ensure_cursor_visible();
+    }
+
+    /// Run a cursor movement; with `extend` (Shift held) the selection grows
+    /// from where it started instead of being cleared
+    fn move_or_select(&mut self, extend: bool, movement: fn(&mut Self)) {
+        let anchor = match self.selection {
+            Some((anchor, _)) if extend => anchor,
+            _ => SelectionPos::new(self.cursor.0, self.cursor.1),
+        };
+        movement(self);
+        if extend {
+            self.selection = Some((anchor, SelectionPos::new(self.cursor.0, self.cursor.1)));
+        }
+    }
+
+    /// Delete from the cursor to where `movement` takes it (or the selection, if any)
+    fn delete_with(&mut self, movement: fn(&mut Self)) {
+        if self.delete_selection() {
+            return;
+        }
+        let from = SelectionPos::new(self.cursor.0, self.cursor.1);
+        movement(self);
+        self.selection = Some((from, SelectionPos::new(self.cursor.0, self.cursor.1)));
+        self.delete_selection();
+    }
+
+    fn select_all(&mut self) {
+        self.move_to_start();
+        self.move_or_select(true, Self::move_to_end);
+    }
+
+    /// Cursor movement bound to `key`, ignoring Shift (which extends the selection)
+    fn movement(&self, key: &KeyEvent) -> Option<fn(&mut Self)> {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        let mods = key.modifiers - KeyModifiers::SHIFT;
+        let plain = mods.is_empty();
+        let ctrl = mods == KeyModifiers::CONTROL;
+        let alt = mods == KeyModifiers::ALT;
+        let emacs = self.emacs_keys;
+        Some(match key.code {
+            KeyCode::Left if plain => Self::move_left,
+            KeyCode::Right if plain => Self::move_right,
+            KeyCode::Left if ctrl || alt => Self::move_word_left,
+            KeyCode::Right if ctrl || alt => Self::move_word_right,
+            // Plain Up/Down also browse the history, see handle_key
+            KeyCode::Up if plain && shift => Self::move_up,
+            KeyCode::Down if plain && shift => Self::move_down,
+            KeyCode::Home if plain => Self::move_to_line_start,
+            KeyCode::End if plain => Self::move_to_line_end,
+            KeyCode::Home if ctrl => Self::move_to_start,
+            KeyCode::End if ctrl => Self::move_to_end,
+            KeyCode::Char('a') if ctrl && emacs => Self::move_to_line_start,
+            KeyCode::Char('e') if ctrl && emacs => Self::move_to_line_end,
+            KeyCode::Char('b') if alt && emacs => Self::move_word_left,
+            KeyCode::Char('f') if alt && emacs => Self::move_word_right,
+            _ => return None,
+        })
+    }
+
     /// Move cursor to start of line
     fn move_to_line_start(&mut self) {
         self.clear_selection();
@@ -579,30 +939,60 @@ impl ChatInput {
     /// Handle key events for text editing
     /// Returns Some(Action::None) when the event was consumed but no dispatch is needed
     fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if self.search.is_some() {
+            return self.handle_search_key(key);
+        }
+        // Send key (`ui.chat_send_key`) first: it may be a modified Enter or Ctrl+J
+        if self.is_send_key(&key) {
+            return self.send();
+        }
+        if let Some(movement) = self.movement(&key) {
+            self.move_or_select(key.modifiers.contains(KeyModifiers::SHIFT), movement);
+            return Some(Action::None);
+        }
         match (key.modifiers, key.code) {
-            // Ctrl+J: insert newline (traditional Unix, reliable in WSL2)
+            // Ctrl+J when it isn't the send key (chat_send_key = enter or alt_enter)
             (mods, KeyCode::Char('j')) if mods.contains(KeyModifiers::CONTROL) => {
                 self.insert_newline();
                 Some(Action::None)
             }
             
-            // Alt+Enter, Shift+Enter, or Ctrl+Enter: insert newline (must check BEFORE plain Enter)
-            (mods, KeyCode::Enter) if mods.intersects(KeyModifiers::ALT | KeyModifiers::SHIFT | KeyModifiers::CONTROL) => {
+            // Any other Enter inserts a newline
+            (_, KeyCode::Enter) => {
                 self.insert_newline();
                 Some(Action::None) // Consumed, don't bubble up
             }
             
-            // Enter: send message (more intuitive UX)
-            (_, KeyCode::Enter) => {
-                if !self.is_empty() {
-                    let message = self.text();
-                    self.clear();
-                    return Some(Action::LlmSendMessage(message));
-                }
-                // Empty message: consume the event but do nothing
+            // Ctrl+W, Alt/Ctrl+Backspace: delete the word before the cursor
+            (KeyModifiers::CONTROL, KeyCode::Char('w'))
+            | (KeyModifiers::ALT | KeyModifiers::CONTROL, KeyCode::Backspace) => {
+                self.delete_with(Self::move_word_left);
                 Some(Action::None)
             }
-            
+
+            // Alt+D, Ctrl+Delete: delete the word after the cursor
+            (KeyModifiers::ALT, KeyCode::Char('d')) | (KeyModifiers::CONTROL, KeyCode::Delete) => {
+                self.delete_with(Self::move_word_right);
+                Some(Action::None)
+            }
+
+            // Ctrl+A selects everything when emacs keys are off
+            (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
+                self.select_all();
+                Some(Action::None)
+            }
+
+            // Ctrl+Shift+V: attach an image from the clipboard
+            (mods, KeyCode::Char('v' | 'V')) if mods.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) => {
+                Some(Action::ChatInputPasteImage)
+            }
+
+            // Backspace in an empty input drops the last attached image
+            (_, KeyCode::Backspace) if self.is_empty() && !self.images.is_empty() => {
+                self.images.pop();
+                Some(Action::None)
+            }
+
             // Backspace
             (_, KeyCode::Backspace) => {
                 self.delete_char_before();
@@ -615,51 +1005,28 @@ impl ChatInput {
                 Some(Action::None)
             }
             
-            // Arrow keys
-            (KeyModifiers::NONE, KeyCode::Left) => {
-                self.move_left();
-                Some(Action::None)
-            }
-            (KeyModifiers::NONE, KeyCode::Right) => {
-                self.move_right();
-                Some(Action::None)
-            }
+            // Up/Down move between lines; past the first/last line they
+            // step through previously sent messages
             (KeyModifiers::NONE, KeyCode::Up) => {
-                self.move_up();
+                if self.cursor.0 == 0 {
+                    self.history_older();
+                } else {
+                    self.move_up();
+                }
                 Some(Action::None)
             }
             (KeyModifiers::NONE, KeyCode::Down) => {
-                self.move_down();
-                Some(Action::None)
-            }
-            
-            // Home/End
-            (KeyModifiers::NONE, KeyCode::Home) => {
-                self.move_to_line_start();
-                Some(Action::None)
-            }
-            (KeyModifiers::NONE, KeyCode::End) => {
-                self.move_to_line_end();
-                Some(Action::None)
-            }
-            (KeyModifiers::CONTROL, KeyCode::Home) => {
-                self.move_to_start();
-                Some(Action::None)
-            }
-            (KeyModifiers::CONTROL, KeyCode::End) => {
-                self.move_to_end();
-                Some(Action::None)
-            }
-            
-            // Ctrl+A: start of line (emacs-style)
-            (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
-                self.move_to_line_start();
+                if self.cursor.0 + 1 == self.lines.len() && self.history.is_browsing() {
+                    self.history_newer();
+                } else {
+                    self.move_down();
+                }
                 Some(Action::None)
             }
-            
-            // Ctrl+E: end of line (emacs-style)
-            (KeyModifiers::CONTROL, KeyCode::Char('e')) => {
-                self.move_to_line_end();
+
+            // Ctrl+R: reverse search through sent messages
+            (KeyModifiers::CONTROL, KeyCode::Char('r')) => {
+                self.search = Some(HistorySearch { query: String::new(), found: None, original: self.text() });
                 Some(Action::None)
             }
             
@@ -722,7 +1089,20 @@ impl ChatInput {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(border_style)
-            .title(Span::styled(" Message ", title_style));
+            .title(Span::styled(self.title(), title_style));
+        // Which key sends, while typing
+        let block = if focused {
+            let newline = if self.send_key == ChatSendKey::Enter { "Shift+Enter" } else { "Enter" };
+            block.title_bottom(
+                Line::from(Span::styled(
+                    format!(" {} send · {} newline ", self.send_key_label(), newline),
+                    Style::default().fg(theme.colors.muted.to_color()),
+                ))
+                .right_aligned(),
+            )
+        } else {
+            block
+        };
 
         let inner_area = block.inner(area);
         let wrap_width = inner_area.width as usize;
@@ -807,6 +1187,13 @@ impl ChatInput {
                     visual_lines.push((Line::from(spans), line_idx, char_offset));
                     char_offset = chunk_end;
                 }
+
+                // A cursor after a line that exactly fills the width goes on a row of its own
+                if is_cursor_line && self.cursor.1 == chars.len() && chars.len() % wrap_width == 0 {
+                    cursor_visual_row = Some(visual_lines.len());
+                    cursor_visual_col = 0;
+                    visual_lines.push((Line::from(""), line_idx, chars.len()));
+                }
             }
         }
 
@@ -886,6 +1273,21 @@ impl ChatInput {
         }
     }
 
+    /// Block title; shows the query during Ctrl+R search
+    fn title(&self) -> String {
+        match self.search {
+            Some(ref search) if search.found.is_none() && !search.query.is_empty() => {
+                format!(" (failed reverse-i-search)`{}' ", search.query)
+            }
+            Some(ref search) => format!(" (reverse-i-search)`{}' ", search.query),
+            None if !self.images.is_empty() => {
+                let labels: Vec<String> = self.images.iter().map(PastedImage::label).collect();
+                format!(" Message · {} ", labels.join(" · "))
+            }
+            None => " Message ".to_string(),
+        }
+    }
+
     // Internal method to update visible height without &mut self (for render)
     fn set_visible_height_internal(&self, height: u16) {
         // This is a workaround - in practice the caller should set this
@@ -918,6 +1320,11 @@ impl Component for ChatInput {
     }
 }
 
+/// Characters that make up a word for word movement and deletion
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
 /// Convert a character index to a byte index in a string
 fn char_to_byte_pos(s: &str, char_pos: usize) -> usize {
     s.char_indices()
@@ -1038,4 +1445,179 @@ mod tests {
         input.delete_char_before();
         assert_eq!(input.text(), "日本");
     }
+
+    #[test]
+    fn test_send_key_and_newlines() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let mut input = ChatInput::new();
+        // Without keyboard enhancement the default is Alt+Enter
+        input.paste_text("a");
+        assert!(matches!(input.handle_key(key(KeyCode::Enter, KeyModifiers::ALT)), Some(Action::LlmSendMessage(_))));
+        assert_eq!(input.send_key_label(), "Alt+Enter");
+
+        input.set_send_key(ChatSendKey::CtrlEnter, false);
+        assert_eq!(input.send_key_label(), "Ctrl+J");
+        input.set_send_key(ChatSendKey::Auto, true);
+        assert_eq!(input.send_key_label(), "Ctrl+Enter");
+        input.paste_text("a");
+        assert!(matches!(input.handle_key(key(KeyCode::Enter, KeyModifiers::NONE)), Some(Action::None)));
+        input.handle_key(key(KeyCode::Enter, KeyModifiers::SHIFT));
+        input.paste_text("b");
+        assert_eq!(input.text(), "a\n\nb");
+        assert_eq!(input.height_for(20), 5);
+        assert!(matches!(
+            input.handle_key(key(KeyCode::Char('j'), KeyModifiers::CONTROL)),
+            Some(Action::LlmSendMessage(ref m)) if m == "a\n\nb"
+        ));
+        assert_eq!(input.height_for(20), MIN_HEIGHT);
+
+        input.set_send_key(ChatSendKey::Enter, true);
+        input.paste_text("x");
+        input.handle_key(key(KeyCode::Enter, KeyModifiers::ALT));
+        input.paste_text(&"y".repeat(200));
+        assert_eq!(input.height_for(20), MAX_HEIGHT);
+        assert!(matches!(input.handle_key(key(KeyCode::Enter, KeyModifiers::NONE)), Some(Action::LlmSendMessage(_))));
+    }
+
+    #[test]
+    fn test_history_recall_and_search() {
+        let press = |input: &mut ChatInput, modifiers: KeyModifiers, code: KeyCode| {
+            input.handle_key(KeyEvent::new(code, modifiers));
+        };
+        let mut input = ChatInput::new();
+        input.set_send_key(ChatSendKey::CtrlEnter, true);
+        for message in ["fix the parser", "two\nlines"] {
+            input.paste_text(message);
+            assert!(matches!(input.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL)), Some(Action::LlmSendMessage(_))));
+        }
+
+        // Up recalls the multi-line message intact; the unsent draft comes back on Down
+        input.paste_text("draft");
+        press(&mut input, KeyModifiers::NONE, KeyCode::Up);
+        assert_eq!(input.text(), "two\nlines");
+        press(&mut input, KeyModifiers::NONE, KeyCode::Up); // moves within the message
+        press(&mut input, KeyModifiers::NONE, KeyCode::Up);
+        assert_eq!(input.text(), "fix the parser");
+        press(&mut input, KeyModifiers::NONE, KeyCode::Down);
+        press(&mut input, KeyModifiers::NONE, KeyCode::Down);
+        assert_eq!(input.text(), "draft");
+
+        press(&mut input, KeyModifiers::CONTROL, KeyCode::Char('r'));
+        for c in "pars".chars() {
+            press(&mut input, KeyModifiers::NONE, KeyCode::Char(c));
+        }
+        assert_eq!(input.text(), "fix the parser");
+        assert_eq!(input.title(), " (reverse-i-search)`pars' ");
+        press(&mut input, KeyModifiers::NONE, KeyCode::Esc);
+        assert!(!input.is_searching());
+        assert_eq!(input.text(), "draft");
+    }
+
+    #[test]
+    fn test_word_movement_and_deletion_unicode() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let mut input = ChatInput::new();
+        input.paste_text("héllo wörld, 日本語");
+        assert_eq!(input.cursor, (0, 16));
+
+        input.handle_key(key(KeyCode::Left, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor, (0, 13));
+        input.handle_key(key(KeyCode::Left, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor, (0, 6));
+        input.handle_key(key(KeyCode::Right, KeyModifiers::CONTROL));
+        assert_eq!(input.cursor, (0, 11));
+
+        // Ctrl+W deletes back to the word start, Alt+D forward to the word end
+        input.handle_key(key(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(input.text(), "héllo , 日本語");
+        input.handle_key(key(KeyCode::Char('d'), KeyModifiers::ALT));
+        assert_eq!(input.text(), "héllo ");
+        input.handle_key(key(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_eq!(input.text(), "");
+    }
+
+    #[test]
+    fn test_shift_movement_selects() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let mut input = ChatInput::new();
+        input.paste_text("one two\nthree");
+        input.handle_key(key(KeyCode::Left, KeyModifiers::SHIFT | KeyModifiers::CONTROL));
+        input.handle_key(key(KeyCode::Up, KeyModifiers::SHIFT));
+        assert_eq!(input.get_selected_text().as_deref(), Some("one two\nthree"));
+        input.handle_key(key(KeyCode::Right, KeyModifiers::SHIFT));
+        assert_eq!(input.get_selected_text().as_deref(), Some("ne two\nthree"));
+
+        // Plain movement drops the selection; typing replaces it
+        input.handle_key(key(KeyCode::End, KeyModifiers::NONE));
+        assert!(!input.has_selection());
+        input.handle_key(key(KeyCode::Home, KeyModifiers::SHIFT));
+        assert_eq!(input.get_selected_text().as_deref(), Some("one two"));
+        input.handle_key(key(KeyCode::Char('!'), KeyModifiers::NONE));
+        assert_eq!(input.text(), "!\nthree");
+
+        // Ctrl+A moves to the line start, or selects all without emacs keys
+        input.handle_key(key(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        assert_eq!(input.cursor, (0, 0));
+        input.set_emacs_keys(false);
+        input.handle_key(key(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        assert_eq!(input.get_selected_text().as_deref(), Some("!\nthree"));
+    }
+
+    #[test]
+    fn test_pasted_images_ride_along_until_sent() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let mut input = ChatInput::new();
+        assert!(matches!(
+            input.handle_key(key(KeyCode::Char('V'), KeyModifiers::CONTROL | KeyModifiers::SHIFT)),
+            Some(Action::ChatInputPasteImage)
+        ));
+
+        let image = PastedImage::from_rgba(2, 1, &[255, 0, 0, 255, 0, 0, 255, 255]).unwrap();
+        assert!(image.data.starts_with("iVBORw0KGgo"), "PNG signature");
+        input.attach_image(image.clone());
+        input.attach_image(image);
+        assert_eq!(input.title(), " Message · 📎 image pasted (2x1) · 📎 image pasted (2x1) ");
+
+        // Backspace in the empty input drops the last image
+        input.handle_key(key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input.images.len(), 1);
+
+        // Sending clears the text but leaves the image for the send handler
+        input.paste_text("what is this error?");
+        assert!(matches!(input.handle_key(key(KeyCode::Enter, KeyModifiers::ALT)), Some(Action::LlmSendMessage(_))));
+        let images = input.take_images();
+        assert_eq!(images.len(), 1);
+        assert!(matches!(images[0].clone().into_content().source, ImageSource::Base64(_)));
+        assert_eq!(input.title(), " Message ");
+    }
+
+    #[test]
+    fn test_image_command_attaches_file() {
+        let mut input = ChatInput::new();
+        input.paste_text("/image ~/shots/error.png ");
+        match input.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)) {
+            Some(Action::ChatInputAttachImageFile(path)) => assert_eq!(path, std::path::PathBuf::from("~/shots/error.png")),
+            other => panic!("expected an attach action, got {:?}", other),
+        }
+        assert!(input.is_empty());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("shot.JPG");
+        std::fs::write(&path, [0xff, 0xd8, 0xff]).unwrap();
+        let image = PastedImage::from_file(&path).unwrap();
+        assert_eq!(image.label(), "📎 shot.JPG");
+        let content = image.into_content();
+        assert_eq!(content.media_type, "image/jpeg");
+        assert!(matches!(content.source, ImageSource::Base64(ref b64) if b64 == "/9j/"));
+
+        assert!(PastedImage::from_file(&dir.path().join("missing.png")).is_err());
+
+        // The contents decide, not the extension
+        let text = dir.path().join("notes.png");
+        std::fs::write(&text, "not an image").unwrap();
+        assert!(PastedImage::from_file(&text).is_err());
+        let webp = dir.path().join("capture");
+        std::fs::write(&webp, b"RIFF\0\0\0\0WEBPVP8 ").unwrap();
+        assert_eq!(PastedImage::from_file(&webp).unwrap().into_content().media_type, "image/webp");
+    }
 }
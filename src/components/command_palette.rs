@@ -11,7 +11,7 @@ use ratatui::{
 };
 
 use crate::action::Action;
-use crate::config::{SubagentsConfig, Theme};
+use crate::config::{PersonaTemplate, SubagentsConfig, Theme};
 
 /// A command that can be executed from the command palette
 #[derive(Debug, Clone)]
@@ -64,7 +64,10 @@ impl CommandRegistry {
             Command::new("scroll_down", "Scroll Down", "Scroll down one line", Action::ScrollDown(1)),
             Command::new("scroll_page_up", "Scroll Page Up", "Scroll up one page", Action::ScrollPageUp),
             Command::new("scroll_page_down", "Scroll Page Down", "Scroll down one page", Action::ScrollPageDown),
+            Command::new("scroll_half_page_up", "Scroll Half Page Up", "Scroll up half a page", Action::ScrollHalfPageUp),
+            Command::new("scroll_half_page_down", "Scroll Half Page Down", "Scroll down half a page", Action::ScrollHalfPageDown),
             Command::new("scroll_top", "Scroll to Top", "Scroll to beginning", Action::ScrollToTop),
+            Command::new("terminal_reset", "Reset Terminal", "Full reset of a garbled or stuck terminal, keeping the shell", Action::TerminalReset),
             Command::new("scroll_bottom", "Scroll to Bottom", "Scroll to end", Action::ScrollToBottom),
             Command::new("copy", "Copy", "Copy selected text to clipboard", Action::Copy),
             Command::new("paste", "Paste", "Paste from clipboard", Action::Paste),
@@ -77,11 +80,36 @@ impl CommandRegistry {
             // TRC-028: Config panel command (always accessible per CONTRACT requirement)
             Command::new("config_panel_toggle", "Settings", "Open settings panel (view config, theme, providers)", Action::ConfigPanelToggle),
             Command::new("config_panel_show", "Show Settings", "Open settings panel", Action::ConfigPanelShow),
+            Command::new("config_open_dir", "Open Config Directory", "Open a shell tab in the config directory", Action::ConfigOpenDir),
+            Command::new("config_edit_file", "Edit config.toml", "Open config.toml in $EDITOR in a new tab (reloads on save)", Action::ConfigEditFile),
             Command::new("config_panel_hide", "Hide Settings", "Close settings panel", Action::ConfigPanelHide),
+            Command::new("model_picker_show", "Switch Model", "Quick model switcher across all providers", Action::ModelPickerShow),
+            Command::new("overlay_cycle_position", "Move Overlay", "Move the open stream/log/settings panel (center, right, left, full)", Action::OverlayCyclePosition),
             Command::new("llm_cancel", "Cancel LLM", "Cancel current LLM request", Action::LlmCancel),
+            Command::new("llm_pause", "Pause Agent", "Hold the agent loop after the current tool results", Action::LlmPause),
+            Command::new("llm_resume", "Resume Agent", "Continue a paused agent loop", Action::LlmResume),
+            Command::new("agent_retry_last_turn", "Retry Last Turn", "Re-send the request that failed with an error", Action::AgentRetryLastTurn),
+            Command::new("llm_show_last_exchange", "Show Last LLM Exchange", "View the raw request and response of the last LLM call", Action::ShowLastLlmExchange),
+            Command::new("crash_report", "Generate Crash Report", "Bundle logs, redacted config and the last LLM exchange into a .tar.gz", Action::GenerateCrashReport),
+            Command::new("show_turn_changes", "Show Turn Changes", "List files the last agent turn created, modified or deleted", Action::ShowTurnChanges),
+            Command::new("undo_last_edit", "Undo Last Edit", "Restore the files the last agent turn changed", Action::UndoLastEdit),
+            Command::new("chat_paste_image", "Paste Image into Chat", "Attach the clipboard image to the next message (Ctrl+Shift+V)", Action::ChatInputPasteImage),
+            Command::new("show_system_prompt", "Show System Prompt", "View the resolved system prompt sent with each request", Action::ShowSystemPrompt),
             Command::new("llm_clear", "Clear Conversation", "Clear LLM conversation history", Action::LlmClearConversation),
+            Command::new("toggle_compact_mode", "Toggle Compact Mode", "Give the terminal and conversation the full width; process monitor and menu open as overlays", Action::ToggleCompactMode),
             Command::new("conversation_toggle", "Toggle Conversation View", "Show/hide LLM conversation panel (Ctrl+L)", Action::ConversationToggle),
+            Command::new("plan_panel_toggle", "Toggle Plan Panel", "Show/hide the agent's plan beside the conversation", Action::PlanPanelToggle),
             Command::new("toggle_dangerous_mode", "Toggle Dangerous Mode", "Enable/disable dangerous tool execution", Action::ToolToggleDangerousMode),
+            Command::new("agent_toggle_step_mode", "Toggle Step Mode", "Confirm every tool and pause the agent after each result", Action::AgentToggleStepMode),
+            Command::new("agent_toggle_dry_run", "Toggle Dry-Run", "Describe tool calls to the model instead of executing them", Action::AgentToggleDryRun),
+            Command::new("agent_toggle_auto_continue", "Toggle Auto-Continue", "Continue automatically after tool results, or wait for Enter", Action::AgentToggleAutoContinue),
+            Command::new("tool_view_output", "View Tool Output", "Open the selected tool call's full output in the pager (o)", Action::ToolCallViewOutput),
+            Command::new("log_viewer_pager", "Open Logs in Pager", "View filtered log entries in the pager", Action::LogViewerOpenPager),
+            Command::new("log_viewer_wrap", "Toggle Log Wrap", "Wrap or clip long lines in the log viewer", Action::LogViewerToggleWrap),
+            Command::new("log_viewer_timestamps", "Toggle Log Timestamps", "Show or hide the timestamp column in the log viewer", Action::LogViewerToggleTimestamps),
+            Command::new("log_viewer_follow", "Toggle Log Follow", "Follow new log entries or hold the current position (f)", Action::LogViewerToggleAutoScroll),
+            Command::new("stream_viewer_follow", "Toggle Stream Follow", "Follow new stream output or hold the current position (f)", Action::StreamViewerToggleAutoScroll),
+            Command::new("stream_viewer_wrap", "Toggle Stream Wrap", "Wrap or clip long lines in the stream viewer", Action::StreamViewerToggleWrap),
             // Settings Editor commands (TS-014)
             Command::new("settings_editor_toggle", "Edit Settings", "Open settings editor (API keys, provider, model)", Action::SettingsToggle),
             Command::new("settings_editor_show", "Open Settings Editor", "Open the full settings editor panel", Action::SettingsShow),
@@ -93,6 +121,11 @@ impl CommandRegistry {
             Command::new("thread_clear", "Clear Thread", "Clear current thread (start fresh)", Action::ThreadClear),
             Command::new("thread_continue", "Continue Thread", "Resume a saved conversation thread", Action::ThreadPickerShow),
             Command::new("thread_rename", "Rename Thread", "Rename the current conversation thread", Action::ThreadStartRename),
+            Command::new("thread_tags", "Tag Thread", "Edit tags on the current conversation thread", Action::ThreadStartTagEdit),
+            Command::new("thread_usage", "Usage Report", "Token usage and cost for the current thread", Action::ThreadUsageReport),
+            Command::new("thread_usage_export", "Export Usage Report", "Write the current thread's usage report to CSV and JSON", Action::ThreadUsageExport),
+            Command::new("subagent_peek", "Peek Sub-agents", "Show progress and output of running sub-agents", Action::SubagentPeek),
+            Command::new("thread_prune", "Delete Old Threads", "Delete saved threads past the retention period", Action::ThreadPrune),
             // Tab commands
             Command::new("tab_new", "New Tab", "Create a new tab (Ctrl+T)", Action::TabCreate),
             Command::new("tab_close", "Close Tab", "Close current tab (Ctrl+W)", Action::TabClose),
@@ -178,6 +211,27 @@ impl CommandRegistry {
         }
     }
 
+    /// Set available personas (removes old persona commands and adds new ones)
+    pub fn set_personas(&mut self, personas: &[PersonaTemplate], current: Option<&str>) {
+        self.remove_commands_with_prefix("persona:");
+
+        let mark = |active: bool| if active { " ✓" } else { "" };
+        self.commands.push(Command::new(
+            "persona:default",
+            format!("Persona: default{}", mark(current.is_none())),
+            "Use the system prompt without a persona",
+            Action::AgentSelectPersona(None),
+        ));
+        for persona in personas {
+            self.commands.push(Command::new(
+                format!("persona:{}", persona.name),
+                format!("Persona: {}{}", persona.name, mark(current == Some(persona.name.as_str()))),
+                persona.description.clone(),
+                Action::AgentSelectPersona(Some(persona.name.clone())),
+            ));
+        }
+    }
+
     /// Set available models for each subagent type (T2.1b)
     ///
     /// # Arguments
@@ -222,6 +276,26 @@ impl Default for CommandRegistry {
     }
 }
 
+/// Parse a palette-style fuzzy query (smart case, smart normalization)
+pub(crate) fn fuzzy_pattern(query: &str) -> nucleo::pattern::Pattern {
+    nucleo::pattern::Pattern::parse(
+        query,
+        nucleo::pattern::CaseMatching::Smart,
+        nucleo::pattern::Normalization::Smart,
+    )
+}
+
+/// Score `text` against `pattern`, filling `indices` with the matched char positions
+pub(crate) fn fuzzy_indices(
+    pattern: &nucleo::pattern::Pattern,
+    matcher: &mut Matcher,
+    text: &str,
+    indices: &mut Vec<u32>,
+) -> Option<u32> {
+    let text: Utf32String = text.into();
+    pattern.indices(text.slice(..), matcher, indices)
+}
+
 /// Fuzzy matcher result with score and indices
 struct MatchResult {
     command_idx: usize,
@@ -276,6 +350,11 @@ impl CommandPalette {
         self.registry.set_models(models, current_model);
     }
 
+    /// Set available personas in the command palette
+    pub fn set_personas(&mut self, personas: &[PersonaTemplate], current: Option<&str>) {
+        self.registry.set_personas(personas, current);
+    }
+
     /// Set available models for subagents in the command palette
     pub fn set_subagent_models(
         &mut self,
@@ -311,32 +390,17 @@ impl CommandPalette {
             }
         } else {
             // Fuzzy match against query
-            let pattern = nucleo::pattern::Pattern::parse(
-                &self.query,
-                nucleo::pattern::CaseMatching::Smart,
-                nucleo::pattern::Normalization::Smart,
-            );
+            let pattern = fuzzy_pattern(&self.query);
 
             for (idx, cmd) in self.registry.commands().iter().enumerate() {
                 // Match against both name and description
-                let name_utf32: Utf32String = cmd.name.as_str().into();
-                let desc_utf32: Utf32String = cmd.description.as_str().into();
-
                 let mut indices = Vec::new();
-                let name_score = pattern.indices(
-                    name_utf32.slice(..),
-                    &mut self.matcher,
-                    &mut indices,
-                );
+                let name_score = fuzzy_indices(&pattern, &mut self.matcher, &cmd.name, &mut indices);
 
                 // Also check description if name didn't match well
                 let desc_score = if name_score.is_none() {
                     let mut desc_indices = Vec::new();
-                    pattern.indices(
-                        desc_utf32.slice(..),
-                        &mut self.matcher,
-                        &mut desc_indices,
-                    )
+                    fuzzy_indices(&pattern, &mut self.matcher, &cmd.description, &mut desc_indices)
                 } else {
                     None
                 };
@@ -353,7 +417,7 @@ impl CommandPalette {
             }
 
             // Sort by score (higher is better)
-            self.filtered_results.sort_by(|a, b| b.score.cmp(&a.score));
+            self.filtered_results.sort_by_key(|r| std::cmp::Reverse(r.score));
         }
 
         // Reset selection to first item
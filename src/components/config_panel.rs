@@ -113,6 +113,7 @@ impl ConfigPanel {
                         self.add_setting("Shell", config.terminal.shell.as_deref().unwrap_or("default"));
                         self.add_setting("Shell Args", &if config.terminal.shell_args.is_empty() { "none".to_string() } else { config.terminal.shell_args.join(" ") });
                         self.add_setting("TERM Env", &config.terminal.term_env);
+                        self.add_setting("PTY Read Buffer", &format!("{} bytes", config.terminal.pty_read_buf));
                     }
                     ConfigSection::ProcessMonitor => {
                         self.add_setting("Refresh Interval", &format!("{}ms", config.process_monitor.refresh_interval_ms));
@@ -1,5 +1,8 @@
 // Confirm dialog - some methods for future tool confirmation features
 
+use std::path::Path;
+use std::time::{Duration, Instant};
+
 use crossterm::event::{Event, KeyCode};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -10,26 +13,128 @@ use ratatui::{
 };
 
 use crate::action::Action;
-use crate::config::Theme;
-use crate::llm::{PendingToolUse, ToolExecutionCheck};
+use crate::components::diff_view::{DiffComputer, DiffLine, DiffRenderer};
+use crate::config::{ConfirmDefault, Theme};
+use crate::llm::tools::resolve_tool_path;
+use crate::llm::{patch, PendingToolUse, ToolExecutionCheck};
+
+/// Maximum diff lines shown in the dialog
+const MAX_PREVIEW_LINES: usize = 20;
+
+/// Files larger than this are not read or diffed while the dialog opens
+const MAX_PREVIEW_BYTES: u64 = 256 * 1024;
+
+/// What the dialog shows for a pending apply_edit
+enum Preview {
+    Diff(Vec<DiffLine>),
+    /// The target is too large to diff on the UI thread
+    Unavailable,
+}
+
+/// Auto-answer timer for an unattended confirmation
+struct Countdown {
+    default: ConfirmDefault,
+    total: Duration,
+    remaining: Duration,
+    last_tick: Instant,
+    /// Set once the user interacts with the dialog
+    paused: bool,
+}
 
 /// Confirmation dialog for tool execution
 pub struct ConfirmDialog {
     pending_tool: Option<PendingToolUse>,
+    /// Diff of the pending edit against the file on disk (apply_edit only)
+    preview: Option<Preview>,
+    countdown: Option<Countdown>,
 }
 
 #[allow(dead_code)]
 impl ConfirmDialog {
     pub fn new() -> Self {
-        Self { pending_tool: None }
+        Self { pending_tool: None, preview: None, countdown: None }
     }
     
-    pub fn show(&mut self, pending: PendingToolUse) {
+    /// Show `pending`; relative edit paths resolve against `working_dir`
+    pub fn show(&mut self, pending: PendingToolUse, working_dir: &Path) {
+        self.preview = Self::compute_preview(&pending, working_dir);
         self.pending_tool = Some(pending);
+        self.countdown = None;
+    }
+
+    /// Resolve the shown confirmation to `default` if unanswered after `duration`
+    pub fn start_countdown(&mut self, default: ConfirmDefault, duration: Duration) {
+        if !matches!(self.pending_tool, Some(ref p) if p.check == ToolExecutionCheck::RequiresConfirmation) {
+            return;
+        }
+        self.countdown = Some(Countdown {
+            default,
+            total: duration,
+            remaining: duration,
+            last_tick: Instant::now(),
+            paused: false,
+        });
+    }
+
+    /// Whether a countdown is running (needs periodic redraws)
+    pub fn countdown_active(&self) -> bool {
+        self.countdown.as_ref().is_some_and(|c| !c.paused)
+    }
+
+    /// Advance the countdown; returns the default action once it expires
+    pub fn tick(&mut self) -> Option<Action> {
+        let elapsed = self.countdown.as_ref()?.last_tick.elapsed();
+        self.advance(elapsed)
+    }
+
+    fn advance(&mut self, elapsed: Duration) -> Option<Action> {
+        let countdown = self.countdown.as_mut()?;
+        countdown.last_tick = Instant::now();
+        if countdown.paused {
+            return None;
+        }
+        countdown.remaining = countdown.remaining.saturating_sub(elapsed);
+        if !countdown.remaining.is_zero() {
+            return None;
+        }
+        let default = countdown.default;
+        self.countdown = None;
+        Some(match default {
+            ConfirmDefault::Approve => Action::ToolConfirm,
+            ConfirmDefault::Deny => Action::ToolReject,
+        })
     }
     
     pub fn dismiss(&mut self) {
         self.pending_tool = None;
+        self.preview = None;
+        self.countdown = None;
+    }
+
+    pub fn preview(&self) -> Option<&[DiffLine]> {
+        match &self.preview {
+            Some(Preview::Diff(lines)) => Some(lines),
+            _ => None,
+        }
+    }
+
+    pub fn preview_unavailable(&self) -> bool {
+        matches!(self.preview, Some(Preview::Unavailable))
+    }
+
+    /// Diff an apply_edit against the current file so the user sees exactly what changes
+    fn compute_preview(pending: &PendingToolUse, working_dir: &Path) -> Option<Preview> {
+        if pending.tool.name != "apply_edit" {
+            return None;
+        }
+        let path = pending.tool.input.get("path")?.as_str()?;
+        let full_path = resolve_tool_path(working_dir, path);
+        if std::fs::metadata(&full_path).ok()?.len() > MAX_PREVIEW_BYTES {
+            return Some(Preview::Unavailable);
+        }
+        let original = std::fs::read_to_string(full_path).ok()?;
+        let outcome = patch::apply_edit_input(&original, &pending.tool.input).ok()?;
+        Some(Preview::Diff(DiffComputer::new().compute(&original, &outcome.content, path)))
     }
     
     pub fn is_visible(&self) -> bool {
@@ -53,7 +158,20 @@ impl ConfirmDialog {
                 KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                     return Some(Action::ToolReject);
                 }
-                _ => {}
+                KeyCode::Char('a') | KeyCode::Char('A')
+                    if matches!(self.pending_tool, Some(ref p) if p.check == ToolExecutionCheck::RequiresConfirmation) =>
+                {
+                    return Some(Action::ToolConfirmAll);
+                }
+                KeyCode::Char('r') | KeyCode::Char('R') => {
+                    return Some(Action::ToolRejectAll);
+                }
+                _ => {
+                    // Any other key means the user is looking; stop the countdown
+                    if let Some(countdown) = self.countdown.as_mut() {
+                        countdown.paused = true;
+                    }
+                }
             }
         }
         
@@ -68,7 +186,12 @@ impl ConfirmDialog {
         
         // Calculate dialog size (centered, 60% width, adaptive height)
         let dialog_width = (area.width * 60 / 100).clamp(40, 80);
-        let dialog_height = 12;
+        let preview_height = match &self.preview {
+            Some(Preview::Diff(diff)) => diff.len().min(MAX_PREVIEW_LINES) as u16 + 2,
+            Some(Preview::Unavailable) => 1,
+            None => 0,
+        };
+        let dialog_height = (12 + preview_height).min(area.height);
         
         let dialog_x = (area.width.saturating_sub(dialog_width)) / 2;
         let dialog_y = (area.height.saturating_sub(dialog_height)) / 2;
@@ -105,6 +228,7 @@ impl ConfirmDialog {
                 Constraint::Length(1),  // Tool name
                 Constraint::Length(1),  // Spacer
                 Constraint::Length(2),  // Parameters
+                Constraint::Length(preview_height), // Diff preview
                 Constraint::Length(1),  // Spacer
                 Constraint::Min(1),     // Instructions
             ])
@@ -123,6 +247,21 @@ impl ConfirmDialog {
             .style(Style::default().fg(theme.colors.primary.to_color()))
             .wrap(Wrap { trim: true });
         frame.render_widget(params_para, chunks[2]);
+
+        match &self.preview {
+            Some(Preview::Diff(diff)) => {
+                let renderer = DiffRenderer::new(theme);
+                let mut preview_lines = vec![renderer.render_summary(diff)];
+                preview_lines.extend(renderer.render(diff, MAX_PREVIEW_LINES));
+                frame.render_widget(Paragraph::new(preview_lines), chunks[3]);
+            }
+            Some(Preview::Unavailable) => {
+                let note = Paragraph::new("Preview unavailable (file too large)")
+                    .style(Style::default().fg(theme.colors.muted.to_color()));
+                frame.render_widget(note, chunks[3]);
+            }
+            None => {}
+        }
         
         // Instructions based on check result
         let instructions = match pending.check {
@@ -134,6 +273,12 @@ impl ConfirmDialog {
                         Span::styled("[N/Esc]", Style::default().fg(theme.colors.error.to_color()).add_modifier(Modifier::BOLD)),
                         Span::raw(" Cancel"),
                     ]),
+                    Line::from(vec![
+                        Span::styled("[A]", Style::default().fg(theme.colors.success.to_color()).add_modifier(Modifier::BOLD)),
+                        Span::raw(" Approve all this turn   "),
+                        Span::styled("[R]", Style::default().fg(theme.colors.error.to_color()).add_modifier(Modifier::BOLD)),
+                        Span::raw(" Reject all"),
+                    ]),
                 ]
             }
             ToolExecutionCheck::RequiresDangerousMode => {
@@ -172,7 +317,34 @@ impl ConfirmDialog {
         
         let instructions_para = Paragraph::new(instructions)
             .alignment(Alignment::Center);
-        frame.render_widget(instructions_para, chunks[4]);
+        frame.render_widget(instructions_para, chunks[5]);
+
+        if let Some(countdown) = &self.countdown {
+            frame.render_widget(Self::countdown_line(countdown, chunks[4].width, theme), chunks[4]);
+        }
+    }
+
+    /// "Deny in 7s ██████░░░░" or "Approve paused" once the user interacted
+    fn countdown_line(countdown: &Countdown, width: u16, theme: &Theme) -> Line<'static> {
+        let (verb, color) = match countdown.default {
+            ConfirmDefault::Approve => ("Approve", theme.colors.success.to_color()),
+            ConfirmDefault::Deny => ("Deny", theme.colors.error.to_color()),
+        };
+        if countdown.paused {
+            return Line::from(Span::styled(
+                format!("Auto-{} paused", verb.to_lowercase()),
+                Style::default().fg(theme.colors.muted.to_color()),
+            ));
+        }
+        let label = format!("{} in {}s ", verb, countdown.remaining.as_secs_f32().ceil() as u64);
+        let bar_width = (width as usize).saturating_sub(label.chars().count());
+        let ratio = countdown.remaining.as_secs_f32() / countdown.total.as_secs_f32().max(f32::EPSILON);
+        let filled = ((bar_width as f32) * ratio).round() as usize;
+        Line::from(vec![
+            Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled("█".repeat(filled), Style::default().fg(color)),
+            Span::styled("░".repeat(bar_width - filled.min(bar_width)), Style::default().fg(theme.colors.muted.to_color())),
+        ])
     }
 }
 
@@ -181,3 +353,100 @@ impl Default for ConfirmDialog {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ToolUse;
+
+    fn pending(check: ToolExecutionCheck) -> PendingToolUse {
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "bash_execute".to_string(),
+            input: serde_json::json!({"command": "ls"}),
+        };
+        PendingToolUse::new(tool, check)
+    }
+
+    #[test]
+    fn test_countdown_resolves_to_default() {
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(pending(ToolExecutionCheck::RequiresConfirmation), Path::new("."));
+        dialog.start_countdown(ConfirmDefault::Approve, Duration::from_secs(10));
+        assert!(dialog.countdown_active());
+
+        assert!(dialog.advance(Duration::from_secs(9)).is_none());
+        assert!(matches!(dialog.advance(Duration::from_secs(1)), Some(Action::ToolConfirm)));
+        assert!(!dialog.countdown_active());
+
+        dialog.show(pending(ToolExecutionCheck::RequiresConfirmation), Path::new("."));
+        dialog.start_countdown(ConfirmDefault::Deny, Duration::from_secs(10));
+        assert!(matches!(dialog.advance(Duration::from_secs(10)), Some(Action::ToolReject)));
+    }
+
+    #[test]
+    fn test_countdown_pauses_on_interaction() {
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(pending(ToolExecutionCheck::RequiresConfirmation), Path::new("."));
+        dialog.start_countdown(ConfirmDefault::Approve, Duration::from_secs(10));
+
+        let key = Event::Key(crossterm::event::KeyEvent::from(KeyCode::Down));
+        assert!(dialog.handle_event(&key).is_none());
+        assert!(!dialog.countdown_active());
+        assert!(dialog.advance(Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_countdown_only_for_confirmable_tools() {
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(pending(ToolExecutionCheck::RequiresDangerousMode), Path::new("."));
+        dialog.start_countdown(ConfirmDefault::Approve, Duration::from_secs(1));
+        assert!(!dialog.countdown_active());
+        assert!(dialog.advance(Duration::from_secs(5)).is_none());
+    }
+
+    #[test]
+    fn test_batch_keys() {
+        let key = |c| Event::Key(crossterm::event::KeyEvent::from(KeyCode::Char(c)));
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(pending(ToolExecutionCheck::RequiresConfirmation), Path::new("."));
+        assert!(matches!(dialog.handle_event(&key('a')), Some(Action::ToolConfirmAll)));
+        assert!(matches!(dialog.handle_event(&key('r')), Some(Action::ToolRejectAll)));
+
+        dialog.show(pending(ToolExecutionCheck::PathNotAllowed), Path::new("."));
+        assert!(dialog.handle_event(&key('a')).is_none(), "blocked tools can't be approved");
+        assert!(matches!(dialog.handle_event(&key('r')), Some(Action::ToolRejectAll)));
+    }
+
+    #[test]
+    fn test_preview_resolves_relative_path_against_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "alpha\nbeta\n").unwrap();
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "apply_edit".to_string(),
+            input: serde_json::json!({"path": "notes.txt", "old_string": "beta", "new_string": "gamma"}),
+        };
+
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(PendingToolUse::new(tool, ToolExecutionCheck::RequiresConfirmation), dir.path());
+        assert!(dialog.preview().is_some_and(|lines| !lines.is_empty()));
+    }
+
+    #[test]
+    fn test_preview_unavailable_for_large_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let big = "beta\n".repeat(MAX_PREVIEW_BYTES as usize);
+        std::fs::write(dir.path().join("big.txt"), big).unwrap();
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "apply_edit".to_string(),
+            input: serde_json::json!({"path": "big.txt", "old_string": "beta", "new_string": "gamma"}),
+        };
+
+        let mut dialog = ConfirmDialog::new();
+        dialog.show(PendingToolUse::new(tool, ToolExecutionCheck::RequiresConfirmation), dir.path());
+        assert!(dialog.preview().is_none());
+        assert!(dialog.preview_unavailable());
+    }
+}
@@ -4,7 +4,7 @@ use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
@@ -15,7 +15,9 @@ use crate::action::Action;
 use crate::agent::ContextStats;
 use crate::components::search::{SearchState, SearchBar, SearchAction};
 use crate::components::spinner::{Spinner, SpinnerStyle};
+use crate::components::inline_image;
 use crate::components::tool_call_widget::{ToolCallManager, ToolCallWidget, ToolStatus, ToolVerbosity};
+use crate::components::scroll::{self, Follow, Following};
 use crate::components::Component;
 use crate::config::Theme;
 use crate::llm::{ContentBlock, Message, Role, ToolUse, ToolResult};
@@ -26,7 +28,7 @@ pub struct ConversationViewer {
     scroll_offset: u16,
     line_count: usize,
     visible_height: u16,
-    auto_scroll: bool,
+    follow: Follow,
     inner_area: Rect,
     streaming_spinner: Spinner,
     tool_spinner: Spinner,
@@ -58,10 +60,27 @@ pub struct ConversationViewer {
     cached_message_lines: Vec<Line<'static>>,
     /// Hash of last rendered message content (for cache invalidation)
     cached_message_hash: u64,
-    /// Last streaming buffer length (for incremental updates)
+    /// Dim right-aligned footer per message (token usage / cost), parallel to messages
+    message_footers: Vec<Option<String>>,
+    /// Bytes of the streaming buffer already rendered into `streaming_lines`
     last_streaming_len: usize,
+    /// Rendered lines for the newline-terminated part of the streaming buffer
+    streaming_lines: Vec<Line<'static>>,
+    /// Fence state at the end of `streaming_lines`
+    streaming_state: TextBlockState,
     /// Last thinking buffer length (for incremental updates)
     last_thinking_len: usize,
+    /// Active persona name, shown in the title (survives `clear`)
+    persona: Option<String>,
+    /// Estimated USD spent on the current thread, shown in the title
+    thread_cost: Option<f64>,
+}
+
+/// Code/diff fence state carried from one line of assistant text to the next
+#[derive(Debug, Clone, Copy, Default)]
+struct TextBlockState {
+    in_code_block: bool,
+    in_diff_block: bool,
 }
 
 /// Text selection in the conversation viewer
@@ -80,7 +99,7 @@ impl ConversationViewer {
             scroll_offset: 0,
             line_count: 0,
             visible_height: 10,
-            auto_scroll: true,
+            follow: Follow::default(),
             inner_area: Rect::default(),
             streaming_spinner: Spinner::new(SpinnerStyle::BrailleDots),
             tool_spinner: Spinner::new(SpinnerStyle::Braille),
@@ -99,11 +118,25 @@ impl ConversationViewer {
             // Phase 3: Initialize caching fields
             cached_message_lines: Vec::new(),
             cached_message_hash: 0,
+            message_footers: Vec::new(),
             last_streaming_len: 0,
+            streaming_lines: Vec::new(),
+            streaming_state: TextBlockState::default(),
             last_thinking_len: 0,
+            persona: None,
+            thread_cost: None,
         }
     }
     
+    pub fn set_streaming_spinner(&mut self, spinner: Spinner) {
+        self.streaming_spinner = spinner;
+    }
+
+    /// Set per-message footers for the next render (index-aligned with the messages)
+    pub fn set_message_footers(&mut self, footers: Vec<Option<String>>) {
+        self.message_footers = footers;
+    }
+
     pub fn tick_spinner(&mut self) {
         self.streaming_spinner.tick();
         self.tool_spinner.tick();
@@ -130,14 +163,22 @@ impl ConversationViewer {
     
     /// Set auto-scroll state
     pub fn set_auto_scroll(&mut self, enabled: bool) {
-        self.auto_scroll = enabled;
+        self.follow.set(enabled);
     }
     
+    pub fn set_persona(&mut self, persona: Option<String>) {
+        self.persona = persona;
+    }
+
+    pub fn set_thread_cost(&mut self, cost: Option<f64>) {
+        self.thread_cost = cost;
+    }
+
     /// Clear the conversation viewer state for a new thread
     pub fn clear(&mut self) {
         self.scroll_offset = 0;
         self.line_count = 0;
-        self.auto_scroll = true;
+        self.follow.set(true);
         self.tool_call_manager.clear();
         self.tool_navigation_mode = false;
         self.tool_results_collapsed = false;
@@ -151,7 +192,7 @@ impl ConversationViewer {
         // Phase 3: Clear caching state
         self.cached_message_lines.clear();
         self.cached_message_hash = 0;
-        self.last_streaming_len = 0;
+        self.reset_streaming_cache();
         self.last_thinking_len = 0;
     }
     
@@ -184,9 +225,9 @@ impl ConversationViewer {
     }
     
     /// Register a new tool use from LLM
-    /// For file_write, captures original file content for diff view
+    /// For file_write and apply_edit, captures original file content for diff view
     pub fn register_tool_use(&mut self, tool_use: ToolUse) {
-        if tool_use.name == "file_write" {
+        if tool_use.name == "file_write" || tool_use.name == "apply_edit" {
             // Extract path and read original content for diff view
             let original_content = tool_use.input.get("path")
                 .and_then(|v| v.as_str())
@@ -273,7 +314,7 @@ impl ConversationViewer {
     /// Start search in conversation (TRC-021)
     pub fn start_search(&mut self) {
         self.search_state.activate();
-        self.auto_scroll = false;
+        self.follow.set(false);
     }
 
     /// Close search (TRC-021)
@@ -301,14 +342,9 @@ impl ConversationViewer {
     }
 
     fn scroll_to_current_search_match(&mut self) {
-        if let Some(m) = self.search_state.current_match() {
-            let target_line = m.line_index as u16;
-            if target_line < self.scroll_offset {
-                self.scroll_offset = target_line.saturating_sub(2);
-            } else if target_line >= self.scroll_offset + self.visible_height {
-                self.scroll_offset = target_line.saturating_sub(self.visible_height / 2);
-            }
-        }
+        self.scroll_offset = self
+            .search_state
+            .scroll_offset_for_current_match(self.scroll_offset, self.visible_height);
     }
 
     // =====================================================================
@@ -351,7 +387,7 @@ impl ConversationViewer {
                 end: (line, col),
             });
             self.selecting = true;
-            self.auto_scroll = false;
+            self.follow.set(false);
         }
     }
 
@@ -407,7 +443,7 @@ impl ConversationViewer {
 
     /// Apply scroll delta during drag selection
     fn apply_scroll_delta(&mut self, delta: i16) {
-        let max_scroll = self.line_count.saturating_sub(self.visible_height as usize) as i32;
+        let max_scroll = scroll::max_offset(self.line_count, self.visible_height) as i32;
         let new_scroll = (self.scroll_offset as i32 + delta as i32).clamp(0, max_scroll);
         self.scroll_offset = new_scroll as u16;
     }
@@ -520,6 +556,9 @@ impl ConversationViewer {
     /// Handle mouse events for selection
     pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
         match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.follow.hint_hit(mouse.column, mouse.row) => {
+                Some(Action::ConversationScrollToBottom)
+            }
             MouseEventKind::Down(MouseButton::Left) => {
                 self.start_selection(mouse.column, mouse.row);
                 None
@@ -789,6 +828,8 @@ impl ConversationViewer {
             }
         }
 
+        self.message_footers.hash(&mut hasher);
+
         // Hash collapse states (affect rendering)
         self.thinking_collapsed.hash(&mut hasher);
         self.tool_results_collapsed.hash(&mut hasher);
@@ -844,11 +885,18 @@ impl ConversationViewer {
         // Build title with status indicators (TRC-017: include thinking indicator, TRC-021: search, Phase 3: context stats)
         let title = self.build_title(streaming_buffer, thinking_buffer, model_info, context_stats);
 
-        let block = Block::default()
+        let mut block = Block::default()
             .title(title)
             .title_style(title_style)
             .borders(Borders::ALL)
             .border_style(border_style);
+        // Where the context budget goes, shown while the conversation is focused
+        if let Some(stats) = context_stats.filter(|s| focused && s.tokens_budget > 0) {
+            block = block.title_bottom(Line::from(Span::styled(
+                format!(" {} ", stats.format_breakdown()),
+                Style::default().fg(theme.colors.muted.to_color()),
+            )));
+        }
 
         let inner = block.inner(conversation_area);
         self.visible_height = inner.height;
@@ -862,7 +910,7 @@ impl ConversationViewer {
             // Cache miss - rebuild message lines
             let mut message_lines: Vec<Line<'static>> = Vec::new();
 
-            for message in messages {
+            for (idx, message) in messages.iter().enumerate() {
                 // Check if this is a tool-result-only message (should not show "User:" header)
                 let is_tool_result_only = message.role == Role::User
                     && !message.content.is_empty()
@@ -911,15 +959,25 @@ impl ConversationViewer {
                                 message_lines.extend(self.render_tool_result(result, theme));
                             }
                         }
-                        ContentBlock::Image(_) => {
+                        ContentBlock::Image(image) => {
                             message_lines.push(Line::from(Span::styled(
-                                "  [Image]",
+                                format!("  {}", inline_image::placeholder(image)),
                                 Style::default().fg(theme.colors.muted.to_color()),
                             )));
                         }
                     }
                 }
 
+                if let Some(Some(footer)) = self.message_footers.get(idx) {
+                    message_lines.push(
+                        Line::from(Span::styled(
+                            footer.clone(),
+                            Style::default().fg(theme.colors.muted.to_color()).add_modifier(Modifier::DIM),
+                        ))
+                        .alignment(Alignment::Right),
+                    );
+                }
+
                 // Add spacing between messages
                 message_lines.push(Line::from(""));
             }
@@ -927,6 +985,8 @@ impl ConversationViewer {
             // Update cache
             self.cached_message_lines = message_lines;
             self.cached_message_hash = current_hash;
+            // Re-render the live response against the new message state too
+            self.reset_streaming_cache();
         }
 
         // Start with cached message lines (clone for this render)
@@ -990,7 +1050,9 @@ impl ConversationViewer {
         }
 
         // Add streaming buffer if present
-        if !streaming_buffer.is_empty() {
+        if streaming_buffer.is_empty() {
+            self.reset_streaming_cache();
+        } else {
             // Only add assistant header if we don't have streaming thinking
             if thinking_buffer.is_empty() {
                 lines.push(Line::from(Span::styled(
@@ -1000,13 +1062,7 @@ impl ConversationViewer {
                         .add_modifier(Modifier::BOLD),
                 )));
             }
-            let clean_streaming = strip_ansi(streaming_buffer);
-            for line in clean_streaming.lines() {
-                lines.push(Line::from(Span::styled(
-                    format!("  {}", line),
-                    Style::default().fg(theme.colors.foreground.to_color()),
-                )));
-            }
+            lines.extend(self.streaming_text_lines(streaming_buffer, theme));
             // Add cursor indicator for streaming
             if let Some(last) = lines.last_mut() {
                 last.spans.push(Span::styled(
@@ -1038,20 +1094,25 @@ impl ConversationViewer {
 
         // Calculate actual line count after text wrapping
         // This accounts for long lines that wrap to multiple visual lines
+        let previous_line_count = self.line_count;
         self.line_count = paragraph.line_count(inner.width);
+        self.follow.content_added(self.line_count.saturating_sub(previous_line_count));
 
         // Now that we know the actual visual line count, rebuild the mapping
         // This ensures visual line indices map correctly to cached text indices
         self.build_visual_to_cached_mapping();
 
-        // Auto-scroll to bottom if enabled and new content
-        if self.auto_scroll && self.line_count > self.visible_height as usize {
-            self.scroll_offset = (self.line_count - self.visible_height as usize) as u16;
+        // Track the newest content while following; otherwise stay put
+        let max_scroll = scroll::max_offset(self.line_count, self.visible_height);
+        if self.follow.is_following() {
+            self.scroll_offset = max_scroll;
         }
-        
+
         // Clamp scroll offset to valid range (prevents scrolling past content)
-        let max_scroll = self.line_count.saturating_sub(self.visible_height as usize) as u16;
         self.scroll_offset = self.scroll_offset.min(max_scroll);
+        if self.scroll_offset >= max_scroll {
+            self.follow.reached_bottom();
+        }
 
         // Apply scroll offset and render
         let scrolled_paragraph = paragraph.scroll((self.scroll_offset, 0));
@@ -1082,6 +1143,8 @@ impl ConversationViewer {
             );
         }
 
+        self.follow.render_hint(frame, conversation_area, theme);
+
         // TRC-021: Render search bar if active
         if let Some(search_rect) = search_area {
             let search_bar = SearchBar::new(&self.search_state, theme);
@@ -1110,6 +1173,10 @@ impl ConversationViewer {
             }
         }
 
+        if let Some(ref persona) = self.persona {
+            title_parts.push(format!(" 󰀄{}", persona));
+        }
+
         // Phase 3: Add context/token stats
         if let Some(stats) = context_stats {
             if stats.tokens_budget > 0 {
@@ -1117,6 +1184,10 @@ impl ConversationViewer {
                 title_parts.push(format!(" 󰊤{}{}", stats.format_compact(), truncated_indicator));
             }
         }
+
+        if let Some(cost) = self.thread_cost {
+            title_parts.push(format!(" ${:.4} this thread", cost));
+        }
         
         // Add tool status indicators
         let tool_count = self.tool_call_manager.len();
@@ -1152,6 +1223,8 @@ impl ConversationViewer {
             title_parts.push(" 󰍉".to_string());
         }
 
+        title_parts.push(format!(" {}", self.follow.indicator()));
+
         // Phase 4: Add verbosity indicator if not Normal
         match self.tool_call_manager.verbosity() {
             ToolVerbosity::Compact => title_parts.push(" [C]".to_string()),
@@ -1342,60 +1415,91 @@ impl ConversationViewer {
 
     /// Render text content, detecting and styling ```diff code blocks
     fn render_text_with_diff_blocks(&self, text: &str, theme: &Theme) -> Vec<Line<'static>> {
-        let mut lines = Vec::new();
-        let mut in_diff_block = false;
-        let mut in_code_block = false;
-
-        for line in text.lines() {
-            let trimmed = line.trim();
-
-            // Check for code block markers
-            if trimmed.starts_with("```") {
-                if trimmed == "```diff" {
-                    in_diff_block = true;
-                    in_code_block = true;
-                    // Render the marker in muted color
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", line),
-                        Style::default().fg(theme.colors.muted.to_color()),
-                    )));
-                    continue;
-                } else if in_code_block && trimmed == "```" {
-                    // End of code block
-                    in_diff_block = false;
-                    in_code_block = false;
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", line),
-                        Style::default().fg(theme.colors.muted.to_color()),
-                    )));
-                    continue;
-                } else if trimmed.starts_with("```") {
-                    // Start of non-diff code block
-                    in_code_block = true;
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", line),
-                        Style::default().fg(theme.colors.muted.to_color()),
-                    )));
-                    continue;
-                }
-            }
+        let mut state = TextBlockState::default();
+        text.lines()
+            .map(|line| self.render_text_line(line, &mut state, theme))
+            .collect()
+    }
+
+    /// Render one line of assistant text, tracking code/diff fences in `state`
+    fn render_text_line(&self, line: &str, state: &mut TextBlockState, theme: &Theme) -> Line<'static> {
+        let trimmed = line.trim();
+        let muted = || {
+            Line::from(Span::styled(
+                format!("  {}", line),
+                Style::default().fg(theme.colors.muted.to_color()),
+            ))
+        };
 
-            if in_diff_block {
-                // Apply diff styling - reuse the existing method with adjusted prefix
-                let styled = self.style_diff_line_for_text(line, theme);
-                lines.push(styled);
+        // Check for code block markers
+        if trimmed.starts_with("```") {
+            if trimmed == "```diff" {
+                state.in_diff_block = true;
+                state.in_code_block = true;
+                // Render the marker in muted color
+                return muted();
+            } else if state.in_code_block && trimmed == "```" {
+                // End of code block
+                state.in_diff_block = false;
+                state.in_code_block = false;
+                return muted();
             } else {
-                // Normal text rendering
-                lines.push(Line::from(Span::styled(
-                    format!("  {}", line),
-                    Style::default().fg(theme.colors.foreground.to_color()),
-                )));
+                // Start of non-diff code block
+                state.in_code_block = true;
+                return muted();
             }
         }
 
+        if state.in_diff_block {
+            // Apply diff styling - reuse the existing method with adjusted prefix
+            self.style_diff_line_for_text(line, theme)
+        } else {
+            // Normal text rendering
+            Line::from(Span::styled(
+                format!("  {}", line),
+                Style::default().fg(theme.colors.foreground.to_color()),
+            ))
+        }
+    }
+
+    /// Render the live streaming buffer append-only. Lines already ended by a
+    /// newline are rendered once and reused; only the trailing partial line is
+    /// rebuilt each frame. Uses the same per-line renderer as a completed text
+    /// block, so the message looks identical once it lands in the thread.
+    fn streaming_text_lines(&mut self, buffer: &str, theme: &Theme) -> Vec<Line<'static>> {
+        if buffer.len() < self.last_streaming_len {
+            // A new response started since the last frame
+            self.reset_streaming_cache();
+        }
+
+        let committed_end = buffer.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        if committed_end > self.last_streaming_len {
+            let fresh = strip_ansi(&buffer[self.last_streaming_len..committed_end]);
+            let mut state = self.streaming_state;
+            let rendered: Vec<Line<'static>> = fresh
+                .lines()
+                .map(|line| self.render_text_line(line, &mut state, theme))
+                .collect();
+            self.streaming_lines.extend(rendered);
+            self.streaming_state = state;
+            self.last_streaming_len = committed_end;
+        }
+
+        let mut lines = self.streaming_lines.clone();
+        let tail = strip_ansi(&buffer[committed_end..]);
+        if !tail.is_empty() {
+            let mut state = self.streaming_state;
+            lines.push(self.render_text_line(&tail, &mut state, theme));
+        }
         lines
     }
 
+    fn reset_streaming_cache(&mut self) {
+        self.streaming_lines.clear();
+        self.streaming_state = TextBlockState::default();
+        self.last_streaming_len = 0;
+    }
+
     /// Style a diff line within markdown text (uses "  " prefix instead of "    ")
     fn style_diff_line_for_text(&self, line: &str, theme: &Theme) -> Line<'static> {
         let trimmed = line.trim_start();
@@ -1467,7 +1571,7 @@ impl ConversationViewer {
             crate::llm::ToolResultContent::Json(json) => {
                 serde_json::to_string_pretty(json).unwrap_or_else(|_| json.to_string())
             }
-            crate::llm::ToolResultContent::Image(_) => "[Image result]".to_string(),
+            crate::llm::ToolResultContent::Image(image) => inline_image::placeholder(image),
         };
         
         let content_lines: Vec<&str> = content_str.lines().collect();
@@ -1601,42 +1705,46 @@ impl ConversationViewer {
     }
 
     pub fn scroll_up(&mut self, n: u16) {
-        self.auto_scroll = false;
+        self.follow.set(false);
         self.scroll_offset = self.scroll_offset.saturating_sub(n);
     }
 
+    /// Scrolling down to the bottom clears the new-lines hint; only 'f' or G resume following
     pub fn scroll_down(&mut self, n: u16) {
-        let max_scroll = self.line_count.saturating_sub(self.visible_height as usize) as u16;
+        let max_scroll = scroll::max_offset(self.line_count, self.visible_height);
         self.scroll_offset = self.scroll_offset.saturating_add(n).min(max_scroll);
-        // Re-enable auto-scroll if at bottom
         if self.scroll_offset >= max_scroll {
-            self.auto_scroll = true;
+            self.follow.reached_bottom();
         }
     }
 
     pub fn scroll_to_top(&mut self) {
-        self.auto_scroll = false;
+        self.follow.set(false);
         self.scroll_offset = 0;
     }
 
     pub fn scroll_to_bottom(&mut self) {
-        self.auto_scroll = true;
-        if self.line_count > self.visible_height as usize {
-            self.scroll_offset = (self.line_count - self.visible_height as usize) as u16;
-        }
+        self.follow.set(true);
+        self.scroll_offset = scroll::max_offset(self.line_count, self.visible_height);
     }
 
     pub fn toggle_auto_scroll(&mut self) {
-        self.auto_scroll = !self.auto_scroll;
-        if self.auto_scroll {
+        if self.follow.is_following() {
+            self.follow.set(false);
+        } else {
             self.scroll_to_bottom();
         }
     }
 
+    pub fn visible_height(&self) -> u16 {
+        self.visible_height
+    }
+
     #[allow(dead_code)]
     pub fn is_auto_scroll(&self) -> bool {
-        self.auto_scroll
+        self.follow.is_following()
     }
+
     
     /// Select next tool call (for navigation)
     pub fn select_next_tool(&mut self) {
@@ -1662,6 +1770,26 @@ impl ConversationViewer {
     pub fn collapse_all_tools(&mut self) {
         self.tool_call_manager.collapse_all();
     }
+
+    /// Title and full output of the selected tool call, falling back to the most recent one
+    pub fn selected_tool_output(&self) -> Option<(String, String)> {
+        let tool_call = self
+            .tool_call_manager
+            .selected()
+            .or_else(|| self.tool_call_manager.tool_calls().last())?;
+        let title = format!("{}: {}", tool_call.tool_name(), tool_call.input_summary());
+        Some((title, tool_call.full_output()))
+    }
+}
+
+impl Following for ConversationViewer {
+    fn follow(&self) -> &Follow {
+        &self.follow
+    }
+
+    fn follow_mut(&mut self) -> &mut Follow {
+        &mut self.follow
+    }
 }
 
 impl Default for ConversationViewer {
@@ -1745,6 +1873,7 @@ impl ConversationViewer {
                 KeyCode::Enter | KeyCode::Char(' ') => return Some(Action::ToolCallToggleExpand),
                 KeyCode::Char('e') => return Some(Action::ToolCallExpandAll),
                 KeyCode::Char('c') => return Some(Action::ToolCallCollapseAll),
+                KeyCode::Char('o') => return Some(Action::ToolCallViewOutput),
                 KeyCode::Esc => {
                     self.tool_navigation_mode = false;
                     return None;
@@ -1780,9 +1909,9 @@ impl ConversationViewer {
             KeyCode::Char('k') | KeyCode::Up => Some(Action::ConversationScrollUp(1)),
             KeyCode::Char('g') => Some(Action::ConversationScrollToTop),
             KeyCode::Char('G') => Some(Action::ConversationScrollToBottom),
-            KeyCode::PageUp => Some(Action::ConversationScrollUp(10)),
-            KeyCode::PageDown => Some(Action::ConversationScrollDown(10)),
-            KeyCode::Char('a') => {
+            KeyCode::PageUp => Some(Action::ConversationScrollUp(scroll::page_step(self.visible_height))),
+            KeyCode::PageDown => Some(Action::ConversationScrollDown(scroll::page_step(self.visible_height))),
+            KeyCode::Char('a') | KeyCode::Char('f') => {
                 self.toggle_auto_scroll();
                 None
             }
@@ -1838,7 +1967,7 @@ mod tests {
     fn test_conversation_viewer_new() {
         let viewer = ConversationViewer::new();
         assert_eq!(viewer.scroll_offset, 0);
-        assert!(viewer.auto_scroll);
+        assert!(viewer.is_auto_scroll());
         assert!(viewer.tool_call_manager.is_empty());
         assert!(!viewer.tool_navigation_mode);
     }
@@ -1849,17 +1978,21 @@ mod tests {
         viewer.line_count = 100;
         viewer.visible_height = 20;
 
-        // First scroll up to disable auto_scroll
+        // First scroll up to pause following
         viewer.scroll_up(5);
         assert_eq!(viewer.scroll_offset, 0); // Saturates at 0
-        assert!(!viewer.auto_scroll); // scroll_up disables auto_scroll
+        assert!(!viewer.is_auto_scroll()); // scroll_up pauses following
 
         // Now scroll down
         viewer.scroll_down(10);
         assert_eq!(viewer.scroll_offset, 10);
 
+        // Reaching the bottom by scrolling does not resume following
+        viewer.scroll_down(100);
+        assert!(!viewer.is_auto_scroll());
+
         viewer.scroll_to_bottom();
-        assert!(viewer.auto_scroll);
+        assert!(viewer.is_auto_scroll());
         assert_eq!(viewer.scroll_offset, 80); // 100 - 20
     }
 
@@ -1994,6 +2127,43 @@ mod tests {
         assert_ne!(hash1, hash2, "Different messages should produce different hash");
     }
 
+    #[test]
+    fn test_streaming_lines_match_final_render() {
+        let mut viewer = ConversationViewer::new();
+        let theme = Theme::default();
+        let text = "Here is the change:\n```diff\n-old line\n+new line\n```\nDone.";
+
+        // Feed the response a few bytes at a time, as the stream would
+        let mut buffer = String::new();
+        for chunk in text.as_bytes().chunks(5) {
+            buffer.push_str(std::str::from_utf8(chunk).unwrap());
+            viewer.streaming_text_lines(&buffer, &theme);
+        }
+
+        let streamed = viewer.streaming_text_lines(&buffer, &theme);
+        assert_eq!(streamed, viewer.render_text_with_diff_blocks(text, &theme));
+    }
+
+    #[test]
+    fn test_streaming_lines_are_append_only() {
+        let mut viewer = ConversationViewer::new();
+        let theme = Theme::default();
+
+        viewer.streaming_text_lines("first line\nsecond", &theme);
+        assert_eq!(viewer.streaming_lines.len(), 1);
+        assert_eq!(viewer.last_streaming_len, "first line\n".len());
+
+        // Growing the partial line leaves committed lines alone
+        let lines = viewer.streaming_text_lines("first line\nsecond line", &theme);
+        assert_eq!(viewer.streaming_lines.len(), 1);
+        assert_eq!(lines.len(), 2);
+
+        // A shorter buffer means a new response
+        let lines = viewer.streaming_text_lines("new", &theme);
+        assert_eq!(lines.len(), 1);
+        assert!(viewer.streaming_lines.is_empty());
+    }
+
     #[test]
     fn test_cache_cleared_on_clear() {
         // Phase 3: Test that cache is cleared when viewer is cleared
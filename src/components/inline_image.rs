@@ -0,0 +1,256 @@
+// Inline image support for image tool results
+//
+// Terminal graphics capability is detected from the environment at startup
+// (kitty graphics protocol or sixel). Drawing through those protocols is not
+// wired into the render loop yet, so every image currently takes the fallback
+// path: it is decoded, saved to a per-user cache file, and shown as a one-line
+// "[image WxH, saved to ...]" placeholder instead of being dropped. The
+// placeholder is worked out once per image and reused on later redraws.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+
+use crate::config::ImageProtocolSetting;
+use crate::llm::{ImageContent, ImageSource};
+
+/// Terminal graphics protocol able to draw images inline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    Sixel,
+}
+
+/// Resolve `ui.image_protocol` against the current terminal
+pub fn resolve_protocol(setting: ImageProtocolSetting) -> Option<ImageProtocol> {
+    match setting {
+        ImageProtocolSetting::Off => None,
+        ImageProtocolSetting::Auto => detect_protocol(|name| std::env::var(name).ok()),
+        ImageProtocolSetting::Kitty => Some(ImageProtocol::Kitty),
+        ImageProtocolSetting::Sixel => Some(ImageProtocol::Sixel),
+    }
+}
+
+/// Guess the terminal's graphics support from its environment variables
+pub fn detect_protocol(env: impl Fn(&str) -> Option<String>) -> Option<ImageProtocol> {
+    let term = env("TERM").unwrap_or_default();
+    let program = env("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+
+    if term == "xterm-kitty"
+        || env("KITTY_WINDOW_ID").is_some()
+        || matches!(program.as_str(), "wezterm" | "ghostty")
+    {
+        return Some(ImageProtocol::Kitty);
+    }
+    if term.contains("sixel")
+        || matches!(term.as_str(), "foot" | "foot-extra" | "mlterm" | "yaft-256color")
+        || matches!(program.as_str(), "iterm.app" | "mintty" | "contour")
+    {
+        return Some(ImageProtocol::Sixel);
+    }
+    None
+}
+
+/// Width and height from a PNG, GIF or JPEG header
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.len() >= 24 {
+        let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+        return Some((width, height));
+    }
+    if bytes.starts_with(b"GIF8") && bytes.len() >= 10 {
+        let width = u16::from_le_bytes([bytes[6], bytes[7]]);
+        let height = u16::from_le_bytes([bytes[8], bytes[9]]);
+        return Some((width.into(), height.into()));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Walk JPEG segments to the first start-of-frame marker
+        let mut i = 2;
+        while i + 9 < bytes.len() {
+            if bytes[i] != 0xFF {
+                return None;
+            }
+            let marker = bytes[i + 1];
+            let len = u16::from_be_bytes([bytes[i + 2], bytes[i + 3]]) as usize;
+            if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                let height = u16::from_be_bytes([bytes[i + 5], bytes[i + 6]]);
+                let width = u16::from_be_bytes([bytes[i + 7], bytes[i + 8]]);
+                return Some((width.into(), height.into()));
+            }
+            i += 2 + len;
+        }
+    }
+    None
+}
+
+fn extension(media_type: &str) -> &'static str {
+    match media_type {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "img",
+    }
+}
+
+/// Directory image tool results are saved to. Per-user (the cache dir, not a
+/// shared temp dir) so other users can neither read nor pre-create the files.
+fn image_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("ridge-control").join("images"))
+}
+
+/// Create `dir` readable by the current user only
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::create_dir_all(dir)
+    }
+}
+
+/// Write `bytes` to a file readable by the current user only. An existing
+/// file (same content, by name) is kept but made private.
+fn write_private_file(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    if path.exists() {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        return Ok(());
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(bytes)
+}
+
+/// Placeholders already worked out, keyed by a hash of the image data
+static PLACEHOLDERS: LazyLock<Mutex<HashMap<u64, String>>> = LazyLock::new(Mutex::default);
+
+/// Placeholders kept before the cache starts over
+const MAX_CACHED_PLACEHOLDERS: usize = 256;
+
+/// Text shown in place of an image: size and the cache file it was saved to,
+/// or the URL for remote images. Decoding and saving happen once per image;
+/// redraws reuse the cached text.
+pub fn placeholder(image: &ImageContent) -> String {
+    placeholder_in(image_dir().as_deref(), image)
+}
+
+/// `placeholder`, saving into `dir` instead of the per-user image dir
+fn placeholder_in(dir: Option<&Path>, image: &ImageContent) -> String {
+    let data = match &image.source {
+        ImageSource::Url(url) => return format!("[image {}]", url),
+        ImageSource::Base64(data) => data,
+    };
+    let mut hasher = DefaultHasher::new();
+    dir.hash(&mut hasher);
+    image.media_type.hash(&mut hasher);
+    data.hash(&mut hasher);
+    let key = hasher.finish();
+
+    let mut cache = PLACEHOLDERS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(label) = cache.get(&key) {
+        return label.clone();
+    }
+    let label = save_image(dir, data, &image.media_type);
+    if cache.len() >= MAX_CACHED_PLACEHOLDERS {
+        cache.clear();
+    }
+    cache.insert(key, label.clone());
+    label
+}
+
+/// Decode a base64 image and save it to `dir`
+fn save_image(dir: Option<&Path>, data: &str, media_type: &str) -> String {
+    let bytes = match base64::engine::general_purpose::STANDARD.decode(data.trim()) {
+        Ok(bytes) => bytes,
+        Err(e) => return format!("[image {}, undecodable: {}]", media_type, e),
+    };
+    let size = image_dimensions(&bytes)
+        .map(|(w, h)| format!("{}x{}", w, h))
+        .unwrap_or_else(|| media_type.to_string());
+
+    // Content-addressed, so the same image from another session reuses the file
+    let name = format!("{:x}.{}", Sha1::digest(&bytes), extension(media_type));
+    let saved = dir.filter(|dir| create_private_dir(dir).is_ok()).and_then(|dir| {
+        let path = dir.join(name);
+        write_private_file(&path, &bytes).is_ok().then_some(path)
+    });
+    match saved {
+        Some(path) => format!("[image {}, saved to {}]", size, path.display()),
+        None => format!("[image {}, could not be saved]", size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_detect_protocol_from_env() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: HashMap<String, String> =
+                vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+            detect_protocol(|name| vars.get(name).cloned())
+        };
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), Some(ImageProtocol::Kitty));
+        assert_eq!(detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]), Some(ImageProtocol::Kitty));
+        assert_eq!(detect(&[("TERM", "foot")]), Some(ImageProtocol::Sixel));
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+    }
+
+    #[test]
+    fn test_placeholder_saves_png_with_dimensions() {
+        // 1x1 transparent PNG
+        let dir = tempfile::tempdir().unwrap();
+        let images = dir.path().join("images");
+        let png = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+        let label = placeholder_in(Some(&images), &ImageContent {
+            source: ImageSource::Base64(png.to_string()),
+            media_type: "image/png".to_string(),
+        });
+        assert!(label.starts_with("[image 1x1, saved to "), "{}", label);
+        let path = label.trim_start_matches("[image 1x1, saved to ").trim_end_matches(']');
+        assert!(std::path::Path::new(path).starts_with(&images));
+        assert!(std::path::Path::new(path).exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o077, 0, "image file is private");
+        }
+
+        // Later redraws reuse the placeholder without touching the file
+        std::fs::remove_file(path).unwrap();
+        let again = placeholder_in(Some(&images), &ImageContent {
+            source: ImageSource::Base64(png.to_string()),
+            media_type: "image/png".to_string(),
+        });
+        assert_eq!(again, label);
+        assert!(!std::path::Path::new(path).exists());
+
+        let url = placeholder(&ImageContent {
+            source: ImageSource::Url("https://example.com/chart.png".to_string()),
+            media_type: "image/png".to_string(),
+        });
+        assert_eq!(url, "[image https://example.com/chart.png]");
+    }
+}
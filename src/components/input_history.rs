@@ -0,0 +1,178 @@
+//! Input history - previously sent chat messages, recalled shell-style
+//!
+//! Entries are whole messages, so multi-line ones come back intact; they're
+//! saved as a JSON list to keep the newlines. Edits made to recalled entries
+//! (and the unsent draft) are kept while browsing and dropped once a message
+//! is sent, like readline.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::util::write_atomic_mode;
+
+/// Messages kept (oldest are dropped first)
+pub const HISTORY_LIMIT: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    /// Sent messages, oldest first
+    entries: Vec<String>,
+    /// Where the history is saved (None = memory only)
+    path: Option<PathBuf>,
+    /// Entry shown while browsing (None = the draft)
+    position: Option<usize>,
+    /// Unsent text by position; the draft is stored at `entries.len()`
+    edits: HashMap<usize, String>,
+}
+
+impl InputHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the history saved at `path`; a missing or unreadable file starts empty
+    pub fn load(path: PathBuf) -> Self {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable chat history {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { entries, path: Some(path), ..Self::default() }
+    }
+
+    /// Record a sent message and stop browsing
+    pub fn push(&mut self, message: &str) {
+        self.position = None;
+        self.edits.clear();
+        if message.trim().is_empty() || self.entries.last().map(String::as_str) == Some(message) {
+            return;
+        }
+        self.entries.push(message.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.drain(..self.entries.len() - HISTORY_LIMIT);
+        }
+        self.save();
+    }
+
+    fn save(&self) {
+        let Some(ref path) = self.path else {
+            return;
+        };
+        let result = serde_json::to_vec(&self.entries)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                // Sent messages can hold pasted secrets; keep them private
+                write_atomic_mode(path, &json, 0o600)
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to save chat history {}: {}", path.display(), e);
+        }
+    }
+
+    pub fn is_browsing(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Step to the next older entry, keeping `current` for when we come back.
+    /// Returns the text to show, or None at the oldest entry.
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let pos = self.position.unwrap_or(self.entries.len());
+        if pos == 0 {
+            return None;
+        }
+        self.edits.insert(pos, current.to_string());
+        self.position = Some(pos - 1);
+        Some(self.text_at(pos - 1))
+    }
+
+    /// Step to the next newer entry (or back to the draft).
+    /// Returns None when not browsing.
+    pub fn newer(&mut self, current: &str) -> Option<String> {
+        let pos = self.position?;
+        self.edits.insert(pos, current.to_string());
+        let next = pos + 1;
+        self.position = (next < self.entries.len()).then_some(next);
+        Some(self.text_at(next))
+    }
+
+    fn text_at(&self, pos: usize) -> String {
+        self.edits
+            .get(&pos)
+            .or_else(|| self.entries.get(pos))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Most recent entry before index `before` that contains `query`
+    pub fn search(&self, query: &str, before: usize) -> Option<(usize, &str)> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, entry)| entry.contains(query))
+            .map(|(i, entry)| (i, entry.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browse_keeps_draft_and_edits() {
+        let mut history = InputHistory::new();
+        history.push("first");
+        history.push("second\nline two");
+
+        assert_eq!(history.older("draft").as_deref(), Some("second\nline two"));
+        assert_eq!(history.older("second, edited").as_deref(), Some("first"));
+        assert_eq!(history.older("first"), None);
+        assert_eq!(history.newer("first").as_deref(), Some("second, edited"));
+        assert_eq!(history.newer("second, edited").as_deref(), Some("draft"));
+        assert!(!history.is_browsing());
+        assert_eq!(history.newer("draft"), None);
+
+        // Sending drops the edits and consecutive duplicates
+        history.push("second\nline two");
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.older("").as_deref(), Some("second\nline two"));
+    }
+
+    #[test]
+    fn test_search_and_persistence() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("chat_history.json");
+        let mut history = InputHistory::load(path.clone());
+        for message in ["fix the parser", "run tests", "fix\nthe lexer"] {
+            history.push(message);
+        }
+        assert_eq!(history.search("fix", usize::MAX), Some((2, "fix\nthe lexer")));
+        assert_eq!(history.search("fix", 2), Some((0, "fix the parser")));
+        assert_eq!(history.search("fix", 0), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reloaded = InputHistory::load(path);
+        assert_eq!(reloaded.entries, history.entries);
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut history = InputHistory::new();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.push(&i.to_string());
+        }
+        assert_eq!(history.entries.len(), HISTORY_LIMIT);
+        assert_eq!(history.entries[0], "5");
+    }
+}
@@ -5,7 +5,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
 use std::collections::VecDeque;
@@ -13,10 +13,11 @@ use std::collections::VecDeque;
 use crate::action::Action;
 use crate::components::search::{
     SearchState, SearchBar, SearchAction, highlight_matches_in_line,
-    FilterState, FilterBar, FilterAction,
+    FilterState, FilterBar, FilterAction, HORIZONTAL_SCROLL_STEP,
 };
+use crate::components::scroll::{self, Follow, Following, LineWrap};
 use crate::components::Component;
-use crate::config::Theme;
+use crate::config::{Theme, TimestampFormat};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -53,6 +54,8 @@ impl LogLevel {
 #[derive(Debug, Clone)]
 pub struct LogEntry {
     pub timestamp: String,
+    /// When the entry was pushed; drives the timestamp column
+    pub at: chrono::DateTime<chrono::Local>,
     pub level: LogLevel,
     pub target: String,
     pub message: String,
@@ -64,6 +67,7 @@ impl LogEntry {
         let now = chrono::Local::now();
         Self {
             timestamp: now.format("%H:%M:%S%.3f").to_string(),
+            at: now,
             level,
             target: target.into(),
             message: message.into(),
@@ -94,10 +98,17 @@ pub struct LogViewer {
     scroll_offset: u16,
     visible_height: u16,
     inner_area: Rect,
-    auto_scroll: bool,
+    follow: Follow,
     filter_level: Option<LogLevel>,
     search_state: SearchState,
     filter_state: FilterState,
+    wrap: LineWrap,
+    show_timestamps: bool,
+    timestamp_format: TimestampFormat,
+    /// Reference time for relative timestamps, refreshed on tick
+    now: chrono::DateTime<chrono::Local>,
+    /// Lines per mouse-wheel notch (`ui.scroll_lines`)
+    scroll_lines: u16,
 }
 
 #[allow(dead_code)]
@@ -109,10 +120,15 @@ impl LogViewer {
             scroll_offset: 0,
             visible_height: 10,
             inner_area: Rect::default(),
-            auto_scroll: true,
+            follow: Follow::default(),
             filter_level: None,
             search_state: SearchState::new(),
             filter_state: FilterState::new(),
+            wrap: LineWrap::default(),
+            show_timestamps: true,
+            timestamp_format: TimestampFormat::default(),
+            now: chrono::Local::now(),
+            scroll_lines: scroll::DEFAULT_SCROLL_LINES,
         }
     }
 
@@ -122,6 +138,10 @@ impl LogViewer {
     }
 
     pub fn push(&mut self, entry: LogEntry) {
+        let shown = self
+            .filter_level
+            .as_ref()
+            .map_or(true, |min| self.level_includes(&entry.level, min));
         self.logs.push_back(entry);
         
         while self.logs.len() > self.max_entries {
@@ -131,7 +151,7 @@ impl LogViewer {
             }
         }
 
-        if self.auto_scroll {
+        if self.follow.content_added(usize::from(shown)) {
             self.scroll_to_bottom();
         }
     }
@@ -165,22 +185,28 @@ impl LogViewer {
         self.logs.is_empty()
     }
 
+    pub fn visible_height(&self) -> u16 {
+        self.visible_height
+    }
+
     pub fn is_auto_scroll(&self) -> bool {
-        self.auto_scroll
+        self.follow.is_following()
+    }
+
+    /// `ui.scroll_lines`: lines per mouse-wheel notch
+    pub fn set_scroll_lines(&mut self, lines: u16) {
+        self.scroll_lines = lines.max(1);
     }
 
     pub fn set_auto_scroll(&mut self, enabled: bool) {
-        self.auto_scroll = enabled;
+        self.follow.set(enabled);
         if enabled {
             self.scroll_to_bottom();
         }
     }
 
     pub fn toggle_auto_scroll(&mut self) {
-        self.auto_scroll = !self.auto_scroll;
-        if self.auto_scroll {
-            self.scroll_to_bottom();
-        }
+        self.set_auto_scroll(!self.follow.is_following());
     }
 
     pub fn set_filter_level(&mut self, level: Option<LogLevel>) {
@@ -196,41 +222,95 @@ impl LogViewer {
     pub fn scroll_up(&mut self, n: u16) {
         self.scroll_offset = self.scroll_offset.saturating_sub(n);
         if n > 0 {
-            self.auto_scroll = false;
+            self.follow.set(false);
         }
     }
 
     pub fn scroll_down(&mut self, n: u16) {
-        let filtered_count = self.filtered_entries().count();
-        let max_scroll = filtered_count.saturating_sub(self.visible_height as usize) as u16;
-        self.scroll_offset = (self.scroll_offset + n).min(max_scroll);
-        
-        if self.scroll_offset >= max_scroll && max_scroll > 0 {
-            self.auto_scroll = true;
+        let max_scroll = scroll::max_offset(self.filtered_entries().count(), self.visible_height);
+        self.scroll_offset = self.scroll_offset.saturating_add(n).min(max_scroll);
+        if self.scroll_offset >= max_scroll {
+            self.follow.reached_bottom();
         }
     }
 
     pub fn scroll_to_top(&mut self) {
         self.scroll_offset = 0;
-        self.auto_scroll = false;
+        self.follow.set(false);
     }
 
     pub fn scroll_to_bottom(&mut self) {
-        let filtered_count = self.filtered_entries().count();
-        if filtered_count > self.visible_height as usize {
-            self.scroll_offset = (filtered_count - self.visible_height as usize) as u16;
-        } else {
-            self.scroll_offset = 0;
-        }
-        self.auto_scroll = true;
+        self.scroll_offset = scroll::max_offset(self.filtered_entries().count(), self.visible_height);
+        self.follow.set(true);
     }
 
     pub fn scroll_page_up(&mut self) {
-        self.scroll_up(self.visible_height.saturating_sub(2).max(1));
+        self.scroll_up(scroll::page_step(self.visible_height));
+    }
+
+    pub fn is_wrap(&self) -> bool {
+        self.wrap.is_wrap()
+    }
+
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap.set_wrap(wrap);
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap.toggle();
+    }
+
+    pub fn h_scroll(&self) -> u16 {
+        self.wrap.h_scroll()
+    }
+
+    pub fn scroll_left(&mut self, n: u16) {
+        self.wrap.scroll_left(n);
+    }
+
+    /// Scroll right, stopping once the longest visible line's last column is reached
+    pub fn scroll_right(&mut self, n: u16) {
+        let widest = self
+            .filtered_entries()
+            .map(|entry| self.prefix_width(entry) + entry.message.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.wrap.scroll_right(n, widest);
+    }
+
+    /// Columns drawn before an entry's message: "[<timestamp> ]<LEVEL> [<target>] "
+    fn prefix_width(&self, entry: &LogEntry) -> usize {
+        let timestamp = if self.show_timestamps { TimestampFormat::WIDTH + 1 } else { 0 };
+        timestamp + 5 + 1 + entry.target.chars().count() + 3
+    }
+
+    pub fn show_timestamps(&self) -> bool {
+        self.show_timestamps
+    }
+
+    pub fn set_show_timestamps(&mut self, show: bool) {
+        self.show_timestamps = show;
+    }
+
+    pub fn toggle_timestamps(&mut self) {
+        self.show_timestamps = !self.show_timestamps;
+    }
+
+    pub fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp_format = format;
+    }
+
+    /// Advance the reference time used by relative timestamps
+    pub fn tick(&mut self) {
+        self.now = chrono::Local::now();
+    }
+
+    fn format_timestamp(&self, entry: &LogEntry) -> String {
+        self.timestamp_format.format(entry.at, self.now.max(entry.at))
     }
 
     pub fn scroll_page_down(&mut self) {
-        self.scroll_down(self.visible_height.saturating_sub(2).max(1));
+        self.scroll_down(scroll::page_step(self.visible_height));
     }
 
     pub fn is_search_active(&self) -> bool {
@@ -247,7 +327,7 @@ impl LogViewer {
 
     pub fn start_search(&mut self) {
         self.search_state.activate();
-        self.auto_scroll = false;
+        self.follow.set(false);
     }
 
     pub fn close_search(&mut self) {
@@ -272,7 +352,7 @@ impl LogViewer {
 
     pub fn start_filter(&mut self) {
         self.filter_state.activate();
-        self.auto_scroll = false;
+        self.follow.set(false);
     }
 
     pub fn close_filter(&mut self) {
@@ -317,14 +397,32 @@ impl LogViewer {
     }
 
     fn scroll_to_current_match(&mut self) {
-        if let Some(m) = self.search_state.current_match() {
-            let target_line = m.line_index as u16;
-            if target_line < self.scroll_offset {
-                self.scroll_offset = target_line.saturating_sub(2);
-            } else if target_line >= self.scroll_offset + self.visible_height {
-                self.scroll_offset = target_line.saturating_sub(self.visible_height / 2);
-            }
-        }
+        self.scroll_offset = self
+            .search_state
+            .scroll_offset_for_current_match(self.scroll_offset, self.visible_height);
+        let prefix = self
+            .search_state
+            .current_match()
+            .and_then(|m| self.filtered_entries().nth(m.line_index))
+            .map(|entry| self.prefix_width(entry))
+            .unwrap_or(0);
+        self.wrap.reveal_match(&self.search_state, self.inner_area.width, prefix);
+    }
+
+    /// Filtered entries rendered as plain text, one per line (for the pager)
+    pub fn to_plain_text(&self) -> String {
+        self.filtered_entries()
+            .map(|entry| {
+                format!(
+                    "{} {:5} [{}] {}",
+                    entry.timestamp,
+                    entry.level.as_str(),
+                    entry.target,
+                    entry.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 
     fn filtered_entries(&self) -> impl Iterator<Item = &LogEntry> {
@@ -421,7 +519,7 @@ impl LogViewer {
         let border_style = theme.border_style(focused);
         let title_style = theme.title_style(focused);
 
-        let auto_scroll_indicator = if self.auto_scroll { "⏬" } else { "⏸" };
+        let wrap_indicator = self.wrap.indicator();
         let search_indicator = if self.search_state.is_active() { " 󰍉" } else { "" };
         let filter_indicator = if self.has_active_filter() {
             format!(" 󰈶:{}", self.filter_state.pattern())
@@ -431,9 +529,10 @@ impl LogViewer {
             String::new()
         };
         let title = format!(
-            " Logs ({}) {}{}{} ",
+            " Logs ({}) {}{}{}{} ",
             self.filtered_entries().count(),
-            auto_scroll_indicator,
+            self.follow.indicator(),
+            wrap_indicator,
             search_indicator,
             filter_indicator
         );
@@ -489,11 +588,6 @@ impl LogViewer {
                         .add_modifier(Modifier::BOLD),
                 );
 
-                let timestamp_span = Span::styled(
-                    format!("{} ", entry.timestamp),
-                    Style::default().fg(theme.colors.muted.to_color()),
-                );
-
                 let target_span = Span::styled(
                     format!("[{}] ", entry.target),
                     Style::default()
@@ -515,17 +609,23 @@ impl LogViewer {
                     vec![Span::styled(entry.message.clone(), normal_style)]
                 };
 
-                let mut spans = vec![timestamp_span, level_span, Span::raw(" "), target_span];
+                let mut spans = Vec::with_capacity(5);
+                if self.show_timestamps {
+                    spans.push(Span::styled(
+                        format!("{} ", self.format_timestamp(entry)),
+                        Style::default().fg(theme.colors.muted.to_color()),
+                    ));
+                }
+                spans.extend([level_span, Span::raw(" "), target_span]);
                 spans.extend(message_spans);
                 Line::from(spans)
             })
             .collect();
 
-        let paragraph = Paragraph::new(lines)
-            .block(block)
-            .wrap(Wrap { trim: false });
+        let paragraph = self.wrap.apply(Paragraph::new(lines).block(block));
 
         frame.render_widget(paragraph, log_area);
+        self.follow.render_hint(frame, log_area, theme);
 
         if let Some(rect) = bar_area {
             if self.search_state.is_active() {
@@ -624,6 +724,22 @@ impl LogViewer {
                 self.toggle_auto_scroll();
                 Some(Action::LogViewerToggleAutoScroll)
             }
+            KeyCode::Char('w') => {
+                self.toggle_wrap();
+                Some(Action::LogViewerToggleWrap)
+            }
+            KeyCode::Char('t') => {
+                self.toggle_timestamps();
+                Some(Action::LogViewerToggleTimestamps)
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.scroll_left(HORIZONTAL_SCROLL_STEP);
+                Some(Action::LogViewerScrollLeft(HORIZONTAL_SCROLL_STEP))
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.scroll_right(HORIZONTAL_SCROLL_STEP);
+                Some(Action::LogViewerScrollRight(HORIZONTAL_SCROLL_STEP))
+            }
             KeyCode::Char('c') => {
                 self.clear();
                 Some(Action::LogViewerClear)
@@ -644,12 +760,12 @@ impl LogViewer {
     fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
         match mouse.kind {
             MouseEventKind::ScrollUp => {
-                self.scroll_up(3);
-                Some(Action::LogViewerScrollUp(3))
+                self.scroll_up(self.scroll_lines);
+                Some(Action::LogViewerScrollUp(self.scroll_lines))
             }
             MouseEventKind::ScrollDown => {
-                self.scroll_down(3);
-                Some(Action::LogViewerScrollDown(3))
+                self.scroll_down(self.scroll_lines);
+                Some(Action::LogViewerScrollDown(self.scroll_lines))
             }
             MouseEventKind::Down(MouseButton::Left) => {
                 if mouse.row == 0 {
@@ -664,6 +780,16 @@ impl LogViewer {
     }
 }
 
+impl Following for LogViewer {
+    fn follow(&self) -> &Follow {
+        &self.follow
+    }
+
+    fn follow_mut(&mut self) -> &mut Follow {
+        &mut self.follow
+    }
+}
+
 impl Default for LogViewer {
     fn default() -> Self {
         Self::new()
@@ -688,6 +814,11 @@ impl Component for LogViewer {
             Action::LogViewerScrollPageUp => self.scroll_page_up(),
             Action::LogViewerScrollPageDown => self.scroll_page_down(),
             Action::LogViewerToggleAutoScroll => self.toggle_auto_scroll(),
+            Action::LogViewerToggleWrap => self.toggle_wrap(),
+            Action::LogViewerToggleTimestamps => self.toggle_timestamps(),
+            Action::Tick => self.tick(),
+            Action::LogViewerScrollLeft(n) => self.scroll_left(*n),
+            Action::LogViewerScrollRight(n) => self.scroll_right(*n),
             Action::LogViewerClear => self.clear(),
             Action::LogViewerSearchStart => self.start_search(),
             Action::LogViewerSearchClose => self.close_search(),
@@ -785,6 +916,29 @@ mod tests {
         assert!(!viewer.is_auto_scroll());
     }
 
+    #[test]
+    fn test_paused_viewer_stays_put_and_counts_new_lines() {
+        let mut viewer = LogViewer::new();
+        viewer.visible_height = 5;
+        for i in 0..20 {
+            viewer.push_info("test", format!("Message {}", i));
+        }
+        viewer.scroll_up(3);
+        let offset = viewer.scroll_offset;
+        viewer.push_info("test", "late");
+        viewer.push_info("test", "later");
+        assert_eq!(viewer.scroll_offset, offset);
+        assert_eq!(viewer.follow().unseen_hint().as_deref(), Some("2 new lines below"));
+
+        // Scrolling back down clears the hint but stays paused until 'f' or G
+        viewer.scroll_down(100);
+        assert_eq!(viewer.follow().unseen_hint(), None);
+        assert!(!viewer.is_auto_scroll());
+        viewer.toggle_auto_scroll();
+        assert!(viewer.is_auto_scroll());
+        assert_eq!(viewer.scroll_offset, 17);
+    }
+
     #[test]
     fn test_scroll_to_bottom_enables_auto_scroll() {
         let mut viewer = LogViewer::new();
@@ -798,6 +952,71 @@ mod tests {
         assert!(viewer.is_auto_scroll());
     }
 
+    #[test]
+    fn test_to_plain_text_respects_filter() {
+        let mut viewer = LogViewer::new();
+        viewer.push_info("app", "started");
+        viewer.push_error("net", "connection lost");
+        viewer.set_filter_level(Some(LogLevel::Error));
+
+        let text = viewer.to_plain_text();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.ends_with("ERROR [net] connection lost"));
+    }
+
+    #[test]
+    fn test_wrap_toggle_and_horizontal_scroll() {
+        let mut viewer = LogViewer::new();
+        viewer.set_inner_area(Rect::new(0, 0, 40, 10));
+        viewer.push_info("app", format!("{}needle", "x".repeat(200)));
+        assert!(viewer.is_wrap());
+
+        // Horizontal scroll is a no-op while wrapping
+        viewer.scroll_right(8);
+        assert_eq!(viewer.h_scroll(), 0);
+
+        viewer.toggle_wrap();
+        assert!(!viewer.is_wrap());
+        viewer.scroll_right(8);
+        assert_eq!(viewer.h_scroll(), 8);
+        viewer.scroll_left(20);
+        assert_eq!(viewer.h_scroll(), 0);
+
+        // Clamped to the widest line
+        viewer.scroll_right(u16::MAX);
+        let widest = viewer.prefix_width(&viewer.logs[0]) + 206;
+        assert_eq!(viewer.h_scroll() as usize, widest - 1);
+
+        // Search brings an off-screen match into view
+        viewer.scroll_left(u16::MAX);
+        viewer.search_state.set_query("needle".to_string());
+        viewer.update_search();
+        viewer.search_next();
+        let column = viewer.prefix_width(&viewer.logs[0]) + 200;
+        assert!((viewer.h_scroll() as usize) <= column);
+        assert!(viewer.h_scroll() as usize + 40 >= column + 6);
+
+        // Re-enabling wrap resets the horizontal offset
+        viewer.toggle_wrap();
+        assert_eq!(viewer.h_scroll(), 0);
+    }
+
+    #[test]
+    fn test_timestamp_toggle_and_relative_format() {
+        let mut viewer = LogViewer::new();
+        viewer.push_info("app", "hello");
+        let with_timestamps = viewer.prefix_width(&viewer.logs[0]);
+
+        viewer.update(&Action::LogViewerToggleTimestamps);
+        assert!(!viewer.show_timestamps());
+        assert_eq!(viewer.prefix_width(&viewer.logs[0]), with_timestamps - TimestampFormat::WIDTH - 1);
+
+        viewer.set_timestamp_format(TimestampFormat::Relative);
+        viewer.logs[0].at -= chrono::Duration::seconds(12);
+        viewer.update(&Action::Tick);
+        assert_eq!(viewer.format_timestamp(&viewer.logs[0]).trim(), "12s ago");
+    }
+
     #[test]
     fn test_clear() {
         let mut viewer = LogViewer::new();
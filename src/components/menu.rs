@@ -11,7 +11,7 @@ use crate::action::Action;
 use crate::components::spinner::{Spinner, SpinnerStyle};
 use crate::components::Component;
 use crate::config::Theme;
-use crate::streams::{ConnectionState, StreamClient};
+use crate::streams::{ConnectionState, PoolStatus, StreamClient};
 
 pub struct Menu {
     selected: usize,
@@ -30,6 +30,10 @@ impl Menu {
         }
     }
     
+    pub fn set_connecting_spinner(&mut self, spinner: Spinner) {
+        self.connecting_spinner = spinner;
+    }
+
     pub fn tick_spinners(&mut self) {
         self.connecting_spinner.tick();
     }
@@ -102,14 +106,14 @@ impl Menu {
         }
     }
 
-    pub fn render_with_streams(&self, frame: &mut Frame, area: Rect, focused: bool, streams: &[StreamClient], theme: &Theme) {
+    pub fn render_with_streams(&self, frame: &mut Frame, area: Rect, focused: bool, streams: &[StreamClient], pool: PoolStatus, theme: &Theme) {
         let border_style = theme.border_style(focused);
         let title_style = theme.title_style(focused);
 
         let title = if focused {
-            " Streams [↵=toggle v=view] "
+            format!(" Streams {} [↵=toggle v=view] ", pool)
         } else {
-            " Streams "
+            format!(" Streams {} ", pool)
         };
 
         let block = Block::default()
@@ -135,6 +139,9 @@ impl Menu {
                     ConnectionState::Disconnected => {
                         ("○".to_string(), theme.menu.stream_disconnected.to_color())
                     }
+                    ConnectionState::Queued => {
+                        ("⧖".to_string(), theme.menu.stream_connecting.to_color())
+                    }
                     ConnectionState::Failed => {
                         ("✗".to_string(), theme.menu.stream_error.to_color())
                     }
@@ -8,11 +8,18 @@ pub mod context_menu;
 pub mod conversation_viewer;
 pub mod diff_view;
 pub mod gpu_monitor;
+pub mod inline_image;
+pub mod input_history;
 pub mod log_viewer;
 pub mod menu;
+pub mod model_picker;
 pub mod notification;
+pub mod overlay_layout;
+pub mod pager;
 pub mod pane_layout;
+pub mod plan_panel;
 pub mod process_monitor;
+pub mod scroll;
 pub mod search;
 pub mod settings_editor;
 pub mod spinner;
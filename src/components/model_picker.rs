@@ -0,0 +1,342 @@
+//! Quick model switcher
+//!
+//! Lists every model in the catalog across providers with fuzzy search,
+//! showing the context window and whether the provider has an API key
+//! registered. Enter switches provider and model in one step.
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use nucleo::{Config, Matcher};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::action::Action;
+use crate::components::command_palette::{fuzzy_indices, fuzzy_pattern};
+use crate::config::Theme;
+
+/// One selectable provider/model pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelEntry {
+    pub provider: String,
+    pub model: String,
+    pub context_window: u32,
+    /// Provider has an API key and is registered with the LLM manager
+    pub registered: bool,
+    /// Currently selected provider/model
+    pub current: bool,
+}
+
+struct MatchResult {
+    entry_idx: usize,
+    score: u32,
+    /// Matched positions in the model name (empty for provider-only matches)
+    indices: Vec<u32>,
+}
+
+pub struct ModelPicker {
+    visible: bool,
+    query: String,
+    entries: Vec<ModelEntry>,
+    matcher: Matcher,
+    filtered_results: Vec<MatchResult>,
+    list_state: ListState,
+}
+
+impl ModelPicker {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            query: String::new(),
+            entries: Vec::new(),
+            matcher: Matcher::new(Config::DEFAULT),
+            filtered_results: Vec::new(),
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show the picker with the current model preselected
+    pub fn show(&mut self, entries: Vec<ModelEntry>) {
+        self.visible = true;
+        self.query.clear();
+        self.entries = entries;
+        self.update_filtered_results();
+        let current = self.filtered_results.iter().position(|r| self.entries[r.entry_idx].current);
+        self.list_state.select(current.or(Some(0)).filter(|_| !self.filtered_results.is_empty()));
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.query.clear();
+        self.entries.clear();
+        self.filtered_results.clear();
+        self.list_state.select(None);
+    }
+
+    fn update_filtered_results(&mut self) {
+        self.filtered_results.clear();
+
+        if self.query.is_empty() {
+            self.filtered_results.extend((0..self.entries.len()).map(|entry_idx| MatchResult {
+                entry_idx,
+                score: 0,
+                indices: Vec::new(),
+            }));
+        } else {
+            let pattern = fuzzy_pattern(&self.query);
+            for (entry_idx, entry) in self.entries.iter().enumerate() {
+                let mut indices = Vec::new();
+                let score = fuzzy_indices(&pattern, &mut self.matcher, &entry.model, &mut indices).or_else(|| {
+                    indices.clear();
+                    fuzzy_indices(&pattern, &mut self.matcher, &entry.provider, &mut Vec::new())
+                });
+                if let Some(score) = score {
+                    self.filtered_results.push(MatchResult { entry_idx, score, indices });
+                }
+            }
+            self.filtered_results.sort_by_key(|r| std::cmp::Reverse(r.score));
+        }
+
+        self.list_state.select(if self.filtered_results.is_empty() { None } else { Some(0) });
+    }
+
+    fn select_next(&mut self) {
+        if self.filtered_results.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some((current + 1) % self.filtered_results.len()));
+    }
+
+    fn select_prev(&mut self) {
+        if self.filtered_results.is_empty() {
+            return;
+        }
+        let current = self.list_state.selected().unwrap_or(0);
+        let len = self.filtered_results.len();
+        self.list_state.select(Some((current + len - 1) % len));
+    }
+
+    fn selected_entry(&self) -> Option<&ModelEntry> {
+        let result = self.filtered_results.get(self.list_state.selected()?)?;
+        self.entries.get(result.entry_idx)
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        if !self.visible {
+            return None;
+        }
+
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Esc => return Some(Action::ModelPickerHide),
+                KeyCode::Enter => {
+                    let entry = self.selected_entry()?;
+                    return Some(Action::ModelPickerSelect {
+                        provider: entry.provider.clone(),
+                        model: entry.model.clone(),
+                    });
+                }
+                KeyCode::Up | KeyCode::BackTab => self.select_prev(),
+                KeyCode::Down | KeyCode::Tab => self.select_next(),
+                KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => self.select_next(),
+                KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => self.select_prev(),
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.update_filtered_results();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.update_filtered_results();
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.visible {
+            return;
+        }
+
+        let dialog_width = (area.width * 60 / 100).clamp(50, 100).min(area.width);
+        let dialog_height = (area.height * 50 / 100).clamp(10, 30).min(area.height);
+        let dialog_x = (area.width.saturating_sub(dialog_width)) / 2;
+        let dialog_y = (area.height.saturating_sub(dialog_height)) / 2;
+        let dialog_area = Rect::new(dialog_x, dialog_y, dialog_width, dialog_height);
+
+        frame.render_widget(Clear, dialog_area);
+
+        let block = Block::default()
+            .title(" Switch Model ")
+            .title_style(Style::default().fg(theme.command_palette.border.to_color()).add_modifier(Modifier::BOLD))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.command_palette.border.to_color()));
+        let inner = block.inner(dialog_area);
+        frame.render_widget(block, dialog_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Input
+                Constraint::Length(1), // Info
+                Constraint::Min(1),    // Results
+            ])
+            .split(inner);
+
+        let input_line = Line::from(vec![
+            Span::styled(": ", Style::default().fg(theme.colors.primary.to_color()).add_modifier(Modifier::BOLD)),
+            Span::styled(&self.query, Style::default().fg(theme.command_palette.input_fg.to_color())),
+            Span::styled("▎", Style::default().fg(theme.colors.primary.to_color())),
+        ]);
+        frame.render_widget(Paragraph::new(input_line), chunks[0]);
+
+        let info = if self.query.is_empty() {
+            format!("{} models", self.entries.len())
+        } else {
+            format!("{}/{} matching", self.filtered_results.len(), self.entries.len())
+        };
+        frame.render_widget(
+            Paragraph::new(info)
+                .style(Style::default().fg(theme.command_palette.description_fg.to_color()))
+                .alignment(Alignment::Right),
+            chunks[1],
+        );
+
+        let items: Vec<ListItem> = self
+            .filtered_results
+            .iter()
+            .map(|result| self.render_entry(&self.entries[result.entry_idx], &result.indices, theme))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(theme.command_palette.selected_bg.to_color())
+                    .fg(theme.command_palette.selected_fg.to_color())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶ ");
+
+        let mut list_state = self.list_state.clone();
+        frame.render_stateful_widget(list, chunks[2], &mut list_state);
+    }
+
+    fn render_entry(&self, entry: &ModelEntry, indices: &[u32], theme: &Theme) -> ListItem<'static> {
+        let item_style = Style::default().fg(theme.command_palette.item_fg.to_color());
+        let match_style = Style::default().fg(theme.command_palette.match_highlight.to_color()).add_modifier(Modifier::BOLD);
+        let dim_style = Style::default().fg(theme.command_palette.description_fg.to_color());
+
+        let mut spans: Vec<Span<'static>> = entry
+            .model
+            .chars()
+            .enumerate()
+            .map(|(i, ch)| {
+                let style = if indices.contains(&(i as u32)) { match_style } else { item_style };
+                Span::styled(ch.to_string(), style)
+            })
+            .collect();
+        if entry.current {
+            spans.push(Span::styled(" ✓", Style::default().fg(theme.colors.success.to_color())));
+        }
+        spans.push(Span::styled(
+            format!("  {} · {} ctx", entry.provider, format_context(entry.context_window)),
+            dim_style,
+        ));
+        if !entry.registered {
+            spans.push(Span::styled("  no key", Style::default().fg(theme.colors.warning.to_color())));
+        }
+
+        ListItem::new(Line::from(spans))
+    }
+}
+
+impl Default for ModelPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compact context window size ("200k", "1M")
+fn format_context(tokens: u32) -> String {
+    if tokens >= 1_000_000 && tokens % 1_000_000 == 0 {
+        format!("{}M", tokens / 1_000_000)
+    } else if tokens >= 1_000 {
+        format!("{}k", tokens / 1_000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn entry(provider: &str, model: &str, registered: bool, current: bool) -> ModelEntry {
+        ModelEntry {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            context_window: 200_000,
+            registered,
+            current,
+        }
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_show_selects_current_model() {
+        let mut picker = ModelPicker::new();
+        picker.show(vec![
+            entry("anthropic", "claude-sonnet-4", true, false),
+            entry("openai", "gpt-4o", false, true),
+        ]);
+        assert_eq!(picker.selected_entry().unwrap().model, "gpt-4o");
+    }
+
+    #[test]
+    fn test_fuzzy_filter_and_select() {
+        let mut picker = ModelPicker::new();
+        picker.show(vec![
+            entry("anthropic", "claude-sonnet-4", true, true),
+            entry("openai", "gpt-4o", false, false),
+            entry("gemini", "gemini-2.5-pro", false, false),
+        ]);
+
+        for c in "gpt".chars() {
+            picker.handle_event(&key(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.filtered_results.len(), 1);
+        assert!(matches!(
+            picker.handle_event(&key(KeyCode::Enter)),
+            Some(Action::ModelPickerSelect { provider, model }) if provider == "openai" && model == "gpt-4o"
+        ));
+
+        // Provider names match too
+        picker.handle_event(&key(KeyCode::Backspace));
+        picker.handle_event(&key(KeyCode::Backspace));
+        picker.handle_event(&key(KeyCode::Backspace));
+        for c in "anthropic".chars() {
+            picker.handle_event(&key(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.selected_entry().unwrap().model, "claude-sonnet-4");
+    }
+
+    #[test]
+    fn test_format_context() {
+        assert_eq!(format_context(200_000), "200k");
+        assert_eq!(format_context(1_000_000), "1M");
+        assert_eq!(format_context(1_048_576), "1048k");
+    }
+}
@@ -18,6 +18,9 @@ const MAX_VISIBLE_NOTIFICATIONS: usize = 5;
 const NOTIFICATION_WIDTH: u16 = 40;
 const NOTIFICATION_HEIGHT: u16 = 3;
 const NOTIFICATION_MARGIN: u16 = 1;
+/// Identical notifications repeated within this window collapse even when
+/// other notifications arrived in between
+const RATE_LIMIT_WINDOW_MS: u64 = 1500;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NotificationLevel {
@@ -47,6 +50,8 @@ pub struct Notification {
     pub created_at: Instant,
     pub duration: Duration,
     pub dismissable: bool,
+    /// Occurrences collapsed into this notification
+    pub count: u32,
 }
 
 #[allow(dead_code)]
@@ -60,9 +65,15 @@ impl Notification {
             created_at: Instant::now(),
             duration: Duration::from_millis(DEFAULT_DURATION_MS),
             dismissable: true,
+            count: 1,
         }
     }
 
+    /// Same severity, title and message
+    fn same_content(&self, other: &Notification) -> bool {
+        self.level == other.level && self.title == other.title && self.message == other.message
+    }
+
     pub fn with_message(mut self, message: impl Into<String>) -> Self {
         self.message = Some(message.into());
         self
@@ -103,7 +114,23 @@ impl NotificationManager {
         }
     }
 
+    /// Add a notification, collapsing it into an identical live one when it
+    /// repeats back-to-back or within the rate-limit window
     pub fn push(&mut self, mut notification: Notification) {
+        let last_index = self.notifications.len().checked_sub(1);
+        let window = Duration::from_millis(RATE_LIMIT_WINDOW_MS);
+        let duplicate = self.notifications.iter_mut().enumerate().rev().find(|(i, n)| {
+            n.same_content(&notification)
+                && !n.is_expired()
+                && (Some(*i) == last_index || n.created_at.elapsed() < window)
+        });
+        if let Some((_, existing)) = duplicate {
+            existing.count += notification.count;
+            // Keep it on screen while the storm lasts
+            existing.created_at = notification.created_at;
+            return;
+        }
+
         notification.id = self.next_id;
         self.next_id += 1;
         self.notifications.push_back(notification);
@@ -253,7 +280,11 @@ impl NotificationManager {
         };
 
         let icon = notification.level.icon();
-        let title = format!("{} {}", icon, notification.title);
+        let title = if notification.count > 1 {
+            format!("{} {} (x{})", icon, notification.title, notification.count)
+        } else {
+            format!("{} {}", icon, notification.title)
+        };
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -428,6 +459,26 @@ mod tests {
         assert_eq!(visible_count, MAX_VISIBLE_NOTIFICATIONS);
     }
 
+    #[test]
+    fn test_manager_collapses_identical() {
+        let mut mgr = NotificationManager::new();
+        for _ in 0..10 {
+            mgr.error_with_message("Stream error", "connection reset");
+        }
+        assert_eq!(mgr.count(), 1);
+        assert_eq!(mgr.visible().next().unwrap().count, 10);
+
+        // Different message or severity is a separate entry
+        mgr.error_with_message("Stream error", "timed out");
+        mgr.warning_with_message("Stream error", "timed out");
+        assert_eq!(mgr.count(), 3);
+
+        // Interleaved repeats inside the rate-limit window still collapse
+        mgr.error_with_message("Stream error", "connection reset");
+        assert_eq!(mgr.count(), 3);
+        assert_eq!(mgr.visible().next().unwrap().count, 11);
+    }
+
     #[test]
     fn test_truncate_string() {
         assert_eq!(truncate_string("short", 10), "short");
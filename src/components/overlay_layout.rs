@@ -0,0 +1,327 @@
+// Overlay geometry for the stream viewer, log viewer and config panel
+//
+// Each overlay keeps a position (center/right/left/full) and a size in percent of
+// the content area. Areas are recomputed from the current content area on every
+// draw, so they always stay inside the terminal and below the tab bar.
+//
+// Up to `ui.max_overlays` overlays can be open at once; when several are open
+// they are tiled side by side inside the region their geometries cover.
+
+use ratatui::layout::Rect;
+
+use crate::action::OverlayKind;
+use crate::config::{OverlayPosition, UiConfig};
+
+const MIN_OVERLAY_PERCENT: u16 = 20;
+const MAX_OVERLAY_PERCENT: u16 = 100;
+const MIN_OVERLAY_WIDTH: u16 = 30;
+const MIN_OVERLAY_HEIGHT: u16 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayGeometry {
+    pub position: OverlayPosition,
+    pub width_percent: u16,
+    pub height_percent: u16,
+}
+
+impl OverlayGeometry {
+    pub fn new(position: OverlayPosition, width_percent: u16, height_percent: u16) -> Self {
+        Self {
+            position,
+            width_percent: width_percent.clamp(MIN_OVERLAY_PERCENT, MAX_OVERLAY_PERCENT),
+            height_percent: height_percent.clamp(MIN_OVERLAY_PERCENT, MAX_OVERLAY_PERCENT),
+        }
+    }
+
+    /// Overlay rectangle inside `content`, clamped to its bounds
+    pub fn area(&self, content: Rect) -> Rect {
+        if content.width == 0 || content.height == 0 {
+            return Rect::default();
+        }
+        let width = (content.width as u32 * self.width_percent as u32 / 100) as u16;
+        let width = width.max(MIN_OVERLAY_WIDTH).min(content.width);
+        let height = (content.height as u32 * self.height_percent as u32 / 100) as u16;
+        let height = height.max(MIN_OVERLAY_HEIGHT).min(content.height);
+
+        match self.position {
+            OverlayPosition::Full => content,
+            OverlayPosition::Right => Rect::new(content.right() - width, content.y, width, content.height),
+            OverlayPosition::Left => Rect::new(content.x, content.y, width, content.height),
+            OverlayPosition::Center => Rect::new(
+                content.x + (content.width - width) / 2,
+                content.y + (content.height - height) / 2,
+                width,
+                height,
+            ),
+        }
+    }
+
+    /// Whether (x, y) is on the border that resizes this overlay
+    pub fn hit_test_border(&self, x: u16, y: u16, content: Rect) -> bool {
+        let area = self.area(content);
+        if area.width == 0 || y < area.y || y >= area.bottom() {
+            return false;
+        }
+        let left = x == area.x;
+        let right = x + 1 == area.right();
+        match self.position {
+            OverlayPosition::Full => false,
+            OverlayPosition::Right => left,
+            OverlayPosition::Left => right,
+            OverlayPosition::Center => left || right,
+        }
+    }
+
+    /// Resize by dragging the border to column `x`
+    pub fn drag_to(&mut self, x: u16, content: Rect) {
+        if content.width == 0 {
+            return;
+        }
+        let width = match self.position {
+            OverlayPosition::Full => return,
+            OverlayPosition::Right => content.right().saturating_sub(x),
+            OverlayPosition::Left => x.saturating_sub(content.x) + 1,
+            OverlayPosition::Center => {
+                let center = content.x + content.width / 2;
+                x.abs_diff(center) * 2
+            }
+        };
+        let percent = (width as u32 * 100 / content.width as u32) as u16;
+        self.width_percent = percent.clamp(MIN_OVERLAY_PERCENT, MAX_OVERLAY_PERCENT);
+    }
+
+    pub fn cycle_position(&mut self) {
+        self.position = match self.position {
+            OverlayPosition::Center => OverlayPosition::Right,
+            OverlayPosition::Right => OverlayPosition::Left,
+            OverlayPosition::Left => OverlayPosition::Full,
+            OverlayPosition::Full => OverlayPosition::Center,
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OverlayLayout {
+    stream: OverlayGeometry,
+    log: OverlayGeometry,
+    config: OverlayGeometry,
+    /// Geometries last read from config, to tell user resizes from config edits
+    configured: [OverlayGeometry; 3],
+    /// Open overlays, oldest first (last = most recently opened)
+    open: Vec<OverlayKind>,
+    max_open: usize,
+}
+
+impl Default for OverlayLayout {
+    fn default() -> Self {
+        Self::from_config(&UiConfig::default())
+    }
+}
+
+impl OverlayLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defaults from `ui.overlay_*`; without a configured size the panels keep
+    /// their usual proportions (70% stream/log, 60% config)
+    pub fn from_config(ui: &UiConfig) -> Self {
+        let geometry = |default_percent: u16| {
+            OverlayGeometry::new(
+                ui.overlay_position,
+                ui.overlay_width_percent.unwrap_or(default_percent),
+                ui.overlay_height_percent.unwrap_or(default_percent),
+            )
+        };
+        let (stream, log, config) = (geometry(70), geometry(70), geometry(60));
+        Self {
+            stream,
+            log,
+            config,
+            configured: [stream, log, config],
+            open: Vec::new(),
+            max_open: ui.max_overlays.max(1),
+        }
+    }
+
+    /// Re-read geometry defaults from config, keeping the open overlays.
+    /// Geometry the user has moved or resized is kept unless its config
+    /// values changed. Overlays past a lowered limit are closed and returned,
+    /// oldest first.
+    pub fn reconfigure(&mut self, ui: &UiConfig) -> Vec<OverlayKind> {
+        let previous = std::mem::replace(self, Self::from_config(ui));
+        for (i, kind) in [OverlayKind::StreamViewer, OverlayKind::LogViewer, OverlayKind::ConfigPanel]
+            .into_iter()
+            .enumerate()
+        {
+            if self.configured[i] == previous.configured[i] {
+                *self.get_mut(kind) = *previous.get(kind);
+            }
+        }
+        let open = previous.open;
+        let excess = open.len().saturating_sub(self.max_open);
+        self.open = open[excess..].to_vec();
+        open[..excess].to_vec()
+    }
+
+    /// Mark `kind` open and on top. Returns the overlay closed to stay within the limit.
+    pub fn open(&mut self, kind: OverlayKind) -> Option<OverlayKind> {
+        self.open.retain(|k| *k != kind);
+        self.open.push(kind);
+        if self.open.len() > self.max_open {
+            Some(self.open.remove(0))
+        } else {
+            None
+        }
+    }
+
+    pub fn close(&mut self, kind: OverlayKind) {
+        self.open.retain(|k| *k != kind);
+    }
+
+    /// Open overlays, oldest first
+    pub fn open_overlays(&self) -> &[OverlayKind] {
+        &self.open
+    }
+
+    pub fn topmost(&self) -> Option<OverlayKind> {
+        self.open.last().copied()
+    }
+
+    pub fn is_tiled(&self) -> bool {
+        self.open.len() > 1
+    }
+
+    /// Open overlay after (or before) `current`, wrapping around
+    pub fn cycle(&self, current: OverlayKind, forward: bool) -> Option<OverlayKind> {
+        let idx = self.open.iter().position(|k| *k == current)?;
+        let len = self.open.len();
+        let next = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        Some(self.open[next])
+    }
+
+    /// Area for each open overlay. A single overlay uses its own geometry; several
+    /// are tiled in equal columns across the region their geometries cover.
+    pub fn tiled_areas(&self, content: Rect) -> Vec<(OverlayKind, Rect)> {
+        if !self.is_tiled() {
+            return self.open.iter().map(|k| (*k, self.area(*k, content))).collect();
+        }
+        let region = self.open.iter()
+            .map(|k| self.area(*k, content))
+            .reduce(|a, b| a.union(b))
+            .unwrap_or(content);
+        let count = self.open.len() as u16;
+        let column = region.width / count;
+        self.open.iter().enumerate().map(|(i, kind)| {
+            let i = i as u16;
+            let x = region.x + i * column;
+            // Last column takes the remainder
+            let width = if i + 1 == count { region.right() - x } else { column };
+            (*kind, Rect::new(x, region.y, width, region.height))
+        }).collect()
+    }
+
+    pub fn get(&self, kind: OverlayKind) -> &OverlayGeometry {
+        match kind {
+            OverlayKind::StreamViewer => &self.stream,
+            OverlayKind::LogViewer => &self.log,
+            OverlayKind::ConfigPanel => &self.config,
+        }
+    }
+
+    pub fn get_mut(&mut self, kind: OverlayKind) -> &mut OverlayGeometry {
+        match kind {
+            OverlayKind::StreamViewer => &mut self.stream,
+            OverlayKind::LogViewer => &mut self.log,
+            OverlayKind::ConfigPanel => &mut self.config,
+        }
+    }
+
+    pub fn area(&self, kind: OverlayKind, content: Rect) -> Rect {
+        self.get(kind).area(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: Rect = Rect { x: 0, y: 1, width: 200, height: 50 };
+
+    #[test]
+    fn test_overlay_positions() {
+        let mut geometry = OverlayGeometry::new(OverlayPosition::Right, 50, 70);
+        assert_eq!(geometry.area(CONTENT), Rect::new(100, 1, 100, 50));
+
+        geometry.position = OverlayPosition::Left;
+        assert_eq!(geometry.area(CONTENT), Rect::new(0, 1, 100, 50));
+
+        geometry.position = OverlayPosition::Center;
+        assert_eq!(geometry.area(CONTENT), Rect::new(50, 8, 100, 35));
+
+        geometry.position = OverlayPosition::Full;
+        assert_eq!(geometry.area(CONTENT), CONTENT);
+    }
+
+    #[test]
+    fn test_overlay_clamps_to_small_terminal() {
+        let geometry = OverlayGeometry::new(OverlayPosition::Center, 50, 50);
+        let tiny = Rect::new(0, 1, 20, 6);
+        let area = geometry.area(tiny);
+        assert_eq!(area, tiny);
+        assert!(area.y >= 1, "overlay must stay below the tab bar");
+    }
+
+    #[test]
+    fn test_overlay_open_limit_and_tiling() {
+        let ui = UiConfig { max_overlays: 2, ..UiConfig::default() };
+        let mut layout = OverlayLayout::from_config(&ui);
+        assert_eq!(layout.open(OverlayKind::StreamViewer), None);
+        assert_eq!(layout.open(OverlayKind::LogViewer), None);
+
+        let areas = layout.tiled_areas(CONTENT);
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[0].1.right(), areas[1].1.x);
+        assert_eq!(areas[0].1.y, areas[1].1.y);
+
+        // Third overlay closes the oldest
+        assert_eq!(layout.open(OverlayKind::ConfigPanel), Some(OverlayKind::StreamViewer));
+        assert_eq!(layout.open_overlays(), &[OverlayKind::LogViewer, OverlayKind::ConfigPanel]);
+        assert_eq!(layout.cycle(OverlayKind::ConfigPanel, true), Some(OverlayKind::LogViewer));
+        assert_eq!(layout.cycle(OverlayKind::LogViewer, false), Some(OverlayKind::ConfigPanel));
+
+        let closed = layout.reconfigure(&UiConfig { max_overlays: 1, ..UiConfig::default() });
+        assert_eq!(closed, vec![OverlayKind::LogViewer]);
+        assert_eq!(layout.topmost(), Some(OverlayKind::ConfigPanel));
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_user_resized_geometry() {
+        let mut layout = OverlayLayout::from_config(&UiConfig::default());
+        layout.get_mut(OverlayKind::LogViewer).drag_to(40, CONTENT);
+        let dragged = *layout.get(OverlayKind::LogViewer);
+
+        // Unrelated config change: the dragged size survives
+        layout.reconfigure(&UiConfig { max_overlays: 3, ..UiConfig::default() });
+        assert_eq!(*layout.get(OverlayKind::LogViewer), dragged);
+
+        // Editing the overlay size in config takes effect
+        let ui = UiConfig { overlay_width_percent: Some(50), ..UiConfig::default() };
+        layout.reconfigure(&ui);
+        assert_eq!(layout.get(OverlayKind::LogViewer).width_percent, 50);
+    }
+
+    #[test]
+    fn test_overlay_drag_resize() {
+        let mut geometry = OverlayGeometry::new(OverlayPosition::Right, 50, 70);
+        assert!(geometry.hit_test_border(100, 10, CONTENT));
+        assert!(!geometry.hit_test_border(199, 10, CONTENT));
+
+        geometry.drag_to(40, CONTENT);
+        assert_eq!(geometry.width_percent, 80);
+        assert_eq!(geometry.area(CONTENT).x, 40);
+
+        geometry.drag_to(199, CONTENT);
+        assert_eq!(geometry.width_percent, MIN_OVERLAY_PERCENT);
+    }
+}
@@ -0,0 +1,582 @@
+// Pager: full-screen overlay for long tool and log output with scroll, search and wrap
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::action::Action;
+use crate::components::scroll::{self, LineWrap};
+use crate::components::search::{
+    highlight_matches_in_line, SearchAction, SearchBar, SearchState, HORIZONTAL_SCROLL_STEP,
+};
+use crate::components::Component;
+use crate::config::Theme;
+
+/// Tabs are expanded so column math and rendering agree
+const TAB_WIDTH: usize = 4;
+
+/// What the pager is showing, for views the app keeps up to date while open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PagerKind {
+    /// Fixed text handed over once
+    #[default]
+    Text,
+    /// The resolved system prompt, rebuilt as persona, redaction or working dir change
+    SystemPrompt,
+}
+
+/// Reusable read-only viewer for arbitrary text content
+pub struct Pager {
+    visible: bool,
+    kind: PagerKind,
+    title: String,
+    /// Source lines as provided by the caller
+    lines: Vec<String>,
+    /// First display row of each source line, plus the total row count at the end
+    row_starts: Vec<usize>,
+    /// Top display row
+    scroll_offset: u16,
+    visible_height: u16,
+    width: u16,
+    wrap: LineWrap,
+    search_state: SearchState,
+    /// Lines per mouse-wheel notch (`ui.scroll_lines`)
+    scroll_lines: u16,
+}
+
+impl Pager {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            kind: PagerKind::Text,
+            title: String::new(),
+            lines: Vec::new(),
+            row_starts: vec![0],
+            scroll_offset: 0,
+            visible_height: 20,
+            width: 80,
+            wrap: LineWrap::default(),
+            search_state: SearchState::new(),
+            scroll_lines: scroll::DEFAULT_SCROLL_LINES,
+        }
+    }
+
+    /// `ui.scroll_lines`: lines per mouse-wheel notch
+    pub fn set_scroll_lines(&mut self, lines: u16) {
+        self.scroll_lines = lines.max(1);
+    }
+
+    /// Open the pager with new content, resetting scroll and search
+    pub fn show(&mut self, title: impl Into<String>, content: &str) {
+        self.show_kind(PagerKind::Text, title, content);
+    }
+
+    /// Open the pager on a view of `kind`
+    pub fn show_kind(&mut self, kind: PagerKind, title: impl Into<String>, content: &str) {
+        self.kind = kind;
+        self.title = title.into();
+        self.lines = expand_tabs(content);
+        self.scroll_offset = 0;
+        self.search_state.deactivate();
+        self.visible = true;
+        self.rebuild_rows();
+    }
+
+    /// Replace the content in place, keeping scroll position and search
+    pub fn refresh(&mut self, content: &str) {
+        let lines = expand_tabs(content);
+        if lines != self.lines {
+            self.lines = lines;
+            self.rebuild_rows();
+        }
+    }
+
+    pub fn kind(&self) -> PagerKind {
+        self.kind
+    }
+
+    /// The shown text, one source line per line
+    pub fn content(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.kind = PagerKind::Text;
+        self.search_state.deactivate();
+        self.lines.clear();
+        self.row_starts = vec![0];
+        self.scroll_offset = 0;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Set the content area; re-wraps when the width changes
+    pub fn set_inner_area(&mut self, area: Rect) {
+        let bar_height = if self.search_state.is_active() { SearchBar::height() } else { 0 };
+        self.visible_height = area.height.saturating_sub(bar_height).max(1);
+        if area.width != self.width {
+            self.width = area.width.max(1);
+            self.rebuild_rows();
+        }
+        self.clamp_scroll();
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap.toggle();
+        self.rebuild_rows();
+    }
+
+    /// Recount display rows the way `LineWrap::apply` will draw them
+    fn rebuild_rows(&mut self) {
+        let mut row = 0;
+        self.row_starts = Vec::with_capacity(self.lines.len() + 1);
+        for line in &self.lines {
+            self.row_starts.push(row);
+            row += if self.wrap.is_wrap() {
+                Paragraph::new(line.as_str()).wrap(Wrap { trim: false }).line_count(self.width).max(1)
+            } else {
+                1
+            };
+        }
+        self.row_starts.push(row);
+        if self.search_state.is_active() {
+            self.update_search();
+        }
+        self.clamp_scroll();
+    }
+
+    fn total_rows(&self) -> usize {
+        self.row_starts.last().copied().unwrap_or(0)
+    }
+
+    fn max_scroll(&self) -> u16 {
+        scroll::max_offset(self.total_rows(), self.visible_height)
+    }
+
+    fn clamp_scroll(&mut self) {
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
+    }
+
+    pub fn scroll_up(&mut self, n: u16) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(n);
+    }
+
+    pub fn scroll_down(&mut self, n: u16) {
+        self.scroll_offset = self.scroll_offset.saturating_add(n).min(self.max_scroll());
+    }
+
+    pub fn scroll_page_up(&mut self) {
+        self.scroll_up(scroll::page_step(self.visible_height));
+    }
+
+    pub fn scroll_page_down(&mut self) {
+        self.scroll_down(scroll::page_step(self.visible_height));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = self.max_scroll();
+    }
+
+    pub fn scroll_left(&mut self, n: u16) {
+        self.wrap.scroll_left(n);
+    }
+
+    pub fn scroll_right(&mut self, n: u16) {
+        let widest = self.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        self.wrap.scroll_right(n, widest);
+    }
+
+    /// Percentage of content shown up to the bottom of the viewport
+    pub fn percent(&self) -> u16 {
+        let total = self.total_rows();
+        if total <= self.visible_height as usize {
+            return 100;
+        }
+        let bottom = (self.scroll_offset as usize + self.visible_height as usize).min(total);
+        (bottom * 100 / total) as u16
+    }
+
+    pub fn start_search(&mut self) {
+        self.search_state.activate();
+    }
+
+    pub fn close_search(&mut self) {
+        self.search_state.deactivate();
+    }
+
+    pub fn update_search(&mut self) {
+        self.search_state
+            .search_in_lines(self.lines.iter().enumerate().map(|(i, l)| (i, l.as_str())));
+    }
+
+    pub fn search_next(&mut self) {
+        self.search_state.next_match();
+        self.scroll_to_current_match();
+    }
+
+    pub fn search_prev(&mut self) {
+        self.search_state.prev_match();
+        self.scroll_to_current_match();
+    }
+
+    fn scroll_to_current_match(&mut self) {
+        let row_starts = &self.row_starts;
+        self.scroll_offset = self
+            .search_state
+            .scroll_offset_for_current_match_by(self.scroll_offset, self.visible_height, |line| row_starts[line])
+            .min(self.max_scroll());
+        self.wrap.reveal_match(&self.search_state, self.width, 0);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if self.search_state.is_active() {
+            match self.search_state.handle_key(key) {
+                SearchAction::Close => self.close_search(),
+                SearchAction::NavigateToMatch => self.scroll_to_current_match(),
+                SearchAction::RefreshSearch => {
+                    self.update_search();
+                    self.scroll_to_current_match();
+                }
+                SearchAction::None => {}
+            }
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Action::PagerClose),
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_down(1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_up(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_page_down(),
+            KeyCode::PageUp => self.scroll_page_up(),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_down(scroll::half_page_step(self.visible_height))
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_up(scroll::half_page_step(self.visible_height))
+            }
+            KeyCode::Char('g') | KeyCode::Home => self.scroll_to_top(),
+            KeyCode::Char('G') | KeyCode::End => self.scroll_to_bottom(),
+            KeyCode::Char('w') => self.toggle_wrap(),
+            KeyCode::Char('h') | KeyCode::Left => self.scroll_left(HORIZONTAL_SCROLL_STEP),
+            KeyCode::Char('l') | KeyCode::Right => self.scroll_right(HORIZONTAL_SCROLL_STEP),
+            KeyCode::Char('y') => return Some(Action::PagerCopy),
+            KeyCode::Char('/') => self.start_search(),
+            KeyCode::Char('n') => self.search_next(),
+            KeyCode::Char('N') => self.search_prev(),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.scroll_up(self.scroll_lines),
+            MouseEventKind::ScrollDown => self.scroll_down(self.scroll_lines),
+            _ => {}
+        }
+        None
+    }
+
+    fn render_themed(&self, frame: &mut Frame, area: Rect, focused: bool, theme: &Theme) {
+        let (content_area, bar_area) = if self.search_state.is_active() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(SearchBar::height())])
+                .split(area);
+            (chunks[0], Some(chunks[1]))
+        } else {
+            (area, None)
+        };
+
+        let wrap_indicator = if self.wrap.is_wrap() { "wrap" } else { "nowrap" };
+        let title = format!(
+            " {} ({} lines) {} {}% ",
+            self.title,
+            self.lines.len(),
+            wrap_indicator,
+            self.percent()
+        );
+
+        let block = Block::default()
+            .title(title)
+            .title_style(theme.title_style(focused))
+            .title_bottom(Line::from(Span::styled(
+                " j/k scroll  h/l pan  g/G top/bottom  / search  n/N next/prev  w wrap  y copy  q close ",
+                Style::default().fg(theme.colors.muted.to_color()),
+            )))
+            .borders(Borders::ALL)
+            .border_style(theme.border_style(focused));
+
+        let match_style = Style::default()
+            .fg(Color::Black)
+            .bg(theme.colors.warning.to_color())
+            .add_modifier(Modifier::BOLD);
+        let current_match_style = Style::default()
+            .fg(Color::Black)
+            .bg(theme.colors.success.to_color())
+            .add_modifier(Modifier::BOLD);
+        let normal_style = Style::default().fg(theme.colors.foreground.to_color());
+        let highlight = self.search_state.is_active() && !self.search_state.query().is_empty();
+
+        // Hand over only the source lines that reach the viewport, starting with
+        // the one holding the top row
+        let top = self.scroll_offset as usize;
+        let bottom = top + self.visible_height as usize;
+        let first = self.row_starts.partition_point(|&start| start <= top).saturating_sub(1);
+        let skip_rows = (top - self.row_starts.get(first).copied().unwrap_or(0)).min(u16::MAX as usize) as u16;
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take_while(|(idx, _)| self.row_starts[*idx] < bottom)
+            .map(|(idx, row)| {
+                if highlight {
+                    Line::from(highlight_matches_in_line(
+                        row,
+                        idx,
+                        self.search_state.matches(),
+                        self.search_state.current_match_index(),
+                        normal_style,
+                        match_style,
+                        current_match_style,
+                    ))
+                } else {
+                    Line::from(Span::styled(row.clone(), normal_style))
+                }
+            })
+            .collect();
+
+        let paragraph = self
+            .wrap
+            .apply(Paragraph::new(lines).block(block))
+            .scroll((skip_rows, self.wrap.h_scroll()));
+        frame.render_widget(paragraph, content_area);
+
+        if let Some(rect) = bar_area {
+            SearchBar::new(&self.search_state, theme).render(frame, rect);
+        }
+    }
+}
+
+/// Source lines with tabs expanded
+fn expand_tabs(content: &str) -> Vec<String> {
+    content.lines().map(|l| l.replace('\t', &" ".repeat(TAB_WIDTH))).collect()
+}
+
+impl Default for Pager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for Pager {
+    fn handle_event(&mut self, event: &Event) -> Option<Action> {
+        if !self.visible {
+            return None;
+        }
+        match event {
+            Event::Key(key) => self.handle_key(*key),
+            Event::Mouse(mouse) => self.handle_mouse(*mouse),
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, action: &Action) {
+        match action {
+            Action::PagerShow { title, content } => self.show(title.clone(), content),
+            Action::PagerClose => self.hide(),
+            _ => {}
+        }
+    }
+
+    fn render(&self, frame: &mut Frame, area: Rect, focused: bool, theme: &Theme) {
+        if self.visible {
+            self.render_themed(frame, area, focused, theme);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn pager_with_lines(n: usize) -> Pager {
+        let content: Vec<String> = (0..n).map(|i| format!("line {}", i)).collect();
+        let mut pager = Pager::new();
+        pager.show("test", &content.join("\n"));
+        pager.set_inner_area(Rect::new(0, 0, 80, 10));
+        pager
+    }
+
+    #[test]
+    fn test_show_and_hide() {
+        let mut pager = pager_with_lines(5);
+        assert!(pager.is_visible());
+        assert_eq!(pager.lines.len(), 5);
+        assert_eq!(pager.title, "test");
+
+        pager.update(&Action::PagerClose);
+        assert!(!pager.is_visible());
+        assert_eq!(pager.lines.len(), 0);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped() {
+        let mut pager = pager_with_lines(30);
+        pager.scroll_down(100);
+        assert_eq!(pager.scroll_offset, 20);
+        pager.scroll_up(100);
+        assert_eq!(pager.scroll_offset, 0);
+
+        pager.handle_event(&key(KeyCode::Char('G')));
+        assert_eq!(pager.scroll_offset, 20);
+        pager.handle_event(&key(KeyCode::Char('g')));
+        assert_eq!(pager.scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_percent_indicator() {
+        let mut pager = pager_with_lines(40);
+        assert_eq!(pager.percent(), 25);
+        pager.scroll_to_bottom();
+        assert_eq!(pager.percent(), 100);
+
+        let short = pager_with_lines(3);
+        assert_eq!(short.percent(), 100);
+    }
+
+    #[test]
+    fn test_wrap_toggle() {
+        let mut pager = Pager::new();
+        pager.show("wrap", &"x".repeat(25));
+        pager.set_inner_area(Rect::new(0, 0, 10, 5));
+        assert!(pager.wrap.is_wrap());
+        assert_eq!(pager.total_rows(), 3);
+
+        pager.handle_event(&key(KeyCode::Char('w')));
+        assert!(!pager.wrap.is_wrap());
+        assert_eq!(pager.total_rows(), 1);
+
+        // Unwrapped, long lines pan sideways up to their last column
+        pager.handle_event(&key(KeyCode::Char('l')));
+        assert_eq!(pager.wrap.h_scroll(), 8);
+        pager.scroll_right(100);
+        assert_eq!(pager.wrap.h_scroll(), 24);
+        pager.handle_event(&key(KeyCode::Char('h')));
+        assert_eq!(pager.wrap.h_scroll(), 16);
+    }
+
+    #[test]
+    fn test_search_scrolls_to_wrapped_row() {
+        let mut content: Vec<String> = (0..20).map(|_| "x".repeat(30)).collect();
+        content.push("needle".to_string());
+        let mut pager = Pager::new();
+        pager.show("wrapped", &content.join("\n"));
+        pager.set_inner_area(Rect::new(0, 0, 10, 5));
+        assert_eq!(pager.total_rows(), 61);
+
+        pager.handle_event(&key(KeyCode::Char('/')));
+        for c in "needle".chars() {
+            pager.handle_event(&key(KeyCode::Char(c)));
+        }
+        // The match sits on row 60, past the 20th source line
+        assert!(pager.scroll_offset <= 60 && pager.scroll_offset + 5 > 60);
+    }
+
+    #[test]
+    fn test_search_scrolls_to_match() {
+        let mut pager = pager_with_lines(100);
+        pager.handle_event(&key(KeyCode::Char('/')));
+        assert!(pager.search_state.is_active());
+
+        for c in "line 75".chars() {
+            pager.handle_event(&key(KeyCode::Char(c)));
+        }
+        assert_eq!(pager.search_state.match_count(), 1);
+        assert!(pager.scroll_offset <= 75);
+        assert!(pager.scroll_offset + 10 > 75);
+
+        // Esc closes the search first, then the pager
+        assert!(pager.handle_event(&key(KeyCode::Esc)).is_none());
+        assert!(!pager.search_state.is_active());
+        assert!(matches!(
+            pager.handle_event(&key(KeyCode::Esc)),
+            Some(Action::PagerClose)
+        ));
+    }
+
+    #[test]
+    fn test_refresh_keeps_kind_and_scroll() {
+        let mut pager = Pager::new();
+        let content: Vec<String> = (0..100).map(|i| format!("line {}", i)).collect();
+        pager.show_kind(PagerKind::SystemPrompt, "System Prompt", &content.join("\n"));
+        pager.set_inner_area(Rect::new(0, 0, 80, 10));
+        pager.handle_event(&key(KeyCode::PageDown));
+        let offset = pager.scroll_offset;
+        assert!(offset > 0);
+
+        pager.refresh(&format!("{}\nline 100", content.join("\n")));
+        assert_eq!(pager.kind(), PagerKind::SystemPrompt);
+        assert_eq!(pager.scroll_offset, offset);
+        assert_eq!(pager.lines.len(), 101);
+
+        pager.show("Logs", "x");
+        assert_eq!(pager.kind(), PagerKind::Text);
+    }
+
+    #[test]
+    fn test_wheel_and_half_page_steps() {
+        let mut pager = pager_with_lines(100);
+        pager.set_scroll_lines(7);
+        let wheel = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        pager.handle_event(&wheel);
+        assert_eq!(pager.scroll_offset, 7);
+
+        // A one-row viewport still moves on Ctrl+d
+        pager.set_inner_area(Rect::new(0, 0, 80, 1));
+        pager.handle_event(&Event::Key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL)));
+        assert_eq!(pager.scroll_offset, 8);
+    }
+
+    #[test]
+    fn test_tabs_expanded() {
+        let mut pager = Pager::new();
+        pager.show("tabs", "a\tb");
+        assert_eq!(pager.lines[0], "a    b");
+    }
+
+    #[test]
+    fn test_copy_key_yields_full_content() {
+        let mut pager = pager_with_lines(30);
+        assert!(pager.content().ends_with("line 29"));
+        assert!(matches!(pager.handle_event(&key(KeyCode::Char('y'))), Some(Action::PagerCopy)));
+    }
+
+    #[test]
+    fn test_hidden_pager_ignores_events() {
+        let mut pager = Pager::new();
+        assert!(pager.handle_event(&key(KeyCode::Esc)).is_none());
+    }
+}
@@ -1,6 +1,8 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use serde::{Deserialize, Serialize};
 
+use crate::action::OverlayKind;
+
 const MIN_PANE_PERCENT: u16 = 10;
 const MAX_PANE_PERCENT: u16 = 90;
 const RESIZE_STEP: u16 = 2;
@@ -25,6 +27,9 @@ pub struct PaneLayout {
     pub main_split_percent: u16,
     pub right_split_percent: u16,
     pub left_split_percent: u16,
+    /// Terminal/conversation take the full width; process monitor and menu
+    /// only appear as an overlay while focused
+    pub compact: bool,
 }
 
 impl Default for PaneLayout {
@@ -33,6 +38,7 @@ impl Default for PaneLayout {
             main_split_percent: 67,
             right_split_percent: 50,
             left_split_percent: 60,
+            compact: false,
         }
     }
 }
@@ -99,11 +105,26 @@ impl PaneLayout {
         self.left_split_percent = percent.clamp(MIN_PANE_PERCENT, MAX_PANE_PERCENT);
     }
     
+    /// Reset split sizes; compact mode is a separate toggle and stays as is
     pub fn reset_to_defaults(&mut self) {
-        *self = Self::default();
+        *self = Self { compact: self.compact, ..Self::default() };
+    }
+
+    pub fn toggle_compact(&mut self) {
+        self.compact = !self.compact;
+    }
+
+    /// Where the focused process monitor or menu shows in compact mode
+    pub fn compact_overlay_area(&self, area: Rect) -> Rect {
+        // u32: `width * 40` overflows u16 on terminals wider than 1638 columns
+        let width = ((area.width as u32 * 40 / 100) as u16).clamp(40, 80).min(area.width);
+        Rect::new(area.right() - width, area.y, width, area.height)
     }
     
     pub fn main_constraints(&self) -> [Constraint; 2] {
+        if self.compact {
+            return [Constraint::Percentage(100), Constraint::Length(0)];
+        }
         [
             Constraint::Percentage(self.main_split_percent),
             Constraint::Percentage(100 - self.main_split_percent),
@@ -177,6 +198,15 @@ impl PaneLayout {
     }
     
     pub fn hit_test_border(&self, x: u16, y: u16, area: Rect, show_conversation: bool) -> Option<ResizableBorder> {
+        if self.compact {
+            // Only the terminal/conversation split is on screen
+            let left_border = self.calculate_border_position(area, ResizableBorder::LeftHorizontal, show_conversation);
+            let on_left = show_conversation
+                && y >= left_border.y.saturating_sub(1) && y <= left_border.y + BORDER_HIT_WIDTH
+                && x >= left_border.x && x < left_border.x + left_border.width;
+            return on_left.then_some(ResizableBorder::LeftHorizontal);
+        }
+
         let main_border = self.calculate_border_position(area, ResizableBorder::MainVertical, show_conversation);
         if x >= main_border.x.saturating_sub(1) && x <= main_border.x + BORDER_HIT_WIDTH 
             && y >= main_border.y && y < main_border.y + main_border.height {
@@ -248,16 +278,28 @@ pub enum DragState {
     #[default]
     None,
     Dragging(ResizableBorder),
+    DraggingOverlay(OverlayKind),
 }
 
 impl DragState {
     pub fn is_dragging(&self) -> bool {
-        matches!(self, DragState::Dragging(_))
+        !matches!(self, DragState::None)
     }
     
     pub fn start(&mut self, border: ResizableBorder) {
         *self = DragState::Dragging(border);
     }
+
+    pub fn start_overlay(&mut self, kind: OverlayKind) {
+        *self = DragState::DraggingOverlay(kind);
+    }
+
+    pub fn overlay(&self) -> Option<OverlayKind> {
+        match self {
+            DragState::DraggingOverlay(kind) => Some(*kind),
+            _ => None,
+        }
+    }
     
     pub fn stop(&mut self) {
         *self = DragState::None;
@@ -266,7 +308,7 @@ impl DragState {
     pub fn border(&self) -> Option<ResizableBorder> {
         match self {
             DragState::Dragging(b) => Some(*b),
-            DragState::None => None,
+            _ => None,
         }
     }
 }
@@ -386,4 +428,37 @@ mod tests {
         layout.handle_mouse_drag(75, 15, area, ResizableBorder::RightHorizontal, false);
         assert!(layout.right_split_percent >= MIN_PANE_PERCENT && layout.right_split_percent <= MAX_PANE_PERCENT);
     }
+
+    #[test]
+    fn test_compact_gives_left_full_width() {
+        let mut layout = PaneLayout::new();
+        layout.main_split_percent = 55;
+        layout.toggle_compact();
+        let area = Rect::new(0, 0, 100, 40);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(layout.main_constraints())
+            .split(area);
+        assert_eq!(chunks[0].width, 100);
+        assert_eq!(chunks[1].width, 0);
+
+        // Only the terminal/conversation border can be dragged
+        assert_eq!(layout.hit_test_border(99, 10, area, true), None);
+        let left = layout.calculate_border_position(area, ResizableBorder::LeftHorizontal, true);
+        assert_eq!(layout.hit_test_border(10, left.y, area, true), Some(ResizableBorder::LeftHorizontal));
+
+        // The split comes back when compact mode is left; a reset keeps compact on
+        layout.toggle_compact();
+        assert_eq!(layout.main_constraints()[0], Constraint::Percentage(55));
+        layout.toggle_compact();
+        layout.reset_to_defaults();
+        assert!(layout.compact);
+    }
+
+    #[test]
+    fn test_compact_overlay_area_on_very_wide_terminal() {
+        let layout = PaneLayout::new();
+        let area = Rect::new(0, 1, 2000, 40);
+        assert_eq!(layout.compact_overlay_area(area), Rect::new(1920, 1, 80, 40));
+    }
 }
@@ -0,0 +1,104 @@
+//! Plan panel - sidebar next to the conversation showing the agent's plan
+//!
+//! Steps come from the update_plan tool and are stored on the thread, so the
+//! panel follows thread switches and restores with saved threads.
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::agent::{Plan, PlanStatus};
+use crate::config::Theme;
+
+/// Sidebar width in columns
+pub const PLAN_PANEL_WIDTH: u16 = 34;
+
+pub struct PlanPanel {
+    visible: bool,
+}
+
+impl PlanPanel {
+    pub fn new() -> Self {
+        Self { visible: true }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Whether to reserve space for `plan` in an area `width` columns wide
+    pub fn shown_for(&self, plan: &Plan, width: u16) -> bool {
+        self.visible && !plan.is_empty() && width >= PLAN_PANEL_WIDTH * 2
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, plan: &Plan, theme: &Theme) {
+        let block = Block::default()
+            .title(format!(" Plan {}/{} ", plan.completed(), plan.items().len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.colors.muted.to_color()));
+
+        let lines: Vec<Line> = plan.items().iter().map(|item| {
+            let (marker, style) = match item.status {
+                PlanStatus::Completed => (
+                    "✓",
+                    Style::default().fg(theme.colors.muted.to_color()).add_modifier(Modifier::CROSSED_OUT),
+                ),
+                PlanStatus::InProgress => (
+                    "▶",
+                    Style::default().fg(theme.colors.primary.to_color()).add_modifier(Modifier::BOLD),
+                ),
+                PlanStatus::Pending => ("○", Style::default().fg(theme.colors.foreground.to_color())),
+            };
+            Line::from(vec![
+                Span::styled(format!("{} ", marker), style.remove_modifier(Modifier::CROSSED_OUT)),
+                Span::styled(item.content.clone(), style),
+            ])
+        }).collect();
+
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+}
+
+impl Default for PlanPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_renders_steps_with_status() {
+        let plan = Plan::from_tool_input(&serde_json::json!({"items": [
+            {"content": "Read code", "status": "completed"},
+            {"content": "Patch it", "status": "in_progress"},
+        ]}))
+        .unwrap();
+        let panel = PlanPanel::new();
+        assert!(panel.shown_for(&plan, 100));
+        assert!(!panel.shown_for(&Plan::default(), 100));
+
+        let mut terminal = Terminal::new(TestBackend::new(PLAN_PANEL_WIDTH, 5)).unwrap();
+        terminal.draw(|f| panel.render(f, f.area(), &plan, &Theme::default())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..PLAN_PANEL_WIDTH).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).contains("Plan 1/2"));
+        assert!(row(1).contains("✓ Read code"));
+        assert!(buffer[(4, 1)].modifier.contains(Modifier::CROSSED_OUT));
+        assert!(row(2).contains("▶ Patch it"));
+    }
+}
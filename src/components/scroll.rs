@@ -0,0 +1,371 @@
+// Scroll step math, follow mode and line wrapping shared by the terminal, conversation, log and stream viewers and the pager
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::components::search::SearchState;
+use crate::config::Theme;
+
+/// Lines per mouse-wheel notch unless `ui.scroll_lines` says otherwise
+pub const DEFAULT_SCROLL_LINES: u16 = 3;
+
+/// Lines of wheel scrolling up absorbed before following pauses (`ui.scroll_lock_threshold`)
+pub const DEFAULT_SCROLL_LOCK_THRESHOLD: u16 = 3;
+
+/// Wheel notches further apart than this don't add up toward the lock threshold
+const NUDGE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Lines of the previous page kept on screen after paging, for context
+pub const PAGE_OVERLAP: u16 = 2;
+
+/// Largest scroll offset over `total` lines that still fills a viewport `height` rows tall
+pub fn max_offset(total: usize, height: u16) -> u16 {
+    total.saturating_sub(height as usize).min(u16::MAX as usize) as u16
+}
+
+/// Lines moved by PageUp/PageDown in a viewport `height` rows tall
+pub fn page_step(height: u16) -> u16 {
+    height.saturating_sub(PAGE_OVERLAP).max(1)
+}
+
+/// Lines moved by a half-page scroll (Ctrl+u / Ctrl+d)
+pub fn half_page_step(height: u16) -> u16 {
+    (height / 2).max(1)
+}
+
+/// Whether a viewer tracks its newest content ('f' toggles).
+/// While paused the view stays put and counts the lines that arrived below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Follow {
+    following: bool,
+    unseen: usize,
+    lock_threshold: usize,
+    /// Wheel lines scrolled up while following, and when the last notch came
+    nudged: usize,
+    last_nudge: Option<Instant>,
+    /// Where the unseen hint was last drawn, for clicks
+    hint_area: Cell<Option<Rect>>,
+}
+
+impl Default for Follow {
+    fn default() -> Self {
+        Self {
+            following: true,
+            unseen: 0,
+            lock_threshold: usize::from(DEFAULT_SCROLL_LOCK_THRESHOLD),
+            nudged: 0,
+            last_nudge: None,
+            hint_area: Cell::new(None),
+        }
+    }
+}
+
+impl Follow {
+    pub fn is_following(&self) -> bool {
+        self.following
+    }
+
+    pub fn set(&mut self, following: bool) {
+        self.following = following;
+        self.nudged = 0;
+        if following {
+            self.unseen = 0;
+        }
+    }
+
+    pub fn set_lock_threshold(&mut self, lines: u16) {
+        self.lock_threshold = usize::from(lines);
+    }
+
+    /// A mouse-wheel scroll up of `lines`. While following, notches in quick
+    /// succession that add up to no more than the lock threshold are absorbed
+    /// (false) so a stray nudge doesn't pause; otherwise the scroll goes ahead.
+    pub fn wheel_up(&mut self, lines: u16) -> bool {
+        self.wheel_up_at(lines, Instant::now())
+    }
+
+    fn wheel_up_at(&mut self, lines: u16, now: Instant) -> bool {
+        if !self.following {
+            return true;
+        }
+        if self.last_nudge.is_some_and(|last| now.duration_since(last) > NUDGE_WINDOW) {
+            self.nudged = 0;
+        }
+        self.nudged += usize::from(lines);
+        self.last_nudge = Some(now);
+        self.nudged > self.lock_threshold
+    }
+
+    /// Record `lines` of new content. Returns true if the view should jump to the bottom.
+    pub fn content_added(&mut self, lines: usize) -> bool {
+        if !self.following {
+            self.unseen += lines;
+        }
+        self.following
+    }
+
+    /// The user has scrolled down to the newest content
+    pub fn reached_bottom(&mut self) {
+        self.unseen = 0;
+    }
+
+    /// Title indicator: "[FOLLOWING]" or "[PAUSED]"
+    pub fn indicator(&self) -> &'static str {
+        if self.following {
+            "[FOLLOWING]"
+        } else {
+            "[PAUSED]"
+        }
+    }
+
+    /// "N new lines below" while paused with unseen content
+    pub fn unseen_hint(&self) -> Option<String> {
+        match self.unseen {
+            0 => None,
+            1 => Some("1 new line below".to_string()),
+            n => Some(format!("{} new lines below", n)),
+        }
+    }
+
+    /// Draw the unseen hint on the bottom border of a viewer's `area`.
+    /// Clicking it (see `hint_hit`) jumps to the bottom and resumes following.
+    pub fn render_hint(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.hint_area.set(None);
+        let Some(hint) = self.unseen_hint() else {
+            return;
+        };
+        if area.height < 2 || area.width < 4 {
+            return;
+        }
+        let text = format!(" ↓ {} ", hint);
+        let width = (text.chars().count() as u16).min(area.width - 2);
+        let pill = Rect::new(area.x + area.width - 1 - width, area.y + area.height - 1, width, 1);
+        let style = Style::default()
+            .fg(theme.colors.warning.to_color())
+            .add_modifier(Modifier::BOLD);
+        let line = Line::from(Span::styled(text, style));
+        frame.render_widget(Paragraph::new(line).alignment(Alignment::Right), pill);
+        self.hint_area.set(Some(pill));
+    }
+
+    /// Whether (`column`, `row`) is on the unseen hint as last drawn
+    pub fn hint_hit(&self, column: u16, row: u16) -> bool {
+        self.unseen > 0 && self.hint_area.get().is_some_and(|pill| pill.contains((column, row).into()))
+    }
+}
+
+/// Viewers that follow new output expose their `Follow` here, so the app
+/// applies the wheel lock threshold and routes wheel nudges and hint clicks
+/// the same way for each of them
+pub trait Following {
+    fn follow(&self) -> &Follow;
+    fn follow_mut(&mut self) -> &mut Follow;
+}
+
+/// Line wrapping for the log and stream viewers and the pager ('w' toggles). While
+/// unwrapped, long lines are clipped and scroll horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineWrap {
+    wrap: bool,
+    /// Horizontal scroll in columns (unwrapped only)
+    h_scroll: u16,
+}
+
+impl Default for LineWrap {
+    fn default() -> Self {
+        Self { wrap: true, h_scroll: 0 }
+    }
+}
+
+impl LineWrap {
+    pub fn is_wrap(&self) -> bool {
+        self.wrap
+    }
+
+    /// Switching either way starts again from the first column
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+        self.h_scroll = 0;
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_wrap(!self.wrap);
+    }
+
+    pub fn h_scroll(&self) -> u16 {
+        self.h_scroll
+    }
+
+    pub fn scroll_left(&mut self, n: u16) {
+        self.h_scroll = self.h_scroll.saturating_sub(n);
+    }
+
+    /// Scroll right, stopping once the last column of the `widest` line is reached.
+    /// No-op while wrapping.
+    pub fn scroll_right(&mut self, n: u16, widest: usize) {
+        if self.wrap {
+            return;
+        }
+        let max = widest.saturating_sub(1).min(u16::MAX as usize) as u16;
+        self.h_scroll = self.h_scroll.saturating_add(n).min(max);
+    }
+
+    /// Bring the current search match into view in a viewport `width` columns
+    /// wide, where its line starts `prefix` columns in. No-op while wrapping.
+    pub fn reveal_match(&mut self, search: &SearchState, width: u16, prefix: usize) {
+        if !self.wrap {
+            self.h_scroll = search.h_scroll_for_current_match(self.h_scroll, width, prefix);
+        }
+    }
+
+    /// Title indicator: " ↔" while unwrapped
+    pub fn indicator(&self) -> &'static str {
+        if self.wrap {
+            ""
+        } else {
+            " ↔"
+        }
+    }
+
+    /// Wrap `paragraph`, or shift it by the horizontal scroll
+    pub fn apply<'a>(&self, paragraph: Paragraph<'a>) -> Paragraph<'a> {
+        if self.wrap {
+            paragraph.wrap(Wrap { trim: false })
+        } else {
+            paragraph.scroll((0, self.h_scroll))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_follow_counts_unseen_only_while_paused() {
+        let mut follow = Follow::default();
+        assert!(follow.content_added(5));
+        assert_eq!(follow.unseen_hint(), None);
+        assert_eq!(follow.indicator(), "[FOLLOWING]");
+
+        follow.set(false);
+        assert!(!follow.content_added(1));
+        assert_eq!(follow.unseen_hint().as_deref(), Some("1 new line below"));
+        follow.content_added(2);
+        assert_eq!(follow.unseen_hint().as_deref(), Some("3 new lines below"));
+        assert_eq!(follow.indicator(), "[PAUSED]");
+
+        follow.reached_bottom();
+        assert_eq!(follow.unseen_hint(), None);
+        assert!(!follow.is_following(), "reaching the bottom does not resume following");
+
+        follow.content_added(4);
+        follow.set(true);
+        assert_eq!(follow.unseen_hint(), None);
+    }
+
+    #[test]
+    fn test_wheel_nudges_within_threshold_keep_following() {
+        let mut follow = Follow::default();
+        let start = Instant::now();
+        assert!(!follow.wheel_up_at(3, start));
+        assert!(follow.is_following());
+        // A second notch right after exceeds the threshold
+        assert!(follow.wheel_up_at(3, start + Duration::from_millis(200)));
+
+        // Notches far apart don't add up
+        let mut follow = Follow::default();
+        assert!(!follow.wheel_up_at(3, start));
+        assert!(!follow.wheel_up_at(3, start + NUDGE_WINDOW * 2));
+
+        // Threshold 0: any wheel scroll goes ahead; paused viewers always scroll
+        follow.set_lock_threshold(0);
+        follow.set(true);
+        assert!(follow.wheel_up_at(1, start));
+        follow.set(false);
+        assert!(follow.wheel_up_at(1, start));
+    }
+
+    #[test]
+    fn test_hint_is_clickable_where_drawn() {
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut follow = Follow::default();
+        follow.set(false);
+        follow.content_added(2);
+        let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+        terminal.draw(|f| follow.render_hint(f, f.area(), &Theme::default())).unwrap();
+
+        // " ↓ 2 new lines below " sits right-aligned on the bottom border
+        assert!(follow.hint_hit(38, 9));
+        assert!(follow.hint_hit(18, 9));
+        assert!(!follow.hint_hit(10, 9));
+        assert!(!follow.hint_hit(38, 8));
+
+        follow.reached_bottom();
+        assert!(!follow.hint_hit(38, 9));
+    }
+
+    #[test]
+    fn test_line_wrap_horizontal_scroll() {
+        use crate::components::search::SearchMatch;
+
+        let mut wrap = LineWrap::default();
+        assert!(wrap.is_wrap());
+        assert_eq!(wrap.indicator(), "");
+
+        // Horizontal scroll is a no-op while wrapping
+        wrap.scroll_right(8, 100);
+        assert_eq!(wrap.h_scroll(), 0);
+
+        wrap.toggle();
+        assert_eq!(wrap.indicator(), " ↔");
+        wrap.scroll_right(8, 100);
+        assert_eq!(wrap.h_scroll(), 8);
+        wrap.scroll_left(20);
+        assert_eq!(wrap.h_scroll(), 0);
+
+        // Clamped to the widest line's last column
+        wrap.scroll_right(u16::MAX, 100);
+        assert_eq!(wrap.h_scroll(), 99);
+
+        // An off-screen match is brought into view
+        let mut search = SearchState::new();
+        search.set_matches(vec![SearchMatch::new(0, 200, 206)]);
+        wrap.scroll_left(u16::MAX);
+        wrap.reveal_match(&search, 40, 10);
+        assert!(wrap.h_scroll() <= 210);
+        assert!(wrap.h_scroll() + 40 >= 216);
+
+        // Switching back to wrapping resets the offset
+        wrap.toggle();
+        assert_eq!(wrap.h_scroll(), 0);
+        wrap.reveal_match(&search, 40, 0);
+        assert_eq!(wrap.h_scroll(), 0);
+    }
+
+    #[test]
+    fn test_max_offset() {
+        assert_eq!(max_offset(100, 20), 80);
+        assert_eq!(max_offset(5, 20), 0);
+        assert_eq!(max_offset(usize::MAX, 0), u16::MAX);
+    }
+
+    #[test]
+    fn test_page_steps() {
+        assert_eq!(page_step(24), 22);
+        assert_eq!(half_page_step(24), 12);
+        // Tiny viewports still move
+        assert_eq!(page_step(1), 1);
+        assert_eq!(half_page_step(1), 1);
+        assert_eq!(page_step(0), 1);
+    }
+}
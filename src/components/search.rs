@@ -12,6 +12,9 @@ use regex::Regex;
 
 use crate::config::Theme;
 
+/// Columns moved per left/right key press in an unwrapped viewer
+pub const HORIZONTAL_SCROLL_STEP: u16 = 8;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SearchMatch {
     pub line_index: usize,
@@ -128,6 +131,50 @@ impl SearchState {
         }
     }
 
+    /// Scroll offset that brings the current match into view, shared by all
+    /// scrollable viewers so they jump to matches the same way
+    pub fn scroll_offset_for_current_match(&self, scroll_offset: u16, visible_height: u16) -> u16 {
+        self.scroll_offset_for_current_match_by(scroll_offset, visible_height, |line| line)
+    }
+
+    /// As `scroll_offset_for_current_match`, for views that scroll by display
+    /// row: `row_of` maps a line index to the first row it is drawn on
+    pub fn scroll_offset_for_current_match_by(
+        &self,
+        scroll_offset: u16,
+        visible_height: u16,
+        row_of: impl Fn(usize) -> usize,
+    ) -> u16 {
+        let Some(m) = self.current_match() else {
+            return scroll_offset;
+        };
+        let target_line = row_of(m.line_index).min(u16::MAX as usize) as u16;
+        if target_line < scroll_offset {
+            target_line.saturating_sub(2)
+        } else if target_line >= scroll_offset.saturating_add(visible_height) {
+            target_line.saturating_sub(visible_height / 2)
+        } else {
+            scroll_offset
+        }
+    }
+
+    /// Horizontal scroll that keeps the current match visible in an unwrapped
+    /// view `width` columns wide; `prefix` columns are drawn before the searched text
+    pub fn h_scroll_for_current_match(&self, h_scroll: u16, width: u16, prefix: usize) -> u16 {
+        let Some(m) = self.current_match() else {
+            return h_scroll;
+        };
+        let start = (prefix + m.start).min(u16::MAX as usize) as u16;
+        let end = (prefix + m.end).min(u16::MAX as usize) as u16;
+        if start < h_scroll {
+            start.saturating_sub(4)
+        } else if end > h_scroll.saturating_add(width) {
+            end.saturating_sub(width).saturating_add(4).min(start)
+        } else {
+            h_scroll
+        }
+    }
+
     pub fn search_in_lines<'a, I>(&mut self, lines: I)
     where
         I: Iterator<Item = (usize, &'a str)>,
@@ -679,6 +726,22 @@ mod tests {
         assert_eq!(state.matches()[3].line_index, 4);
     }
 
+    #[test]
+    fn test_h_scroll_for_current_match() {
+        let mut state = SearchState::new();
+        state.set_query("needle".to_string());
+        let line = format!("{}needle", " ".repeat(100));
+        state.search_in_lines(vec![(0, line.as_str())].into_iter());
+
+        // Off to the right: scroll so the match ends inside the view
+        let h = state.h_scroll_for_current_match(0, 40, 10);
+        assert!(h <= 110 && h + 40 >= 116);
+        // Already visible: unchanged
+        assert_eq!(state.h_scroll_for_current_match(90, 40, 10), 90);
+        // Off to the left: scroll back to it
+        assert_eq!(state.h_scroll_for_current_match(200, 40, 10), 106);
+    }
+
     #[test]
     fn test_search_case_sensitive() {
         let mut state = SearchState::new();
@@ -747,6 +810,21 @@ mod tests {
         assert_eq!(state.match_count(), 0);
     }
 
+    #[test]
+    fn test_scroll_offset_for_current_match() {
+        let mut state = SearchState::new();
+        // No match keeps the current offset
+        assert_eq!(state.scroll_offset_for_current_match(7, 10), 7);
+
+        state.set_matches(vec![SearchMatch::new(40, 0, 1)]);
+        // Below the viewport: center the match
+        assert_eq!(state.scroll_offset_for_current_match(0, 10), 35);
+        // Already visible: unchanged
+        assert_eq!(state.scroll_offset_for_current_match(35, 10), 35);
+        // Above the viewport: show a little context
+        assert_eq!(state.scroll_offset_for_current_match(60, 10), 38);
+    }
+
     #[test]
     fn test_search_toggle_case() {
         let mut state = SearchState::new();
@@ -2,9 +2,11 @@
 //!
 //! Provides UI for editing:
 //! - API Keys (per provider, masked input)
-//! - Provider selection
+//! - Provider selection and per-provider base URL overrides
 //! - Model selection  
-//! - Parameters (temperature, max_tokens)
+//! - Parameters (temperature, max_tokens, extended thinking)
+
+use std::sync::Arc;
 
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use ratatui::{
@@ -20,6 +22,9 @@ use crate::agent::ModelCatalog;
 use crate::components::Component;
 use crate::config::{KeyId, KeyStore, LLMConfig, Theme};
 
+/// Editing field suffix marking a base URL edit (field is "<provider>.base_url")
+const BASE_URL_FIELD_SUFFIX: &str = ".base_url";
+
 /// Section within the settings editor
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingsSection {
@@ -72,6 +77,14 @@ pub enum SettingsInputMode {
     },
 }
 
+/// Local providers that run without an API key
+const KEYLESS_PROVIDERS: [&str; 1] = ["ollama"];
+
+/// Whether `provider` needs an API key before it can be used
+pub fn needs_api_key(provider: &str) -> bool {
+    !KEYLESS_PROVIDERS.contains(&provider)
+}
+
 /// Provider with key status
 #[derive(Debug, Clone)]
 pub struct ProviderKeyStatus {
@@ -117,8 +130,8 @@ pub struct SettingsEditor {
     config: LLMConfig,
     /// Key test status (TS-007)
     key_test_status: KeyTestStatus,
-    /// Model catalog for rich model info (TS-009)
-    model_catalog: ModelCatalog,
+    /// Model catalog for rich model info (TS-009), including models.toml entries
+    model_catalog: Arc<ModelCatalog>,
 }
 
 impl SettingsEditor {
@@ -142,10 +155,16 @@ impl SettingsEditor {
             available_models: Vec::new(),
             config: LLMConfig::default(),
             key_test_status: KeyTestStatus::Idle,
-            model_catalog: ModelCatalog::new(),
+            model_catalog: Arc::new(ModelCatalog::new()),
         }
     }
 
+    /// Use the app's catalog so the model list matches models.toml
+    pub fn with_model_catalog(mut self, catalog: Arc<ModelCatalog>) -> Self {
+        self.model_catalog = catalog;
+        self
+    }
+
     /// Set the LLM config to display/edit
     pub fn set_config(&mut self, config: LLMConfig) {
         self.config = config;
@@ -157,6 +176,11 @@ impl SettingsEditor {
         &self.config
     }
 
+    /// Update the displayed base URL override for a provider
+    pub fn set_provider_base_url(&mut self, provider: &str, base_url: Option<String>) {
+        self.config.set_base_url_for_provider(provider, base_url);
+    }
+
     /// Set provider key statuses
     #[allow(dead_code)]
     pub fn set_provider_keys(&mut self, keys: Vec<ProviderKeyStatus>) {
@@ -275,57 +299,11 @@ impl SettingsEditor {
     }
 
     fn refresh_models_for_provider(&mut self) {
-        // Placeholder - will be populated by TS-009
-        self.available_models = match self.config.defaults.provider.as_str() {
-            "anthropic" => vec![
-                "claude-sonnet-4-20250514".to_string(),
-                "claude-3-5-sonnet-20241022".to_string(),
-                "claude-3-opus-20240229".to_string(),
-                "claude-3-haiku-20240307".to_string(),
-            ],
-            "openai" => vec![
-                "gpt-5.4-2026-03-05".to_string(),
-                "gpt-5.4-pro-2026-03-05".to_string(),
-                "gpt-5.2-2025-12-11".to_string(),
-                "gpt-5.2-pro-2025-12-11".to_string(),
-                "gpt-5-mini-2025-08-07".to_string(),
-                "gpt-4o".to_string(),
-                "gpt-4o-mini".to_string(),
-                "gpt-4-turbo".to_string(),
-                "o1".to_string(),
-                "o1-mini".to_string(),
-                "o3-mini".to_string(),
-            ],
-            "gemini" => vec![
-                "gemini-2.5-flash".to_string(),
-                "gemini-2.5-pro".to_string(),
-                "gemini-2.0-flash".to_string(),
-                "gemini-1.5-pro".to_string(),
-                "gemini-1.5-flash".to_string(),
-            ],
-            "grok" => vec![
-                    "grok-4".to_string(),
-                    "grok-4-fast-reasoning".to_string(),
-                    "grok-4-fast-non-reasoning".to_string(),
-                    "grok-4-1-fast-reasoning".to_string(),
-                    "grok-4-1-fast-non-reasoning".to_string(),
-                    "grok-code-fast-1".to_string(),
-                    "grok-3".to_string(),
-                    "grok-3-mini".to_string(),
-                    "grok-2-1212".to_string(),
-                    "grok-2-vision-1212".to_string(),
-                ],
-            "groq" => vec![
-                "llama-3.3-70b-versatile".to_string(),
-                "llama-3.1-8b-instant".to_string(),
-                "mixtral-8x7b-32768".to_string(),
-            ],
-            "ollama" => vec![
-                "qwen3:8b".to_string(),
-                "qwen3:4b".to_string(),
-            ],
-            _ => Vec::new(),
-        };
+        self.available_models = self.model_catalog
+            .models_for_provider(&self.config.defaults.provider)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
     }
 
     /// Navigate to next section
@@ -369,7 +347,7 @@ impl SettingsEditor {
             SettingsSection::ApiKeys => self.available_providers.len(),
             SettingsSection::Provider => self.available_providers.len(),
             SettingsSection::Model => self.available_models.len(),
-            SettingsSection::Parameters => 2, // temperature, max_tokens
+            SettingsSection::Parameters => 4, // temperature, max_tokens, thinking, thinking budget
         }
     }
 
@@ -387,6 +365,21 @@ impl SettingsEditor {
         }
     }
 
+    /// Start editing the selected provider's base URL (Provider section)
+    pub fn start_base_url_edit(&mut self) {
+        if self.current_section() == SettingsSection::Provider {
+            if let Some(provider) = self.available_providers.get(self.selected_item) {
+                let current = self.config.providers.get(provider).and_then(|p| p.base_url.clone());
+                self.input_mode = SettingsInputMode::Editing {
+                    field: format!("{}{}", provider, BASE_URL_FIELD_SUFFIX),
+                    buffer: current.unwrap_or_default(),
+                    masked: false,
+                    show_plain: true,
+                };
+            }
+        }
+    }
+
     /// Toggle visibility of masked input (Ctrl+U)
     pub fn toggle_mask_visibility(&mut self) {
         if let SettingsInputMode::Editing { show_plain, .. } = &mut self.input_mode {
@@ -435,25 +428,17 @@ impl SettingsEditor {
         }
         // Provider-specific prefix checks
         match provider {
-            "anthropic" => {
-                if !key.starts_with("sk-ant-") {
-                    return Some("Expected prefix: sk-ant-");
-                }
+            "anthropic" if !key.starts_with("sk-ant-") => {
+                return Some("Expected prefix: sk-ant-");
             }
-            "openai" => {
-                if !key.starts_with("sk-") {
-                    return Some("Expected prefix: sk-");
-                }
+            "openai" if !key.starts_with("sk-") => {
+                return Some("Expected prefix: sk-");
             }
-            "groq" => {
-                if !key.starts_with("gsk_") {
-                    return Some("Expected prefix: gsk_");
-                }
+            "groq" if !key.starts_with("gsk_") => {
+                return Some("Expected prefix: gsk_");
             }
-            "grok" => {
-                if !key.starts_with("xai-") {
-                    return Some("Expected prefix: xai-");
-                }
+            "grok" if !key.starts_with("xai-") => {
+                return Some("Expected prefix: xai-");
             }
             _ => {}
         }
@@ -468,7 +453,13 @@ impl SettingsEditor {
     /// Confirm current edit
     pub fn confirm_edit(&mut self) -> Option<Action> {
         if let SettingsInputMode::Editing { field, buffer, .. } = &self.input_mode {
-            let action = if !buffer.is_empty() {
+            let action = if let Some(provider) = field.strip_suffix(BASE_URL_FIELD_SUFFIX) {
+                // An empty buffer clears the override
+                Some(Action::SettingsBaseUrlEntered {
+                    provider: provider.to_string(),
+                    url: buffer.trim().to_string(),
+                })
+            } else if !buffer.is_empty() {
                 // Return action to store key - TS-006 will wire this
                 Some(Action::SettingsKeyEntered {
                     provider: field.clone(),
@@ -536,6 +527,11 @@ impl SettingsEditor {
     const MAX_TOKENS_STEP: u32 = 256;
     const MAX_TOKENS_LARGE_STEP: u32 = 1024;
 
+    /// Thinking budget range constants
+    const THINKING_BUDGET_MIN: u32 = 1024;
+    const THINKING_BUDGET_MAX: u32 = 64_000;
+    const THINKING_BUDGET_STEP: u32 = 1024;
+
     /// Increase temperature by step
     pub fn increase_temperature(&mut self) -> Option<Action> {
         if self.current_section() == SettingsSection::Parameters && self.selected_item == 0 {
@@ -591,6 +587,37 @@ impl SettingsEditor {
         None
     }
 
+    /// Toggle extended thinking
+    pub fn toggle_thinking(&mut self) -> Option<Action> {
+        if self.current_section() == SettingsSection::Parameters && self.selected_item == 2 {
+            self.config.thinking.enabled = !self.config.thinking.enabled;
+            return Some(Action::SettingsThinkingToggled(self.config.thinking.enabled));
+        }
+        None
+    }
+
+    /// Increase the thinking budget by step
+    pub fn increase_thinking_budget(&mut self) -> Option<Action> {
+        if self.current_section() == SettingsSection::Parameters && self.selected_item == 3 {
+            self.config.thinking.budget_tokens = self.config.thinking.budget_tokens
+                .saturating_add(Self::THINKING_BUDGET_STEP)
+                .min(Self::THINKING_BUDGET_MAX);
+            return Some(Action::SettingsThinkingBudgetChanged(self.config.thinking.budget_tokens));
+        }
+        None
+    }
+
+    /// Decrease the thinking budget by step
+    pub fn decrease_thinking_budget(&mut self) -> Option<Action> {
+        if self.current_section() == SettingsSection::Parameters && self.selected_item == 3 {
+            self.config.thinking.budget_tokens = self.config.thinking.budget_tokens
+                .saturating_sub(Self::THINKING_BUDGET_STEP)
+                .max(Self::THINKING_BUDGET_MIN);
+            return Some(Action::SettingsThinkingBudgetChanged(self.config.thinking.budget_tokens));
+        }
+        None
+    }
+
     /// Adjust current parameter (left = decrease, right = increase)
     pub fn adjust_parameter(&mut self, increase: bool) -> Option<Action> {
         if self.current_section() != SettingsSection::Parameters {
@@ -611,6 +638,14 @@ impl SettingsEditor {
                     self.decrease_max_tokens()
                 }
             }
+            2 => self.toggle_thinking(),
+            3 => {
+                if increase {
+                    self.increase_thinking_budget()
+                } else {
+                    self.decrease_thinking_budget()
+                }
+            }
             _ => None,
         }
     }
@@ -632,6 +667,14 @@ impl SettingsEditor {
         (normalized * 100.0).round() as u8
     }
 
+    /// Get thinking budget as a percentage (0-100) for slider rendering
+    fn thinking_budget_percentage(&self) -> u8 {
+        let range = (Self::THINKING_BUDGET_MAX - Self::THINKING_BUDGET_MIN) as f64;
+        let value = self.config.thinking.budget_tokens.clamp(Self::THINKING_BUDGET_MIN, Self::THINKING_BUDGET_MAX);
+        let normalized = (value - Self::THINKING_BUDGET_MIN) as f64 / range;
+        (normalized * 100.0).round() as u8
+    }
+
     /// Render a slider bar (width chars, filled to percentage)
     fn render_slider_bar(percentage: u8, width: usize) -> String {
         let filled = (percentage as usize * width) / 100;
@@ -711,14 +754,17 @@ impl SettingsEditor {
             KeyCode::Enter | KeyCode::Char(' ') => {
                 match self.current_section() {
                     SettingsSection::ApiKeys => {
+                        if !self.selected_provider().is_some_and(needs_api_key) {
+                            return None;
+                        }
                         self.start_editing();
                         Some(Action::SettingsStartEdit)
                     }
                     SettingsSection::Provider => self.select_provider(),
                     SettingsSection::Model => self.select_model(),
                     SettingsSection::Parameters => {
-                        // Enter/Space on parameters does nothing (use left/right to adjust)
-                        None
+                        // Enter/Space toggles thinking; sliders use left/right to adjust
+                        self.toggle_thinking()
                     }
                 }
             }
@@ -751,6 +797,10 @@ impl SettingsEditor {
                     None
                 }
             }
+            KeyCode::Char('u') if self.current_section() == SettingsSection::Provider => {
+                self.start_base_url_edit();
+                Some(Action::SettingsStartEdit)
+            }
             KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 // Test key - TS-007 will implement
                 Some(Action::SettingsTestKey)
@@ -852,7 +902,8 @@ impl SettingsEditor {
             let is_selected = self.current_section() == SettingsSection::ApiKeys 
                 && self.selected_item == idx;
             
-            let has_key = self.provider_keys
+            let keyless = !needs_api_key(provider);
+            let has_key = keyless || self.provider_keys
                 .iter()
                 .find(|p| &p.name == provider)
                 .map(|p| p.has_key)
@@ -939,7 +990,11 @@ impl SettingsEditor {
                 Span::styled(format!("{:12} ", provider), name_style),
                 Span::styled(status_icon.to_string(), Style::default().fg(status_color)),
                 Span::styled(
-                    if has_key { " configured" } else { " not set" }.to_string(),
+                    match (keyless, has_key) {
+                        (true, _) => " no key needed",
+                        (false, true) => " configured",
+                        (false, false) => " not set",
+                    }.to_string(),
                     Style::default().fg(theme.colors.muted.to_color()),
                 ),
             ];
@@ -956,7 +1011,7 @@ impl SettingsEditor {
             }
 
             // Add action hints for selected item
-            if is_selected && test_status_for_provider.is_none() {
+            if is_selected && test_status_for_provider.is_none() && !keyless {
                 spans.push(Span::styled("  ", Style::default()));
                 spans.push(Span::styled("↵ edit", Style::default().fg(theme.colors.primary.to_color())));
                 if has_key {
@@ -1083,7 +1138,7 @@ impl SettingsEditor {
             let is_current = provider == &self.config.defaults.provider;
 
             // Check if key is configured for this provider
-            let has_key = self.provider_keys
+            let has_key = !needs_api_key(provider) || self.provider_keys
                 .iter()
                 .find(|p| &p.name == provider)
                 .map(|p| p.has_key)
@@ -1157,6 +1212,35 @@ impl SettingsEditor {
                     ),
                 ]));
 
+                // Base URL override (proxy/gateway), editable with 'u'
+                let editing_url = match &self.input_mode {
+                    SettingsInputMode::Editing { field, buffer, .. }
+                        if field.strip_suffix(BASE_URL_FIELD_SUFFIX) == Some(provider.as_str()) =>
+                    {
+                        Some(buffer.clone())
+                    }
+                    _ => None,
+                };
+                let url_line = if let Some(buffer) = editing_url {
+                    vec![
+                        Span::styled("        Base URL [", Style::default().fg(theme.colors.muted.to_color())),
+                        Span::styled(buffer, Style::default().fg(theme.colors.accent.to_color())),
+                        Span::styled("█", Style::default().fg(theme.colors.accent.to_color())),
+                        Span::styled("] ↵ apply, empty = default", Style::default().fg(theme.colors.muted.to_color())),
+                    ]
+                } else {
+                    let url = self.config.providers.get(provider).and_then(|p| p.base_url.clone());
+                    vec![
+                        Span::styled("        Base URL: ", Style::default().fg(theme.colors.muted.to_color())),
+                        match url {
+                            Some(url) => Span::styled(url, Style::default().fg(theme.colors.primary.to_color())),
+                            None => Span::styled("default", Style::default().fg(theme.colors.muted.to_color())),
+                        },
+                        Span::styled("  u edit", Style::default().fg(theme.colors.muted.to_color())),
+                    ]
+                };
+                lines.push(Line::from(url_line));
+
                 // Show warning if key not configured
                 if !has_key {
                     lines.push(Line::from(vec![
@@ -1418,6 +1502,98 @@ impl SettingsEditor {
             ]));
         }
 
+        // Spacing
+        lines.push(Line::default());
+
+        let selector_style = Style::default().fg(theme.colors.accent.to_color());
+        let label_style = |selected: bool| {
+            if selected {
+                Style::default()
+                    .fg(theme.colors.accent.to_color())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.colors.foreground.to_color())
+            }
+        };
+
+        // ===== Thinking toggle (item 2) =====
+        let thinking_selected = self.current_section() == SettingsSection::Parameters
+            && self.selected_item == 2;
+        let enabled = self.config.thinking.enabled;
+
+        let mut thinking_spans = vec![
+            Span::styled(format!(" {} ", if thinking_selected { "▸" } else { " " }), selector_style),
+            Span::styled("Thinking      ", label_style(thinking_selected)),
+            Span::styled(
+                if enabled { "[on] " } else { "[off]" },
+                if enabled {
+                    Style::default().fg(theme.colors.success.to_color())
+                } else {
+                    Style::default().fg(theme.colors.muted.to_color())
+                },
+            ),
+        ];
+        if thinking_selected {
+            thinking_spans.push(Span::styled(
+                "  ↵ toggle",
+                Style::default().fg(theme.colors.muted.to_color()),
+            ));
+        }
+        lines.push(Line::from(thinking_spans));
+
+        if thinking_selected {
+            lines.push(Line::from(vec![
+                Span::styled("        ", Style::default()),
+                Span::styled(
+                    "Reasoning output on models that support it; ignored elsewhere",
+                    Style::default().fg(theme.colors.secondary.to_color()),
+                ),
+            ]));
+        }
+
+        // ===== Thinking budget (item 3) =====
+        let budget_selected = self.current_section() == SettingsSection::Parameters
+            && self.selected_item == 3;
+        let budget_slider = Self::render_slider_bar(self.thinking_budget_percentage(), SLIDER_WIDTH);
+        let budget_value_style = if enabled {
+            Style::default().fg(theme.colors.primary.to_color())
+        } else {
+            Style::default().fg(theme.colors.muted.to_color())
+        };
+
+        let mut budget_spans = vec![
+            Span::styled(format!(" {} ", if budget_selected { "▸" } else { " " }), selector_style),
+            Span::styled("Think Budget  ", label_style(budget_selected)),
+            Span::styled(
+                format!("[{}] ", budget_slider),
+                Style::default().fg(theme.colors.secondary.to_color()),
+            ),
+            Span::styled(
+                Self::format_tokens_display(self.config.thinking.budget_tokens),
+                budget_value_style,
+            ),
+        ];
+        if budget_selected {
+            budget_spans.push(Span::styled(
+                "  ←/→ adjust",
+                Style::default().fg(theme.colors.muted.to_color()),
+            ));
+        }
+        lines.push(Line::from(budget_spans));
+
+        if budget_selected {
+            lines.push(Line::from(vec![
+                Span::styled("        ", Style::default()),
+                Span::styled(
+                    format!("Value: {} tokens (range: {} - {}, kept below Max Tokens)",
+                        self.config.thinking.budget_tokens,
+                        Self::format_tokens_display(Self::THINKING_BUDGET_MIN),
+                        Self::format_tokens_display(Self::THINKING_BUDGET_MAX)),
+                    Style::default().fg(theme.colors.muted.to_color()),
+                ),
+            ]));
+        }
+
         lines
     }
 
@@ -1622,6 +1798,26 @@ mod tests {
         assert_eq!(editor.config.defaults.provider, "openai");
     }
 
+    #[test]
+    fn test_base_url_edit() {
+        let mut editor = SettingsEditor::new();
+        editor.selected_section = 1; // Provider section
+        editor.selected_item = 0; // anthropic
+        editor.set_provider_base_url("anthropic", Some("https://old.example".to_string()));
+
+        editor.handle_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        editor.clear_input();
+        editor.paste_text("https://gateway.internal/v1");
+        match editor.confirm_edit() {
+            Some(Action::SettingsBaseUrlEntered { provider, url }) => {
+                assert_eq!(provider, "anthropic");
+                assert_eq!(url, "https://gateway.internal/v1");
+            }
+            other => panic!("unexpected action: {:?}", other),
+        }
+        assert!(!editor.is_editing());
+    }
+
     #[test]
     fn test_model_selection() {
         let mut editor = SettingsEditor::new();
@@ -1694,6 +1890,25 @@ mod tests {
         assert!(line_text.contains("↵ edit"), "Selected item should show edit hint");
     }
 
+    #[test]
+    fn test_keyless_provider_shows_configured() {
+        use crate::config::Theme;
+
+        let mut editor = SettingsEditor::new();
+        let ollama = editor.available_providers.iter().position(|p| p == "ollama").unwrap();
+        editor.selected_item = ollama;
+
+        let lines = editor.render_api_keys_section(&Theme::default());
+        let line_text: String = lines[ollama].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert!(line_text.contains("✓ no key needed"), "got {:?}", line_text);
+        assert!(!line_text.contains("↵ edit"));
+
+        // There is no key to edit
+        assert!(editor.handle_key_normal(KeyEvent::from(KeyCode::Enter)).is_none());
+        assert!(matches!(editor.input_mode, SettingsInputMode::Normal));
+        assert!(needs_api_key("anthropic"));
+    }
+
     #[test]
     fn test_api_keys_editing_shows_char_count() {
         use crate::config::Theme;
@@ -2226,6 +2441,9 @@ mod tests {
         // Move to Model section
         editor.next_section();
         editor.next_section();
+        editor.selected_item = editor.available_models.iter()
+            .position(|m| m == "claude-sonnet-4-20250514")
+            .unwrap();
         
         let theme = Theme::default();
         let lines = editor.render_model_section(&theme);
@@ -2403,6 +2621,30 @@ mod tests {
         assert!(editor.config().parameters.max_tokens > initial_tokens);
     }
 
+    #[test]
+    fn test_thinking_toggle_and_budget() {
+        let mut editor = SettingsEditor::new();
+        editor.next_section();
+        editor.next_section();
+        editor.next_section();
+        editor.next_item();
+        editor.next_item();
+
+        assert!(!editor.config().thinking.enabled);
+        let action = editor.adjust_parameter(true);
+        assert!(matches!(action, Some(Action::SettingsThinkingToggled(true))));
+        assert!(editor.config().thinking.enabled);
+
+        editor.next_item();
+        let action = editor.adjust_parameter(true);
+        assert!(matches!(action, Some(Action::SettingsThinkingBudgetChanged(5120))));
+
+        for _ in 0..100 {
+            editor.decrease_thinking_budget();
+        }
+        assert_eq!(editor.config().thinking.budget_tokens, 1024);
+    }
+
     #[test]
     fn test_temperature_percentage() {
         let mut editor = SettingsEditor::new();
@@ -17,6 +17,10 @@ pub enum SpinnerStyle {
     Pulse,
     Moon,
     Clock,
+    Dots,
+    Arc,
+    /// Plain ASCII for terminals/fonts without braille or box glyphs
+    Ascii,
 }
 
 #[allow(dead_code)]
@@ -33,6 +37,9 @@ impl SpinnerStyle {
             SpinnerStyle::Pulse => &["█", "▓", "▒", "░", "▒", "▓"],
             SpinnerStyle::Moon => &["🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘"],
             SpinnerStyle::Clock => &["🕐", "🕑", "🕒", "🕓", "🕔", "🕕", "🕖", "🕗", "🕘", "🕙", "🕚", "🕛"],
+            SpinnerStyle::Dots => &["∙", "•", "●", "•"],
+            SpinnerStyle::Arc => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            SpinnerStyle::Ascii => &["-", "\\", "|", "/"],
         }
     }
 
@@ -48,6 +55,9 @@ impl SpinnerStyle {
             SpinnerStyle::Pulse => 120,
             SpinnerStyle::Moon => 150,
             SpinnerStyle::Clock => 100,
+            SpinnerStyle::Dots => 150,
+            SpinnerStyle::Arc => 100,
+            SpinnerStyle::Ascii => 100,
         }
     }
     
@@ -63,6 +73,9 @@ impl SpinnerStyle {
             SpinnerStyle::Pulse => "pulse",
             SpinnerStyle::Moon => "moon",
             SpinnerStyle::Clock => "clock",
+            SpinnerStyle::Dots => "dots",
+            SpinnerStyle::Arc => "arc",
+            SpinnerStyle::Ascii => "ascii",
         }
     }
     
@@ -78,6 +91,9 @@ impl SpinnerStyle {
             "pulse" => Some(SpinnerStyle::Pulse),
             "moon" => Some(SpinnerStyle::Moon),
             "clock" => Some(SpinnerStyle::Clock),
+            "dots" => Some(SpinnerStyle::Dots),
+            "arc" => Some(SpinnerStyle::Arc),
+            "ascii" => Some(SpinnerStyle::Ascii),
             _ => None,
         }
     }
@@ -92,6 +108,8 @@ pub struct Spinner {
     label: Option<String>,
     active: bool,
     color: Color,
+    /// Overrides the style's own frame duration
+    interval: Option<Duration>,
 }
 
 impl Default for Spinner {
@@ -110,8 +128,26 @@ impl Spinner {
             label: None,
             active: true,
             color: Color::Cyan,
+            interval: None,
         }
     }
+
+    pub fn with_interval(mut self, interval_ms: Option<u64>) -> Self {
+        self.interval = interval_ms.filter(|ms| *ms > 0).map(Duration::from_millis);
+        self
+    }
+
+    /// Switch style and speed, restarting from the first frame
+    pub fn set_style(&mut self, style: SpinnerStyle, interval_ms: Option<u64>) {
+        self.style = style;
+        self.frame_index = 0;
+        self.interval = interval_ms.filter(|ms| *ms > 0).map(Duration::from_millis);
+    }
+
+    pub fn frame_duration(&self) -> Duration {
+        self.interval
+            .unwrap_or_else(|| Duration::from_millis(self.style.frame_duration_ms()))
+    }
     
     pub fn with_label(mut self, label: impl Into<String>) -> Self {
         self.label = Some(label.into());
@@ -142,8 +178,7 @@ impl Spinner {
             return false;
         }
         
-        let frame_duration = Duration::from_millis(self.style.frame_duration_ms());
-        if self.last_frame_time.elapsed() >= frame_duration {
+        if self.last_frame_time.elapsed() >= self.frame_duration() {
             let frames = self.style.frames();
             self.frame_index = (self.frame_index + 1) % frames.len();
             self.last_frame_time = Instant::now();
@@ -205,6 +240,9 @@ mod tests {
             SpinnerStyle::Arrow,
             SpinnerStyle::Bounce,
             SpinnerStyle::Pulse,
+            SpinnerStyle::Dots,
+            SpinnerStyle::Arc,
+            SpinnerStyle::Ascii,
         ] {
             let frames = style.frames();
             assert!(!frames.is_empty(), "Style {:?} has no frames", style);
@@ -216,5 +254,21 @@ mod tests {
         assert_eq!(SpinnerStyle::from_name("braille"), Some(SpinnerStyle::Braille));
         assert_eq!(SpinnerStyle::from_name("blocks"), Some(SpinnerStyle::Blocks));
         assert_eq!(SpinnerStyle::from_name("invalid"), None);
+        assert!(SpinnerStyle::Ascii.frames().iter().all(|f| f.is_ascii()));
+    }
+
+    #[test]
+    fn test_spinner_configured_interval() {
+        let mut spinner = Spinner::new(SpinnerStyle::Ascii).with_interval(Some(500));
+        assert_eq!(spinner.frame_duration(), Duration::from_millis(500));
+
+        // Style default (100ms) has passed, but not the configured interval
+        spinner.last_frame_time = Instant::now() - Duration::from_millis(200);
+        assert!(!spinner.tick());
+        assert_eq!(spinner.current_frame(), "-");
+
+        spinner.last_frame_time = Instant::now() - Duration::from_millis(500);
+        assert!(spinner.tick());
+        assert_eq!(spinner.current_frame(), "\\");
     }
 }
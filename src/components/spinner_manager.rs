@@ -34,6 +34,8 @@ impl SpinnerKey {
 pub struct SpinnerManager {
     spinners: HashMap<SpinnerKey, Spinner>,
     default_style: SpinnerStyle,
+    /// Frame interval override from `ui.spinner_interval_ms`
+    interval_ms: Option<u64>,
 }
 
 impl Default for SpinnerManager {
@@ -48,6 +50,7 @@ impl SpinnerManager {
         Self {
             spinners: HashMap::new(),
             default_style: SpinnerStyle::Braille,
+            interval_ms: None,
         }
     }
     
@@ -59,9 +62,36 @@ impl SpinnerManager {
     pub fn set_default_style(&mut self, style: SpinnerStyle) {
         self.default_style = style;
     }
+
+    /// Apply `ui.spinner_style` / `ui.spinner_interval_ms` to new and running spinners.
+    /// Unknown style names keep the current style.
+    pub fn configure(&mut self, style_name: &str, interval_ms: Option<u64>) {
+        if let Some(style) = SpinnerStyle::from_name(style_name) {
+            self.default_style = style;
+        } else {
+            tracing::warn!("Unknown spinner style '{}', keeping '{}'", style_name, self.default_style.name());
+        }
+        self.interval_ms = interval_ms;
+        for spinner in self.spinners.values_mut() {
+            spinner.set_style(self.default_style, interval_ms);
+        }
+    }
+
+    pub fn default_style(&self) -> SpinnerStyle {
+        self.default_style
+    }
+
+    pub fn interval_ms(&self) -> Option<u64> {
+        self.interval_ms
+    }
+
+    /// A spinner using the configured style and speed, for components that own one
+    pub fn spinner(&self) -> Spinner {
+        Spinner::new(self.default_style).with_interval(self.interval_ms)
+    }
     
     pub fn start(&mut self, key: SpinnerKey, label: Option<String>) {
-        let mut spinner = Spinner::new(self.default_style);
+        let mut spinner = self.spinner();
         if let Some(l) = label {
             spinner = spinner.with_label(l);
         }
@@ -70,7 +100,7 @@ impl SpinnerManager {
     }
     
     pub fn start_with_style(&mut self, key: SpinnerKey, style: SpinnerStyle, label: Option<String>) {
-        let mut spinner = Spinner::new(style);
+        let mut spinner = Spinner::new(style).with_interval(self.interval_ms);
         if let Some(l) = label {
             spinner = spinner.with_label(l);
         }
@@ -188,6 +218,21 @@ mod tests {
         manager.tick();
     }
 
+    #[test]
+    fn test_spinner_manager_configure() {
+        let mut manager = SpinnerManager::new();
+        manager.start(SpinnerKey::LlmLoading, None);
+
+        manager.configure("ascii", Some(250));
+        let spinner = manager.get(&SpinnerKey::LlmLoading).unwrap();
+        assert_eq!(spinner.current_frame(), "-");
+        assert_eq!(spinner.frame_duration(), std::time::Duration::from_millis(250));
+
+        manager.configure("nope", None);
+        assert_eq!(manager.default_style(), SpinnerStyle::Ascii);
+        assert_eq!(manager.spinner().frame_duration(), std::time::Duration::from_millis(100));
+    }
+
     #[test]
     fn test_spinner_manager_clear() {
         let mut manager = SpinnerManager::new();
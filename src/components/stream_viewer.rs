@@ -12,8 +12,9 @@ use ratatui::{
 use crate::action::Action;
 use crate::components::search::{
     SearchState, SearchBar, SearchAction, highlight_matches_in_line,
-    FilterState, FilterBar, FilterAction,
+    FilterState, FilterBar, FilterAction, HORIZONTAL_SCROLL_STEP,
 };
+use crate::components::scroll::{self, Follow, Following, LineWrap};
 use crate::components::Component;
 use crate::config::Theme;
 use crate::streams::{ConnectionState, StreamClient, StreamData};
@@ -23,10 +24,14 @@ pub struct StreamViewer {
     scroll_offset: u16,
     line_count: usize,
     visible_height: u16,
+    follow: Follow,
     selected_stream_name: String,
     search_state: SearchState,
     filter_state: FilterState,
     cached_lines: Vec<String>,
+    wrap: LineWrap,
+    /// Inner width from the last render, for keeping search matches in view
+    visible_width: u16,
 }
 
 #[allow(dead_code)]
@@ -36,10 +41,13 @@ impl StreamViewer {
             scroll_offset: 0,
             line_count: 0,
             visible_height: 10,
+            follow: Follow::default(),
             selected_stream_name: String::new(),
             search_state: SearchState::new(),
             filter_state: FilterState::new(),
             cached_lines: Vec::new(),
+            wrap: LineWrap::default(),
+            visible_width: 0,
         }
     }
 
@@ -47,24 +55,77 @@ impl StreamViewer {
         self.visible_height = height.saturating_sub(2);
     }
 
+    pub fn visible_height(&self) -> u16 {
+        self.visible_height
+    }
+
     pub fn scroll_up(&mut self, n: u16) {
         self.scroll_offset = self.scroll_offset.saturating_sub(n);
+        if n > 0 {
+            self.follow.set(false);
+        }
     }
 
     pub fn scroll_down(&mut self, n: u16) {
-        self.scroll_offset = self.scroll_offset.saturating_add(n);
+        let max_scroll = scroll::max_offset(self.line_count, self.visible_height);
+        self.scroll_offset = self.scroll_offset.saturating_add(n).min(max_scroll);
+        if self.scroll_offset >= max_scroll {
+            self.follow.reached_bottom();
+        }
     }
 
     pub fn scroll_to_top(&mut self) {
         self.scroll_offset = 0;
+        self.follow.set(false);
     }
 
     pub fn scroll_to_bottom(&mut self) {
-        if self.line_count > self.visible_height as usize {
-            self.scroll_offset = (self.line_count - self.visible_height as usize) as u16;
+        self.scroll_offset = scroll::max_offset(self.line_count, self.visible_height);
+        self.follow.set(true);
+    }
+
+    pub fn is_auto_scroll(&self) -> bool {
+        self.follow.is_following()
+    }
+
+    pub fn toggle_auto_scroll(&mut self) {
+        if self.follow.is_following() {
+            self.follow.set(false);
+        } else {
+            self.scroll_to_bottom();
         }
     }
 
+    pub fn is_wrap(&self) -> bool {
+        self.wrap.is_wrap()
+    }
+
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap.set_wrap(wrap);
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap.toggle();
+    }
+
+    pub fn h_scroll(&self) -> u16 {
+        self.wrap.h_scroll()
+    }
+
+    pub fn scroll_left(&mut self, n: u16) {
+        self.wrap.scroll_left(n);
+    }
+
+    /// Scroll right, stopping once the longest visible line's last column is reached
+    pub fn scroll_right(&mut self, n: u16) {
+        let widest = self
+            .filtered_lines()
+            .map(|(_, line)| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.wrap.scroll_right(n, widest);
+    }
+
     pub fn is_search_active(&self) -> bool {
         self.search_state.is_active()
     }
@@ -102,14 +163,10 @@ impl StreamViewer {
     }
 
     fn scroll_to_current_match(&mut self) {
-        if let Some(m) = self.search_state.current_match() {
-            let target_line = m.line_index as u16;
-            if target_line < self.scroll_offset {
-                self.scroll_offset = target_line.saturating_sub(2);
-            } else if target_line >= self.scroll_offset + self.visible_height {
-                self.scroll_offset = target_line.saturating_sub(self.visible_height / 2);
-            }
-        }
+        self.scroll_offset = self
+            .search_state
+            .scroll_offset_for_current_match(self.scroll_offset, self.visible_height);
+        self.wrap.reveal_match(&self.search_state, self.visible_width, 0);
     }
 
     pub fn is_filter_active(&self) -> bool {
@@ -149,6 +206,7 @@ impl StreamViewer {
     }
 
     fn update_cached_lines(&mut self, stream: Option<&StreamClient>) {
+        let previous_count = self.line_count;
         self.cached_lines.clear();
         if let Some(stream) = stream {
             for data in stream.buffer().iter() {
@@ -165,6 +223,9 @@ impl StreamViewer {
             }
         }
         self.line_count = self.cached_lines.len();
+        if self.follow.content_added(self.line_count.saturating_sub(previous_count)) {
+            self.scroll_to_bottom();
+        }
     }
 
     /// TRC-025: Build lines for graceful degradation display
@@ -191,6 +252,20 @@ impl StreamViewer {
                     info_style,
                 )));
             }
+            ConnectionState::Queued => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled("⧖ Queued", info_style.add_modifier(Modifier::BOLD))));
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Waiting for a free connection slot (streams.max_concurrent_connections)",
+                    muted.add_modifier(Modifier::ITALIC),
+                )));
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Press 'd' to cancel",
+                    muted,
+                )));
+            }
             ConnectionState::Connecting => {
                 lines.push(Line::from(""));
                 lines.push(Line::from(Span::styled("◌ Connecting...", info_style.add_modifier(Modifier::BOLD))));
@@ -365,6 +440,7 @@ impl StreamViewer {
         let border_style = theme.border_style(focused);
         let title_style = theme.title_style(focused);
 
+        let wrap_indicator = self.wrap.indicator();
         let search_indicator = if self.search_state.is_active() { " 󰍉" } else { "" };
         let filter_indicator = if self.has_active_filter() {
             format!(" 󰈶:{}", self.filter_state.pattern())
@@ -374,9 +450,17 @@ impl StreamViewer {
             String::new()
         };
         let title = if let Some(s) = stream {
-            format!(" {} [{}]{}{} ", s.name(), s.state(), search_indicator, filter_indicator)
+            format!(
+                " {} [{}] {}{}{}{} ",
+                s.name(),
+                s.state(),
+                self.follow.indicator(),
+                wrap_indicator,
+                search_indicator,
+                filter_indicator
+            )
         } else {
-            format!(" Stream Viewer{}{} ", search_indicator, filter_indicator)
+            format!(" Stream Viewer{}{}{} ", wrap_indicator, search_indicator, filter_indicator)
         };
 
         let block = Block::default()
@@ -384,6 +468,7 @@ impl StreamViewer {
             .title_style(title_style)
             .borders(Borders::ALL)
             .border_style(border_style);
+        self.visible_width = block.inner(stream_area).width;
 
         if stream.is_none() || self.cached_lines.is_empty() {
             // TRC-025: Show graceful degradation messages
@@ -446,11 +531,10 @@ impl StreamViewer {
             })
             .collect();
 
-        let paragraph = Paragraph::new(lines)
-            .block(block)
-            .wrap(Wrap { trim: false });
+        let paragraph = self.wrap.apply(Paragraph::new(lines).block(block));
 
         frame.render_widget(paragraph, stream_area);
+        self.follow.render_hint(frame, stream_area, theme);
 
         if let Some(rect) = bar_area {
             if self.search_state.is_active() {
@@ -464,6 +548,16 @@ impl StreamViewer {
     }
 }
 
+impl Following for StreamViewer {
+    fn follow(&self) -> &Follow {
+        &self.follow
+    }
+
+    fn follow_mut(&mut self) -> &mut Follow {
+        &mut self.follow
+    }
+}
+
 impl Default for StreamViewer {
     fn default() -> Self {
         Self::new()
@@ -484,6 +578,10 @@ impl Component for StreamViewer {
             Action::ScrollDown(n) => self.scroll_down(*n),
             Action::ScrollToTop => self.scroll_to_top(),
             Action::ScrollToBottom => self.scroll_to_bottom(),
+            Action::StreamViewerToggleWrap => self.toggle_wrap(),
+            Action::StreamViewerToggleAutoScroll => self.toggle_auto_scroll(),
+            Action::StreamViewerScrollLeft(n) => self.scroll_left(*n),
+            Action::StreamViewerScrollRight(n) => self.scroll_right(*n),
             Action::StreamViewerSearchStart => self.start_search(),
             Action::StreamViewerSearchClose => self.close_search(),
             Action::StreamViewerSearchNext => self.search_next(),
@@ -610,6 +708,9 @@ impl StreamViewer {
             KeyCode::Char('G') => Some(Action::ScrollToBottom),
             KeyCode::PageUp => Some(Action::ScrollPageUp),
             KeyCode::PageDown => Some(Action::ScrollPageDown),
+            KeyCode::Char('w') => Some(Action::StreamViewerToggleWrap),
+            KeyCode::Char('h') | KeyCode::Left => Some(Action::StreamViewerScrollLeft(HORIZONTAL_SCROLL_STEP)),
+            KeyCode::Char('l') | KeyCode::Right => Some(Action::StreamViewerScrollRight(HORIZONTAL_SCROLL_STEP)),
             KeyCode::Char('f') => {
                 self.start_filter();
                 Some(Action::StreamViewerFilterStart)
@@ -653,6 +754,27 @@ mod tests {
         assert_eq!(viewer.scroll_offset, 80);
     }
 
+    #[test]
+    fn test_stream_viewer_follow() {
+        let mut viewer = StreamViewer::new();
+        viewer.visible_height = 5;
+        viewer.cached_lines = (0..20).map(|i| i.to_string()).collect();
+        viewer.line_count = 20;
+        viewer.scroll_to_bottom();
+        assert!(viewer.is_auto_scroll());
+
+        viewer.scroll_up(3);
+        assert!(!viewer.is_auto_scroll());
+        // New data while paused leaves the view where it is
+        viewer.follow.content_added(4);
+        assert_eq!(viewer.scroll_offset, 12);
+        assert_eq!(viewer.follow.unseen_hint().as_deref(), Some("4 new lines below"));
+
+        viewer.update(&Action::StreamViewerToggleAutoScroll);
+        assert!(viewer.is_auto_scroll());
+        assert_eq!(viewer.scroll_offset, 15);
+    }
+
     #[test]
     fn test_stream_viewer_search_activate() {
         let mut viewer = StreamViewer::new();
@@ -680,6 +802,22 @@ mod tests {
         assert_eq!(viewer.search_state.match_count(), 2);
     }
 
+    #[test]
+    fn test_stream_viewer_wrap_and_horizontal_scroll() {
+        let mut viewer = StreamViewer::new();
+        viewer.cached_lines = vec!["short".to_string(), "y".repeat(50)];
+
+        viewer.update(&Action::StreamViewerScrollRight(8));
+        assert_eq!(viewer.h_scroll(), 0);
+
+        viewer.update(&Action::StreamViewerToggleWrap);
+        assert!(!viewer.is_wrap());
+        viewer.update(&Action::StreamViewerScrollRight(100));
+        assert_eq!(viewer.h_scroll(), 49);
+        viewer.update(&Action::StreamViewerScrollLeft(9));
+        assert_eq!(viewer.h_scroll(), 40);
+    }
+
     #[test]
     fn test_stream_viewer_search_navigation() {
         let mut viewer = StreamViewer::new();
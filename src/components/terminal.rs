@@ -1,22 +1,24 @@
 // Terminal widget - some methods for future scroll display features
 
-use crossterm::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+use crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Margin, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Widget},
+    widgets::{Block, Borders, Scrollbar, ScrollbarOrientation, ScrollbarState, Widget},
     Frame,
 };
 
 use crate::action::Action;
-use crate::components::Component;
+use crate::components::{scroll, Component};
 use crate::config::Theme;
-use crate::pty::grid::{Grid, MouseMode};
+use crate::pty::grid::{CursorShape, CursorStyle, Grid, MouseMode};
 
 pub struct TerminalWidget {
     grid: Grid,
     inner_area: Option<Rect>,
+    /// Lines per mouse-wheel notch (`ui.scroll_lines`)
+    scroll_lines: u16,
 }
 
 #[allow(dead_code)]
@@ -25,9 +27,15 @@ impl TerminalWidget {
         Self {
             grid: Grid::new(cols, rows),
             inner_area: None,
+            scroll_lines: scroll::DEFAULT_SCROLL_LINES,
         }
     }
 
+    /// `ui.scroll_lines`: lines per mouse-wheel notch
+    pub fn set_scroll_lines(&mut self, lines: u16) {
+        self.scroll_lines = lines.max(1);
+    }
+
     pub fn resize(&mut self, cols: usize, rows: usize) {
         self.grid.resize(cols, rows);
     }
@@ -36,10 +44,18 @@ impl TerminalWidget {
         self.grid.process(data);
     }
 
+    pub fn reset(&mut self) {
+        self.grid.reset();
+    }
+
     pub fn size(&self) -> (usize, usize) {
         self.grid.size()
     }
 
+    fn viewport_rows(&self) -> u16 {
+        self.grid.rows().min(u16::MAX as usize) as u16
+    }
+
     pub fn scroll_up(&mut self, amount: u16) {
         self.grid.scroll_up(amount as usize);
     }
@@ -61,6 +77,16 @@ impl TerminalWidget {
         self.grid.mouse_mode()
     }
 
+    /// Cursor shape requested by the nested application
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.grid.cursor_style()
+    }
+
+    /// Whether arrow keys should be sent in application (SS3) form
+    pub fn application_cursor_keys(&self) -> bool {
+        self.grid.application_cursor_keys()
+    }
+
     /// Check if we're in alternate screen mode (running a TUI)
     pub fn is_alternate_screen(&self) -> bool {
         self.grid.is_alternate_screen()
@@ -109,9 +135,10 @@ impl TerminalWidget {
         }
     }
 
-    pub fn start_selection(&mut self, screen_x: u16, screen_y: u16) {
+    /// Start a selection at a screen position; `block` selects a rectangle
+    pub fn start_selection(&mut self, screen_x: u16, screen_y: u16, block: bool) {
         if let Some((x, y)) = self.screen_to_grid(screen_x, screen_y) {
-            self.grid.start_selection(x, y);
+            self.grid.start_selection(x, y, block);
         }
     }
 
@@ -175,12 +202,16 @@ impl Component for TerminalWidget {
                 self.scroll_to_bottom();
             }
             Action::ScrollPageUp => {
-                let page = self.grid.rows().saturating_sub(1).max(1);
-                self.scroll_up(page as u16);
+                self.scroll_up(scroll::page_step(self.viewport_rows()));
             }
             Action::ScrollPageDown => {
-                let page = self.grid.rows().saturating_sub(1).max(1);
-                self.scroll_down(page as u16);
+                self.scroll_down(scroll::page_step(self.viewport_rows()));
+            }
+            Action::ScrollHalfPageUp => {
+                self.scroll_up(scroll::half_page_step(self.viewport_rows()));
+            }
+            Action::ScrollHalfPageDown => {
+                self.scroll_down(scroll::half_page_step(self.viewport_rows()));
             }
             _ => {}
         }
@@ -192,8 +223,9 @@ impl Component for TerminalWidget {
 
         let scroll_offset = self.grid.scroll_offset();
         let max_scroll = self.grid.max_scroll_offset();
+        // Scrollback position, hidden at the live edge
         let title = if scroll_offset > 0 {
-            format!(" Terminal [{}/{}] ", scroll_offset, max_scroll)
+            format!(" Terminal [-{} lines] ", scroll_offset)
         } else {
             " Terminal ".to_string()
         };
@@ -207,12 +239,40 @@ impl Component for TerminalWidget {
         let inner = block.inner(area);
         frame.render_widget(block, area);
 
+        if scroll_offset > 0 {
+            // Drawn on the right border; the top of scrollback is position 0
+            let mut scrollbar_state = ScrollbarState::new(max_scroll)
+                .position(max_scroll - scroll_offset);
+            frame.render_stateful_widget(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight).begin_symbol(None).end_symbol(None),
+                area.inner(Margin { vertical: 1, horizontal: 0 }),
+                &mut scrollbar_state,
+            );
+        }
+
+        let show_cursor = focused && scroll_offset == 0 && self.grid.cursor_visible();
+        let block_cursor = self.grid.cursor_style().shape == CursorShape::Block;
         let grid_widget = GridWidget {
             grid: &self.grid,
-            show_cursor: focused && scroll_offset == 0,
+            show_cursor: show_cursor && block_cursor,
             theme,
         };
         frame.render_widget(grid_widget, inner);
+
+        // Underline and bar cursors can't be drawn as a cell; place the host
+        // terminal cursor there instead and let the app set its shape
+        if show_cursor && !block_cursor {
+            let (cursor_x, cursor_y) = self.grid.cursor();
+            let view_offset = if !self.grid.is_alternate_screen() && cursor_y >= inner.height as usize {
+                cursor_y - inner.height as usize + 1
+            } else {
+                0
+            };
+            let y = cursor_y - view_offset;
+            if cursor_x < inner.width as usize && y < inner.height as usize {
+                frame.set_cursor_position((inner.x + cursor_x as u16, inner.y + y as u16));
+            }
+        }
     }
 }
 
@@ -220,7 +280,9 @@ impl TerminalWidget {
     fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
         match mouse.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                self.start_selection(mouse.column, mouse.row);
+                // Alt+drag selects a rectangular block (e.g. a column of a table)
+                let block = mouse.modifiers.contains(KeyModifiers::ALT);
+                self.start_selection(mouse.column, mouse.row, block);
                 None
             }
             MouseEventKind::Drag(MouseButton::Left) => {
@@ -251,10 +313,10 @@ impl TerminalWidget {
                 }
             }
             MouseEventKind::ScrollUp => {
-                Some(Action::ScrollUp(3))
+                Some(Action::ScrollUp(self.scroll_lines))
             }
             MouseEventKind::ScrollDown => {
-                Some(Action::ScrollDown(3))
+                Some(Action::ScrollDown(self.scroll_lines))
             }
             _ => None,
         }
@@ -377,3 +439,47 @@ impl<'a> Widget for GridWidget<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn title_row(widget: &TerminalWidget) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(30, 8)).unwrap();
+        terminal
+            .draw(|frame| widget.render(frame, frame.area(), true, &Theme::default()))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width).map(|x| buffer[(x, 0)].symbol()).collect()
+    }
+
+    #[test]
+    fn test_scrollback_indicator_hidden_at_live_edge() {
+        let mut widget = TerminalWidget::new(28, 6);
+        for i in 0..40 {
+            widget.process_output(format!("line {}\r\n", i).as_bytes());
+        }
+        assert!(!title_row(&widget).contains("lines]"));
+
+        widget.scroll_up(12);
+        assert!(title_row(&widget).contains("[-12 lines]"));
+
+        widget.scroll_to_bottom();
+        assert!(!title_row(&widget).contains("lines]"));
+    }
+
+    #[test]
+    fn test_wheel_uses_configured_scroll_lines() {
+        let mut widget = TerminalWidget::new(28, 6);
+        widget.set_scroll_lines(7);
+        let wheel = Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert!(matches!(widget.handle_event(&wheel), Some(Action::ScrollUp(7))));
+    }
+}
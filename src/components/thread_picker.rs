@@ -1,7 +1,8 @@
 //! Thread picker component for listing and resuming previous conversations
 //!
 //! Displays saved threads with fuzzy search, allowing users to continue
-//! previous conversations from the command palette.
+//! previous conversations from the command palette. Query words starting
+//! with `#` filter by tag and combine with the fuzzy text search.
 
 use crossterm::event::{Event, KeyCode, KeyModifiers};
 use nucleo::{Config, Matcher, Utf32String};
@@ -38,6 +39,8 @@ pub struct ThreadPicker {
     filtered_results: Vec<MatchResult>,
     /// List selection state
     list_state: ListState,
+    /// Thread awaiting delete confirmation (y/n)
+    pending_delete: Option<String>,
 }
 
 impl ThreadPicker {
@@ -50,6 +53,7 @@ impl ThreadPicker {
             matcher: Matcher::new(config),
             filtered_results: Vec::new(),
             list_state: ListState::default(),
+            pending_delete: None,
         }
     }
 
@@ -77,15 +81,63 @@ impl ThreadPicker {
         self.threads.clear();
         self.filtered_results.clear();
         self.list_state.select(None);
+        self.pending_delete = None;
+    }
+
+    /// Whether there are no threads left to show
+    pub fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+
+    /// Drop a deleted thread, keeping the selection on the same row or the
+    /// nearest one above it
+    pub fn remove_thread(&mut self, id: &str) {
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.threads.retain(|t| t.id != id);
+        self.update_filtered_results();
+        if !self.filtered_results.is_empty() {
+            self.list_state.select(Some(selected.min(self.filtered_results.len() - 1)));
+        }
+    }
+
+    /// The currently highlighted thread
+    fn selected_thread(&self) -> Option<&ThreadSummary> {
+        let result = self.filtered_results.get(self.list_state.selected()?)?;
+        self.threads.get(result.thread_idx)
+    }
+
+    /// Split the query into `#tag` filters and the remaining fuzzy text
+    fn parse_query(&self) -> (Vec<String>, String) {
+        let mut tags = Vec::new();
+        let mut text = Vec::new();
+        for word in self.query.split_whitespace() {
+            match word.strip_prefix('#') {
+                Some(tag) => tags.push(tag.to_lowercase()),
+                None => text.push(word),
+            }
+        }
+        (tags, text.join(" "))
+    }
+
+    /// Whether a thread carries every tag filter (prefix match, so partially
+    /// typed tags already narrow the list)
+    fn has_tags(thread: &ThreadSummary, filters: &[String]) -> bool {
+        filters
+            .iter()
+            .all(|filter| thread.tags.iter().any(|tag| tag.starts_with(filter.as_str())))
     }
 
     /// Update filtered results based on current query
     fn update_filtered_results(&mut self) {
         self.filtered_results.clear();
+        let (tag_filters, text) = self.parse_query();
 
-        if self.query.is_empty() {
-            // Show all threads when no query (already sorted by updated_at)
-            for (idx, _) in self.threads.iter().enumerate() {
+        if text.is_empty() {
+            // Show all (tag-matching) threads when no text (already sorted by updated_at)
+            for (idx, thread) in self.threads.iter().enumerate() {
+                if !Self::has_tags(thread, &tag_filters) {
+                    continue;
+                }
                 self.filtered_results.push(MatchResult {
                     thread_idx: idx,
                     score: 0,
@@ -95,12 +147,15 @@ impl ThreadPicker {
         } else {
             // Fuzzy match against query
             let pattern = nucleo::pattern::Pattern::parse(
-                &self.query,
+                &text,
                 nucleo::pattern::CaseMatching::Smart,
                 nucleo::pattern::Normalization::Smart,
             );
 
             for (idx, thread) in self.threads.iter().enumerate() {
+                if !Self::has_tags(thread, &tag_filters) {
+                    continue;
+                }
                 // Match against title and model
                 let title_utf32: Utf32String = thread.title.as_str().into();
                 let model_utf32: Utf32String = thread.model.as_str().into();
@@ -136,7 +191,7 @@ impl ThreadPicker {
             }
 
             // Sort by score (higher is better)
-            self.filtered_results.sort_by(|a, b| b.score.cmp(&a.score));
+            self.filtered_results.sort_by_key(|r| std::cmp::Reverse(r.score));
         }
 
         // Reset selection to first item
@@ -188,6 +243,14 @@ impl ThreadPicker {
         }
 
         if let Event::Key(key) = event {
+            // Any key other than 'y' cancels a pending delete
+            if let Some(id) = self.pending_delete.take() {
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    return Some(Action::ThreadDelete(id));
+                }
+                return None;
+            }
+
             match key.code {
                 KeyCode::Esc => {
                     self.hide();
@@ -210,6 +273,12 @@ impl ThreadPicker {
                 KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                     self.select_prev();
                 }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.pending_delete = self.selected_thread().map(|t| t.id.clone());
+                }
+                KeyCode::Delete => {
+                    self.pending_delete = self.selected_thread().map(|t| t.id.clone());
+                }
                 KeyCode::Char(c) => {
                     self.query.push(c);
                     self.update_filtered_results();
@@ -271,18 +340,26 @@ impl ThreadPicker {
         ]);
         frame.render_widget(Paragraph::new(input_line), chunks[0]);
 
-        // Info line
-        let count = self.filtered_results.len();
-        let total = self.threads.len();
-        let info = if self.query.is_empty() {
-            format!("{} threads", total)
+        // Info line, or the delete prompt while one is pending
+        let pending = self
+            .pending_delete
+            .as_ref()
+            .and_then(|id| self.threads.iter().find(|t| &t.id == id));
+        let info_line = if let Some(thread) = pending {
+            Paragraph::new(format!("Delete \"{}\"? (y/n)", thread.title))
+                .style(Style::default().fg(theme.colors.warning.to_color()).add_modifier(Modifier::BOLD))
         } else {
-            format!("{}/{} matching", count, total)
+            let count = self.filtered_results.len();
+            let total = self.threads.len();
+            let info = if self.query.is_empty() {
+                format!("{} threads · ^D delete", total)
+            } else {
+                format!("{}/{} matching", count, total)
+            };
+            Paragraph::new(info)
+                .style(Style::default().fg(theme.command_palette.description_fg.to_color()))
         };
-        let info_line = Paragraph::new(info)
-            .style(Style::default().fg(theme.command_palette.description_fg.to_color()))
-            .alignment(Alignment::Right);
-        frame.render_widget(info_line, chunks[1]);
+        frame.render_widget(info_line.alignment(Alignment::Right), chunks[1]);
 
         // Results list
         let items: Vec<ListItem> = self
@@ -338,6 +415,17 @@ impl ThreadPicker {
             }
         }
 
+        // Tag chips
+        for tag in &thread.tags {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!(" {} ", tag),
+                Style::default()
+                    .fg(theme.colors.background.to_color())
+                    .bg(theme.colors.accent.to_color()),
+            ));
+        }
+
         // Model info
         spans.push(Span::styled(
             format!(" - {}", Self::abbreviate_model(&thread.model)),
@@ -391,6 +479,7 @@ impl Default for ThreadPicker {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use crossterm::event::KeyEvent;
 
     fn create_test_threads() -> Vec<ThreadSummary> {
         vec![
@@ -400,6 +489,7 @@ mod tests {
                 model: "claude-sonnet-4-20250514".to_string(),
                 updated_at: Utc::now(),
                 segment_count: 12,
+                tags: vec!["debugging".to_string(), "work".to_string()],
             },
             ThreadSummary {
                 id: "T-002".to_string(),
@@ -407,6 +497,7 @@ mod tests {
                 model: "gpt-4o".to_string(),
                 updated_at: Utc::now(),
                 segment_count: 45,
+                tags: vec!["work".to_string()],
             },
             ThreadSummary {
                 id: "T-003".to_string(),
@@ -414,6 +505,7 @@ mod tests {
                 model: "claude-opus-4-20250514".to_string(),
                 updated_at: Utc::now(),
                 segment_count: 8,
+                tags: Vec::new(),
             },
         ]
     }
@@ -460,6 +552,31 @@ mod tests {
         assert_eq!(picker.threads[first_result.thread_idx].id, "T-001");
     }
 
+    #[test]
+    fn test_tag_filter_combines_with_search() {
+        let mut picker = ThreadPicker::new();
+        picker.show(create_test_threads());
+
+        picker.query = "#work".to_string();
+        picker.update_filtered_results();
+        assert_eq!(picker.filtered_results.len(), 2);
+
+        // Partial tag narrows by prefix
+        picker.query = "#deb".to_string();
+        picker.update_filtered_results();
+        assert_eq!(picker.filtered_results.len(), 1);
+
+        // Tag plus text: only work threads matching "settings"
+        picker.query = "#work settings".to_string();
+        picker.update_filtered_results();
+        assert_eq!(picker.filtered_results.len(), 1);
+        assert_eq!(picker.threads[picker.filtered_results[0].thread_idx].id, "T-002");
+
+        picker.query = "#work review".to_string();
+        picker.update_filtered_results();
+        assert!(picker.filtered_results.is_empty());
+    }
+
     #[test]
     fn test_selection_navigation() {
         let mut picker = ThreadPicker::new();
@@ -500,6 +617,47 @@ mod tests {
         assert_eq!(ThreadPicker::abbreviate_model("short"), "short");
     }
 
+    #[test]
+    fn test_delete_requires_confirmation() {
+        let mut picker = ThreadPicker::new();
+        picker.show(create_test_threads());
+        picker.select_next();
+
+        let ctrl_d = Event::Key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        let key = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+
+        // Anything but 'y' cancels without touching the query
+        assert!(picker.handle_event(&ctrl_d).is_none());
+        assert!(picker.handle_event(&key('n')).is_none());
+        assert!(picker.query.is_empty());
+
+        assert!(picker.handle_event(&ctrl_d).is_none());
+        let action = picker.handle_event(&key('y'));
+        assert!(matches!(action, Some(Action::ThreadDelete(ref id)) if id == "T-002"));
+        assert!(picker.is_visible());
+    }
+
+    #[test]
+    fn test_remove_thread_selects_neighbor() {
+        let mut picker = ThreadPicker::new();
+        picker.show(create_test_threads());
+
+        // Removing the last row moves the selection up to the new last row
+        picker.select_prev();
+        picker.remove_thread("T-003");
+        assert_eq!(picker.list_state.selected(), Some(1));
+        assert_eq!(picker.selected_thread().unwrap().id, "T-002");
+
+        // Removing a middle row keeps the same index, now the next thread
+        picker.list_state.select(Some(0));
+        picker.remove_thread("T-001");
+        assert_eq!(picker.selected_thread().unwrap().id, "T-002");
+
+        picker.remove_thread("T-002");
+        assert!(picker.is_empty());
+        assert_eq!(picker.list_state.selected(), None);
+    }
+
     #[test]
     fn test_hide_clears_state() {
         let mut picker = ThreadPicker::new();
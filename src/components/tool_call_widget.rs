@@ -7,7 +7,8 @@ use ratatui::{
 use std::time::Instant;
 
 use crate::config::Theme;
-use crate::llm::{ToolUse, ToolResult, ToolResultContent};
+use crate::llm::{patch, ToolUse, ToolResult, ToolResultContent};
+use crate::components::inline_image;
 use crate::components::spinner::Spinner;
 use crate::components::diff_view::{DiffComputer, DiffRenderer};
 
@@ -67,6 +68,8 @@ pub struct ToolCall {
     pub original_content: Option<String>,
     /// For file_write: whether original content was captured
     pub original_captured: bool,
+    /// Placeholder for an image result, computed once on completion
+    image_placeholder: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -81,6 +84,7 @@ impl ToolCall {
             end_time: None,
             original_content: None,
             original_captured: false,
+            image_placeholder: None,
         }
     }
 
@@ -91,14 +95,21 @@ impl ToolCall {
         self
     }
 
-    /// Get the new content for file_write (extracts and unescapes from tool input)
+    /// Get the new content for file_write (extracts and unescapes from tool input),
+    /// or the result of applying an apply_edit to the captured original
     pub fn get_file_write_content(&self) -> Option<String> {
-        if self.tool_use.name != "file_write" {
-            return None;
+        match self.tool_use.name.as_str() {
+            "file_write" => self.tool_use.input.get("content")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            "apply_edit" => {
+                let original = self.original_content.as_deref()?;
+                patch::apply_edit_input(original, &self.tool_use.input)
+                    .ok()
+                    .map(|outcome| outcome.content)
+            }
+            _ => None,
         }
-        self.tool_use.input.get("content")
-            .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
     }
 
     /// Get the file path for file_write/file_read
@@ -112,6 +123,11 @@ impl ToolCall {
     pub fn is_file_write(&self) -> bool {
         self.tool_use.name == "file_write"
     }
+
+    /// Check if this tool call renders as a diff against the original file
+    pub fn has_diff_preview(&self) -> bool {
+        matches!(self.tool_use.name.as_str(), "file_write" | "apply_edit")
+    }
     
     pub fn with_status(mut self, status: ToolStatus) -> Self {
         self.status = status;
@@ -124,8 +140,7 @@ impl ToolCall {
         } else {
             ToolStatus::Success
         };
-        self.result = Some(result);
-        self.end_time = Some(Instant::now());
+        self.set_result(result);
         self
     }
     
@@ -144,6 +159,14 @@ impl ToolCall {
         if self.status == ToolStatus::Success {
             self.expanded = false;
         }
+        self.set_result(result);
+    }
+
+    fn set_result(&mut self, result: ToolResult) {
+        self.image_placeholder = match &result.content {
+            ToolResultContent::Image(image) => Some(inline_image::placeholder(image)),
+            _ => None,
+        };
         self.result = Some(result);
         self.end_time = Some(Instant::now());
     }
@@ -165,6 +188,49 @@ impl ToolCall {
         }
     }
     
+    /// Compact run time: "12ms" below a second, "1.5s" above
+    pub fn duration_label(&self) -> Option<String> {
+        self.elapsed_ms().map(|ms| {
+            if ms < 1000 {
+                format!("{}ms", ms)
+            } else {
+                format!("{:.1}s", ms as f64 / 1000.0)
+            }
+        })
+    }
+
+    /// Size of the result: a line count for multi-line output, bytes otherwise
+    pub fn output_size_label(&self) -> Option<String> {
+        let text = match &self.result.as_ref()?.content {
+            ToolResultContent::Image(_) => return None,
+            _ => self.result_text()?,
+        };
+        let lines = text.lines().count();
+        if lines > 1 {
+            return Some(format!("{} lines", lines));
+        }
+        let bytes = text.len();
+        Some(if bytes < 1024 {
+            format!("{} B", bytes)
+        } else {
+            format!("{:.1} KB", bytes as f64 / 1024.0)
+        })
+    }
+
+    /// First non-empty line of an error result, truncated for the header
+    pub fn error_summary(&self) -> Option<String> {
+        if !self.result.as_ref()?.is_error {
+            return None;
+        }
+        let text = self.result_text()?;
+        let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+        Some(if line.chars().count() > 60 {
+            format!("{}...", line.chars().take(60).collect::<String>())
+        } else {
+            line.to_string()
+        })
+    }
+
     pub fn tool_id(&self) -> &str {
         &self.tool_use.id
     }
@@ -176,7 +242,7 @@ impl ToolCall {
     /// Get a summary of the tool input for display
     pub fn input_summary(&self) -> String {
         match self.tool_use.name.as_str() {
-            "file_read" | "file_write" | "list_directory" | "file_delete" => {
+            "file_read" | "file_write" | "apply_edit" | "list_directory" | "file_delete" => {
                 self.tool_use.input.get("path")
                     .and_then(|p| p.as_str())
                     .map(|s| s.to_string())
@@ -213,9 +279,25 @@ impl ToolCall {
             ToolResultContent::Json(json) => {
                 serde_json::to_string_pretty(json).unwrap_or_else(|_| json.to_string())
             }
-            ToolResultContent::Image(_) => "[Image result]".to_string(),
+            ToolResultContent::Image(_) => self.image_placeholder.clone().unwrap_or_default(),
         })
     }
+
+    /// Full, untruncated input and output for viewing in the pager
+    pub fn full_output(&self) -> String {
+        let input = serde_json::to_string_pretty(&self.tool_use.input)
+            .unwrap_or_else(|_| self.tool_use.input.to_string());
+        let output = self
+            .result_text()
+            .unwrap_or_else(|| "(no output yet)".to_string());
+        format!(
+            "Tool: {}\nStatus: {}\n\nInput:\n{}\n\nOutput:\n{}",
+            self.tool_use.name,
+            self.status.label(),
+            input,
+            output
+        )
+    }
 }
 
 /// Widget for rendering a tool call in the conversation
@@ -268,15 +350,18 @@ impl<'a> ToolCallWidget<'a> {
             Color::Reset
         };
 
-        // Phase 1: Compact timing format (ms for <1s, otherwise Xs)
-        let timing = self.tool_call.elapsed_ms()
-            .map(|ms| {
-                if ms < 1000 {
-                    format!(" [{}ms]", ms)
-                } else {
-                    format!(" [{:.1}s]", ms as f64 / 1000.0)
-                }
-            })
+        // Run time and output size, e.g. " 12ms · 340 lines"
+        let metrics: Vec<String> = self.tool_call.duration_label()
+            .into_iter()
+            .chain(self.tool_call.output_size_label())
+            .collect();
+        let timing = if metrics.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", metrics.join(" · "))
+        };
+        let error_summary = self.tool_call.error_summary()
+            .map(|summary| format!(" — {}", summary))
             .unwrap_or_default();
 
         // Phase 1: Extra info for specific tools (file changes, etc.)
@@ -317,12 +402,20 @@ impl<'a> ToolCallWidget<'a> {
                 timing,
                 Style::default().fg(self.theme.colors.muted.to_color()).bg(header_bg),
             ),
+            Span::styled(
+                error_summary,
+                Style::default().fg(self.theme.colors.error.to_color()).bg(header_bg),
+            ),
         ]));
         
         // Expanded content
         if self.tool_call.expanded {
-            // Special handling for file_write: show diff view
-            if self.tool_call.is_file_write() && self.tool_call.original_captured {
+            // Special handling for file_write/apply_edit: show diff view
+            // (an apply_edit that doesn't apply cleanly falls back to its raw input)
+            if self.tool_call.has_diff_preview()
+                && self.tool_call.original_captured
+                && self.tool_call.get_file_write_content().is_some()
+            {
                 lines.extend(self.render_file_write_diff());
             } else {
                 // Default: show JSON input parameters
@@ -485,11 +578,11 @@ impl<'a> ToolCallWidget<'a> {
             // File operations
             "file_read" => "",      // File icon
             "file_write" => "",     // Pencil/write icon
-            "edit" => "",           // Edit/pencil icon
+            "edit" | "apply_edit" => "", // Edit/pencil icon
             "glob" | "find" => "",  // Folder search
 
             // Search operations
-            "grep" => "",           // Search icon
+            "grep" | "search_code" => "", // Search icon
             "ast_search" => "",     // Code search
 
             // Shell operations
@@ -505,10 +598,11 @@ impl<'a> ToolCallWidget<'a> {
             name if name.starts_with("mcp_") || name.starts_with("mandrel_") => "󱂛", // Database
 
             // Task/agent operations
-            "task" | "subagent" => "󰜎", // Robot
+            "task" | "task_output" | "subagent" => "󰜎", // Robot
 
             // Question/user interaction
             "ask_user" => "", // Chat bubble
+            "update_plan" => "󰄲", // Checklist
 
             // Default
             _ => "󰡨", // Tool icon
@@ -523,7 +617,7 @@ impl<'a> ToolCallWidget<'a> {
         }
 
         match self.tool_call.tool_name() {
-            "file_write" | "edit" => {
+            "file_write" | "edit" | "apply_edit" => {
                 // Show +lines/-lines from diff
                 if let Some(ref original) = self.tool_call.original_content {
                     if let Some(new_content) = self.tool_call.get_file_write_content() {
@@ -548,16 +642,6 @@ impl<'a> ToolCallWidget<'a> {
                 }
                 String::new()
             }
-            "file_read" => {
-                // Show line count from result
-                if let Some(result_text) = self.tool_call.result_text() {
-                    let lines = result_text.lines().count();
-                    if lines > 0 {
-                        return format!(" [{} lines]", lines);
-                    }
-                }
-                String::new()
-            }
             "grep" => {
                 // Show match count from result
                 if let Some(result_text) = self.tool_call.result_text() {
@@ -578,18 +662,6 @@ impl<'a> ToolCallWidget<'a> {
                 }
                 String::new()
             }
-            "bash_execute" => {
-                // Show exit code if available (lines of output)
-                if let Some(result_text) = self.tool_call.result_text() {
-                    let lines = result_text.lines().count();
-                    if lines > 0 && lines <= 10 {
-                        return format!(" [{} lines]", lines);
-                    } else if lines > 10 {
-                        return format!(" [{}+ lines]", lines);
-                    }
-                }
-                String::new()
-            }
             _ => String::new(),
         }
     }
@@ -898,6 +970,51 @@ mod tests {
         assert_eq!(tool_call.status, ToolStatus::Error);
     }
 
+    #[test]
+    fn test_tool_call_full_output() {
+        let tool_use = create_test_tool_use("file_read");
+        let mut tool_call = ToolCall::new(tool_use);
+        assert!(tool_call.full_output().contains("(no output yet)"));
+
+        tool_call.complete(create_test_result("tool_file_read", false));
+        let output = tool_call.full_output();
+        assert!(output.starts_with("Tool: file_read"));
+        assert!(output.ends_with("Output:\nTest result"));
+    }
+
+    #[test]
+    fn test_tool_call_output_metrics() {
+        let mut tool_call = ToolCall::new(create_test_tool_use("file_read"));
+        assert!(tool_call.duration_label().is_none());
+        assert!(tool_call.output_size_label().is_none());
+
+        tool_call.start_execution();
+        tool_call.complete(ToolResult {
+            tool_use_id: "tool_file_read".to_string(),
+            content: ToolResultContent::Text("a\nb\nc".to_string()),
+            is_error: false,
+        });
+        assert!(tool_call.duration_label().unwrap().ends_with("ms"));
+        assert_eq!(tool_call.output_size_label().as_deref(), Some("3 lines"));
+        assert!(tool_call.error_summary().is_none());
+
+        let mut failed = ToolCall::new(create_test_tool_use("bash_execute"));
+        failed.complete(ToolResult {
+            tool_use_id: "tool_bash_execute".to_string(),
+            content: ToolResultContent::Text("\nPermission denied\nmore detail".to_string()),
+            is_error: true,
+        });
+        assert_eq!(failed.error_summary().as_deref(), Some("Permission denied"));
+
+        let header: String = ToolCallWidget::new(&failed, &Theme::default())
+            .render_lines()[0]
+            .spans
+            .iter()
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert!(header.ends_with(" 3 lines — Permission denied"), "{}", header);
+    }
+
     #[test]
     fn test_tool_call_toggle_expanded() {
         let tool_use = create_test_tool_use("test");
@@ -1056,4 +1173,20 @@ mod tests {
         manager.cycle_verbosity();
         assert_eq!(manager.verbosity(), ToolVerbosity::Normal);
     }
+
+    #[test]
+    fn test_apply_edit_diff_preview_content() {
+        let tool_use = ToolUse {
+            id: "tool_apply_edit".to_string(),
+            name: "apply_edit".to_string(),
+            input: json!({"path": "/test/path", "old_string": "b", "new_string": "c"}),
+        };
+        let call = ToolCall::new(tool_use.clone()).with_original_content(Some("a\nb\n".to_string()));
+        assert!(call.has_diff_preview());
+        assert_eq!(call.get_file_write_content().as_deref(), Some("a\nc\n"));
+
+        // An edit that doesn't apply has no preview content
+        let stale = ToolCall::new(tool_use).with_original_content(Some("a\n".to_string()));
+        assert!(stale.get_file_write_content().is_none());
+    }
 }
@@ -206,15 +206,60 @@ pub struct ModeBindings {
     pub bindings: HashMap<String, ActionBinding>,
 }
 
+/// Ctrl+\ then n: Ctrl+\ is rarely bound by programs running in the PTY
+const DEFAULT_PTY_ESCAPE: &str = "C-\\ n";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct KeybindingsConfig {
+    /// Space-separated key sequence that always leaves PTY mode, checked before
+    /// anything is passed to the inner program
+    pub pty_escape: String,
     pub normal: ModeBindings,
     pub pty_raw: ModeBindings,
     pub insert: ModeBindings,
     pub command_palette: ModeBindings,
 }
 
+/// Result of feeding one key press to a [`KeySequence`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum SequenceStep {
+    /// The key continues the sequence; hold it back
+    Pending,
+    /// The key finished the sequence
+    Complete,
+    /// The key doesn't continue the sequence; carries the keys held back so far
+    Unmatched(Vec<KeyEvent>),
+}
+
+/// Matches a multi-key sequence one key press at a time
+#[derive(Debug, Default)]
+pub struct KeySequence {
+    held: Vec<KeyEvent>,
+}
+
+impl KeySequence {
+    pub fn feed(&mut self, sequence: &[KeyBinding], key: &KeyEvent) -> SequenceStep {
+        // Legacy terminals report Ctrl+\ as Ctrl+4 (0x1c)
+        let mut normalized = *key;
+        if normalized.code == KeyCode::Char('4') && normalized.modifiers == KeyModifiers::CONTROL {
+            normalized.code = KeyCode::Char('\\');
+        }
+        match sequence.get(self.held.len()) {
+            Some(binding) if binding.matches(&normalized) => {
+                self.held.push(*key);
+                if self.held.len() == sequence.len() {
+                    self.held.clear();
+                    SequenceStep::Complete
+                } else {
+                    SequenceStep::Pending
+                }
+            }
+            _ => SequenceStep::Unmatched(std::mem::take(&mut self.held)),
+        }
+    }
+}
+
 impl Default for KeybindingsConfig {
     fn default() -> Self {
         let mut normal = ModeBindings::default();
@@ -270,11 +315,11 @@ impl Default for KeybindingsConfig {
         );
         normal.bindings.insert(
             "C-u".to_string(),
-            ActionBinding { action: "scroll_page_up".to_string(), args: vec![] },
+            ActionBinding { action: "scroll_half_page_up".to_string(), args: vec![] },
         );
         normal.bindings.insert(
             "C-d".to_string(),
-            ActionBinding { action: "scroll_page_down".to_string(), args: vec![] },
+            ActionBinding { action: "scroll_half_page_down".to_string(), args: vec![] },
         );
         normal.bindings.insert(
             "pageup".to_string(),
@@ -374,6 +419,10 @@ impl Default for KeybindingsConfig {
             "A-a".to_string(),
             ActionBinding { action: "activity_stream_toggle".to_string(), args: vec![] },
         );
+        normal.bindings.insert(
+            "A-m".to_string(),
+            ActionBinding { action: "model_picker_show".to_string(), args: vec![] },
+        );
 
         let mut pty_raw = ModeBindings::default();
         pty_raw.bindings.insert(
@@ -434,6 +483,7 @@ impl Default for KeybindingsConfig {
         );
         
         Self {
+            pty_escape: DEFAULT_PTY_ESCAPE.to_string(),
             normal,
             pty_raw,
             insert: ModeBindings::default(),
@@ -443,6 +493,15 @@ impl Default for KeybindingsConfig {
 }
 
 impl KeybindingsConfig {
+    /// The PTY escape sequence; an empty or unparsable setting uses the default
+    pub fn pty_escape_sequence(&self) -> Vec<KeyBinding> {
+        let parse = |s: &str| -> Option<Vec<KeyBinding>> {
+            let keys: Option<Vec<_>> = s.split_whitespace().map(KeyBinding::from_helix_notation).collect();
+            keys.filter(|k| !k.is_empty())
+        };
+        parse(&self.pty_escape).or_else(|| parse(DEFAULT_PTY_ESCAPE)).unwrap_or_default()
+    }
+
     pub fn get_action(&self, mode: &InputMode, key: &KeyEvent) -> Option<Action> {
         let bindings = match mode {
             InputMode::Normal => &self.normal,
@@ -485,6 +544,9 @@ impl KeybindingsConfig {
             "scroll_page_up" => Some(Action::ScrollPageUp),
             "scroll_page_down" => Some(Action::ScrollPageDown),
             "scroll_to_top" => Some(Action::ScrollToTop),
+            "scroll_half_page_up" => Some(Action::ScrollHalfPageUp),
+            "scroll_half_page_down" => Some(Action::ScrollHalfPageDown),
+            "terminal_reset" => Some(Action::TerminalReset),
             "scroll_to_bottom" => Some(Action::ScrollToBottom),
             "copy" => Some(Action::Copy),
             "paste" => Some(Action::Paste),
@@ -496,8 +558,24 @@ impl KeybindingsConfig {
             "process_select_prev" => Some(Action::ProcessSelectPrev),
             "stream_refresh" => Some(Action::StreamRefresh),
             "llm_cancel" => Some(Action::LlmCancel),
+            "llm_pause" => Some(Action::LlmPause),
+            "llm_resume" => Some(Action::LlmResume),
+            "agent_retry_last_turn" => Some(Action::AgentRetryLastTurn),
             "llm_clear_conversation" => Some(Action::LlmClearConversation),
+            "show_last_llm_exchange" => Some(Action::ShowLastLlmExchange),
+            "generate_crash_report" => Some(Action::GenerateCrashReport),
+            "show_turn_changes" => Some(Action::ShowTurnChanges),
+            "undo_last_edit" => Some(Action::UndoLastEdit),
+            "chat_paste_image" => Some(Action::ChatInputPasteImage),
+            "show_system_prompt" => Some(Action::ShowSystemPrompt),
             "tool_toggle_dangerous_mode" => Some(Action::ToolToggleDangerousMode),
+            "agent_toggle_step_mode" => Some(Action::AgentToggleStepMode),
+            "agent_toggle_auto_continue" => Some(Action::AgentToggleAutoContinue),
+            "agent_toggle_dry_run" => Some(Action::AgentToggleDryRun),
+            "agent_persona" => {
+                let name = args.first().filter(|n| n.as_str() != "default").cloned();
+                Some(Action::AgentSelectPersona(name))
+            }
             "tab_create" => Some(Action::TabCreate),
             "tab_close" => Some(Action::TabClose),
             "tab_next" => Some(Action::TabNext),
@@ -514,7 +592,10 @@ impl KeybindingsConfig {
             "tab_start_rename" => Some(Action::TabStartRename),
             "tab_cancel_rename" => Some(Action::TabCancelRename),
             "config_reload" => Some(Action::ConfigReload),
+            "config_open_dir" => Some(Action::ConfigOpenDir),
+            "config_edit_file" => Some(Action::ConfigEditFile),
             "conversation_toggle" => Some(Action::ConversationToggle),
+            "plan_panel_toggle" => Some(Action::PlanPanelToggle),
             "conversation_scroll_up" => {
                 let n = args.first().and_then(|s| s.parse().ok()).unwrap_or(1);
                 Some(Action::ConversationScrollUp(n))
@@ -525,6 +606,11 @@ impl KeybindingsConfig {
             }
             "conversation_scroll_to_top" => Some(Action::ConversationScrollToTop),
             "conversation_scroll_to_bottom" => Some(Action::ConversationScrollToBottom),
+            // Pager actions
+            "tool_view_output" => Some(Action::ToolCallViewOutput),
+            "log_viewer_open_pager" => Some(Action::LogViewerOpenPager),
+            "log_viewer_toggle_follow" => Some(Action::LogViewerToggleAutoScroll),
+            "stream_viewer_toggle_follow" => Some(Action::StreamViewerToggleAutoScroll),
             // Notification actions (TRC-023)
             "notify_dismiss" => Some(Action::NotifyDismiss),
             "notify_dismiss_all" => Some(Action::NotifyDismissAll),
@@ -536,6 +622,9 @@ impl KeybindingsConfig {
             "pane_resize_left_grow" => Some(Action::PaneResizeLeftGrow),
             "pane_resize_left_shrink" => Some(Action::PaneResizeLeftShrink),
             "pane_reset_layout" => Some(Action::PaneResetLayout),
+            "toggle_compact_mode" => Some(Action::ToggleCompactMode),
+            "overlay_cycle_position" => Some(Action::OverlayCyclePosition),
+            "model_picker_show" => Some(Action::ModelPickerShow),
             // SIRK Panel and Activity Stream actions
             "sirk_panel_toggle" => Some(Action::SirkPanelToggle),
             "sirk_panel_show" => Some(Action::SirkPanelShow),
@@ -552,6 +641,32 @@ impl KeybindingsConfig {
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_pty_escape_sequence() {
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+        let config = KeybindingsConfig::default();
+        let sequence = config.pty_escape_sequence();
+        assert_eq!(sequence.len(), 2);
+
+        let mut matcher = KeySequence::default();
+        assert_eq!(matcher.feed(&sequence, &press(KeyCode::Char('\\'), KeyModifiers::CONTROL)), SequenceStep::Pending);
+        assert_eq!(matcher.feed(&sequence, &press(KeyCode::Char('n'), KeyModifiers::NONE)), SequenceStep::Complete);
+
+        // Ctrl+4 is how legacy terminals deliver Ctrl+\
+        let prefix = press(KeyCode::Char('4'), KeyModifiers::CONTROL);
+        assert_eq!(matcher.feed(&sequence, &prefix), SequenceStep::Pending);
+        assert_eq!(
+            matcher.feed(&sequence, &press(KeyCode::Char('x'), KeyModifiers::NONE)),
+            SequenceStep::Unmatched(vec![prefix])
+        );
+        assert_eq!(matcher.feed(&sequence, &press(KeyCode::Char('n'), KeyModifiers::NONE)), SequenceStep::Unmatched(vec![]));
+
+        let custom = KeybindingsConfig { pty_escape: "C-g".to_string(), ..KeybindingsConfig::default() };
+        assert_eq!(custom.pty_escape_sequence(), vec![KeyBinding::new(KeyCode::Char('g'), KeyModifiers::CONTROL)]);
+        let invalid = KeybindingsConfig { pty_escape: "C-nope".to_string(), ..KeybindingsConfig::default() };
+        assert_eq!(invalid.pty_escape_sequence(), sequence);
+    }
+
     #[test]
     fn test_keybinding_from_helix_notation() {
         let binding = KeyBinding::from_helix_notation("C-c").unwrap();
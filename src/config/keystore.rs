@@ -5,7 +5,7 @@
 use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHasher, SaltString},
@@ -15,7 +15,6 @@ use chacha20poly1305::{
     aead::{Aead, KeyInit},
     ChaCha20Poly1305, Nonce,
 };
-use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -25,6 +24,12 @@ const SERVICE_NAME: &str = "ridge-control";
 const KEYS_DIR: &str = "keys";
 const KEYSTORE_FILE: &str = "keystore.enc";
 const SALT_FILE: &str = "keystore.salt";
+const PLAINTEXT_FILE: &str = "keystore.json";
+/// Keystore entry holding the random data key for encrypted threads
+const THREADS_KEY_ID: &str = "__threads_data_key";
+
+/// Header marking data written by `seal` (followed by nonce and ciphertext)
+pub const SEALED_MAGIC: &[u8] = b"RCSEAL1\n";
 
 /// A secret value that is zeroed on drop
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
@@ -81,6 +86,28 @@ impl KeyId {
             other => KeyId::Custom(other.to_string()),
         }
     }
+
+    /// Standard environment variables holding this provider's key, in lookup order
+    pub fn env_vars(&self) -> &'static [&'static str] {
+        match self {
+            KeyId::Anthropic => &["ANTHROPIC_API_KEY"],
+            KeyId::OpenAI => &["OPENAI_API_KEY"],
+            KeyId::Gemini => &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+            KeyId::Grok => &["GROK_API_KEY", "XAI_API_KEY"],
+            KeyId::Groq => &["GROQ_API_KEY"],
+            KeyId::Custom(_) => &[],
+        }
+    }
+
+    /// First non-empty key found in `env_vars`, with the variable it came from
+    pub fn env_value(&self) -> Option<(&'static str, String)> {
+        self.env_vars().iter().find_map(|var| {
+            std::env::var(var)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+                .map(|value| (*var, value))
+        })
+    }
 }
 
 impl std::fmt::Display for KeyId {
@@ -97,13 +124,28 @@ pub enum KeyStoreResult {
     Error(String),
 }
 
-/// Backend storage strategy
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Backend storage strategy, selected by `[keystore] backend` in config.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum KeyStoreBackend {
     /// Use system keyring (Secret Service on Linux)
+    #[default]
+    #[serde(rename = "keyring")]
     SystemKeyring,
     /// Use encrypted file storage
     EncryptedFile,
+    /// Unencrypted JSON file (0600); for headless machines without a keyring
+    Plaintext,
+}
+
+impl std::fmt::Display for KeyStoreBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyStoreBackend::SystemKeyring => write!(f, "keyring"),
+            KeyStoreBackend::EncryptedFile => write!(f, "encrypted_file"),
+            KeyStoreBackend::Plaintext => write!(f, "plaintext"),
+        }
+    }
 }
 
 /// Encrypted keystore file format
@@ -126,26 +168,47 @@ pub struct KeyStore {
     keys_dir: PathBuf,
     /// Cached master password hash for encrypted backend
     master_key: Option<[u8; 32]>,
+    /// Why the configured backend was replaced, if it was
+    fallback_reason: Option<String>,
 }
 
 impl KeyStore {
-    /// Create a new KeyStore, automatically selecting the best available backend
+    /// Create a new KeyStore using the backend configured in config.toml
     pub fn new() -> Result<Self> {
-        let keys_dir = Self::get_keys_dir()?;
-        
-        // Try system keyring first, fall back to encrypted file
-        let backend = if Self::is_keyring_available() {
-            tracing::info!("Using system keyring for key storage");
-            KeyStoreBackend::SystemKeyring
-        } else {
-            tracing::info!("System keyring unavailable, using encrypted file storage");
-            KeyStoreBackend::EncryptedFile
+        Self::new_in(&super::ConfigManager::get_config_dir()?)
+    }
+
+    /// Like `new`, for the config dir given by `--config-dir`
+    pub fn new_in(config_dir: &Path) -> Result<Self> {
+        let backend = super::ConfigManager::load_app_config(config_dir).keystore.backend;
+        Self::open_in(backend, config_dir)
+    }
+
+    /// Create a KeyStore for the preferred backend, keeping file-backed keys
+    /// under `<config_dir>/keys`. If the system keyring is unavailable, falls
+    /// back to the encrypted file and records the reason (see `fallback_reason`).
+    pub fn open_in(preferred: KeyStoreBackend, config_dir: &Path) -> Result<Self> {
+        let keys_dir = config_dir.join(KEYS_DIR);
+
+        let (backend, fallback_reason) = match preferred {
+            KeyStoreBackend::SystemKeyring if !Self::is_keyring_available() => {
+                tracing::info!("System keyring unavailable, using encrypted file storage");
+                (
+                    KeyStoreBackend::EncryptedFile,
+                    Some("System keyring is unavailable; API keys are stored in the encrypted file instead".to_string()),
+                )
+            }
+            backend => {
+                tracing::info!("Using {} backend for key storage", backend);
+                (backend, None)
+            }
         };
 
         let mut keystore = Self {
             backend,
             keys_dir,
             master_key: None,
+            fallback_reason,
         };
         
         // Auto-initialize encrypted backend with machine-derived key
@@ -205,14 +268,13 @@ impl KeyStore {
             backend,
             keys_dir,
             master_key: None,
+            fallback_reason: None,
         })
     }
 
     /// Get the keys directory path
     fn get_keys_dir() -> Result<PathBuf> {
-        BaseDirs::new()
-            .map(|dirs| dirs.config_dir().join(SERVICE_NAME).join(KEYS_DIR))
-            .ok_or_else(|| RidgeError::Config("Could not determine config directory".to_string()))
+        super::paths::default_config_dir().map(|dir| dir.join(KEYS_DIR))
     }
 
     /// Check if system keyring is available
@@ -238,11 +300,17 @@ impl KeyStore {
         self.backend
     }
 
+    /// Set when the configured backend could not be used
+    pub fn fallback_reason(&self) -> Option<&str> {
+        self.fallback_reason.as_deref()
+    }
+
     /// Store an API key
     pub fn store(&mut self, key_id: &KeyId, secret: &SecretString) -> Result<()> {
         match self.backend {
             KeyStoreBackend::SystemKeyring => self.store_keyring(key_id, secret),
             KeyStoreBackend::EncryptedFile => self.store_encrypted(key_id, secret),
+            KeyStoreBackend::Plaintext => self.store_plaintext(key_id, secret),
         }
     }
 
@@ -251,6 +319,7 @@ impl KeyStore {
         match self.backend {
             KeyStoreBackend::SystemKeyring => self.get_keyring(key_id),
             KeyStoreBackend::EncryptedFile => self.get_encrypted(key_id),
+            KeyStoreBackend::Plaintext => self.get_plaintext(key_id),
         }
     }
 
@@ -259,6 +328,7 @@ impl KeyStore {
         match self.backend {
             KeyStoreBackend::SystemKeyring => self.delete_keyring(key_id),
             KeyStoreBackend::EncryptedFile => self.delete_encrypted(key_id),
+            KeyStoreBackend::Plaintext => self.delete_plaintext(key_id),
         }
     }
 
@@ -267,6 +337,7 @@ impl KeyStore {
         match self.backend {
             KeyStoreBackend::SystemKeyring => self.list_keyring(),
             KeyStoreBackend::EncryptedFile => self.list_encrypted(),
+            KeyStoreBackend::Plaintext => self.list_plaintext(),
         }
     }
 
@@ -301,7 +372,7 @@ impl KeyStore {
     /// Check if the keystore is unlocked
     pub fn is_unlocked(&self) -> bool {
         match self.backend {
-            KeyStoreBackend::SystemKeyring => true,
+            KeyStoreBackend::SystemKeyring | KeyStoreBackend::Plaintext => true,
             KeyStoreBackend::EncryptedFile => self.master_key.is_some(),
         }
     }
@@ -332,6 +403,21 @@ impl KeyStore {
         Ok(())
     }
 
+    /// Data key for encrypting conversation threads at rest. Generated on first
+    /// use and stored in the keystore itself, so it is protected by the keystore
+    /// password and survives `change_password`.
+    pub fn threads_key(&mut self) -> Result<[u8; 32]> {
+        let id = KeyId::Custom(THREADS_KEY_ID.to_string());
+        if let Some(existing) = self.get(&id)? {
+            return decode_key(existing.expose());
+        }
+        let mut key = [0u8; 32];
+        getrandom(&mut key)?;
+        let encoded: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        self.store(&id, &SecretString::new(encoded))?;
+        Ok(key)
+    }
+
     /// Change the master password
     pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
         if self.backend != KeyStoreBackend::EncryptedFile {
@@ -593,8 +679,11 @@ impl KeyStore {
 
     fn list_encrypted(&self) -> Result<Vec<KeyId>> {
         let data = self.load_encrypted_data()?;
-        Ok(data
-            .keys
+        Ok(Self::key_ids(&data))
+    }
+
+    fn key_ids(data: &KeyStoreData) -> Vec<KeyId> {
+        data.keys
             .keys()
             .map(|k| match k.as_str() {
                 "anthropic" => KeyId::Anthropic,
@@ -604,8 +693,107 @@ impl KeyStore {
                 "groq" => KeyId::Groq,
                 other => KeyId::Custom(other.to_string()),
             })
-            .collect())
+            .filter(|id| id.as_str() != THREADS_KEY_ID)
+            .collect()
+    }
+
+    // ─────────────────────────────────────────────────────────────────────────
+    // Plaintext File Backend
+    // ─────────────────────────────────────────────────────────────────────────
+
+    fn plaintext_file(&self) -> PathBuf {
+        self.keys_dir.join(PLAINTEXT_FILE)
     }
+
+    fn load_plaintext_data(&self) -> Result<KeyStoreData> {
+        let path = self.plaintext_file();
+        if !path.exists() {
+            return Ok(KeyStoreData::default());
+        }
+        let json = fs::read_to_string(&path)
+            .map_err(|e| RidgeError::Config(format!("Failed to read keystore: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| RidgeError::Config(format!("Invalid keystore data: {}", e)))
+    }
+
+    fn save_plaintext_data(&self, data: &KeyStoreData) -> Result<()> {
+        self.ensure_keys_dir()?;
+
+        let json = serde_json::to_vec_pretty(data)
+            .map_err(|e| RidgeError::Config(format!("Failed to serialize keystore: {}", e)))?;
+        let path = self.plaintext_file();
+        crate::util::write_atomic_mode(&path, &json, 0o600)
+            .map_err(|e| RidgeError::Config(format!("Failed to write keystore: {}", e)))
+    }
+
+    fn store_plaintext(&mut self, key_id: &KeyId, secret: &SecretString) -> Result<()> {
+        let mut data = self.load_plaintext_data()?;
+        data.keys.insert(key_id.as_str().to_string(), secret.expose().to_string());
+        self.save_plaintext_data(&data)
+    }
+
+    fn get_plaintext(&self, key_id: &KeyId) -> Result<Option<SecretString>> {
+        let data = self.load_plaintext_data()?;
+        Ok(data.keys.get(key_id.as_str()).map(|s| SecretString::new(s.clone())))
+    }
+
+    fn delete_plaintext(&mut self, key_id: &KeyId) -> Result<()> {
+        let mut data = self.load_plaintext_data()?;
+        data.keys.remove(key_id.as_str());
+        self.save_plaintext_data(&data)
+    }
+
+    fn list_plaintext(&self) -> Result<Vec<KeyId>> {
+        Ok(Self::key_ids(&self.load_plaintext_data()?))
+    }
+}
+
+fn decode_key(hex: &str) -> Result<[u8; 32]> {
+    let invalid = || RidgeError::Config("Stored threads key is corrupt".to_string());
+    if hex.len() != 64 {
+        return Err(invalid());
+    }
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(key)
+}
+
+/// Whether `data` was produced by `seal`
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(SEALED_MAGIC)
+}
+
+/// Encrypt with ChaCha20-Poly1305: `SEALED_MAGIC || nonce || ciphertext`
+pub fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce_bytes = [0u8; 12];
+    getrandom(&mut nonce_bytes)?;
+    let cipher = ChaCha20Poly1305::new_from_slice(key)
+        .map_err(|e| RidgeError::Config(format!("Failed to create cipher: {}", e)))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|e| RidgeError::Config(format!("Failed to encrypt: {}", e)))?;
+
+    let mut sealed = Vec::with_capacity(SEALED_MAGIC.len() + nonce_bytes.len() + ciphertext.len());
+    sealed.extend_from_slice(SEALED_MAGIC);
+    sealed.extend_from_slice(&nonce_bytes);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypt data produced by `seal`
+pub fn unseal(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>> {
+    let body = sealed
+        .strip_prefix(SEALED_MAGIC)
+        .filter(|body| body.len() > 12)
+        .ok_or_else(|| RidgeError::Config("Not an encrypted file".to_string()))?;
+    let (nonce, ciphertext) = body.split_at(12);
+    let cipher = ChaCha20Poly1305::new_from_slice(key)
+        .map_err(|e| RidgeError::Config(format!("Failed to create cipher: {}", e)))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| RidgeError::Config("Failed to decrypt (wrong key?)".to_string()))
 }
 
 /// Get random bytes using the OS random source
@@ -670,6 +858,7 @@ mod tests {
             backend: KeyStoreBackend::EncryptedFile,
             keys_dir: keys_dir.clone(),
             master_key: None,
+            fallback_reason: None,
         };
         
         // Initialize with test password
@@ -707,6 +896,13 @@ mod tests {
         assert_eq!(KeyId::Custom("mykey".to_string()).as_str(), "mykey");
     }
 
+    #[test]
+    fn test_key_id_env_vars() {
+        assert_eq!(KeyId::Grok.env_vars(), ["GROK_API_KEY", "XAI_API_KEY"]);
+        assert_eq!(KeyId::Gemini.env_vars()[0], "GEMINI_API_KEY");
+        assert!(KeyId::Custom("brave_search".to_string()).env_value().is_none());
+    }
+
     #[test]
     fn test_key_id_from_provider_str() {
         assert_eq!(KeyId::from_provider_str("anthropic"), KeyId::Anthropic);
@@ -784,6 +980,19 @@ mod tests {
         assert_eq!(parsed.ciphertext, vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    fn test_threads_key_is_stable_and_hidden() {
+        let (mut store, _temp) = test_keystore();
+        let key = store.threads_key().unwrap();
+        assert_eq!(store.threads_key().unwrap(), key);
+        assert!(store.list().unwrap().is_empty(), "data key is not an API key");
+
+        let sealed = seal(&key, b"secret thread").unwrap();
+        assert!(is_sealed(&sealed));
+        assert_eq!(unseal(&key, &sealed).unwrap(), b"secret thread");
+        assert!(unseal(&[7u8; 32], &sealed).is_err());
+    }
+
     #[test]
     fn test_key_derivation_produces_consistent_results() {
         let temp_dir = TempDir::new().unwrap();
@@ -793,6 +1002,7 @@ mod tests {
             backend: KeyStoreBackend::EncryptedFile,
             keys_dir,
             master_key: None,
+            fallback_reason: None,
         };
         
         let salt = SaltString::from_b64("dGVzdHNhbHQxMjM0NQ").unwrap();
@@ -812,6 +1022,7 @@ mod tests {
             backend: KeyStoreBackend::EncryptedFile,
             keys_dir,
             master_key: None,
+            fallback_reason: None,
         };
         
         let salt = SaltString::from_b64("dGVzdHNhbHQxMjM0NQ").unwrap();
@@ -821,4 +1032,40 @@ mod tests {
         
         assert_ne!(key1, key2);
     }
+
+    #[test]
+    fn test_plaintext_backend_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = KeyStore {
+            backend: KeyStoreBackend::Plaintext,
+            keys_dir: temp_dir.path().join("keys"),
+            master_key: None,
+            fallback_reason: None,
+        };
+
+        assert!(store.is_unlocked());
+        store.store(&KeyId::Groq, &SecretString::new("gsk-test")).unwrap();
+        assert_eq!(store.get(&KeyId::Groq).unwrap().unwrap().expose(), "gsk-test");
+        assert_eq!(store.list().unwrap(), vec![KeyId::Groq]);
+
+        store.delete(&KeyId::Groq).unwrap();
+        assert!(!store.exists(&KeyId::Groq).unwrap());
+    }
+
+    #[test]
+    fn test_backend_config_names() {
+        #[derive(Deserialize)]
+        struct Section {
+            backend: KeyStoreBackend,
+        }
+        for (name, expected) in [
+            ("keyring", KeyStoreBackend::SystemKeyring),
+            ("encrypted_file", KeyStoreBackend::EncryptedFile),
+            ("plaintext", KeyStoreBackend::Plaintext),
+        ] {
+            let section: Section = toml::from_str(&format!("backend = \"{}\"", name)).unwrap();
+            assert_eq!(section.backend, expected);
+            assert_eq!(expected.to_string(), name);
+        }
+    }
 }
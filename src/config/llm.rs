@@ -14,10 +14,44 @@ use crate::error::{RidgeError, Result};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LLMConfig {
+    /// Models tried in order when the default model fails after retries
+    /// (e.g. `fallback_models = ["claude-3-5-haiku-20241022", "gpt-4o"]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback_models: Vec<String>,
+    /// Providers tried in order, on their `default_model`, after the fallback
+    /// models fail or the primary provider rejects its API key
+    /// (e.g. `fallback_providers = ["openai"]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback_providers: Vec<String>,
+    /// Retries on the same model after a transient error (429, 5xx, overload)
+    /// that arrives before any output, before moving to the fallback models
+    pub max_retries: u32,
+    /// PEM bundle of extra root certificates (TLS-intercepting proxies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<std::path::PathBuf>,
+    /// Record in-session provider/model switches as the new defaults, so the
+    /// last-used model is restored on launch. New threads start on the defaults;
+    /// a resumed thread keeps its own saved model unless it is unavailable.
+    pub persist_model_switch: bool,
     /// Default provider and model
     pub defaults: LLMDefaults,
     /// LLM inference parameters
     pub parameters: LLMParameters,
+    /// Extended thinking / reasoning output (models that support it; ignored elsewhere)
+    pub thinking: LLMThinking,
+    /// USD per million tokens, keyed by model id or id prefix, for usage reports
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub pricing: HashMap<String, ModelPricing>,
+    /// Headers added to every hosted-provider request (gateway auth, routing)
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Keep the last request body and streamed response for the debug view
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub debug_capture: bool,
+    /// Mark the system prompt and tool definitions as cacheable (Anthropic
+    /// prompt caching; only some models support it, so off by default)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub prompt_cache: bool,
     /// Per-provider configuration (default models, etc.)
     #[serde(flatten)]
     pub providers: HashMap<String, ProviderConfig>,
@@ -29,49 +63,59 @@ impl Default for LLMConfig {
         providers.insert(
             "anthropic".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "claude-sonnet-4-20250514".to_string(),
+                ..Default::default()
             },
         );
         providers.insert(
             "openai".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "gpt-5.2-2025-12-11".to_string(),
+                ..Default::default()
             },
         );
         providers.insert(
             "gemini".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "gemini-2.5-flash".to_string(),
+                ..Default::default()
             },
         );
         providers.insert(
             "grok".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "grok-4-fast-non-reasoning".to_string(),
+                ..Default::default()
             },
         );
         providers.insert(
             "groq".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "llama-3.3-70b-versatile".to_string(),
+                ..Default::default()
             },
         );
         providers.insert(
             "ollama".to_string(),
             ProviderConfig {
-                base_url: None,
                 default_model: "qwen3:8b".to_string(),
+                ..Default::default()
             },
         );
 
         Self {
+            fallback_models: Vec::new(),
+            fallback_providers: Vec::new(),
+            max_retries: 2,
+            ca_bundle: None,
+            persist_model_switch: true,
             defaults: LLMDefaults::default(),
             parameters: LLMParameters::default(),
+            thinking: LLMThinking::default(),
+            pricing: HashMap::new(),
+            extra_headers: HashMap::new(),
+            debug_capture: false,
+            prompt_cache: false,
             providers,
         }
     }
@@ -115,15 +159,95 @@ impl Default for LLMParameters {
     }
 }
 
+/// Token prices for a model, in USD per million tokens
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    /// Prompt-cache writes; defaults to 1.25x the input price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_per_mtok: Option<f64>,
+    /// Prompt-cache reads; defaults to 0.1x the input price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_per_mtok: Option<f64>,
+}
+
+impl ModelPricing {
+    /// USD for the given token counts
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_mtok + output_tokens as f64 * self.output_per_mtok) / 1_000_000.0
+    }
+
+    /// USD for input tokens written to and read from the prompt cache
+    pub fn cache_cost(&self, write_tokens: u64, read_tokens: u64) -> f64 {
+        let write = self.cache_write_per_mtok.unwrap_or(self.input_per_mtok * 1.25);
+        let read = self.cache_read_per_mtok.unwrap_or(self.input_per_mtok * 0.1);
+        (write_tokens as f64 * write + read_tokens as f64 * read) / 1_000_000.0
+    }
+}
+
+/// Extended thinking settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LLMThinking {
+    /// Request a thinking budget from models that support it
+    pub enabled: bool,
+    /// Tokens the model may spend thinking (Anthropic minimum is 1024)
+    pub budget_tokens: u32,
+}
+
+impl Default for LLMThinking {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budget_tokens: 4096,
+        }
+    }
+}
+
 /// Per-provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct ProviderConfig {
     /// Default model for this provider
     pub default_model: String,
-    /// Base URL for local providers (ollama, llama-server)
+    /// API root for this provider, e.g. a LiteLLM proxy or corporate gateway
+    /// (`https://gateway.internal/anthropic/v1`); for ollama/llama-server, the server URL
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_url: Option<String>,
+    /// `api-version` query parameter added to every request; for Azure OpenAI,
+    /// together with a deployment base URL
+    /// (`https://{resource}.openai.azure.com/openai/deployments/{deployment}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// How the API key is sent (openai only; Azure OpenAI wants `api_key`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthScheme>,
+}
+
+/// How a provider's API key goes on the request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <key>`
+    #[default]
+    Bearer,
+    /// `api-key: <key>`
+    ApiKey,
+}
+
+/// Validate a base URL override: http(s) with a host. Returns the URL without
+/// a trailing slash, or None when blank (use the provider default).
+pub fn normalize_base_url(url: &str) -> std::result::Result<Option<String>, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid base URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!("Invalid base URL '{}': expected http(s)://host/...", url));
+    }
+    Ok(Some(url.trim_end_matches('/').to_string()))
 }
 
 impl LLMConfig {
@@ -166,6 +290,17 @@ impl LLMConfig {
             .map(|p| p.default_model.as_str())
     }
 
+    /// `fallback_providers` with each one's configured default model, if any
+    pub fn fallback_provider_models(&self) -> Vec<(String, Option<String>)> {
+        self.fallback_providers
+            .iter()
+            .map(|name| {
+                let model = self.default_model_for_provider(name).filter(|m| !m.is_empty());
+                (name.clone(), model.map(str::to_string))
+            })
+            .collect()
+    }
+
     /// Set the default model for a specific provider
     pub fn set_default_model_for_provider(&mut self, provider: &str, model: &str) {
         self.providers
@@ -174,6 +309,53 @@ impl LLMConfig {
             .default_model = model.to_string();
     }
 
+    /// Validated base URL override for a provider; invalid values are logged
+    /// and ignored so the provider falls back to its default endpoint
+    pub fn base_url_for_provider(&self, provider: &str) -> Option<String> {
+        let url = self.providers.get(provider)?.base_url.as_deref()?;
+        match normalize_base_url(url) {
+            Ok(url) => url,
+            Err(e) => {
+                tracing::warn!("Ignoring {} base_url: {}", provider, e);
+                None
+            }
+        }
+    }
+
+    /// `api-version` for a provider; blank counts as unset
+    pub fn api_version_for_provider(&self, provider: &str) -> Option<String> {
+        let version = self.providers.get(provider)?.api_version.as_deref()?.trim();
+        (!version.is_empty()).then(|| version.to_string())
+    }
+
+    /// Auth scheme for a provider (Bearer unless llm.toml says otherwise)
+    pub fn auth_for_provider(&self, provider: &str) -> AuthScheme {
+        self.providers.get(provider).and_then(|p| p.auth).unwrap_or_default()
+    }
+
+    /// Set or clear (None) a provider's base URL override
+    pub fn set_base_url_for_provider(&mut self, provider: &str, base_url: Option<String>) {
+        self.providers.entry(provider.to_string()).or_default().base_url = base_url;
+    }
+
+    /// Apply an in-session provider and/or model switch to the defaults when
+    /// `persist_model_switch` is on. Returns true if the defaults changed.
+    pub fn record_model_switch(&mut self, provider: Option<&str>, model: Option<&str>) -> bool {
+        if !self.persist_model_switch {
+            return false;
+        }
+        let mut changed = false;
+        if let Some(provider) = provider.filter(|p| *p != self.defaults.provider) {
+            self.defaults.provider = provider.to_string();
+            changed = true;
+        }
+        if let Some(model) = model.filter(|m| *m != self.defaults.model) {
+            self.defaults.model = model.to_string();
+            changed = true;
+        }
+        changed
+    }
+
     /// Get list of configured provider names
     pub fn configured_providers(&self) -> Vec<&str> {
         self.providers.keys().map(|s| s.as_str()).collect()
@@ -257,6 +439,29 @@ mod tests {
         assert_eq!(loaded.parameters.max_tokens, 4096);
     }
 
+    #[test]
+    fn test_model_switch_persists_as_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("llm.toml");
+
+        let mut config = LLMConfig::default();
+        assert!(config.record_model_switch(Some("openai"), Some("gpt-4o")));
+        assert!(!config.record_model_switch(None, Some("gpt-4o")));
+        config.save(&path).unwrap();
+
+        let mut loaded = LLMConfig::load(&path).unwrap();
+        assert_eq!(loaded.defaults.provider, "openai");
+        assert_eq!(loaded.defaults.model, "gpt-4o");
+
+        // With the flag off, switches stay in-session only
+        loaded.persist_model_switch = false;
+        assert!(!loaded.record_model_switch(Some("gemini"), Some("gemini-2.5-pro")));
+        loaded.save(&path).unwrap();
+        let reloaded = LLMConfig::load(&path).unwrap();
+        assert!(!reloaded.persist_model_switch);
+        assert_eq!(reloaded.defaults.model, "gpt-4o");
+    }
+
     #[test]
     fn test_set_default_model_for_provider() {
         let mut config = LLMConfig::default();
@@ -317,4 +522,118 @@ default_model = "gpt-4-turbo"
             Some("gpt-4-turbo")
         );
     }
+
+    #[test]
+    fn test_azure_openai_settings_round_trip() {
+        let toml_content = r#"
+[openai]
+default_model = "gpt-4o"
+base_url = "https://acme.openai.azure.com/openai/deployments/gpt-4o"
+api_version = "2024-10-21"
+auth = "api_key"
+"#;
+
+        let config: LLMConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.api_version_for_provider("openai").as_deref(), Some("2024-10-21"));
+        assert_eq!(config.auth_for_provider("openai"), AuthScheme::ApiKey);
+        assert_eq!(config.auth_for_provider("anthropic"), AuthScheme::Bearer);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("llm.toml");
+        config.save(&path).unwrap();
+        let reloaded = LLMConfig::load(&path).unwrap();
+        assert_eq!(reloaded.api_version_for_provider("openai").as_deref(), Some("2024-10-21"));
+        assert_eq!(reloaded.auth_for_provider("openai"), AuthScheme::ApiKey);
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("auth = \"bearer\""));
+    }
+
+    #[test]
+    fn test_thinking_parses() {
+        let toml_content = r#"
+[thinking]
+enabled = true
+budget_tokens = 10000
+"#;
+
+        let config: LLMConfig = toml::from_str(toml_content).unwrap();
+        assert!(config.thinking.enabled);
+        assert_eq!(config.thinking.budget_tokens, 10000);
+        assert!(!config.providers.contains_key("thinking"));
+        assert!(!LLMConfig::default().thinking.enabled);
+    }
+
+    #[test]
+    fn test_pricing_parses() {
+        let toml_content = r#"
+[pricing.claude-sonnet-4]
+input_per_mtok = 3.0
+output_per_mtok = 15.0
+"#;
+
+        let config: LLMConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.pricing["claude-sonnet-4"].output_per_mtok, 15.0);
+        assert!(!config.providers.contains_key("pricing"));
+        assert!(LLMConfig::default().pricing.is_empty());
+    }
+
+    #[test]
+    fn test_fallback_models_round_trip() {
+        let toml_content = r#"
+fallback_models = ["claude-3-5-haiku-20241022", "gpt-4o"]
+
+[defaults]
+provider = "anthropic"
+model = "claude-sonnet-4-20250514"
+"#;
+
+        let config: LLMConfig = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.fallback_models, vec!["claude-3-5-haiku-20241022", "gpt-4o"]);
+        assert!(!config.providers.contains_key("fallback_models"));
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let parsed: LLMConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.fallback_models, config.fallback_models);
+    }
+
+    #[test]
+    fn test_fallback_providers_use_configured_models() {
+        let config: LLMConfig = toml::from_str(
+            "fallback_providers = [\"openai\", \"groq\"]\n\n[openai]\ndefault_model = \"gpt-4.1\"\n",
+        )
+        .unwrap();
+        assert!(!config.providers.contains_key("fallback_providers"));
+        assert_eq!(
+            config.fallback_provider_models(),
+            vec![("openai".to_string(), Some("gpt-4.1".to_string())), ("groq".to_string(), None)]
+        );
+    }
+
+    #[test]
+    fn test_base_url_override() {
+        let config: LLMConfig = toml::from_str(
+            "[anthropic]\ndefault_model = \"claude-sonnet-4\"\nbase_url = \"https://gateway.internal/anthropic/v1/\"\n\n[openai]\nbase_url = \"not a url\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.base_url_for_provider("anthropic").as_deref(),
+            Some("https://gateway.internal/anthropic/v1")
+        );
+        // Invalid and unset overrides fall back to the provider default
+        assert_eq!(config.base_url_for_provider("openai"), None);
+        assert_eq!(config.base_url_for_provider("groq"), None);
+
+        assert_eq!(normalize_base_url("  "), Ok(None));
+        assert!(normalize_base_url("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn test_http_settings_parse() {
+        let config: LLMConfig = toml::from_str(
+            "ca_bundle = \"/etc/ssl/corp.pem\"\n\n[extra_headers]\nX-Team = \"platform\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.ca_bundle.as_deref(), Some(Path::new("/etc/ssl/corp.pem")));
+        assert_eq!(config.extra_headers.get("X-Team").map(String::as_str), Some("platform"));
+        assert!(!config.providers.contains_key("extra_headers"));
+    }
 }
@@ -7,28 +7,32 @@ mod keystore;
 mod llm;
 pub mod lsp;
 mod mandrel;
+mod paths;
+mod prompts;
 mod session;
 mod subagent;
 mod theme;
 mod watcher;
 
-pub use keybindings::KeybindingsConfig;
-pub use keystore::{KeyId, KeyStore, SecretString};
-pub use llm::LLMConfig;
+pub use keybindings::{KeySequence, KeybindingsConfig, SequenceStep};
+pub use keystore::{is_sealed, seal, unseal, KeyId, KeyStore, KeyStoreBackend, SecretString};
+pub use llm::{normalize_base_url, AuthScheme, LLMConfig, ModelPricing};
 pub use lsp::LspConfig;
 pub use mandrel::{MandrelConfig, MandrelError};
-pub use session::{SessionData, SessionManager};
+pub use paths::{default_config_dir, log_dir, resolve_data_dir, resolve_log_dir};
+pub use prompts::{PersonaTemplate, PromptsConfig};
+use session::SESSION_FILE;
+pub use session::{SessionData, SessionManager, ViewerPrefs};
 pub use subagent::{SubagentConfig, SubagentsConfig};
 pub use theme::Theme;
 pub use watcher::{ConfigWatcherMode, ConfigEvent};
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use directories::BaseDirs;
 
 use crate::error::{RidgeError, Result};
 
-const CONFIG_DIR: &str = "ridge-control";
 const MAIN_CONFIG_FILE: &str = "config.toml";
 const KEYBINDINGS_FILE: &str = "keybindings.toml";
 const THEME_FILE: &str = "theme.toml";
@@ -36,6 +40,9 @@ const LLM_CONFIG_FILE: &str = "llm.toml";
 const SUBAGENT_CONFIG_FILE: &str = "subagents.toml";
 const MANDREL_CONFIG_FILE: &str = "mandrel.toml";
 const LSP_CONFIG_FILE: &str = "lsp.toml";
+const PROMPTS_FILE: &str = "prompts.toml";
+const MODELS_FILE: &str = "models.toml";
+const CHAT_HISTORY_FILE: &str = "chat_history.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(Default)]
@@ -44,6 +51,16 @@ pub struct AppConfig {
     pub general: GeneralConfig,
     pub terminal: TerminalConfig,
     pub process_monitor: ProcessMonitorConfig,
+    pub prompt: PromptConfig,
+    pub tools: ToolsConfig,
+    pub conversation: ConversationConfig,
+    pub ask_user: AskUserConfig,
+    pub logs: LogsConfig,
+    pub keystore: KeystoreConfig,
+    pub ui: UiConfig,
+    pub notifications: NotificationsConfig,
+    pub agent: AgentStorageConfig,
+    pub session: SessionConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +71,8 @@ pub struct GeneralConfig {
     pub log_file: Option<PathBuf>,
     pub watch_config: bool,
     pub config_watch_debounce_ms: u64,
+    /// Reopen the last active conversation thread when the session is restored
+    pub restore_last_thread: bool,
 }
 
 impl Default for GeneralConfig {
@@ -64,6 +83,7 @@ impl Default for GeneralConfig {
             log_file: None,
             watch_config: true,
             config_watch_debounce_ms: 2000,
+            restore_last_thread: true,
         }
     }
 }
@@ -75,6 +95,8 @@ pub struct TerminalConfig {
     pub shell: Option<String>,
     pub shell_args: Vec<String>,
     pub term_env: String,
+    /// Bytes the PTY I/O thread reads per syscall
+    pub pty_read_buf: usize,
 }
 
 impl Default for TerminalConfig {
@@ -84,10 +106,341 @@ impl Default for TerminalConfig {
             shell: None,
             shell_args: vec![],
             term_env: "xterm-256color".to_string(),
+            pty_read_buf: 16 * 1024,
         }
     }
 }
 
+/// What the system prompt may reveal about this machine
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptConfig {
+    /// Show paths under the home directory as `~/...`
+    pub redact_paths: bool,
+    /// Replace the username and hostname with placeholders
+    pub redact_user: bool,
+    /// Leave the repository root path out of the prompt
+    pub omit_repo_path: bool,
+}
+
+/// Conversation panel behavior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversationConfig {
+    /// Open the conversation panel when a response starts streaming
+    pub auto_open: bool,
+    /// "Delete Old Threads" removes saved threads not updated in this many days
+    pub thread_retention_days: u32,
+    /// Ask before sending a message that would overflow the context window
+    pub warn_context_overflow: bool,
+}
+
+impl Default for ConversationConfig {
+    fn default() -> Self {
+        Self {
+            auto_open: true,
+            thread_retention_days: 30,
+            warn_context_overflow: true,
+        }
+    }
+}
+
+/// ask_user dialog behavior
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AskUserConfig {
+    /// Seconds before unanswered questions fall back to their defaults
+    /// (0 = wait indefinitely). The tool's `timeout_secs` input overrides it.
+    pub default_timeout_secs: u64,
+}
+
+/// API key storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeystoreConfig {
+    /// "keyring", "encrypted_file" or "plaintext". An unavailable keyring
+    /// falls back to the encrypted file.
+    pub backend: KeyStoreBackend,
+    /// Register providers from ANTHROPIC_API_KEY etc. at startup
+    pub import_env: bool,
+}
+
+impl Default for KeystoreConfig {
+    fn default() -> Self {
+        Self {
+            backend: KeyStoreBackend::default(),
+            import_env: true,
+        }
+    }
+}
+
+/// Terminal input polling and animations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Longest input poll interval once the app has been idle for a while.
+    /// Lower values react faster after long pauses; higher values save battery.
+    pub idle_poll_ms: u64,
+    /// Spinner frame set: braille, dots, line, arc, ascii (or any other spinner style name).
+    /// Use "ascii" if your terminal font doesn't render braille.
+    pub spinner_style: String,
+    /// Milliseconds per spinner frame (unset: the style's own speed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spinner_interval_ms: Option<u64>,
+    /// Where the stream/log/config overlays open: center, right, left or full
+    pub overlay_position: OverlayPosition,
+    /// Overlay width in percent of the content area (unset: per-panel default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlay_width_percent: Option<u16>,
+    /// Overlay height in percent (center position only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlay_height_percent: Option<u16>,
+    /// Overlays open at once (tiled side by side); opening one more closes the oldest
+    pub max_overlays: usize,
+    /// Lines scrolled per mouse-wheel notch in the terminal and viewers
+    pub scroll_lines: u16,
+    /// Lines of wheel scrolling up the conversation, log and stream viewers
+    /// absorb before they stop following new output (0 = any scroll pauses)
+    pub scroll_lock_threshold: u16,
+    /// Graphics protocol for image tool results: off, auto, kitty or sixel
+    pub image_protocol: ImageProtocolSetting,
+    /// Key that sends a chat message: auto, ctrl_enter, alt_enter or enter.
+    /// With ctrl_enter/alt_enter, Enter inserts a newline. auto is ctrl_enter
+    /// when the terminal reports modified Enter keys, alt_enter otherwise.
+    pub chat_send_key: ChatSendKey,
+    /// Emacs keys in the chat input: Ctrl+A/Ctrl+E line start/end, Alt+B/Alt+F
+    /// word movement. Off: Ctrl+A selects all.
+    pub chat_emacs_keys: bool,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            idle_poll_ms: 250,
+            spinner_style: "braille".to_string(),
+            spinner_interval_ms: None,
+            overlay_position: OverlayPosition::default(),
+            overlay_width_percent: None,
+            overlay_height_percent: None,
+            max_overlays: 2,
+            scroll_lines: 3,
+            scroll_lock_threshold: 3,
+            image_protocol: ImageProtocolSetting::default(),
+            chat_send_key: ChatSendKey::default(),
+            chat_emacs_keys: true,
+        }
+    }
+}
+
+/// Placement of the stream/log/config overlays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayPosition {
+    #[default]
+    Center,
+    Right,
+    Left,
+    Full,
+}
+
+/// Terminal graphics protocol setting for image tool results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocolSetting {
+    /// Always show the text placeholder
+    #[default]
+    Off,
+    /// Detect from the terminal environment at startup
+    Auto,
+    Kitty,
+    Sixel,
+}
+
+/// Key that sends the chat input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatSendKey {
+    /// Ctrl+Enter where the terminal can tell it apart, else Alt+Enter
+    #[default]
+    Auto,
+    /// Ctrl+Enter (without keyboard enhancement terminals send Ctrl+J, which also sends)
+    CtrlEnter,
+    AltEnter,
+    /// Plain Enter; Shift/Alt/Ctrl+Enter or Ctrl+J insert a newline
+    Enter,
+}
+
+impl ChatSendKey {
+    /// The key actually used, given whether the terminal accepted keyboard
+    /// enhancement (needed to report Ctrl+Enter as such)
+    pub fn resolve(self, keyboard_enhanced: bool) -> Self {
+        match self {
+            ChatSendKey::Auto if keyboard_enhanced => ChatSendKey::CtrlEnter,
+            ChatSendKey::Auto => ChatSendKey::AltEnter,
+            key => key,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChatSendKey::Auto => "Auto",
+            ChatSendKey::CtrlEnter => "Ctrl+Enter",
+            ChatSendKey::AltEnter => "Alt+Enter",
+            ChatSendKey::Enter => "Enter",
+        }
+    }
+}
+
+/// Where conversation threads are stored
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AgentStorageConfig {
+    /// Thread directory (default: <data dir>/threads). `--threads-dir` overrides it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads_dir: Option<PathBuf>,
+    /// Encrypt thread files with a data key kept in the keystore
+    pub encrypt_threads: bool,
+}
+
+/// Where the tab/session state is stored
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Directory holding session.toml (default: the data dir)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+}
+
+/// A storage directory resolved from config, with the reason if it fell back
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageDir {
+    pub path: PathBuf,
+    /// Set when the configured directory was unusable and the default was used
+    pub warning: Option<String>,
+}
+
+/// Desktop notifications for events that happen while the app is in the background
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Master switch; off by default
+    pub desktop: bool,
+    /// Agent finished its turn
+    pub turn_complete: bool,
+    /// A tool call is waiting for confirmation
+    pub tool_confirmation: bool,
+    /// A stream disconnected without reconnecting
+    pub stream_disconnect: bool,
+    /// The LLM request failed
+    pub llm_error: bool,
+    /// Ring the bell when the agent finishes a turn
+    pub bell_on_complete: bool,
+    /// Ring the bell when a tool call needs confirmation
+    pub bell_on_confirm: bool,
+    /// Shell command to play instead of the terminal BEL (e.g. "paplay done.oga")
+    pub bell_command: Option<String>,
+    /// Turns shorter than this don't ring on completion
+    pub bell_min_turn_secs: u64,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            turn_complete: true,
+            tool_confirmation: true,
+            stream_disconnect: true,
+            llm_error: true,
+            bell_on_complete: false,
+            bell_on_confirm: false,
+            bell_command: None,
+            bell_min_turn_secs: 10,
+        }
+    }
+}
+
+/// Timestamp column in the log viewer and activity stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogsConfig {
+    /// Show the timestamp column
+    pub show_timestamps: bool,
+    /// "absolute" (12:34:56.789) or "relative" (12s ago)
+    pub timestamp_format: TimestampFormat,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            show_timestamps: true,
+            timestamp_format: TimestampFormat::Absolute,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampFormat {
+    #[default]
+    Absolute,
+    Relative,
+}
+
+impl TimestampFormat {
+    /// Width every rendered timestamp is padded to, so columns line up
+    pub const WIDTH: usize = 12;
+
+    /// Render `at` as seen at `now`, padded to `WIDTH`
+    pub fn format(self, at: chrono::DateTime<chrono::Local>, now: chrono::DateTime<chrono::Local>) -> String {
+        let text = match self {
+            TimestampFormat::Absolute => at.format("%H:%M:%S%.3f").to_string(),
+            TimestampFormat::Relative => {
+                let secs = (now - at).num_seconds();
+                match secs {
+                    ..=0 => "now".to_string(),
+                    1..=59 => format!("{}s ago", secs),
+                    60..=3599 => format!("{}m ago", secs / 60),
+                    3600..=86_399 => format!("{}h ago", secs / 3600),
+                    _ => format!("{}d ago", secs / 86_400),
+                }
+            }
+        };
+        format!("{:>width$}", text, width = Self::WIDTH)
+    }
+}
+
+/// How tool definitions are sent to the model, and how confirmations time out
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// Send one-sentence tool descriptions without per-parameter docs
+    pub compact_descriptions: bool,
+    /// Auto-answer tool confirmations after this many seconds (0 = wait for the user)
+    pub confirm_countdown_secs: u64,
+    /// Answer given when the countdown runs out
+    pub confirm_default: ConfirmDefault,
+    /// Per-tool overrides of `confirm_default`, keyed by tool name
+    pub confirm_defaults: HashMap<String, ConfirmDefault>,
+    /// In dry-run mode, describe read-only tools too instead of running them
+    pub dry_run_intercept_reads: bool,
+}
+
+impl ToolsConfig {
+    /// Countdown answer for `tool`
+    pub fn confirm_default_for(&self, tool: &str) -> ConfirmDefault {
+        self.confirm_defaults.get(tool).copied().unwrap_or(self.confirm_default)
+    }
+}
+
+/// What an unanswered tool confirmation resolves to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmDefault {
+    Approve,
+    #[default]
+    Deny,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ProcessMonitorConfig {
@@ -112,6 +465,10 @@ impl Default for ProcessMonitorConfig {
 
 pub struct ConfigManager {
     config_dir: PathBuf,
+    /// Threads and session state
+    data_dir: PathBuf,
+    /// Rotating logs; see `resolve_log_dir`
+    log_dir: PathBuf,
     app_config: AppConfig,
     keybindings: KeybindingsConfig,
     theme: Theme,
@@ -119,12 +476,19 @@ pub struct ConfigManager {
     subagent_config: SubagentsConfig,
     mandrel_config: MandrelConfig,
     lsp_config: LspConfig,
+    prompts_config: PromptsConfig,
 }
 
 impl ConfigManager {
     pub fn new() -> Result<Self> {
-        let config_dir = Self::get_config_dir()?;
+        let data_dir = resolve_data_dir(None, None)?;
+        Ok(Self::new_in(Self::get_config_dir()?).with_data_dir(data_dir))
+    }
 
+    /// Load every config file from `config_dir` instead of the XDG config dir
+    /// (`--config-dir`). Threads and session state are stored there too unless
+    /// `with_data_dir` moves them; logs stay in the XDG data dir.
+    pub fn new_in(config_dir: PathBuf) -> Self {
         let app_config = Self::load_app_config(&config_dir);
         let keybindings = Self::load_keybindings(&config_dir);
         let theme = Self::load_theme(&config_dir);
@@ -132,8 +496,11 @@ impl ConfigManager {
         let subagent_config = Self::load_subagent_config(&config_dir);
         let mandrel_config = Self::load_mandrel_config(&config_dir);
         let lsp_config = Self::load_lsp_config(&config_dir);
+        let prompts_config = Self::load_prompts_config(&config_dir);
 
-        Ok(Self {
+        Self {
+            data_dir: config_dir.clone(),
+            log_dir: resolve_log_dir(None).unwrap_or_else(|_| log_dir(&config_dir)),
             config_dir,
             app_config,
             keybindings,
@@ -142,12 +509,77 @@ impl ConfigManager {
             subagent_config,
             mandrel_config,
             lsp_config,
-        })
+            prompts_config,
+        }
     }
     
     pub fn config_dir(&self) -> &Path {
         &self.config_dir
     }
+
+    /// Store logs, threads and session state in `data_dir` (`--data-dir`)
+    pub fn with_data_dir(mut self, data_dir: PathBuf) -> Self {
+        self.log_dir = log_dir(&data_dir);
+        self.data_dir = data_dir;
+        self
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+
+    /// Messages sent from the chat input, for recall
+    pub fn chat_history_path(&self) -> PathBuf {
+        self.data_dir.join(CHAT_HISTORY_FILE)
+    }
+
+    /// Path of the main `config.toml` (may not exist yet)
+    pub fn main_config_path(&self) -> PathBuf {
+        self.config_dir.join(MAIN_CONFIG_FILE)
+    }
+
+    /// Path of `models.toml`, user additions to the model catalog (optional)
+    pub fn models_path(&self) -> PathBuf {
+        self.config_dir.join(MODELS_FILE)
+    }
+
+    /// Thread storage directory: `cli_override`, else `agent.threads_dir`, else
+    /// `<data dir>/threads`. Created if missing.
+    pub fn threads_dir(&self, cli_override: Option<&Path>) -> StorageDir {
+        let configured = cli_override.or(self.app_config.agent.threads_dir.as_deref());
+        // Threads used to default to the config dir
+        let default = paths::prefer_existing(self.data_dir.join("threads"), self.config_dir.join("threads"));
+        Self::resolve_storage_dir(configured, default)
+    }
+
+    /// Session directory: `session.dir`, else the data dir. Created if missing.
+    pub fn session_dir(&self) -> StorageDir {
+        // The session file used to default to the config dir
+        let default = paths::prefer_existing(self.data_dir.join(SESSION_FILE), self.config_dir.join(SESSION_FILE))
+            .parent()
+            .map_or_else(|| self.data_dir.clone(), Path::to_path_buf);
+        Self::resolve_storage_dir(self.app_config.session.dir.as_deref(), default)
+    }
+
+    /// Use `configured` if it can be created and written to, otherwise `default`
+    fn resolve_storage_dir(configured: Option<&Path>, default: PathBuf) -> StorageDir {
+        let Some(configured) = configured else {
+            return StorageDir { path: default, warning: None };
+        };
+        let path = expand_home(configured);
+        match ensure_writable_dir(&path) {
+            Ok(()) => StorageDir { path, warning: None },
+            Err(e) => {
+                let warning = format!("{} is not writable ({}); using {}", path.display(), e, default.display());
+                tracing::warn!("{}", warning);
+                StorageDir { path: default, warning: Some(warning) }
+            }
+        }
+    }
     
     pub fn app_config(&self) -> &AppConfig {
         &self.app_config
@@ -219,6 +651,10 @@ impl ConfigManager {
         &mut self.lsp_config
     }
 
+    pub fn prompts_config(&self) -> &PromptsConfig {
+        &self.prompts_config
+    }
+
     pub fn reload_all(&mut self) {
         self.app_config = Self::load_app_config(&self.config_dir);
         self.keybindings = Self::load_keybindings(&self.config_dir);
@@ -227,6 +663,7 @@ impl ConfigManager {
         self.subagent_config = Self::load_subagent_config(&self.config_dir);
         self.mandrel_config = Self::load_mandrel_config(&self.config_dir);
         self.lsp_config = Self::load_lsp_config(&self.config_dir);
+        self.prompts_config = Self::load_prompts_config(&self.config_dir);
     }
     
     pub fn reload_file(&mut self, path: &Path) {
@@ -254,6 +691,9 @@ impl ConfigManager {
             Some(LSP_CONFIG_FILE) => {
                 self.lsp_config = Self::load_lsp_config(&self.config_dir);
             }
+            Some(PROMPTS_FILE) => {
+                self.prompts_config = Self::load_prompts_config(&self.config_dir);
+            }
             _ => {
                 self.reload_all();
             }
@@ -261,9 +701,7 @@ impl ConfigManager {
     }
     
     fn get_config_dir() -> Result<PathBuf> {
-        BaseDirs::new()
-            .map(|dirs| dirs.config_dir().join(CONFIG_DIR))
-            .ok_or_else(|| RidgeError::Config("Could not determine config directory".to_string()))
+        paths::default_config_dir()
     }
     
     fn load_app_config(config_dir: &Path) -> AppConfig {
@@ -301,6 +739,11 @@ impl ConfigManager {
         Self::load_toml_file(&path).unwrap_or_default()
     }
 
+    fn load_prompts_config(config_dir: &Path) -> PromptsConfig {
+        let path = config_dir.join(PROMPTS_FILE);
+        Self::load_toml_file(&path).unwrap_or_default()
+    }
+
     fn load_toml_file<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> Option<T> {
         if !path.exists() {
             return None;
@@ -360,6 +803,22 @@ impl ConfigManager {
     }
 }
 
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Create `dir` if needed and check a file can be written inside it
+fn ensure_writable_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".ridge-control-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,4 +837,165 @@ mod tests {
         let parsed: AppConfig = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.general.tick_interval_ms, config.general.tick_interval_ms);
     }
+
+    #[test]
+    fn test_prompt_config_parses() {
+        let config: AppConfig = toml::from_str("[prompt]\nredact_paths = true\n").unwrap();
+        assert!(config.prompt.redact_paths);
+        assert!(!config.prompt.redact_user);
+        assert!(!AppConfig::default().prompt.omit_repo_path);
+    }
+
+    #[test]
+    fn test_conversation_auto_open_defaults_on() {
+        assert!(AppConfig::default().conversation.auto_open);
+        let config: AppConfig = toml::from_str("[conversation]\nauto_open = false\n").unwrap();
+        assert!(!config.conversation.auto_open);
+        assert_eq!(config.conversation.thread_retention_days, 30);
+        assert!(config.conversation.warn_context_overflow);
+    }
+
+    #[test]
+    fn test_ask_user_config_parses() {
+        assert_eq!(AppConfig::default().ask_user.default_timeout_secs, 0);
+        let config: AppConfig = toml::from_str("[ask_user]\ndefault_timeout_secs = 60\n").unwrap();
+        assert_eq!(config.ask_user.default_timeout_secs, 60);
+    }
+
+    #[test]
+    fn test_logs_config_parses() {
+        let config: AppConfig =
+            toml::from_str("[logs]\nshow_timestamps = false\ntimestamp_format = \"relative\"\n").unwrap();
+        assert!(!config.logs.show_timestamps);
+        assert_eq!(config.logs.timestamp_format, TimestampFormat::Relative);
+        assert!(AppConfig::default().logs.show_timestamps);
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        use chrono::TimeZone;
+        let at = chrono::Local.with_ymd_and_hms(2026, 1, 17, 9, 5, 7).unwrap();
+        let later = |secs| at + chrono::Duration::seconds(secs);
+
+        assert_eq!(TimestampFormat::Absolute.format(at, later(5)), "09:05:07.000");
+        assert_eq!(TimestampFormat::Relative.format(at, at).trim(), "now");
+        assert_eq!(TimestampFormat::Relative.format(at, later(12)).trim(), "12s ago");
+        assert_eq!(TimestampFormat::Relative.format(at, later(125)).trim(), "2m ago");
+        assert_eq!(TimestampFormat::Relative.format(at, later(7200)).trim(), "2h ago");
+        assert_eq!(TimestampFormat::Relative.format(at, later(12)).len(), TimestampFormat::WIDTH);
+    }
+
+    #[test]
+    fn test_tools_config_parses() {
+        let config: AppConfig = toml::from_str("[tools]\ncompact_descriptions = true\n").unwrap();
+        assert!(config.tools.compact_descriptions);
+        assert!(!AppConfig::default().tools.compact_descriptions);
+    }
+
+    #[test]
+    fn test_confirm_countdown_config() {
+        let defaults = AppConfig::default().tools;
+        assert_eq!(defaults.confirm_countdown_secs, 0);
+        assert_eq!(defaults.confirm_default_for("bash_execute"), ConfirmDefault::Deny);
+
+        let config: AppConfig = toml::from_str(
+            "[tools]\nconfirm_countdown_secs = 10\nconfirm_default = \"approve\"\n\n[tools.confirm_defaults]\nbash_execute = \"deny\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.tools.confirm_countdown_secs, 10);
+        assert_eq!(config.tools.confirm_default_for("file_write"), ConfirmDefault::Approve);
+        assert_eq!(config.tools.confirm_default_for("bash_execute"), ConfirmDefault::Deny);
+    }
+
+    #[test]
+    fn test_notifications_config_parses() {
+        assert!(!AppConfig::default().notifications.desktop);
+        let config: AppConfig =
+            toml::from_str("[notifications]\ndesktop = true\nturn_complete = false\n").unwrap();
+        assert!(config.notifications.desktop);
+        assert!(!config.notifications.turn_complete);
+        assert!(config.notifications.llm_error);
+    }
+
+    #[test]
+    fn test_ui_config_parses() {
+        assert_eq!(AppConfig::default().ui.idle_poll_ms, 250);
+        let config: AppConfig = toml::from_str("[ui]\nidle_poll_ms = 100\n").unwrap();
+        assert_eq!(config.ui.idle_poll_ms, 100);
+        assert_eq!(config.ui.spinner_style, "braille");
+
+        let config: AppConfig = toml::from_str("[ui]\nspinner_style = \"ascii\"\nspinner_interval_ms = 200\n").unwrap();
+        assert_eq!(config.ui.spinner_style, "ascii");
+        assert_eq!(config.ui.spinner_interval_ms, Some(200));
+
+        let config: AppConfig = toml::from_str("[ui]\noverlay_position = \"right\"\noverlay_width_percent = 40\n").unwrap();
+        assert_eq!(config.ui.overlay_position, OverlayPosition::Right);
+        assert_eq!(config.ui.overlay_width_percent, Some(40));
+
+        assert_eq!(AppConfig::default().ui.scroll_lines, 3);
+        let config: AppConfig = toml::from_str("[ui]\nscroll_lines = 5\n").unwrap();
+        assert_eq!(config.ui.scroll_lines, 5);
+        assert_eq!(config.ui.scroll_lock_threshold, 3);
+
+        assert_eq!(AppConfig::default().ui.chat_send_key, ChatSendKey::Auto);
+        assert_eq!(ChatSendKey::Auto.resolve(true), ChatSendKey::CtrlEnter);
+        assert_eq!(ChatSendKey::Auto.resolve(false), ChatSendKey::AltEnter);
+        assert_eq!(ChatSendKey::Enter.resolve(false), ChatSendKey::Enter);
+        let config: AppConfig = toml::from_str("[ui]\nchat_send_key = \"alt_enter\"\n").unwrap();
+        assert_eq!(config.ui.chat_send_key, ChatSendKey::AltEnter);
+    }
+
+    #[test]
+    fn test_new_in_loads_from_custom_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(temp.path().join(MAIN_CONFIG_FILE), "[ui]\nscroll_lines = 7\n").unwrap();
+
+        let manager = ConfigManager::new_in(temp.path().to_path_buf());
+        assert_eq!(manager.config_dir(), temp.path());
+        assert_eq!(manager.app_config().ui.scroll_lines, 7);
+        assert_eq!(manager.threads_dir(None).path, temp.path().join("threads"));
+        assert_eq!(manager.session_dir().path, temp.path());
+    }
+
+    #[test]
+    fn test_data_dir_holds_threads_and_session() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = temp.path().join("config");
+        let data_dir = temp.path().join("data");
+
+        let manager = ConfigManager::new_in(config_dir.clone()).with_data_dir(data_dir.clone());
+        assert_eq!(manager.threads_dir(None).path, data_dir.join("threads"));
+        assert_eq!(manager.session_dir().path, data_dir);
+
+        // Threads and session saved in the config dir by older releases stay in use
+        std::fs::create_dir_all(config_dir.join("threads")).unwrap();
+        std::fs::write(config_dir.join(SESSION_FILE), "").unwrap();
+        assert_eq!(manager.threads_dir(None).path, config_dir.join("threads"));
+        assert_eq!(manager.session_dir().path, config_dir);
+    }
+
+    #[test]
+    fn test_storage_dirs_resolve_and_fall_back() {
+        let temp = tempfile::tempdir().unwrap();
+        let default = temp.path().join("default");
+
+        let resolved = ConfigManager::resolve_storage_dir(None, default.clone());
+        assert_eq!(resolved, StorageDir { path: default.clone(), warning: None });
+
+        let custom = temp.path().join("synced").join("threads");
+        let resolved = ConfigManager::resolve_storage_dir(Some(&custom), default.clone());
+        assert_eq!(resolved.path, custom);
+        assert!(custom.is_dir());
+
+        // A path under a regular file can never be created
+        let blocker = temp.path().join("file");
+        std::fs::write(&blocker, "").unwrap();
+        let resolved = ConfigManager::resolve_storage_dir(Some(&blocker.join("threads")), default.clone());
+        assert_eq!(resolved.path, default);
+        assert!(resolved.warning.unwrap().contains("not writable"));
+
+        let config: AppConfig = toml::from_str("[agent]\nthreads_dir = \"/vol/threads\"\n[session]\ndir = \"/vol\"\n").unwrap();
+        assert_eq!(config.agent.threads_dir, Some(PathBuf::from("/vol/threads")));
+        assert_eq!(config.session.dir, Some(PathBuf::from("/vol")));
+    }
 }
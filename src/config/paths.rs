@@ -0,0 +1,101 @@
+//! On-disk locations
+//!
+//! `ProjectDirs` is the single source for default paths: config under the XDG
+//! config dir, and everything the app writes (logs, threads, session) under the
+//! XDG data dir. `--data-dir` relocates the latter in one place.
+
+use std::path::{Path, PathBuf};
+
+use directories::{BaseDirs, ProjectDirs};
+
+use crate::error::{Result, RidgeError};
+
+const LOGS_DIR: &str = "logs";
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("com", "ridgetopai", "ridge-control")
+}
+
+/// Default config directory (`~/.config/ridge-control` on Linux).
+///
+/// Releases before `ProjectDirs` used `<config dir>/ridge-control`, which differs
+/// on macOS and Windows; an existing config there is still picked up.
+pub fn default_config_dir() -> Result<PathBuf> {
+    let dir = project_dirs()
+        .map(|dirs| dirs.config_dir().to_path_buf())
+        .ok_or_else(|| RidgeError::Config("Could not determine config directory".to_string()))?;
+    let legacy = BaseDirs::new().map(|dirs| dirs.config_dir().join("ridge-control"));
+    Ok(match legacy {
+        Some(legacy) => prefer_existing(dir, legacy),
+        None => dir,
+    })
+}
+
+/// Data directory: `--data-dir`, else `--config-dir` (which has always held
+/// threads and session state), else the XDG data dir
+/// (`~/.local/share/ridge-control` on Linux)
+pub fn resolve_data_dir(data_dir: Option<&Path>, config_dir: Option<&Path>) -> Result<PathBuf> {
+    if let Some(dir) = data_dir.or(config_dir) {
+        return Ok(dir.to_path_buf());
+    }
+    project_dirs()
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .ok_or_else(|| {
+            RidgeError::Config("Could not determine data directory; pass --data-dir".to_string())
+        })
+}
+
+/// Rotating log files live under `<data dir>/logs`
+pub fn log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOGS_DIR)
+}
+
+/// Log directory: under `--data-dir` if given, else the XDG data dir.
+/// Unlike threads and session state, logs never followed `--config-dir`.
+pub fn resolve_log_dir(data_dir: Option<&Path>) -> Result<PathBuf> {
+    resolve_data_dir(data_dir, None).map(|dir| log_dir(&dir))
+}
+
+/// `path`, unless only `legacy` exists yet; keeps data written by older
+/// releases in use instead of silently starting over
+pub fn prefer_existing(path: PathBuf, legacy: PathBuf) -> PathBuf {
+    if path != legacy && !path.exists() && legacy.exists() {
+        tracing::info!("Using {} (new default location {} does not exist yet)", legacy.display(), path.display());
+        legacy
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_data_dir_precedence() {
+        let data = Path::new("/vol/data");
+        let config = Path::new("/vol/config");
+        assert_eq!(resolve_data_dir(Some(data), Some(config)).unwrap(), data);
+        assert_eq!(resolve_data_dir(None, Some(config)).unwrap(), config);
+        assert_eq!(log_dir(data), data.join("logs"));
+        assert_eq!(resolve_log_dir(Some(data)).unwrap(), data.join("logs"));
+    }
+
+    #[test]
+    fn test_prefer_existing_keeps_legacy_data() {
+        let temp = tempfile::tempdir().unwrap();
+        let new = temp.path().join("data").join("threads");
+        let legacy = temp.path().join("config").join("threads");
+
+        // Fresh install: the new location
+        assert_eq!(prefer_existing(new.clone(), legacy.clone()), new);
+
+        // Only the legacy location exists: keep using it
+        std::fs::create_dir_all(&legacy).unwrap();
+        assert_eq!(prefer_existing(new.clone(), legacy.clone()), legacy);
+
+        // Once the new location exists it wins
+        std::fs::create_dir_all(&new).unwrap();
+        assert_eq!(prefer_existing(new.clone(), legacy), new);
+    }
+}
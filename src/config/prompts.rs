@@ -0,0 +1,111 @@
+//! Persona templates
+//!
+//! Named prompt templates loaded from ~/.config/ridge-control/prompts.toml and
+//! composed with the base system prompt by `SystemPromptBuilder::with_persona`.
+//! A few built-in personas are always available; a user template with the same
+//! name replaces the built-in one.
+
+use serde::{Deserialize, Serialize};
+
+/// A named persona: text placed before and after the base system prompt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonaTemplate {
+    pub name: String,
+    /// One-line summary shown in the command palette
+    #[serde(default)]
+    pub description: String,
+    /// Inserted right after the role line, before the environment sections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Appended at the end of the prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+}
+
+impl PersonaTemplate {
+    fn builtin(name: &str, description: &str, prefix: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            prefix: Some(prefix.to_string()),
+            suffix: None,
+        }
+    }
+}
+
+/// Contents of prompts.toml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PromptsConfig {
+    /// User-defined personas (`[[personas]]` tables)
+    pub personas: Vec<PersonaTemplate>,
+}
+
+impl PromptsConfig {
+    /// Personas shipped with ridge-control
+    pub fn builtin_personas() -> Vec<PersonaTemplate> {
+        vec![
+            PersonaTemplate::builtin(
+                "concise",
+                "Short, direct answers",
+                "Answer as briefly as possible. Skip preamble and recaps; show code or the result first.",
+            ),
+            PersonaTemplate::builtin(
+                "teacher",
+                "Explain reasoning step by step",
+                "Act as a patient teacher. Explain the why behind each step, define unfamiliar terms, \
+                 and check understanding before moving on.",
+            ),
+            PersonaTemplate::builtin(
+                "code-reviewer",
+                "Review changes like a strict maintainer",
+                "Act as a strict code reviewer. Look for bugs, unclear naming, missing tests and \
+                 deviations from the surrounding code's conventions. Do not modify files unless asked.",
+            ),
+        ]
+    }
+
+    /// All personas, user templates first, then built-ins they don't override
+    pub fn all(&self) -> Vec<PersonaTemplate> {
+        let mut all = self.personas.clone();
+        for builtin in Self::builtin_personas() {
+            if !all.iter().any(|p| p.name == builtin.name) {
+                all.push(builtin);
+            }
+        }
+        all
+    }
+
+    /// Look up a persona by name (user templates take precedence)
+    pub fn find(&self, name: &str) -> Option<PersonaTemplate> {
+        self.all().into_iter().find(|p| p.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_personas_override_builtins() {
+        let config: PromptsConfig = toml::from_str(
+            r#"
+            [[personas]]
+            name = "concise"
+            prefix = "One sentence max."
+
+            [[personas]]
+            name = "pirate"
+            description = "Arr"
+            suffix = "Speak like a pirate."
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.find("concise").unwrap().prefix.as_deref(), Some("One sentence max."));
+        assert_eq!(config.find("pirate").unwrap().suffix.as_deref(), Some("Speak like a pirate."));
+        assert!(config.find("teacher").is_some());
+        assert!(config.find("missing").is_none());
+        assert_eq!(config.all().iter().filter(|p| p.name == "concise").count(), 1);
+    }
+}
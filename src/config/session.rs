@@ -12,25 +12,37 @@
 //! Saves:
 //! - Tab names and their order
 //! - Active tab index
+//! - Last active conversation thread id
+//! - Log and stream viewer wrap settings
 //! - Optional: Working directories per tab (future)
 //!
-//! Location: ~/.config/ridge-control/session.toml
+//! Location: <data dir>/session.toml, or `session.dir` when set (see
+//! `ConfigManager::session_dir`). An existing session.toml in the config dir,
+//! the old default, is still used if the data dir one does not exist yet.
 
 use std::path::{Path, PathBuf};
 
-use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{RidgeError, Result};
 
-const SESSION_FILE: &str = "session.toml";
+pub(super) const SESSION_FILE: &str = "session.toml";
+
+/// Current session file format.
+///
+/// - v1: tabs, active tab index and save time
+/// - v2: adds the active thread, viewer settings and compact mode, all optional
+pub const SESSION_VERSION: u32 = 2;
 
 /// Persistent session data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
-    /// Version for forward compatibility
+    /// File format version (see `SESSION_VERSION`)
     #[serde(default = "default_version")]
     pub version: u32,
+    /// Id of the conversation thread that was active when the session was saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_thread_id: Option<String>,
     /// Tabs in order (main tab is always first)
     pub tabs: Vec<TabData>,
     /// Index of the active tab (0-based)
@@ -38,6 +50,31 @@ pub struct SessionData {
     /// Timestamp when session was saved (Unix epoch seconds)
     #[serde(default)]
     pub saved_at: u64,
+    /// Display settings for the log and stream viewers
+    #[serde(default)]
+    pub viewers: ViewerPrefs,
+    /// Compact layout (process monitor and menu as overlays)
+    #[serde(default)]
+    pub compact_mode: bool,
+}
+
+/// Per-viewer display settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerPrefs {
+    /// Wrap long lines in the log viewer
+    pub log_wrap: bool,
+    /// Wrap long lines in the stream viewer
+    pub stream_wrap: bool,
+}
+
+impl Default for ViewerPrefs {
+    fn default() -> Self {
+        Self {
+            log_wrap: true,
+            stream_wrap: true,
+        }
+    }
 }
 
 fn default_version() -> u32 {
@@ -60,7 +97,8 @@ pub struct TabData {
 impl Default for SessionData {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: SESSION_VERSION,
+            active_thread_id: None,
             tabs: vec![TabData {
                 name: "Ridge-Control".to_string(),
                 is_main: true,
@@ -68,6 +106,8 @@ impl Default for SessionData {
             }],
             active_tab_index: 0,
             saved_at: 0,
+            viewers: ViewerPrefs::default(),
+            compact_mode: false,
         }
     }
 }
@@ -87,16 +127,37 @@ impl SessionData {
             .collect();
 
         Self {
-            version: 1,
+            version: SESSION_VERSION,
+            active_thread_id: None,
             tabs,
             active_tab_index: active_index,
             saved_at: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .map(|d| d.as_secs())
                 .unwrap_or(0),
+            viewers: ViewerPrefs::default(),
+            compact_mode: false,
         }
     }
 
+    /// Record the active conversation thread
+    pub fn with_active_thread(mut self, thread_id: Option<String>) -> Self {
+        self.active_thread_id = thread_id;
+        self
+    }
+
+    /// Record the viewer display settings
+    pub fn with_viewers(mut self, viewers: ViewerPrefs) -> Self {
+        self.viewers = viewers;
+        self
+    }
+
+    /// Record whether the compact layout is on
+    pub fn with_compact_mode(mut self, compact: bool) -> Self {
+        self.compact_mode = compact;
+        self
+    }
+
     /// Check if this is a valid session (has at least the main tab)
     pub fn is_valid(&self) -> bool {
         !self.tabs.is_empty() && self.tabs.iter().any(|t| t.is_main)
@@ -109,15 +170,9 @@ pub struct SessionManager {
 }
 
 impl SessionManager {
-    /// Create a new SessionManager using the default config directory
+    /// Create a new SessionManager using the default data directory
     pub fn new() -> Result<Self> {
-        let config_dir = BaseDirs::new()
-            .map(|dirs| dirs.config_dir().join("ridge-control"))
-            .ok_or_else(|| RidgeError::Config("Could not determine config directory".to_string()))?;
-
-        Ok(Self {
-            session_path: config_dir.join(SESSION_FILE),
-        })
+        Ok(Self::in_dir(&super::paths::resolve_data_dir(None, None)?))
     }
 
     /// Create a SessionManager with a custom path (for testing)
@@ -125,6 +180,11 @@ impl SessionManager {
         Self { session_path: path }
     }
 
+    /// Create a SessionManager storing session.toml in `dir`
+    pub fn in_dir(dir: &Path) -> Self {
+        Self::with_path(dir.join(SESSION_FILE))
+    }
+
     /// Get the session file path
     pub fn session_path(&self) -> &Path {
         &self.session_path
@@ -133,34 +193,53 @@ impl SessionManager {
     /// Load session data from disk
     /// Returns default session if file doesn't exist or is invalid
     pub fn load(&self) -> SessionData {
+        self.load_checked().unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            SessionData::default()
+        })
+    }
+
+    /// Load session data, migrating older formats to `SESSION_VERSION`.
+    ///
+    /// A missing file yields the default session. A file that exists but
+    /// can't be read or migrated is moved aside to `session.toml.bak` so the
+    /// next save doesn't overwrite it, and the error says where it went.
+    pub fn load_checked(&self) -> std::result::Result<SessionData, String> {
         if !self.session_path.exists() {
             tracing::debug!("No session file found, using default");
-            return SessionData::default();
+            return Ok(SessionData::default());
         }
 
-        match std::fs::read_to_string(&self.session_path) {
-            Ok(content) => match toml::from_str::<SessionData>(&content) {
-                Ok(session) => {
-                    if session.is_valid() {
-                        tracing::info!(
-                            "Loaded session with {} tabs, active: {}",
-                            session.tabs.len(),
-                            session.active_tab_index
-                        );
-                        session
-                    } else {
-                        tracing::warn!("Session file invalid, using default");
-                        SessionData::default()
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to parse session file: {}", e);
-                    SessionData::default()
-                }
-            },
+        let parsed = std::fs::read_to_string(&self.session_path)
+            .map_err(|e| format!("Failed to read session file: {}", e))
+            .and_then(|content| {
+                let mut value: toml::Value = toml::from_str(&content)
+                    .map_err(|e| format!("Failed to parse session file: {}", e))?;
+                migrate_session(&mut value);
+                value
+                    .try_into::<SessionData>()
+                    .map_err(|e| format!("Incompatible session file: {}", e))
+            });
+
+        match parsed {
+            Ok(session) if session.is_valid() => {
+                tracing::info!(
+                    "Loaded session with {} tabs, active: {}",
+                    session.tabs.len(),
+                    session.active_tab_index
+                );
+                Ok(session)
+            }
+            Ok(_) => {
+                tracing::warn!("Session file invalid, using default");
+                Ok(SessionData::default())
+            }
             Err(e) => {
-                tracing::warn!("Failed to read session file: {}", e);
-                SessionData::default()
+                let backup = self.session_path.with_extension("toml.bak");
+                match std::fs::rename(&self.session_path, &backup) {
+                    Ok(()) => Err(format!("{}; previous session kept at {}", e, backup.display())),
+                    Err(_) => Err(e),
+                }
             }
         }
     }
@@ -179,7 +258,7 @@ impl SessionManager {
         let content = toml::to_string_pretty(session)
             .map_err(|e| RidgeError::Config(format!("Failed to serialize session: {}", e)))?;
 
-        std::fs::write(&self.session_path, content)
+        crate::util::write_atomic(&self.session_path, content.as_bytes())
             .map_err(|e| RidgeError::Config(format!("Failed to write session file: {}", e)))?;
 
         tracing::info!(
@@ -207,6 +286,23 @@ impl SessionManager {
     }
 }
 
+/// Upgrade a raw session table in place to `SESSION_VERSION`.
+/// Fields added in v2 all have defaults, so v1 files only need the version
+/// bumped. Files from newer versions are left as-is; unknown fields are ignored on parse.
+fn migrate_session(value: &mut toml::Value) {
+    let Some(table) = value.as_table_mut() else {
+        return;
+    };
+    let version = table
+        .get("version")
+        .and_then(|v| v.as_integer())
+        .unwrap_or(1);
+
+    if version < SESSION_VERSION as i64 {
+        table.insert("version".to_string(), toml::Value::Integer(SESSION_VERSION as i64));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,6 +365,58 @@ mod tests {
         assert_eq!(loaded.active_tab_index, 1);
     }
 
+    #[test]
+    fn test_viewer_prefs_round_trip() {
+        let (manager, _temp_dir) = temp_session_manager();
+
+        let viewers = ViewerPrefs { log_wrap: false, stream_wrap: true };
+        let session = SessionData::default().with_viewers(viewers);
+        manager.save(&session).unwrap();
+        assert_eq!(manager.load().viewers, viewers);
+
+        // Sessions saved before viewer settings existed default to wrapping
+        let legacy: SessionData = toml::from_str(
+            "active_tab_index = 0\n[[tabs]]\nname = \"Ridge-Control\"\nis_main = true\n",
+        )
+        .unwrap();
+        assert_eq!(legacy.viewers, ViewerPrefs::default());
+        assert!(!legacy.compact_mode);
+
+        manager.save(&SessionData::default().with_compact_mode(true)).unwrap();
+        assert!(manager.load().compact_mode);
+    }
+
+    #[test]
+    fn test_load_migrates_v1_session() {
+        let (manager, _temp_dir) = temp_session_manager();
+
+        // v1 as written before the active thread and viewer settings existed
+        let v1 = "version = 1\nactive_tab_index = 1\nsaved_at = 1735689600\n\n\
+                  [[tabs]]\nname = \"Ridge-Control\"\nis_main = true\n\n\
+                  [[tabs]]\nname = \"Dev\"\nis_main = false\nworking_dir = \"/tmp\"\n";
+        std::fs::write(manager.session_path(), v1).unwrap();
+
+        let session = manager.load_checked().unwrap();
+        assert_eq!(session.version, SESSION_VERSION);
+        assert_eq!(session.tabs.len(), 2);
+        assert!(session.tabs[0].is_main);
+        assert_eq!(session.tabs[1].working_dir.as_deref(), Some(Path::new("/tmp")));
+        assert_eq!(session.active_tab_index, 1);
+        assert_eq!(session.active_thread_id, None);
+        assert_eq!(session.viewers, ViewerPrefs::default());
+    }
+
+    #[test]
+    fn test_incompatible_session_is_kept_aside() {
+        let (manager, temp_dir) = temp_session_manager();
+        std::fs::write(manager.session_path(), "tabs = \"not a list\"\n").unwrap();
+
+        let err = manager.load_checked().unwrap_err();
+        assert!(err.contains("session.toml.bak"));
+        assert!(!manager.exists());
+        assert!(temp_dir.path().join("session.toml.bak").exists());
+    }
+
     #[test]
     fn test_load_nonexistent_returns_default() {
         let (manager, _temp_dir) = temp_session_manager();
@@ -298,10 +446,13 @@ mod tests {
 
         // Write invalid session (empty tabs)
         let invalid = SessionData {
-            version: 1,
+            version: SESSION_VERSION,
+            active_thread_id: None,
             tabs: vec![],
             active_tab_index: 0,
             saved_at: 0,
+            viewers: ViewerPrefs::default(),
+            compact_mode: false,
         };
         
         let content = toml::to_string_pretty(&invalid).unwrap();
@@ -324,13 +475,39 @@ mod tests {
         let toml_str = toml::to_string_pretty(&session).unwrap();
         
         // Verify key fields are present
-        assert!(toml_str.contains("version = 1"));
+        assert!(toml_str.contains("version = 2"));
         assert!(toml_str.contains("active_tab_index = 0"));
         assert!(toml_str.contains("[[tabs]]"));
         assert!(toml_str.contains("name = \"Ridge-Control\""));
         assert!(toml_str.contains("is_main = true"));
     }
 
+    #[test]
+    fn test_active_thread_id_round_trip() {
+        let (manager, _temp_dir) = temp_session_manager();
+
+        let session = SessionData::from_tabs([("Ridge-Control", true)].into_iter(), 0)
+            .with_active_thread(Some("thread-123".to_string()));
+        manager.save(&session).unwrap();
+
+        let loaded = manager.load();
+        assert_eq!(loaded.active_thread_id.as_deref(), Some("thread-123"));
+    }
+
+    #[test]
+    fn test_session_without_thread_id_loads() {
+        let (manager, _temp_dir) = temp_session_manager();
+
+        // Sessions written before thread persistence have no active_thread_id
+        let session = SessionData::default();
+        manager.save(&session).unwrap();
+        let content = std::fs::read_to_string(manager.session_path()).unwrap();
+        assert!(!content.contains("active_thread_id"));
+
+        let loaded = manager.load();
+        assert!(loaded.active_thread_id.is_none());
+    }
+
     #[test]
     fn test_corrupted_session_file() {
         let (manager, _temp_dir) = temp_session_manager();
@@ -61,6 +61,10 @@ impl SubagentConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SubagentsConfig {
+    /// Sub-agents allowed to run at once; extra spawns wait in a queue
+    pub max_concurrent: usize,
+    /// Sub-agents a single conversation turn may spawn
+    pub max_per_turn: usize,
     /// Agent for exploration/research tasks (codebase search, understanding)
     pub explore: SubagentConfig,
     /// Agent for planning/architecture decisions
@@ -74,6 +78,8 @@ pub struct SubagentsConfig {
 impl Default for SubagentsConfig {
     fn default() -> Self {
         Self {
+            max_concurrent: 3,
+            max_per_turn: 8,
             explore: SubagentConfig {
                 model: "claude-haiku-4-5-20251001".to_string(),
                 provider: "anthropic".to_string(),
@@ -81,6 +87,7 @@ impl Default for SubagentsConfig {
                 allowed_tools: vec![
                     "file_read".to_string(),
                     "grep".to_string(),
+                    "search_code".to_string(),
                     "glob".to_string(),
                     "tree".to_string(),
                     "find_symbol".to_string(),
@@ -194,6 +201,14 @@ mod tests {
         assert_eq!(config.review.provider, "openai");
     }
 
+    #[test]
+    fn test_concurrency_limits_parse() {
+        let config: SubagentsConfig = toml::from_str("max_concurrent = 1\n").unwrap();
+        assert_eq!(config.max_concurrent, 1);
+        assert_eq!(config.max_per_turn, 8);
+        assert_eq!(SubagentsConfig::default().max_concurrent, 3);
+    }
+
     #[test]
     fn test_get_agent_config() {
         let config = SubagentsConfig::default();
@@ -193,6 +193,7 @@ impl Default for ProcessMonitorStyle {
 #[serde(default)]
 pub struct TerminalStyle {
     pub cursor_color: HexColor,
+    /// Let underline/bar cursors requested by terminal apps blink
     pub cursor_blink: bool,
     pub selection_bg: HexColor,
     pub selection_fg: HexColor,
@@ -0,0 +1,342 @@
+//! Crash report bundle
+//!
+//! Nothing here touches the network. The panic hook writes the panic location
+//! and backtrace under `<data dir>/crash`; `ridge-control crash-report` (or the
+//! "Generate Crash Report" command) packs that record together with the log
+//! tail, redacted config files, terminal details and the last captured LLM
+//! exchange into one `.tar.gz` the user can inspect and attach to an issue.
+
+use std::io::{self, Write};
+use std::any::Any;
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::config::{KeyId, KeyStore};
+use crate::llm::debug_capture::LlmExchange;
+use crate::redact::{is_secret_name, redact_text, REDACTED};
+use crate::util::write_atomic_mode;
+
+const CRASH_DIR: &str = "crash";
+const PANIC_FILE: &str = "last_panic.txt";
+
+/// Log lines kept from the newest log file
+const LOG_TAIL_LINES: usize = 2000;
+
+/// Shorter known values are skipped; redacting them would mangle ordinary text
+const MIN_SECRET_LEN: usize = 8;
+
+/// Where the panic hook writes; unset until the data dir is known
+static PANIC_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Crash records and generated bundles live under `<data dir>/crash`
+pub fn crash_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(CRASH_DIR)
+}
+
+/// Start recording panics under `data_dir`; call once the data dir is resolved
+pub fn set_panic_dir(data_dir: &Path) {
+    let _ = PANIC_PATH.set(crash_dir(data_dir).join(PANIC_FILE));
+}
+
+/// Write the panic location, message and backtrace for the next bundle.
+/// Called from the panic hook with its payload and location, so failures are ignored.
+pub fn record_panic(payload: &(dyn Any + Send), location: Option<&Location>) {
+    let Some(path) = PANIC_PATH.get() else {
+        return;
+    };
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string panic payload>");
+    let location = location
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "<unknown>".to_string());
+    let record = format!(
+        "ridge-control v{}\nTime: {}\nThread: {}\nLocation: {}\nMessage: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        std::thread::current().name().unwrap_or("<unnamed>"),
+        location,
+        message,
+        std::backtrace::Backtrace::force_capture(),
+    );
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    // Private: the message and backtrace may carry prompt text or paths
+    let _ = write_atomic_mode(path, record.as_bytes(), 0o600);
+}
+
+/// Files gathered for one bundle, by path inside the archive
+pub struct CrashReport {
+    name: String,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl CrashReport {
+    /// Gather everything from `config_dir`, `data_dir` and `log_dir`; `exchange`
+    /// is the last captured LLM call, already redacted. `known_secrets` are
+    /// redacted wherever they appear, whatever they look like.
+    pub fn collect(
+        config_dir: &Path,
+        data_dir: &Path,
+        log_dir: &Path,
+        exchange: Option<LlmExchange>,
+        known_secrets: &[String],
+    ) -> Self {
+        let known_secrets: Vec<&str> = known_secrets
+            .iter()
+            .map(String::as_str)
+            .filter(|s| s.len() >= MIN_SECRET_LEN)
+            .collect();
+        let name = format!("ridge-control-crash-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let mut report = Self { name, files: Vec::new() };
+
+        report.add("summary.txt", summary(config_dir, data_dir));
+        if let Ok(panic) = std::fs::read_to_string(crash_dir(data_dir).join(PANIC_FILE)) {
+            report.add("panic.txt", panic);
+        }
+        if let Some(tail) = log_tail(log_dir, LOG_TAIL_LINES, &known_secrets) {
+            report.add("log_tail.txt", tail);
+        }
+        for (file_name, content) in config_files(config_dir, &known_secrets) {
+            report.add(&format!("config/{}", file_name), content);
+        }
+        if let Some(exchange) = exchange {
+            report.add("llm_exchange.txt", exchange.render());
+        }
+        report
+    }
+
+    fn add(&mut self, path: &str, content: String) {
+        self.files.push((format!("{}/{}", self.name, path), content.into_bytes()));
+    }
+
+    /// Write the bundle as `<dir>/<name>.tar.gz` and return its path
+    pub fn write_to(&self, dir: &Path) -> io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.tar.gz", self.name));
+        // Owner-only: the bundle holds logs and config even after redaction
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut gz = GzEncoder::new(options.open(&path)?, Compression::default());
+        let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+        for (name, content) in &self.files {
+            gz.write_all(&tar_header(name, content.len() as u64, mtime)?)?;
+            gz.write_all(content)?;
+            gz.write_all(&vec![0; padding(content.len())])?;
+        }
+        // Two zero blocks end the archive
+        gz.write_all(&[0; 1024])?;
+        gz.finish()?.sync_all()?;
+        Ok(path)
+    }
+}
+
+/// API keys in use: everything readable from `keystore` plus the standard
+/// provider environment variables
+pub fn known_secrets(keystore: Option<&KeyStore>) -> Vec<String> {
+    let mut secrets: Vec<String> = keystore
+        .and_then(|ks| Some((ks, ks.list().ok()?)))
+        .map(|(ks, ids)| {
+            ids.iter()
+                .filter_map(|id| ks.get(id).ok().flatten())
+                .map(|secret| secret.expose().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    for id in [KeyId::Anthropic, KeyId::OpenAI, KeyId::Gemini, KeyId::Grok, KeyId::Groq] {
+        secrets.extend(id.env_value().map(|(_, value)| value));
+    }
+    secrets
+}
+
+fn summary(config_dir: &Path, data_dir: &Path) -> String {
+    let env = |name: &str| std::env::var(name).unwrap_or_else(|_| "<unset>".to_string());
+    let size = crossterm::terminal::size()
+        .map(|(cols, rows)| format!("{}x{}", cols, rows))
+        .unwrap_or_else(|_| "<not a terminal>".to_string());
+    format!(
+        "ridge-control v{}\nGenerated: {}\nOS: {} ({})\n\nTerminal\n  TERM: {}\n  COLORTERM: {}\n  TERM_PROGRAM: {}\n  Size: {}\n\nConfig dir: {}\nData dir: {}\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        env("TERM"),
+        env("COLORTERM"),
+        env("TERM_PROGRAM"),
+        size,
+        config_dir.display(),
+        data_dir.display(),
+    )
+}
+
+/// Last `lines` lines of the most recently written log file, redacted
+fn log_tail(log_dir: &Path, lines: usize, known_secrets: &[&str]) -> Option<String> {
+    let newest = std::fs::read_dir(log_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())?;
+    let content = std::fs::read(newest.path()).ok()?;
+    let content = String::from_utf8_lossy(&content);
+    let all: Vec<&str> = content.lines().collect();
+    let tail = all[all.len().saturating_sub(lines)..].join("\n");
+    Some(format!("# {}\n{}\n", newest.file_name().to_string_lossy(), redact_text(&tail, known_secrets)))
+}
+
+/// Every `.toml` file in `config_dir` with secret-looking values redacted.
+/// Files that don't parse are left out rather than copied verbatim.
+fn config_files(config_dir: &Path, known_secrets: &[&str]) -> Vec<(String, String)> {
+    let Ok(entries) = std::fs::read_dir(config_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, String)> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let content = std::fs::read_to_string(entry.path()).ok()?;
+            match content.parse::<toml::Table>() {
+                Ok(mut table) => {
+                    redact_table(&mut table, known_secrets);
+                    Some((name, toml::to_string_pretty(&table).ok()?))
+                }
+                Err(_) => Some((name, "# not valid TOML; left out\n".to_string())),
+            }
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn redact_table(table: &mut toml::Table, known_secrets: &[&str]) {
+    for (key, value) in table.iter_mut() {
+        if value.is_str() && is_secret_name(key) {
+            *value = toml::Value::String(REDACTED.to_string());
+        } else {
+            redact_value(value, known_secrets);
+        }
+    }
+}
+
+fn redact_value(value: &mut toml::Value, known_secrets: &[&str]) {
+    match value {
+        toml::Value::String(s) => *s = redact_text(s, known_secrets),
+        toml::Value::Array(items) => items.iter_mut().for_each(|v| redact_value(v, known_secrets)),
+        toml::Value::Table(table) => redact_table(table, known_secrets),
+        _ => {}
+    }
+}
+
+/// ustar header for a regular file
+fn tar_header(name: &str, size: u64, mtime: u64) -> io::Result<[u8; 512]> {
+    if name.len() > 100 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("archive path too long: {}", name)));
+    }
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", size).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime).as_bytes());
+    header[148..156].copy_from_slice(b"        ");
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+/// Zero bytes after `len` bytes of content to reach the next 512-byte block
+fn padding(len: usize) -> usize {
+    (512 - len % 512) % 512
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// (path, content) of every file in an uncompressed tar
+    fn read_tar(bytes: &[u8]) -> Vec<(String, String)> {
+        let mut files = Vec::new();
+        let mut offset = 0;
+        while offset + 512 <= bytes.len() && bytes[offset] != 0 {
+            let header = &bytes[offset..offset + 512];
+            let name = String::from_utf8_lossy(&header[..100]).trim_end_matches('\0').to_string();
+            let size_field = String::from_utf8_lossy(&header[124..135]).to_string();
+            let size = usize::from_str_radix(&size_field, 8).unwrap();
+            let stored: u32 = u32::from_str_radix(String::from_utf8_lossy(&header[148..154]).as_ref(), 8).unwrap();
+            let computed: u32 = header.iter().enumerate()
+                .map(|(i, &b)| if (148..156).contains(&i) { b' ' as u32 } else { b as u32 })
+                .sum();
+            assert_eq!(stored, computed, "checksum of {}", name);
+            let start = offset + 512;
+            files.push((name, String::from_utf8_lossy(&bytes[start..start + size]).into_owned()));
+            offset = start + size + padding(size);
+        }
+        files
+    }
+
+    #[test]
+    fn test_bundle_contents_are_redacted() {
+        let temp = tempfile::tempdir().unwrap();
+        let config_dir = temp.path().join("config");
+        let data_dir = temp.path().join("data");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::write(
+            config_dir.join("streams.toml"),
+            "[[streams]]\nname = \"api\"\napi_key = \"hunter2\"\nurl = \"wss://example.com\"\n\n[streams.headers]\nAuthorization = \"Bearer abcdefgh12345\"\n",
+        ).unwrap();
+        std::fs::write(
+            config_dir.join("config.toml"),
+            "[general]\nlog_level = \"debug\"\n\n[llm]\nendpoint_key = \"plainkey9876\"\n",
+        ).unwrap();
+        std::fs::write(config_dir.join("notes.txt"), "not config").unwrap();
+        let log_dir = crate::config::log_dir(&data_dir);
+        std::fs::create_dir_all(&log_dir).unwrap();
+        let log: Vec<String> = (0..LOG_TAIL_LINES + 10).map(|i| format!("line {}", i)).collect();
+        std::fs::write(log_dir.join("ridge-control.log.2026-01-01"), log.join("\n") + "\nkey sk-abcdefghijklmnopqrstuv\n").unwrap();
+        std::fs::create_dir_all(crash_dir(&data_dir)).unwrap();
+        std::fs::write(crash_dir(&data_dir).join(PANIC_FILE), "Location: src/app.rs:1:1\n").unwrap();
+
+        let secrets = ["plainkey9876".to_string(), "short".to_string()];
+        let report = CrashReport::collect(&config_dir, &data_dir, &log_dir, None, &secrets);
+        let path = report.write_to(temp.path()).unwrap();
+        assert!(path.to_string_lossy().ends_with(".tar.gz"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let mut tar = Vec::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(&path).unwrap()).read_to_end(&mut tar).unwrap();
+        let files = read_tar(&tar);
+        let names: Vec<&str> = files.iter().map(|(n, _)| n.rsplit_once(&report.name).unwrap().1).collect();
+        assert_eq!(names, ["/summary.txt", "/panic.txt", "/log_tail.txt", "/config/config.toml", "/config/streams.toml"]);
+
+        let all: String = files.iter().map(|(_, c)| c.as_str()).collect();
+        assert!(all.contains(env!("CARGO_PKG_VERSION")));
+        assert!(all.contains("src/app.rs:1:1"));
+        assert!(all.contains("log_level = \"debug\""));
+        assert!(all.contains("wss://example.com"));
+        for secret in ["hunter2", "abcdefgh12345", "sk-abcdefghij", "plainkey9876"] {
+            assert!(!all.contains(secret), "{} leaked into the bundle", secret);
+        }
+
+        let tail = &files[2].1;
+        assert!(!tail.contains("line 10\n"));
+        assert!(tail.contains(&format!("line {}\n", LOG_TAIL_LINES + 9)));
+    }
+}
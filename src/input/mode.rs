@@ -27,6 +27,7 @@ pub enum InsertTarget {
     StreamFilter,
     TabRename,
     ThreadRename,
+    ThreadTags,
     Search,
 }
 
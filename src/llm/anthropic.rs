@@ -10,18 +10,26 @@ use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
 use super::provider::{Capability, ModelInfo, Provider, StreamBox};
+use super::rate_limit::RateLimits;
 use super::types::{
     BlockType, ContentBlock, LLMError, LLMRequest, LLMResponse, StopReason, StreamChunk,
     StreamDelta, ToolUse, Usage,
 };
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+/// API root; endpoint paths are appended. Overridable via `base_url` in llm.toml
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
 const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Smallest thinking budget the API accepts
+const MIN_THINKING_BUDGET: u32 = 1024;
 
 /// Anthropic Claude provider
 pub struct AnthropicProvider {
     api_key: String,
+    /// API root without a trailing slash
+    base_url: String,
     http_client: Client,
+    /// Rate-limit headers of each response end up here
+    rate_limits: RateLimits,
     models: Vec<ModelInfo>,
     default_model: String,
 }
@@ -65,12 +73,54 @@ impl AnthropicProvider {
 
         Self {
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
             http_client,
             models,
+            rate_limits: RateLimits::default(),
             default_model: "claude-sonnet-4-5-20250929".to_string(),
         }
     }
 
+    /// Use a preconfigured client (extra headers, custom CA bundle)
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Record rate-limit headers in a state shared with `LLMManager`
+    pub fn with_rate_limits(mut self, rate_limits: RateLimits) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+
+    /// Send requests to a proxy or gateway instead of the public API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Thinking budget to send for this request, if thinking applies.
+    ///
+    /// Skipped for models without thinking support, when the budget can't fit
+    /// under `max_tokens`, and on tool-result continuations: thinking block
+    /// signatures aren't round-tripped, and the API requires them there.
+    fn thinking_budget(&self, model: &str, request: &LLMRequest) -> Option<u32> {
+        let thinking = request.thinking.as_ref().filter(|t| t.enabled)?;
+        let supported = self.models.iter().any(|m| m.id == model && m.supports_thinking);
+        let continuing_tool_use = request.messages.last().is_some_and(|m| {
+            m.content.iter().any(|c| matches!(c, ContentBlock::ToolResult(_)))
+        });
+        if !supported || continuing_tool_use {
+            return None;
+        }
+        let max_tokens = request.max_tokens.unwrap_or(4096);
+        let budget = thinking
+            .budget_tokens
+            .unwrap_or(MIN_THINKING_BUDGET)
+            .min(max_tokens.saturating_sub(1));
+        (budget >= MIN_THINKING_BUDGET).then_some(budget)
+    }
+
     fn build_request_body(&self, request: &LLMRequest) -> serde_json::Value {
         let messages: Vec<serde_json::Value> = request
             .messages
@@ -89,6 +139,16 @@ impl AnthropicProvider {
                             "type": "text",
                             "text": text
                         })),
+                        ContentBlock::Image(img) => Some(match &img.source {
+                            super::types::ImageSource::Base64(data) => json!({
+                                "type": "image",
+                                "source": { "type": "base64", "media_type": img.media_type, "data": data }
+                            }),
+                            super::types::ImageSource::Url(url) => json!({
+                                "type": "image",
+                                "source": { "type": "url", "url": url }
+                            }),
+                        }),
                         ContentBlock::ToolUse(tool_use) => Some(json!({
                             "type": "tool_use",
                             "id": tool_use.id,
@@ -134,15 +194,27 @@ impl AnthropicProvider {
         });
 
         if let Some(system) = &request.system {
-            body["system"] = json!(system);
+            body["system"] = if request.prompt_cache {
+                json!([{ "type": "text", "text": system, "cache_control": { "type": "ephemeral" } }])
+            } else {
+                json!(system)
+            };
         }
 
-        if let Some(temp) = request.temperature {
-            body["temperature"] = json!(temp);
+        match self.thinking_budget(model_to_use, request) {
+            Some(budget) => {
+                // The API rejects a custom temperature while thinking is enabled
+                body["thinking"] = json!({ "type": "enabled", "budget_tokens": budget });
+            }
+            None => {
+                if let Some(temp) = request.temperature {
+                    body["temperature"] = json!(temp);
+                }
+            }
         }
 
         if !request.tools.is_empty() {
-            let tools: Vec<serde_json::Value> = request
+            let mut tools: Vec<serde_json::Value> = request
                 .tools
                 .iter()
                 .map(|t| {
@@ -153,6 +225,12 @@ impl AnthropicProvider {
                     })
                 })
                 .collect();
+            // A breakpoint on the last tool caches the whole tool block
+            if request.prompt_cache {
+                if let Some(last) = tools.last_mut() {
+                    last["cache_control"] = json!({ "type": "ephemeral" });
+                }
+            }
             body["tools"] = json!(tools);
         }
 
@@ -189,7 +267,7 @@ impl Provider for AnthropicProvider {
 
         let response = self
             .http_client
-            .post(ANTHROPIC_API_URL)
+            .post(format!("{}/messages", self.base_url))
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", ANTHROPIC_VERSION)
             .header("content-type", "application/json")
@@ -200,10 +278,12 @@ impl Provider for AnthropicProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let resp: AnthropicResponse = response.json().await.map_err(|e| LLMError::ParseError {
@@ -213,6 +293,12 @@ impl Provider for AnthropicProvider {
         Ok(convert_response(resp))
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        let mut req = request.clone();
+        req.stream = true;
+        Some(self.build_request_body(&req))
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let mut req = request;
         req.stream = true;
@@ -226,7 +312,7 @@ impl Provider for AnthropicProvider {
 
         let response = self
             .http_client
-            .post(ANTHROPIC_API_URL)
+            .post(format!("{}/messages", self.base_url))
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", ANTHROPIC_VERSION)
             .header("content-type", "application/json")
@@ -237,10 +323,12 @@ impl Provider for AnthropicProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
@@ -264,7 +352,7 @@ impl Provider for AnthropicProvider {
 
         let response = self
             .http_client
-            .post(ANTHROPIC_API_URL)
+            .post(format!("{}/messages", self.base_url))
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", ANTHROPIC_VERSION)
             .header("content-type", "application/json")
@@ -275,10 +363,12 @@ impl Provider for AnthropicProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         Ok(())
@@ -404,10 +494,15 @@ fn parse_sse_event(event_str: &str) -> Option<StreamChunk> {
                 _ => StopReason::EndTurn,
             });
 
+            let count = |u: &serde_json::Map<String, serde_json::Value>, key: &str| {
+                u.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32
+            };
             let usage = json["usage"].as_object().map(|u| Usage {
-                input_tokens: u.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
-                output_tokens: u.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                input_tokens: count(u, "input_tokens"),
+                output_tokens: count(u, "output_tokens"),
                 thinking_tokens: None,
+                cache_creation_tokens: count(u, "cache_creation_input_tokens"),
+                cache_read_tokens: count(u, "cache_read_input_tokens"),
             });
 
             stop_reason.map(|reason| StreamChunk::Stop { reason, usage })
@@ -423,6 +518,7 @@ fn parse_sse_event(event_str: &str) -> Option<StreamChunk> {
             Some(StreamChunk::Error(LLMError::ProviderError {
                 status: 500,
                 message: error_msg,
+                retry_after_secs: None,
             }))
         }
         _ => None,
@@ -444,15 +540,16 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
         match error_type {
             "authentication_error" => LLMError::AuthError { message },
             "rate_limit_error" => LLMError::RateLimit {
-                retry_after_secs: 60,
+                retry_after_secs: None,
             },
             "invalid_request_error" => LLMError::InvalidRequest { message },
-            _ => LLMError::ProviderError { status, message },
+            _ => LLMError::ProviderError { status, message, retry_after_secs: None },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -473,6 +570,16 @@ fn convert_response(resp: AnthropicResponse) -> LLMResponse {
         })
         .collect();
 
+    // The API folds thinking into output_tokens; estimate the share (~4 chars/token)
+    let thinking_chars: usize = content
+        .iter()
+        .filter_map(|c| match c {
+            ContentBlock::Thinking(t) => Some(t.len()),
+            _ => None,
+        })
+        .sum();
+    let thinking_tokens = (thinking_chars > 0).then(|| thinking_chars.div_ceil(4) as u32);
+
     let stop_reason = match resp.stop_reason.as_deref() {
         Some("end_turn") => StopReason::EndTurn,
         Some("max_tokens") => StopReason::MaxTokens,
@@ -489,7 +596,9 @@ fn convert_response(resp: AnthropicResponse) -> LLMResponse {
         usage: Usage {
             input_tokens: resp.usage.input_tokens,
             output_tokens: resp.usage.output_tokens,
-            thinking_tokens: None,
+            thinking_tokens,
+            cache_creation_tokens: resp.usage.cache_creation_input_tokens,
+            cache_read_tokens: resp.usage.cache_read_input_tokens,
         },
     }
 }
@@ -518,11 +627,139 @@ struct AnthropicContentBlock {
 struct AnthropicUsage {
     input_tokens: u32,
     output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm::types::{Message, Role, ThinkingConfig, ToolResult, ToolResultContent};
+
+    fn thinking_request(model: &str, budget: u32) -> LLMRequest {
+        LLMRequest {
+            model: model.to_string(),
+            messages: vec![Message::user("hi")],
+            max_tokens: Some(8192),
+            temperature: Some(0.7),
+            thinking: Some(ThinkingConfig { enabled: true, budget_tokens: Some(budget) }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_sse_thinking_stream() {
+        let start = "event: content_block_start\ndata: {\"index\":0,\"content_block\":{\"type\":\"thinking\",\"thinking\":\"\"}}";
+        assert!(matches!(
+            parse_sse_event(start),
+            Some(StreamChunk::BlockStart { index: 0, block_type: BlockType::Thinking, .. })
+        ));
+
+        let delta = "event: content_block_delta\ndata: {\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Considering\"}}";
+        assert!(matches!(parse_sse_event(delta), Some(StreamChunk::Delta(StreamDelta::Thinking(t))) if t == "Considering"));
+    }
+
+    #[test]
+    fn test_thinking_budget_in_request_body() {
+        let provider = AnthropicProvider::new("key");
+        let body = provider.build_request_body(&thinking_request("claude-sonnet-4-20250514", 4096));
+        assert_eq!(body["thinking"]["type"], "enabled");
+        assert_eq!(body["thinking"]["budget_tokens"], 4096);
+        assert!(body.get("temperature").is_none());
+
+        // Clamped below max_tokens
+        let body = provider.build_request_body(&thinking_request("claude-sonnet-4-20250514", 50_000));
+        assert_eq!(body["thinking"]["budget_tokens"], 8191);
+    }
+
+    #[test]
+    fn test_thinking_ignored_when_unsupported() {
+        let provider = AnthropicProvider::new("key");
+        let body = provider.build_request_body(&thinking_request("claude-3-5-sonnet-20241022", 4096));
+        assert!(body.get("thinking").is_none());
+        assert_eq!(body["temperature"], json!(0.7f32));
+
+        let mut request = thinking_request("claude-sonnet-4-20250514", 4096);
+        request.messages.push(Message {
+            role: Role::User,
+            content: vec![ContentBlock::ToolResult(ToolResult {
+                tool_use_id: "t1".to_string(),
+                content: ToolResultContent::Text("ok".to_string()),
+                is_error: false,
+            })],
+        });
+        assert!(provider.build_request_body(&request).get("thinking").is_none());
+    }
+
+    #[test]
+    fn test_image_block_in_request_body() {
+        use crate::llm::types::{ImageContent, ImageSource};
+
+        let provider = AnthropicProvider::new("key");
+        let request = LLMRequest {
+            model: "claude-sonnet-4-20250514".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: vec![
+                    ContentBlock::Image(ImageContent {
+                        source: ImageSource::Base64("iVBORw0KGgo=".to_string()),
+                        media_type: "image/png".to_string(),
+                    }),
+                    ContentBlock::Text("what is this error?".to_string()),
+                ],
+            }],
+            ..Default::default()
+        };
+        let body = provider.build_request_body(&request);
+        let image = &body["messages"][0]["content"][0];
+        assert_eq!(image["type"], "image");
+        assert_eq!(image["source"]["type"], "base64");
+        assert_eq!(image["source"]["media_type"], "image/png");
+        assert_eq!(body["messages"][0]["content"][1]["text"], "what is this error?");
+    }
+
+    #[test]
+    fn test_prompt_cache_markers() {
+        use crate::llm::types::ToolDefinition;
+
+        let provider = AnthropicProvider::new("key");
+        let tool = |name: &str| ToolDefinition {
+            name: name.to_string(),
+            description: String::new(),
+            input_schema: json!({ "type": "object" }),
+        };
+        let mut request = LLMRequest {
+            model: "claude-sonnet-4-20250514".to_string(),
+            system: Some("You are helpful".to_string()),
+            messages: vec![Message::user("hi")],
+            tools: vec![tool("file_read"), tool("bash")],
+            ..Default::default()
+        };
+        let body = provider.build_request_body(&request);
+        assert_eq!(body["system"], "You are helpful");
+        assert!(body["tools"][1].get("cache_control").is_none());
+
+        request.prompt_cache = true;
+        let body = provider.build_request_body(&request);
+        assert_eq!(body["system"][0]["text"], "You are helpful");
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"]["type"], "ephemeral");
+    }
+
+    #[test]
+    fn test_parse_cache_usage() {
+        let event = "event: message_delta\ndata: {\"delta\":{\"stop_reason\":\"end_turn\"},\"usage\":{\"input_tokens\":12,\"output_tokens\":40,\"cache_creation_input_tokens\":2048,\"cache_read_input_tokens\":0}}";
+        let Some(StreamChunk::Stop { usage: Some(usage), .. }) = parse_sse_event(event) else {
+            panic!("expected a stop chunk with usage");
+        };
+        assert_eq!((usage.input_tokens, usage.cache_creation_tokens, usage.cache_read_tokens), (12, 2048, 0));
+
+        let usage: AnthropicUsage = serde_json::from_str("{\"input_tokens\":5,\"output_tokens\":7}").unwrap();
+        assert_eq!((usage.cache_creation_input_tokens, usage.cache_read_input_tokens), (0, 0));
+    }
 
     #[test]
     fn test_parse_sse_message_start() {
@@ -0,0 +1,499 @@
+// Code Search - Structured, bounded code search for the search_code tool
+//
+// Uses ripgrep when it is installed and falls back to a built-in walker
+// (regex + .gitignore) otherwise. Both paths return the same structured
+// matches so the model sees identical output either way.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde::Serialize;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::Command;
+
+/// Longest matched line returned to the model; longer lines are cut
+const MAX_LINE_CHARS: usize = 500;
+
+/// Files larger than this are skipped by the built-in walker (2MB)
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Parameters for a code search
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    pub pattern: String,
+    /// Optional glob filter, e.g. `*.rs` or `src/**/*.ts`
+    pub path_glob: Option<String>,
+    pub max_results: usize,
+    /// Search files that .gitignore would normally hide
+    pub include_ignored: bool,
+}
+
+/// A single match, with a 1-indexed line and column
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeMatch {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+/// Result of a search, capped at `max_results`
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchOutcome {
+    pub matches: Vec<CodeMatch>,
+    pub truncated: bool,
+    pub engine: &'static str,
+}
+
+impl SearchOutcome {
+    fn push(&mut self, m: CodeMatch, max_results: usize) -> bool {
+        if self.matches.len() >= max_results {
+            self.truncated = true;
+            return false;
+        }
+        self.matches.push(m);
+        true
+    }
+}
+
+/// Search `root` for `request.pattern`, reporting files relative to `display_base`.
+/// Tries ripgrep first and falls back to the built-in walker if it isn't installed.
+pub async fn search(
+    root: &Path,
+    display_base: &Path,
+    request: &SearchRequest,
+) -> Result<SearchOutcome, String> {
+    // Validate up front so both engines reject bad patterns the same way
+    let regex = Regex::new(&request.pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    match search_ripgrep(root, display_base, request).await {
+        Some(result) => result,
+        None => {
+            let root = root.to_path_buf();
+            let display_base = display_base.to_path_buf();
+            let request = request.clone();
+            tokio::task::spawn_blocking(move || search_builtin(&root, &display_base, &request, &regex))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+    }
+}
+
+/// Run ripgrep with JSON output. Returns None if ripgrep is not available.
+/// Output is read as it streams and ripgrep is killed once the result cap is
+/// hit, so a broad pattern in a large tree never buffers every match.
+async fn search_ripgrep(
+    root: &Path,
+    display_base: &Path,
+    request: &SearchRequest,
+) -> Option<Result<SearchOutcome, String>> {
+    let mut cmd = Command::new("rg");
+    cmd.arg("--json")
+        .arg("--no-follow")
+        // No file can contribute more than the cap; one extra marks truncation
+        .arg("--max-count")
+        .arg((request.max_results + 1).to_string())
+        .arg("--max-filesize")
+        .arg(MAX_FILE_BYTES.to_string());
+    if request.include_ignored {
+        cmd.arg("--no-ignore").arg("--hidden");
+    }
+    if let Some(ref glob) = request.path_glob {
+        cmd.arg("--glob").arg(glob);
+    }
+    cmd.arg("-e")
+        .arg(&request.pattern)
+        .arg(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Some(Err("ripgrep output unavailable".to_string()));
+    };
+    // Drain stderr alongside stdout so a noisy run can't block on a full pipe
+    let stderr_task = tokio::spawn(async move {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text).await;
+        text
+    });
+
+    let mut outcome = SearchOutcome { matches: Vec::new(), truncated: false, engine: "ripgrep" };
+    let mut saw_output = false;
+    let mut lines = BufReader::new(stdout).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => return Some(Err(e.to_string())),
+        };
+        saw_output = true;
+        let Some(m) = parse_rg_match(&line, display_base) else { continue };
+        if !outcome.push(m, request.max_results) {
+            let _ = child.kill().await;
+            return Some(Ok(outcome));
+        }
+    }
+
+    let status = match child.wait().await {
+        Ok(status) => status,
+        Err(e) => return Some(Err(e.to_string())),
+    };
+    // Exit code 1 means "no matches"; 2 is a real error
+    if status.code() == Some(2) && !saw_output {
+        let stderr = stderr_task.await.unwrap_or_default();
+        return Some(Err(stderr.trim().to_string()));
+    }
+    Some(Ok(outcome))
+}
+
+/// Parse a `"type": "match"` record from `rg --json`
+fn parse_rg_match(line: &str, display_base: &Path) -> Option<CodeMatch> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    if json.get("type")?.as_str()? != "match" {
+        return None;
+    }
+    let data = json.get("data")?;
+    let path = data.get("path")?.get("text")?.as_str()?;
+    let line_number = data.get("line_number")?.as_u64()? as usize;
+    let text = data.get("lines")?.get("text")?.as_str().unwrap_or("");
+    let byte_offset = data
+        .get("submatches")
+        .and_then(|s| s.as_array())
+        .and_then(|s| s.first())
+        .and_then(|s| s.get("start"))
+        .and_then(|s| s.as_u64())
+        .unwrap_or(0) as usize;
+
+    Some(CodeMatch {
+        file: display_path(Path::new(path), display_base),
+        line: line_number,
+        column: column_for_byte(text, byte_offset),
+        text: clip_line(text),
+    })
+}
+
+/// Pure-Rust search used when ripgrep is unavailable
+fn search_builtin(
+    root: &Path,
+    display_base: &Path,
+    request: &SearchRequest,
+    regex: &Regex,
+) -> Result<SearchOutcome, String> {
+    let path_glob = request
+        .path_glob
+        .as_deref()
+        .map(Pattern::new)
+        .transpose()
+        .map_err(|e| format!("Invalid path_glob: {}", e))?;
+
+    let mut outcome = SearchOutcome { matches: Vec::new(), truncated: false, engine: "builtin" };
+
+    if root.is_file() {
+        search_file(root, display_base, regex, request.max_results, &mut outcome);
+        return Ok(outcome);
+    }
+
+    let mut stack: Vec<(PathBuf, Vec<IgnoreRule>)> = vec![(root.to_path_buf(), Vec::new())];
+    while let Some((dir, inherited)) = stack.pop() {
+        let mut rules = inherited;
+        if !request.include_ignored {
+            rules.extend(IgnoreRule::load(&dir));
+        }
+
+        let mut entries: Vec<_> = match std::fs::read_dir(&dir) {
+            Ok(rd) => rd.filter_map(|e| e.ok()).collect(),
+            Err(_) => continue,
+        };
+        entries.sort_by_key(|e| e.file_name());
+
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            // Never follow symlinks out of the search root
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_symlink() {
+                continue;
+            }
+            let path = entry.path();
+            let is_dir = file_type.is_dir();
+            let name = entry.file_name();
+
+            if is_dir && name == ".git" {
+                continue;
+            }
+            if !request.include_ignored
+                && (name.to_string_lossy().starts_with('.') || is_ignored(&rules, &path, is_dir))
+            {
+                continue;
+            }
+
+            if is_dir {
+                subdirs.push(path);
+            } else {
+                let rel = path.strip_prefix(root).unwrap_or(&path);
+                if let Some(ref glob) = path_glob {
+                    if !glob_matches(glob, rel) {
+                        continue;
+                    }
+                }
+                if !search_file(&path, display_base, regex, request.max_results, &mut outcome) {
+                    return Ok(outcome);
+                }
+            }
+        }
+
+        // Reverse so the stack visits directories in sorted order
+        for sub in subdirs.into_iter().rev() {
+            stack.push((sub, rules.clone()));
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Search one file; returns false once the result cap is hit
+fn search_file(
+    path: &Path,
+    display_base: &Path,
+    regex: &Regex,
+    max_results: usize,
+    outcome: &mut SearchOutcome,
+) -> bool {
+    if std::fs::metadata(path).map(|m| m.len() > MAX_FILE_BYTES).unwrap_or(true) {
+        return true;
+    }
+    let Ok(bytes) = std::fs::read(path) else { return true };
+    if bytes[..bytes.len().min(8192)].contains(&0) {
+        return true; // Binary
+    }
+    let content = String::from_utf8_lossy(&bytes);
+
+    for (idx, line) in content.lines().enumerate() {
+        if let Some(m) = regex.find(line) {
+            let found = CodeMatch {
+                file: display_path(path, display_base),
+                line: idx + 1,
+                column: column_for_byte(line, m.start()),
+                text: clip_line(line),
+            };
+            if !outcome.push(found, max_results) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// A single .gitignore line, anchored to the directory that contains it
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    base: PathBuf,
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn load(dir: &Path) -> Vec<IgnoreRule> {
+        std::fs::read_to_string(dir.join(".gitignore"))
+            .map(|content| content.lines().filter_map(|l| Self::parse(dir, l)).collect())
+            .unwrap_or_default()
+    }
+
+    fn parse(base: &Path, line: &str) -> Option<IgnoreRule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // A slash anywhere but the end anchors the pattern to this directory
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        Some(IgnoreRule {
+            base: base.to_path_buf(),
+            pattern: Pattern::new(line).ok()?,
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(rel) = path.strip_prefix(&self.base) else { return false };
+        if self.anchored {
+            self.pattern.matches_path_with(rel, literal_separator())
+        } else {
+            rel.file_name()
+                .map(|n| self.pattern.matches(&n.to_string_lossy()))
+                .unwrap_or(false)
+        }
+    }
+}
+
+/// Last matching rule wins, as in git
+fn is_ignored(rules: &[IgnoreRule], path: &Path, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|r| r.matches(path, is_dir))
+        .map(|r| !r.negated)
+        .unwrap_or(false)
+}
+
+/// Globs without a slash match the file name, like ripgrep's `--glob`
+fn glob_matches(glob: &Pattern, rel: &Path) -> bool {
+    if glob.as_str().contains('/') {
+        glob.matches_path_with(rel, literal_separator())
+    } else {
+        rel.file_name()
+            .map(|n| glob.matches(&n.to_string_lossy()))
+            .unwrap_or(false)
+    }
+}
+
+fn literal_separator() -> MatchOptions {
+    MatchOptions { require_literal_separator: true, ..MatchOptions::new() }
+}
+
+fn display_path(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base).unwrap_or(path).to_string_lossy().to_string()
+}
+
+/// Convert a byte offset within a line to a 1-indexed character column
+fn column_for_byte(line: &str, byte_offset: usize) -> usize {
+    let offset = byte_offset.min(line.len());
+    line.char_indices().take_while(|(i, _)| *i < offset).count() + 1
+}
+
+fn clip_line(line: &str) -> String {
+    let line = line.trim_end_matches(['\n', '\r']);
+    if line.chars().count() > MAX_LINE_CHARS {
+        let clipped: String = line.chars().take(MAX_LINE_CHARS).collect();
+        format!("{}…", clipped)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(pattern: &str) -> SearchRequest {
+        SearchRequest {
+            pattern: pattern.to_string(),
+            path_glob: None,
+            max_results: 100,
+            include_ignored: false,
+        }
+    }
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {\n    let needle = 1;\n}\n").unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn needle() {}\n").unwrap();
+        std::fs::write(dir.path().join("target/out.rs"), "needle\n").unwrap();
+        std::fs::write(dir.path().join("debug.log"), "needle\n").unwrap();
+        dir
+    }
+
+    fn run(dir: &Path, req: &SearchRequest) -> SearchOutcome {
+        let regex = Regex::new(&req.pattern).unwrap();
+        search_builtin(dir, dir, req, &regex).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_reports_line_and_column() {
+        let dir = fixture();
+        let outcome = run(dir.path(), &request("needle"));
+
+        assert_eq!(outcome.matches.len(), 2);
+        assert_eq!(
+            outcome.matches[1],
+            CodeMatch {
+                file: "src/main.rs".to_string(),
+                line: 2,
+                column: 9,
+                text: "    let needle = 1;".to_string(),
+            }
+        );
+        assert!(!outcome.truncated);
+    }
+
+    #[test]
+    fn test_builtin_respects_gitignore() {
+        let dir = fixture();
+        let files: Vec<_> = run(dir.path(), &request("needle"))
+            .matches
+            .into_iter()
+            .map(|m| m.file)
+            .collect();
+        assert!(!files.iter().any(|f| f.starts_with("target") || f.ends_with(".log")));
+
+        let mut req = request("needle");
+        req.include_ignored = true;
+        let files: Vec<_> = run(dir.path(), &req).matches.into_iter().map(|m| m.file).collect();
+        assert!(files.contains(&"target/out.rs".to_string()));
+        assert!(files.contains(&"debug.log".to_string()));
+    }
+
+    #[test]
+    fn test_builtin_path_glob_and_cap() {
+        let dir = fixture();
+        let mut req = request("needle");
+        req.path_glob = Some("lib.rs".to_string());
+        let outcome = run(dir.path(), &req);
+        assert_eq!(outcome.matches.len(), 1);
+        assert_eq!(outcome.matches[0].file, "src/lib.rs");
+
+        let mut req = request("needle");
+        req.max_results = 1;
+        let outcome = run(dir.path(), &req);
+        assert_eq!(outcome.matches.len(), 1);
+        assert!(outcome.truncated);
+    }
+
+    #[test]
+    fn test_gitignore_negation() {
+        let base = Path::new("/repo");
+        let rules: Vec<_> = ["*.log", "!keep.log"]
+            .iter()
+            .filter_map(|l| IgnoreRule::parse(base, l))
+            .collect();
+        assert!(is_ignored(&rules, Path::new("/repo/a/debug.log"), false));
+        assert!(!is_ignored(&rules, Path::new("/repo/keep.log"), false));
+    }
+
+    #[test]
+    fn test_parse_rg_match() {
+        let line = r#"{"type":"match","data":{"path":{"text":"/w/src/a.rs"},"lines":{"text":"  héllo world\n"},"line_number":7,"submatches":[{"match":{"text":"world"},"start":9,"end":14}]}}"#;
+        let m = parse_rg_match(line, Path::new("/w")).unwrap();
+        assert_eq!(m.file, "src/a.rs");
+        assert_eq!(m.line, 7);
+        assert_eq!(m.column, 9);
+        assert_eq!(m.text, "  héllo world");
+        assert!(parse_rg_match(r#"{"type":"begin","data":{}}"#, Path::new("/w")).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+// Capture of the last LLM request/response for debugging provider issues
+//
+// Enabled with `debug_capture = true` in llm.toml. Only the most recent
+// exchange is kept; every new attempt (including retries and fallbacks)
+// replaces it.
+
+use std::sync::{Arc, Mutex};
+
+use super::types::{LLMError, StreamChunk};
+use crate::redact::{is_secret_name, redact_text, REDACTED};
+
+/// Stream events kept per exchange before later ones are dropped
+const MAX_RESPONSE_EVENTS: usize = 10_000;
+
+/// One request sent to a provider and what came back
+#[derive(Debug, Clone)]
+pub struct LlmExchange {
+    pub provider: String,
+    pub model: String,
+    pub started_at: chrono::DateTime<chrono::Local>,
+    /// Serialized request body (None if the provider does not expose it)
+    pub request: Option<serde_json::Value>,
+    /// Stream events in arrival order, one per line
+    pub response: Vec<String>,
+    /// Events dropped after `MAX_RESPONSE_EVENTS`
+    pub dropped_events: usize,
+    pub error: Option<String>,
+}
+
+impl LlmExchange {
+    /// Copy with API keys and key-shaped strings replaced by `<redacted>`
+    pub fn redacted(&self, known_secrets: &[&str]) -> Self {
+        let mut exchange = self.clone();
+        if let Some(request) = exchange.request.as_mut() {
+            redact_json(request, known_secrets);
+        }
+        for line in exchange.response.iter_mut() {
+            *line = redact_text(line, known_secrets);
+        }
+        exchange.error = exchange.error.map(|e| redact_text(&e, known_secrets));
+        exchange
+    }
+
+    /// Plain-text rendering for the pager
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Provider: {}\nModel: {}\nStarted: {}\n\n── Request ──\n",
+            self.provider,
+            self.model,
+            self.started_at.format("%Y-%m-%d %H:%M:%S"),
+        );
+        match &self.request {
+            Some(body) => out.push_str(&serde_json::to_string_pretty(body).unwrap_or_default()),
+            None => out.push_str("(provider does not expose its request body)"),
+        }
+        out.push_str(&format!("\n\n── Response ({} events) ──\n", self.response.len() + self.dropped_events));
+        for line in &self.response {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if self.dropped_events > 0 {
+            out.push_str(&format!("… {} more events not kept\n", self.dropped_events));
+        }
+        if let Some(error) = &self.error {
+            out.push_str(&format!("\n── Error ──\n{}\n", error));
+        }
+        out
+    }
+}
+
+/// Shared slot holding the most recent exchange
+#[derive(Debug, Clone, Default)]
+pub struct DebugCapture {
+    last: Arc<Mutex<Option<LlmExchange>>>,
+}
+
+impl DebugCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new exchange, replacing the previous one
+    pub fn begin(&self, provider: &str, model: &str, request: Option<serde_json::Value>) {
+        let exchange = LlmExchange {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            started_at: chrono::Local::now(),
+            request,
+            response: Vec::new(),
+            dropped_events: 0,
+            error: None,
+        };
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(exchange);
+        }
+    }
+
+    pub fn record_chunk(&self, chunk: &StreamChunk) {
+        self.update(|exchange| {
+            if exchange.response.len() < MAX_RESPONSE_EVENTS {
+                exchange.response.push(format!("{:?}", chunk));
+            } else {
+                exchange.dropped_events += 1;
+            }
+        });
+    }
+
+    pub fn record_error(&self, error: &LLMError) {
+        self.update(|exchange| exchange.error = Some(error.to_string()));
+    }
+
+    pub fn last(&self) -> Option<LlmExchange> {
+        self.last.lock().ok().and_then(|last| last.clone())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LlmExchange)) {
+        if let Ok(mut last) = self.last.lock() {
+            if let Some(exchange) = last.as_mut() {
+                f(exchange);
+            }
+        }
+    }
+}
+
+fn redact_json(value: &mut serde_json::Value, known_secrets: &[&str]) {
+    match value {
+        serde_json::Value::String(s) => *s = redact_text(s, known_secrets),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| redact_json(v, known_secrets)),
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if v.is_string() && is_secret_name(key) {
+                    *v = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(v, known_secrets);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::types::StreamDelta;
+
+    #[test]
+    fn test_capture_keeps_only_latest_exchange() {
+        let capture = DebugCapture::new();
+        capture.begin("anthropic", "claude", Some(serde_json::json!({"model": "claude"})));
+        capture.record_chunk(&StreamChunk::Delta(StreamDelta::Text("first".to_string())));
+
+        capture.begin("openai", "gpt-4o", None);
+        capture.record_chunk(&StreamChunk::Delta(StreamDelta::Text("second".to_string())));
+        capture.record_error(&LLMError::StreamInterrupted);
+
+        let last = capture.last().unwrap();
+        assert_eq!(last.provider, "openai");
+        assert_eq!(last.response.len(), 1);
+        assert!(last.response[0].contains("second"));
+        assert!(last.render().contains("── Error ──"));
+    }
+
+    #[test]
+    fn test_redaction() {
+        let capture = DebugCapture::new();
+        capture.begin(
+            "openai",
+            "gpt-4o",
+            Some(serde_json::json!({
+                "max_tokens": 1024,
+                "api_key": "plain-value",
+                "messages": [{"content": "my key is sk-abcdefghijklmnopqrstuv and my-own-secret"}],
+                "tools": [{"input_schema": {"properties": {"key": {"type": "string"}}}}],
+            })),
+        );
+        let exchange = capture.last().unwrap().redacted(&["my-own-secret"]);
+        let body = exchange.request.unwrap();
+
+        assert_eq!(body["max_tokens"], 1024);
+        assert_eq!(body["api_key"], REDACTED);
+        assert_eq!(body["messages"][0]["content"], "my key is <redacted> and <redacted>");
+        assert_eq!(body["tools"][0]["input_schema"]["properties"]["key"]["type"], "string");
+    }
+}
@@ -15,11 +15,14 @@ use super::types::{
     StreamDelta, ToolUse, Usage,
 };
 
-const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+/// API root; endpoint paths are appended. Overridable via `base_url` in llm.toml
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
 /// Google Gemini provider
 pub struct GeminiProvider {
     api_key: String,
+    /// API root without a trailing slash
+    base_url: String,
     http_client: Client,
     models: Vec<ModelInfo>,
     default_model: String,
@@ -51,12 +54,25 @@ impl GeminiProvider {
 
         Self {
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
             http_client,
             models,
             default_model: "gemini-2.5-flash".to_string(),
         }
     }
 
+    /// Use a preconfigured client (extra headers, custom CA bundle)
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Send requests to a proxy or gateway instead of the public API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     fn build_request_body(&self, request: &LLMRequest) -> serde_json::Value {
         let mut contents: Vec<serde_json::Value> = Vec::new();
 
@@ -136,6 +152,19 @@ impl GeminiProvider {
             generation_config["temperature"] = json!(temp);
         }
 
+        // Thought summaries are only returned when asked for
+        let model = if request.model.is_empty() { &self.default_model } else { &request.model };
+        let thinking = request.thinking.as_ref().filter(|t| t.enabled);
+        if let Some(thinking) = thinking {
+            if self.models.iter().any(|m| &m.id == model && m.supports_thinking) {
+                let mut thinking_config = json!({ "includeThoughts": true });
+                if let Some(budget) = thinking.budget_tokens {
+                    thinking_config["thinkingBudget"] = json!(budget);
+                }
+                generation_config["thinkingConfig"] = thinking_config;
+            }
+        }
+
         if generation_config.as_object().is_some_and(|o| !o.is_empty()) {
             body["generationConfig"] = generation_config;
         }
@@ -167,8 +196,8 @@ impl GeminiProvider {
             "generateContent"
         };
         format!(
-            "{}/{}:{}?key={}",
-            GEMINI_API_URL, model, action, self.api_key
+            "{}/models/{}:{}?key={}",
+            self.base_url, model, action, self.api_key
         )
     }
 }
@@ -220,8 +249,9 @@ impl Provider for GeminiProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let resp: GeminiResponse = response.json().await.map_err(|e| LLMError::ParseError {
@@ -231,6 +261,10 @@ impl Provider for GeminiProvider {
         Ok(convert_response(resp, model))
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        Some(self.build_request_body(request))
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let model = if request.model.is_empty() {
             self.default_model.clone()
@@ -254,8 +288,9 @@ impl Provider for GeminiProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
@@ -290,8 +325,9 @@ impl Provider for GeminiProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         Ok(())
@@ -307,6 +343,7 @@ async fn parse_stream(
     let mut sent_start = false;
     let mut block_index: usize = 0;
     let mut in_function_call = false;
+    let mut in_thought = false;
 
     while let Some(chunk_result) = byte_stream.next().await {
         let chunk = match chunk_result {
@@ -330,6 +367,7 @@ async fn parse_stream(
                 &mut sent_start,
                 &mut block_index,
                 &mut in_function_call,
+                &mut in_thought,
             ) {
                 for chunk in chunks {
                     if tx.send(Ok(chunk)).await.is_err() {
@@ -398,6 +436,7 @@ fn parse_json_chunk(
     sent_start: &mut bool,
     block_index: &mut usize,
     in_function_call: &mut bool,
+    in_thought: &mut bool,
 ) -> Option<Vec<StreamChunk>> {
     let json: serde_json::Value = serde_json::from_str(json_str).ok()?;
     let mut chunks = Vec::new();
@@ -416,26 +455,58 @@ fn parse_json_chunk(
             if let Some(content) = candidate["content"].as_object() {
                 if let Some(parts) = content.get("parts").and_then(|p| p.as_array()) {
                     for part in parts {
+                        // Thought summaries are text parts flagged with "thought": true
+                        if part["thought"].as_bool() == Some(true) {
+                            if let Some(text) = part["text"].as_str() {
+                                if !*in_thought {
+                                    *in_thought = true;
+                                    chunks.push(StreamChunk::BlockStart {
+                                        index: *block_index,
+                                        block_type: BlockType::Thinking,
+                                        tool_id: None,
+                                        tool_name: None,
+                                    });
+                                }
+                                chunks.push(StreamChunk::Delta(StreamDelta::Thinking(text.to_string())));
+                            }
+                            continue;
+                        }
+
                         if let Some(text) = part["text"].as_str() {
-                            if *in_function_call {
+                            if *in_thought {
+                                // Thoughts finished; the answer gets its own text block
                                 chunks.push(StreamChunk::BlockStop {
                                     index: *block_index,
                                 });
                                 *block_index += 1;
-                                *in_function_call = false;
-                            }
-
-                            if *block_index == 0
-                                || !chunks
-                                    .iter()
-                                    .any(|c| matches!(c, StreamChunk::BlockStart { .. }))
-                            {
+                                *in_thought = false;
                                 chunks.push(StreamChunk::BlockStart {
                                     index: *block_index,
                                     block_type: BlockType::Text,
                                     tool_id: None,
                                     tool_name: None,
                                 });
+                            } else {
+                                if *in_function_call {
+                                    chunks.push(StreamChunk::BlockStop {
+                                        index: *block_index,
+                                    });
+                                    *block_index += 1;
+                                    *in_function_call = false;
+                                }
+
+                                if *block_index == 0
+                                    || !chunks
+                                        .iter()
+                                        .any(|c| matches!(c, StreamChunk::BlockStart { .. }))
+                                {
+                                    chunks.push(StreamChunk::BlockStart {
+                                        index: *block_index,
+                                        block_type: BlockType::Text,
+                                        tool_id: None,
+                                        tool_name: None,
+                                    });
+                                }
                             }
 
                             chunks.push(StreamChunk::Delta(StreamDelta::Text(text.to_string())));
@@ -446,7 +517,13 @@ fn parse_json_chunk(
                             let tool_id = format!("call_{}", uuid::Uuid::new_v4());
                             
                             if !*in_function_call {
-                                if *block_index > 0 {
+                                if *in_thought {
+                                    chunks.push(StreamChunk::BlockStop {
+                                        index: *block_index,
+                                    });
+                                    *block_index += 1;
+                                    *in_thought = false;
+                                } else if *block_index > 0 {
                                     chunks.push(StreamChunk::BlockStop {
                                         index: *block_index - 1,
                                     });
@@ -503,7 +580,11 @@ fn parse_json_chunk(
                         .get("candidatesTokenCount")
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0) as u32,
-                    thinking_tokens: None,
+                    thinking_tokens: u
+                        .get("thoughtsTokenCount")
+                        .and_then(|v| v.as_u64())
+                        .map(|v| v as u32),
+                    ..Default::default()
                 });
 
                 chunks.push(StreamChunk::Stop { reason, usage });
@@ -519,6 +600,7 @@ fn parse_json_chunk(
         chunks.push(StreamChunk::Error(LLMError::ProviderError {
             status: error["code"].as_u64().unwrap_or(500) as u16,
             message,
+            retry_after_secs: None,
         }));
     }
 
@@ -541,7 +623,7 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
         match status {
             401 | 403 => LLMError::AuthError { message },
             429 => LLMError::RateLimit {
-                retry_after_secs: 60,
+                retry_after_secs: None,
             },
             400 => {
                 if message.contains("API key") {
@@ -554,12 +636,14 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
             _ => LLMError::ProviderError {
                 status: status_code,
                 message,
+                retry_after_secs: None,
             },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -571,7 +655,11 @@ fn convert_response(resp: GeminiResponse, model: &str) -> LLMResponse {
     if let Some(parts) = candidate.content.and_then(|c| c.parts) {
         for part in parts {
             if let Some(text) = part.text {
-                content.push(ContentBlock::Text(text));
+                if part.thought {
+                    content.push(ContentBlock::Thinking(text));
+                } else {
+                    content.push(ContentBlock::Text(text));
+                }
             }
             if let Some(function_call) = part.function_call {
                 content.push(ContentBlock::ToolUse(ToolUse {
@@ -595,6 +683,7 @@ fn convert_response(resp: GeminiResponse, model: &str) -> LLMResponse {
         input_tokens: u.prompt_token_count,
         output_tokens: u.candidates_token_count,
         thinking_tokens: None,
+        ..Default::default()
     });
 
     LLMResponse {
@@ -631,6 +720,8 @@ struct GeminiContent {
 #[serde(rename_all = "camelCase")]
 struct GeminiPart {
     text: Option<String>,
+    #[serde(default)]
+    thought: bool,
     function_call: Option<GeminiFunctionCall>,
 }
 
@@ -692,6 +783,48 @@ mod tests {
         assert!(matches!(err, LLMError::RateLimit { .. }));
     }
 
+    #[test]
+    fn test_parse_thought_parts_as_thinking() {
+        let mut sent_start = false;
+        let mut block_index = 0;
+        let mut in_function_call = false;
+        let mut in_thought = false;
+        let events = [
+            r#"{"candidates":[{"content":{"parts":[{"text":"Weighing options","thought":true}],"role":"model"}}]}"#,
+            r#"{"candidates":[{"content":{"parts":[{"text":"The answer"}],"role":"model"}}]}"#,
+        ];
+
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .filter_map(|e| {
+                parse_json_chunk(e, "gemini-2.5-pro", &mut sent_start, &mut block_index, &mut in_function_call, &mut in_thought)
+            })
+            .flatten()
+            .collect();
+
+        assert!(matches!(chunks[1], StreamChunk::BlockStart { index: 0, block_type: BlockType::Thinking, .. }));
+        assert!(matches!(&chunks[2], StreamChunk::Delta(StreamDelta::Thinking(t)) if t == "Weighing options"));
+        assert!(matches!(chunks[3], StreamChunk::BlockStop { index: 0 }));
+        assert!(matches!(chunks[4], StreamChunk::BlockStart { index: 1, block_type: BlockType::Text, .. }));
+        assert!(matches!(&chunks[5], StreamChunk::Delta(StreamDelta::Text(t)) if t == "The answer"));
+    }
+
+    #[test]
+    fn test_include_thoughts_requested_for_thinking_models() {
+        let provider = GeminiProvider::new("key");
+        let mut request = LLMRequest {
+            model: "gemini-2.5-pro".to_string(),
+            thinking: Some(crate::llm::types::ThinkingConfig { enabled: true, budget_tokens: Some(2048) }),
+            ..Default::default()
+        };
+        let body = provider.build_request_body(&request);
+        assert_eq!(body["generationConfig"]["thinkingConfig"]["includeThoughts"], true);
+        assert_eq!(body["generationConfig"]["thinkingConfig"]["thinkingBudget"], 2048);
+
+        request.model = "gemini-2.0-flash".to_string();
+        assert!(provider.build_request_body(&request)["generationConfig"].get("thinkingConfig").is_none());
+    }
+
     #[test]
     fn test_convert_response_text() {
         let resp = GeminiResponse {
@@ -699,6 +832,7 @@ mod tests {
                 content: Some(GeminiContent {
                     parts: Some(vec![GeminiPart {
                         text: Some("Hello, world!".to_string()),
+                        thought: false,
                         function_call: None,
                     }]),
                 }),
@@ -726,6 +860,7 @@ mod tests {
                 content: Some(GeminiContent {
                     parts: Some(vec![GeminiPart {
                         text: None,
+                        thought: false,
                         function_call: Some(GeminiFunctionCall {
                             name: "get_weather".to_string(),
                             args: Some(json!({"location": "London"})),
@@ -15,11 +15,14 @@ use super::types::{
     StreamDelta, ToolUse, Usage,
 };
 
-const GROK_API_URL: &str = "https://api.x.ai/v1/chat/completions";
+/// API root; endpoint paths are appended. Overridable via `base_url` in llm.toml
+const DEFAULT_BASE_URL: &str = "https://api.x.ai/v1";
 
 /// xAI Grok provider (OpenAI-compatible API)
 pub struct GrokProvider {
     api_key: String,
+    /// API root without a trailing slash
+    base_url: String,
     http_client: Client,
     models: Vec<ModelInfo>,
     default_model: String,
@@ -69,12 +72,25 @@ impl GrokProvider {
 
         Self {
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
             http_client,
             models,
             default_model: "grok-4-fast-non-reasoning".to_string(),
         }
     }
 
+    /// Use a preconfigured client (extra headers, custom CA bundle)
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Send requests to a proxy or gateway instead of the public API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     fn build_request_body(&self, request: &LLMRequest) -> serde_json::Value {
         let mut messages: Vec<serde_json::Value> = Vec::new();
 
@@ -268,7 +284,7 @@ impl Provider for GrokProvider {
 
         let response = self
             .http_client
-            .post(GROK_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -280,8 +296,9 @@ impl Provider for GrokProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let resp: GrokResponse = response.json().await.map_err(|e| LLMError::ParseError {
@@ -291,6 +308,12 @@ impl Provider for GrokProvider {
         Ok(convert_response(resp))
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        let mut req = request.clone();
+        req.stream = true;
+        Some(self.build_request_body(&req))
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let mut req = request;
         req.stream = true;
@@ -299,7 +322,7 @@ impl Provider for GrokProvider {
 
         let response = self
             .http_client
-            .post(GROK_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -311,8 +334,9 @@ impl Provider for GrokProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
@@ -336,7 +360,7 @@ impl Provider for GrokProvider {
 
         let response = self
             .http_client
-            .post(GROK_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -348,8 +372,9 @@ impl Provider for GrokProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         Ok(())
@@ -366,6 +391,7 @@ async fn parse_sse_stream(
     let mut current_tool_name = String::new();
     let mut block_index: usize = 0;
     let mut in_tool_block = false;
+    let mut in_thinking_block = false;
 
     while let Some(chunk_result) = byte_stream.next().await {
         let chunk = match chunk_result {
@@ -402,6 +428,7 @@ async fn parse_sse_stream(
                     &mut current_tool_name,
                     &mut block_index,
                     &mut in_tool_block,
+                    &mut in_thinking_block,
                 ) {
                     for chunk in chunks {
                         if tx.send(Ok(chunk)).await.is_err() {
@@ -421,6 +448,7 @@ fn parse_sse_data(
     current_tool_name: &mut String,
     block_index: &mut usize,
     in_tool_block: &mut bool,
+    in_thinking_block: &mut bool,
 ) -> Option<Vec<StreamChunk>> {
     let json: serde_json::Value = serde_json::from_str(data).ok()?;
     let mut chunks = Vec::new();
@@ -437,6 +465,7 @@ fn parse_sse_data(
                 tool_id: None,
                 tool_name: None,
             });
+            *block_index = 1;
         }
     }
 
@@ -444,6 +473,31 @@ fn parse_sse_data(
         for choice in choices {
             let delta = &choice["delta"];
 
+            // Reasoning arrives alongside content; route it to a thinking block
+            // (xAI reasoning models stream "reasoning_content")
+            let reasoning_value = delta["reasoning_content"].as_str()
+                .or_else(|| delta["reasoning"].as_str());
+            if let Some(reasoning) = reasoning_value {
+                if !reasoning.is_empty() {
+                    if !*in_thinking_block {
+                        *in_thinking_block = true;
+                        if *block_index > 0 {
+                            chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
+                        }
+                        chunks.push(StreamChunk::BlockStart {
+                            index: *block_index,
+                            block_type: BlockType::Thinking,
+                            tool_id: None,
+                            tool_name: None,
+                        });
+                        *block_index += 1;
+                    }
+                    chunks.push(StreamChunk::Delta(StreamDelta::Thinking(
+                        reasoning.to_string(),
+                    )));
+                }
+            }
+
             if let Some(tool_calls) = delta["tool_calls"].as_array() {
                 for tool_call in tool_calls {
                     let tc_index = tool_call["index"].as_u64().unwrap_or(0) as usize;
@@ -455,6 +509,8 @@ fn parse_sse_data(
                             chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
                         }
                         
+                        *in_thinking_block = false;
+                        
                         *current_tool_id = id.to_string();
                         *in_tool_block = true;
                         
@@ -492,8 +548,9 @@ fn parse_sse_data(
 
             if let Some(content) = delta["content"].as_str() {
                 if !content.is_empty() {
-                    if *in_tool_block {
+                    if *in_tool_block || *in_thinking_block {
                         *in_tool_block = false;
+                        *in_thinking_block = false;
                         chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
                         chunks.push(StreamChunk::BlockStart {
                             index: *block_index,
@@ -530,6 +587,7 @@ fn parse_sse_data(
                         .and_then(|d| d.get("reasoning_tokens"))
                         .and_then(|v| v.as_u64())
                         .map(|v| v as u32),
+                    ..Default::default()
                 });
 
                 chunks.push(StreamChunk::Stop { reason, usage });
@@ -556,7 +614,7 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
         match (error_type, error_code) {
             ("invalid_api_key", _) | (_, "invalid_api_key") => LLMError::AuthError { message },
             ("rate_limit_error", _) | (_, "rate_limit_exceeded") => LLMError::RateLimit {
-                retry_after_secs: 60,
+                retry_after_secs: None,
             },
             ("invalid_request_error", _) => LLMError::InvalidRequest { message },
             ("model_not_found", _) | (_, "model_not_found") => LLMError::ModelNotFound {
@@ -565,12 +623,13 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
             ("content_filter", _) | (_, "content_policy_violation") => {
                 LLMError::ContentFiltered { reason: message }
             }
-            _ => LLMError::ProviderError { status, message },
+            _ => LLMError::ProviderError { status, message, retry_after_secs: None },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -587,8 +646,7 @@ fn convert_response(resp: GrokResponse) -> LLMResponse {
 
     if let Some(tool_calls) = choice.message.tool_calls {
         for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+            let input = ToolUse::parse_input(&tc.function.arguments);
             content.push(ContentBlock::ToolUse(ToolUse {
                 id: tc.id,
                 name: tc.function.name,
@@ -615,6 +673,7 @@ fn convert_response(resp: GrokResponse) -> LLMResponse {
             output_tokens: resp.usage.completion_tokens,
             thinking_tokens: resp.usage.completion_tokens_details
                 .and_then(|d| d.reasoning_tokens),
+            ..Default::default()
         },
     }
 }
@@ -678,6 +737,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 0;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -686,6 +746,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -703,6 +764,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 1;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -711,6 +773,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -725,6 +788,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 1;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -733,6 +797,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -771,6 +836,50 @@ mod tests {
         assert_eq!(response.usage.thinking_tokens, Some(100));
     }
 
+    #[test]
+    fn test_parse_sse_reasoning_then_text() {
+        let events = [
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"reasoning_content":"Let me think"},"index":0}]}"#,
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"reasoning_content":" it through"},"index":0}]}"#,
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"content":"Answer"},"index":0}]}"#,
+        ];
+        let mut message_id = String::new();
+        let mut tool_id = String::new();
+        let mut tool_name = String::new();
+        let mut block_index = 0;
+        let mut in_tool = false;
+        let mut in_thinking = false;
+
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .filter_map(|data| {
+                parse_sse_data(
+                    data,
+                    &mut message_id,
+                    &mut tool_id,
+                    &mut tool_name,
+                    &mut block_index,
+                    &mut in_tool,
+                    &mut in_thinking,
+                )
+            })
+            .flatten()
+            .collect();
+
+        assert!(chunks.iter().any(|c| matches!(c, StreamChunk::BlockStart { index: 1, block_type: BlockType::Thinking, .. })));
+        let thinking: String = chunks
+            .iter()
+            .filter_map(|c| match c {
+                StreamChunk::Delta(StreamDelta::Thinking(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(thinking, "Let me think it through");
+        assert!(chunks.iter().any(|c| matches!(c, StreamChunk::BlockStart { index: 2, block_type: BlockType::Text, .. })));
+        assert!(matches!(chunks.last(), Some(StreamChunk::Delta(StreamDelta::Text(t))) if t == "Answer"));
+        assert!(!in_thinking);
+    }
+
     #[test]
     fn test_parse_error_response() {
         let body = r#"{"error":{"message":"Invalid API key","type":"invalid_api_key","code":"invalid_api_key"}}"#;
@@ -15,11 +15,14 @@ use super::types::{
     StreamDelta, ToolUse, Usage,
 };
 
-const GROQ_API_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+/// API root; endpoint paths are appended. Overridable via `base_url` in llm.toml
+const DEFAULT_BASE_URL: &str = "https://api.groq.com/openai/v1";
 
 /// Groq provider (OpenAI-compatible API with fast inference)
 pub struct GroqProvider {
     api_key: String,
+    /// API root without a trailing slash
+    base_url: String,
     http_client: Client,
     models: Vec<ModelInfo>,
     default_model: String,
@@ -64,12 +67,25 @@ impl GroqProvider {
 
         Self {
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
             http_client,
             models,
             default_model: "llama-3.3-70b-versatile".to_string(),
         }
     }
 
+    /// Use a preconfigured client (extra headers, custom CA bundle)
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Send requests to a proxy or gateway instead of the public API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
     fn build_request_body(&self, request: &LLMRequest) -> serde_json::Value {
         let mut messages: Vec<serde_json::Value> = Vec::new();
 
@@ -172,6 +188,11 @@ impl GroqProvider {
             body["temperature"] = json!(temp);
         }
 
+        // Return reasoning in its own field rather than inline <think> tags
+        if self.models.iter().any(|m| &m.id == model && m.supports_thinking) {
+            body["reasoning_format"] = json!("parsed");
+        }
+
         if !request.tools.is_empty() {
             let tools: Vec<serde_json::Value> = request
                 .tools
@@ -258,7 +279,7 @@ impl Provider for GroqProvider {
 
         let response = self
             .http_client
-            .post(GROQ_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -270,8 +291,9 @@ impl Provider for GroqProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let resp: GroqResponse = response.json().await.map_err(|e| LLMError::ParseError {
@@ -281,6 +303,12 @@ impl Provider for GroqProvider {
         Ok(convert_response(resp))
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        let mut req = request.clone();
+        req.stream = true;
+        Some(self.build_request_body(&req))
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let mut req = request;
         req.stream = true;
@@ -289,7 +317,7 @@ impl Provider for GroqProvider {
 
         let response = self
             .http_client
-            .post(GROQ_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -301,8 +329,9 @@ impl Provider for GroqProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
@@ -326,7 +355,7 @@ impl Provider for GroqProvider {
 
         let response = self
             .http_client
-            .post(GROQ_API_URL)
+            .post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&body)
@@ -338,8 +367,9 @@ impl Provider for GroqProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         Ok(())
@@ -356,6 +386,7 @@ async fn parse_sse_stream(
     let mut current_tool_name = String::new();
     let mut block_index: usize = 0;
     let mut in_tool_block = false;
+    let mut in_thinking_block = false;
 
     while let Some(chunk_result) = byte_stream.next().await {
         let chunk = match chunk_result {
@@ -392,6 +423,7 @@ async fn parse_sse_stream(
                     &mut current_tool_name,
                     &mut block_index,
                     &mut in_tool_block,
+                    &mut in_thinking_block,
                 ) {
                     for chunk in chunks {
                         if tx.send(Ok(chunk)).await.is_err() {
@@ -411,6 +443,7 @@ fn parse_sse_data(
     current_tool_name: &mut String,
     block_index: &mut usize,
     in_tool_block: &mut bool,
+    in_thinking_block: &mut bool,
 ) -> Option<Vec<StreamChunk>> {
     let json: serde_json::Value = serde_json::from_str(data).ok()?;
     let mut chunks = Vec::new();
@@ -427,6 +460,7 @@ fn parse_sse_data(
                 tool_id: None,
                 tool_name: None,
             });
+            *block_index = 1;
         }
     }
 
@@ -434,6 +468,31 @@ fn parse_sse_data(
         for choice in choices {
             let delta = &choice["delta"];
 
+            // Reasoning arrives alongside content; route it to a thinking block
+            // (Groq streams "reasoning" when reasoning_format is "parsed")
+            let reasoning_value = delta["reasoning"].as_str()
+                .or_else(|| delta["reasoning_content"].as_str());
+            if let Some(reasoning) = reasoning_value {
+                if !reasoning.is_empty() {
+                    if !*in_thinking_block {
+                        *in_thinking_block = true;
+                        if *block_index > 0 {
+                            chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
+                        }
+                        chunks.push(StreamChunk::BlockStart {
+                            index: *block_index,
+                            block_type: BlockType::Thinking,
+                            tool_id: None,
+                            tool_name: None,
+                        });
+                        *block_index += 1;
+                    }
+                    chunks.push(StreamChunk::Delta(StreamDelta::Thinking(
+                        reasoning.to_string(),
+                    )));
+                }
+            }
+
             if let Some(tool_calls) = delta["tool_calls"].as_array() {
                 for tool_call in tool_calls {
                     let tc_index = tool_call["index"].as_u64().unwrap_or(0) as usize;
@@ -447,6 +506,8 @@ fn parse_sse_data(
                             });
                         }
 
+                        *in_thinking_block = false;
+
                         *current_tool_id = id.to_string();
                         *in_tool_block = true;
 
@@ -484,8 +545,9 @@ fn parse_sse_data(
 
             if let Some(content) = delta["content"].as_str() {
                 if !content.is_empty() {
-                    if *in_tool_block {
+                    if *in_tool_block || *in_thinking_block {
                         *in_tool_block = false;
+                        *in_thinking_block = false;
                         chunks.push(StreamChunk::BlockStop {
                             index: *block_index - 1,
                         });
@@ -526,6 +588,7 @@ fn parse_sse_data(
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0) as u32,
                     thinking_tokens: None,
+                    ..Default::default()
                 });
 
                 chunks.push(StreamChunk::Stop { reason, usage });
@@ -552,7 +615,7 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
         match (error_type, error_code) {
             ("invalid_api_key", _) | (_, "invalid_api_key") => LLMError::AuthError { message },
             ("rate_limit_error", _) | (_, "rate_limit_exceeded") => LLMError::RateLimit {
-                retry_after_secs: 60,
+                retry_after_secs: None,
             },
             ("invalid_request_error", _) => LLMError::InvalidRequest { message },
             ("model_not_found", _) | (_, "model_not_found") => LLMError::ModelNotFound {
@@ -561,12 +624,13 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
             ("content_filter", _) | (_, "content_policy_violation") => {
                 LLMError::ContentFiltered { reason: message }
             }
-            _ => LLMError::ProviderError { status, message },
+            _ => LLMError::ProviderError { status, message, retry_after_secs: None },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -583,8 +647,7 @@ fn convert_response(resp: GroqResponse) -> LLMResponse {
 
     if let Some(tool_calls) = choice.message.tool_calls {
         for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+            let input = ToolUse::parse_input(&tc.function.arguments);
             content.push(ContentBlock::ToolUse(ToolUse {
                 id: tc.id,
                 name: tc.function.name,
@@ -610,6 +673,7 @@ fn convert_response(resp: GroqResponse) -> LLMResponse {
             input_tokens: resp.usage.prompt_tokens,
             output_tokens: resp.usage.completion_tokens,
             thinking_tokens: None,
+            ..Default::default()
         },
     }
 }
@@ -667,6 +731,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 0;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -675,6 +740,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -701,6 +767,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 1;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -709,6 +776,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -725,6 +793,7 @@ mod tests {
         let mut tool_name = String::new();
         let mut block_index = 1;
         let mut in_tool = false;
+        let mut in_thinking = false;
 
         let chunks = parse_sse_data(
             data,
@@ -733,6 +802,7 @@ mod tests {
             &mut tool_name,
             &mut block_index,
             &mut in_tool,
+            &mut in_thinking,
         )
         .unwrap();
 
@@ -773,6 +843,50 @@ mod tests {
         assert_eq!(response.usage.output_tokens, 8);
     }
 
+    #[test]
+    fn test_parse_sse_reasoning_then_text() {
+        let events = [
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"reasoning":"Let me think"},"index":0}]}"#,
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"reasoning":" it through"},"index":0}]}"#,
+            r#"{"id":"chatcmpl-1","choices":[{"delta":{"content":"Answer"},"index":0}]}"#,
+        ];
+        let mut message_id = String::new();
+        let mut tool_id = String::new();
+        let mut tool_name = String::new();
+        let mut block_index = 0;
+        let mut in_tool = false;
+        let mut in_thinking = false;
+
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .filter_map(|data| {
+                parse_sse_data(
+                    data,
+                    &mut message_id,
+                    &mut tool_id,
+                    &mut tool_name,
+                    &mut block_index,
+                    &mut in_tool,
+                    &mut in_thinking,
+                )
+            })
+            .flatten()
+            .collect();
+
+        assert!(chunks.iter().any(|c| matches!(c, StreamChunk::BlockStart { index: 1, block_type: BlockType::Thinking, .. })));
+        let thinking: String = chunks
+            .iter()
+            .filter_map(|c| match c {
+                StreamChunk::Delta(StreamDelta::Thinking(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(thinking, "Let me think it through");
+        assert!(chunks.iter().any(|c| matches!(c, StreamChunk::BlockStart { index: 2, block_type: BlockType::Text, .. })));
+        assert!(matches!(chunks.last(), Some(StreamChunk::Delta(StreamDelta::Text(t))) if t == "Answer"));
+        assert!(!in_thinking);
+    }
+
     #[test]
     fn test_parse_error_response() {
         let body = r#"{"error":{"message":"Invalid API key","type":"invalid_api_key","code":"invalid_api_key"}}"#;
@@ -0,0 +1,153 @@
+// HTTP client settings shared by the hosted LLM providers and SSE streams
+//
+// Builds one reqwest client carrying the `extra_headers` and `ca_bundle`
+// settings from llm.toml; streams get the same `ca_bundle` but not the
+// headers. Proxies come from the standard HTTP_PROXY / HTTPS_PROXY /
+// ALL_PROXY / NO_PROXY environment variables, which reqwest reads for every
+// client it builds.
+
+use std::path::Path;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, ClientBuilder};
+
+use crate::config::LLMConfig;
+use crate::redact::{is_secret_name, REDACTED};
+
+/// "Name: value" pairs for logging, with sensitive values redacted
+pub fn describe_headers(headers: &HeaderMap) -> String {
+    let mut parts: Vec<String> = headers
+        .iter()
+        .map(|(name, value)| {
+            let shown = if is_secret_name(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.to_str().unwrap_or("<binary>").to_string()
+            };
+            format!("{}: {}", name, shown)
+        })
+        .collect();
+    parts.sort();
+    parts.join(", ")
+}
+
+/// Parse `extra_headers` into a header map, rejecting invalid names or values
+pub fn header_map(headers: &std::collections::HashMap<String, String>) -> Result<HeaderMap, String> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name '{}'", name))?;
+        let mut header_value =
+            HeaderValue::from_str(value).map_err(|_| format!("Invalid value for header '{}'", name))?;
+        if is_secret_name(name) {
+            header_value.set_sensitive(true);
+        }
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+/// Seconds from a `Retry-After` header, given either as a number of seconds
+/// or as an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<u32> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u32>() {
+        return Some(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(secs.clamp(0, u32::MAX as i64) as u32)
+}
+
+fn load_ca_bundle(path: &Path) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem = std::fs::read(path).map_err(|e| format!("Failed to read CA bundle {}: {}", path.display(), e))?;
+    let certs = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("Invalid CA bundle {}: {}", path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("CA bundle {} contains no certificates", path.display()));
+    }
+    Ok(certs)
+}
+
+/// Client builder trusting the certificates in `ca_bundle` as well as the system roots
+pub fn client_builder(ca_bundle: Option<&Path>) -> Result<ClientBuilder, String> {
+    let mut builder = Client::builder();
+    if let Some(path) = ca_bundle {
+        let certs = load_ca_bundle(path)?;
+        tracing::info!("Trusting {} certificate(s) from {}", certs.len(), path.display());
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    Ok(builder)
+}
+
+/// Client for the hosted providers, or None when llm.toml sets no extra
+/// headers or CA bundle (providers then keep their default client)
+pub fn build_client(config: &LLMConfig) -> Result<Option<Client>, String> {
+    if config.extra_headers.is_empty() && config.ca_bundle.is_none() {
+        return Ok(None);
+    }
+
+    let headers = header_map(&config.extra_headers)?;
+    if !headers.is_empty() {
+        tracing::info!("LLM requests send extra headers: {}", describe_headers(&headers));
+    }
+    client_builder(config.ca_bundle.as_deref())?
+        .default_headers(headers)
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sensitive_headers_are_redacted() {
+        let headers = header_map(&HashMap::from([
+            ("Authorization".to_string(), "Bearer secret".to_string()),
+            ("X-Gateway-Token".to_string(), "abc".to_string()),
+            ("X-Team".to_string(), "platform".to_string()),
+        ]))
+        .unwrap();
+
+        let described = describe_headers(&headers);
+        assert!(described.contains("x-team: platform"));
+        assert!(described.contains("authorization: <redacted>"));
+        assert!(described.contains("x-gateway-token: <redacted>"));
+        assert!(!described.contains("secret"));
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let header = |value: &str| HeaderMap::from_iter([(reqwest::header::RETRY_AFTER, HeaderValue::from_str(value).unwrap())]);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&header("12")), Some(12));
+        assert_eq!(retry_after(&header("soon")), None);
+        assert_eq!(retry_after(&header("Wed, 21 Oct 2015 07:28:00 GMT")), Some(0));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        assert!(retry_after(&header(&later)).is_some_and(|secs| (85..=90).contains(&secs)));
+    }
+
+    #[test]
+    fn test_build_client() {
+        assert!(build_client(&LLMConfig::default()).unwrap().is_none());
+
+        let mut config = LLMConfig::default();
+        config.extra_headers.insert("bad header".to_string(), "x".to_string());
+        assert!(build_client(&config).is_err());
+
+        let config = LLMConfig {
+            ca_bundle: Some("/nonexistent/ca.pem".into()),
+            ..Default::default()
+        };
+        assert!(build_client(&config).unwrap_err().contains("CA bundle"));
+
+        let mut config = LLMConfig::default();
+        config.extra_headers.insert("X-Team".to_string(), "platform".to_string());
+        assert!(build_client(&config).unwrap().is_some());
+    }
+}
@@ -2,22 +2,25 @@
 #![allow(dead_code)]
 
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use futures::StreamExt;
 use tokio::sync::mpsc;
 
-use crate::config::{KeyId, KeyStore, LLMConfig};
+use crate::config::{AuthScheme, KeyId, KeyStore, LLMConfig};
 
 use std::collections::HashMap;
 
 use super::anthropic::AnthropicProvider;
+use super::debug_capture::{DebugCapture, LlmExchange};
 use super::gemini::GeminiProvider;
 use super::grok::GrokProvider;
 use super::groq::GroqProvider;
 use super::ollama::OllamaProvider;
 use super::openai::OpenAIProvider;
-use super::provider::{Provider, ProviderRegistry};
-use super::types::{LLMError, LLMRequest, Message, StreamChunk, StreamDelta, BlockType, ToolUse, ContentBlock, ToolResult, ToolDefinition};
+use super::provider::{Capability, Provider, ProviderRegistry};
+use super::rate_limit::{RateLimitState, RateLimits};
+use super::types::{LLMError, LLMRequest, Message, StreamChunk, StreamDelta, BlockType, ToolUse, ContentBlock, ToolResult, ToolDefinition, ThinkingConfig};
 
 /// Helper struct for assembling tool uses from streaming chunks
 struct PendingToolUse {
@@ -27,19 +30,19 @@ struct PendingToolUse {
 }
 
 /// Tool use assembler that tracks pending tool uses by block index
-struct ToolAssembler {
+pub(crate) struct ToolAssembler {
     pending_tools: HashMap<usize, PendingToolUse>,
 }
 
 impl ToolAssembler {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             pending_tools: HashMap::new(),
         }
     }
 
     /// Process a stream chunk and return any completed tool uses
-    fn process_chunk(&mut self, chunk: &StreamChunk) -> Option<ToolUse> {
+    pub(crate) fn process_chunk(&mut self, chunk: &StreamChunk) -> Option<ToolUse> {
         match chunk {
             StreamChunk::BlockStart { index, block_type, tool_id, tool_name } => {
                 if *block_type == BlockType::ToolUse {
@@ -65,35 +68,17 @@ impl ToolAssembler {
             }
             StreamChunk::BlockStop { index } => {
                 if let Some(p) = self.pending_tools.remove(index) {
-                    // Handle empty input: Anthropic sometimes sends empty string for tools with no required params
-                    let input_to_parse = if p.input_buf.trim().is_empty() {
-                        "{}".to_string()
-                    } else {
-                        p.input_buf.clone()
+                    // Empty input (Anthropic sends "" for tools without arguments) parses as {};
+                    // invalid JSON is still emitted so the tool returns an error result
+                    let tool_use = ToolUse {
+                        input: ToolUse::parse_input(&p.input_buf),
+                        id: p.id,
+                        name: p.name,
                     };
-                    
-                    match serde_json::from_str::<serde_json::Value>(&input_to_parse) {
-                        Ok(input_value) => {
-                            Some(ToolUse {
-                                id: p.id,
-                                name: p.name,
-                                input: input_value,
-                            })
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                "Failed to parse tool input JSON for tool {}: {}",
-                                p.name,
-                                e
-                            );
-                            // Still emit the tool so we can return an error result to the API
-                            Some(ToolUse {
-                                id: p.id,
-                                name: p.name,
-                                input: serde_json::json!({"_parse_error": e.to_string()}),
-                            })
-                        }
+                    if let Some(e) = tool_use.input_parse_error() {
+                        tracing::warn!("Failed to parse tool input JSON for tool {}: {}", tool_use.name, e);
                     }
+                    Some(tool_use)
                 } else {
                     None
                 }
@@ -103,6 +88,29 @@ impl ToolAssembler {
     }
 }
 
+/// Retries on the same model before moving down the fallback chain
+const MAX_RETRIES: u32 = 2;
+
+/// Delay before the first retry; doubles on each subsequent attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait before a retry; a server asking for more is not retried
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Wait before retry number `retry` (1-based): exponential backoff with ±20%
+/// jitter, or the server's `Retry-After` when that is longer
+fn retry_delay(base: Duration, retry: u32, error: &LLMError) -> Duration {
+    let backoff = base.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+    let jitter_range = (backoff.as_millis() / 5) as u64;
+    let backoff = if jitter_range == 0 {
+        backoff
+    } else {
+        let jitter = fastrand::u64(0..=jitter_range * 2);
+        (backoff + Duration::from_millis(jitter)).saturating_sub(Duration::from_millis(jitter_range))
+    };
+    error.retry_after().map_or(backoff, |wait| wait.max(backoff))
+}
+
 /// Event from the LLM subsystem
 #[derive(Debug, Clone)]
 pub enum LLMEvent {
@@ -111,8 +119,239 @@ pub enum LLMEvent {
     Error(LLMError),
     /// Tool use detected, needs handling
     ToolUseDetected(ToolUse),
+    /// Primary model failed; this turn continues on a fallback model.
+    /// `after` is the provider whose error caused the switch.
+    Fallback { provider: String, model: String, after: String },
+    /// Transient error before any output; the same model is tried again after `delay`
+    Retrying { retry: u32, max_retries: u32, delay: Duration, error: LLMError },
+    /// The provider's rate limit is used up; the request goes out after `delay`
+    Throttled { provider: String, delay: Duration },
+}
+
+/// How transient failures are retried on one model
+struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+}
+
+/// A provider/model pair to try, in fallback-chain order
+struct Attempt {
+    provider: Arc<dyn Provider>,
+    model: String,
+}
+
+/// Outcome of a single streaming attempt
+enum StreamOutcome {
+    /// Stream finished, was cancelled, or failed after output was forwarded
+    Done,
+    /// Failed before any output was forwarded, so another attempt is safe
+    Failed(LLMError),
+}
+
+/// Stream one request, forwarding chunks and detected tool uses to `event_tx`
+async fn run_stream(
+    provider: &Arc<dyn Provider>,
+    request: LLMRequest,
+    event_tx: &mpsc::UnboundedSender<LLMEvent>,
+    cancel_rx: &mut mpsc::Receiver<()>,
+    capture: Option<&DebugCapture>,
+) -> StreamOutcome {
+    let has_images = request.messages.iter()
+        .any(|m| m.content.iter().any(|b| matches!(b, ContentBlock::Image(_))));
+    if has_images && !provider.supports(Capability::Vision) {
+        return StreamOutcome::Failed(LLMError::InvalidRequest {
+            message: format!("{} doesn't accept images; switch to a vision-capable provider", provider.name()),
+        });
+    }
+    if let Some(capture) = capture {
+        capture.begin(provider.name(), &request.model, provider.request_body(&request));
+    }
+    let mut stream = match provider.stream(request).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::error!("Failed to create stream: {}", e);
+            if let Some(capture) = capture {
+                capture.record_error(&e);
+            }
+            return StreamOutcome::Failed(e);
+        }
+    };
+
+    let mut tool_assembler = ToolAssembler::new();
+    let mut forwarded = false;
+    loop {
+        tokio::select! {
+            chunk = stream.next() => {
+                match chunk {
+                    Some(Ok(c)) => {
+                        tracing::trace!("Got stream chunk: {:?}", c);
+                        if let Some(capture) = capture {
+                            capture.record_chunk(&c);
+                        }
+                        // Check for completed tool uses
+                        if let Some(tool_use) = tool_assembler.process_chunk(&c) {
+                            let _ = event_tx.send(LLMEvent::ToolUseDetected(tool_use));
+                        }
+                        // Always forward the raw chunk for UI
+                        if event_tx.send(LLMEvent::Chunk(c)).is_err() {
+                            tracing::warn!("Event channel closed");
+                            return StreamOutcome::Done;
+                        }
+                        forwarded = true;
+                    }
+                    Some(Err(e)) => {
+                        tracing::error!("Stream error: {}", e);
+                        if let Some(capture) = capture {
+                            capture.record_error(&e);
+                        }
+                        if !forwarded {
+                            return StreamOutcome::Failed(e);
+                        }
+                        let _ = event_tx.send(LLMEvent::Error(e));
+                        return StreamOutcome::Done;
+                    }
+                    None => {
+                        tracing::info!("Stream complete");
+                        let _ = event_tx.send(LLMEvent::Complete);
+                        return StreamOutcome::Done;
+                    }
+                }
+            }
+            _ = cancel_rx.recv() => {
+                tracing::info!("Stream cancelled");
+                let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
+                return StreamOutcome::Done;
+            }
+        }
+    }
+}
+
+/// Hold a request while `provider`'s rate limit is used up (at most
+/// `MAX_RETRY_DELAY`). Returns false if cancelled while waiting.
+async fn wait_for_rate_limit(
+    provider: &str,
+    rate_limits: &RateLimits,
+    event_tx: &mpsc::UnboundedSender<LLMEvent>,
+    cancel_rx: &mut mpsc::Receiver<()>,
+) -> bool {
+    let Some(wait) = rate_limits.get(provider).and_then(|state| state.wait(Instant::now())) else {
+        return true;
+    };
+    let delay = wait.min(MAX_RETRY_DELAY);
+    tracing::info!("{} rate limit used up, holding request for {:?}", provider, delay);
+    let _ = event_tx.send(LLMEvent::Throttled { provider: provider.to_string(), delay });
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => true,
+        _ = cancel_rx.recv() => {
+            let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
+            false
+        }
+    }
 }
 
+/// Stream a request, retrying transient failures and then walking the fallback chain.
+/// The first attempt is the primary model; every later one emits `LLMEvent::Fallback`.
+/// An auth error moves on at once and skips later attempts on the same provider.
+async fn stream_with_fallback(
+    attempts: Vec<Attempt>,
+    mut request: LLMRequest,
+    event_tx: mpsc::UnboundedSender<LLMEvent>,
+    mut cancel_rx: mpsc::Receiver<()>,
+    retry: RetryPolicy,
+    rate_limits: RateLimits,
+    capture: Option<DebugCapture>,
+) {
+    let RetryPolicy { max_retries, base_delay: retry_base_delay } = retry;
+    // Provider and error of the last failed attempt
+    let mut failure: Option<(String, LLMError)> = None;
+    for attempt in attempts {
+        if let Some((failed_provider, error)) = &failure {
+            if error.is_auth_error() && failed_provider == attempt.provider.name() {
+                continue;
+            }
+            tracing::warn!(
+                "Falling back to {} ({}) after {} error",
+                attempt.model,
+                attempt.provider.name(),
+                failed_provider
+            );
+            let _ = event_tx.send(LLMEvent::Fallback {
+                provider: attempt.provider.name().to_string(),
+                model: attempt.model.clone(),
+                after: failed_provider.clone(),
+            });
+        }
+        request.model = attempt.model.clone();
+
+        let mut retries = 0;
+        loop {
+            if !wait_for_rate_limit(attempt.provider.name(), &rate_limits, &event_tx, &mut cancel_rx).await {
+                return;
+            }
+            let error = match run_stream(&attempt.provider, request.clone(), &event_tx, &mut cancel_rx, capture.as_ref()).await {
+                StreamOutcome::Done => return,
+                StreamOutcome::Failed(e) => e,
+            };
+
+            if error.is_auth_error() {
+                tracing::warn!("{} rejected the API key: {}", attempt.provider.name(), error);
+                failure = Some((attempt.provider.name().to_string(), error));
+                break;
+            }
+            if !error.is_retryable() {
+                let _ = event_tx.send(LLMEvent::Error(error));
+                return;
+            }
+
+            let delay = retry_delay(retry_base_delay, retries + 1, &error);
+            if retries < max_retries && delay <= MAX_RETRY_DELAY {
+                retries += 1;
+                tracing::warn!(
+                    "Retryable error from {} ({}), retry {}/{} in {:?}",
+                    attempt.model, error, retries, max_retries, delay
+                );
+                let _ = event_tx.send(LLMEvent::Retrying { retry: retries, max_retries, delay, error });
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancel_rx.recv() => {
+                        let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
+                        return;
+                    }
+                }
+                continue;
+            }
+
+            tracing::warn!("Retries exhausted for {}: {}", attempt.model, error);
+            failure = Some((attempt.provider.name().to_string(), error));
+            break;
+        }
+    }
+
+    // Every attempt failed: report the last error
+    if let Some((_, error)) = failure {
+        let _ = event_tx.send(LLMEvent::Error(error));
+    }
+}
+
+/// Guess which provider serves a model id from its naming convention
+fn provider_hint_for_model(model: &str) -> Option<&'static str> {
+    let model = model.to_lowercase();
+    if model.starts_with("claude") {
+        Some("anthropic")
+    } else if model.starts_with("gpt") || model.starts_with("o1") || model.starts_with("o3") || model.starts_with("o4") {
+        Some("openai")
+    } else if model.starts_with("gemini") {
+        Some("gemini")
+    } else if model.starts_with("grok") {
+        Some("grok")
+    } else {
+        None
+    }
+}
+
+/// Providers that take an API key and a `base_url` override
+const HOSTED_PROVIDERS: [&str; 5] = ["anthropic", "openai", "gemini", "grok", "groq"];
+
 /// Manages LLM providers and handles streaming requests
 pub struct LLMManager {
     registry: ProviderRegistry,
@@ -123,6 +362,29 @@ pub struct LLMManager {
     event_rx: Option<mpsc::UnboundedReceiver<LLMEvent>>,
     cancel_tx: Option<mpsc::Sender<()>>,
     ollama_detected: bool,
+    /// Models tried in order when the current model keeps failing (llm.fallback_models)
+    fallback_models: Vec<String>,
+    /// Providers tried after the fallback models, each with the model to use
+    /// (None = the provider's default model) (llm.fallback_providers)
+    fallback_providers: Vec<(String, Option<String>)>,
+    /// Sampling temperature sent with each request (None = provider default)
+    temperature: Option<f32>,
+    /// Extended thinking request (providers without support ignore it)
+    thinking: Option<ThinkingConfig>,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    /// Base URL overrides for hosted providers, by provider name
+    base_urls: HashMap<String, String>,
+    /// openai `api_version` and auth scheme from llm.toml (Azure OpenAI)
+    openai_endpoint: (Option<String>, AuthScheme),
+    /// Keys of registered hosted providers, so a base URL change can rebuild them
+    api_keys: HashMap<String, String>,
+    /// Shared client for hosted providers when llm.toml sets extra headers or a CA bundle
+    http_client: Option<reqwest::Client>,
+    /// Last request/response, kept when llm.toml sets `debug_capture`
+    debug_capture: Option<DebugCapture>,
+    /// Rate-limit headers last seen from each hosted provider
+    rate_limits: RateLimits,
 }
 
 impl LLMManager {
@@ -138,11 +400,22 @@ impl LLMManager {
             event_rx: Some(event_rx),
             ollama_detected: false,
             cancel_tx: None,
+            fallback_models: Vec::new(),
+            fallback_providers: Vec::new(),
+            temperature: None,
+            thinking: None,
+            max_retries: MAX_RETRIES,
+            retry_base_delay: RETRY_BASE_DELAY,
+            base_urls: HashMap::new(),
+            openai_endpoint: (None, AuthScheme::Bearer),
+            api_keys: HashMap::new(),
+            http_client: None,
+            debug_capture: None,
+            rate_limits: RateLimits::default(),
         }
     }
 
-    pub fn register_anthropic(&mut self, api_key: impl Into<String>) {
-        let provider = Arc::new(AnthropicProvider::new(api_key));
+    fn register_provider(&mut self, provider: Arc<dyn Provider>) {
         let default_model = provider.default_model().to_string();
         let name = provider.name().to_string();
 
@@ -154,56 +427,155 @@ impl LLMManager {
         }
     }
 
-    pub fn register_gemini(&mut self, api_key: impl Into<String>) {
-        let provider = Arc::new(GeminiProvider::new(api_key));
-        let default_model = provider.default_model().to_string();
-        let name = provider.name().to_string();
+    /// Apply the `base_url` overrides from llm.toml to the hosted providers,
+    /// and openai's `api_version`/`auth` for Azure deployments
+    pub fn set_base_urls(&mut self, config: &LLMConfig) {
+        for name in HOSTED_PROVIDERS {
+            self.set_base_url(name, config.base_url_for_provider(name));
+        }
+        let endpoint = (config.api_version_for_provider("openai"), config.auth_for_provider("openai"));
+        if endpoint != self.openai_endpoint {
+            self.openai_endpoint = endpoint;
+            if let Some(key) = self.api_keys.get("openai").cloned() {
+                self.register_openai(key);
+            }
+        }
+    }
 
-        self.registry.register(provider);
+    /// Point a hosted provider at a proxy/gateway (None restores the default).
+    /// An already-registered provider is rebuilt with its existing key.
+    pub fn set_base_url(&mut self, provider: &str, base_url: Option<String>) {
+        let changed = match base_url {
+            Some(url) => self.base_urls.insert(provider.to_string(), url.clone()).as_ref() != Some(&url),
+            None => self.base_urls.remove(provider).is_some(),
+        };
+        if changed {
+            if let Some(key) = self.api_keys.get(provider).cloned() {
+                self.register_key(&KeyId::from_provider_str(provider), &key);
+            }
+        }
+    }
 
-        if self.current_provider.is_empty() {
-            self.current_provider = name;
-            self.current_model = default_model;
+    /// Apply `extra_headers` and `ca_bundle` from llm.toml to the hosted
+    /// providers. On error the previous client is kept.
+    pub fn set_http_settings(&mut self, config: &LLMConfig) -> Result<(), String> {
+        self.http_client = super::http::build_client(config)?;
+        let registered: Vec<(String, String)> = self.api_keys.iter().map(|(p, k)| (p.clone(), k.clone())).collect();
+        for (provider, key) in registered {
+            self.register_key(&KeyId::from_provider_str(&provider), &key);
         }
+        Ok(())
     }
 
-    pub fn register_grok(&mut self, api_key: impl Into<String>) {
-        let provider = Arc::new(GrokProvider::new(api_key));
-        let default_model = provider.default_model().to_string();
-        let name = provider.name().to_string();
+    /// Base URL override in effect for a provider
+    pub fn base_url(&self, provider: &str) -> Option<&str> {
+        self.base_urls.get(provider).map(|s| s.as_str())
+    }
 
-        self.registry.register(provider);
+    /// Register the provider a key belongs to. Returns false for custom keys.
+    pub fn register_key(&mut self, key_id: &KeyId, api_key: &str) -> bool {
+        match key_id {
+            KeyId::Anthropic => self.register_anthropic(api_key),
+            KeyId::OpenAI => self.register_openai(api_key),
+            KeyId::Gemini => self.register_gemini(api_key),
+            KeyId::Grok => self.register_grok(api_key),
+            KeyId::Groq => self.register_groq(api_key),
+            KeyId::Custom(_) => return false,
+        }
+        true
+    }
 
-        if self.current_provider.is_empty() {
-            self.current_provider = name;
-            self.current_model = default_model;
+    pub fn register_anthropic(&mut self, api_key: impl Into<String>) {
+        let api_key = api_key.into();
+        let mut provider = AnthropicProvider::new(api_key.clone()).with_rate_limits(self.rate_limits.clone());
+        if let Some(url) = self.base_urls.get("anthropic") {
+            provider = provider.with_base_url(url.clone());
+        }
+        if let Some(ref client) = self.http_client {
+            provider = provider.with_http_client(client.clone());
         }
+        self.api_keys.insert("anthropic".to_string(), api_key);
+        self.register_provider(Arc::new(provider));
     }
 
-    pub fn register_openai(&mut self, api_key: impl Into<String>) {
-        let provider = Arc::new(OpenAIProvider::new(api_key));
-        let default_model = provider.default_model().to_string();
-        let name = provider.name().to_string();
+    pub fn register_gemini(&mut self, api_key: impl Into<String>) {
+        let api_key = api_key.into();
+        let mut provider = GeminiProvider::new(api_key.clone());
+        if let Some(url) = self.base_urls.get("gemini") {
+            provider = provider.with_base_url(url.clone());
+        }
+        if let Some(ref client) = self.http_client {
+            provider = provider.with_http_client(client.clone());
+        }
+        self.api_keys.insert("gemini".to_string(), api_key);
+        self.register_provider(Arc::new(provider));
+    }
 
-        self.registry.register(provider);
+    pub fn register_grok(&mut self, api_key: impl Into<String>) {
+        let api_key = api_key.into();
+        let mut provider = GrokProvider::new(api_key.clone());
+        if let Some(url) = self.base_urls.get("grok") {
+            provider = provider.with_base_url(url.clone());
+        }
+        if let Some(ref client) = self.http_client {
+            provider = provider.with_http_client(client.clone());
+        }
+        self.api_keys.insert("grok".to_string(), api_key);
+        self.register_provider(Arc::new(provider));
+    }
 
-        if self.current_provider.is_empty() {
-            self.current_provider = name;
-            self.current_model = default_model;
+    pub fn register_openai(&mut self, api_key: impl Into<String>) {
+        let api_key = api_key.into();
+        let (api_version, auth) = self.openai_endpoint.clone();
+        let mut provider = OpenAIProvider::new(api_key.clone())
+            .with_endpoint(api_version, auth)
+            .with_rate_limits(self.rate_limits.clone());
+        if let Some(url) = self.base_urls.get("openai") {
+            provider = provider.with_base_url(url.clone());
+        }
+        if let Some(ref client) = self.http_client {
+            provider = provider.with_http_client(client.clone());
         }
+        self.api_keys.insert("openai".to_string(), api_key);
+        self.register_provider(Arc::new(provider));
     }
 
     pub fn register_groq(&mut self, api_key: impl Into<String>) {
-        let provider = Arc::new(GroqProvider::new(api_key));
-        let default_model = provider.default_model().to_string();
-        let name = provider.name().to_string();
+        let api_key = api_key.into();
+        let mut provider = GroqProvider::new(api_key.clone());
+        if let Some(url) = self.base_urls.get("groq") {
+            provider = provider.with_base_url(url.clone());
+        }
+        if let Some(ref client) = self.http_client {
+            provider = provider.with_http_client(client.clone());
+        }
+        self.api_keys.insert("groq".to_string(), api_key);
+        self.register_provider(Arc::new(provider));
+    }
 
-        self.registry.register(provider);
+    /// Register the scripted "mock" provider (no network or key needed)
+    pub fn register_mock(&mut self, script: super::mock::MockScript) {
+        self.register_provider(Arc::new(super::mock::MockProvider::new(script)));
+    }
 
-        if self.current_provider.is_empty() {
-            self.current_provider = name;
-            self.current_model = default_model;
-        }
+    /// Register the mock provider and return the log of requests it receives
+    #[cfg(test)]
+    pub fn register_recording_mock(
+        &mut self,
+        script: super::mock::MockScript,
+    ) -> Arc<std::sync::Mutex<Vec<LLMRequest>>> {
+        self.register_recording_provider(super::mock::MockProvider::new(script))
+    }
+
+    /// Register a (configured) mock provider and return its request log
+    #[cfg(test)]
+    pub fn register_recording_provider(
+        &mut self,
+        provider: super::mock::MockProvider,
+    ) -> Arc<std::sync::Mutex<Vec<LLMRequest>>> {
+        let (provider, log) = provider.recording();
+        self.register_provider(Arc::new(provider));
+        log
     }
 
     /// Register Ollama local provider (no API key needed)
@@ -243,49 +615,22 @@ impl LLMManager {
         }
     }
 
-    /// Register all providers from a KeyStore
-    /// Returns a list of successfully registered provider names
-    pub fn register_from_keystore(&mut self, keystore: &KeyStore) -> Vec<String> {
-        self.register_from_keystore_with_config(keystore, None)
+    /// Register providers whose keys became readable when the keystore was
+    /// unlocked. Providers already registered keep their key, so keys from the
+    /// command line or environment still take precedence over the keystore.
+    /// Returns the newly registered provider names.
+    pub fn register_unlocked_keys(&mut self, keystore: &KeyStore) -> Vec<String> {
+        self.register_keystore_keys(keystore, true)
     }
 
     /// Register all providers from a KeyStore, with optional LLM config for base URLs
     pub fn register_from_keystore_with_config(&mut self, keystore: &KeyStore, llm_config: Option<&LLMConfig>) -> Vec<String> {
-        let mut registered = Vec::new();
-
-        // Try to get each known provider's key
-        let providers = [
-            (KeyId::Anthropic, "anthropic"),
-            (KeyId::OpenAI, "openai"),
-            (KeyId::Gemini, "gemini"),
-            (KeyId::Grok, "grok"),
-            (KeyId::Groq, "groq"),
-        ];
-
-        for (key_id, name) in providers {
-            match keystore.get(&key_id) {
-                Ok(Some(secret)) => {
-                    tracing::info!("Found key for {} in keystore, registering...", name);
-                    match key_id {
-                        KeyId::Anthropic => self.register_anthropic(secret.expose()),
-                        KeyId::OpenAI => self.register_openai(secret.expose()),
-                        KeyId::Gemini => self.register_gemini(secret.expose()),
-                        KeyId::Grok => self.register_grok(secret.expose()),
-                        KeyId::Groq => self.register_groq(secret.expose()),
-                        KeyId::Custom(_) => continue,
-                    }
-                    registered.push(name.to_string());
-                    tracing::info!("Registered {} provider from keystore", name);
-                }
-                Ok(None) => {
-                    tracing::debug!("No key found for {} in keystore", name);
-                }
-                Err(e) => {
-                    tracing::warn!("Error checking keystore for {}: {}", name, e);
-                }
-            }
+        if let Some(config) = llm_config {
+            self.set_base_urls(config);
         }
 
+        let mut registered = self.register_keystore_keys(keystore, false);
+
         // Auto-register local LLM server (Ollama or llama-server)
         // Use configured base_url if available, otherwise probe defaults
         let ollama_base_url = llm_config
@@ -359,8 +704,45 @@ impl LLMManager {
         registered
     }
 
+    fn register_keystore_keys(&mut self, keystore: &KeyStore, skip_registered: bool) -> Vec<String> {
+        let mut registered = Vec::new();
+
+        // Try to get each known provider's key
+        let providers = [
+            (KeyId::Anthropic, "anthropic"),
+            (KeyId::OpenAI, "openai"),
+            (KeyId::Gemini, "gemini"),
+            (KeyId::Grok, "grok"),
+            (KeyId::Groq, "groq"),
+        ];
+
+        for (key_id, name) in providers {
+            if skip_registered && self.has_provider(name) {
+                continue;
+            }
+            match keystore.get(&key_id) {
+                Ok(Some(secret)) => {
+                    tracing::info!("Found key for {} in keystore, registering...", name);
+                    if !self.register_key(&key_id, secret.expose()) {
+                        continue;
+                    }
+                    registered.push(name.to_string());
+                    tracing::info!("Registered {} provider from keystore", name);
+                }
+                Ok(None) => {
+                    tracing::debug!("No key found for {} in keystore", name);
+                }
+                Err(e) => {
+                    tracing::warn!("Error checking keystore for {}: {}", name, e);
+                }
+            }
+        }
+
+        registered
+    }
+
     /// Re-register Ollama with model discovery if it was detected during keystore registration.
-    /// Call this after `register_from_keystore` in an async context.
+    /// Call this after `register_from_keystore_with_config` in an async context.
     pub async fn discover_ollama_models(&mut self) {
         if !self.ollama_detected {
             return;
@@ -439,6 +821,126 @@ impl LLMManager {
         self.current_model = model.to_string();
     }
 
+    /// Set the fallback chain used after retries on the current model are exhausted
+    pub fn set_fallback_models(&mut self, models: Vec<String>) {
+        self.fallback_models = models;
+    }
+
+    /// Set the providers tried once the fallback models are used up
+    pub fn set_fallback_providers(&mut self, providers: Vec<(String, Option<String>)>) {
+        self.fallback_providers = providers;
+    }
+
+    pub fn set_max_retries(&mut self, max_retries: u32) {
+        self.max_retries = max_retries;
+    }
+
+    pub fn fallback_models(&self) -> &[String] {
+        &self.fallback_models
+    }
+
+    pub fn set_temperature(&mut self, temperature: Option<f32>) {
+        self.temperature = temperature;
+    }
+
+    pub fn set_thinking(&mut self, thinking: Option<ThinkingConfig>) {
+        self.thinking = thinking;
+    }
+
+    /// Name of the registered provider that can serve `model`, if any
+    pub fn provider_name_for_model(&self, model: &str) -> Option<String> {
+        self.provider_for_model(model).map(|p| p.name().to_string())
+    }
+
+    /// Find a registered provider that serves `model`
+    fn provider_for_model(&self, model: &str) -> Option<Arc<dyn Provider>> {
+        if let Some(provider) = self.registry.get(&self.current_provider) {
+            if provider.models().iter().any(|m| m.id == model) {
+                return Some(provider);
+            }
+        }
+        for name in self.registry.list() {
+            if let Some(provider) = self.registry.get(name) {
+                if provider.models().iter().any(|m| m.id == model) {
+                    return Some(provider);
+                }
+            }
+        }
+        provider_hint_for_model(model).and_then(|name| self.registry.get(name))
+    }
+
+    /// Primary provider/model followed by every resolvable fallback model,
+    /// then every registered fallback provider
+    fn build_attempts(&self, primary: Arc<dyn Provider>, primary_model: &str) -> Vec<Attempt> {
+        let mut attempts = vec![Attempt {
+            provider: primary,
+            model: primary_model.to_string(),
+        }];
+        for model in &self.fallback_models {
+            if attempts.iter().any(|a| &a.model == model) {
+                continue;
+            }
+            match self.provider_for_model(model) {
+                Some(provider) => attempts.push(Attempt {
+                    provider,
+                    model: model.clone(),
+                }),
+                None => tracing::warn!("Skipping fallback model '{}': no registered provider", model),
+            }
+        }
+        for (name, model) in &self.fallback_providers {
+            let Some(provider) = self.registry.get(name) else {
+                tracing::warn!("Skipping fallback provider '{}': not registered", name);
+                continue;
+            };
+            let model = model.clone().unwrap_or_else(|| provider.default_model().to_string());
+            if attempts.iter().any(|a| a.provider.name() == provider.name() && a.model == model) {
+                continue;
+            }
+            attempts.push(Attempt { provider, model });
+        }
+        attempts
+    }
+
+    /// Spawn the streaming task for a request, wiring up cancellation
+    fn spawn_stream(&mut self, primary: Arc<dyn Provider>, request: LLMRequest) {
+        let attempts = self.build_attempts(primary, &request.model);
+        let event_tx = self.event_tx.clone();
+        let (cancel_tx, cancel_rx) = mpsc::channel::<()>(1);
+        self.cancel_tx = Some(cancel_tx);
+
+        tokio::spawn(stream_with_fallback(
+            attempts,
+            request,
+            event_tx,
+            cancel_rx,
+            RetryPolicy { max_retries: self.max_retries, base_delay: self.retry_base_delay },
+            self.rate_limits.clone(),
+            self.debug_capture.clone(),
+        ));
+    }
+
+    /// Keep the last request/response for `last_exchange`; disabling drops it
+    pub fn set_debug_capture(&mut self, enabled: bool) {
+        match (enabled, self.debug_capture.is_some()) {
+            (true, false) => self.debug_capture = Some(DebugCapture::new()),
+            (false, true) => self.debug_capture = None,
+            _ => {}
+        }
+    }
+
+    /// Rate-limit budgets of the current provider as of its last response
+    pub fn rate_limit(&self) -> Option<RateLimitState> {
+        self.rate_limits.get(&self.current_provider)
+    }
+
+    /// Last captured exchange with API keys redacted (None unless capture is on)
+    pub fn last_exchange(&self) -> Option<LlmExchange> {
+        let exchange = self.debug_capture.as_ref()?.last()?;
+        let secrets: Vec<&str> = self.api_keys.values().map(String::as_str).collect();
+        Some(exchange.redacted(&secrets))
+    }
+
     pub fn conversation(&self) -> &[Message] {
         &self.conversation
     }
@@ -451,6 +953,11 @@ impl LLMManager {
         self.conversation.push(Message::user(text));
     }
 
+    /// Append a complete message, keeping every content block (images included)
+    pub fn add_message(&mut self, message: Message) {
+        self.conversation.push(message);
+    }
+
     pub fn add_assistant_message(&mut self, text: String) {
         self.conversation.push(Message::assistant(text));
     }
@@ -480,8 +987,15 @@ impl LLMManager {
         });
     }
     
-    /// Continue the conversation after a tool result (re-send to get LLM response)
-    pub fn continue_after_tool(&mut self, system_prompt: Option<String>, tools: Vec<ToolDefinition>, max_tokens: Option<u32>) {
+    /// Continue the conversation after a tool result (re-send to get LLM response).
+    /// `prompt_cache` marks the system prompt and tools cacheable (llm.prompt_cache).
+    pub fn continue_after_tool(
+        &mut self,
+        system_prompt: Option<String>,
+        tools: Vec<ToolDefinition>,
+        max_tokens: Option<u32>,
+        prompt_cache: bool,
+    ) {
         tracing::debug!("continue_after_tool called with {} messages in conversation, max_tokens={:?}", self.conversation.len(), max_tokens);
 
         let provider = match self.registry.get(&self.current_provider) {
@@ -492,6 +1006,7 @@ impl LLMManager {
                 let _ = self.event_tx.send(LLMEvent::Error(LLMError::ProviderError {
                     status: 0,
                     message: error_msg,
+                    retry_after_secs: None,
                 }));
                 return;
             }
@@ -504,53 +1019,13 @@ impl LLMManager {
             tools,
             stream: true,
             max_tokens,
+            temperature: self.temperature,
+            thinking: self.thinking.clone(),
+            prompt_cache,
             ..Default::default()
         };
 
-        let event_tx = self.event_tx.clone();
-        let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
-        self.cancel_tx = Some(cancel_tx);
-
-        tokio::spawn(async move {
-            match provider.stream(request).await {
-                Ok(mut stream) => {
-                    let mut tool_assembler = ToolAssembler::new();
-                    loop {
-                        tokio::select! {
-                            chunk = stream.next() => {
-                                match chunk {
-                                    Some(Ok(c)) => {
-                                        // Check for completed tool uses
-                                        if let Some(tool_use) = tool_assembler.process_chunk(&c) {
-                                            let _ = event_tx.send(LLMEvent::ToolUseDetected(tool_use));
-                                        }
-                                        // Always forward the raw chunk for UI
-                                        if event_tx.send(LLMEvent::Chunk(c)).is_err() {
-                                            break;
-                                        }
-                                    }
-                                    Some(Err(e)) => {
-                                        let _ = event_tx.send(LLMEvent::Error(e));
-                                        break;
-                                    }
-                                    None => {
-                                        let _ = event_tx.send(LLMEvent::Complete);
-                                        break;
-                                    }
-                                }
-                            }
-                            _ = cancel_rx.recv() => {
-                                let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    let _ = event_tx.send(LLMEvent::Error(e));
-                }
-            }
-        });
+        self.spawn_stream(provider, request);
     }
 
     /// Get the max output tokens for the current model from provider info
@@ -584,55 +1059,13 @@ impl LLMManager {
                 let _ = self.event_tx.send(LLMEvent::Error(LLMError::ProviderError {
                     status: 0,
                     message: error_msg,
+                    retry_after_secs: None,
                 }));
                 return;
             }
         };
 
-        let event_tx = self.event_tx.clone();
-        let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
-        self.cancel_tx = Some(cancel_tx);
-
-        tokio::spawn(async move {
-            match provider.stream(request).await {
-                Ok(mut stream) => {
-                    let mut tool_assembler = ToolAssembler::new();
-                    loop {
-                        tokio::select! {
-                            chunk = stream.next() => {
-                                match chunk {
-                                    Some(Ok(c)) => {
-                                        // Check for completed tool uses
-                                        if let Some(tool_use) = tool_assembler.process_chunk(&c) {
-                                            let _ = event_tx.send(LLMEvent::ToolUseDetected(tool_use));
-                                        }
-                                        // Always forward the raw chunk for UI
-                                        if event_tx.send(LLMEvent::Chunk(c)).is_err() {
-                                            break;
-                                        }
-                                    }
-                                    Some(Err(e)) => {
-                                        let _ = event_tx.send(LLMEvent::Error(e));
-                                        break;
-                                    }
-                                    None => {
-                                        let _ = event_tx.send(LLMEvent::Complete);
-                                        break;
-                                    }
-                                }
-                            }
-                            _ = cancel_rx.recv() => {
-                                let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    let _ = event_tx.send(LLMEvent::Error(e));
-                }
-            }
-        });
+        self.spawn_stream(provider, request);
     }
 
     pub fn send_message(&mut self, user_message: String, system_prompt: Option<String>, tools: Vec<ToolDefinition>) {
@@ -656,6 +1089,7 @@ impl LLMManager {
                 let _ = self.event_tx.send(LLMEvent::Error(LLMError::ProviderError {
                     status: 0,
                     message: error_msg,
+                    retry_after_secs: None,
                 }));
                 return;
             }
@@ -673,6 +1107,8 @@ impl LLMManager {
             tools,
             stream: true,
             max_tokens,
+            temperature: self.temperature,
+            thinking: self.thinking.clone(),
             ..Default::default()
         };
         tracing::info!(
@@ -684,59 +1120,8 @@ impl LLMManager {
             request.max_tokens
         );
 
-        let event_tx = self.event_tx.clone();
-        let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
-        self.cancel_tx = Some(cancel_tx);
-
         tracing::info!("Spawning async LLM request task");
-        tokio::spawn(async move {
-            tracing::debug!("Async task started, calling provider.stream()");
-            match provider.stream(request).await {
-                Ok(mut stream) => {
-                    tracing::debug!("Stream created successfully");
-                    let mut tool_assembler = ToolAssembler::new();
-                    loop {
-                        tokio::select! {
-                            chunk = stream.next() => {
-                                match chunk {
-                                    Some(Ok(c)) => {
-                                        tracing::trace!("Got stream chunk: {:?}", c);
-                                        // Check for completed tool uses
-                                        if let Some(tool_use) = tool_assembler.process_chunk(&c) {
-                                            let _ = event_tx.send(LLMEvent::ToolUseDetected(tool_use));
-                                        }
-                                        // Always forward the raw chunk for UI
-                                        if event_tx.send(LLMEvent::Chunk(c)).is_err() {
-                                            tracing::warn!("Event channel closed");
-                                            break;
-                                        }
-                                    }
-                                    Some(Err(e)) => {
-                                        tracing::error!("Stream error: {}", e);
-                                        let _ = event_tx.send(LLMEvent::Error(e));
-                                        break;
-                                    }
-                                    None => {
-                                        tracing::info!("Stream complete");
-                                        let _ = event_tx.send(LLMEvent::Complete);
-                                        break;
-                                    }
-                                }
-                            }
-                            _ = cancel_rx.recv() => {
-                                tracing::info!("Stream cancelled");
-                                let _ = event_tx.send(LLMEvent::Error(LLMError::StreamInterrupted));
-                                break;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to create stream: {}", e);
-                    let _ = event_tx.send(LLMEvent::Error(e));
-                }
-            }
-        });
+        self.spawn_stream(provider, request);
         tracing::debug!("send_message completed, task spawned");
     }
 }
@@ -748,40 +1133,453 @@ impl Default for LLMManager {
 }
 
 /// Test an API key for a specific provider (TS-007)
-/// Creates a temporary provider instance and validates the key
 /// Returns Ok(()) if valid, Err with LLMError if invalid
-pub async fn test_api_key(provider_name: &str, api_key: &str) -> Result<(), LLMError> {
-    use super::provider::Provider;
-    
-    match provider_name.to_lowercase().as_str() {
-        "anthropic" => {
-            let provider = AnthropicProvider::new(api_key);
-            provider.test_key().await
-        }
-        "openai" => {
-            let provider = OpenAIProvider::new(api_key);
-            provider.test_key().await
-        }
-        "gemini" | "google" => {
-            let provider = GeminiProvider::new(api_key);
-            provider.test_key().await
-        }
-        "grok" | "xai" => {
-            let provider = GrokProvider::new(api_key);
-            provider.test_key().await
-        }
-        "groq" => {
-            let provider = GroqProvider::new(api_key);
-            provider.test_key().await
-        }
-        "ollama" => {
-            // Ollama doesn't use API keys - just check if server is reachable
-            let provider = OllamaProvider::new(None);
-            provider.test_key().await
-        }
-        _ => Err(LLMError::ProviderError {
-            status: 0,
-            message: format!("Unknown provider: {}", provider_name),
-        }),
+pub async fn test_api_key(provider_name: &str, api_key: &str, config: &LLMConfig) -> Result<(), LLMError> {
+    key_test_provider(provider_name, api_key, config)?.test_key().await
+}
+
+/// Build the provider the way a configured `LLMManager` does (base URL,
+/// CA bundle, extra headers, Azure `api_version`/`auth`, Ollama URL) so the
+/// test reaches the same endpoint as real requests
+fn key_test_provider(provider_name: &str, api_key: &str, config: &LLMConfig) -> Result<Arc<dyn Provider>, LLMError> {
+    let config_error = |message: String| LLMError::ProviderError { status: 0, message, retry_after_secs: None };
+    let mut manager = LLMManager::new();
+    manager.set_base_urls(config);
+    manager.set_http_settings(config).map_err(config_error)?;
+
+    let name = match provider_name.to_lowercase().as_str() {
+        "google" => "gemini".to_string(),
+        "xai" => "grok".to_string(),
+        other => other.to_string(),
+    };
+    if name == "ollama" {
+        // Ollama doesn't use API keys - just check if server is reachable
+        manager.register_ollama(config.providers.get("ollama").and_then(|p| p.base_url.clone()));
+    } else {
+        manager.register_key(&KeyId::from_provider_str(&name), api_key);
+    }
+    manager
+        .registry
+        .get(&name)
+        .ok_or_else(|| config_error(format!("Unknown provider: {}", provider_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use crate::llm::provider::{Capability, ModelInfo, StreamBox};
+    use crate::llm::types::{LLMResponse, StopReason, Usage};
+
+    /// Provider whose `failing_model` always returns an overloaded (529) error
+    struct MockProvider {
+        name: &'static str,
+        models: Vec<ModelInfo>,
+        failing_model: Option<&'static str>,
+        error: LLMError,
+        calls: AtomicUsize,
+    }
+
+    impl MockProvider {
+        fn new(name: &'static str, models: &[&str], failing_model: Option<&'static str>) -> Self {
+            Self {
+                name,
+                models: models.iter().map(|m| ModelInfo::new(*m, *m)).collect(),
+                failing_model,
+                error: LLMError::ProviderError {
+                    status: 529,
+                    message: "overloaded".to_string(),
+                    retry_after_secs: None,
+                },
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn failing_with(mut self, error: LLMError) -> Self {
+            self.error = error;
+            self
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn models(&self) -> &[ModelInfo] {
+            &self.models
+        }
+
+        fn default_model(&self) -> &str {
+            &self.models[0].id
+        }
+
+        fn supports(&self, _capability: Capability) -> bool {
+            true
+        }
+
+        async fn complete(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if Some(request.model.as_str()) == self.failing_model {
+                return Err(self.error.clone());
+            }
+            Ok(LLMResponse {
+                id: "mock".to_string(),
+                content: vec![ContentBlock::Text(format!("hello from {}", request.model))],
+                model: request.model,
+                stop_reason: StopReason::EndTurn,
+                usage: Usage::default(),
+            })
+        }
+
+        async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if Some(request.model.as_str()) == self.failing_model {
+                return Err(self.error.clone());
+            }
+            let chunks = vec![Ok(StreamChunk::Delta(StreamDelta::Text(format!(
+                "hello from {}",
+                request.model
+            ))))];
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+
+        async fn test_key(&self) -> Result<(), LLMError> {
+            Ok(())
+        }
+    }
+
+    async fn collect_events(rx: &mut mpsc::UnboundedReceiver<LLMEvent>) -> Vec<LLMEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            let done = matches!(event, LLMEvent::Complete | LLMEvent::Error(_));
+            events.push(event);
+            if done {
+                break;
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_base_url_override_rebuilds_registered_provider() {
+        let mut manager = LLMManager::new();
+        let mut config = LLMConfig::default();
+        config.set_base_url_for_provider("groq", Some("https://proxy.internal/groq/v1/".to_string()));
+        config.set_base_url_for_provider("openai", Some("not a url".to_string()));
+        manager.set_base_urls(&config);
+
+        assert_eq!(manager.base_url("groq"), Some("https://proxy.internal/groq/v1"));
+        assert_eq!(manager.base_url("openai"), None);
+
+        manager.register_groq("gsk_test");
+        manager.set_base_url("groq", None);
+        assert_eq!(manager.base_url("groq"), None);
+        assert!(manager.registry.get("groq").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_key_test_uses_configured_endpoint() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 8192];
+            let n = socket.read(&mut request).await.unwrap();
+            let body = r#"{"error":{"message":"bad key","type":"invalid_request_error"}}"#;
+            let response = format!("HTTP/1.1 401 Unauthorized\r\ncontent-length: {}\r\n\r\n{}", body.len(), body);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+
+        let mut config = LLMConfig::default();
+        config.set_base_url_for_provider("groq", Some(format!("http://127.0.0.1:{}/proxy/v1", port)));
+        config.extra_headers.insert("X-Team".to_string(), "platform".to_string());
+        let result = test_api_key("groq", "gsk_test", &config).await;
+        assert!(result.is_err());
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("post /proxy/v1/chat/completions "));
+        assert!(request.contains("x-team: platform"));
+    }
+
+    #[tokio::test]
+    async fn test_debug_capture_records_last_exchange() {
+        use crate::llm::mock::{MockScript, MockStep, MOCK_PROVIDER};
+
+        let mut manager = LLMManager::new();
+        manager.register_mock(
+            MockScript::new(1)
+                .turn(vec![MockStep::text("first")])
+                .turn(vec![MockStep::text("second")]),
+        );
+        manager.set_provider(MOCK_PROVIDER);
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        collect_events(&mut rx).await;
+        assert!(manager.last_exchange().is_none(), "capture is off by default");
+
+        manager.set_debug_capture(true);
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        collect_events(&mut rx).await;
+        let exchange = manager.last_exchange().unwrap();
+        assert_eq!(exchange.provider, MOCK_PROVIDER);
+        assert!(exchange.response.last().is_some_and(|line| line.contains("EndTurn")));
+    }
+
+    #[tokio::test]
+    async fn test_mock_script_drives_tool_round_trip() {
+        use crate::llm::mock::{MockScript, MockStep, MOCK_MODEL, MOCK_PROVIDER};
+
+        let mut manager = LLMManager::new();
+        manager.register_mock(
+            MockScript::new(1)
+                .turn(vec![MockStep::tool_use("file_read", serde_json::json!({"path": "a.txt"}))])
+                .turn(vec![MockStep::text("done")]),
+        );
+        manager.set_provider(MOCK_PROVIDER);
+        assert_eq!(manager.current_model(), MOCK_MODEL);
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+        assert!(events.iter().any(|e| matches!(
+            e,
+            LLMEvent::ToolUseDetected(tool) if tool.name == "file_read" && tool.input["path"] == "a.txt"
+        )));
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+        assert!(events.iter().any(|e| matches!(
+            e,
+            LLMEvent::Chunk(StreamChunk::Delta(StreamDelta::Text(t))) if t.starts_with('d')
+        )));
+        assert!(matches!(events.last(), Some(LLMEvent::Complete)));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(LLMError::ProviderError { status: 529, message: String::new(), retry_after_secs: None }.is_retryable());
+        assert!(LLMError::RateLimit { retry_after_secs: Some(1) }.is_retryable());
+        assert!(!LLMError::ProviderError { status: 0, message: String::new(), retry_after_secs: None }.is_retryable());
+        assert!(!LLMError::AuthError { message: String::new() }.is_retryable());
+
+        // Retry-After is kept on every retryable status, and the message with it
+        let limited = LLMError::ProviderError { status: 429, message: "slow down".to_string(), retry_after_secs: None }
+            .with_retry_after(Some(3));
+        assert_eq!(limited.retry_after(), Some(Duration::from_secs(3)));
+        assert_eq!(limited.to_string(), "Provider error: 429 - slow down");
+        for status in [503, 529] {
+            let overloaded = LLMError::ProviderError { status, message: String::new(), retry_after_secs: None }
+                .with_retry_after(Some(3));
+            assert_eq!(overloaded.retry_after(), Some(Duration::from_secs(3)));
+        }
+        let rejected = LLMError::ProviderError { status: 400, message: String::new(), retry_after_secs: None }
+            .with_retry_after(Some(3));
+        assert!(rejected.retry_after().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_produces_response() {
+        let primary = Arc::new(MockProvider::new("anthropic", &["primary-model"], Some("primary-model")));
+        let backup = Arc::new(MockProvider::new("openai", &["backup-model"], None));
+
+        let mut manager = LLMManager::new();
+        manager.registry.register(primary.clone());
+        manager.registry.register(backup.clone());
+        manager.set_provider("anthropic");
+        manager.set_model("primary-model");
+        manager.set_fallback_models(vec!["backup-model".to_string()]);
+        manager.retry_base_delay = Duration::ZERO;
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+
+        // Primary tried once plus MAX_RETRIES retries, then the backup once
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1 + MAX_RETRIES as usize);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 1);
+
+        // Each retry is announced before the switch to the backup
+        for (i, event) in events[..MAX_RETRIES as usize].iter().enumerate() {
+            assert!(matches!(
+                event,
+                LLMEvent::Retrying { retry, max_retries: MAX_RETRIES, .. } if *retry == i as u32 + 1
+            ));
+        }
+        assert!(matches!(
+            &events[MAX_RETRIES as usize],
+            LLMEvent::Fallback { provider, model, after }
+                if provider == "openai" && model == "backup-model" && after == "anthropic"
+        ));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            LLMEvent::Chunk(StreamChunk::Delta(StreamDelta::Text(t))) if t == "hello from backup-model"
+        )));
+        assert!(matches!(events.last(), Some(LLMEvent::Complete)));
+        // The configured model is unchanged; the fallback only applies to this turn
+        assert_eq!(manager.current_model(), "primary-model");
+    }
+
+    #[tokio::test]
+    async fn test_auth_error_switches_to_fallback_provider() {
+        let primary = Arc::new(
+            MockProvider::new("anthropic", &["primary-model", "haiku"], Some("primary-model"))
+                .failing_with(LLMError::AuthError { message: "invalid x-api-key".to_string() }),
+        );
+        let backup = Arc::new(MockProvider::new("openai", &["gpt-4o"], None));
+
+        let mut manager = LLMManager::new();
+        manager.registry.register(primary.clone());
+        manager.registry.register(backup.clone());
+        manager.set_provider("anthropic");
+        manager.set_model("primary-model");
+        // Same provider as the rejected key, so it's skipped
+        manager.set_fallback_models(vec!["haiku".to_string()]);
+        manager.set_fallback_providers(vec![("openai".to_string(), None)]);
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+
+        // Auth errors aren't retried
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(backup.calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            &events[0],
+            LLMEvent::Fallback { provider, model, after }
+                if provider == "openai" && model == "gpt-4o" && after == "anthropic"
+        ));
+        assert!(matches!(events.last(), Some(LLMEvent::Complete)));
+    }
+
+    #[tokio::test]
+    async fn test_images_rejected_without_vision() {
+        use super::super::types::{ImageContent, ImageSource, Role};
+
+        let provider: Arc<dyn Provider> = Arc::new(OllamaProvider::new(None));
+        let request = LLMRequest {
+            model: "qwen3:8b".to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: vec![ContentBlock::Image(ImageContent {
+                    source: ImageSource::Base64(String::new()),
+                    media_type: "image/png".to_string(),
+                })],
+            }],
+            ..Default::default()
+        };
+        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        match run_stream(&provider, request, &event_tx, &mut cancel_rx, None).await {
+            StreamOutcome::Failed(LLMError::InvalidRequest { message }) => {
+                assert!(message.contains("ollama doesn't accept images"))
+            }
+            _ => panic!("expected the request to be rejected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_surfaces_when_chain_exhausted() {
+        let primary = Arc::new(MockProvider::new("anthropic", &["primary-model"], Some("primary-model")));
+
+        let mut manager = LLMManager::new();
+        manager.registry.register(primary);
+        manager.set_provider("anthropic");
+        manager.set_model("primary-model");
+        manager.set_fallback_models(vec!["unknown-model".to_string()]);
+        manager.retry_base_delay = Duration::ZERO;
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+
+        // Unresolvable fallback is skipped, so the primary error is reported
+        assert_eq!(events.len(), 1 + MAX_RETRIES as usize);
+        assert!(matches!(events.last(), Some(LLMEvent::Error(LLMError::ProviderError { status: 529, .. }))));
+    }
+
+    #[tokio::test]
+    async fn test_used_up_rate_limit_holds_request() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let provider = Arc::new(MockProvider::new("anthropic", &["model"], None));
+        let mut manager = LLMManager::new();
+        manager.registry.register(provider.clone());
+        manager.set_provider("anthropic");
+        manager.set_model("model");
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("50ms"));
+        manager.rate_limits.record("anthropic", &headers);
+        assert_eq!(manager.rate_limit().unwrap().requests.unwrap().remaining, 0);
+        let mut rx = manager.take_event_rx().unwrap();
+
+        let started = Instant::now();
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+        assert!(matches!(&events[0], LLMEvent::Throttled { provider, .. } if provider == "anthropic"));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(events.last(), Some(LLMEvent::Complete)));
+    }
+
+    #[tokio::test]
+    async fn test_max_retries_is_configurable() {
+        let primary = Arc::new(MockProvider::new("anthropic", &["primary-model"], Some("primary-model")));
+
+        let mut manager = LLMManager::new();
+        manager.registry.register(primary.clone());
+        manager.set_provider("anthropic");
+        manager.set_model("primary-model");
+        manager.set_max_retries(0);
+        let mut rx = manager.take_event_rx().unwrap();
+
+        manager.continue_after_tool(None, Vec::new(), None, false);
+        let events = collect_events(&mut rx).await;
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(events.as_slice(), [LLMEvent::Error(_)]));
+    }
+
+    #[test]
+    fn test_retry_delay_backoff_and_retry_after() {
+        let overloaded = LLMError::ProviderError { status: 529, message: String::new(), retry_after_secs: None };
+        let base = Duration::from_millis(500);
+        for _ in 0..50 {
+            let delay = retry_delay(base, 3, &overloaded);
+            assert!((Duration::from_millis(1600)..=Duration::from_millis(2400)).contains(&delay), "{:?}", delay);
+        }
+        assert_eq!(retry_delay(Duration::ZERO, 1, &overloaded), Duration::ZERO);
+
+        // Retry-After wins when the server asks for longer than the backoff
+        let limited = LLMError::RateLimit { retry_after_secs: Some(7) };
+        assert_eq!(retry_delay(base, 1, &limited), Duration::from_secs(7));
+        let limited = LLMError::RateLimit { retry_after_secs: Some(0) };
+        assert!(retry_delay(base, 1, &limited) >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_unlock_keeps_command_line_keys() {
+        use crate::config::{KeyStoreBackend, SecretString};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut keystore = KeyStore::open_in(KeyStoreBackend::Plaintext, dir.path()).unwrap();
+        keystore.store(&KeyId::OpenAI, &SecretString::new("sk-stored")).unwrap();
+        keystore.store(&KeyId::Groq, &SecretString::new("gsk-stored")).unwrap();
+
+        let mut manager = LLMManager::new();
+        manager.register_key(&KeyId::OpenAI, "sk-cli");
+
+        assert_eq!(manager.register_unlocked_keys(&keystore), vec!["groq".to_string()]);
+        assert_eq!(manager.api_keys["openai"], "sk-cli");
+        assert_eq!(manager.api_keys["groq"], "gsk-stored");
     }
 }
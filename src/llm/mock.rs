@@ -0,0 +1,475 @@
+// Mock provider - replays a scripted conversation without network or keys
+//
+// Each `stream`/`complete` call consumes the next turn of a `MockScript` and
+// emits the same chunk sequence a real provider would (Start, BlockStart,
+// deltas, BlockStop, Stop). Text is split into deltas and ids are generated
+// from the script's seed, so a given script and seed always produce the same
+// stream. Used for demos (`--mock-script`) and agent-loop tests.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::provider::{Capability, ModelInfo, Provider, StreamBox};
+use super::types::{
+    BlockType, ContentBlock, LLMError, LLMRequest, LLMResponse, StopReason, StreamChunk, StreamDelta, ToolUse,
+    Usage,
+};
+
+pub const MOCK_PROVIDER: &str = "mock";
+pub const MOCK_MODEL: &str = "mock-1";
+
+/// One step of a scripted response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MockStep {
+    Text { text: String },
+    Thinking { text: String },
+    ToolUse {
+        name: String,
+        #[serde(default)]
+        input: serde_json::Value,
+        /// Generated from the seed when omitted
+        #[serde(default)]
+        id: Option<String>,
+    },
+    /// Fail the stream here. Before any other step this counts as a failed
+    /// request, which the manager retries with the next turn.
+    Error {
+        message: String,
+        #[serde(default = "default_error_status")]
+        status: u16,
+    },
+}
+
+fn default_error_status() -> u16 {
+    500
+}
+
+/// Inline script builders for tests
+#[cfg(test)]
+impl MockStep {
+    pub fn text(text: impl Into<String>) -> Self {
+        MockStep::Text { text: text.into() }
+    }
+
+    pub fn thinking(text: impl Into<String>) -> Self {
+        MockStep::Thinking { text: text.into() }
+    }
+
+    pub fn tool_use(name: impl Into<String>, input: serde_json::Value) -> Self {
+        MockStep::ToolUse { name: name.into(), input, id: None }
+    }
+}
+
+/// One scripted LLM response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockTurn {
+    pub steps: Vec<MockStep>,
+}
+
+/// Scripted responses, replayed in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockScript {
+    /// Seeds generated ids and delta boundaries
+    pub seed: u64,
+    /// Start over after the last turn instead of failing
+    pub repeat: bool,
+    pub turns: Vec<MockTurn>,
+}
+
+#[cfg(test)]
+impl MockScript {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, ..Default::default() }
+    }
+
+    /// Append a turn (inline script builder)
+    pub fn turn(mut self, steps: Vec<MockStep>) -> Self {
+        self.turns.push(MockTurn { steps });
+        self
+    }
+}
+
+impl MockScript {
+    /// Load a script from a .toml or .json file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read mock script {}: {}", path.display(), e))?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content).map_err(|e| format!("Invalid mock script {}: {}", path.display(), e))
+        } else {
+            toml::from_str(&content).map_err(|e| format!("Invalid mock script {}: {}", path.display(), e))
+        }
+    }
+}
+
+/// splitmix64: small, dependency-free and stable across platforms
+struct SeededRng(u64);
+
+impl SeededRng {
+    fn new(seed: u64, turn: usize) -> Self {
+        Self(seed ^ (turn as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 1..=max
+    fn range(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize + 1
+    }
+}
+
+/// Split text into deltas of 1-8 characters
+fn split_deltas(text: &str, rng: &mut SeededRng) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut deltas = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let end = (start + rng.range(8)).min(chars.len());
+        deltas.push(chars[start..end].iter().collect());
+        start = end;
+    }
+    deltas
+}
+
+fn estimate_tokens(chars: usize) -> u32 {
+    (chars / 4).max(1) as u32
+}
+
+fn request_tokens(request: &LLMRequest) -> u32 {
+    let chars: usize = request
+        .messages
+        .iter()
+        .flat_map(|m| m.content.iter())
+        .map(|c| match c {
+            ContentBlock::Text(t) | ContentBlock::Thinking(t) => t.len(),
+            _ => 100,
+        })
+        .sum();
+    estimate_tokens(chars + request.system.as_ref().map_or(0, |s| s.len()))
+}
+
+/// Provider "mock" that replays a `MockScript`
+pub struct MockProvider {
+    script: MockScript,
+    models: Vec<ModelInfo>,
+    next_turn: AtomicUsize,
+    /// Every request received, when recording (tests)
+    recorded: Option<Arc<Mutex<Vec<LLMRequest>>>>,
+    vision: bool,
+}
+
+impl MockProvider {
+    pub fn new(script: MockScript) -> Self {
+        Self {
+            script,
+            models: vec![ModelInfo::new(MOCK_MODEL, "Mock (scripted)").with_thinking()],
+            next_turn: AtomicUsize::new(0),
+            recorded: None,
+            vision: true,
+        }
+    }
+
+    /// Stand in for a provider that rejects images
+    #[cfg(test)]
+    pub fn without_vision(mut self) -> Self {
+        self.vision = false;
+        self
+    }
+
+    /// Keep every request this provider receives in the returned log
+    #[cfg(test)]
+    pub fn recording(mut self) -> (Self, Arc<Mutex<Vec<LLMRequest>>>) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        self.recorded = Some(log.clone());
+        (self, log)
+    }
+
+    fn record(&self, request: &LLMRequest) {
+        if let Some(log) = &self.recorded {
+            log.lock().unwrap_or_else(|e| e.into_inner()).push(request.clone());
+        }
+    }
+
+    /// Turns consumed so far
+    #[cfg(test)]
+    pub fn turns_played(&self) -> usize {
+        self.next_turn.load(Ordering::SeqCst)
+    }
+
+    fn take_turn(&self) -> Result<(usize, &MockTurn), LLMError> {
+        let index = self.next_turn.fetch_add(1, Ordering::SeqCst);
+        let count = self.script.turns.len();
+        let turn = if self.script.repeat && count > 0 {
+            self.script.turns.get(index % count)
+        } else {
+            self.script.turns.get(index)
+        };
+        turn.map(|t| (index, t)).ok_or_else(|| LLMError::InvalidRequest {
+            message: format!("Mock script exhausted after {} turn(s)", count),
+        })
+    }
+
+    /// The chunk sequence for one turn
+    fn chunks(&self, index: usize, turn: &MockTurn, request: &LLMRequest) -> Vec<Result<StreamChunk, LLMError>> {
+        let mut rng = SeededRng::new(self.script.seed, index);
+        let mut chunks = vec![Ok(StreamChunk::Start { message_id: format!("msg_mock_{:016x}", rng.next_u64()) })];
+        let mut output_chars = 0;
+        let mut thinking_chars = 0;
+        let mut has_tool_use = false;
+
+        for (block, step) in turn.steps.iter().enumerate() {
+            match step {
+                MockStep::Text { text } | MockStep::Thinking { text } => {
+                    let thinking = matches!(step, MockStep::Thinking { .. });
+                    chunks.push(Ok(StreamChunk::BlockStart {
+                        index: block,
+                        block_type: if thinking { BlockType::Thinking } else { BlockType::Text },
+                        tool_id: None,
+                        tool_name: None,
+                    }));
+                    for delta in split_deltas(text, &mut rng) {
+                        chunks.push(Ok(StreamChunk::Delta(if thinking {
+                            StreamDelta::Thinking(delta)
+                        } else {
+                            StreamDelta::Text(delta)
+                        })));
+                    }
+                    chunks.push(Ok(StreamChunk::BlockStop { index: block }));
+                    output_chars += text.len();
+                    if thinking {
+                        thinking_chars += text.len();
+                    }
+                }
+                MockStep::ToolUse { name, input, id } => {
+                    has_tool_use = true;
+                    let tool_id = id.clone().unwrap_or_else(|| format!("toolu_mock_{:016x}", rng.next_u64()));
+                    let input_json = input_or_empty(input).to_string();
+                    output_chars += input_json.len();
+                    chunks.push(Ok(StreamChunk::BlockStart {
+                        index: block,
+                        block_type: BlockType::ToolUse,
+                        tool_id: Some(tool_id),
+                        tool_name: Some(name.clone()),
+                    }));
+                    for delta in split_deltas(&input_json, &mut rng) {
+                        chunks.push(Ok(StreamChunk::Delta(StreamDelta::ToolInput {
+                            block_index: block,
+                            input_json: delta,
+                        })));
+                    }
+                    chunks.push(Ok(StreamChunk::BlockStop { index: block }));
+                }
+                MockStep::Error { message, status } => {
+                    chunks.push(Err(LLMError::ProviderError { status: *status, message: message.clone(), retry_after_secs: None }));
+                    return chunks;
+                }
+            }
+        }
+
+        chunks.push(Ok(StreamChunk::Stop {
+            reason: if has_tool_use { StopReason::ToolUse } else { StopReason::EndTurn },
+            usage: Some(Usage {
+                input_tokens: request_tokens(request),
+                output_tokens: estimate_tokens(output_chars),
+                thinking_tokens: (thinking_chars > 0).then(|| estimate_tokens(thinking_chars)),
+                ..Default::default()
+            }),
+        }));
+        chunks
+    }
+}
+
+fn input_or_empty(input: &serde_json::Value) -> serde_json::Value {
+    if input.is_null() {
+        serde_json::json!({})
+    } else {
+        input.clone()
+    }
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    fn name(&self) -> &str {
+        MOCK_PROVIDER
+    }
+
+    fn models(&self) -> &[ModelInfo] {
+        &self.models
+    }
+
+    fn default_model(&self) -> &str {
+        MOCK_MODEL
+    }
+
+    fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Vision => self.vision,
+            _ => matches!(capability, Capability::Streaming | Capability::ToolUse | Capability::Thinking),
+        }
+    }
+
+    async fn complete(&self, request: LLMRequest) -> Result<LLMResponse, LLMError> {
+        self.record(&request);
+        let (index, turn) = self.take_turn()?;
+        let mut response = LLMResponse {
+            id: String::new(),
+            model: request.model.clone(),
+            content: Vec::new(),
+            stop_reason: StopReason::EndTurn,
+            usage: Usage::default(),
+        };
+        let mut pending_tool: Option<(String, String, String)> = None;
+
+        for chunk in self.chunks(index, turn, &request) {
+            match chunk? {
+                StreamChunk::Start { message_id } => response.id = message_id,
+                StreamChunk::BlockStart { block_type, tool_id, tool_name, .. } => match block_type {
+                    BlockType::Text => response.content.push(ContentBlock::Text(String::new())),
+                    BlockType::Thinking => response.content.push(ContentBlock::Thinking(String::new())),
+                    BlockType::ToolUse => {
+                        pending_tool = Some((tool_id.unwrap_or_default(), tool_name.unwrap_or_default(), String::new()))
+                    }
+                },
+                StreamChunk::Delta(StreamDelta::Text(t)) | StreamChunk::Delta(StreamDelta::Thinking(t)) => {
+                    if let Some(ContentBlock::Text(s) | ContentBlock::Thinking(s)) = response.content.last_mut() {
+                        s.push_str(&t);
+                    }
+                }
+                StreamChunk::Delta(StreamDelta::ToolInput { input_json, .. }) => {
+                    if let Some((_, _, buf)) = pending_tool.as_mut() {
+                        buf.push_str(&input_json);
+                    }
+                }
+                StreamChunk::BlockStop { .. } => {
+                    if let Some((id, name, input)) = pending_tool.take() {
+                        let input = serde_json::from_str(&input).unwrap_or_else(|_| serde_json::json!({}));
+                        response.content.push(ContentBlock::ToolUse(ToolUse { id, name, input }));
+                    }
+                }
+                StreamChunk::Stop { reason, usage } => {
+                    response.stop_reason = reason;
+                    response.usage = usage.unwrap_or_default();
+                }
+                StreamChunk::Error(e) => return Err(e),
+            }
+        }
+        Ok(response)
+    }
+
+    async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
+        self.record(&request);
+        let (index, turn) = self.take_turn()?;
+        Ok(Box::pin(futures::stream::iter(self.chunks(index, turn, &request))))
+    }
+
+    async fn test_key(&self) -> Result<(), LLMError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn script() -> MockScript {
+        MockScript::new(42)
+            .turn(vec![
+                MockStep::thinking("Need the README first."),
+                MockStep::tool_use("file_read", serde_json::json!({"path": "README.md"})),
+            ])
+            .turn(vec![MockStep::text("The README describes ridge-control.")])
+    }
+
+    fn request() -> LLMRequest {
+        LLMRequest { model: MOCK_MODEL.to_string(), ..Default::default() }
+    }
+
+    async fn collect(provider: &MockProvider) -> Vec<StreamChunk> {
+        let stream = provider.stream(request()).await.unwrap();
+        stream.map(|c| c.unwrap()).collect().await
+    }
+
+    fn describe(chunks: &[StreamChunk]) -> Vec<String> {
+        chunks.iter().map(|c| format!("{:?}", c)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_replays_turns_deterministically() {
+        let first = MockProvider::new(script());
+        let second = MockProvider::new(script());
+
+        let turn_one = collect(&first).await;
+        assert_eq!(describe(&turn_one), describe(&collect(&second).await));
+        assert!(matches!(
+            turn_one.last(),
+            Some(StreamChunk::Stop { reason: StopReason::ToolUse, .. })
+        ));
+
+        let turn_two = collect(&first).await;
+        let text: String = turn_two
+            .iter()
+            .filter_map(|c| match c {
+                StreamChunk::Delta(StreamDelta::Text(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "The README describes ridge-control.");
+
+        // Script exhausted
+        assert!(first.stream(request()).await.is_err());
+        assert_eq!(first.turns_played(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_complete_assembles_tool_use() {
+        let provider = MockProvider::new(script());
+        let response = provider.complete(request()).await.unwrap();
+        assert_eq!(response.stop_reason, StopReason::ToolUse);
+        assert!(matches!(&response.content[0], ContentBlock::Thinking(t) if t == "Need the README first."));
+        match &response.content[1] {
+            ContentBlock::ToolUse(tool) => {
+                assert_eq!(tool.name, "file_read");
+                assert_eq!(tool.input["path"], "README.md");
+                assert!(tool.id.starts_with("toolu_mock_"));
+            }
+            other => panic!("expected tool use, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_script_parses_from_toml() {
+        let script: MockScript = toml::from_str(
+            r#"
+seed = 7
+repeat = true
+
+[[turns]]
+steps = [
+    { type = "text", text = "hi" },
+    { type = "tool_use", name = "bash", input = { command = "ls" } },
+]
+
+[[turns]]
+steps = [{ type = "error", message = "overloaded", status = 529 }]
+"#,
+        )
+        .unwrap();
+        assert_eq!(script.seed, 7);
+        assert!(script.repeat);
+        assert_eq!(script.turns[0].steps[0], MockStep::text("hi"));
+        assert_eq!(script.turns[1].steps[0], MockStep::Error { message: "overloaded".to_string(), status: 529 });
+    }
+}
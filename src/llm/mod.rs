@@ -6,13 +6,19 @@ pub mod gemini;
 pub mod grok;
 pub mod groq;
 pub mod ollama;
+pub mod mock;
 pub mod manager;
+pub mod debug_capture;
+pub mod rate_limit;
+pub mod http;
 pub mod tools;
+pub mod code_search;
+pub mod patch;
 pub mod shell_session;
 
 pub use types::*;
 pub use manager::{LLMManager, LLMEvent};
-pub use tools::{ToolExecutor, ToolExecutionCheck, PendingToolUse, ToolError, ParsedQuestion, ParsedOption};
+pub use tools::{DryRun, ToolExecutor, ToolExecutionCheck, PendingToolUse, ToolError, AnswerValidation, ParsedQuestion, ParsedOption};
 // Re-export shell session types (used by ToolExecutor)
 #[allow(unused_imports)]
 pub use shell_session::{ShellSessionPool, ShellSession, SessionError, ExecResult, BackgroundTaskOutput};
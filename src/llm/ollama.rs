@@ -1,4 +1,5 @@
-// Ollama provider - local LLM via Ollama's OpenAI-compatible API
+// Ollama provider - local LLM via Ollama's native /api/chat (NDJSON streaming)
+// or llama-server's OpenAI-compatible /v1 API
 #![allow(dead_code)]
 
 use async_trait::async_trait;
@@ -104,6 +105,7 @@ impl OllamaProvider {
             return Err(LLMError::ProviderError {
                 status: response.status().as_u16(),
                 message: "Failed to list Ollama models".to_string(),
+                retry_after_secs: None,
             });
         }
 
@@ -152,6 +154,7 @@ impl OllamaProvider {
             return Err(LLMError::ProviderError {
                 status: response.status().as_u16(),
                 message: "Failed to list llama-server models".to_string(),
+                retry_after_secs: None,
             });
         }
 
@@ -240,6 +243,139 @@ impl OllamaProvider {
         format!("{}/v1/chat/completions", self.base_url)
     }
 
+    /// Ollama's native chat endpoint, which streams newline-delimited JSON
+    fn native_chat_url(&self) -> String {
+        format!("{}/api/chat", self.base_url)
+    }
+
+    fn supports_thinking(&self, model: &str) -> bool {
+        self.models.iter().any(|m| m.id == model && m.supports_thinking)
+    }
+
+    /// Request body for Ollama's native /api/chat: plain-string content, base64
+    /// `images`, tool-call arguments as objects and sampling under `options`
+    fn build_native_body(&self, request: &LLMRequest) -> serde_json::Value {
+        let mut messages: Vec<serde_json::Value> = Vec::new();
+
+        if let Some(system) = &request.system {
+            messages.push(json!({
+                "role": "system",
+                "content": system
+            }));
+        }
+
+        // Native tool messages carry the tool's name rather than a call id
+        let tool_names: std::collections::HashMap<&str, &str> = request
+            .messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|c| match c {
+                ContentBlock::ToolUse(tool_use) => Some((tool_use.id.as_str(), tool_use.name.as_str())),
+                _ => None,
+            })
+            .collect();
+
+        for m in &request.messages {
+            let role = match m.role {
+                super::types::Role::User => "user",
+                super::types::Role::Assistant => "assistant",
+            };
+
+            let mut text = String::new();
+            let mut images = Vec::new();
+            let mut tool_calls = Vec::new();
+            for block in &m.content {
+                match block {
+                    ContentBlock::Text(t) => text.push_str(t),
+                    ContentBlock::Image(image) => {
+                        if let super::types::ImageSource::Base64(data) = &image.source {
+                            images.push(json!(data));
+                        }
+                    }
+                    ContentBlock::ToolUse(tool_use) => tool_calls.push(json!({
+                        "function": {
+                            "name": tool_use.name,
+                            "arguments": tool_use.input
+                        }
+                    })),
+                    ContentBlock::ToolResult(result) => {
+                        let mut msg = json!({
+                            "role": "tool",
+                            "content": match &result.content {
+                                super::types::ToolResultContent::Text(t) => t.clone(),
+                                super::types::ToolResultContent::Json(j) => j.to_string(),
+                                super::types::ToolResultContent::Image(_) => "[image]".to_string(),
+                            }
+                        });
+                        if let Some(name) = tool_names.get(result.tool_use_id.as_str()) {
+                            msg["tool_name"] = json!(name);
+                        }
+                        messages.push(msg);
+                    }
+                    ContentBlock::Thinking(_) => {}
+                }
+            }
+
+            if !text.is_empty() || !images.is_empty() || !tool_calls.is_empty() {
+                let mut msg = json!({ "role": role, "content": text });
+                if !images.is_empty() {
+                    msg["images"] = json!(images);
+                }
+                if !tool_calls.is_empty() {
+                    msg["tool_calls"] = json!(tool_calls);
+                }
+                messages.push(msg);
+            }
+        }
+
+        let model = if request.model.is_empty() {
+            &self.default_model
+        } else {
+            &request.model
+        };
+
+        let mut body = json!({
+            "model": model,
+            "messages": messages,
+            "stream": request.stream,
+        });
+
+        let mut options = serde_json::Map::new();
+        if let Some(max_tokens) = request.max_tokens {
+            options.insert("num_predict".to_string(), json!(max_tokens));
+        }
+        if let Some(temp) = request.temperature {
+            options.insert("temperature".to_string(), json!(temp));
+        }
+        if !options.is_empty() {
+            body["options"] = json!(options);
+        }
+
+        if request.thinking.as_ref().is_some_and(|t| t.enabled) && self.supports_thinking(model) {
+            body["think"] = json!(true);
+        }
+
+        if !request.tools.is_empty() {
+            let tools: Vec<serde_json::Value> = request
+                .tools
+                .iter()
+                .map(|t| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": t.name,
+                            "description": t.description,
+                            "parameters": t.input_schema
+                        }
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+
+        body
+    }
+
     fn build_request_body(&self, request: &LLMRequest) -> serde_json::Value {
         let mut messages: Vec<serde_json::Value> = Vec::new();
 
@@ -428,8 +564,9 @@ impl Provider for OllamaProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let resp: OllamaResponse = response.json().await.map_err(|e| LLMError::ParseError {
@@ -439,15 +576,28 @@ impl Provider for OllamaProvider {
         Ok(convert_response(resp))
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        let mut req = request.clone();
+        req.stream = true;
+        Some(match self.server_kind {
+            LocalServerKind::Ollama => self.build_native_body(&req),
+            LocalServerKind::LlamaServer => self.build_request_body(&req),
+        })
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let mut req = request;
         req.stream = true;
 
-        let body = self.build_request_body(&req);
+        // Ollama streams NDJSON from /api/chat; llama-server only speaks the /v1 SSE format
+        let (url, body) = match self.server_kind {
+            LocalServerKind::Ollama => (self.native_chat_url(), self.build_native_body(&req)),
+            LocalServerKind::LlamaServer => (self.api_url(), self.build_request_body(&req)),
+        };
 
         let response = self
             .http_client
-            .post(self.api_url())
+            .post(url)
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
@@ -458,15 +608,20 @@ impl Provider for OllamaProvider {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
         let byte_stream = response.bytes_stream();
 
+        let server_kind = self.server_kind;
         tokio::spawn(async move {
-            parse_sse_stream(byte_stream, tx).await;
+            match server_kind {
+                LocalServerKind::Ollama => parse_ndjson_stream(byte_stream, tx).await,
+                LocalServerKind::LlamaServer => parse_sse_stream(byte_stream, tx).await,
+            }
         });
 
         let stream: StreamBox = Box::pin(ReceiverStream::new(rx));
@@ -495,14 +650,174 @@ impl Provider for OllamaProvider {
             Err(LLMError::ProviderError {
                 status: response.status().as_u16(),
                 message: format!("Local server ({:?}) returned error", self.server_kind),
+                retry_after_secs: None,
             })
         }
     }
 }
 
+// --- NDJSON Streaming Parser ---
+// Ollama's native /api/chat sends one JSON object per line. Each carries a
+// message fragment (content, thinking or complete tool calls); the last has
+// `done: true` with the prompt and output token counts.
+
+async fn parse_ndjson_stream(
+    mut byte_stream: impl Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Unpin,
+    tx: mpsc::Sender<Result<StreamChunk, LLMError>>,
+) {
+    let mut buffer = String::new();
+    let mut sent_start = false;
+    let mut block_index: usize = 0;
+    let mut open_block: Option<BlockType> = None;
+
+    while let Some(chunk_result) = byte_stream.next().await {
+        let chunk = match chunk_result {
+            Ok(c) => c,
+            Err(e) => {
+                let _ = tx
+                    .send(Err(LLMError::NetworkError {
+                        message: e.to_string(),
+                    }))
+                    .await;
+                break;
+            }
+        };
+
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(pos) = buffer.find('\n') {
+            let line = buffer[..pos].trim().to_string();
+            buffer = buffer[pos + 1..].to_string();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_ndjson_line(&line, &mut sent_start, &mut block_index, &mut open_block) {
+                Ok(chunks) => {
+                    for chunk in chunks {
+                        if tx.send(Ok(chunk)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Close the open block if it isn't `kind`, then open a `kind` block
+fn switch_block(
+    chunks: &mut Vec<StreamChunk>,
+    block_index: &mut usize,
+    open_block: &mut Option<BlockType>,
+    kind: BlockType,
+) {
+    if *open_block == Some(kind) {
+        return;
+    }
+    if open_block.take().is_some() {
+        chunks.push(StreamChunk::BlockStop { index: *block_index });
+        *block_index += 1;
+    }
+    chunks.push(StreamChunk::BlockStart {
+        index: *block_index,
+        block_type: kind,
+        tool_id: None,
+        tool_name: None,
+    });
+    *open_block = Some(kind);
+}
+
+fn parse_ndjson_line(
+    line: &str,
+    sent_start: &mut bool,
+    block_index: &mut usize,
+    open_block: &mut Option<BlockType>,
+) -> Result<Vec<StreamChunk>, LLMError> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(line) else {
+        return Ok(Vec::new());
+    };
+    // Errors after the response started arrive as {"error": "..."}
+    if let Some(message) = json["error"].as_str() {
+        return Err(LLMError::ProviderError {
+            status: 500,
+            message: message.to_string(),
+            retry_after_secs: None,
+        });
+    }
+
+    let mut chunks = Vec::new();
+    if !*sent_start {
+        *sent_start = true;
+        chunks.push(StreamChunk::Start {
+            message_id: format!("ollama-{}", uuid::Uuid::new_v4()),
+        });
+    }
+
+    let message = &json["message"];
+    if let Some(thinking) = message["thinking"].as_str().filter(|t| !t.is_empty()) {
+        switch_block(&mut chunks, block_index, open_block, BlockType::Thinking);
+        chunks.push(StreamChunk::Delta(StreamDelta::Thinking(thinking.to_string())));
+    }
+
+    if let Some(content) = message["content"].as_str().filter(|c| !c.is_empty()) {
+        switch_block(&mut chunks, block_index, open_block, BlockType::Text);
+        chunks.push(StreamChunk::Delta(StreamDelta::Text(content.to_string())));
+    }
+
+    // Tool calls arrive whole, without ids; each gets its own block
+    for tool_call in message["tool_calls"].as_array().into_iter().flatten() {
+        if open_block.take().is_some() {
+            chunks.push(StreamChunk::BlockStop { index: *block_index });
+            *block_index += 1;
+        }
+        let function = &tool_call["function"];
+        let input_json = match &function["arguments"] {
+            serde_json::Value::String(args) => args.clone(),
+            args => args.to_string(),
+        };
+        chunks.push(StreamChunk::BlockStart {
+            index: *block_index,
+            block_type: BlockType::ToolUse,
+            tool_id: Some(format!("call_{}", uuid::Uuid::new_v4())),
+            tool_name: function["name"].as_str().map(str::to_string),
+        });
+        chunks.push(StreamChunk::Delta(StreamDelta::ToolInput {
+            block_index: *block_index,
+            input_json,
+        }));
+        chunks.push(StreamChunk::BlockStop { index: *block_index });
+        *block_index += 1;
+    }
+
+    if json["done"].as_bool() == Some(true) {
+        if open_block.take().is_some() {
+            chunks.push(StreamChunk::BlockStop { index: *block_index });
+        }
+        let reason = match json["done_reason"].as_str() {
+            Some("length") => StopReason::MaxTokens,
+            _ => StopReason::EndTurn,
+        };
+        let usage = (json.get("prompt_eval_count").is_some() || json.get("eval_count").is_some()).then(|| Usage {
+            input_tokens: json["prompt_eval_count"].as_u64().unwrap_or(0) as u32,
+            output_tokens: json["eval_count"].as_u64().unwrap_or(0) as u32,
+            thinking_tokens: None,
+            ..Default::default()
+        });
+        chunks.push(StreamChunk::Stop { reason, usage });
+    }
+
+    Ok(chunks)
+}
+
 // --- SSE Streaming Parser ---
-// Ollama's /v1/ endpoint uses the same SSE format as OpenAI,
-// but adds a `reasoning` field in deltas for thinking models (Qwen3)
+// llama-server's /v1/ endpoint uses the same SSE format as OpenAI, plus a
+// `reasoning` / `reasoning_content` field in deltas for thinking models (Qwen3)
 
 async fn parse_sse_stream(
     mut byte_stream: impl Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Unpin,
@@ -731,6 +1046,7 @@ fn parse_sse_data(
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0) as u32,
                     thinking_tokens: None,
+                    ..Default::default()
                 });
 
                 chunks.push(StreamChunk::Stop { reason, usage });
@@ -757,12 +1073,13 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
             404 => LLMError::ModelNotFound {
                 model: message.clone(),
             },
-            _ => LLMError::ProviderError { status, message },
+            _ => LLMError::ProviderError { status, message, retry_after_secs: None },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -789,8 +1106,7 @@ fn convert_response(resp: OllamaResponse) -> LLMResponse {
     // Add tool calls
     if let Some(tool_calls) = choice.message.tool_calls {
         for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+            let input = ToolUse::parse_input(&tc.function.arguments);
             content.push(ContentBlock::ToolUse(ToolUse {
                 id: tc.id,
                 name: tc.function.name,
@@ -815,6 +1131,7 @@ fn convert_response(resp: OllamaResponse) -> LLMResponse {
             input_tokens: resp.usage.prompt_tokens,
             output_tokens: resp.usage.completion_tokens,
             thinking_tokens: None,
+            ..Default::default()
         },
     }
 }
@@ -980,6 +1297,163 @@ mod tests {
         assert!(!in_thinking);
     }
 
+    #[test]
+    fn test_parse_sse_reasoning_deltas_emit_thinking() {
+        // Ollama sends "reasoning", llama-server "reasoning_content"
+        for field in ["reasoning", "reasoning_content"] {
+            let events = [
+                format!(r#"{{"id":"chatcmpl-1","choices":[{{"delta":{{"{}":"Let me think"}},"index":0}}]}}"#, field),
+                format!(r#"{{"id":"chatcmpl-1","choices":[{{"delta":{{"{}":" it through"}},"index":0}}]}}"#, field),
+                r#"{"id":"chatcmpl-1","choices":[{"delta":{"content":"Answer"},"index":0}]}"#.to_string(),
+            ];
+            let mut message_id = String::new();
+            let mut tool_id = String::new();
+            let mut tool_name = String::new();
+            let mut block_index = 0;
+            let mut in_tool = false;
+            let mut in_thinking = false;
+
+            let chunks: Vec<StreamChunk> = events
+                .iter()
+                .flat_map(|data| {
+                    parse_sse_data(
+                        data,
+                        &mut message_id,
+                        &mut tool_id,
+                        &mut tool_name,
+                        &mut block_index,
+                        &mut in_tool,
+                        &mut in_thinking,
+                    )
+                    .unwrap()
+                })
+                .collect();
+
+            let thinking: String = chunks
+                .iter()
+                .filter_map(|c| match c {
+                    StreamChunk::Delta(StreamDelta::Thinking(t)) => Some(t.as_str()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(thinking, "Let me think it through", "{}", field);
+            assert!(matches!(chunks.last(), Some(StreamChunk::Delta(StreamDelta::Text(t))) if t == "Answer"));
+            assert!(!in_thinking);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_ndjson_stream() {
+        // Canned /api/chat output, split mid-line the way the network delivers it
+        let body = concat!(
+            r#"{"model":"qwen3:8b","message":{"role":"assistant","content":"","thinking":"Let me think"},"done":false}"#, "\n",
+            r#"{"model":"qwen3:8b","message":{"role":"assistant","content":"Hel"},"done":false}"#, "\n",
+            r#"{"model":"qwen3:8b","message":{"role":"assistant","content":"lo"},"done":false}"#, "\n",
+            r#"{"model":"qwen3:8b","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":26,"eval_count":12}"#, "\n",
+        );
+        let (head, tail) = body.split_at(60);
+        let bytes = futures::stream::iter(vec![
+            Ok::<_, reqwest::Error>(bytes::Bytes::from(head)),
+            Ok(bytes::Bytes::from(tail)),
+        ]);
+        let (tx, mut rx) = mpsc::channel(32);
+        parse_ndjson_stream(bytes, tx).await;
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            chunks.push(chunk.unwrap());
+        }
+
+        assert!(matches!(chunks[0], StreamChunk::Start { .. }));
+        assert!(matches!(chunks[1], StreamChunk::BlockStart { index: 0, block_type: BlockType::Thinking, .. }));
+        assert!(matches!(&chunks[2], StreamChunk::Delta(StreamDelta::Thinking(t)) if t == "Let me think"));
+        assert!(matches!(chunks[3], StreamChunk::BlockStop { index: 0 }));
+        assert!(matches!(chunks[4], StreamChunk::BlockStart { index: 1, block_type: BlockType::Text, .. }));
+        let text: String = chunks
+            .iter()
+            .filter_map(|c| match c {
+                StreamChunk::Delta(StreamDelta::Text(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(text, "Hello");
+        assert!(matches!(chunks[chunks.len() - 2], StreamChunk::BlockStop { index: 1 }));
+        match chunks.last() {
+            Some(StreamChunk::Stop { reason: StopReason::EndTurn, usage: Some(usage) }) => {
+                assert_eq!(usage.input_tokens, 26);
+                assert_eq!(usage.output_tokens, 12);
+            }
+            other => panic!("expected an end-turn stop, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_ndjson_tool_call_and_error() {
+        let mut sent_start = false;
+        let mut block_index = 0;
+        let mut open_block = None;
+
+        let line = r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"read_file","arguments":{"path":"src/main.rs"}}}]},"done":false}"#;
+        let chunks = parse_ndjson_line(line, &mut sent_start, &mut block_index, &mut open_block).unwrap();
+        assert!(matches!(
+            &chunks[1],
+            StreamChunk::BlockStart { index: 0, block_type: BlockType::ToolUse, tool_id: Some(_), tool_name: Some(name) } if name == "read_file"
+        ));
+        assert!(matches!(
+            &chunks[2],
+            StreamChunk::Delta(StreamDelta::ToolInput { block_index: 0, input_json }) if input_json == r#"{"path":"src/main.rs"}"#
+        ));
+        assert!(matches!(chunks[3], StreamChunk::BlockStop { index: 0 }));
+
+        let done = r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"length"}"#;
+        let chunks = parse_ndjson_line(done, &mut sent_start, &mut block_index, &mut open_block).unwrap();
+        assert!(matches!(chunks.as_slice(), [StreamChunk::Stop { reason: StopReason::MaxTokens, usage: None }]));
+
+        let error = r#"{"error":"model runner has unexpectedly stopped"}"#;
+        assert!(parse_ndjson_line(error, &mut sent_start, &mut block_index, &mut open_block).is_err());
+    }
+
+    #[test]
+    fn test_native_body_uses_ollama_message_format() {
+        let provider = OllamaProvider::new(None);
+        let tool_use = ToolUse {
+            id: "call_1".to_string(),
+            name: "read_file".to_string(),
+            input: json!({"path": "a.rs"}),
+        };
+        let request = LLMRequest {
+            model: "qwen3:8b".to_string(),
+            system: Some("Be brief".to_string()),
+            messages: vec![
+                super::super::types::Message::user("read a.rs"),
+                super::super::types::Message {
+                    role: super::super::types::Role::Assistant,
+                    content: vec![ContentBlock::ToolUse(tool_use)],
+                },
+                super::super::types::Message {
+                    role: super::super::types::Role::User,
+                    content: vec![ContentBlock::ToolResult(super::super::types::ToolResult {
+                        tool_use_id: "call_1".to_string(),
+                        content: super::super::types::ToolResultContent::Text("fn main() {}".to_string()),
+                        is_error: false,
+                    })],
+                },
+            ],
+            max_tokens: Some(512),
+            stream: true,
+            ..Default::default()
+        };
+
+        let body = provider.build_native_body(&request);
+        assert_eq!(body["options"]["num_predict"], 512);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[1]["content"], "read a.rs");
+        assert_eq!(messages[2]["tool_calls"][0]["function"]["arguments"]["path"], "a.rs");
+        assert_eq!(messages[3]["role"], "tool");
+        assert_eq!(messages[3]["tool_name"], "read_file");
+    }
+
     #[test]
     fn test_parse_error_response() {
         let body = r#"{"error":"model 'nonexistent' not found"}"#;
@@ -999,6 +1473,7 @@ mod tests {
                 message: OllamaMessage {
                     content: Some("Hi there!".to_string()),
                     reasoning: Some("The user said hi, I should respond.".to_string()),
+                    reasoning_content: None,
                     tool_calls: None,
                 },
                 finish_reason: Some("stop".to_string()),
@@ -3,25 +3,35 @@
 
 use async_trait::async_trait;
 use futures::{Stream, StreamExt};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::Deserialize;
 use serde_json::json;
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 
+use crate::config::AuthScheme;
+
 use super::provider::{Capability, ModelInfo, Provider, StreamBox};
+use super::rate_limit::RateLimits;
 use super::types::{
     BlockType, ContentBlock, LLMError, LLMRequest, LLMResponse, StopReason, StreamChunk,
     StreamDelta, ToolUse, Usage,
 };
 
-const OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
-const OPENAI_RESPONSES_URL: &str = "https://api.openai.com/v1/responses";
+/// API root; endpoint paths are appended. Overridable via `base_url` in llm.toml
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
 
 /// OpenAI GPT provider
 pub struct OpenAIProvider {
     api_key: String,
+    /// API root without a trailing slash
+    base_url: String,
+    /// `api-version` query parameter (Azure OpenAI deployments)
+    api_version: Option<String>,
+    auth: AuthScheme,
     http_client: Client,
+    /// Rate-limit headers of each response end up here
+    rate_limits: RateLimits,
     models: Vec<ModelInfo>,
     default_model: String,
 }
@@ -83,12 +93,67 @@ impl OpenAIProvider {
 
         Self {
             api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_version: None,
+            auth: AuthScheme::Bearer,
             http_client,
             models,
+            rate_limits: RateLimits::default(),
             default_model: "gpt-5.2-2025-12-11".to_string(),
         }
     }
 
+    /// Use a preconfigured client (extra headers, custom CA bundle)
+    pub fn with_http_client(mut self, http_client: Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Record rate-limit headers in a state shared with `LLMManager`
+    pub fn with_rate_limits(mut self, rate_limits: RateLimits) -> Self {
+        self.rate_limits = rate_limits;
+        self
+    }
+
+    /// Send requests to a proxy or gateway instead of the public API
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Azure OpenAI: `api-version` for every request and the key in an
+    /// `api-key` header, with `base_url` pointing at the deployment
+    pub fn with_endpoint(mut self, api_version: Option<String>, auth: AuthScheme) -> Self {
+        self.api_version = api_version;
+        self.auth = auth;
+        self
+    }
+
+    fn endpoint_url(&self, path: &str) -> String {
+        match &self.api_version {
+            Some(version) => format!("{}/{}?api-version={}", self.base_url, path, urlencoding::encode(version)),
+            None => format!("{}/{}", self.base_url, path),
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.auth {
+            AuthScheme::Bearer => request.header("Authorization", format!("Bearer {}", self.api_key)),
+            AuthScheme::ApiKey => request.header("api-key", &self.api_key),
+        }
+    }
+
+    fn supports_thinking(&self, model: &str) -> bool {
+        self.models.iter().any(|m| m.id == model && m.supports_thinking)
+    }
+
+    /// Chat Completions never exposes reasoning, so reasoning models go through
+    /// the Responses API whenever thinking output is requested
+    fn use_responses_api(&self, request: &LLMRequest, model: &str) -> bool {
+        requires_responses_api(model)
+            || (request.thinking.as_ref().is_some_and(|t| t.enabled) && self.supports_thinking(model))
+    }
+
     fn get_model<'a>(&'a self, request: &'a LLMRequest) -> &'a str {
         if request.model.is_empty() {
             &self.default_model
@@ -250,7 +315,7 @@ impl OpenAIProvider {
                     super::types::Role::User => {
                         // User messages
                         let content = self.convert_responses_content(&m.content);
-                        let is_empty = content.is_null() || content.as_array().map_or(false, |a| a.is_empty());
+                        let is_empty = content.is_null() || content.as_array().is_some_and(|a| a.is_empty());
                         if !is_empty {
                             input.push(json!({
                                 "role": "user",
@@ -311,6 +376,11 @@ impl OpenAIProvider {
             body["temperature"] = json!(temp);
         }
 
+        // Reasoning models only stream their reasoning as summaries, and only on request
+        if request.thinking.as_ref().is_some_and(|t| t.enabled) && self.supports_thinking(model) {
+            body["reasoning"] = json!({ "summary": "auto" });
+        }
+
         // Tools use flatter format in Responses API
         if !request.tools.is_empty() {
             let tools: Vec<serde_json::Value> = request
@@ -430,18 +500,16 @@ impl Provider for OpenAIProvider {
         req.stream = false;
 
         let model = self.get_model(&req).to_string();
-        let use_responses = requires_responses_api(&model);
+        let use_responses = self.use_responses_api(&req, &model);
 
         let (url, body) = if use_responses {
-            (OPENAI_RESPONSES_URL, self.build_responses_request_body(&req))
+            (self.endpoint_url("responses"), self.build_responses_request_body(&req))
         } else {
-            (OPENAI_CHAT_URL, self.build_chat_request_body(&req))
+            (self.endpoint_url("chat/completions"), self.build_chat_request_body(&req))
         };
 
         let response = self
-            .http_client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .authorize(self.http_client.post(url))
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
@@ -450,10 +518,12 @@ impl Provider for OpenAIProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         if use_responses {
@@ -469,23 +539,32 @@ impl Provider for OpenAIProvider {
         }
     }
 
+    fn request_body(&self, request: &LLMRequest) -> Option<serde_json::Value> {
+        let mut req = request.clone();
+        req.stream = true;
+        let model = self.get_model(&req).to_string();
+        if self.use_responses_api(&req, &model) {
+            Some(self.build_responses_request_body(&req))
+        } else {
+            Some(self.build_chat_request_body(&req))
+        }
+    }
+
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError> {
         let mut req = request;
         req.stream = true;
 
         let model = self.get_model(&req).to_string();
-        let use_responses = requires_responses_api(&model);
+        let use_responses = self.use_responses_api(&req, &model);
 
         let (url, body) = if use_responses {
-            (OPENAI_RESPONSES_URL, self.build_responses_request_body(&req))
+            (self.endpoint_url("responses"), self.build_responses_request_body(&req))
         } else {
-            (OPENAI_CHAT_URL, self.build_chat_request_body(&req))
+            (self.endpoint_url("chat/completions"), self.build_chat_request_body(&req))
         };
 
         let response = self
-            .http_client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .authorize(self.http_client.post(url))
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
@@ -494,10 +573,12 @@ impl Provider for OpenAIProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         let (tx, rx) = mpsc::channel::<Result<StreamChunk, LLMError>>(32);
@@ -525,9 +606,7 @@ impl Provider for OpenAIProvider {
         });
 
         let response = self
-            .http_client
-            .post(OPENAI_CHAT_URL)
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .authorize(self.http_client.post(self.endpoint_url("chat/completions")))
             .header("Content-Type", "application/json")
             .json(&body)
             .send()
@@ -536,10 +615,12 @@ impl Provider for OpenAIProvider {
                 message: e.to_string(),
             })?;
 
+        self.rate_limits.record(self.name(), response.headers());
         let status = response.status();
         if !status.is_success() {
+            let retry_after = super::http::retry_after(response.headers());
             let text = response.text().await.unwrap_or_default();
-            return Err(parse_error_response(status.as_u16(), &text));
+            return Err(parse_error_response(status.as_u16(), &text).with_retry_after(retry_after));
         }
 
         Ok(())
@@ -728,6 +809,7 @@ fn parse_chat_sse_data(
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0) as u32,
                     thinking_tokens: None,
+                    ..Default::default()
                 });
 
                 chunks.push(StreamChunk::Stop { reason, usage });
@@ -754,7 +836,7 @@ async fn parse_responses_sse_stream(
     let mut started = false;
     let mut block_index: usize = 0;
     let mut in_text_block = false;
-    // Track tool call_ids by output_index for BlockStart/BlockStop
+    // Track tool call and reasoning block indices by output_index for BlockStart/BlockStop
     let mut tool_block_indices: std::collections::HashMap<usize, usize> = std::collections::HashMap::new();
 
     while let Some(chunk_result) = byte_stream.next().await {
@@ -813,14 +895,12 @@ fn parse_responses_sse_data(
     let mut chunks = Vec::new();
 
     match event_type {
-        "response.created" => {
-            if !*started {
-                *started = true;
-                let response_id = json["response"]["id"].as_str().unwrap_or("").to_string();
-                chunks.push(StreamChunk::Start {
-                    message_id: response_id,
-                });
-            }
+        "response.created" if !*started => {
+            *started = true;
+            let response_id = json["response"]["id"].as_str().unwrap_or("").to_string();
+            chunks.push(StreamChunk::Start {
+                message_id: response_id,
+            });
         }
 
         "response.output_item.added" => {
@@ -849,6 +929,24 @@ fn parse_responses_sse_data(
                     });
                     *block_index += 1;
                 }
+                "reasoning" => {
+                    if *in_text_block {
+                        chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
+                        *in_text_block = false;
+                    }
+
+                    // Reasoning summaries map onto a thinking block for the whole item
+                    let bi = *block_index;
+                    tool_block_indices.insert(output_index, bi);
+
+                    chunks.push(StreamChunk::BlockStart {
+                        index: bi,
+                        block_type: BlockType::Thinking,
+                        tool_id: None,
+                        tool_name: None,
+                    });
+                    *block_index += 1;
+                }
                 _ => {
                     // message type - text content will come via content_part.added
                 }
@@ -878,6 +976,23 @@ fn parse_responses_sse_data(
             }
         }
 
+        "response.reasoning_summary_text.delta" | "response.reasoning_text.delta" => {
+            if let Some(delta) = json["delta"].as_str() {
+                if !delta.is_empty() {
+                    chunks.push(StreamChunk::Delta(StreamDelta::Thinking(delta.to_string())));
+                }
+            }
+        }
+
+        // Separate consecutive summary parts like paragraphs
+        "response.reasoning_summary_part.added"
+            if json["summary_index"].as_u64().unwrap_or(0) > 0 =>
+        {
+            chunks.push(StreamChunk::Delta(StreamDelta::Thinking(
+                "\n\n".to_string(),
+            )));
+        }
+
         "response.function_call_arguments.delta" => {
             let output_index = json["output_index"].as_u64().unwrap_or(0) as usize;
             if let Some(delta) = json["delta"].as_str() {
@@ -891,18 +1006,16 @@ fn parse_responses_sse_data(
             }
         }
 
-        "response.content_part.done" => {
-            if *in_text_block {
-                chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
-                *in_text_block = false;
-            }
+        "response.content_part.done" if *in_text_block => {
+            chunks.push(StreamChunk::BlockStop { index: *block_index - 1 });
+            *in_text_block = false;
         }
 
         "response.output_item.done" => {
             let item_type = json["item"]["type"].as_str().unwrap_or("");
             let output_index = json["output_index"].as_u64().unwrap_or(0) as usize;
 
-            if item_type == "function_call" {
+            if item_type == "function_call" || item_type == "reasoning" {
                 if let Some(&bi) = tool_block_indices.get(&output_index) {
                     chunks.push(StreamChunk::BlockStop { index: bi });
                 }
@@ -919,6 +1032,7 @@ fn parse_responses_sse_data(
                     .and_then(|v| v.as_u64())
                     .filter(|&v| v > 0)
                     .map(|v| v as u32),
+                ..Default::default()
             });
 
             // Determine stop reason from response status and output
@@ -946,6 +1060,7 @@ fn parse_responses_sse_data(
             chunks.push(StreamChunk::Error(LLMError::ProviderError {
                 status: 500,
                 message: error_msg,
+                retry_after_secs: None,
             }));
         }
 
@@ -976,7 +1091,7 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
         match (error_type, error_code) {
             ("invalid_api_key", _) | (_, "invalid_api_key") => LLMError::AuthError { message },
             ("rate_limit_error", _) | (_, "rate_limit_exceeded") => LLMError::RateLimit {
-                retry_after_secs: 60,
+                retry_after_secs: None,
             },
             ("invalid_request_error", _) => LLMError::InvalidRequest { message },
             ("model_not_found", _) | (_, "model_not_found") => LLMError::ModelNotFound {
@@ -985,12 +1100,13 @@ fn parse_error_response(status: u16, body: &str) -> LLMError {
             ("content_filter", _) | (_, "content_policy_violation") => {
                 LLMError::ContentFiltered { reason: message }
             }
-            _ => LLMError::ProviderError { status, message },
+            _ => LLMError::ProviderError { status, message, retry_after_secs: None },
         }
     } else {
         LLMError::ProviderError {
             status,
             message: body.to_string(),
+            retry_after_secs: None,
         }
     }
 }
@@ -1051,8 +1167,7 @@ fn convert_chat_response(resp: ChatResponse) -> LLMResponse {
 
     if let Some(tool_calls) = choice.message.tool_calls {
         for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or(serde_json::Value::Null);
+            let input = ToolUse::parse_input(&tc.function.arguments);
             content.push(ContentBlock::ToolUse(ToolUse {
                 id: tc.id,
                 name: tc.function.name,
@@ -1078,6 +1193,7 @@ fn convert_chat_response(resp: ChatResponse) -> LLMResponse {
             input_tokens: resp.usage.prompt_tokens,
             output_tokens: resp.usage.completion_tokens,
             thinking_tokens: None,
+            ..Default::default()
         },
     }
 }
@@ -1110,6 +1226,16 @@ enum ResponsesOutputItem {
         name: String,
         arguments: String,
     },
+    #[serde(rename = "reasoning")]
+    Reasoning {
+        #[serde(default)]
+        summary: Vec<ResponsesReasoningSummary>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponsesReasoningSummary {
+    text: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1150,14 +1276,19 @@ fn convert_responses_response(resp: ResponsesApiResponse) -> LLMResponse {
             }
             ResponsesOutputItem::FunctionCall { call_id, name, arguments, .. } => {
                 has_tool_calls = true;
-                let input: serde_json::Value =
-                    serde_json::from_str(arguments).unwrap_or(serde_json::Value::Null);
+                let input = ToolUse::parse_input(arguments);
                 content.push(ContentBlock::ToolUse(ToolUse {
                     id: call_id.clone(),
                     name: name.clone(),
                     input,
                 }));
             }
+            ResponsesOutputItem::Reasoning { summary } => {
+                let text = summary.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join("\n\n");
+                if !text.is_empty() {
+                    content.push(ContentBlock::Thinking(text));
+                }
+            }
         }
     }
 
@@ -1175,6 +1306,7 @@ fn convert_responses_response(resp: ResponsesApiResponse) -> LLMResponse {
         thinking_tokens: u.output_tokens_details
             .and_then(|d| d.reasoning_tokens)
             .filter(|&v| v > 0),
+        ..Default::default()
     }).unwrap_or_default();
 
     LLMResponse {
@@ -1194,6 +1326,25 @@ fn convert_responses_response(resp: ResponsesApiResponse) -> LLMResponse {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_azure_endpoint_and_auth() {
+        let provider = OpenAIProvider::new("sk-test");
+        assert_eq!(provider.endpoint_url("chat/completions"), "https://api.openai.com/v1/chat/completions");
+        let request = provider.authorize(provider.http_client.post("http://localhost")).build().unwrap();
+        assert_eq!(request.headers()["Authorization"], "Bearer sk-test");
+
+        let azure = OpenAIProvider::new("azure-key")
+            .with_base_url("https://acme.openai.azure.com/openai/deployments/gpt-4o/")
+            .with_endpoint(Some("2024-10-21".to_string()), AuthScheme::ApiKey);
+        assert_eq!(
+            azure.endpoint_url("chat/completions"),
+            "https://acme.openai.azure.com/openai/deployments/gpt-4o/chat/completions?api-version=2024-10-21"
+        );
+        let request = azure.authorize(azure.http_client.post("http://localhost")).build().unwrap();
+        assert_eq!(request.headers()["api-key"], "azure-key");
+        assert!(request.headers().get("Authorization").is_none());
+    }
+
     #[test]
     fn test_requires_responses_api() {
         assert!(requires_responses_api("gpt-5.2-pro-2025-12-11"));
@@ -1349,6 +1500,56 @@ mod tests {
         assert_eq!(*tool_indices.get(&0).unwrap(), 0);
     }
 
+    #[test]
+    fn test_parse_responses_reasoning_summary() {
+        let events = [
+            r#"{"type":"response.output_item.added","item":{"id":"rs_1","type":"reasoning","summary":[]},"output_index":0}"#,
+            r#"{"type":"response.reasoning_summary_part.added","item_id":"rs_1","output_index":0,"summary_index":0}"#,
+            r#"{"type":"response.reasoning_summary_text.delta","item_id":"rs_1","output_index":0,"summary_index":0,"delta":"Checking the file"}"#,
+            r#"{"type":"response.reasoning_summary_part.added","item_id":"rs_1","output_index":0,"summary_index":1}"#,
+            r#"{"type":"response.reasoning_summary_text.delta","item_id":"rs_1","output_index":0,"summary_index":1,"delta":"Then editing"}"#,
+            r#"{"type":"response.output_item.done","item":{"id":"rs_1","type":"reasoning"},"output_index":0}"#,
+        ];
+        let mut started = true;
+        let mut block_index = 0;
+        let mut in_text = false;
+        let mut indices = std::collections::HashMap::new();
+
+        let chunks: Vec<StreamChunk> = events
+            .iter()
+            .filter_map(|e| parse_responses_sse_data(e, &mut started, &mut block_index, &mut in_text, &mut indices))
+            .flatten()
+            .collect();
+
+        assert!(matches!(chunks[0], StreamChunk::BlockStart { index: 0, block_type: BlockType::Thinking, .. }));
+        let thinking: String = chunks
+            .iter()
+            .filter_map(|c| match c {
+                StreamChunk::Delta(StreamDelta::Thinking(t)) => Some(t.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(thinking, "Checking the file\n\nThen editing");
+        assert!(matches!(chunks.last(), Some(StreamChunk::BlockStop { index: 0 })));
+        assert_eq!(block_index, 1);
+    }
+
+    #[test]
+    fn test_reasoning_summary_requested_for_thinking_models() {
+        let provider = OpenAIProvider::new("key");
+        let mut request = LLMRequest {
+            model: "o3-mini".to_string(),
+            thinking: Some(crate::llm::types::ThinkingConfig { enabled: true, budget_tokens: None }),
+            ..Default::default()
+        };
+        assert!(provider.use_responses_api(&request, "o3-mini"));
+        assert_eq!(provider.build_responses_request_body(&request)["reasoning"]["summary"], "auto");
+
+        request.model = "gpt-4o".to_string();
+        assert!(!provider.use_responses_api(&request, "gpt-4o"));
+        assert!(provider.build_responses_request_body(&request).get("reasoning").is_none());
+    }
+
     #[test]
     fn test_parse_responses_completed() {
         let data = r#"{"type":"response.completed","response":{"id":"resp_123","status":"completed","output":[],"usage":{"input_tokens":20,"output_tokens":10,"output_tokens_details":{"reasoning_tokens":0}},"model":"gpt-5.2-pro"},"sequence_number":8}"#;
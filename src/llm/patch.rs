@@ -0,0 +1,302 @@
+// Patch - Targeted file edits for the apply_edit tool
+//
+// Applies unified-diff hunks or a unique old/new string replacement to file
+// content in memory. Every hunk is validated against the current content
+// before anything is written, so a stale or ambiguous edit is rejected whole.
+
+use sha1::{Digest, Sha1};
+
+/// A single `@@ -a,b +c,d @@` hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// 1-indexed start line in the original file
+    pub old_start: usize,
+    /// Context and removed lines, in order
+    pub old_lines: Vec<String>,
+    /// Context and added lines, in order
+    pub new_lines: Vec<String>,
+}
+
+/// Result of applying an edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditOutcome {
+    pub content: String,
+    pub hunks_applied: usize,
+}
+
+/// Parse a single-file unified diff into hunks. File headers are ignored.
+pub fn parse_unified_diff(patch: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut current: Option<Hunk> = None;
+    let mut lines = patch.trim_end_matches('\n').lines().peekable();
+
+    while let Some(line) = lines.next() {
+        // A "---" line followed by "+++" is a file header, not a removed line
+        let is_file_header = line.starts_with("diff ")
+            || (line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ ")));
+        if is_file_header {
+            if current.is_some() || !hunks.is_empty() {
+                return Err("Patch touches more than one file; apply_edit edits a single file".to_string());
+            }
+            if line.starts_with("--- ") {
+                lines.next();
+            }
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            if let Some(hunk) = current.take() {
+                hunks.push(hunk);
+            }
+            current = Some(Hunk {
+                old_start: parse_hunk_header(line)?,
+                old_lines: Vec::new(),
+                new_lines: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(hunk) = current.as_mut() else {
+            // Preamble before the first hunk (index lines, etc.)
+            continue;
+        };
+
+        if let Some(rest) = line.strip_prefix('-') {
+            hunk.old_lines.push(rest.to_string());
+        } else if let Some(rest) = line.strip_prefix('+') {
+            hunk.new_lines.push(rest.to_string());
+        } else if let Some(rest) = line.strip_prefix(' ') {
+            hunk.old_lines.push(rest.to_string());
+            hunk.new_lines.push(rest.to_string());
+        } else if line.is_empty() {
+            // Some tools strip the leading space from blank context lines
+            hunk.old_lines.push(String::new());
+            hunk.new_lines.push(String::new());
+        } else if line.starts_with('\\') {
+            // "\ No newline at end of file"
+        } else {
+            return Err(format!("Unexpected line in hunk: {:?}", line));
+        }
+    }
+
+    if let Some(hunk) = current {
+        hunks.push(hunk);
+    }
+    if hunks.is_empty() {
+        return Err("Patch contains no hunks".to_string());
+    }
+    Ok(hunks)
+}
+
+fn parse_hunk_header(line: &str) -> Result<usize, String> {
+    let invalid = || format!("Invalid hunk header: {:?}", line);
+    let old = line
+        .trim_start_matches('@')
+        .split_whitespace()
+        .find(|part| part.starts_with('-'))
+        .ok_or_else(invalid)?;
+    old[1..]
+        .split(',')
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(invalid)
+}
+
+/// Apply hunks in order. A hunk is placed at its stated line if the content
+/// matches there, otherwise at its single exact match later in the file.
+/// Line endings are kept per line; added lines use the file's CRLF or LF style.
+pub fn apply_hunks(content: &str, hunks: &[Hunk]) -> Result<EditOutcome, String> {
+    let trailing_newline = content.is_empty() || content.ends_with('\n');
+    let eol = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+    let mut endings: Vec<&str> = content
+        .split_inclusive('\n')
+        .map(|l| {
+            if l.ends_with("\r\n") {
+                "\r\n"
+            } else if l.ends_with('\n') {
+                "\n"
+            } else {
+                ""
+            }
+        })
+        .collect();
+    let mut offset: isize = 0;
+    let mut min_pos = 0usize;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let expected = (hunk.old_start as isize - 1 + offset).max(0) as usize;
+
+        let pos = if hunk.old_lines.is_empty() {
+            // Pure insertion: "-a,0" inserts after line a
+            (hunk.old_start as isize + offset).clamp(min_pos as isize, lines.len() as isize) as usize
+        } else if expected >= min_pos && block_matches(&lines, expected, &hunk.old_lines) {
+            expected
+        } else {
+            let candidates: Vec<usize> = (min_pos..lines.len())
+                .filter(|&p| block_matches(&lines, p, &hunk.old_lines))
+                .collect();
+            match candidates.as_slice() {
+                [p] => *p,
+                [] => return Err(format!("Hunk {} does not match the current file content", i + 1)),
+                _ => {
+                    return Err(format!(
+                        "Hunk {} matches {} locations; add more context to make it unique",
+                        i + 1,
+                        candidates.len()
+                    ))
+                }
+            }
+        };
+
+        let end = pos + hunk.old_lines.len();
+        lines.splice(pos..end, hunk.new_lines.iter().cloned());
+        // Replacement lines inherit the endings of the lines they replace
+        let new_endings: Vec<&str> = (0..hunk.new_lines.len())
+            .map(|j| if pos + j < end { endings[pos + j] } else { eol })
+            .collect();
+        endings.splice(pos..end, new_endings);
+        offset += hunk.new_lines.len() as isize - hunk.old_lines.len() as isize;
+        min_pos = pos + hunk.new_lines.len();
+    }
+
+    let mut result = String::with_capacity(content.len());
+    let last = lines.len().saturating_sub(1);
+    for (i, (line, ending)) in lines.iter().zip(&endings).enumerate() {
+        result.push_str(line);
+        if i < last || trailing_newline {
+            result.push_str(if ending.is_empty() { eol } else { ending });
+        }
+    }
+    Ok(EditOutcome { content: result, hunks_applied: hunks.len() })
+}
+
+fn block_matches(lines: &[String], pos: usize, block: &[String]) -> bool {
+    pos + block.len() <= lines.len() && lines[pos..pos + block.len()] == *block
+}
+
+/// Replace `old` with `new`, requiring exactly one occurrence
+pub fn replace_unique(content: &str, old: &str, new: &str) -> Result<EditOutcome, String> {
+    if old.is_empty() {
+        return Err("old_string must not be empty".to_string());
+    }
+    match content.matches(old).count() {
+        0 => Err("old_string not found in file; it must match exactly, including whitespace".to_string()),
+        1 => Ok(EditOutcome { content: content.replacen(old, new, 1), hunks_applied: 1 }),
+        n => Err(format!(
+            "old_string occurs {} times in file; include more surrounding context to make it unique",
+            n
+        )),
+    }
+}
+
+/// Apply an apply_edit tool input (`patch`, or `old_string` + `new_string`) to content
+pub fn apply_edit_input(content: &str, input: &serde_json::Value) -> Result<EditOutcome, String> {
+    if let Some(patch) = input.get("patch").and_then(|p| p.as_str()) {
+        return apply_hunks(content, &parse_unified_diff(patch)?);
+    }
+    let old = input
+        .get("old_string")
+        .and_then(|s| s.as_str())
+        .ok_or_else(|| "Provide either 'patch' or 'old_string' and 'new_string'".to_string())?;
+    let new = input
+        .get("new_string")
+        .and_then(|s| s.as_str())
+        .ok_or_else(|| "Missing 'new_string' parameter".to_string())?;
+    replace_unique(content, old, new)
+}
+
+/// Git blob hash of the content (matches `git hash-object`)
+pub fn content_hash(content: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", content.len()).as_bytes());
+    hasher.update(content.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    #[test]
+    fn test_parse_and_apply_hunk() {
+        let patch = "--- a/main.rs\n+++ b/main.rs\n@@ -2,2 +2,2 @@\n     let a = 1;\n-    let b = 2;\n+    let b = 3;\n";
+        let hunks = parse_unified_diff(patch).unwrap();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_start, 2);
+
+        let outcome = apply_hunks(ORIGINAL, &hunks).unwrap();
+        assert_eq!(outcome.hunks_applied, 1);
+        assert!(outcome.content.contains("let b = 3;"));
+        assert!(outcome.content.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_apply_hunk_with_wrong_line_number_relocates() {
+        let patch = "@@ -40,1 +40,1 @@\n-    let b = 2;\n+    let b = 4;\n";
+        let outcome = apply_hunks(ORIGINAL, &parse_unified_diff(patch).unwrap()).unwrap();
+        assert!(outcome.content.contains("let b = 4;"));
+    }
+
+    #[test]
+    fn test_apply_multiple_hunks_tracks_offset() {
+        let patch = "@@ -1,1 +1,2 @@\n fn main() {\n+    // start\n@@ -5,1 +6,2 @@\n+    // end\n }\n";
+        let outcome = apply_hunks(ORIGINAL, &parse_unified_diff(patch).unwrap()).unwrap();
+        assert_eq!(outcome.hunks_applied, 2);
+        assert!(outcome.content.starts_with("fn main() {\n    // start\n"));
+        assert!(outcome.content.ends_with("    // end\n}\n"));
+    }
+
+    #[test]
+    fn test_apply_hunk_keeps_line_endings() {
+        let patch = "@@ -2,1 +2,2 @@\n-    let a = 1;\n+    let a = 10;\n+    let c = 3;\n";
+        let hunks = parse_unified_diff(patch).unwrap();
+
+        let crlf = ORIGINAL.replace('\n', "\r\n");
+        let outcome = apply_hunks(&crlf, &hunks).unwrap();
+        assert_eq!(outcome.content, "fn main() {\r\n    let a = 10;\r\n    let c = 3;\r\n    let b = 2;\r\n    println!(\"{}\", a + b);\r\n}\r\n");
+
+        let unterminated = ORIGINAL.trim_end();
+        let outcome = apply_hunks(unterminated, &hunks).unwrap();
+        assert!(outcome.content.ends_with("a + b);\n}"));
+
+        // Appending after an unterminated last line keeps the file unterminated
+        let append = parse_unified_diff("@@ -5,0 +6,1 @@\n+// end\n").unwrap();
+        assert_eq!(apply_hunks("a\r\nb", &append).unwrap().content, "a\r\nb\r\n// end");
+    }
+
+    #[test]
+    fn test_apply_hunk_rejects_mismatch() {
+        let patch = "@@ -2,1 +2,1 @@\n-    let a = 99;\n+    let a = 1;\n";
+        let err = apply_hunks(ORIGINAL, &parse_unified_diff(patch).unwrap()).unwrap_err();
+        assert!(err.contains("does not match"));
+    }
+
+    #[test]
+    fn test_parse_rejects_multi_file_patch() {
+        let patch = "@@ -1 +1 @@\n-a\n+b\n--- a/other\n+++ b/other\n@@ -1 +1 @@\n-c\n+d\n";
+        assert!(parse_unified_diff(patch).is_err());
+        assert!(parse_unified_diff("no hunks here").is_err());
+    }
+
+    #[test]
+    fn test_replace_unique() {
+        let outcome = replace_unique(ORIGINAL, "let a = 1;", "let a = 10;").unwrap();
+        assert!(outcome.content.contains("let a = 10;"));
+
+        assert!(replace_unique(ORIGINAL, "missing", "x").unwrap_err().contains("not found"));
+        assert!(replace_unique(ORIGINAL, "let", "x").unwrap_err().contains("2 times"));
+    }
+
+    #[test]
+    fn test_content_hash_matches_git() {
+        // `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(content_hash("hello\n"), "ce013625030ba8dba906f756967f9e9ca394464a");
+    }
+}
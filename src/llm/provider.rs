@@ -34,6 +34,11 @@ pub trait Provider: Send + Sync {
     /// Send a streaming request, returns a stream of chunks
     async fn stream(&self, request: LLMRequest) -> Result<StreamBox, LLMError>;
 
+    /// JSON body `stream` sends for this request, for debug capture
+    fn request_body(&self, _request: &LLMRequest) -> Option<serde_json::Value> {
+        None
+    }
+
     /// Count tokens for a request (optional - default estimates)
     async fn count_tokens(&self, request: &LLMRequest) -> Result<u32, LLMError> {
         let chars: usize = request
@@ -0,0 +1,217 @@
+// Rate-limit headers from hosted providers
+//
+// Anthropic sends `anthropic-ratelimit-{requests,tokens}-{limit,remaining,reset}`
+// with the reset as an RFC 3339 time; OpenAI sends
+// `x-ratelimit-{limit,remaining,reset}-{requests,tokens}` with the reset as a
+// duration such as `6m0s` or `20ms`. Every response updates its provider's
+// state, and the manager holds the next request until the reset once a budget
+// is used up instead of waiting for a 429.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::header::HeaderMap;
+
+/// Below this share of the limit the status bar shows what's left
+const LOW_FRACTION: f64 = 0.1;
+
+/// One budget (requests or tokens) as of the last response
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub limit: Option<u64>,
+    pub remaining: u64,
+    pub reset_at: Option<Instant>,
+}
+
+impl Budget {
+    fn is_low(&self) -> bool {
+        match self.limit {
+            Some(limit) if limit > 0 => (self.remaining as f64) < limit as f64 * LOW_FRACTION,
+            _ => self.remaining == 0,
+        }
+    }
+}
+
+/// Request and token budgets of one provider
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimitState {
+    pub requests: Option<Budget>,
+    pub tokens: Option<Budget>,
+}
+
+impl RateLimitState {
+    /// Parse whichever provider's headers are present; None if there are none
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let now = Instant::now();
+        let anthropic = |kind: &str, field: &str| header(headers, &format!("anthropic-ratelimit-{}-{}", kind, field));
+        let openai = |kind: &str, field: &str| header(headers, &format!("x-ratelimit-{}-{}", field, kind));
+
+        let budget = |kind: &str| -> Option<Budget> {
+            if let Some(remaining) = anthropic(kind, "remaining").and_then(|v| v.parse().ok()) {
+                return Some(Budget {
+                    limit: anthropic(kind, "limit").and_then(|v| v.parse().ok()),
+                    remaining,
+                    reset_at: anthropic(kind, "reset").and_then(|v| instant_at(&v, now)),
+                });
+            }
+            let remaining = openai(kind, "remaining").and_then(|v| v.parse().ok())?;
+            Some(Budget {
+                limit: openai(kind, "limit").and_then(|v| v.parse().ok()),
+                remaining,
+                reset_at: openai(kind, "reset").and_then(|v| parse_duration(&v)).map(|d| now + d),
+            })
+        };
+
+        let state = Self { requests: budget("requests"), tokens: budget("tokens") };
+        (state != Self::default()).then_some(state)
+    }
+
+    /// How long to hold the next request: until the latest reset among the
+    /// budgets that are used up
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        [self.requests, self.tokens]
+            .into_iter()
+            .flatten()
+            .filter(|b| b.remaining == 0)
+            .filter_map(|b| b.reset_at?.checked_duration_since(now))
+            .filter(|d| !d.is_zero())
+            .max()
+    }
+
+    /// Short status-bar text while a budget is low, e.g. "3 req left · 12s";
+    /// a budget whose reset has passed has refilled and isn't shown
+    pub fn summary(&self, now: Instant) -> Option<String> {
+        let (budget, unit) = [(self.requests, "req"), (self.tokens, "tok")]
+            .into_iter()
+            .filter_map(|(b, unit)| Some((b?, unit)))
+            .filter(|(b, _)| b.is_low() && b.reset_at.map_or(true, |at| at > now))
+            .min_by_key(|(b, _)| b.remaining)?;
+        let mut text = format!("{} {} left", budget.remaining, unit);
+        if let Some(reset) = budget.reset_at.and_then(|at| at.checked_duration_since(now)) {
+            text.push_str(&format!(" · {}s", reset.as_secs().max(1)));
+        }
+        Some(text)
+    }
+}
+
+/// Latest state per provider, shared by the providers and `LLMManager`
+#[derive(Debug, Clone, Default)]
+pub struct RateLimits(Arc<Mutex<HashMap<String, RateLimitState>>>);
+
+impl RateLimits {
+    /// Update `provider`'s state from a response; responses without rate-limit
+    /// headers leave it as it was
+    pub fn record(&self, provider: &str, headers: &HeaderMap) {
+        let Some(state) = RateLimitState::from_headers(headers) else {
+            return;
+        };
+        if let Ok(mut states) = self.0.lock() {
+            states.insert(provider.to_string(), state);
+        }
+    }
+
+    pub fn get(&self, provider: &str) -> Option<RateLimitState> {
+        self.0.lock().ok()?.get(provider).copied()
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> Option<String> {
+    Some(headers.get(name)?.to_str().ok()?.trim().to_string())
+}
+
+/// `Instant` for an RFC 3339 time (times in the past map to `now`)
+fn instant_at(value: &str, now: Instant) -> Option<Instant> {
+    let at = chrono::DateTime::parse_from_rfc3339(value).ok()?;
+    let ahead = (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default();
+    Some(now + ahead)
+}
+
+/// Go-style durations as OpenAI sends them: `20ms`, `1s`, `6m0s`, `1h2m3.5s`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).filter(|&n| n > 0)?;
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        total += number * match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(k, v)| (HeaderName::from_bytes(k.as_bytes()).unwrap(), HeaderValue::from_str(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_duration("1h2m3.5s"), Some(Duration::from_secs_f64(3723.5)));
+        assert_eq!(parse_duration("soon"), None);
+        assert_eq!(parse_duration("5x"), None);
+    }
+
+    #[test]
+    fn test_openai_headers_and_wait() {
+        let state = RateLimitState::from_headers(&headers(&[
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "12s"),
+            ("x-ratelimit-remaining-tokens", "90000"),
+        ]))
+        .unwrap();
+        assert_eq!(state.requests.unwrap().limit, Some(500));
+        assert_eq!(state.tokens.unwrap().remaining, 90_000);
+
+        let wait = state.wait(Instant::now()).unwrap();
+        assert!(wait > Duration::from_secs(11) && wait <= Duration::from_secs(12));
+        assert!(state.summary(Instant::now()).unwrap().starts_with("0 req left · "));
+        assert_eq!(state.summary(Instant::now() + Duration::from_secs(13)), None);
+    }
+
+    #[test]
+    fn test_anthropic_headers() {
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let state = RateLimitState::from_headers(&headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-requests-reset", &reset),
+            ("anthropic-ratelimit-tokens-limit", "40000"),
+            ("anthropic-ratelimit-tokens-remaining", "1000"),
+            ("anthropic-ratelimit-tokens-reset", &reset),
+        ]))
+        .unwrap();
+
+        // Nothing used up yet, but tokens are under 10% of the limit
+        assert_eq!(state.wait(Instant::now()), None);
+        assert_eq!(state.summary(Instant::now()).as_deref().map(|s| s.split(" ·").next().unwrap()), Some("1000 tok left"));
+    }
+
+    #[test]
+    fn test_record_ignores_responses_without_headers() {
+        let limits = RateLimits::default();
+        assert!(RateLimitState::from_headers(&HeaderMap::new()).is_none());
+        limits.record("openai", &headers(&[("x-ratelimit-remaining-requests", "7")]));
+        limits.record("openai", &HeaderMap::new());
+        assert_eq!(limits.get("openai").unwrap().requests.unwrap().remaining, 7);
+        assert!(limits.get("anthropic").is_none());
+    }
+}
@@ -604,7 +604,7 @@ pub struct SessionInfo {
 }
 
 /// Shell-escape a string for use in bash
-fn shell_escape(s: &str) -> String {
+pub(crate) fn shell_escape(s: &str) -> String {
     format!("'{}'", s.replace('\'', "'\\''"))
 }
 
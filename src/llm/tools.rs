@@ -1,8 +1,10 @@
 // Tool execution - some types for future features
 
 use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
 use std::num::NonZeroUsize;
-use std::path::{Path, PathBuf};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -18,7 +20,10 @@ use similar::TextDiff;
 
 use super::types::{ToolDefinition, ToolResult, ToolResultContent, ToolUse};
 use super::shell_session::{ShellSessionPool, SessionError};
+use super::code_search;
+use super::patch;
 use crate::agent::mandrel::MandrelClient;
+use crate::agent::plan::Plan;
 use crate::config::{KeyId, KeyStore};
 
 /// Truncate a string at a safe UTF-8 character boundary.
@@ -93,6 +98,9 @@ pub enum ToolError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    #[error("{0}")]
+    InvalidInput(String),
+
     #[error("Mandrel not configured")]
     MandrelNotConfigured,
 
@@ -105,6 +113,8 @@ pub enum ToolError {
         tool_use_id: String,
         /// Parsed questions for the dialog
         questions: Vec<ParsedQuestion>,
+        /// Seconds before defaults are used (None = `ask_user.default_timeout_secs`)
+        timeout_secs: Option<u64>,
     },
 }
 
@@ -115,6 +125,60 @@ pub struct ParsedQuestion {
     pub question: String,
     pub options: Vec<ParsedOption>,
     pub multi_select: bool,
+    /// Whether the dialog can be submitted without an answer
+    pub required: bool,
+    /// Constraints on a custom ("Other") answer
+    pub validation: Option<AnswerValidation>,
+    /// Answer used when the dialog times out: option labels, or custom text
+    pub default: Vec<String>,
+}
+
+/// Constraints on free-text answers to an ask_user question
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnswerValidation {
+    /// Regex the whole answer must match
+    pub pattern: Option<String>,
+    /// Inclusive bounds; setting either requires a numeric answer
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl AnswerValidation {
+    /// Parse the `validation` object of a question, rejecting invalid regexes
+    fn from_json(value: &serde_json::Value) -> Result<Self, ToolError> {
+        let validation = Self {
+            pattern: value.get("pattern").and_then(|p| p.as_str()).map(str::to_string),
+            min: value.get("min").and_then(|m| m.as_f64()),
+            max: value.get("max").and_then(|m| m.as_f64()),
+        };
+        if let Some(ref pattern) = validation.pattern {
+            regex::Regex::new(pattern)
+                .map_err(|e| ToolError::ParseError(format!("Invalid validation pattern: {}", e)))?;
+        }
+        Ok(validation)
+    }
+
+    /// Check an answer, returning the message to show the user when it fails
+    pub fn check(&self, answer: &str) -> Result<(), String> {
+        if let Some(ref pattern) = self.pattern {
+            let anchored = format!("^(?:{})$", pattern);
+            if !regex::Regex::new(&anchored).is_ok_and(|re| re.is_match(answer)) {
+                return Err(format!("Must match {}", pattern));
+            }
+        }
+        if self.min.is_some() || self.max.is_some() {
+            let number: f64 = answer.trim().parse().map_err(|_| "Must be a number".to_string())?;
+            match (self.min, self.max) {
+                (Some(min), Some(max)) if number < min || number > max => {
+                    return Err(format!("Must be between {} and {}", min, max));
+                }
+                (Some(min), _) if number < min => return Err(format!("Must be at least {}", min)),
+                (_, Some(max)) if number > max => return Err(format!("Must be at most {}", max)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Parsed option for ask_user tool
@@ -275,6 +339,16 @@ impl ToolRegistry {
             allowed_paths: vec!["~/".to_string(), "/tmp/".to_string()],
         });
 
+        // search_code - structured code search, safe, read-only
+        self.policies.insert("search_code".to_string(), ToolPolicy {
+            name: "search_code".to_string(),
+            require_confirmation: false,
+            dangerous_mode_only: false,
+            timeout_secs: 30,
+            max_output_bytes: 262_144,
+            allowed_paths: vec!["~/".to_string(), "/tmp/".to_string()],
+        });
+
         // Glob - file discovery, safe, read-only
         self.policies.insert("glob".to_string(), ToolPolicy {
             name: "glob".to_string(),
@@ -325,6 +399,16 @@ impl ToolRegistry {
             allowed_paths: vec!["~/".to_string(), "/tmp/".to_string()],
         });
 
+        // apply_edit - validated patch/replacement, requires confirmation (modifies files)
+        self.policies.insert("apply_edit".to_string(), ToolPolicy {
+            name: "apply_edit".to_string(),
+            require_confirmation: true,
+            dangerous_mode_only: false,
+            timeout_secs: 30,
+            max_output_bytes: 102_400,
+            allowed_paths: vec!["~/".to_string(), "/tmp/".to_string()],
+        });
+
         // task - spawn sub-agents, no confirmation needed (sub-agents have their own tool restrictions)
         self.policies.insert("task".to_string(), ToolPolicy {
             name: "task".to_string(),
@@ -335,6 +419,16 @@ impl ToolRegistry {
             allowed_paths: vec![], // No path restrictions
         });
 
+        // task_output - collect a background sub-agent's result
+        self.policies.insert("task_output".to_string(), ToolPolicy {
+            name: "task_output".to_string(),
+            require_confirmation: false,
+            dangerous_mode_only: false,
+            timeout_secs: 300,
+            max_output_bytes: 1_048_576,
+            allowed_paths: vec![],
+        });
+
         // ─────────────────────────────────────────────────────────────────────
         // Mandrel Cross-Session Memory Tools
         // ─────────────────────────────────────────────────────────────────────
@@ -463,6 +557,16 @@ impl ToolRegistry {
             allowed_paths: vec![],
         });
 
+        // update_plan - keep the step list shown in the plan panel current
+        self.policies.insert("update_plan".to_string(), ToolPolicy {
+            name: "update_plan".to_string(),
+            require_confirmation: false,
+            dangerous_mode_only: false,
+            timeout_secs: 5,
+            max_output_bytes: 4096,
+            allowed_paths: vec![],
+        });
+
         // ─────────────────────────────────────────────────────────────────────
         // LSP (Language Server Protocol) Tools - semantic code navigation
         // ─────────────────────────────────────────────────────────────────────
@@ -599,7 +703,8 @@ impl ToolRegistry {
                 description: "Read file contents. Supports reading specific line ranges or \
                     multiple non-contiguous spans in a single call for efficiency. \
                     Automatically detects binary files and returns metadata only. \
-                    Line numbers are 1-indexed.".to_string(),
+                    Line numbers are 1-indexed; out-of-range values are clamped to the file. \
+                    The result header reports the total line count so you can page through large files.".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -850,6 +955,40 @@ impl ToolRegistry {
                     "required": ["pattern"]
                 }),
             },
+            ToolDefinition {
+                name: "search_code".to_string(),
+                description: "Search code in the working directory for a regex pattern. Returns structured \
+                    matches (file, line, column, matched line) for precise navigation. Respects .gitignore \
+                    unless include_ignored is set. Results are capped; 'truncated' is true when more exist.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "pattern": {
+                            "type": "string",
+                            "description": "Regex pattern to search for"
+                        },
+                        "path_glob": {
+                            "type": "string",
+                            "description": "Only search files matching this glob (e.g. '*.rs', 'src/**/*.ts')"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Directory or file within the working directory to search (default: working directory)"
+                        },
+                        "max_results": {
+                            "type": "integer",
+                            "default": 100,
+                            "description": "Maximum matches to return (default: 100, max: 1000)"
+                        },
+                        "include_ignored": {
+                            "type": "boolean",
+                            "default": false,
+                            "description": "Also search hidden and .gitignore'd files (default: false)"
+                        }
+                    },
+                    "required": ["pattern"]
+                }),
+            },
             ToolDefinition {
                 name: "glob".to_string(),
                 description: "Find files matching a glob pattern. Returns file paths with metadata. Use to discover files before reading or searching.".to_string(),
@@ -987,6 +1126,35 @@ impl ToolRegistry {
                     "required": ["file_path", "old_string", "new_string"]
                 }),
             },
+            ToolDefinition {
+                name: "apply_edit".to_string(),
+                description: "Apply a targeted edit to an existing file. Pass either a unified diff in 'patch' \
+                    (one file, standard @@ hunks with context lines) or an 'old_string'/'new_string' pair where \
+                    old_string occurs exactly once. The edit is validated against the current file and rejected \
+                    if it doesn't match or is ambiguous. Returns hunks applied and the new file hash.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Path to the file to edit"
+                        },
+                        "patch": {
+                            "type": "string",
+                            "description": "Unified diff for this file (hunks starting with @@)"
+                        },
+                        "old_string": {
+                            "type": "string",
+                            "description": "Exact text to replace; must occur exactly once (used when 'patch' is absent)"
+                        },
+                        "new_string": {
+                            "type": "string",
+                            "description": "Replacement text for old_string"
+                        }
+                    },
+                    "required": ["path"]
+                }),
+            },
             ToolDefinition {
                 name: "task".to_string(),
                 description: "Spawn a sub-agent to handle complex tasks autonomously. \
@@ -1009,12 +1177,32 @@ impl ToolRegistry {
                         "run_in_background": {
                             "type": "boolean",
                             "default": false,
-                            "description": "Run asynchronously and retrieve results later"
+                            "description": "Run asynchronously and retrieve results later with task_output"
                         }
                     },
                     "required": ["prompt"]
                 }),
             },
+            ToolDefinition {
+                name: "task_output".to_string(),
+                description: "Get the result of a background sub-agent started with task. \
+                    Use block=false to check without waiting.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "task_id": {
+                            "type": "string",
+                            "description": "Task ID returned when the background sub-agent started"
+                        },
+                        "block": {
+                            "type": "boolean",
+                            "default": true,
+                            "description": "Wait for the sub-agent to finish"
+                        }
+                    },
+                    "required": ["task_id"]
+                }),
+            },
             // ─────────────────────────────────────────────────────────────────────
             // Mandrel Cross-Session Memory Tools
             // ─────────────────────────────────────────────────────────────────────
@@ -1245,15 +1433,80 @@ impl ToolRegistry {
                                         "type": "boolean",
                                         "description": "Allow multiple selections (default: false)",
                                         "default": false
+                                    },
+                                    "required": {
+                                        "type": "boolean",
+                                        "description": "Whether an answer is needed before submitting (default: true)",
+                                        "default": true
+                                    },
+                                    "default": {
+                                        "description": "Answer used if the user doesn't respond before the timeout: an option label, or an array of labels for multiSelect",
+                                        "anyOf": [
+                                            { "type": "string" },
+                                            { "type": "array", "items": { "type": "string" } }
+                                        ]
+                                    },
+                                    "validation": {
+                                        "type": "object",
+                                        "description": "Constraints on a custom 'Other' answer",
+                                        "properties": {
+                                            "pattern": {
+                                                "type": "string",
+                                                "description": "Regex the whole answer must match"
+                                            },
+                                            "min": {
+                                                "type": "number",
+                                                "description": "Minimum numeric value (answer must be a number)"
+                                            },
+                                            "max": {
+                                                "type": "number",
+                                                "description": "Maximum numeric value (answer must be a number)"
+                                            }
+                                        }
                                     }
                                 },
                                 "required": ["header", "question", "options"]
                             }
+                        },
+                        "timeout_secs": {
+                            "type": "integer",
+                            "description": "Seconds to wait before answering with the defaults (0 = wait indefinitely). Omit to use the user's configured timeout.",
+                            "minimum": 0
                         }
                     },
                     "required": ["questions"]
                 }),
             },
+            ToolDefinition {
+                name: "update_plan".to_string(),
+                description: "Record your plan for a multi-step task so the user can follow progress. Send the full \
+                    list each time: it replaces the previous plan. Mark a step in_progress when you start it and \
+                    completed as soon as it is done; keep at most one step in_progress.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "items": {
+                            "type": "array",
+                            "description": "All plan steps, in order",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "content": {
+                                        "type": "string",
+                                        "description": "What the step does, in a few words"
+                                    },
+                                    "status": {
+                                        "type": "string",
+                                        "enum": ["pending", "in_progress", "completed"]
+                                    }
+                                },
+                                "required": ["content", "status"]
+                            }
+                        }
+                    },
+                    "required": ["items"]
+                }),
+            },
             // ─────────────────────────────────────────────────────────────────────
             // LSP (Language Server Protocol) Tools - semantic code navigation
             // ─────────────────────────────────────────────────────────────────────
@@ -1501,6 +1754,24 @@ impl Default for ToolRegistry {
     }
 }
 
+/// Tools that only read state; dry-run still runs them unless `intercept_reads` is set
+const READ_ONLY_TOOLS: &[&str] = &[
+    "file_read", "list_directory", "grep", "search_code", "glob", "tree", "find_symbol", "ast_search",
+    "project_current", "context_get_recent", "context_search", "task_list", "task_details",
+    "task_progress_summary", "smart_search",
+    "lsp_goto_definition", "lsp_find_references", "lsp_hover", "lsp_document_symbols",
+    "lsp_workspace_symbols", "lsp_implementations", "lsp_call_hierarchy",
+    "web_fetch", "web_search",
+];
+
+/// Dry-run planning: tool calls are described back to the model instead of executed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DryRun {
+    pub enabled: bool,
+    /// Describe read-only tools too, instead of really running them
+    pub intercept_reads: bool,
+}
+
 /// Tool executor handles running tools with sandboxing
 pub struct ToolExecutor {
     registry: ToolRegistry,
@@ -1515,6 +1786,9 @@ pub struct ToolExecutor {
     web_cache: Arc<Mutex<WebFetchCache>>,
     /// Shell session pool for persistent bash sessions
     shell_pool: Arc<Mutex<ShellSessionPool>>,
+    /// Config dir holding the keystore (`--config-dir`); the XDG default when unset
+    config_dir: Option<PathBuf>,
+    dry_run: DryRun,
 }
 
 #[allow(dead_code)]
@@ -1539,9 +1813,21 @@ impl ToolExecutor {
             // 100 entries, 15-minute TTL (900 seconds)
             web_cache: Arc::new(Mutex::new(WebFetchCache::new(100, 900))),
             shell_pool: Arc::new(Mutex::new(ShellSessionPool::new())),
+            config_dir: None,
+            dry_run: DryRun::default(),
         }
     }
 
+    /// Directory relative tool paths resolve against
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// Read tool API keys from the keystore under `dir` (`--config-dir`)
+    pub fn set_config_dir(&mut self, dir: PathBuf) {
+        self.config_dir = Some(dir);
+    }
+
     /// Set the Mandrel client for cross-session memory tools
     pub fn set_mandrel_client(&mut self, client: Arc<RwLock<MandrelClient>>) {
         self.mandrel_client = Some(client);
@@ -1573,6 +1859,23 @@ impl ToolExecutor {
     pub fn set_dangerous_mode(&mut self, enabled: bool) {
         self.registry.set_dangerous_mode(enabled);
     }
+
+    pub fn set_dry_run(&mut self, dry_run: DryRun) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn dry_run(&self) -> DryRun {
+        self.dry_run
+    }
+
+    /// Whether dry-run mode describes `tool` instead of running it.
+    /// ask_user still reaches the user and update_plan still updates the
+    /// plan panel: neither changes anything outside the app.
+    pub fn intercepts(&self, tool: &ToolUse) -> bool {
+        self.dry_run.enabled
+            && !matches!(tool.name.as_str(), "ask_user" | "update_plan")
+            && (self.dry_run.intercept_reads || !READ_ONLY_TOOLS.contains(&tool.name.as_str()))
+    }
     
     /// Get tool definitions for LLM requests
     pub fn tool_definitions_for_llm(&self) -> Vec<ToolDefinition> {
@@ -1597,6 +1900,36 @@ impl ToolExecutor {
         ToolExecutionCheck::Allowed
     }
     
+    /// Reject input the model emitted as invalid JSON, or without the
+    /// arguments the tool's schema requires
+    pub fn validate_input(&self, tool: &ToolUse) -> Result<(), ToolError> {
+        if let Some(e) = tool.input_parse_error() {
+            return Err(ToolError::InvalidInput(format!(
+                "model emitted invalid JSON for tool {}: {}. Retry the call with a complete JSON object.",
+                tool.name, e
+            )));
+        }
+        let Some(definition) = self.registry.get_tool_definitions().into_iter().find(|d| d.name == tool.name) else {
+            return Ok(());
+        };
+        let missing: Vec<&str> = definition.input_schema.get("required")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str())
+            .filter(|key| tool.input.get(key).map_or(true, |v| v.is_null()))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolError::InvalidInput(format!(
+                "tool {} called without required argument(s): {}",
+                tool.name,
+                missing.join(", ")
+            )))
+        }
+    }
+
     fn extract_path(&self, input: &serde_json::Value) -> Option<PathBuf> {
         input.get("path").and_then(|p| p.as_str()).map(PathBuf::from)
     }
@@ -1619,8 +1952,7 @@ impl ToolExecutor {
         };
         
         // Check for path traversal
-        let path_str = resolved.to_string_lossy();
-        if path_str.contains("..") {
+        if resolved.components().any(|c| c == Component::ParentDir) {
             return false;
         }
         
@@ -1647,6 +1979,26 @@ impl ToolExecutor {
         let policy = self.registry.get_policy(&tool.name)
             .ok_or_else(|| ToolError::NotFound(tool.name.clone()))?;
 
+        // Bad input goes back to the model as an error result it can retry from
+        if let Err(e) = self.validate_input(tool) {
+            return Ok(ToolResult {
+                tool_use_id: tool.id.clone(),
+                content: ToolResultContent::Text(e.to_string()),
+                is_error: true,
+            });
+        }
+
+        if self.intercepts(tool) {
+            return Ok(ToolResult {
+                tool_use_id: tool.id.clone(),
+                content: ToolResultContent::Text(format!(
+                    "[dry-run] would execute {} with {}. Nothing was run; continue planning as if it succeeded.",
+                    tool.name, tool.input
+                )),
+                is_error: false,
+            });
+        }
+
         let result = match tool.name.as_str() {
             // File operations
             "file_read" => self.execute_file_read(tool, policy).await,
@@ -1658,11 +2010,13 @@ impl ToolExecutor {
             "file_delete" => self.execute_file_delete(tool, policy).await,
             // Search tools
             "grep" => self.execute_grep(tool, policy).await,
+            "search_code" => self.execute_search_code(tool, policy).await,
             "glob" => self.execute_glob(tool, policy).await,
             "tree" => self.execute_tree(tool, policy).await,
             "find_symbol" => self.execute_find_symbol(tool, policy).await,
             "ast_search" => self.execute_ast_search(tool, policy).await,
             "edit" => self.execute_edit(tool, policy).await,
+            "apply_edit" => self.execute_apply_edit(tool, policy).await,
             // Mandrel cross-session memory tools
             "project_switch" => self.execute_mandrel_project_switch(tool).await,
             "project_current" => self.execute_mandrel_project_current(tool).await,
@@ -1677,6 +2031,9 @@ impl ToolExecutor {
             "smart_search" => self.execute_mandrel_smart_search(tool).await,
             // User interaction tools
             "ask_user" => self.execute_ask_user(tool).await,
+            "update_plan" => Plan::from_tool_input(&tool.input)
+                .map(|plan| plan.summary())
+                .map_err(ToolError::InvalidInput),
             // LSP semantic code navigation tools
             "lsp_goto_definition" => self.execute_lsp_goto_definition(tool).await,
             "lsp_find_references" => self.execute_lsp_find_references(tool).await,
@@ -1764,7 +2121,7 @@ impl ToolExecutor {
                     .unwrap_or(1);
                 let end = span.get("end")
                     .and_then(|v| v.as_i64())
-                    .map(|n| n as usize)
+                    .map(|n| n.max(0) as usize)
                     .unwrap_or(total_lines);
                 spans.push((start, end));
             }
@@ -1776,7 +2133,7 @@ impl ToolExecutor {
                 .unwrap_or(1);
             let end_line = tool.input.get("end_line")
                 .and_then(|v| v.as_i64())
-                .map(|n| n as usize)
+                .map(|n| n.max(0) as usize)
                 .unwrap_or(total_lines);
             spans.push((start_line, end_line));
         }
@@ -1796,8 +2153,7 @@ impl ToolExecutor {
             }
 
             // Convert to 0-indexed and clamp to file bounds
-            let start_idx = (span_start - 1).min(total_lines);
-            let end_idx = span_end.min(total_lines);
+            let (start_idx, end_idx) = Self::clamp_span(span_start, span_end, total_lines);
 
             // Apply max_lines limit
             let available_lines = max_lines - total_lines_read;
@@ -1837,6 +2193,7 @@ impl ToolExecutor {
 
         // Build final response
         let result = serde_json::json!({
+            "header": Self::read_header(path, &span_results, total_lines),
             "path": path,
             "total_lines": total_lines,
             "spans": span_results,
@@ -1859,6 +2216,32 @@ impl ToolExecutor {
         }
     }
 
+    /// Clamp a 1-indexed inclusive span to 0-indexed slice bounds within the file.
+    /// Out-of-range values are pulled back to the file, and an inverted span is empty.
+    fn clamp_span(start: usize, end: usize, total_lines: usize) -> (usize, usize) {
+        let start_idx = start.saturating_sub(1).min(total_lines);
+        let end_idx = end.min(total_lines).max(start_idx);
+        (start_idx, end_idx)
+    }
+
+    /// Summary line for a file_read result, e.g. "src/main.rs: lines 10-20 of 5000"
+    fn read_header(path: &str, spans: &[serde_json::Value], total_lines: usize) -> String {
+        let ranges: Vec<String> = spans
+            .iter()
+            .filter_map(|s| {
+                let start = s.get("start")?.as_u64()?;
+                let end = s.get("end")?.as_u64()?;
+                (end >= start).then(|| format!("{}-{}", start, end))
+            })
+            .collect();
+
+        if ranges.is_empty() {
+            format!("{}: no lines in range ({} total)", path, total_lines)
+        } else {
+            format!("{}: lines {} of {}", path, ranges.join(", "), total_lines)
+        }
+    }
+
     /// Merge overlapping or adjacent spans, returns sorted non-overlapping spans
     fn merge_spans(mut spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
         if spans.is_empty() {
@@ -1974,7 +2357,7 @@ impl ToolExecutor {
             
             while let Some(entry) = entries.next_entry().await? {
                 let file_type = entry.file_type().await?;
-                let name = entry.file_name().to_string_lossy().to_string();
+                let name = display_os_str(&entry.file_name());
                 let suffix = if file_type.is_dir() { "/" } else { "" };
                 result.push(format!("{}{}", name, suffix));
             }
@@ -2470,6 +2853,72 @@ impl ToolExecutor {
         }
     }
 
+    async fn execute_search_code(&self, tool: &ToolUse, policy: &ToolPolicy) -> Result<String, ToolError> {
+        let pattern = tool.input.get("pattern")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| ToolError::ParseError("Missing 'pattern' parameter".to_string()))?;
+
+        let search_path = tool.input.get("path")
+            .and_then(|p| p.as_str())
+            .unwrap_or(".");
+        let resolved = self.resolve_path(search_path);
+
+        if !self.is_path_allowed("search_code", &resolved) || !self.is_within_working_dir(&resolved) {
+            return Err(ToolError::PathNotAllowed(search_path.to_string()));
+        }
+
+        let request = code_search::SearchRequest {
+            pattern: pattern.to_string(),
+            path_glob: tool.input.get("path_glob")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            max_results: tool.input.get("max_results")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(100)
+                .clamp(1, 1000) as usize,
+            include_ignored: tool.input.get("include_ignored")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        };
+
+        let outcome = timeout(
+            Duration::from_secs(policy.timeout_secs),
+            code_search::search(&resolved, &self.working_dir, &request),
+        )
+            .await
+            .map_err(|_| ToolError::Timeout(policy.timeout_secs))?
+            .map_err(ToolError::ExecutionFailed)?;
+
+        let result = serde_json::json!({
+            "pattern": pattern,
+            "matches": outcome.matches,
+            "total_shown": outcome.matches.len(),
+            "truncated": outcome.truncated,
+            "engine": outcome.engine
+        });
+
+        let result_str = serde_json::to_string_pretty(&result)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))?;
+
+        if result_str.len() > policy.max_output_bytes {
+            Ok(format!(
+                "{}...\n\n[TRUNCATED: Output exceeds {} bytes]",
+                truncate_utf8_safe(&result_str, policy.max_output_bytes),
+                policy.max_output_bytes
+            ))
+        } else {
+            Ok(result_str)
+        }
+    }
+
+    /// True if `path` resolves inside the working directory (symlinks and `..` resolved)
+    fn is_within_working_dir(&self, path: &Path) -> bool {
+        let Ok(root) = self.working_dir.canonicalize() else { return false };
+        path.canonicalize()
+            .map(|p| p.starts_with(&root))
+            .unwrap_or(false)
+    }
+
     async fn execute_glob(&self, tool: &ToolUse, policy: &ToolPolicy) -> Result<String, ToolError> {
         let pattern = tool.input.get("pattern")
             .and_then(|p| p.as_str())
@@ -2506,7 +2955,7 @@ impl ToolExecutor {
                 if let Ok(path) = entry {
                     if let Ok(metadata) = std::fs::metadata(&path) {
                         files.push(serde_json::json!({
-                            "path": path.to_string_lossy(),
+                            "path": display_os_str(path.as_os_str()),
                             "size": metadata.len(),
                             "is_dir": metadata.is_dir()
                         }));
@@ -2617,7 +3066,7 @@ impl ToolExecutor {
         for (i, entry) in entries.into_iter().enumerate() {
             let is_last = i == count - 1;
             let connector = if is_last { "└── " } else { "├── " };
-            let name = entry.file_name().to_string_lossy().to_string();
+            let name = display_os_str(&entry.file_name());
             let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
             let suffix = if is_dir { "/" } else { "" };
 
@@ -2933,6 +3382,44 @@ impl ToolExecutor {
         }
     }
 
+    async fn execute_apply_edit(&self, tool: &ToolUse, policy: &ToolPolicy) -> Result<String, ToolError> {
+        let path = tool.input.get("path")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| ToolError::ParseError("Missing 'path' parameter".to_string()))?;
+
+        let resolved = self.resolve_path(path);
+
+        if !self.is_path_allowed("apply_edit", &resolved) {
+            return Err(ToolError::PathNotAllowed(path.to_string()));
+        }
+
+        let read_future = tokio::fs::read_to_string(&resolved);
+        let content = timeout(Duration::from_secs(policy.timeout_secs), read_future)
+            .await
+            .map_err(|_| ToolError::Timeout(policy.timeout_secs))?
+            .map_err(|e| ToolError::IoError(format!("Failed to read file: {}", e)))?;
+
+        // Validate every hunk against the current content before touching the file
+        let outcome = patch::apply_edit_input(&content, &tool.input)
+            .map_err(ToolError::ExecutionFailed)?;
+
+        let write_future = tokio::fs::write(&resolved, &outcome.content);
+        timeout(Duration::from_secs(policy.timeout_secs), write_future)
+            .await
+            .map_err(|_| ToolError::Timeout(policy.timeout_secs))?
+            .map_err(|e| ToolError::IoError(format!("Failed to write file: {}", e)))?;
+
+        let result = serde_json::json!({
+            "path": path,
+            "hunks_applied": outcome.hunks_applied,
+            "hash": patch::content_hash(&outcome.content),
+            "lines": outcome.content.lines().count()
+        });
+
+        serde_json::to_string_pretty(&result)
+            .map_err(|e| ToolError::ExecutionFailed(e.to_string()))
+    }
+
     async fn execute_edit(&self, tool: &ToolUse, policy: &ToolPolicy) -> Result<String, ToolError> {
         let file_path = tool.input.get("file_path")
             .and_then(|p| p.as_str())
@@ -3045,21 +3532,17 @@ impl ToolExecutor {
     }
 
     fn resolve_path(&self, path: &str) -> PathBuf {
-        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
-
-        if let Some(stripped) = path.strip_prefix("~/") {
-            home_dir.join(stripped)
-        } else if path.starts_with('/') {
-            PathBuf::from(path)
-        } else {
-            self.working_dir.join(path)
-        }
+        resolve_tool_path(&self.working_dir, path)
     }
 
     /// Get Brave Search API key from KeyStore or environment variable
     fn get_brave_api_key(&self) -> Result<String, ToolError> {
         // Try KeyStore first
-        if let Ok(keystore) = KeyStore::new() {
+        let keystore = match &self.config_dir {
+            Some(dir) => KeyStore::new_in(dir),
+            None => KeyStore::new(),
+        };
+        if let Ok(keystore) = keystore {
             let key_id = KeyId::Custom("brave_search".to_string());
             if let Ok(Some(secret)) = keystore.get(&key_id) {
                 return Ok(secret.expose().to_string());
@@ -3272,11 +3755,30 @@ impl ToolExecutor {
                 .and_then(|m| m.as_bool())
                 .unwrap_or(false);
 
+            let required = q.get("required")
+                .and_then(|r| r.as_bool())
+                .unwrap_or(true);
+
+            let validation = q.get("validation")
+                .map(AnswerValidation::from_json)
+                .transpose()?;
+
+            let default = match q.get("default") {
+                Some(serde_json::Value::String(s)) => vec![s.clone()],
+                Some(serde_json::Value::Array(items)) => {
+                    items.iter().filter_map(|i| i.as_str()).map(str::to_string).collect()
+                }
+                _ => Vec::new(),
+            };
+
             parsed_questions.push(ParsedQuestion {
                 header,
                 question,
                 options,
                 multi_select,
+                required,
+                validation,
+                default,
             });
         }
 
@@ -3285,6 +3787,7 @@ impl ToolExecutor {
         Err(ToolError::WaitingForUserInput {
             tool_use_id: tool.id.clone(),
             questions: parsed_questions,
+            timeout_secs: tool.input.get("timeout_secs").and_then(|t| t.as_u64()),
         })
     }
 
@@ -3956,6 +4459,15 @@ impl PendingToolUse {
                     .unwrap_or(".");
                 format!("'{}' in {}", pattern, path)
             }
+            "search_code" => {
+                let pattern = self.tool.input.get("pattern")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("<pattern>");
+                match self.tool.input.get("path_glob").and_then(|g| g.as_str()) {
+                    Some(glob) => format!("'{}' in {}", pattern, glob),
+                    None => format!("'{}'", pattern),
+                }
+            }
             "glob" => {
                 self.tool.input.get("pattern")
                     .and_then(|p| p.as_str())
@@ -3980,6 +4492,18 @@ impl PendingToolUse {
                     .unwrap_or(".");
                 format!("'{}' in {}", pattern, path)
             }
+            "apply_edit" => {
+                let path = self.tool.input.get("path")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("<file>");
+                match self.tool.input.get("patch").and_then(|p| p.as_str()) {
+                    Some(patch) => {
+                        let hunks = patch.lines().filter(|l| l.starts_with("@@")).count();
+                        format!("{}: {} hunk{}", path, hunks, if hunks == 1 { "" } else { "s" })
+                    }
+                    None => format!("{}: replace string", path),
+                }
+            }
             "edit" => {
                 let file_path = self.tool.input.get("file_path")
                     .and_then(|p| p.as_str())
@@ -4000,6 +4524,66 @@ impl PendingToolUse {
     }
 }
 
+/// A file name as shown in tool results. Bytes that aren't UTF-8 (valid in
+/// Unix names) are written as `\xNN` instead of U+FFFD, so the name stays
+/// distinct and can be passed back to a tool as-is. A literal backslash is
+/// written as `\\`, so a name containing the text `\xNN` isn't misread.
+fn display_os_str(name: &OsStr) -> String {
+    let mut out = String::new();
+    for chunk in name.as_bytes().utf8_chunks() {
+        out.push_str(&chunk.valid().replace('\\', "\\\\"));
+        for byte in chunk.invalid() {
+            out.push_str(&format!("\\x{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Resolve a tool's `path` input the way the executor does: `~/` against the
+/// home directory, relative paths against `working_dir`
+pub fn resolve_tool_path(working_dir: &Path, path: &str) -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+
+    if let Some(stripped) = path.strip_prefix("~/") {
+        home_dir.join(path_from_input(stripped))
+    } else if path.starts_with('/') {
+        path_from_input(path)
+    } else {
+        working_dir.join(path_from_input(path))
+    }
+}
+
+/// A path from tool input, undoing `display_os_str`: `\xNN` escapes become
+/// the non-UTF-8 bytes they stand for and `\\` a single backslash
+fn path_from_input(path: &str) -> PathBuf {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'\\') {
+            out.push(b'\\');
+            i += 2;
+            continue;
+        }
+        let escaped = (bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'x'))
+            .then(|| bytes.get(i + 2..i + 4))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok())
+            .filter(|byte| *byte >= 0x80);
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    PathBuf::from(OsString::from_vec(out))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4380,6 +4964,243 @@ mod tests {
         assert_eq!(merged, vec![(1, 10), (50, 60), (100, 110)]);
     }
 
+    #[tokio::test]
+    async fn test_invalid_tool_input_returns_error_result() {
+        use crate::llm::manager::ToolAssembler;
+        use crate::llm::types::{BlockType, StreamChunk, StreamDelta};
+
+        let mut assembler = ToolAssembler::new();
+        assembler.process_chunk(&StreamChunk::BlockStart {
+            index: 0,
+            block_type: BlockType::ToolUse,
+            tool_id: Some("t1".to_string()),
+            tool_name: Some("file_read".to_string()),
+        });
+        assembler.process_chunk(&StreamChunk::Delta(StreamDelta::ToolInput {
+            block_index: 0,
+            input_json: r#"{"path": "/etc/hos"#.to_string(),
+        }));
+        let tool = assembler.process_chunk(&StreamChunk::BlockStop { index: 0 }).unwrap();
+        assert!(tool.input_parse_error().is_some());
+
+        let executor = ToolExecutor::new(std::env::temp_dir());
+        let result = executor.execute(&tool).await.unwrap();
+        assert!(result.is_error);
+        let ToolResultContent::Text(text) = result.content else { panic!("expected text result") };
+        assert!(text.starts_with("model emitted invalid JSON for tool file_read:"), "{}", text);
+
+        // Empty input parses, but a tool with required arguments still rejects it
+        let empty = ToolUse { id: "t2".to_string(), name: "file_read".to_string(), input: ToolUse::parse_input("") };
+        assert!(matches!(
+            executor.validate_input(&empty),
+            Err(ToolError::InvalidInput(msg)) if msg.contains("required argument(s): path")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_describes_instead_of_executing() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("out.txt");
+        let mut executor = ToolExecutor::new(temp.path().to_path_buf());
+        executor.set_dry_run(DryRun { enabled: true, intercept_reads: false });
+
+        let write = ToolUse {
+            id: "w".to_string(),
+            name: "file_write".to_string(),
+            input: serde_json::json!({"path": target.to_string_lossy(), "content": "hi"}),
+        };
+        let result = executor.execute(&write).await.unwrap();
+        assert!(!result.is_error);
+        let ToolResultContent::Text(text) = result.content else { panic!("expected text result") };
+        assert!(text.starts_with("[dry-run] would execute file_write with {"), "{}", text);
+        assert!(!target.exists());
+
+        // Read-only tools still run unless reads are intercepted too
+        let read = ToolUse { id: "r".to_string(), name: "file_read".to_string(), input: serde_json::json!({"path": "x"}) };
+        let ask = ToolUse { id: "a".to_string(), name: "ask_user".to_string(), input: serde_json::json!({}) };
+        assert!(executor.intercepts(&write));
+        assert!(!executor.intercepts(&read));
+        assert!(!executor.intercepts(&ask));
+        executor.set_dry_run(DryRun { enabled: true, intercept_reads: true });
+        assert!(executor.intercepts(&read));
+        executor.set_dry_run(DryRun::default());
+        assert!(!executor.intercepts(&write));
+    }
+
+    #[tokio::test]
+    async fn test_apply_edit_writes_and_reports_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn a() {}\nfn b() {}\n").unwrap();
+
+        let executor = ToolExecutor::new(dir.path().to_path_buf());
+        let registry = executor.registry();
+        assert_eq!(registry.can_execute("apply_edit", false), ToolExecutionCheck::RequiresConfirmation);
+        let policy = registry.get_policy("apply_edit").unwrap().clone();
+
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "apply_edit".to_string(),
+            input: serde_json::json!({
+                "path": "lib.rs",
+                "patch": "@@ -2,1 +2,1 @@\n-fn b() {}\n+fn b() -> u8 { 1 }\n"
+            }),
+        };
+        let output = executor.execute_apply_edit(&tool, &policy).await.unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        let written = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(written, "fn a() {}\nfn b() -> u8 { 1 }\n");
+        assert_eq!(result["hunks_applied"], 1);
+        assert_eq!(result["hash"], patch::content_hash(&written));
+    }
+
+    #[tokio::test]
+    async fn test_apply_edit_rejects_ambiguous_string() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "x = 1\nx = 1\n").unwrap();
+
+        let executor = ToolExecutor::new(dir.path().to_path_buf());
+        let policy = executor.registry().get_policy("apply_edit").unwrap().clone();
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "apply_edit".to_string(),
+            input: serde_json::json!({ "path": "lib.rs", "old_string": "x = 1", "new_string": "x = 2" }),
+        };
+
+        assert!(executor.execute_apply_edit(&tool, &policy).await.is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "x = 1\nx = 1\n");
+    }
+
+    #[test]
+    fn test_search_code_registered() {
+        let registry = ToolRegistry::new();
+        assert_eq!(registry.can_execute("search_code", false), ToolExecutionCheck::Allowed);
+
+        let definitions = registry.get_tool_definitions();
+        let def = definitions.iter().find(|d| d.name == "search_code").unwrap();
+        let props = def.input_schema.get("properties").unwrap();
+        assert!(props.get("path_glob").is_some());
+        assert!(props.get("max_results").is_some());
+        assert!(props.get("include_ignored").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_search_code_rejects_paths_outside_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("work")).unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "needle\n").unwrap();
+        std::fs::write(dir.path().join("work/a.txt"), "needle\n").unwrap();
+
+        let executor = ToolExecutor::new(dir.path().join("work"));
+        let policy = executor.registry().get_policy("search_code").unwrap().clone();
+
+        let outside = ToolUse {
+            id: "t1".to_string(),
+            name: "search_code".to_string(),
+            input: serde_json::json!({ "pattern": "needle", "path": dir.path().to_string_lossy() }),
+        };
+        assert!(matches!(
+            executor.execute_search_code(&outside, &policy).await,
+            Err(ToolError::PathNotAllowed(_))
+        ));
+
+        let inside = ToolUse {
+            id: "t2".to_string(),
+            name: "search_code".to_string(),
+            input: serde_json::json!({ "pattern": "needle" }),
+        };
+        let output = executor.execute_search_code(&inside, &policy).await.unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(result["matches"][0]["file"], "a.txt");
+        assert_eq!(result["truncated"], false);
+    }
+
+    #[test]
+    fn test_clamp_span_within_file() {
+        assert_eq!(ToolExecutor::clamp_span(10, 20, 100), (9, 20));
+    }
+
+    #[test]
+    fn test_clamp_span_out_of_range() {
+        // Past the end of the file returns what exists
+        assert_eq!(ToolExecutor::clamp_span(90, 500, 100), (89, 100));
+        // Entirely beyond the file is empty
+        assert_eq!(ToolExecutor::clamp_span(200, 300, 100), (100, 100));
+        // Start of 0 is treated as line 1
+        assert_eq!(ToolExecutor::clamp_span(0, 5, 100), (0, 5));
+    }
+
+    #[test]
+    fn test_clamp_span_inverted_is_empty() {
+        assert_eq!(ToolExecutor::clamp_span(20, 10, 100), (19, 19));
+    }
+
+    #[tokio::test]
+    async fn test_file_read_line_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let content: String = (1..=50).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(dir.path().join("big.txt"), content).unwrap();
+
+        let executor = ToolExecutor::new(dir.path().to_path_buf());
+        let policy = executor.registry().get_policy("file_read").unwrap().clone();
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "file_read".to_string(),
+            input: serde_json::json!({ "path": "big.txt", "start_line": 45, "end_line": 80 }),
+        };
+
+        let output = executor.execute_file_read(&tool, &policy).await.unwrap();
+        let result: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(result["total_lines"], 50);
+        assert_eq!(result["total_lines_read"], 6);
+        assert_eq!(result["header"], "big.txt: lines 45-50 of 50");
+        let text = result["spans"][0]["content"].as_str().unwrap();
+        assert!(text.starts_with("45\tline 45"));
+        assert!(text.ends_with("50\tline 50"));
+    }
+
+    #[tokio::test]
+    async fn test_non_utf8_file_names() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let name = OsStr::from_bytes(b"caf\xe9.txt");
+        std::fs::write(dir.path().join(name), "latin-1 name").unwrap();
+        std::fs::write(dir.path().join("plain.txt"), "").unwrap();
+
+        let executor = ToolExecutor::new(dir.path().to_path_buf());
+        let policy = executor.registry().get_policy("list_directory").unwrap().clone();
+        let tool = ToolUse {
+            id: "t1".to_string(),
+            name: "list_directory".to_string(),
+            input: serde_json::json!({ "path": "." }),
+        };
+        let listing = executor.execute_list_directory(&tool, &policy).await.unwrap();
+        assert_eq!(listing, "caf\\xE9.txt\nplain.txt");
+
+        // The listed name reads the file back
+        let policy = executor.registry().get_policy("file_read").unwrap().clone();
+        let tool = ToolUse {
+            id: "t2".to_string(),
+            name: "file_read".to_string(),
+            input: serde_json::json!({ "path": "caf\\xE9.txt" }),
+        };
+        let output = executor.execute_file_read(&tool, &policy).await.unwrap();
+        assert!(output.contains("latin-1 name"));
+
+        // Escapes of ASCII bytes are left alone
+        assert_eq!(path_from_input("a\\x41"), PathBuf::from("a\\x41"));
+
+        // A name that literally contains `\xE9` round-trips instead of decoding
+        let literal = OsStr::new("caf\\xE9.txt");
+        assert_eq!(display_os_str(literal), "caf\\\\xE9.txt");
+        assert_eq!(path_from_input(&display_os_str(literal)), PathBuf::from(literal));
+        assert_eq!(path_from_input(&display_os_str(name)), PathBuf::from(name));
+    }
+
     #[test]
     fn test_detect_mime_type_images() {
         use std::path::Path;
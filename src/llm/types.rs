@@ -29,9 +29,14 @@ pub struct LLMRequest {
     /// Enable streaming
     pub stream: bool,
 
-    /// Enable extended thinking (Anthropic-specific, ignored by others)
+    /// Request reasoning output from models that support it (Anthropic thinking,
+    /// OpenAI reasoning summaries, Gemini thoughts); ignored elsewhere
     pub thinking: Option<ThinkingConfig>,
 
+    /// Mark the system prompt and tool definitions as cacheable (Anthropic
+    /// prompt caching); ignored by other providers
+    pub prompt_cache: bool,
+
     /// Provider-specific options (escape hatch)
     pub extra: HashMap<String, serde_json::Value>,
 }
@@ -47,6 +52,7 @@ impl Default for LLMRequest {
             temperature: None,
             stream: true,
             thinking: None,
+            prompt_cache: false,
             extra: HashMap::new(),
         }
     }
@@ -139,6 +145,25 @@ pub struct ToolUse {
     pub input: serde_json::Value,
 }
 
+/// Input key marking arguments the model sent as invalid JSON
+const INPUT_PARSE_ERROR_KEY: &str = "_parse_error";
+
+impl ToolUse {
+    /// Parse raw tool-call arguments. Empty input means no arguments; invalid
+    /// JSON is kept as a parse-error marker so the tool can report it back.
+    pub fn parse_input(raw: &str) -> serde_json::Value {
+        if raw.trim().is_empty() {
+            return serde_json::json!({});
+        }
+        serde_json::from_str(raw).unwrap_or_else(|e| serde_json::json!({ INPUT_PARSE_ERROR_KEY: e.to_string() }))
+    }
+
+    /// Parse error recorded by `parse_input`, if the arguments were invalid JSON
+    pub fn input_parse_error(&self) -> Option<&str> {
+        self.input.get(INPUT_PARSE_ERROR_KEY)?.as_str()
+    }
+}
+
 /// Tool result to send back
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResult {
@@ -232,6 +257,10 @@ pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
     pub thinking_tokens: Option<u32>,
+    /// Input tokens written to the prompt cache (not included in `input_tokens`)
+    pub cache_creation_tokens: u32,
+    /// Input tokens served from the prompt cache (not included in `input_tokens`)
+    pub cache_read_tokens: u32,
 }
 
 /// Complete (non-streaming) LLM response
@@ -250,8 +279,8 @@ pub enum LLMError {
     #[error("Authentication failed: {message}")]
     AuthError { message: String },
 
-    #[error("Rate limited: retry after {retry_after_secs}s")]
-    RateLimit { retry_after_secs: u32 },
+    #[error("Rate limited{}", retry_after_secs.map(|s| format!(": retry after {}s", s)).unwrap_or_default())]
+    RateLimit { retry_after_secs: Option<u32> },
 
     #[error("Invalid request: {message}")]
     InvalidRequest { message: String },
@@ -263,7 +292,7 @@ pub enum LLMError {
     ContentFiltered { reason: String },
 
     #[error("Provider error: {status} - {message}")]
-    ProviderError { status: u16, message: String },
+    ProviderError { status: u16, message: String, retry_after_secs: Option<u32> },
 
     #[error("Network error: {message}")]
     NetworkError { message: String },
@@ -277,3 +306,48 @@ pub enum LLMError {
     #[error("Parse error: {message}")]
     ParseError { message: String },
 }
+
+impl LLMError {
+    /// The provider rejected the API key; a fallback provider may still work
+    pub fn is_auth_error(&self) -> bool {
+        matches!(self, LLMError::AuthError { .. })
+    }
+
+    /// Transient failures worth retrying or falling back from (rate limits,
+    /// overload such as Anthropic 529, 5xx, network, timeouts)
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            LLMError::RateLimit { .. }
+            | LLMError::NetworkError { .. }
+            | LLMError::Timeout { .. } => true,
+            LLMError::ProviderError { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Attach the response's `Retry-After` to a rate limit or any other
+    /// retryable provider status (429, 503, Anthropic 529, ...)
+    pub fn with_retry_after(self, retry_after_secs: Option<u32>) -> Self {
+        let Some(secs) = retry_after_secs else {
+            return self;
+        };
+        match self {
+            LLMError::RateLimit { .. } => LLMError::RateLimit { retry_after_secs: Some(secs) },
+            LLMError::ProviderError { status, message, .. } if status == 429 || status >= 500 => {
+                LLMError::ProviderError { status, message, retry_after_secs: Some(secs) }
+            }
+            error => error,
+        }
+    }
+
+    /// How long the server asked us to wait before retrying
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            LLMError::RateLimit { retry_after_secs: Some(secs) }
+            | LLMError::ProviderError { retry_after_secs: Some(secs), .. } => {
+                Some(std::time::Duration::from_secs(*secs as u64))
+            }
+            _ => None,
+        }
+    }
+}
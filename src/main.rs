@@ -4,12 +4,14 @@ mod app;
 mod cli;
 mod components;
 mod config;
+mod crash_report;
 mod error;
 mod event;
 mod input;
 mod llm;
 mod lsp;
 mod pty;
+mod redact;
 mod sirk;
 mod spindles;
 mod streams;
@@ -18,7 +20,7 @@ mod util;
 
 use std::io::Write;
 use std::panic;
-use std::path::PathBuf;
+use std::path::Path;
 
 use color_eyre::eyre::Result;
 use crossterm::{
@@ -37,28 +39,20 @@ use tracing_subscriber::{
 use cli::{Cli, Command, KeysAction};
 use config::{KeyId, KeyStore, SecretString};
 
-/// Get the log directory path (~/.local/share/ridge-control/logs/)
-fn log_dir() -> PathBuf {
-    directories::ProjectDirs::from("com", "ridgetopai", "ridge-control")
-        .map(|dirs| dirs.data_dir().join("logs"))
-        .unwrap_or_else(|| PathBuf::from("/tmp/ridge-control/logs"))
-}
-
 /// Initialize the tracing/logging subsystem
 /// 
 /// Logs to:
-/// - File: ~/.local/share/ridge-control/logs/ridge-control.YYYY-MM-DD.log (daily rotation)
+/// - File: <data dir>/logs/ridge-control.YYYY-MM-DD.log (daily rotation;
+///   ~/.local/share/ridge-control/logs on Linux)
 /// - Stderr: Only on panic/crash (via color-eyre)
-fn init_logging(log_level: &str) -> Result<tracing_appender::non_blocking::WorkerGuard> {
-    let log_path = log_dir();
-    
+fn init_logging(log_level: &str, log_path: &Path) -> Result<tracing_appender::non_blocking::WorkerGuard> {
     // Ensure log directory exists
-    std::fs::create_dir_all(&log_path)?;
+    std::fs::create_dir_all(log_path)?;
     
     // Create a daily rotating file appender
     let file_appender = RollingFileAppender::new(
         Rotation::DAILY,
-        &log_path,
+        log_path,
         "ridge-control.log",
     );
     
@@ -89,15 +83,39 @@ fn init_logging(log_level: &str) -> Result<tracing_appender::non_blocking::Worke
 }
 
 /// Handle CLI subcommands (keys, etc.) without launching the TUI
-fn handle_command(command: &Command) -> Result<()> {
+fn handle_command(command: &Command, cli: &Cli) -> Result<()> {
     match command {
-        Command::Keys { action } => handle_keys_command(action),
+        Command::Keys { action } => handle_keys_command(action, cli.config_dir.as_deref()),
+        Command::CrashReport { output } => handle_crash_report_command(output, cli),
     }
 }
 
+/// Handle crash-report subcommand
+fn handle_crash_report_command(output: &Path, cli: &Cli) -> Result<()> {
+    let config_dir = match &cli.config_dir {
+        Some(dir) => dir.clone(),
+        None => config::default_config_dir()?,
+    };
+    let data_dir = config::resolve_data_dir(cli.data_dir.as_deref(), cli.config_dir.as_deref())?;
+    // The app isn't running, so there is no captured LLM exchange to include
+    let log_dir = config::resolve_log_dir(cli.data_dir.as_deref())?;
+    // Best effort: a missing or locked keystore still leaves the env keys
+    let keystore = KeyStore::new_in(&config_dir).ok();
+    let secrets = crash_report::known_secrets(keystore.as_ref());
+    let report = crash_report::CrashReport::collect(&config_dir, &data_dir, &log_dir, None, &secrets);
+    let path = report.write_to(output)?;
+    println!("✅ Crash report written to {}", path.display());
+    println!("   Review it before sharing; API keys and tokens are redacted.");
+    Ok(())
+}
+
 /// Handle keys subcommand
-fn handle_keys_command(action: &KeysAction) -> Result<()> {
-    let mut keystore = KeyStore::new().map_err(|e| {
+fn handle_keys_command(action: &KeysAction, config_dir: Option<&std::path::Path>) -> Result<()> {
+    let keystore = match config_dir {
+        Some(dir) => KeyStore::new_in(dir),
+        None => KeyStore::new(),
+    };
+    let mut keystore = keystore.map_err(|e| {
         color_eyre::eyre::eyre!("Failed to initialize keystore: {}", e)
     })?;
 
@@ -161,31 +179,36 @@ fn handle_keys_command(action: &KeysAction) -> Result<()> {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Install panic hook FIRST to restore terminal state on panic.
+    // color-eyre installs its own panic hook, so wrap it rather than the
+    // default one; ours has to run first to restore the terminal.
     // This is critical because `panic = "abort"` in release mode means Drop won't run.
+    color_eyre::install()?;
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         // Restore terminal state before panic output
+        app::pty_state::pop_keyboard_enhancement();
         let _ = disable_raw_mode();
         let _ = execute!(std::io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
         let _ = std::io::stdout().flush();
+        // Keep the location and backtrace for `ridge-control crash-report`
+        crash_report::record_panic(panic_info.payload(), panic_info.location());
         // Call the original hook for proper panic reporting
         original_hook(panic_info);
     }));
 
-    color_eyre::install()?;
-
     // Parse CLI arguments
     let cli = Cli::parse_args();
 
     // Handle subcommands (these don't need the TUI)
     if let Some(command) = &cli.command {
-        return handle_command(command);
+        return handle_command(command, &cli);
     }
 
     // Initialize logging FIRST (before anything else can log)
     // Keep guard alive for the entire program lifetime
-    let _log_guard = init_logging(&cli.log_level)?;
+    let data_dir = config::resolve_data_dir(cli.data_dir.as_deref(), cli.config_dir.as_deref())?;
+    let _log_guard = init_logging(&cli.log_level, &config::resolve_log_dir(cli.data_dir.as_deref())?)?;
+    crash_report::set_panic_dir(&data_dir);
 
     tracing::info!("Starting ridge-control v{}", env!("CARGO_PKG_VERSION"));
     tracing::info!("CLI options: {:?}", cli);
@@ -211,6 +234,11 @@ async fn main() -> Result<()> {
             tracing::warn!("Failed to restore session: {}", e);
         }
     }
+
+    // --thread / --new-thread replace the restored conversation
+    if let Err(e) = app.open_startup_thread() {
+        tracing::warn!("Failed to open startup thread: {}", e);
+    }
     
     tracing::info!("App initialized, entering main loop");
     
@@ -44,11 +44,22 @@ impl Position {
 pub struct Selection {
     pub start: Position,
     pub end: Position,
+    /// Rectangular (column) selection instead of linear text flow
+    pub block: bool,
 }
 
 impl Selection {
     pub fn new(start: Position, end: Position) -> Self {
-        Self { start, end }
+        Self { start, end, block: false }
+    }
+
+    pub fn new_block(start: Position, end: Position) -> Self {
+        Self { start, end, block: true }
+    }
+
+    /// Inclusive column range of a block selection
+    pub fn columns(&self) -> (usize, usize) {
+        (self.start.x.min(self.end.x), self.start.x.max(self.end.x))
     }
 
     pub fn normalized(&self) -> (Position, Position) {
@@ -64,6 +75,10 @@ impl Selection {
         if pos.y < start.y || pos.y > end.y {
             return false;
         }
+        if self.block {
+            let (left, right) = self.columns();
+            return pos.x >= left && pos.x <= right;
+        }
         if pos.y == start.y && pos.y == end.y {
             return pos.x >= start.x && pos.x <= end.x;
         }
@@ -135,6 +150,45 @@ struct SavedScreen {
     cursor_visible: bool,
 }
 
+/// Cursor shape requested by the nested application (DECSCUSR)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CursorShape {
+    #[default]
+    Block,
+    Underline,
+    Bar,
+}
+
+/// Cursor shape and blink state set via `CSI Ps SP q`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorStyle {
+    pub shape: CursorShape,
+    pub blink: bool,
+}
+
+impl Default for CursorStyle {
+    fn default() -> Self {
+        Self { shape: CursorShape::Block, blink: true }
+    }
+}
+
+impl CursorStyle {
+    /// Decode a DECSCUSR parameter: 0/1 blinking block, 2 steady block,
+    /// 3/4 underline, 5/6 bar (odd values blink)
+    pub fn from_decscusr(ps: u16) -> Option<Self> {
+        let (shape, blink) = match ps {
+            0 | 1 => (CursorShape::Block, true),
+            2 => (CursorShape::Block, false),
+            3 => (CursorShape::Underline, true),
+            4 => (CursorShape::Underline, false),
+            5 => (CursorShape::Bar, true),
+            6 => (CursorShape::Bar, false),
+            _ => return None,
+        };
+        Some(Self { shape, blink })
+    }
+}
+
 /// Mouse tracking modes enabled by the nested application
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MouseMode {
@@ -167,18 +221,35 @@ struct GridPerformer {
     scrollback: RingBuffer,
     scrollback_size: usize,
     cursor_visible: bool,
+    /// Cursor shape requested via DECSCUSR
+    cursor_style: CursorStyle,
     /// Whether we're currently in alternate screen mode
     alternate_screen: bool,
     /// Saved main screen when in alternate mode
     saved_screen: Option<SavedScreen>,
     /// Mouse tracking modes enabled by nested application
     mouse_mode: MouseMode,
-    /// Saved cursor position (DECSC / CSI s)
-    saved_cursor_x: usize,
-    saved_cursor_y: usize,
+    /// Saved cursor state (DECSC / CSI s / ?1048)
+    saved_cursor: Option<SavedCursor>,
     /// Scroll region top/bottom (DECSTBM, CSI r). 0-indexed, inclusive.
     scroll_top: usize,
     scroll_bottom: usize,
+    /// DECAWM (?7): wrap to the next line after the last column
+    autowrap: bool,
+    /// DECOM (?6): cursor rows are relative to, and confined to, the scroll region
+    origin_mode: bool,
+    /// DECCKM (?1): arrow keys send SS3 (`ESC O A`) instead of CSI sequences
+    application_cursor_keys: bool,
+}
+
+/// Cursor state captured by DECSC and restored by DECRC
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    x: usize,
+    y: usize,
+    style: Style,
+    origin_mode: bool,
+    autowrap: bool,
 }
 
 impl GridPerformer {
@@ -203,6 +274,76 @@ impl GridPerformer {
         }
     }
 
+    /// Scroll the scroll region down one line, inserting a blank line at its top
+    fn scroll_down(&mut self) {
+        if self.scroll_bottom < self.cells.len() {
+            self.cells.remove(self.scroll_bottom);
+            self.cells.insert(self.scroll_top, vec![Cell::empty(); self.cols]);
+        }
+    }
+
+    /// DECSC - save position, attributes and the modes that affect placement
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some(SavedCursor {
+            x: self.cursor_x,
+            y: self.cursor_y,
+            style: self.current_style,
+            origin_mode: self.origin_mode,
+            autowrap: self.autowrap,
+        });
+    }
+
+    /// DECRC - restore the saved cursor, or home with default attributes if none
+    fn restore_cursor(&mut self) {
+        let saved = self.saved_cursor.unwrap_or(SavedCursor {
+            x: 0,
+            y: 0,
+            style: Style::default(),
+            origin_mode: false,
+            autowrap: true,
+        });
+        self.cursor_x = saved.x.min(self.cols.saturating_sub(1));
+        self.cursor_y = saved.y.min(self.rows.saturating_sub(1));
+        self.current_style = saved.style;
+        self.origin_mode = saved.origin_mode;
+        self.autowrap = saved.autowrap;
+    }
+
+    /// Move to a 0-indexed row, relative to the scroll region in origin mode
+    fn set_cursor_row(&mut self, row: usize) {
+        self.cursor_y = if self.origin_mode {
+            (self.scroll_top + row).min(self.scroll_bottom)
+        } else {
+            row.min(self.rows.saturating_sub(1))
+        };
+    }
+
+    /// Home the cursor: top-left of the screen, or of the region in origin mode
+    fn home_cursor(&mut self) {
+        self.cursor_x = 0;
+        self.set_cursor_row(0);
+    }
+
+    /// RIS - back to power-on state; scrollback is kept
+    fn reset(&mut self) {
+        self.exit_alternate_screen();
+        self.clear_screen();
+        self.current_style = Style::default();
+        self.cursor_visible = true;
+        self.cursor_style = CursorStyle::default();
+        self.mouse_mode = MouseMode::default();
+        self.saved_cursor = None;
+        self.scroll_top = 0;
+        self.scroll_bottom = self.rows.saturating_sub(1);
+        self.autowrap = true;
+        self.origin_mode = false;
+        self.application_cursor_keys = false;
+    }
+
+    fn in_scroll_region(&self) -> bool {
+        self.cursor_y >= self.scroll_top && self.cursor_y <= self.scroll_bottom
+    }
+
     /// Enter alternate screen mode - save main screen and clear for TUI
     fn enter_alternate_screen(&mut self) {
         if self.alternate_screen {
@@ -249,13 +390,11 @@ impl GridPerformer {
 
     fn newline(&mut self) {
         self.cursor_x = 0;
-        if self.cursor_y >= self.scroll_bottom {
+        if self.cursor_y == self.scroll_bottom {
             // At bottom of scroll region — scroll up within the region
             self.scroll_up();
-        } else if self.cursor_y + 1 >= self.rows {
-            // At bottom of screen (outside scroll region) — scroll anyway
-            self.scroll_up();
-        } else {
+        } else if self.cursor_y + 1 < self.rows {
+            // Below the region the cursor moves down but never scrolls
             self.cursor_y += 1;
         }
     }
@@ -266,7 +405,12 @@ impl GridPerformer {
 
     fn put_char(&mut self, c: char) {
         if self.cursor_x >= self.cols {
-            self.newline();
+            if self.autowrap {
+                self.newline();
+            } else {
+                // Without autowrap, output keeps overwriting the last column
+                self.cursor_x = self.cols.saturating_sub(1);
+            }
         }
         if self.cursor_y < self.rows && self.cursor_x < self.cols {
             self.cells[self.cursor_y][self.cursor_x] = Cell::new(c, self.current_style);
@@ -308,21 +452,25 @@ impl GridPerformer {
         }
     }
 
+    /// DL - lines below the cursor move up within the scroll region
     fn delete_lines(&mut self, count: usize) {
-        for _ in 0..count {
-            if self.cursor_y < self.rows {
-                self.cells.remove(self.cursor_y);
-                self.cells.push(vec![Cell::empty(); self.cols]);
-            }
+        if !self.in_scroll_region() || self.scroll_bottom >= self.cells.len() {
+            return;
+        }
+        for _ in 0..count.min(self.scroll_bottom + 1 - self.cursor_y) {
+            self.cells.remove(self.cursor_y);
+            self.cells.insert(self.scroll_bottom, vec![Cell::empty(); self.cols]);
         }
     }
 
+    /// IL - lines at and below the cursor move down within the scroll region
     fn insert_lines(&mut self, count: usize) {
-        for _ in 0..count {
-            if self.cursor_y < self.rows {
-                self.cells.pop();
-                self.cells.insert(self.cursor_y, vec![Cell::empty(); self.cols]);
-            }
+        if !self.in_scroll_region() || self.scroll_bottom >= self.cells.len() {
+            return;
+        }
+        for _ in 0..count.min(self.scroll_bottom + 1 - self.cursor_y) {
+            self.cells.remove(self.scroll_bottom);
+            self.cells.insert(self.cursor_y, vec![Cell::empty(); self.cols]);
         }
     }
 
@@ -362,6 +510,19 @@ impl GridPerformer {
     }
 }
 
+/// Escape sequence coverage.
+///
+/// Supported: cursor movement (CUU/CUD/CUF/CUB/CNL/CPL/CHA/VPA/CUP), erase and
+/// insert/delete (ED/EL/ECH/DCH/ICH/IL/DL), SU/SD, SGR, DECSTBM, DECSCUSR,
+/// save/restore cursor (DECSC/DECRC, CSI s/u, ?1048), IND/NEL/RI and RIS.
+/// Private modes: ?1 DECCKM, ?6 DECOM, ?7 DECAWM, ?25 DECTCEM,
+/// ?47/?1047/?1049 alternate screen, ?9/?1000/?1002/?1003/?1006 mouse.
+///
+/// Intentionally unsupported (ignored): ?3 DECCOLM (the grid follows the pane
+/// size), ?5 DECSCNM reverse video, ?12 cursor blink (blink comes from DECSCUSR
+/// and the theme), ?1004 focus events, ?2004 bracketed paste (pastes are always
+/// bracketed), ?2026 synchronized output, DECSLRM left/right margins,
+/// character set designation and OSC sequences.
 impl Perform for GridPerformer {
     fn print(&mut self, c: char) {
         self.put_char(c);
@@ -371,10 +532,8 @@ impl Perform for GridPerformer {
         match byte {
             b'\n' => self.newline(),
             b'\r' => self.carriage_return(),
-            0x08 => {
-                if self.cursor_x > 0 {
-                    self.cursor_x -= 1;
-                }
+            0x08 if self.cursor_x > 0 => {
+                self.cursor_x -= 1;
             }
             0x07 => {}
             0x09 => {
@@ -406,16 +565,24 @@ impl Perform for GridPerformer {
             'H' | 'f' => {
                 let row = params.first().copied().unwrap_or(1).saturating_sub(1) as usize;
                 let col = params.get(1).copied().unwrap_or(1).saturating_sub(1) as usize;
-                self.cursor_y = row.min(self.rows.saturating_sub(1));
+                self.set_cursor_row(row);
                 self.cursor_x = col.min(self.cols.saturating_sub(1));
             }
             'A' => {
+                // CUU stops at the top margin when starting inside the region
                 let n = params.first().copied().unwrap_or(1).max(1) as usize;
-                self.cursor_y = self.cursor_y.saturating_sub(n);
+                let top = if self.cursor_y >= self.scroll_top { self.scroll_top } else { 0 };
+                self.cursor_y = self.cursor_y.saturating_sub(n).max(top);
             }
             'B' | 'e' => {
+                // CUD stops at the bottom margin when starting inside the region
                 let n = params.first().copied().unwrap_or(1).max(1) as usize;
-                self.cursor_y = (self.cursor_y + n).min(self.rows.saturating_sub(1));
+                let bottom = if self.cursor_y <= self.scroll_bottom {
+                    self.scroll_bottom
+                } else {
+                    self.rows.saturating_sub(1)
+                };
+                self.cursor_y = (self.cursor_y + n).min(bottom);
             }
             'C' | 'a' => {
                 let n = params.first().copied().unwrap_or(1).max(1) as usize;
@@ -441,7 +608,7 @@ impl Perform for GridPerformer {
             }
             'd' => {
                 let row = params.first().copied().unwrap_or(1).saturating_sub(1) as usize;
-                self.cursor_y = row.min(self.rows.saturating_sub(1));
+                self.set_cursor_row(row);
             }
             'J' => {
                 let mode = params.first().copied().unwrap_or(0);
@@ -504,10 +671,7 @@ impl Perform for GridPerformer {
             'T' => {
                 let n = params.first().copied().unwrap_or(1).max(1) as usize;
                 for _ in 0..n {
-                    if !self.cells.is_empty() {
-                        self.cells.pop();
-                        self.cells.insert(0, vec![Cell::empty(); self.cols]);
-                    }
+                    self.scroll_down();
                 }
             }
             'm' => {
@@ -621,31 +785,49 @@ impl Perform for GridPerformer {
                 }
             }
             's' if !is_private_mode => {
-                // SCP - Save Cursor Position
-                self.saved_cursor_x = self.cursor_x;
-                self.saved_cursor_y = self.cursor_y;
+                // SCOSC - Save Cursor (same state as DECSC)
+                self.save_cursor();
             }
             'u' if !is_private_mode => {
-                // RCP - Restore Cursor Position
-                self.cursor_x = self.saved_cursor_x.min(self.cols.saturating_sub(1));
-                self.cursor_y = self.saved_cursor_y.min(self.rows.saturating_sub(1));
+                // SCORC - Restore Cursor
+                self.restore_cursor();
+            }
+            'q' if intermediates == [b' '] => {
+                // DECSCUSR - Set Cursor Style; unknown values are ignored
+                if let Some(style) = CursorStyle::from_decscusr(params.first().copied().unwrap_or(0)) {
+                    self.cursor_style = style;
+                }
             }
             'r' if !is_private_mode => {
                 // DECSTBM - Set Scrolling Region
-                let top = params.first().copied().unwrap_or(1).saturating_sub(1) as usize;
-                let bottom = params.get(1).copied().map(|b| (b as usize).saturating_sub(1))
-                    .unwrap_or(self.rows.saturating_sub(1));
-                self.scroll_top = top.min(self.rows.saturating_sub(1));
-                self.scroll_bottom = bottom.min(self.rows.saturating_sub(1));
+                // Missing or zero parameters mean the screen edges
+                let last_row = self.rows.saturating_sub(1);
+                let top = params.first().copied().unwrap_or(0).max(1) as usize - 1;
+                let bottom = match params.get(1).copied().unwrap_or(0) {
+                    0 => last_row,
+                    b => (b as usize - 1).min(last_row),
+                };
+                // A region must span at least two lines; otherwise it is ignored
+                if top >= bottom {
+                    return;
+                }
+                self.scroll_top = top;
+                self.scroll_bottom = bottom;
                 // DECSTBM also homes the cursor
-                self.cursor_x = 0;
-                self.cursor_y = 0;
+                self.home_cursor();
             }
             // DECSET - Set private mode
             'h' if is_private_mode => {
                 for &param in &params {
                     match param {
+                        1 => self.application_cursor_keys = true,
+                        6 => {
+                            self.origin_mode = true;
+                            self.home_cursor();
+                        }
+                        7 => self.autowrap = true,
                         25 => self.cursor_visible = true, // Show cursor
+                        1048 => self.save_cursor(),
                         // Alternate screen modes - all treated similarly
                         // 1049: Save cursor + switch to alternate + clear (most common, used by vim, less, etc)
                         // 1047: Switch to alternate screen
@@ -680,7 +862,14 @@ impl Perform for GridPerformer {
             'l' if is_private_mode => {
                 for &param in &params {
                     match param {
+                        1 => self.application_cursor_keys = false,
+                        6 => {
+                            self.origin_mode = false;
+                            self.home_cursor();
+                        }
+                        7 => self.autowrap = false,
                         25 => self.cursor_visible = false, // Hide cursor
+                        1048 => self.restore_cursor(),
                         // Exit alternate screen - restore main screen
                         47 | 1047 | 1049 => self.exit_alternate_screen(),
                         // Mouse tracking modes - disable
@@ -697,26 +886,40 @@ impl Perform for GridPerformer {
         }
     }
 
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, byte: u8) {
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        // Charset designations (ESC ( B) and DEC line attributes (ESC # 8)
+        // share final bytes with the sequences below
+        if !intermediates.is_empty() {
+            return;
+        }
         match byte {
             b'7' => {
                 // DECSC - Save Cursor
-                self.saved_cursor_x = self.cursor_x;
-                self.saved_cursor_y = self.cursor_y;
+                self.save_cursor();
             }
             b'8' => {
                 // DECRC - Restore Cursor
-                self.cursor_x = self.saved_cursor_x.min(self.cols.saturating_sub(1));
-                self.cursor_y = self.saved_cursor_y.min(self.rows.saturating_sub(1));
+                self.restore_cursor();
+            }
+            b'D' => {
+                // IND - Index: line feed without carriage return
+                let x = self.cursor_x;
+                self.newline();
+                self.cursor_x = x;
+            }
+            b'E' => {
+                // NEL - Next Line
+                self.newline();
+            }
+            b'c' => {
+                // RIS - Reset to Initial State
+                self.reset();
             }
             b'M' => {
                 // RI - Reverse Index: move cursor up, scroll down if at top of scroll region
                 if self.cursor_y == self.scroll_top {
                     // At top of scroll region — scroll down within the region
-                    if self.scroll_bottom < self.cells.len() {
-                        self.cells.remove(self.scroll_bottom);
-                        self.cells.insert(self.scroll_top, vec![Cell::empty(); self.cols]);
-                    }
+                    self.scroll_down();
                 } else if self.cursor_y > 0 {
                     self.cursor_y -= 1;
                 }
@@ -751,13 +954,16 @@ impl Grid {
                 scrollback: RingBuffer::new(scrollback_size),
                 scrollback_size,
                 cursor_visible: true,
+                cursor_style: CursorStyle::default(),
                 alternate_screen: false,
                 saved_screen: None,
                 mouse_mode: MouseMode::default(),
-                saved_cursor_x: 0,
-                saved_cursor_y: 0,
+                saved_cursor: None,
                 scroll_top: 0,
                 scroll_bottom: rows.saturating_sub(1),
+                autowrap: true,
+                origin_mode: false,
+                application_cursor_keys: false,
             },
             parser: Parser::new(),
             scroll_offset: 0,
@@ -790,6 +996,8 @@ impl Grid {
         }
     }
 
+    /// Feed raw PTY bytes. Chunks may end mid-character; the vte parser keeps the
+    /// incomplete UTF-8 sequence and finishes it on the next call, so never decode here.
     pub fn process(&mut self, data: &[u8]) {
         // NOTE: Previously reset scroll_offset to 0 on every output, preventing
         // users from scrolling up while Claude Code or other tools were responding.
@@ -798,6 +1006,15 @@ impl Grid {
         self.parser.advance(&mut self.performer, data);
     }
 
+    /// Full reset (as if RIS was received) with a fresh parser, so a program that
+    /// died mid-sequence can't leave the parser stuck. Scrollback is kept.
+    pub fn reset(&mut self) {
+        self.parser = Parser::new();
+        self.performer.reset();
+        self.scroll_offset = 0;
+        self.selection = None;
+    }
+
     pub fn cells(&self) -> &Vec<Vec<Cell>> {
         &self.performer.cells
     }
@@ -810,6 +1027,16 @@ impl Grid {
         self.performer.cursor_visible
     }
 
+    /// Cursor shape requested by the nested application
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.performer.cursor_style
+    }
+
+    /// Whether the nested application enabled DECCKM application cursor keys
+    pub fn application_cursor_keys(&self) -> bool {
+        self.performer.application_cursor_keys
+    }
+
     pub fn size(&self) -> (usize, usize) {
         (self.performer.cols, self.performer.rows)
     }
@@ -895,10 +1122,15 @@ impl Grid {
         }
     }
 
-    pub fn start_selection(&mut self, x: usize, visible_y: usize) {
+    /// Start a selection at a visible position; `block` selects a rectangle
+    pub fn start_selection(&mut self, x: usize, visible_y: usize, block: bool) {
         let absolute_y = self.visible_to_absolute(visible_y);
         let pos = Position::new(x, absolute_y);
-        self.selection = Some(Selection::new(pos, pos));
+        self.selection = Some(if block {
+            Selection::new_block(pos, pos)
+        } else {
+            Selection::new(pos, pos)
+        });
     }
 
     pub fn update_selection(&mut self, x: usize, visible_y: usize) {
@@ -940,6 +1172,21 @@ impl Grid {
         let scrollback_len = self.performer.scrollback.len();
         let max_absolute = scrollback_len + self.performer.rows;
 
+        // Block mode: the same column slice from every row, trailing blanks trimmed
+        if sel.block {
+            let (left, right) = sel.columns();
+            let rows: Vec<String> = (start.y..=end.y.min(max_absolute.saturating_sub(1)))
+                .filter_map(|abs_y| self.get_line_absolute(abs_y))
+                .map(|line| {
+                    let row: String = line.iter().take(right + 1).skip(left).map(|cell| cell.c).collect();
+                    row.trim_end().to_string()
+                })
+                .collect();
+            let joined = rows.join("\n");
+            let trimmed = joined.trim_end();
+            return if trimmed.is_empty() { None } else { Some(trimmed.to_string()) };
+        }
+
         for abs_y in start.y..=end.y.min(max_absolute.saturating_sub(1)) {
             let line = if let Some(line) = self.get_line_absolute(abs_y) {
                 line
@@ -1045,4 +1292,195 @@ mod tests {
         grid.scroll_to_bottom();
         assert_eq!(grid.scroll_offset(), 0);
     }
+
+    #[test]
+    fn test_grid_reset_recovers_wedged_state() {
+        let mut grid = Grid::with_scrollback(10, 3, 100);
+        for i in 0..5 {
+            grid.process(format!("Line {}\n", i).as_bytes());
+        }
+        let scrollback = grid.scrollback_len();
+        // Alternate screen, hidden cursor, mouse tracking, then an unterminated CSI
+        grid.process(b"\x1b[?1049h\x1b[?25l\x1b[?1000hjunk\x1b[3");
+        grid.scroll_up(1);
+
+        grid.reset();
+        assert!(!grid.is_alternate_screen());
+        assert!(grid.cursor_visible());
+        assert!(!grid.mouse_mode().any_enabled());
+        assert_eq!(grid.cursor(), (0, 0));
+        assert_eq!(grid.scroll_offset(), 0);
+        assert_eq!(grid.scrollback_len(), scrollback);
+
+        // The pending CSI was dropped: "A" prints instead of finishing CSI 3A
+        grid.process(b"A");
+        assert_eq!(grid.cells()[0][0].c, 'A');
+    }
+
+    #[test]
+    fn test_utf8_split_across_chunks() {
+        let mut grid = Grid::new(10, 3);
+        let bytes = "€!".as_bytes();
+        assert_eq!(bytes.len(), 4);
+
+        // A 3-byte character split 1 + 2 across reads must still render as one glyph
+        grid.process(&bytes[..1]);
+        grid.process(&bytes[1..]);
+
+        assert_eq!(grid.cells()[0][0].c, '€');
+        assert_eq!(grid.cells()[0][1].c, '!');
+    }
+
+    #[test]
+    fn test_block_selection_contains() {
+        let sel = Selection::new_block(Position::new(5, 1), Position::new(2, 3));
+        assert!(sel.contains(Position::new(2, 2)));
+        assert!(sel.contains(Position::new(5, 3)));
+        // Linear selection would include these; a block does not
+        assert!(!sel.contains(Position::new(6, 2)));
+        assert!(!sel.contains(Position::new(0, 2)));
+        assert!(!sel.contains(Position::new(3, 0)));
+    }
+
+    #[test]
+    fn test_block_selection_text() {
+        let mut grid = Grid::new(20, 5);
+        grid.process(b"name   size\r\nalpha  1\r\nb\r\nbeta   4096 kb\r\n");
+
+        // Drag from the bottom-right back to the top-left of the size column
+        grid.start_selection(10, 3, true);
+        grid.update_selection(7, 0);
+        assert_eq!(grid.get_selected_text().as_deref(), Some("size\n1\n\n4096"));
+
+        // Same drag without the modifier stays linear
+        grid.start_selection(7, 0, false);
+        grid.update_selection(10, 3);
+        let linear = grid.get_selected_text().unwrap();
+        assert!(linear.starts_with("size"));
+        assert!(linear.contains("alpha  1"));
+        assert!(linear.ends_with("beta   4096"));
+    }
+
+    fn row_text(grid: &Grid, row: usize) -> String {
+        grid.cells()[row].iter().map(|c| c.c).collect::<String>().trim_end().to_string()
+    }
+
+    #[test]
+    fn test_scroll_region_line_feeds() {
+        let mut grid = Grid::new(10, 5);
+        grid.process(b"header\r\n\r\n\r\n\r\nstatus");
+
+        // Region rows 2-4 (1-indexed), then fill it past its bottom margin
+        grid.process(b"\x1b[2;4r\x1b[2;1Ha\r\nb\r\nc\r\nd\r\ne");
+
+        assert_eq!(row_text(&grid, 0), "header");
+        assert_eq!(row_text(&grid, 1), "c");
+        assert_eq!(row_text(&grid, 2), "d");
+        assert_eq!(row_text(&grid, 3), "e");
+        assert_eq!(row_text(&grid, 4), "status");
+        // Lines scrolled out of a sub-region never reach scrollback
+        assert_eq!(grid.scrollback_len(), 0);
+    }
+
+    #[test]
+    fn test_scroll_region_keeps_outside_lines() {
+        let mut grid = Grid::new(10, 5);
+        grid.process(b"top\r\n1\r\n2\r\n3\r\nbottom\x1b[2;4r");
+
+        // Scroll down (SD) and reverse index only move lines inside the region
+        grid.process(b"\x1b[T");
+        assert_eq!(row_text(&grid, 0), "top");
+        assert_eq!(row_text(&grid, 1), "");
+        assert_eq!(row_text(&grid, 2), "1");
+        assert_eq!(row_text(&grid, 3), "2");
+        assert_eq!(row_text(&grid, 4), "bottom");
+
+        // Delete line at the region top pulls the region up, leaving margins alone
+        grid.process(b"\x1b[2;1H\x1b[M");
+        assert_eq!(row_text(&grid, 1), "1");
+        assert_eq!(row_text(&grid, 3), "");
+        assert_eq!(row_text(&grid, 4), "bottom");
+
+        // A line feed on the last row, below the region, does not scroll it
+        grid.process(b"\x1b[5;1H\n");
+        assert_eq!(row_text(&grid, 0), "top");
+        assert_eq!(row_text(&grid, 1), "1");
+        assert_eq!(row_text(&grid, 4), "bottom");
+
+        // Resetting the region restores full-screen scrolling
+        grid.process(b"\x1b[r\x1b[5;1H\n");
+        assert_eq!(row_text(&grid, 0), "1");
+        assert_eq!(grid.scrollback_len(), 1);
+    }
+
+    #[test]
+    fn test_decscusr_cursor_style() {
+        let mut grid = Grid::new(10, 3);
+        assert_eq!(grid.cursor_style(), CursorStyle::default());
+
+        // vim insert mode: steady bar
+        grid.process(b"\x1b[6 q");
+        assert_eq!(grid.cursor_style(), CursorStyle { shape: CursorShape::Bar, blink: false });
+
+        grid.process(b"\x1b[3 q");
+        assert_eq!(grid.cursor_style(), CursorStyle { shape: CursorShape::Underline, blink: true });
+
+        // Unknown values leave the style alone; 0 resets to a blinking block
+        grid.process(b"\x1b[9 q");
+        assert_eq!(grid.cursor_style().shape, CursorShape::Underline);
+        grid.process(b"\x1b[0 q");
+        assert_eq!(grid.cursor_style(), CursorStyle::default());
+
+        grid.process(b"\x1b[?25l");
+        assert!(!grid.cursor_visible());
+        grid.process(b"\x1b[?25h");
+        assert!(grid.cursor_visible());
+    }
+
+    #[test]
+    fn test_cursor_save_restore_and_private_modes() {
+        // (description, input, expected cursor (x, y))
+        let cases: &[(&str, &[u8], (usize, usize))] = &[
+            ("DECSC/DECRC", b"\x1b[3;4H\x1b7\x1b[1;1H\x1b8", (3, 2)),
+            ("CSI s/u", b"\x1b[2;5H\x1b[s\x1b[5;1H\x1b[u", (4, 1)),
+            ("?1048 save/restore", b"\x1b[4;2H\x1b[?1048h\x1b[H\x1b[?1048l", (1, 3)),
+            ("restore without save homes", b"\x1b[3;3H\x1b8", (0, 0)),
+            ("DECSC survives DECALN-like ESC # 8", b"\x1b[2;2H\x1b7\x1b[4;4H\x1b#8", (3, 3)),
+            ("autowrap on wraps", b"\x1b[1;9Habc", (1, 1)),
+            ("autowrap off pins last column", b"\x1b[?7l\x1b[1;9Habc", (10, 0)),
+            ("origin mode homes to region", b"\x1b[2;4r\x1b[?6h", (0, 1)),
+            ("origin mode CUP is region-relative", b"\x1b[2;4r\x1b[?6h\x1b[2;3H", (2, 2)),
+            ("origin mode CUP clamps to region", b"\x1b[2;4r\x1b[?6h\x1b[9;1H", (0, 3)),
+            ("origin mode off homes to screen", b"\x1b[2;4r\x1b[?6h\x1b[?6l", (0, 0)),
+            ("CUU stops at top margin", b"\x1b[2;4r\x1b[3;1H\x1b[9A", (0, 1)),
+            ("CUD stops at bottom margin", b"\x1b[2;4r\x1b[3;1H\x1b[9B", (0, 3)),
+            ("CUD below region reaches screen bottom", b"\x1b[2;3r\x1b[4;1H\x1b[9B", (0, 4)),
+            ("IND keeps column", b"\x1b[1;5H\x1bD", (4, 1)),
+            ("NEL returns to column 0", b"\x1b[1;5H\x1bE", (0, 1)),
+            ("RIS homes cursor", b"\x1b[3;3H\x1bc", (0, 0)),
+        ];
+
+        for (name, input, expected) in cases {
+            let mut grid = Grid::new(10, 5);
+            grid.process(input);
+            assert_eq!(grid.cursor(), *expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_saved_cursor_restores_attributes_and_modes() {
+        let mut grid = Grid::new(10, 5);
+        grid.process(b"\x1b[1m\x1b[?7l\x1b7\x1b[0m\x1b[?7h\x1b8X");
+        assert!(grid.cells()[0][0].style.add_modifier.contains(ratatui::style::Modifier::BOLD));
+        // Autowrap came back disabled with the saved cursor
+        grid.process(b"\x1b[1;10Hyz");
+        assert_eq!(grid.cursor(), (10, 0));
+        assert_eq!(grid.cells()[0][9].c, 'z');
+
+        grid.process(b"\x1b[?1h");
+        assert!(grid.application_cursor_keys());
+        grid.process(b"\x1bc");
+        assert!(!grid.application_cursor_keys());
+        assert_eq!(grid.cells()[0][0].c, ' ');
+    }
 }
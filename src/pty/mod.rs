@@ -4,7 +4,7 @@
 
 pub mod grid;
 
-pub use grid::MouseMode;
+pub use grid::{CursorShape, CursorStyle, MouseMode};
 
 use std::io::{self, Read, Write};
 use std::os::fd::{AsRawFd, RawFd};
@@ -0,0 +1,60 @@
+//! Secret redaction shared by request logging, the LLM debug capture and crash bundles
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+pub const REDACTED: &str = "<redacted>";
+
+/// Fragments that mark a header, JSON key or config key as holding a secret;
+/// matched case-insensitively anywhere in the name
+const SECRET_NAME_PARTS: [&str; 9] = [
+    "api_key", "apikey", "api-key", "-key", "auth", "cookie", "token", "secret", "password",
+];
+
+/// Provider key formats that can turn up in prompts, tool output or logs
+static SECRET_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(sk-[A-Za-z0-9_-]{16,}|gsk_[A-Za-z0-9]{16,}|xai-[A-Za-z0-9]{16,}|AIza[0-9A-Za-z_-]{30,}|Bearer\s+[A-Za-z0-9._~+/=-]{8,})")
+        .expect("valid secret pattern")
+});
+
+/// Whether a value stored under `name` must never be shown
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_NAME_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Replace key-shaped strings and any of `known_secrets` with `<redacted>`
+pub fn redact_text(text: &str, known_secrets: &[&str]) -> String {
+    let mut text = SECRET_PATTERN.replace_all(text, REDACTED).into_owned();
+    for secret in known_secrets.iter().filter(|s| !s.is_empty()) {
+        text = text.replace(secret, REDACTED);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_names() {
+        for name in [
+            "Authorization", "proxy-authorization", "Cookie", "x-api-key", "api-key", "x-goog-api-key",
+            "X-Gateway-Token", "api_key", "apiKey", "access_token", "refresh_token", "client_secret", "password",
+        ] {
+            assert!(is_secret_name(name), "{} should be secret", name);
+        }
+        for name in ["x-team", "content-type", "chat_send_key", "model"] {
+            assert!(!is_secret_name(name), "{} should not be secret", name);
+        }
+    }
+
+    #[test]
+    fn test_redact_text() {
+        let text = "key sk-abcdefghijklmnopqrstuv, header Bearer abcdefgh12345, mine hunter2";
+        assert_eq!(
+            redact_text(text, &["hunter2", ""]),
+            "key <redacted>, header <redacted>, mine <redacted>"
+        );
+    }
+}
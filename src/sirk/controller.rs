@@ -217,13 +217,13 @@ impl ForgeController {
         let stdout = child
             .stdout
             .take()
-            .ok_or_else(|| ForgeControllerError::StdoutUnavailable)?;
+            .ok_or(ForgeControllerError::StdoutUnavailable)?;
 
         // Take stderr for logging
         let stderr = child
             .stderr
             .take()
-            .ok_or_else(|| ForgeControllerError::StderrUnavailable)?;
+            .ok_or(ForgeControllerError::StderrUnavailable)?;
 
         // Store the child process
         self.child = Some(child);
@@ -464,13 +464,11 @@ mod tests {
     #[test]
     fn test_forge_connection_state_variants() {
         // Ensure all variants are defined
-        let states = vec![
-            ForgeConnectionState::Disconnected,
+        let states = [ForgeConnectionState::Disconnected,
             ForgeConnectionState::Connecting,
             ForgeConnectionState::Connected,
             ForgeConnectionState::Disconnecting,
-            ForgeConnectionState::Failed,
-        ];
+            ForgeConnectionState::Failed];
         assert_eq!(states.len(), 5);
     }
 
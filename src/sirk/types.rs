@@ -232,9 +232,11 @@ mod tests {
 
     #[test]
     fn test_forge_config_with_resume() {
-        let mut config = ForgeConfig::default();
-        config.run_name = "test-run".to_string();
-        config.resume = true;
+        let config = ForgeConfig {
+            run_name: "test-run".to_string(),
+            resume: true,
+            ..Default::default()
+        };
         let json = serde_json::to_string(&config).unwrap();
         assert!(json.contains("\"resume\":true"));
     }
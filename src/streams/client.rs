@@ -2,12 +2,16 @@
 
 #![allow(dead_code)]
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::net::{TcpStream, UnixStream};
 use tokio::sync::mpsc;
-use futures::{SinkExt, StreamExt};
+use tokio::task::{AbortHandle, JoinHandle};
+use eventsource_stream::{Event as SseEvent, Eventsource};
+use futures::{SinkExt, Stream, StreamExt};
 use tokio_tungstenite::{connect_async, tungstenite::Message as WsMessage};
 
 use crate::streams::config::{ConnectionState, StreamDefinition, StreamProtocol};
@@ -136,12 +140,25 @@ impl ConnectionHealth {
     }
 }
 
+/// Resume point of an SSE stream, updated by the running connection and
+/// read back when reconnecting
+#[derive(Debug, Default)]
+pub struct SseSession {
+    /// Sent as `Last-Event-ID` on reconnect
+    pub last_event_id: Option<String>,
+    /// Server-suggested reconnection delay (`retry:` field)
+    pub retry_ms: Option<u64>,
+}
+
 #[derive(Clone)]
 pub struct StreamClient {
     definition: StreamDefinition,
     state: ConnectionState,
     buffer: Vec<StreamData>,
     health: ConnectionHealth,
+    sse: Arc<Mutex<SseSession>>,
+    /// Connect/read task holding the socket, aborted on disconnect
+    task: Option<AbortHandle>,
 }
 
 impl StreamClient {
@@ -151,6 +168,21 @@ impl StreamClient {
             state: ConnectionState::Disconnected,
             buffer: Vec::with_capacity(1000),
             health: ConnectionHealth::default(),
+            sse: Arc::default(),
+            task: None,
+        }
+    }
+
+    /// Track the task that owns this stream's connection, stopping any previous one
+    fn set_task(&mut self, handle: JoinHandle<()>) {
+        self.stop_task();
+        self.task = Some(handle.abort_handle());
+    }
+
+    /// Abort the connection task, closing its socket
+    fn stop_task(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
         }
     }
 
@@ -215,9 +247,17 @@ impl StreamClient {
         self.definition.reconnect
     }
 
-    /// Get reconnect delay from definition
+    /// Get reconnect delay: the server's SSE `retry:` if it sent one, else the definition's
     pub fn reconnect_delay_ms(&self) -> u64 {
-        self.definition.reconnect_delay_ms
+        self.sse
+            .lock()
+            .ok()
+            .and_then(|session| session.retry_ms)
+            .unwrap_or(self.definition.reconnect_delay_ms)
+    }
+
+    pub fn sse_session(&self) -> Arc<Mutex<SseSession>> {
+        self.sse.clone()
     }
 }
 
@@ -227,10 +267,37 @@ impl Default for StreamManager {
     }
 }
 
+/// Connection slot usage, shown in the streams menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    pub active: usize,
+    pub limit: Option<usize>,
+    pub queued: usize,
+}
+
+impl std::fmt::Display for PoolStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            Some(limit) => write!(f, "{}/{}", self.active, limit)?,
+            None => write!(f, "{} active", self.active)?,
+        }
+        if self.queued > 0 {
+            write!(f, " · {} queued", self.queued)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct StreamManager {
     clients: Vec<StreamClient>,
     event_tx: mpsc::UnboundedSender<StreamEvent>,
     event_rx: Option<mpsc::UnboundedReceiver<StreamEvent>>,
+    /// Maximum streams holding a connection slot at once (None = unlimited)
+    max_concurrent: Option<usize>,
+    /// Stream ids waiting for a free slot, in request order
+    queue: VecDeque<String>,
+    /// Client for SSE streams, trusting llm.toml's `ca_bundle`
+    http_client: reqwest::Client,
 }
 
 impl StreamManager {
@@ -240,9 +307,21 @@ impl StreamManager {
             clients: Vec::new(),
             event_tx,
             event_rx: Some(event_rx),
+            max_concurrent: None,
+            queue: VecDeque::new(),
+            http_client: reqwest::Client::new(),
         }
     }
 
+    /// Trust the certificates in `ca_bundle` (llm.toml) for SSE streams.
+    /// On error the previous client is kept.
+    pub fn set_ca_bundle(&mut self, ca_bundle: Option<&std::path::Path>) -> Result<(), String> {
+        self.http_client = crate::llm::http::client_builder(ca_bundle)?
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        Ok(())
+    }
+
     pub fn take_event_rx(&mut self) -> Option<mpsc::UnboundedReceiver<StreamEvent>> {
         self.event_rx.take()
     }
@@ -252,12 +331,45 @@ impl StreamManager {
     }
 
     pub fn load_streams(&mut self, config: &super::config::StreamsConfig) {
+        for client in &mut self.clients {
+            client.stop_task();
+        }
         self.clients.clear();
+        self.queue.clear();
+        // A limit of 0 would queue every connect forever; treat it as unlimited
+        self.max_concurrent = config.max_concurrent_connections.filter(|&n| n > 0);
         for def in &config.streams {
             self.clients.push(StreamClient::new(def.clone()));
         }
     }
 
+    /// Current connection slot usage
+    pub fn pool_status(&self) -> PoolStatus {
+        PoolStatus {
+            active: self.clients.iter().filter(|c| c.state().holds_slot()).count(),
+            limit: self.max_concurrent,
+            queued: self.queue.len(),
+        }
+    }
+
+    fn has_free_slot(&self) -> bool {
+        let status = self.pool_status();
+        status.limit.map_or(true, |limit| status.active < limit)
+    }
+
+    /// Connect queued streams while slots are free. Call after a stream
+    /// gives up its slot for good (disconnect, failure without reconnect).
+    pub fn connect_queued(&mut self) {
+        while self.has_free_slot() {
+            let Some(id) = self.queue.pop_front() else {
+                break;
+            };
+            if self.get_client(&id).map(|c| c.state()) == Some(ConnectionState::Queued) {
+                self.start_connection(&id);
+            }
+        }
+    }
+
     pub fn clients(&self) -> &[StreamClient] {
         &self.clients
     }
@@ -287,56 +399,68 @@ impl StreamManager {
     }
 
     fn connect_internal(&mut self, id: &str, is_reconnect: bool) {
-        if let Some(client) = self.get_client_mut(id) {
-            if matches!(client.state(), ConnectionState::Connected | ConnectionState::Connecting) {
-                return;
-            }
-            
-            // Don't reconnect if already reconnecting from elsewhere
-            if is_reconnect && matches!(client.state(), ConnectionState::Reconnecting { .. }) {
-                return;
+        let Some(client) = self.get_client(id) else {
+            return;
+        };
+        if matches!(client.state(), ConnectionState::Connected | ConnectionState::Connecting | ConnectionState::Queued) {
+            return;
+        }
+
+        // Don't reconnect if already reconnecting from elsewhere
+        if is_reconnect && matches!(client.state(), ConnectionState::Reconnecting { .. }) {
+            return;
+        }
+
+        if !self.has_free_slot() {
+            if let Some(client) = self.get_client_mut(id) {
+                client.set_state(ConnectionState::Queued);
             }
+            self.queue.push_back(id.to_string());
+            return;
+        }
+        self.start_connection(id);
+    }
 
+    fn start_connection(&mut self, id: &str) {
+        let event_tx = self.event_tx.clone();
+        let http_client = self.http_client.clone();
+        if let Some(client) = self.get_client_mut(id) {
             client.set_state(ConnectionState::Connecting);
             let definition = client.definition().clone();
-            let event_tx = self.event_tx.clone();
+            let session = client.sse_session();
 
-            match definition.protocol {
-                StreamProtocol::WebSocket => {
-                    tokio::spawn(async move {
-                        Self::websocket_connect(definition, event_tx).await;
-                    });
-                }
-                StreamProtocol::Unix => {
-                    tokio::spawn(async move {
-                        Self::unix_socket_connect(definition, event_tx).await;
-                    });
-                }
-                StreamProtocol::Tcp => {
-                    tokio::spawn(async move {
-                        Self::tcp_connect(definition, event_tx).await;
-                    });
-                }
+            let task = match definition.protocol {
+                StreamProtocol::WebSocket => tokio::spawn(Self::websocket_connect(definition, event_tx)),
+                StreamProtocol::Unix => tokio::spawn(Self::unix_socket_connect(definition, event_tx)),
+                StreamProtocol::Tcp => tokio::spawn(Self::tcp_connect(definition, event_tx)),
+                StreamProtocol::SSE => tokio::spawn(Self::sse_connect(http_client, definition, session, event_tx)),
                 _ => {
                     let _ = event_tx.send(StreamEvent::Error(
                         id.to_string(),
                         format!("Protocol {:?} not yet implemented", definition.protocol),
                     ));
+                    return;
                 }
-            }
+            };
+            client.set_task(task);
         }
     }
 
     pub fn disconnect(&mut self, id: &str) {
         if let Some(client) = self.get_client_mut(id) {
+            client.stop_task();
             client.set_state(ConnectionState::Disconnected);
             client.health_mut().stop_reconnect();
             let _ = self.event_tx.send(StreamEvent::Disconnected(id.to_string(), None));
         }
+        self.queue.retain(|queued| queued != id);
+        self.connect_queued();
     }
 
     /// Start auto-reconnect for a stream (called after failure if enabled)
     pub fn start_reconnect(&mut self, id: &str) {
+        let event_tx = self.event_tx.clone();
+        let http_client = self.http_client.clone();
         if let Some(client) = self.get_client_mut(id) {
             if !client.reconnect_enabled() {
                 return;
@@ -362,22 +486,25 @@ impl StreamManager {
             
             let protocol = client.protocol();
             let definition = client.definition().clone();
-            let event_tx = self.event_tx.clone();
+            let session = client.sse_session();
             let stream_id = id.to_string();
 
             // Notify about reconnect attempt
             let _ = event_tx.send(StreamEvent::ReconnectAttempt(stream_id.clone(), attempt));
 
-            Self::spawn_reconnect(protocol, definition, event_tx, delay);
+            let task = Self::spawn_reconnect(protocol, http_client, definition, session, event_tx, delay);
+            client.set_task(task);
         }
     }
 
     /// Cancel ongoing reconnection attempts for a stream
     pub fn cancel_reconnect(&mut self, id: &str) {
         if let Some(client) = self.get_client_mut(id) {
+            client.stop_task();
             client.health_mut().stop_reconnect();
             client.set_state(ConnectionState::Failed);
         }
+        self.connect_queued();
     }
 
     async fn websocket_connect(definition: StreamDefinition, event_tx: mpsc::UnboundedSender<StreamEvent>) {
@@ -523,16 +650,102 @@ impl StreamManager {
         }
     }
 
-    fn spawn_reconnect(protocol: StreamProtocol, definition: StreamDefinition, event_tx: mpsc::UnboundedSender<StreamEvent>, delay: Duration) {
+    async fn sse_connect(http_client: reqwest::Client, definition: StreamDefinition, session: Arc<Mutex<SseSession>>, event_tx: mpsc::UnboundedSender<StreamEvent>) {
+        let id = definition.id.clone();
+
+        let mut request = http_client
+            .get(&definition.url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        for (name, value) in &definition.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let last_event_id = session.lock().ok().and_then(|s| s.last_event_id.clone());
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("Last-Event-ID", last_event_id);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                let _ = event_tx.send(StreamEvent::StateChanged(id.clone(), ConnectionState::Connected));
+                let _ = event_tx.send(StreamEvent::Connected(id.clone()));
+
+                let closed_cleanly = Self::forward_sse_events(&id, response.bytes_stream(), &session, &event_tx).await;
+                if closed_cleanly {
+                    let _ = event_tx.send(StreamEvent::Disconnected(
+                        id.clone(),
+                        Some("Event stream closed by server".to_string()),
+                    ));
+                }
+
+                let _ = event_tx.send(StreamEvent::StateChanged(id, ConnectionState::Disconnected));
+            }
+            Ok(response) => {
+                let _ = event_tx.send(StreamEvent::StateChanged(id.clone(), ConnectionState::Failed));
+                let _ = event_tx.send(StreamEvent::Error(id, format!("HTTP {}", response.status())));
+            }
+            Err(e) => {
+                let _ = event_tx.send(StreamEvent::StateChanged(id.clone(), ConnectionState::Failed));
+                let _ = event_tx.send(StreamEvent::Error(id, e.to_string()));
+            }
+        }
+    }
+
+    /// Parse an SSE byte stream, emitting each event as `StreamEvent::Data` and
+    /// recording `id:`/`retry:` in the session. Returns false if the stream
+    /// ended with an error (already reported).
+    async fn forward_sse_events<B, E>(
+        id: &str,
+        bytes: impl Stream<Item = Result<B, E>> + Unpin,
+        session: &Mutex<SseSession>,
+        event_tx: &mpsc::UnboundedSender<StreamEvent>,
+    ) -> bool
+    where
+        B: AsRef<[u8]>,
+        E: std::fmt::Display,
+    {
+        let mut events = bytes.eventsource();
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(event) => {
+                    if let Ok(mut session) = session.lock() {
+                        if !event.id.is_empty() {
+                            session.last_event_id = Some(event.id.clone());
+                        }
+                        if let Some(retry) = event.retry {
+                            session.retry_ms = Some(retry.as_millis() as u64);
+                        }
+                    }
+                    let _ = event_tx.send(StreamEvent::Data(id.to_string(), StreamData::Text(Self::format_sse_event(&event))));
+                }
+                Err(e) => {
+                    let _ = event_tx.send(StreamEvent::Error(id.to_string(), e.to_string()));
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Plain `message` events show just their data; named events are prefixed
+    fn format_sse_event(event: &SseEvent) -> String {
+        if event.event == "message" {
+            event.data.clone()
+        } else {
+            format!("[{}] {}", event.event, event.data)
+        }
+    }
+
+    fn spawn_reconnect(protocol: StreamProtocol, http_client: reqwest::Client, definition: StreamDefinition, session: Arc<Mutex<SseSession>>, event_tx: mpsc::UnboundedSender<StreamEvent>, delay: Duration) -> JoinHandle<()> {
         tokio::spawn(async move {
             tokio::time::sleep(delay).await;
             match protocol {
                 StreamProtocol::WebSocket => Self::websocket_connect(definition, event_tx).await,
                 StreamProtocol::Unix => Self::unix_socket_connect(definition, event_tx).await,
                 StreamProtocol::Tcp => Self::tcp_connect(definition, event_tx).await,
+                StreamProtocol::SSE => Self::sse_connect(http_client, definition, session, event_tx).await,
                 _ => {}
             }
-        });
+        })
     }
 }
 
@@ -730,6 +943,7 @@ mod tests {
                     headers: Default::default(),
                 },
             ],
+            max_concurrent_connections: None,
         };
         
         manager.load_streams(&config);
@@ -745,6 +959,163 @@ mod tests {
         assert_eq!(unix_client.reconnect_delay_ms(), 2000);
     }
 
+    #[tokio::test]
+    async fn test_max_concurrent_connections_queues_extra_streams() {
+        use crate::streams::config::StreamsConfig;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let mut manager = StreamManager::new();
+        let mut rx = manager.take_event_rx().unwrap();
+        manager.load_streams(&StreamsConfig {
+            streams: (0..5)
+                .map(|i| StreamDefinition {
+                    id: format!("feed-{}", i),
+                    name: format!("Feed {}", i),
+                    protocol: StreamProtocol::Tcp,
+                    url: addr.clone(),
+                    auto_connect: false,
+                    reconnect: false,
+                    reconnect_delay_ms: 1000,
+                    headers: Default::default(),
+                })
+                .collect(),
+            max_concurrent_connections: Some(2),
+        });
+
+        let count = |manager: &StreamManager, state: ConnectionState| {
+            manager.clients().iter().filter(|c| c.state() == state).count()
+        };
+        async fn pump(manager: &mut StreamManager, rx: &mut mpsc::UnboundedReceiver<StreamEvent>) {
+            while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await {
+                if let StreamEvent::StateChanged(id, state) = event {
+                    if let Some(client) = manager.get_client_mut(&id) {
+                        client.set_state(state);
+                    }
+                }
+            }
+        }
+
+        for i in 0..5 {
+            manager.connect(&format!("feed-{}", i));
+        }
+        assert_eq!(count(&manager, ConnectionState::Queued), 3);
+        pump(&mut manager, &mut rx).await;
+        assert_eq!(count(&manager, ConnectionState::Connected), 2);
+        assert_eq!(manager.pool_status().to_string(), "2/2 · 3 queued");
+
+        manager.disconnect("feed-0");
+        pump(&mut manager, &mut rx).await;
+        assert_eq!(count(&manager, ConnectionState::Connected), 2);
+        assert_eq!(manager.get_client("feed-2").unwrap().state(), ConnectionState::Connected);
+        assert_eq!(count(&manager, ConnectionState::Queued), 2);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_closes_the_connection() {
+        use crate::streams::config::StreamsConfig;
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            // Returns once the client side of the socket is closed
+            let mut buf = [0u8; 16];
+            socket.read(&mut buf).await.unwrap()
+        });
+
+        let mut manager = StreamManager::new();
+        let mut rx = manager.take_event_rx().unwrap();
+        manager.load_streams(&StreamsConfig {
+            streams: vec![StreamDefinition {
+                id: "feed".to_string(),
+                name: "Feed".to_string(),
+                protocol: StreamProtocol::Tcp,
+                url: addr,
+                auto_connect: false,
+                reconnect: false,
+                reconnect_delay_ms: 1000,
+                headers: Default::default(),
+            }],
+            max_concurrent_connections: None,
+        });
+        manager.connect("feed");
+        while !matches!(rx.recv().await, Some(StreamEvent::Connected(_))) {}
+
+        manager.disconnect("feed");
+        let read = tokio::time::timeout(Duration::from_secs(5), server).await;
+        assert_eq!(read.expect("the connection task kept its socket open").unwrap(), 0);
+        assert!(manager.get_client("feed").unwrap().task.is_none());
+    }
+
+    #[test]
+    fn test_zero_max_concurrent_connections_is_unlimited() {
+        use crate::streams::config::StreamsConfig;
+
+        let mut manager = StreamManager::new();
+        manager.load_streams(&StreamsConfig {
+            streams: Vec::new(),
+            max_concurrent_connections: Some(0),
+        });
+        assert_eq!(manager.pool_status().limit, None);
+    }
+
+    #[test]
+    fn test_sse_client_uses_ca_bundle() {
+        let mut manager = StreamManager::new();
+        assert!(manager.set_ca_bundle(None).is_ok());
+        let err = manager.set_ca_bundle(Some(std::path::Path::new("/nonexistent/ca.pem"))).unwrap_err();
+        assert!(err.contains("CA bundle"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_sse_events_parsed_from_canned_stream() {
+        // Chunk boundaries fall mid-field to exercise buffering
+        let chunks: Vec<Result<&[u8], std::io::Error>> = vec![
+            Ok(b": keep-alive comment\n\nretry: 2500\nid: 1\nda"),
+            Ok(b"ta: first\n\nevent: alert\ndata: line one\ndata: line two\nid: 2\n\n"),
+            Ok(b"data: no id here\n\n"),
+        ];
+        let session = Mutex::new(SseSession::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let clean = StreamManager::forward_sse_events("sse", futures::stream::iter(chunks), &session, &tx).await;
+        assert!(clean);
+
+        let mut texts = Vec::new();
+        while let Ok(StreamEvent::Data(_, StreamData::Text(text))) = rx.try_recv() {
+            texts.push(text);
+        }
+        assert_eq!(texts, ["first", "[alert] line one\nline two", "no id here"]);
+
+        // Last-Event-ID survives events without an id; retry overrides the configured delay
+        let session = session.into_inner().unwrap();
+        assert_eq!(session.last_event_id.as_deref(), Some("2"));
+        assert_eq!(session.retry_ms, Some(2500));
+
+        let client = StreamClient::new(StreamDefinition {
+            id: "sse".to_string(),
+            name: "SSE".to_string(),
+            protocol: StreamProtocol::SSE,
+            url: "http://localhost/events".to_string(),
+            auto_connect: false,
+            reconnect: true,
+            reconnect_delay_ms: 5000,
+            headers: Default::default(),
+        });
+        assert_eq!(client.reconnect_delay_ms(), 5000);
+        client.sse_session().lock().unwrap().retry_ms = Some(2500);
+        assert_eq!(client.reconnect_delay_ms(), 2500);
+    }
+
     #[test]
     fn test_unix_socket_path_parsing() {
         let path_str = "/var/run/myapp/socket.sock";
@@ -805,6 +1176,7 @@ mod tests {
                     headers: Default::default(),
                 },
             ],
+            max_concurrent_connections: None,
         };
         
         manager.load_streams(&config);
@@ -872,6 +1244,7 @@ mod tests {
                     headers: Default::default(),
                 },
             ],
+            max_concurrent_connections: None,
         };
         
         manager.load_streams(&config);
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamsConfig {
     #[serde(default)]
     pub streams: Vec<StreamDefinition>,
+    /// Connections allowed at once; further connects wait in a queue (None or 0 = unlimited)
+    #[serde(default)]
+    pub max_concurrent_connections: Option<usize>,
 }
 
 impl Default for StreamsConfig {
@@ -22,13 +25,15 @@ impl Default for StreamsConfig {
                     headers: Default::default(),
                 },
             ],
+            max_concurrent_connections: None,
         }
     }
 }
 
 impl StreamsConfig {
-    pub fn load() -> Self {
-        let config_path = Self::config_path();
+    /// Load `streams.toml` from `config_dir`, or the built-in defaults
+    pub fn load(config_dir: &Path) -> Self {
+        let config_path = config_dir.join("streams.toml");
         if config_path.exists() {
             match std::fs::read_to_string(&config_path) {
                 Ok(content) => match toml::from_str(&content) {
@@ -44,12 +49,6 @@ impl StreamsConfig {
         }
         Self::default()
     }
-
-    fn config_path() -> PathBuf {
-        directories::BaseDirs::new()
-            .map(|dirs| dirs.config_dir().join("ridge-control").join("streams.toml"))
-            .unwrap_or_else(|| PathBuf::from("~/.config/ridge-control/streams.toml"))
-    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -105,16 +104,29 @@ fn default_reconnect_delay() -> u64 {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionState {
     Disconnected,
+    /// Waiting for a free connection slot
+    Queued,
     Connecting,
     Connected,
     Reconnecting { attempt: u32 },
     Failed,
 }
 
+impl ConnectionState {
+    /// Whether the stream holds one of the limited connection slots
+    pub fn holds_slot(&self) -> bool {
+        matches!(
+            self,
+            ConnectionState::Connecting | ConnectionState::Connected | ConnectionState::Reconnecting { .. }
+        )
+    }
+}
+
 impl std::fmt::Display for ConnectionState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ConnectionState::Disconnected => write!(f, "⭘"),
+            ConnectionState::Queued => write!(f, "⧖"),
             ConnectionState::Connecting => write!(f, "◌"),
             ConnectionState::Connected => write!(f, "●"),
             ConnectionState::Reconnecting { attempt } => write!(f, "↻{}", attempt),
@@ -3,5 +3,5 @@
 pub mod client;
 pub mod config;
 
-pub use client::{StreamClient, StreamData, StreamEvent, StreamManager};
+pub use client::{PoolStatus, StreamClient, StreamData, StreamEvent, StreamManager};
 pub use config::{ConnectionState, StreamsConfig};
@@ -106,6 +106,10 @@ pub struct TabManager {
     pty_sessions: HashMap<TabId, PtySession>,
     /// Terminal size for new PTY sessions
     terminal_size: (u16, u16),
+    /// Read buffer size for new PTY sessions (`terminal.pty_read_buf`)
+    pty_read_buf: usize,
+    /// Mouse-wheel step for terminal widgets (`ui.scroll_lines`)
+    scroll_lines: u16,
     /// TRC-029: Inline rename state - buffer for editing tab name
     rename_buffer: Option<String>,
     /// TRC-029: Original name before rename started (for cancel/revert)
@@ -139,6 +143,8 @@ impl TabManager {
             next_id: 1, // 0 is reserved for main tab
             pty_sessions: HashMap::new(),
             terminal_size: (80, 24), // Default, will be set properly on first resize
+            pty_read_buf: 16 * 1024,
+            scroll_lines: crate::components::scroll::DEFAULT_SCROLL_LINES,
             rename_buffer: None,
             rename_original: None,
         }
@@ -153,6 +159,19 @@ impl TabManager {
         }
     }
 
+    /// Set the read buffer size for PTYs spawned from now on
+    pub fn set_pty_read_buf(&mut self, bytes: usize) {
+        self.pty_read_buf = bytes;
+    }
+
+    /// Set the mouse-wheel step for all terminal widgets, current and future
+    pub fn set_scroll_lines(&mut self, lines: u16) {
+        self.scroll_lines = lines;
+        for session in self.pty_sessions.values_mut() {
+            session.terminal_mut().set_scroll_lines(lines);
+        }
+    }
+
     /// Spawn PTY for a tab if not already spawned
     /// Returns a receiver for PTY events from this tab
     pub fn spawn_pty_for_tab(&mut self, tab_id: TabId) -> Result<Option<mpsc::UnboundedReceiver<(TabId, PtyEvent)>>> {
@@ -165,7 +184,8 @@ impl TabManager {
 
         let (cols, rows) = self.terminal_size;
         let mut session = PtySession::new(tab_id, cols as usize, rows as usize);
-        let rx = session.spawn(cols, rows)?;
+        session.terminal_mut().set_scroll_lines(self.scroll_lines);
+        let rx = session.spawn(cols, rows, self.pty_read_buf)?;
         self.pty_sessions.insert(tab_id, session);
         Ok(Some(rx))
     }
@@ -46,6 +46,34 @@ fn drain_eventfd(fd: RawFd) {
     }
 }
 
+/// Bounds for `terminal.pty_read_buf`
+pub const MIN_READ_BUF: usize = 1024;
+pub const MAX_READ_BUF: usize = 1024 * 1024;
+
+/// Most output one wakeup collects before the I/O thread goes back to
+/// service writes and resizes
+const MAX_READ_PER_WAKEUP: usize = 1024 * 1024;
+
+/// Read everything the PTY has ready into one chunk, so a burst of output
+/// becomes one event instead of one per buffer-full. Stops at WouldBlock, EOF
+/// or MAX_READ_PER_WAKEUP; an error is returned alongside what was read before it.
+fn read_available(
+    mut read: impl FnMut(&mut [u8]) -> io::Result<usize>,
+    buf: &mut [u8],
+) -> (Vec<u8>, Option<io::Error>) {
+    let mut out = Vec::new();
+    while out.len() < MAX_READ_PER_WAKEUP {
+        match read(buf) {
+            Ok(0) => break,
+            Ok(n) => out.extend_from_slice(&buf[..n]),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return (out, Some(e)),
+        }
+    }
+    (out, None)
+}
+
 /// A PTY session tied to a specific tab
 pub struct PtySession {
     /// The tab this session belongs to
@@ -85,9 +113,9 @@ impl PtySession {
         self.alive
     }
 
-    /// Spawn the PTY process and start the I/O thread
+    /// Spawn the PTY process and start the I/O thread, reading `read_buf` bytes per syscall
     /// Returns a receiver for PTY events that should be polled by the app
-    pub fn spawn(&mut self, cols: u16, rows: u16) -> Result<mpsc::UnboundedReceiver<(TabId, PtyEvent)>> {
+    pub fn spawn(&mut self, cols: u16, rows: u16, read_buf: usize) -> Result<mpsc::UnboundedReceiver<(TabId, PtyEvent)>> {
         let pty = PtyHandle::spawn()?;
         pty.resize(cols, rows)?;
 
@@ -113,7 +141,7 @@ impl PtySession {
 
         std::thread::spawn(move || {
             let mut pty = pty;
-            let mut buf = [0u8; 16384]; // 16KB read buffer
+            let mut buf = vec![0u8; read_buf.clamp(MIN_READ_BUF, MAX_READ_BUF)];
             let mut write_rx = write_rx;
             let pty_fd = pty.raw_fd();
             let has_wake = thread_wake_fd >= 0;
@@ -154,18 +182,15 @@ impl PtySession {
                             }
                         }
 
-                        // PTY data ready
+                        // PTY data ready: drain it so the shell never blocks on a full PTY
                         if fds[0].revents & libc::POLLIN != 0 {
-                            match pty.try_read(&mut buf) {
-                                Ok(0) => {} // EOF
-                                Ok(n) => {
-                                    let _ = event_tx.send((tab_id, PtyEvent::Output(buf[..n].to_vec())));
-                                }
-                                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
-                                Err(e) => {
-                                    let _ = event_tx.send((tab_id, PtyEvent::Error(e)));
-                                    break;
-                                }
+                            let (data, err) = read_available(|b| pty.try_read(b), &mut buf);
+                            if !data.is_empty() {
+                                let _ = event_tx.send((tab_id, PtyEvent::Output(data)));
+                            }
+                            if let Some(e) = err {
+                                let _ = event_tx.send((tab_id, PtyEvent::Error(e)));
+                                break;
                             }
                         }
                     }
@@ -179,16 +204,13 @@ impl PtySession {
                     let poll_result = unsafe { libc::poll(&mut pollfd, 1, 10) };
 
                     if poll_result > 0 && (pollfd.revents & libc::POLLIN) != 0 {
-                        match pty.try_read(&mut buf) {
-                            Ok(0) => {}
-                            Ok(n) => {
-                                let _ = event_tx.send((tab_id, PtyEvent::Output(buf[..n].to_vec())));
-                            }
-                            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
-                            Err(e) => {
-                                let _ = event_tx.send((tab_id, PtyEvent::Error(e)));
-                                break;
-                            }
+                        let (data, err) = read_available(|b| pty.try_read(b), &mut buf);
+                        if !data.is_empty() {
+                            let _ = event_tx.send((tab_id, PtyEvent::Output(data)));
+                        }
+                        if let Some(e) = err {
+                            let _ = event_tx.send((tab_id, PtyEvent::Error(e)));
+                            break;
                         }
                     }
                 }
@@ -225,6 +247,16 @@ impl PtySession {
         }
     }
 
+    /// Reset the terminal emulator without touching the shell, then re-send the
+    /// current size so the running program knows the dimensions
+    pub fn reset(&mut self) {
+        self.terminal_widget.reset();
+        let (cols, rows) = self.terminal_widget.size();
+        if let Some(ref tx) = self.resize_tx {
+            let _ = tx.send((cols as u16, rows as u16));
+        }
+    }
+
     /// Process PTY output data
     pub fn process_output(&mut self, data: &[u8]) {
         self.terminal_widget.process_output(data);
@@ -272,6 +304,44 @@ impl Drop for PtySession {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_available_coalesces_until_would_block() {
+        let mut chunks = vec![Ok(b"abc".to_vec()), Ok(b"def".to_vec())].into_iter();
+        let read = |buf: &mut [u8]| match chunks.next() {
+            Some(Ok(data)) => {
+                buf[..data.len()].copy_from_slice(&data);
+                Ok(data.len())
+            }
+            Some(Err(e)) => Err(e),
+            None => Err(io::Error::from(io::ErrorKind::WouldBlock)),
+        };
+        let (data, err) = read_available(read, &mut [0u8; 4]);
+        assert_eq!(data, b"abcdef");
+        assert!(err.is_none());
+
+        // Output read before an error is still delivered with it
+        let mut calls = 0;
+        let read = |buf: &mut [u8]| {
+            calls += 1;
+            if calls == 1 {
+                buf[0] = b'x';
+                Ok(1)
+            } else {
+                Err(io::Error::other("EIO"))
+            }
+        };
+        let (data, err) = read_available(read, &mut [0u8; 4]);
+        assert_eq!(data, b"x");
+        assert!(err.is_some());
+    }
+
+    #[test]
+    fn test_read_available_stops_at_wakeup_limit() {
+        let read = |buf: &mut [u8]| Ok(buf.len());
+        let (data, _) = read_available(read, &mut vec![0u8; MAX_READ_BUF]);
+        assert_eq!(data.len(), MAX_READ_PER_WAKEUP);
+    }
+
     #[test]
     fn test_pty_session_new() {
         let session = PtySession::new(1, 80, 24);
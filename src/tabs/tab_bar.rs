@@ -18,6 +18,7 @@ use ratatui::{
     widgets::{Block, Borders, Widget},
 };
 
+use crate::agent::subagent::SubagentActivity;
 use crate::config::Theme;
 use crate::input::mode::InputMode;
 use super::{Tab, TabManager};
@@ -123,10 +124,30 @@ pub struct TabBar<'a> {
     show_close_buttons: bool,
     /// TRC-018: Show dangerous mode warning indicator
     dangerous_mode: bool,
+    /// Agent loop is held before its next continuation
+    agent_paused: bool,
+    /// Pause requested, waiting for the current tool results
+    agent_pause_pending: bool,
+    /// Step-through tool execution is on
+    step_mode: bool,
+    /// Tools are described instead of executed
+    dry_run: bool,
+    /// Compact layout is on
+    compact_mode: bool,
+    /// Agent waits for Enter after tool results
+    manual_continue: bool,
     /// TRC-029: Inline rename buffer (if renaming active tab)
     rename_buffer: Option<&'a str>,
     /// Current input mode for status indicator
     input_mode: InputMode,
+    /// A response is streaming into the hidden conversation panel
+    background_response: bool,
+    /// Running and queued sub-agents
+    subagents: SubagentActivity,
+    /// What the latest sub-agent is doing
+    subagent_status: Option<String>,
+    /// Provider rate-limit budget, shown while it's running low
+    rate_limit: Option<String>,
 }
 
 impl<'a> TabBar<'a> {
@@ -139,8 +160,18 @@ impl<'a> TabBar<'a> {
             show_indices: true,
             show_close_buttons: true,
             dangerous_mode: false,
+            agent_paused: false,
+            agent_pause_pending: false,
+            step_mode: false,
+            dry_run: false,
+            compact_mode: false,
+            manual_continue: false,
             rename_buffer: manager.rename_buffer(),
             input_mode: InputMode::Normal,
+            background_response: false,
+            subagents: SubagentActivity::default(),
+            subagent_status: None,
+            rate_limit: None,
         }
     }
 
@@ -153,8 +184,18 @@ impl<'a> TabBar<'a> {
             show_indices: true,
             show_close_buttons: true,
             dangerous_mode: false,
+            agent_paused: false,
+            agent_pause_pending: false,
+            step_mode: false,
+            dry_run: false,
+            compact_mode: false,
+            manual_continue: false,
             rename_buffer: manager.rename_buffer(),
             input_mode: InputMode::Normal,
+            background_response: false,
+            subagents: SubagentActivity::default(),
+            subagent_status: None,
+            rate_limit: None,
         }
     }
 
@@ -167,8 +208,18 @@ impl<'a> TabBar<'a> {
             show_indices: true,
             show_close_buttons: true,
             dangerous_mode: false,
+            agent_paused: false,
+            agent_pause_pending: false,
+            step_mode: false,
+            dry_run: false,
+            compact_mode: false,
+            manual_continue: false,
             rename_buffer: None,
             input_mode: InputMode::Normal,
+            background_response: false,
+            subagents: SubagentActivity::default(),
+            subagent_status: None,
+            rate_limit: None,
         }
     }
     
@@ -178,6 +229,61 @@ impl<'a> TabBar<'a> {
         self
     }
 
+    /// Show the agent pause indicator
+    pub fn agent_paused(mut self, paused: bool, pending: bool) -> Self {
+        self.agent_paused = paused;
+        self.agent_pause_pending = pending;
+        self
+    }
+
+    /// Show the step-through mode indicator
+    pub fn step_mode(mut self, enabled: bool) -> Self {
+        self.step_mode = enabled;
+        self
+    }
+
+    /// Show the dry-run planning indicator
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Show the compact layout indicator
+    pub fn compact_mode(mut self, enabled: bool) -> Self {
+        self.compact_mode = enabled;
+        self
+    }
+
+    /// Show the manual-continue indicator when auto-continue is off
+    pub fn auto_continue(mut self, enabled: bool) -> Self {
+        self.manual_continue = !enabled;
+        self
+    }
+
+    /// Show that a response is streaming while the conversation is closed
+    pub fn background_response(mut self, active: bool) -> Self {
+        self.background_response = active;
+        self
+    }
+
+    /// Show running/queued sub-agent counts
+    pub fn subagents(mut self, activity: SubagentActivity) -> Self {
+        self.subagents = activity;
+        self
+    }
+
+    /// Show the latest sub-agent status next to the counts
+    pub fn subagent_status(mut self, status: Option<String>) -> Self {
+        self.subagent_status = status;
+        self
+    }
+
+    /// Show a low rate-limit budget (e.g. "3 req left · 12s")
+    pub fn rate_limit(mut self, summary: Option<String>) -> Self {
+        self.rate_limit = summary;
+        self
+    }
+
     /// Set input mode for status indicator
     pub fn input_mode(mut self, mode: InputMode) -> Self {
         self.input_mode = mode;
@@ -360,8 +466,39 @@ impl Widget for TabBar<'_> {
 
         let warning_text = if self.dangerous_mode { " ⚠ DANGEROUS MODE " } else { "" };
         let warning_width = warning_text.chars().count();
+        let response_text = if self.background_response { " ● RESPONDING " } else { "" };
+        let response_width = response_text.chars().count();
+        let paused_text = if self.agent_paused {
+            " ⏸ PAUSED "
+        } else if self.agent_pause_pending {
+            " ⏸ PAUSING "
+        } else {
+            ""
+        };
+        let paused_width = paused_text.chars().count();
+        let step_text = match (self.step_mode, self.manual_continue) {
+            (true, _) => " STEP ",
+            (false, true) => " MANUAL ",
+            (false, false) => "",
+        };
+        let step_width = step_text.chars().count();
+        let dry_run_text = if self.dry_run { " DRY-RUN " } else { "" };
+        let dry_run_width = dry_run_text.chars().count();
+        let compact_text = if self.compact_mode { " COMPACT " } else { "" };
+        let compact_width = compact_text.chars().count();
+        let mut subagent_text = match (self.subagents.running, self.subagents.queued) {
+            (0, 0) => String::new(),
+            (running, 0) => format!(" AGENTS {} ", running),
+            (running, queued) => format!(" AGENTS {} +{} queued ", running, queued),
+        };
+        if let (false, Some(status)) = (subagent_text.is_empty(), &self.subagent_status) {
+            subagent_text.push_str(&format!("· {} ", status));
+        }
+        let subagent_width = subagent_text.chars().count();
+        let rate_limit_text = self.rate_limit.as_ref().map(|s| format!(" ⧗ {} ", s)).unwrap_or_default();
+        let rate_limit_width = rate_limit_text.chars().count();
         let mode_width = mode_text.chars().count();
-        let total_right_width = mode_width + warning_width;
+        let total_right_width = rate_limit_width + subagent_width + compact_width + dry_run_width + step_width + paused_width + response_width + mode_width + warning_width;
 
         if tabs_width + total_right_width + 2 < available {
             let padding = available.saturating_sub(tabs_width + total_right_width + 1);
@@ -370,6 +507,78 @@ impl Widget for TabBar<'_> {
                 Style::default().bg(self.style.background),
             ));
 
+            if rate_limit_width > 0 {
+                spans.push(Span::styled(
+                    rate_limit_text,
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(243, 139, 168)) // Red
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            // Sub-agent activity
+            if subagent_width > 0 {
+                spans.push(Span::styled(
+                    subagent_text,
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(148, 226, 213)) // Teal
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            if compact_width > 0 {
+                spans.push(Span::styled(
+                    compact_text.to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(180, 190, 254)) // Lavender
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            if dry_run_width > 0 {
+                spans.push(Span::styled(
+                    dry_run_text.to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(116, 199, 236)) // Sapphire
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            if step_width > 0 {
+                spans.push(Span::styled(
+                    step_text.to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(245, 194, 231)) // Pink
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            if paused_width > 0 {
+                spans.push(Span::styled(
+                    paused_text.to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(250, 179, 135)) // Peach
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
+            // Streaming indicator while the conversation panel is closed
+            if self.background_response {
+                spans.push(Span::styled(
+                    response_text.to_string(),
+                    Style::default()
+                        .fg(Color::Rgb(0, 0, 0))
+                        .bg(Color::Rgb(249, 226, 175)) // Yellow
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+
             // Mode indicator
             spans.push(Span::styled(mode_text.to_string(), mode_style));
 
@@ -457,4 +666,69 @@ mod tests {
         let hit_areas = tab_bar.calculate_hit_areas(area);
         assert_eq!(hit_areas.len(), 2); // Main tab + Test tab
     }
+
+    #[test]
+    fn test_background_response_indicator() {
+        let manager = TabManager::new();
+        let area = Rect::new(0, 0, 80, 1);
+        let row = |buf: &Buffer| (0..80).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>();
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).render(area, &mut buf);
+        assert!(!row(&buf).contains("RESPONDING"));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).background_response(true).render(area, &mut buf);
+        assert!(row(&buf).contains("RESPONDING"));
+    }
+
+    #[test]
+    fn test_agent_paused_indicator() {
+        let manager = TabManager::new();
+        let area = Rect::new(0, 0, 80, 1);
+        let row = |buf: &Buffer| (0..80).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>();
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).agent_paused(false, true).render(area, &mut buf);
+        assert!(row(&buf).contains("PAUSING"));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).agent_paused(true, false).render(area, &mut buf);
+        assert!(row(&buf).contains("PAUSED"));
+        assert!(!row(&buf).contains("STEP"));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).step_mode(true).render(area, &mut buf);
+        assert!(row(&buf).contains(" STEP "));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).auto_continue(false).render(area, &mut buf);
+        assert!(row(&buf).contains(" MANUAL "));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).dry_run(true).render(area, &mut buf);
+        assert!(row(&buf).contains(" DRY-RUN "));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).compact_mode(true).render(area, &mut buf);
+        assert!(row(&buf).contains(" COMPACT "));
+    }
+
+    #[test]
+    fn test_subagent_indicator() {
+        let manager = TabManager::new();
+        let area = Rect::new(0, 0, 80, 1);
+        let row = |buf: &Buffer| (0..80).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>();
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager).render(area, &mut buf);
+        assert!(!row(&buf).contains("AGENTS"));
+
+        let mut buf = Buffer::empty(area);
+        TabBar::from_manager(&manager)
+            .subagents(SubagentActivity { running: 2, queued: 3 })
+            .subagent_status(Some("explore: calling grep".to_string()))
+            .render(area, &mut buf);
+        assert!(row(&buf).contains("AGENTS 2 +3 queued · explore: calling grep"));
+    }
 }
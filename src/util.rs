@@ -1,6 +1,9 @@
-//! Utility functions for text processing
+//! Utility functions for text processing and file persistence
 
 use regex::Regex;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
 use std::sync::LazyLock;
 
 /// ANSI escape sequence regex pattern
@@ -24,6 +27,88 @@ pub fn strip_ansi(text: &str) -> String {
     ANSI_REGEX.replace_all(text, "").into_owned()
 }
 
+/// Atomically replace `path` with `contents`.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// Atomically replace `path` with `contents`, creating the file with `mode`.
+///
+/// The temp file carries the mode from the moment it is created, so secrets
+/// are never readable by other users, even briefly. `mode` is ignored on
+/// platforms without Unix permissions.
+pub fn write_atomic_mode(path: &Path, contents: &[u8], mode: u32) -> io::Result<()> {
+    replace_atomic(path, Some(mode), |file| file.write_all(contents))
+}
+
+/// Atomically replace `path` with whatever `write` produces.
+///
+/// Output goes to a hidden temp file in the same directory, which is fsynced
+/// and then renamed over the target. If `write` fails or the process dies
+/// part-way, the previous file is left untouched.
+pub fn write_atomic_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    replace_atomic(path, None, write)
+}
+
+fn replace_atomic<F>(path: &Path, mode: Option<u32>, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let temp_path = dir.join(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let result = create_temp(&temp_path, mode)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.flush()?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Persist the rename itself; not supported on every platform
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_temp(temp_path: &Path, mode: Option<u32>) -> io::Result<File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let Some(mode) = mode else {
+        return File::create(temp_path);
+    };
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(mode)
+        .open(temp_path)?;
+    // A stale temp file keeps its old mode, and umask may have masked bits
+    file.set_permissions(fs::Permissions::from_mode(mode))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn create_temp(temp_path: &Path, _mode: Option<u32>) -> io::Result<File> {
+    File::create(temp_path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,6 +155,53 @@ mod tests {
         assert_eq!(strip_ansi(input), "╔══════════╗\n║  Hello   ║");
     }
 
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.toml");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_mode_sets_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("keys.json");
+        fs::write(&path, b"old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_atomic_mode(&path, b"secret", 0o600).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+    }
+
+    #[test]
+    fn test_write_atomic_failure_keeps_previous_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("thread.json");
+        write_atomic(&path, b"{\"intact\": true}").unwrap();
+
+        // Fail half-way through serializing the replacement
+        let err = write_atomic_with(&path, |file| {
+            file.write_all(b"{\"trunc")?;
+            Err(io::Error::other("serializer failed"))
+        })
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "serializer failed");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"intact\": true}");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn test_empty_string() {
         assert_eq!(strip_ansi(""), "");